@@ -0,0 +1,296 @@
+//! Errors clients of the server can act on.
+//!
+//! Failures such as a missing session or an exhausted budget are
+//! [`GameDesignerError`]s. Inside the server they travel through `anyhow`
+//! like any other error; at the tool boundary they are turned into a
+//! [`ToolError`] whose message is a JSON object, so that agents can tell the
+//! failures apart without parsing prose:
+//!
+//! ```json
+//! {
+//!   "error": "session_not_found",
+//!   "code": -32002,
+//!   "message": "Failed to submit feature review: Session 'space_cats' not found",
+//!   "retryable": false
+//! }
+//! ```
+//!
+//! `error` is a stable identifier, `code` the JSON-RPC error code of the MCP
+//! specification that fits the failure, and `retryable` tells whether the
+//! same call may succeed later; `retryAfterSecs` suggests when, if known.
+//! Errors of no known kind are reported as `failed`.
+
+use crate::{
+    game_design::designer_llm,
+    tools::{queue::Busy, quota::QuotaExceeded},
+};
+use mcp_core::handler::ToolError;
+use serde_json::{Value, json};
+use std::{fmt, path::PathBuf, time::Duration};
+
+/// JSON-RPC code of invalid method parameters.
+pub const INVALID_PARAMS: i64 = -32602;
+
+/// MCP code of a resource that does not exist.
+pub const RESOURCE_NOT_FOUND: i64 = -32002;
+
+/// JSON-RPC code of a failure inside the server.
+pub const INTERNAL_ERROR: i64 = -32603;
+
+/// Ways to configure an LLM provider, sent with `llm_unavailable` errors.
+pub const LLM_SETUP: &[&str] = &[
+    "Set OPENROUTER_API_KEY (and optionally GAMEDESIGNER_MODEL) in the server's environment \
+     and restart the server",
+    "Or write the key to a file named by `api_key_file` in the [llm] section of \
+     gamedesignerd.toml and call configReload or send SIGHUP, without a restart",
+    "Or set `provider` in the [llm] section of gamedesignerd.toml and restart the server",
+    "Or run the server with `--llm-provider mock` for deterministic offline responses",
+];
+
+/// A failure reported to clients with a stable code.
+#[derive(Debug, Clone)]
+pub enum GameDesignerError {
+    /// The arguments don't fit the tool.
+    InvalidArguments(String),
+    /// No tool of that name exists.
+    ToolNotFound(String),
+    SessionNotFound(String),
+    /// The session was frozen with `designFreeze` and the tool would change
+    /// it.
+    SessionFrozen(String),
+    FeatureNotFound(String),
+    /// Another named thing does not exist, such as a level or a job.
+    NotFound(String),
+    /// No LLM provider is configured.
+    LlmUnavailable,
+    /// The LLM provider could not be reached or answered with an error
+    /// status.
+    LlmFailed {
+        status: Option<u16>,
+        message: String,
+    },
+    /// The session used up one of its quotas.
+    BudgetExceeded(QuotaExceeded),
+    /// Too many LLM-backed calls are in progress.
+    Busy(Busy),
+    /// A stored file could not be read back.
+    StorageCorrupt {
+        path: PathBuf,
+        message: String,
+    },
+    Timeout {
+        tool: String,
+        after: Duration,
+    },
+    Cancelled {
+        tool: String,
+    },
+    /// Any other failure, e.g. an LLM response that could not be parsed.
+    Failed(String),
+}
+
+impl GameDesignerError {
+    /// Stable identifier of the kind of failure.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidArguments(_) => "invalid_arguments",
+            Self::ToolNotFound(_) => "tool_not_found",
+            Self::SessionNotFound(_) => "session_not_found",
+            Self::SessionFrozen(_) => "session_frozen",
+            Self::FeatureNotFound(_) => "feature_not_found",
+            Self::NotFound(_) => "not_found",
+            Self::LlmUnavailable => "llm_unavailable",
+            Self::LlmFailed { .. } => "llm_failed",
+            Self::BudgetExceeded(_) => "budget_exhausted",
+            Self::Busy(_) => "busy",
+            Self::StorageCorrupt { .. } => "storage_corrupt",
+            Self::Timeout { .. } => "timeout",
+            Self::Cancelled { .. } => "cancelled",
+            Self::Failed(_) => "failed",
+        }
+    }
+
+    /// JSON-RPC error code of the MCP specification that fits the failure.
+    pub fn mcp_code(&self) -> i64 {
+        match self {
+            Self::InvalidArguments(_) | Self::ToolNotFound(_) => INVALID_PARAMS,
+            Self::SessionNotFound(_) | Self::FeatureNotFound(_) | Self::NotFound(_) => {
+                RESOURCE_NOT_FOUND
+            }
+            _ => INTERNAL_ERROR,
+        }
+    }
+
+    /// Whether repeating the same call may succeed. Rate limits and server
+    /// errors of the LLM provider pass; other provider errors, such as a
+    /// rejected API key, don't.
+    pub fn retryable(&self) -> bool {
+        match self {
+            Self::LlmFailed { status, .. } => {
+                status.is_none_or(|status| status == 429 || status >= 500)
+            }
+            Self::BudgetExceeded(_) | Self::Busy(_) | Self::Timeout { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// How long to wait before retrying, if known.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::BudgetExceeded(exceeded) => Some(exceeded.retry_after),
+            Self::Busy(busy) => Some(busy.retry_after),
+            _ => None,
+        }
+    }
+
+    /// The JSON object sent to clients, with `message` as its message.
+    pub fn to_json(&self, message: &str) -> Value {
+        let mut value = json!({
+            "error": self.code(),
+            "code": self.mcp_code(),
+            "message": message,
+            "retryable": self.retryable(),
+        });
+
+        if let Some(retry_after) = self.retry_after() {
+            value["retryAfterSecs"] = json!(retry_after.as_secs().max(1));
+        }
+        match self {
+            Self::BudgetExceeded(exceeded) => {
+                value["limit"] = json!(exceeded.limit);
+                value["session"] = json!(exceeded.session);
+            }
+            Self::SessionNotFound(session) | Self::SessionFrozen(session) => {
+                value["session"] = json!(session)
+            }
+            Self::FeatureNotFound(feature) => value["feature"] = json!(feature),
+            Self::LlmFailed {
+                status: Some(status),
+                ..
+            } => value["status"] = json!(status),
+            Self::StorageCorrupt { path, .. } => value["path"] = json!(path),
+            Self::LlmUnavailable => value["setup"] = json!(LLM_SETUP),
+            _ => {}
+        }
+
+        value
+    }
+
+    /// Converts the error into a [`ToolError`] carrying `message`.
+    fn into_tool_error(self, message: &str) -> ToolError {
+        let json = self.to_json(message).to_string();
+
+        match self.mcp_code() {
+            INVALID_PARAMS => ToolError::InvalidParameters(json),
+            RESOURCE_NOT_FOUND => ToolError::NotFound(json),
+            _ => ToolError::ExecutionError(json),
+        }
+    }
+}
+
+impl fmt::Display for GameDesignerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidArguments(message) | Self::NotFound(message) | Self::Failed(message) => {
+                write!(f, "{}", message)
+            }
+            Self::ToolNotFound(tool) => write!(f, "Tool '{}' not found.", tool),
+            Self::SessionNotFound(session) => write!(f, "Session '{}' not found", session),
+            Self::SessionFrozen(session) => write!(
+                f,
+                "Session '{}' is frozen: read tools and exports still work, but nothing can \
+                 change it until designUnfreeze is called with confirm: true",
+                session
+            ),
+            Self::FeatureNotFound(feature) => write!(f, "Feature '{}' not found", feature),
+            Self::LlmUnavailable => {
+                write!(f, "LlmUnavailable: no LLM provider is configured")?;
+                if let Some(reason) = designer_llm::unavailable_reason() {
+                    write!(f, " ({})", reason)?;
+                }
+                write!(
+                    f,
+                    ". Set OPENROUTER_API_KEY (and optionally GAMEDESIGNER_MODEL) in the server's \
+                     environment and restart it to enable this tool, or run the server with \
+                     `--llm-provider mock` for offline use."
+                )
+            }
+            Self::LlmFailed { message, .. } => write!(f, "LLM request failed: {}", message),
+            Self::BudgetExceeded(exceeded) => write!(f, "{}", exceeded),
+            Self::Busy(busy) => write!(f, "{}", busy),
+            Self::StorageCorrupt { path, message } => write!(
+                f,
+                "{} is corrupt: {}. Run `gamedesignerd doctor` to repair or quarantine it.",
+                path.display(),
+                message
+            ),
+            Self::Timeout { tool, after } => write!(
+                f,
+                "{} timed out after {} seconds; changes not yet saved were discarded",
+                tool,
+                after.as_secs()
+            ),
+            Self::Cancelled { tool } => write!(
+                f,
+                "{} was cancelled; changes not yet saved were discarded",
+                tool
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GameDesignerError {}
+
+impl From<QuotaExceeded> for GameDesignerError {
+    fn from(exceeded: QuotaExceeded) -> Self {
+        Self::BudgetExceeded(exceeded)
+    }
+}
+
+impl From<Busy> for GameDesignerError {
+    fn from(busy: Busy) -> Self {
+        Self::Busy(busy)
+    }
+}
+
+impl From<GameDesignerError> for ToolError {
+    fn from(error: GameDesignerError) -> Self {
+        let message = error.to_string();
+        error.into_tool_error(&message)
+    }
+}
+
+/// Reports that `action` failed with `error`, e.g. `tool_error("load
+/// session", e)`. A [`GameDesignerError`] keeps its code; any other error is
+/// reported as `failed`.
+pub fn tool_error(action: &str, error: anyhow::Error) -> ToolError {
+    let message = format!("Failed to {}: {}", action, error);
+
+    match error.downcast::<GameDesignerError>() {
+        Ok(error) => error.into_tool_error(&message),
+        Err(_) => GameDesignerError::Failed(message.clone()).into_tool_error(&message),
+    }
+}
+
+/// Gives a [`ToolError`] raised without a [`GameDesignerError`] the JSON
+/// message of one, so that every failed call carries a code.
+pub fn normalize(error: ToolError) -> ToolError {
+    let message = match &error {
+        ToolError::InvalidParameters(message)
+        | ToolError::ExecutionError(message)
+        | ToolError::SchemaError(message)
+        | ToolError::NotFound(message) => message,
+    };
+    if serde_json::from_str::<Value>(message).is_ok_and(|value| value.get("error").is_some()) {
+        return error;
+    }
+
+    match error {
+        ToolError::InvalidParameters(message) => GameDesignerError::InvalidArguments(message),
+        ToolError::NotFound(message) => GameDesignerError::NotFound(message),
+        ToolError::ExecutionError(message) | ToolError::SchemaError(message) => {
+            GameDesignerError::Failed(message)
+        }
+    }
+    .into()
+}
@@ -0,0 +1,142 @@
+//! Human approval gate of feature reviews.
+//!
+//! With `[review] require_human_approval` the designer LLM is not the sole
+//! gatekeeper: a feature it accepts moves to
+//! [`FeatureStatus::AwaitingApproval`](crate::game_design::state::FeatureStatus)
+//! instead of `Reviewed`, and stays the feature being implemented until a
+//! person signs it off or sends it back with `humanApprove`.
+//!
+//! A decision only counts with the HMAC-SHA256 of its details under the
+//! approver secret, which the agent calling the tools doesn't know: the
+//! person signs it with `gamedesignerd sign-approval` and hands over the
+//! signature. The signature covers the review round, so it can't be replayed
+//! once the feature comes back for approval.
+
+use crate::{
+    error::GameDesignerError,
+    game_design::{config, signature, state::SessionState},
+};
+use anyhow::Result;
+use hmac::Mac;
+use std::{env, fs, path::PathBuf};
+
+/// Whether accepted features wait for human approval. Read from
+/// `GAMEDESIGNER_REQUIRE_HUMAN_APPROVAL` (`true`/`false`), falling back to the
+/// configuration file; off by default.
+pub fn required() -> bool {
+    env::var("GAMEDESIGNER_REQUIRE_HUMAN_APPROVAL")
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .or(config::current().review.require_human_approval)
+        .unwrap_or(false)
+}
+
+/// Reads the approver secret from `GAMEDESIGNER_APPROVER_SECRET`, or from the
+/// file named by `GAMEDESIGNER_APPROVER_SECRET_FILE` or
+/// `[review] approver_secret_file`.
+pub fn secret() -> Result<String> {
+    if let Ok(secret) = env::var("GAMEDESIGNER_APPROVER_SECRET") {
+        return Ok(secret);
+    }
+
+    let path: PathBuf = env::var_os("GAMEDESIGNER_APPROVER_SECRET_FILE")
+        .map(Into::into)
+        .or_else(|| config::current().review.approver_secret_file.clone())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Human approval needs an approver secret: set \
+                 GAMEDESIGNER_APPROVER_SECRET_FILE or [review] approver_secret_file"
+            )
+        })?;
+    let secret = fs::read_to_string(&path)
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "failed to read approver secret file {}: {}",
+                path.display(),
+                e
+            )
+        })?
+        .trim()
+        .to_string();
+
+    if secret.is_empty() {
+        anyhow::bail!("Approver secret file {} is empty", path.display());
+    }
+
+    Ok(secret)
+}
+
+/// Signs the decision on `feature` of `session` in `project` with the
+/// approver secret. Returns the hex-encoded signature.
+pub fn sign(
+    project: Option<&str>,
+    session: &SessionState,
+    feature: &str,
+    approved: bool,
+    author: &str,
+    comment: Option<&str>,
+) -> Result<String> {
+    Ok(signature::sign(
+        &secret()?,
+        message(project, session, feature, approved, author, comment).as_bytes(),
+    ))
+}
+
+/// Checks the `signature` of the decision on `feature` of `session` in
+/// `project`.
+pub fn verify(
+    project: Option<&str>,
+    session: &SessionState,
+    feature: &str,
+    approved: bool,
+    author: &str,
+    comment: Option<&str>,
+    signature: &str,
+) -> Result<()> {
+    let mut mac = signature::mac(&secret()?);
+    mac.update(message(project, session, feature, approved, author, comment).as_bytes());
+
+    let valid =
+        hex::decode(signature.trim()).is_ok_and(|signature| mac.verify_slice(&signature).is_ok());
+    if !valid {
+        return Err(GameDesignerError::InvalidArguments(format!(
+            "The signature does not match this decision on '{}'; sign it with \
+             `gamedesignerd sign-approval`",
+            feature
+        ))
+        .into());
+    }
+
+    Ok(())
+}
+
+/// The signed details of a decision. The project keeps a signature from
+/// counting for a session of the same name in another project. The review
+/// round is the number of reports and replies submitted for the feature so
+/// far.
+fn message(
+    project: Option<&str>,
+    session: &SessionState,
+    feature: &str,
+    approved: bool,
+    author: &str,
+    comment: Option<&str>,
+) -> String {
+    let round = session
+        .review_log
+        .iter()
+        .filter(|entry| entry.feature == feature)
+        .count();
+
+    // A JSON array keeps the fields apart whatever they contain
+    serde_json::json!([
+        project,
+        session.id,
+        feature,
+        round,
+        if approved { "approve" } else { "reject" },
+        author,
+        comment.unwrap_or_default(),
+    ])
+    .to_string()
+}
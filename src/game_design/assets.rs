@@ -0,0 +1,121 @@
+//! Art and audio requirements implied by features, aggregated per session so
+//! artists can work from the same plan as the coding agent.
+
+use crate::game_design::state::{FeatureStatus, SessionState};
+use serde::{Deserialize, Serialize};
+
+/// Kind of asset a feature needs.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetKind {
+    Sprite,
+    Animation,
+    Sfx,
+    Music,
+    Shader,
+    Model,
+    Font,
+    #[default]
+    #[serde(other)]
+    Other,
+}
+
+impl AssetKind {
+    pub const ALL: [Self; 8] = [
+        Self::Sprite,
+        Self::Animation,
+        Self::Sfx,
+        Self::Music,
+        Self::Shader,
+        Self::Model,
+        Self::Font,
+        Self::Other,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sprite => "sprite",
+            Self::Animation => "animation",
+            Self::Sfx => "sfx",
+            Self::Music => "music",
+            Self::Shader => "shader",
+            Self::Model => "model",
+            Self::Font => "font",
+            Self::Other => "other",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+/// A single asset a feature needs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetRequirement {
+    #[serde(default)]
+    pub kind: AssetKind,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// An asset requirement together with the feature that needs it.
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetEntry {
+    pub feature: String,
+    pub feature_status: FeatureStatus,
+    pub asset: AssetRequirement,
+}
+
+/// Collects the asset requirements of all planned features, optionally
+/// restricted to one kind, in feature order.
+pub fn collect(session: &SessionState, kind: Option<AssetKind>) -> Vec<AssetEntry> {
+    session
+        .planned_features
+        .iter()
+        .flat_map(|feature| {
+            feature.required_assets.iter().map(|asset| AssetEntry {
+                feature: feature.name.clone(),
+                feature_status: feature.status.clone(),
+                asset: asset.clone(),
+            })
+        })
+        .filter(|entry| kind.is_none_or(|kind| entry.asset.kind == kind))
+        .collect()
+}
+
+/// Renders asset entries as CSV with a header row.
+pub fn to_csv(entries: &[AssetEntry]) -> String {
+    let mut csv = String::from("kind,name,description,feature,feature_status\n");
+
+    for entry in entries {
+        let row = [
+            entry.asset.kind.name().to_string(),
+            entry.asset.name.clone(),
+            entry.asset.description.clone(),
+            entry.feature.clone(),
+            format!("{:?}", entry.feature_status),
+        ];
+
+        csv.push_str(
+            &row.iter()
+                .map(|field| csv_field(field))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        csv.push('\n');
+    }
+
+    csv
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,183 @@
+//! Screenshots and captures attached to feature reviews.
+//!
+//! `featureReview` accepts MCP image and embedded resource content parts.
+//! Each is stored once as `<session>/assets/<sha256>.<ext>` in the
+//! persistence directory, listed in the review prompt and recorded with the
+//! review in the transcript. If the model can see images (see
+//! [`designer_llm::vision`]), the images among them are shown to the
+//! reviewers as well.
+
+use crate::{
+    error::GameDesignerError,
+    game_design::{
+        designer_llm::{self, Image},
+        storage::Storage,
+    },
+};
+use anyhow::Result;
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+/// Most attachments of a single review.
+pub const MAX_ATTACHMENTS: usize = 8;
+
+/// Largest attachment, in bytes.
+pub const MAX_ATTACHMENT_BYTES: usize = 10 * 1024 * 1024;
+
+/// Directory of a session's attachments, inside its session directory.
+pub const ASSETS_DIR: &str = "assets";
+
+/// Image types shown to models that can see images.
+const IMAGE_TYPES: &[&str] = &["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+/// An attachment as submitted, before it is stored.
+#[derive(Debug, Clone)]
+pub struct Upload {
+    pub data: Vec<u8>,
+    pub mime_type: String,
+    /// URI the client gave an embedded resource, if any.
+    pub uri: Option<String>,
+}
+
+impl Upload {
+    /// Decodes the base64 `data` of an attachment of type `mime_type`.
+    pub fn decode(data: &str, mime_type: &str, uri: Option<String>) -> Result<Self> {
+        let mime_type = mime_type.trim().to_lowercase();
+        if mime_type.is_empty() || !mime_type.contains('/') {
+            return Err(GameDesignerError::InvalidArguments(format!(
+                "Attachments need a MIME type such as image/png, got '{}'",
+                mime_type
+            ))
+            .into());
+        }
+
+        let data = STANDARD.decode(data.trim()).map_err(|e| {
+            GameDesignerError::InvalidArguments(format!(
+                "Attachment of type {} is not valid base64: {}",
+                mime_type, e
+            ))
+        })?;
+        if data.is_empty() || data.len() > MAX_ATTACHMENT_BYTES {
+            return Err(GameDesignerError::InvalidArguments(format!(
+                "Attachments must have 1 to {} bytes, got {}",
+                MAX_ATTACHMENT_BYTES,
+                data.len()
+            ))
+            .into());
+        }
+
+        Ok(Self {
+            data,
+            mime_type,
+            uri,
+        })
+    }
+
+    /// The upload as an image for the model, if it is one the model can
+    /// read.
+    pub fn image(&self) -> Option<Image> {
+        IMAGE_TYPES
+            .contains(&self.mime_type.as_str())
+            .then(|| Image {
+                mime_type: self.mime_type.clone(),
+                data: STANDARD.encode(&self.data),
+            })
+    }
+}
+
+/// A stored attachment, as recorded in the review transcript.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attachment {
+    /// SHA-256 of the contents, hex-encoded.
+    pub sha256: String,
+    pub mime_type: String,
+    /// Where the contents are stored.
+    pub path: String,
+    pub bytes: usize,
+    /// URI the client gave an embedded resource, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+}
+
+/// Describes `upload` as stored in the assets directory of the session
+/// directory `session_dir`, without storing it, e.g. to list it in a prompt
+/// before the call that stores it succeeds.
+pub fn describe(session_dir: &Path, upload: &Upload) -> Attachment {
+    let sha256 = hex::encode(Sha256::digest(&upload.data));
+    let path =
+        session_dir
+            .join(ASSETS_DIR)
+            .join(format!("{}.{}", sha256, extension(&upload.mime_type)));
+
+    Attachment {
+        sha256,
+        mime_type: upload.mime_type.clone(),
+        path: path.to_string_lossy().into_owned(),
+        bytes: upload.data.len(),
+        uri: upload.uri.clone(),
+    }
+}
+
+/// Stores `upload` in `storage`, in the assets directory of the session
+/// directory `session_dir`, unless an attachment with the same contents is
+/// stored already.
+pub fn store(storage: &dyn Storage, session_dir: &Path, upload: &Upload) -> Result<Attachment> {
+    let attachment = describe(session_dir, upload);
+    let path = Path::new(&attachment.path);
+
+    if !storage.exists(path) {
+        storage.create_dir_all(&session_dir.join(ASSETS_DIR))?;
+        storage.write_bytes(path, &upload.data)?;
+    }
+
+    Ok(attachment)
+}
+
+/// The images among `uploads` if the model can see images, otherwise none.
+pub fn images(uploads: &[Upload]) -> Vec<Image> {
+    if !designer_llm::vision() {
+        return Vec::new();
+    }
+
+    uploads.iter().filter_map(Upload::image).collect()
+}
+
+/// Renders the attachments as a prompt section; empty without any. With
+/// `shown`, the images among them follow the prompt.
+pub fn section(attachments: &[Attachment], shown: bool) -> String {
+    if attachments.is_empty() {
+        return String::new();
+    }
+
+    let mut section = "Attachments submitted with the report:\n".to_string();
+    for attachment in attachments {
+        section.push_str(&format!(
+            "- {} ({}, {} bytes)\n",
+            attachment.path, attachment.mime_type, attachment.bytes
+        ));
+    }
+    if shown {
+        section.push_str("The images among them are attached below; check them too.\n");
+    }
+    section.push('\n');
+
+    section
+}
+
+/// File extension of attachments of type `mime_type`.
+fn extension(mime_type: &str) -> &str {
+    match mime_type {
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "video/mp4" => "mp4",
+        "video/webm" => "webm",
+        "text/plain" => "txt",
+        "application/json" => "json",
+        _ => "bin",
+    }
+}
@@ -0,0 +1,396 @@
+//! Mirrors the planned features of a session to a team's tracker.
+//!
+//! With GitHub, every feature gets an issue labelled `gamedesignerd` and a
+//! `status: ...` label, closed once the feature is reviewed. A sync pushes
+//! the statuses changed in the session since the last sync and pulls those
+//! changed on GitHub, by moving the status label or closing or reopening the
+//! issue. When both sides changed, the session wins. Trello has no import
+//! API, so its boards are exported as JSON in the shape of Trello's own
+//! board export instead.
+
+use crate::game_design::{
+    state::{Board, BoardLink, Feature, FeatureStatus, SessionState},
+    trackers::USER_AGENT,
+};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, Method};
+use serde_json::{Value, json};
+use std::{collections::HashMap, env};
+
+/// Label of every issue created for a feature.
+pub const LABEL: &str = "gamedesignerd";
+
+/// Most issue pages read from GitHub in one sync.
+const MAX_PAGES: u32 = 20;
+
+/// Statuses in the order of the board's columns.
+const COLUMNS: [FeatureStatus; 6] = [
+    FeatureStatus::Planned,
+    FeatureStatus::InProgress,
+    FeatureStatus::NeedsRework,
+    FeatureStatus::Implemented,
+    FeatureStatus::AwaitingApproval,
+    FeatureStatus::Reviewed,
+];
+
+/// Outcome of a sync with GitHub.
+#[derive(Debug, Clone)]
+pub struct BoardSync {
+    pub board: Board,
+    /// Features an issue was created for.
+    pub created: Vec<String>,
+    /// Features whose issue was updated to their status.
+    pub pushed: Vec<String>,
+    /// Features whose status was changed on GitHub, with the status they had
+    /// and the one to set.
+    pub pulled: Vec<(String, FeatureStatus, FeatureStatus)>,
+    /// Features GitHub refused to create or update, with the error. They
+    /// are retried on the next sync.
+    pub failed: Vec<(String, String)>,
+}
+
+/// Name of a status on the board, e.g. `In Progress`.
+pub fn column_name(status: &FeatureStatus) -> &'static str {
+    match status {
+        FeatureStatus::Planned => "Planned",
+        FeatureStatus::InProgress => "In Progress",
+        FeatureStatus::NeedsRework => "Needs Rework",
+        FeatureStatus::Implemented => "Implemented",
+        FeatureStatus::AwaitingApproval => "Awaiting Approval",
+        FeatureStatus::Reviewed => "Reviewed",
+    }
+}
+
+/// Issue label of a status, e.g. `status: in progress`.
+fn status_label(status: &FeatureStatus) -> String {
+    format!("status: {}", column_name(status).to_lowercase())
+}
+
+/// Status of an issue: closed issues are reviewed, open ones have the
+/// status of their label. A reopened issue that still carries the reviewed
+/// label needs rework.
+fn issue_status(issue: &Value) -> Option<FeatureStatus> {
+    if issue["state"] == "closed" {
+        return Some(FeatureStatus::Reviewed);
+    }
+
+    let labels = labels_of(issue);
+    let status = COLUMNS
+        .into_iter()
+        .find(|status| labels.contains(&status_label(status)))?;
+
+    Some(match status {
+        FeatureStatus::Reviewed => FeatureStatus::NeedsRework,
+        status => status,
+    })
+}
+
+fn labels_of(issue: &Value) -> Vec<String> {
+    issue["labels"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|label| label["name"].as_str())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Sends a GitHub API request. Requires `GITHUB_TOKEN` with write access
+/// to the repository's issues.
+async fn github(method: Method, path: &str, body: Option<Value>) -> Result<Value> {
+    let token = env::var("GITHUB_TOKEN")
+        .map_err(|_| anyhow::anyhow!("GITHUB_TOKEN environment variable not set"))?;
+
+    let mut request = Client::new()
+        .request(method, format!("https://api.github.com{}", path))
+        .header("User-Agent", USER_AGENT)
+        .header("Accept", "application/vnd.github+json")
+        .header("Authorization", format!("Bearer {}", token));
+
+    if let Some(body) = body {
+        request = request.json(&body);
+    }
+
+    let response = request.send().await?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().await?;
+
+        return Err(anyhow::anyhow!(
+            "GitHub API request failed with status {}: {}",
+            status,
+            error_text
+        ));
+    }
+
+    Ok(response.json().await?)
+}
+
+/// Reads every issue of `repository` labelled [`LABEL`], keyed by number.
+async fn fetch_issues(repository: &str) -> Result<HashMap<u64, Value>> {
+    let mut issues = HashMap::new();
+
+    for page in 1..=MAX_PAGES {
+        let batch = github(
+            Method::GET,
+            &format!(
+                "/repos/{}/issues?labels={}&state=all&per_page=100&page={}",
+                repository, LABEL, page
+            ),
+            None,
+        )
+        .await?;
+
+        let batch = batch.as_array().cloned().unwrap_or_default();
+        let last = batch.len() < 100;
+
+        for issue in batch {
+            if let Some(number) = issue["number"].as_u64() {
+                issues.insert(number, issue);
+            }
+        }
+
+        if last {
+            break;
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Syncs the features of `session` with the issues of `repository`, dating
+/// the sync `now`.
+///
+/// Issues of a board previously synced with another repository are not
+/// carried over; the features get new issues in `repository`.
+pub async fn sync_github(
+    session: &SessionState,
+    repository: &str,
+    now: DateTime<Utc>,
+) -> Result<BoardSync> {
+    if repository.split('/').count() != 2 {
+        return Err(anyhow::anyhow!(
+            "Invalid GitHub repository '{}'. Expected format: owner/name",
+            repository
+        ));
+    }
+
+    let previous = session
+        .board
+        .as_ref()
+        .filter(|board| board.repository == repository)
+        .map(|board| board.links.clone())
+        .unwrap_or_default();
+
+    let issues = fetch_issues(repository).await?;
+
+    let mut sync = BoardSync {
+        board: Board {
+            repository: repository.to_string(),
+            links: Default::default(),
+            synced_at: now,
+        },
+        created: Vec::new(),
+        pushed: Vec::new(),
+        pulled: Vec::new(),
+        failed: Vec::new(),
+    };
+
+    for feature in &session.planned_features {
+        let linked = previous
+            .get(&feature.name)
+            .and_then(|link| Some((link, issues.get(&link.issue)?)));
+
+        let Some((link, issue)) = linked else {
+            // New features, and those whose issue was deleted or transferred
+            match create_issue(repository, &session.id, feature).await {
+                Ok(created) => {
+                    sync.created.push(feature.name.clone());
+                    sync.board.links.insert(feature.name.clone(), created);
+                }
+                Err(e) => sync.failed.push((feature.name.clone(), e.to_string())),
+            }
+            continue;
+        };
+
+        let remote = issue_status(issue).unwrap_or_else(|| link.synced_status.clone());
+
+        let status = if feature.status != link.synced_status {
+            if remote != feature.status {
+                if let Err(e) = update_issue(repository, link.issue, issue, &feature.status).await {
+                    sync.failed.push((feature.name.clone(), e.to_string()));
+                    sync.board.links.insert(feature.name.clone(), link.clone());
+                    continue;
+                }
+                sync.pushed.push(feature.name.clone());
+            }
+            feature.status.clone()
+        } else if remote != link.synced_status {
+            sync.pulled
+                .push((feature.name.clone(), feature.status.clone(), remote.clone()));
+            remote
+        } else {
+            feature.status.clone()
+        };
+
+        sync.board.links.insert(
+            feature.name.clone(),
+            BoardLink {
+                synced_status: status,
+                ..link.clone()
+            },
+        );
+    }
+
+    Ok(sync)
+}
+
+async fn create_issue(repository: &str, session_id: &str, feature: &Feature) -> Result<BoardLink> {
+    let issue = github(
+        Method::POST,
+        &format!("/repos/{}/issues", repository),
+        Some(json!({
+            "title": feature.name,
+            "body": format!(
+                "{}\n\n---\n_Planned in design session `{}`. Synced by gamedesignerd's \
+                 `boardSync`: move the `status: ...` label or close the issue to change \
+                 the feature's status._",
+                feature.description, session_id
+            ),
+            "labels": [LABEL, status_label(&feature.status)],
+        })),
+    )
+    .await?;
+
+    let number = issue["number"]
+        .as_u64()
+        .ok_or_else(|| anyhow::anyhow!("GitHub returned an issue without a number"))?;
+
+    let mut synced_status = feature.status.clone();
+
+    // Issues can't be created closed
+    if feature.status == FeatureStatus::Reviewed {
+        let closed = github(
+            Method::PATCH,
+            &format!("/repos/{}/issues/{}", repository, number),
+            Some(json!({ "state": "closed", "state_reason": "completed" })),
+        )
+        .await;
+
+        if let Err(e) = closed {
+            // The issue exists either way; recording it as open makes the
+            // next sync close it
+            tracing::warn!("Failed to close issue #{} of {}: {}", number, repository, e);
+            synced_status = FeatureStatus::NeedsRework;
+        }
+    }
+
+    Ok(BoardLink {
+        issue: number,
+        url: issue["html_url"].as_str().unwrap_or_default().to_string(),
+        synced_status,
+    })
+}
+
+/// Sets the status label and state of an issue, keeping its other labels.
+async fn update_issue(
+    repository: &str,
+    number: u64,
+    issue: &Value,
+    status: &FeatureStatus,
+) -> Result<()> {
+    let mut labels = labels_of(issue)
+        .into_iter()
+        .filter(|label| !label.starts_with("status: "))
+        .collect::<Vec<_>>();
+    labels.push(status_label(status));
+
+    let state = if *status == FeatureStatus::Reviewed {
+        "closed"
+    } else {
+        "open"
+    };
+
+    github(
+        Method::PATCH,
+        &format!("/repos/{}/issues/{}", repository, number),
+        Some(json!({ "labels": labels, "state": state })),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Renders the features of `session` as a Trello board export: one list
+/// per status and one card per feature, labelled with its milestone.
+pub fn trello_board(session: &SessionState) -> Value {
+    let mut milestones = session
+        .planned_features
+        .iter()
+        .filter_map(|feature| feature.milestone.clone())
+        .collect::<Vec<_>>();
+    milestones.sort();
+    milestones.dedup();
+
+    let label_id = |milestone: &str| {
+        let index = milestones.iter().position(|m| m == milestone).unwrap_or(0);
+        format!("label-{}", index + 1)
+    };
+    let list_id = |status: &FeatureStatus| {
+        let index = COLUMNS.iter().position(|s| s == status).unwrap_or(0);
+        format!("list-{}", index + 1)
+    };
+
+    let lists = COLUMNS
+        .iter()
+        .enumerate()
+        .map(|(index, status)| {
+            json!({
+                "id": list_id(status),
+                "name": column_name(status),
+                "closed": false,
+                "pos": (index + 1) * 1024,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let labels = milestones
+        .iter()
+        .map(|milestone| json!({ "id": label_id(milestone), "name": milestone, "color": null }))
+        .collect::<Vec<_>>();
+
+    let cards = session
+        .planned_features
+        .iter()
+        .enumerate()
+        .map(|(index, feature)| {
+            let card_labels = feature
+                .milestone
+                .iter()
+                .map(|milestone| json!({ "id": label_id(milestone), "name": milestone }))
+                .collect::<Vec<_>>();
+
+            json!({
+                "id": format!("card-{}", index + 1),
+                "name": feature.name,
+                "desc": feature.description,
+                "idList": list_id(&feature.status),
+                "closed": false,
+                "pos": (index + 1) * 1024,
+                "idLabels": card_labels.iter().map(|label| label["id"].clone()).collect::<Vec<_>>(),
+                "labels": card_labels,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "name": session.id,
+        "desc": format!("Features of the design session '{}', exported by gamedesignerd.", session.id),
+        "closed": false,
+        "lists": lists,
+        "labels": labels,
+        "cards": cards,
+    })
+}
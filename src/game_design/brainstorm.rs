@@ -0,0 +1,175 @@
+//! Diverge/converge brainstorming of mechanics and twists on a topic.
+//!
+//! The designer first lists many raw ideas without judging them, then
+//! shortlists the strongest and scores each against the game's design
+//! pillars. Shortlisted ideas join the session's idea pool, which
+//! `nextFeature` draws on.
+
+use crate::game_design::{
+    DesignerLlmClient,
+    designer_llm::{ChatMessage, extract_json},
+    memory, prompts,
+    state::SessionState,
+};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Highest score of an idea against a single pillar.
+pub const MAX_PILLAR_SCORE: u32 = 5;
+
+/// An unjudged idea of the diverging phase.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawIdea {
+    pub name: String,
+    pub description: String,
+}
+
+/// How well an idea serves one design pillar.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PillarScore {
+    pub pillar: String,
+    /// From `0`, working against the pillar, to [`MAX_PILLAR_SCORE`].
+    pub score: u32,
+}
+
+/// A shortlisted idea in the session's idea pool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Idea {
+    pub name: String,
+    pub description: String,
+    /// Topic of the brainstorm the idea came from.
+    pub topic: String,
+    pub scores: Vec<PillarScore>,
+    /// Why the idea made the shortlist.
+    pub rationale: String,
+    /// Feature that was built on the idea, once `nextFeature` drew on it.
+    #[serde(default)]
+    pub feature: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Idea {
+    /// Sum of the pillar scores.
+    pub fn total(&self) -> u32 {
+        self.scores.iter().map(|score| score.score).sum()
+    }
+}
+
+/// Outcome of a brainstorm.
+#[derive(Debug, Clone)]
+pub struct Brainstorm {
+    pub ideas: Vec<RawIdea>,
+    /// The strongest ideas, highest total score first.
+    pub shortlist: Vec<Idea>,
+}
+
+/// Asks the LLM for `ideas` raw ideas on `topic` and a shortlist of the
+/// `shortlist` strongest, scored against the session's design pillars.
+/// Shortlisted ideas are dated `now`.
+pub async fn brainstorm(
+    llm_client: &DesignerLlmClient,
+    session: &SessionState,
+    topic: &str,
+    ideas: usize,
+    shortlist: usize,
+    now: DateTime<Utc>,
+) -> Result<Brainstorm> {
+    let mut prompt = memory::design_context(session);
+
+    if !session.planned_features.is_empty() {
+        prompt.push_str("Already planned features:\n");
+        for feature in &session.planned_features {
+            prompt.push_str(&format!("- {}\n", feature.name));
+        }
+        prompt.push('\n');
+    }
+
+    if !session.ideas.is_empty() {
+        prompt.push_str("Ideas already in the pool (do not repeat them):\n");
+        for idea in &session.ideas {
+            prompt.push_str(&format!("- {}\n", idea.name));
+        }
+        prompt.push('\n');
+    }
+
+    prompt.push_str(&format!(
+        "Brainstorm on this topic: {}\n\n\
+         First diverge: list {} raw ideas, including unusual twists, without judging them. \
+         Then converge: identify the design pillars of this game (its stated core goals) and \
+         shortlist the {} ideas that serve them best. Score each shortlisted idea against every \
+         pillar from 0 (works against it) to {} (embodies it) and explain in one sentence why \
+         it made the shortlist. Format your response as a JSON object with an 'ideas' array \
+         of objects with 'name' and 'description' fields, and a 'shortlist' array of objects \
+         with 'name', 'description', 'scores' (array of objects with 'pillar' and 'score') \
+         and 'rationale' fields. Only return the JSON, nothing else.",
+        topic.trim(),
+        ideas,
+        shortlist,
+        MAX_PILLAR_SCORE
+    ));
+
+    let messages = vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: session.system_prompt(prompts::DESIGN_BRAINSTORM),
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content: prompt,
+        },
+    ];
+
+    let response = llm_client.call_llm(messages).await?;
+
+    #[derive(Deserialize)]
+    struct ShortlistedIdea {
+        name: String,
+        description: String,
+        #[serde(default)]
+        scores: Vec<PillarScore>,
+        #[serde(default)]
+        rationale: String,
+    }
+
+    #[derive(Deserialize)]
+    struct Response {
+        #[serde(default)]
+        ideas: Vec<RawIdea>,
+        #[serde(default)]
+        shortlist: Vec<ShortlistedIdea>,
+    }
+
+    let json = extract_json(&response)
+        .ok_or_else(|| anyhow::anyhow!("LLM response was not valid JSON: {}", response))?;
+    let response: Response = serde_json::from_str(json)?;
+
+    let mut shortlist = response
+        .shortlist
+        .into_iter()
+        .take(shortlist)
+        .map(|idea| Idea {
+            name: idea.name.trim().to_string(),
+            description: idea.description.trim().to_string(),
+            topic: topic.trim().to_string(),
+            scores: idea
+                .scores
+                .into_iter()
+                .map(|score| PillarScore {
+                    score: score.score.min(MAX_PILLAR_SCORE),
+                    ..score
+                })
+                .collect(),
+            rationale: idea.rationale.trim().to_string(),
+            feature: None,
+            created_at: now,
+        })
+        .filter(|idea| !idea.name.is_empty())
+        .collect::<Vec<_>>();
+    shortlist.sort_by_key(|idea| std::cmp::Reverse(idea.total()));
+
+    Ok(Brainstorm {
+        ideas: response.ideas,
+        shortlist,
+    })
+}
@@ -0,0 +1,178 @@
+//! Content-addressed cache of LLM responses, so that repeating an identical
+//! request (e.g. `nextFeature` after a crash) does not cost another paid call.
+//!
+//! Entries are stored as `<sha256 of the request>.json` in `cache/` of the
+//! persistence directory and expire after a TTL (`GAMEDESIGNER_CACHE_TTL_SECS`,
+//! default one day). `--no-cache` disables the cache for the process.
+//! `gamedesignerd cache stats|clear` inspects or empties it.
+
+use crate::game_design::{config, session::persistence_dir};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    env, fmt, fs,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+/// TTL used when neither the environment nor the configuration sets one.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Disables the cache for every LLM call of this process.
+pub fn disable() {
+    DISABLED.store(true, Ordering::Relaxed);
+}
+
+/// Reads the TTL from `GAMEDESIGNER_CACHE_TTL_SECS`, falling back to the
+/// configuration file. A TTL of zero disables the cache.
+pub fn ttl() -> Duration {
+    env::var("GAMEDESIGNER_CACHE_TTL_SECS")
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .or(config::current().llm.cache_ttl_secs)
+        .map_or(DEFAULT_TTL, Duration::from_secs)
+}
+
+fn enabled() -> bool {
+    !DISABLED.load(Ordering::Relaxed) && !ttl().is_zero()
+}
+
+/// Directory holding the cache entries.
+pub fn dir() -> PathBuf {
+    persistence_dir().join("cache")
+}
+
+/// Entries and size of a cache directory.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub bytes: u64,
+    /// Entries past their TTL that have not been removed yet.
+    pub expired: usize,
+    pub oldest: Option<DateTime<Utc>>,
+}
+
+impl fmt::Display for CacheStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} entries, {:.1} MB, {} expired",
+            self.entries,
+            self.bytes as f64 / (1024.0 * 1024.0),
+            self.expired
+        )?;
+        if let Some(oldest) = self.oldest {
+            write!(f, ", oldest from {}", oldest.format("%Y-%m-%d %H:%M"))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    created_at: DateTime<Utc>,
+    response: String,
+}
+
+/// Hashes the complete request body, which covers the model, the messages
+/// and the sampling parameters.
+fn entry_path(request: &impl Serialize) -> Result<PathBuf> {
+    let digest = Sha256::digest(serde_json::to_vec(request)?);
+    Ok(dir().join(format!("{}.json", hex::encode(digest))))
+}
+
+/// Returns the cached response to `request` if there is a fresh one.
+/// Expired entries are removed.
+pub fn lookup(request: &impl Serialize) -> Option<String> {
+    if !enabled() {
+        return None;
+    }
+
+    let path = entry_path(request).ok()?;
+    let entry: CacheEntry = serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+
+    let age = (Utc::now() - entry.created_at).to_std().unwrap_or_default();
+    if age > ttl() {
+        fs::remove_file(&path).ok();
+        return None;
+    }
+
+    tracing::debug!("LLM response served from cache: {}", path.display());
+    Some(entry.response)
+}
+
+/// Caches the response to `request`. Failures are logged.
+pub fn store(request: &impl Serialize, response: &str) {
+    if !enabled() {
+        return;
+    }
+
+    let result = entry_path(request).and_then(|path| {
+        fs::create_dir_all(dir())?;
+        let entry = CacheEntry {
+            created_at: Utc::now(),
+            response: response.to_string(),
+        };
+        fs::write(path, serde_json::to_string(&entry)?)?;
+        Ok(())
+    });
+
+    if let Err(e) = result {
+        tracing::warn!("Failed to cache LLM response: {}", e);
+    }
+}
+
+/// Counts the cached responses and their size.
+pub fn stats() -> Result<CacheStats> {
+    let mut stats = CacheStats::default();
+    if !dir().exists() {
+        return Ok(stats);
+    }
+
+    let ttl = ttl();
+    for file in fs::read_dir(dir())? {
+        let path = file?.path();
+        stats.entries += 1;
+        stats.bytes += fs::metadata(&path)?.len();
+
+        let entry = fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<CacheEntry>(&json).ok());
+        match entry {
+            Some(entry) => {
+                let age = (Utc::now() - entry.created_at).to_std().unwrap_or_default();
+                if age > ttl {
+                    stats.expired += 1;
+                }
+                stats.oldest = Some(
+                    stats
+                        .oldest
+                        .map_or(entry.created_at, |oldest| oldest.min(entry.created_at)),
+                );
+            }
+            None => stats.expired += 1,
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Removes every cached response and returns how many there were.
+pub fn clear() -> Result<usize> {
+    if !dir().exists() {
+        return Ok(0);
+    }
+
+    let mut removed = 0;
+    for file in fs::read_dir(dir())? {
+        fs::remove_file(file?.path())?;
+        removed += 1;
+    }
+
+    Ok(removed)
+}
@@ -0,0 +1,439 @@
+//! Layered configuration. Settings are read, from lowest to highest
+//! precedence, from the defaults, the user configuration
+//! `~/.config/gamedesignerd/config.toml`, the project configuration
+//! `gamedesignerd.toml` in the working directory, environment variables and
+//! command-line flags.
+//!
+//! ```toml
+//! default_persona = "indie_minimalist"
+//!
+//! [storage]
+//! path = ".gamedesignerd"
+//!
+//! [llm]
+//! provider = "openrouter"
+//! api_key_file = "/run/secrets/openrouter_api_key"
+//! model = "tngtech/deepseek-r1t2-chimera:free"
+//! temperature = 0.7
+//! max_tokens = 4000
+//! prompt_budget = 24000
+//! concurrency = 2
+//! queue_capacity = 8
+//! cache_ttl_secs = 86400
+//! vision = true
+//! vision_model = "google/gemini-2.5-flash"
+//!
+//! [llm.tools.nextFeature]
+//! temperature = 1.0
+//!
+//! [embeddings]
+//! provider = "openai"
+//! model = "text-embedding-3-small"
+//! api_key_file = "/run/secrets/openai_api_key"
+//!
+//! [scope]
+//! effort_budget = 40
+//!
+//! [features]
+//! spec_check = true
+//! duplicate_threshold = 0.85
+//! similar_threshold = 0.6
+//!
+//! [review]
+//! max_follow_ups = 3
+//! require_human_approval = true
+//! approver_secret_file = "/run/secrets/gamedesignerd_approver"
+//!
+//! [monetization]
+//! regions = ["EU", "BE", "NL", "US"]
+//! audience_includes_minors = true
+//! rules = ["No energy systems that block play"]
+//!
+//! [tools]
+//! timeout_secs = 300
+//!
+//! [tools.timeouts]
+//! designNew = 600
+//!
+//! [limits.fields]
+//! changesMade = 24000
+//!
+//! [docs]
+//! cache_ttl_secs = 604800
+//! cache_max_mb = 100
+//!
+//! [redaction]
+//! enabled = true
+//! patterns = ["MYSTUDIO-[0-9A-F]{32}"]
+//!
+//! [guard]
+//! max_calls_per_minute = 30
+//! max_consecutive_next_feature = 5
+//! cooldown_secs = 300
+//! daily_token_budget = 2000000
+//!
+//! [log]
+//! level = "info"
+//! format = "text"
+//!
+//! [transport]
+//! address = "127.0.0.1:8080"
+//! socket = "/tmp/gamedesignerd.sock"
+//! metrics_address = "127.0.0.1:9090"
+//! ```
+//!
+//! Tables of the files are merged key by key, so the project file only needs
+//! to name the settings it changes. The files can be re-read at runtime and
+//! applied with [`replace`], which the server does on `SIGHUP` and through the
+//! `configReload` tool. A reload is applied as a whole or not at all, and
+//! every changed setting is logged; the storage path, logging and transport
+//! settings take effect on restart.
+
+use crate::game_design::{
+    designer_llm::{LlmProvider, Sampling},
+    embeddings::EmbeddingProvider,
+    persona::Persona,
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    env, fs,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{Arc, LazyLock, RwLock},
+};
+
+/// Name of the project configuration file.
+pub const CONFIG_FILE: &str = "gamedesignerd.toml";
+
+/// Returns the path of the user configuration file:
+/// `$XDG_CONFIG_HOME/gamedesignerd/config.toml`, falling back to
+/// `~/.config` (or `%APPDATA%` on Windows).
+pub fn user_config_path() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))?;
+
+    Some(base.join("gamedesignerd").join("config.toml"))
+}
+
+/// Returns the configuration files that exist, lowest precedence first.
+pub fn sources() -> Vec<PathBuf> {
+    user_config_path()
+        .into_iter()
+        .chain([PathBuf::from(CONFIG_FILE)])
+        .filter(|path| path.exists())
+        .collect()
+}
+
+/// Storage settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StorageConfig {
+    /// Directory holding sessions, logs of events, caches and exports;
+    /// `--data-dir` and `GAMEDESIGNERD_DATA_DIR` win.
+    pub path: Option<PathBuf>,
+    /// Sessions kept in memory per project, least recently used ones
+    /// evicted first; `0` for no limit.
+    pub max_resident_sessions: Option<usize>,
+}
+
+/// LLM settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LlmConfig {
+    pub provider: Option<LlmProvider>,
+    /// File holding the OpenRouter API key, re-read on reload so that keys
+    /// can be rotated; `OPENROUTER_API_KEY` wins.
+    pub api_key_file: Option<PathBuf>,
+    pub model: Option<String>,
+    pub temperature: Option<f32>,
+    /// Nucleus sampling cutoff; left to the provider when unset.
+    pub top_p: Option<f32>,
+    /// Longest completion of a call.
+    pub max_tokens: Option<u32>,
+    pub prompt_budget: Option<usize>,
+    /// LLM-backed tool calls running at the same time.
+    pub concurrency: Option<usize>,
+    /// LLM-backed tool calls allowed to wait for a slot.
+    pub queue_capacity: Option<usize>,
+    /// Lifetime of cached LLM responses; `0` disables the cache.
+    pub cache_ttl_secs: Option<u64>,
+    /// Mark the repeating start of prompts for providers that cache
+    /// prompts only at explicit breakpoints.
+    pub prompt_caching: Option<bool>,
+    /// The model can see images, e.g. screenshots attached to reviews.
+    pub vision: Option<bool>,
+    /// Model of the calls that show images, if `model` can't see them.
+    pub vision_model: Option<String>,
+    /// Sampling of single tools by name, overriding the settings above.
+    pub tools: HashMap<String, Sampling>,
+}
+
+/// Embeddings of `designSearch`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmbeddingsConfig {
+    pub provider: Option<EmbeddingProvider>,
+    /// Model of the `openai` provider.
+    pub model: Option<String>,
+    /// Endpoint of the `openai` provider, for OpenAI-compatible services.
+    pub url: Option<String>,
+    /// File holding the API key of the `openai` provider.
+    pub api_key_file: Option<PathBuf>,
+}
+
+/// Roadmap scoping settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScopeConfig {
+    pub effort_budget: Option<u32>,
+}
+
+/// Feature specification settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeaturesConfig {
+    /// New `nextFeature` specifications are checked for duplicates and
+    /// contradictions before they are handed out.
+    pub spec_check: Option<bool>,
+    /// Similarity to a rejected feature from which a proposal is skipped.
+    pub duplicate_threshold: Option<f32>,
+    /// Similarity to a rejected feature from which a proposal is annotated.
+    pub similar_threshold: Option<f32>,
+}
+
+/// Feature review settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReviewConfig {
+    /// `reviewReply` rounds before the designer must accept or reject; `0`
+    /// disables the limit.
+    pub max_follow_ups: Option<usize>,
+    /// Features the designer accepts wait for `humanApprove` before they
+    /// count as reviewed.
+    pub require_human_approval: Option<bool>,
+    /// File holding the secret that `humanApprove` decisions are signed
+    /// with; `GAMEDESIGNER_APPROVER_SECRET` wins.
+    pub approver_secret_file: Option<PathBuf>,
+}
+
+/// Policy of monetization reviews.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MonetizationConfig {
+    /// Markets the game ships in.
+    pub regions: Vec<String>,
+    /// Whether players may be minors; assumed unless set to `false`.
+    pub audience_includes_minors: Option<bool>,
+    /// Rules checked in addition to the built-in ones.
+    pub rules: Vec<String>,
+    /// Whether the built-in rules apply.
+    pub default_rules: Option<bool>,
+}
+
+/// Tool call settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolsConfig {
+    /// Time a tool call may take before it is aborted; `0` disables the
+    /// limit.
+    pub timeout_secs: Option<u64>,
+    /// Timeouts of single tools by name, overriding `timeout_secs`.
+    pub timeouts: HashMap<String, u64>,
+}
+
+/// Size limits of free-text tool arguments.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LimitsConfig {
+    /// Limits in characters by argument name, overriding the defaults; `0`
+    /// disables the limit.
+    pub fields: HashMap<String, usize>,
+}
+
+/// Documentation lookup settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DocsConfig {
+    /// Lifetime of cached documentation of the latest crate versions; `0`
+    /// disables the cache.
+    pub cache_ttl_secs: Option<u64>,
+    /// Size of the documentation cache beyond which the oldest entries are
+    /// evicted.
+    pub cache_max_mb: Option<u64>,
+}
+
+/// Redaction of secrets in transcripts and LLM requests.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionConfig {
+    /// Whether secrets are redacted; on unless set to `false`.
+    pub enabled: Option<bool>,
+    /// Regular expressions of secrets in addition to the built-in ones.
+    pub patterns: Vec<String>,
+}
+
+/// Circuit breaker limits.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuardLimits {
+    pub max_calls_per_minute: Option<usize>,
+    pub max_consecutive_next_feature: Option<u32>,
+    pub cooldown_secs: Option<u64>,
+    /// LLM tokens a session may consume per day; `0` disables the limit.
+    pub daily_token_budget: Option<u64>,
+}
+
+/// Format of server log lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines
+    Text,
+    /// One JSON object per line, with span fields, for Loki or Elastic
+    Json,
+}
+
+/// Logging settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogConfig {
+    /// Level such as `info` or `debug`; `RUST_LOG` and `--debug` win.
+    pub level: Option<String>,
+    pub format: Option<LogFormat>,
+}
+
+/// Server transport settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransportConfig {
+    /// Address of the HTTP server.
+    pub address: Option<String>,
+    /// Local socket served by `stdio` instead of stdin/stdout.
+    pub socket: Option<PathBuf>,
+    /// Address serving Prometheus metrics in `stdio` mode.
+    pub metrics_address: Option<SocketAddr>,
+}
+
+/// Merged contents of the configuration files. Every setting is optional.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Persona of new sessions that don't choose one.
+    pub default_persona: Option<Persona>,
+    pub storage: StorageConfig,
+    pub llm: LlmConfig,
+    pub embeddings: EmbeddingsConfig,
+    pub scope: ScopeConfig,
+    pub features: FeaturesConfig,
+    pub review: ReviewConfig,
+    pub monetization: MonetizationConfig,
+    pub tools: ToolsConfig,
+    pub limits: LimitsConfig,
+    pub docs: DocsConfig,
+    pub redaction: RedactionConfig,
+    pub guard: GuardLimits,
+    pub log: LogConfig,
+    pub transport: TransportConfig,
+}
+
+impl Config {
+    /// Loads and merges the configuration files returned by [`sources`].
+    /// Without any file the default configuration is returned.
+    pub fn load() -> Result<Self> {
+        let mut merged = toml::Table::new();
+
+        for path in sources() {
+            let table: toml::Table = toml::from_str(&fs::read_to_string(&path)?)
+                .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+            merge(&mut merged, table);
+        }
+
+        Ok(toml::Value::Table(merged).try_into()?)
+    }
+}
+
+/// Merges `overlay` into `base`, descending into tables present in both.
+fn merge(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => merge(base, overlay),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+static CONFIG: LazyLock<RwLock<Arc<Config>>> = LazyLock::new(|| {
+    let config = Config::load().unwrap_or_else(|e| {
+        tracing::warn!("Failed to load the configuration: {}", e);
+        Config::default()
+    });
+
+    RwLock::new(Arc::new(config))
+});
+
+/// Returns the active configuration.
+pub fn current() -> Arc<Config> {
+    CONFIG.read().unwrap().clone()
+}
+
+/// Makes `config` the active configuration and returns the previous one.
+pub fn replace(config: Config) -> Arc<Config> {
+    std::mem::replace(&mut *CONFIG.write().unwrap(), Arc::new(config))
+}
+
+/// Describes the settings that differ between two configurations, one line
+/// per setting, e.g. `llm.model: "a" -> "b"`.
+pub fn changes(old: &Config, new: &Config) -> Vec<String> {
+    fn flatten(prefix: String, value: serde_json::Value, out: &mut HashMap<String, String>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, value) in map {
+                    let key = if prefix.is_empty() {
+                        key
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    flatten(key, value, out);
+                }
+            }
+            value => {
+                out.insert(prefix, value.to_string());
+            }
+        }
+    }
+
+    let settings = [old, new].map(|config| {
+        let mut settings = HashMap::new();
+        flatten(
+            String::new(),
+            serde_json::to_value(config).unwrap_or_default(),
+            &mut settings,
+        );
+        settings
+    });
+    let [old, new] = &settings;
+
+    let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    keys.into_iter()
+        .filter(|key| old.get(*key) != new.get(*key))
+        .map(|key| {
+            let value = |settings: &HashMap<String, String>| {
+                settings
+                    .get(key)
+                    .cloned()
+                    .unwrap_or_else(|| "null".to_string())
+            };
+            format!("{}: {} -> {}", key, value(old), value(new))
+        })
+        .collect()
+}
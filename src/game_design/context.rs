@@ -0,0 +1,242 @@
+//! Keeps designer prompts within the model's context window.
+//!
+//! Token counts are estimated (roughly four characters per token) rather than
+//! computed with a model-specific tokenizer; the budget leaves enough headroom
+//! for that to be safe.
+
+use crate::game_design::{
+    DesignerLlmClient, config, designer_llm::ChatMessage, prompts, state::SessionState,
+};
+use anyhow::Result;
+use std::env;
+
+/// Prompt budget used when `GAMEDESIGNER_PROMPT_BUDGET` is not set.
+pub const DEFAULT_PROMPT_BUDGET_TOKENS: usize = 24_000;
+
+/// Number of most recent implementation reports always included verbatim.
+const RECENT_FULL_REPORTS: usize = 3;
+
+/// Size a report digest is truncated to if the LLM cannot produce one.
+const FALLBACK_DIGEST_TOKENS: usize = 200;
+
+/// Estimates the number of tokens in `text`.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Reads the prompt budget from `GAMEDESIGNER_PROMPT_BUDGET`, falling back to
+/// the configuration file.
+pub fn prompt_budget() -> usize {
+    env::var("GAMEDESIGNER_PROMPT_BUDGET")
+        .ok()
+        .and_then(|budget| budget.parse().ok())
+        .or(config::current().llm.prompt_budget)
+        .unwrap_or(DEFAULT_PROMPT_BUDGET_TOKENS)
+}
+
+/// Shortens `text` to about `max_tokens` by cutting out its middle, leaving a
+/// visible marker so that the omission is never silent.
+pub fn truncate_middle(text: &str, max_tokens: usize) -> String {
+    let max_chars = max_tokens * 4;
+    let char_count = text.chars().count();
+
+    if char_count <= max_chars {
+        return text.to_string();
+    }
+
+    let head_chars = max_chars * 2 / 3;
+    let tail_chars = max_chars - head_chars;
+    let head: String = text.chars().take(head_chars).collect();
+    let tail: String = text.chars().skip(char_count - tail_chars).collect();
+
+    format!(
+        "{}\n[... {} characters omitted to fit the context window ...]\n{}",
+        head,
+        char_count - head_chars - tail_chars,
+        tail
+    )
+}
+
+/// Shrinks the largest messages until the conversation fits into `budget`.
+pub fn fit_to_budget(mut messages: Vec<ChatMessage>, budget: usize) -> Vec<ChatMessage> {
+    let total: usize = messages.iter().map(|m| estimate_tokens(&m.content)).sum();
+
+    if total <= budget {
+        return messages;
+    }
+
+    tracing::warn!(
+        "Prompt of ~{} tokens exceeds the budget of {} tokens, truncating",
+        total,
+        budget
+    );
+
+    let mut excess = total - budget;
+
+    while excess > 0 {
+        let Some(largest) = messages
+            .iter_mut()
+            .max_by_key(|m| estimate_tokens(&m.content))
+        else {
+            break;
+        };
+
+        let size = estimate_tokens(&largest.content);
+        let target = size.saturating_sub(excess);
+
+        // Even an empty message would not make the prompt fit
+        if target == 0 || size <= 1 {
+            largest.content = truncate_middle(&largest.content, 1);
+            break;
+        }
+
+        largest.content = truncate_middle(&largest.content, target);
+        excess = excess.saturating_sub(size - estimate_tokens(&largest.content));
+    }
+
+    messages
+}
+
+/// Builds the "already implemented features" prompt section within
+/// `budget` tokens.
+///
+/// The most recent reports are included verbatim. Older ones are replaced by
+/// digests that are generated once and cached in the session, and if that is
+/// still too large every entry is truncated evenly.
+pub async fn implemented_features_section(
+    llm_client: &DesignerLlmClient,
+    session: &mut SessionState,
+    budget: usize,
+) -> String {
+    // Reports in the order their features were planned
+    let mut names: Vec<String> = session
+        .planned_features
+        .iter()
+        .map(|f| f.name.clone())
+        .filter(|name| session.implemented_features_reports.contains_key(name))
+        .collect();
+    for name in session.implemented_features_reports.keys() {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+
+    if names.is_empty() {
+        return String::new();
+    }
+
+    let mut entries: Vec<(String, String)> = names
+        .iter()
+        .map(|name| {
+            (
+                name.clone(),
+                session.implemented_features_reports[name].clone(),
+            )
+        })
+        .collect();
+
+    let render = |entries: &[(String, String)]| {
+        let mut section =
+            "Already implemented features with their implementation reports:\n".to_string();
+        for (name, report) in entries {
+            section.push_str(&format!("- {}: {}\n", name, report));
+        }
+        section.push('\n');
+        section
+    };
+
+    let section = render(&entries);
+    if estimate_tokens(&section) <= budget {
+        return section;
+    }
+
+    // Replace older reports with cached digests, requesting the missing
+    // ones at the same time
+    let older = entries.len().saturating_sub(RECENT_FULL_REPORTS);
+    let requests = entries
+        .iter()
+        .take(older)
+        .filter(|(name, _)| !session.report_digests.contains_key(name))
+        .map(|(name, report)| async move { (name, digest_report(llm_client, name, report).await) });
+    for (name, digest) in futures::future::join_all(requests).await {
+        match digest {
+            Ok(digest) => {
+                session.report_digests.insert(name.clone(), digest);
+            }
+            Err(e) => tracing::warn!("Failed to digest report of '{}': {}", name, e),
+        }
+    }
+
+    for (name, report) in entries.iter_mut().take(older) {
+        *report = match session.report_digests.get(name.as_str()) {
+            Some(digest) => digest.clone(),
+            None => truncate_middle(report, FALLBACK_DIGEST_TOKENS),
+        };
+    }
+
+    let section = render(&entries);
+    if estimate_tokens(&section) <= budget {
+        return section;
+    }
+
+    // Still too large: give every entry an equal share of the budget
+    let share = (budget / entries.len()).max(1);
+    for (_, report) in entries.iter_mut() {
+        *report = truncate_middle(report, share);
+    }
+
+    render(&entries)
+}
+
+/// Asks the LLM to condense the oversized argument `field` of `tool` to
+/// about `max_chars` characters.
+pub async fn summarize_input(
+    llm_client: &DesignerLlmClient,
+    tool: &str,
+    field: &str,
+    text: &str,
+    max_chars: usize,
+) -> Result<String> {
+    let messages = vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: prompts::system_prompt(prompts::INPUT_SUMMARY),
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content: format!(
+                "Condense the `{}` argument of {} to at most {} characters:\n\n{}",
+                field,
+                tool,
+                max_chars,
+                truncate_middle(text, prompt_budget() / 2)
+            ),
+        },
+    ];
+
+    Ok(llm_client.call_llm(messages).await?.trim().to_string())
+}
+
+/// Asks the LLM for a compact digest of an implementation report.
+async fn digest_report(
+    llm_client: &DesignerLlmClient,
+    feature_name: &str,
+    report: &str,
+) -> Result<String> {
+    let messages = vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: prompts::system_prompt(prompts::REPORT_DIGEST),
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content: format!(
+                "Condense the implementation report of feature '{}' into at most three \
+                 sentences:\n\n{}",
+                feature_name, report
+            ),
+        },
+    ];
+
+    Ok(llm_client.call_llm(messages).await?.trim().to_string())
+}
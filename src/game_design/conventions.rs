@@ -0,0 +1,94 @@
+//! Project conventions injected into the designer's prompts.
+//!
+//! Conventions are named, free-form rules of a session such as `naming`,
+//! `codeStyle`, `engineVersion` or `targetFps`, set with `conventionsSet`.
+//! They are part of [`memory::design_context`](crate::game_design::memory),
+//! so every feature specification and review is written against them. Since
+//! they are repeated in every prompt, their size is limited to a share of
+//! the prompt budget.
+
+use crate::{error::GameDesignerError, game_design::context};
+use anyhow::Result;
+use std::collections::BTreeMap;
+
+/// Conventions may take up at most 1/`MAX_BUDGET_SHARE` of the prompt budget.
+pub const MAX_BUDGET_SHARE: usize = 10;
+
+/// Longest name of a convention, in characters.
+pub const MAX_NAME_CHARS: usize = 64;
+
+/// Largest size of all conventions together, in estimated tokens.
+pub fn max_tokens() -> usize {
+    context::prompt_budget() / MAX_BUDGET_SHARE
+}
+
+/// Applies `update` to `conventions`: a convention with an empty rule is
+/// removed, any other is set. With `replace` the conventions not in
+/// `update` are removed as well.
+pub fn merge(
+    conventions: &BTreeMap<String, String>,
+    update: BTreeMap<String, String>,
+    replace: bool,
+) -> BTreeMap<String, String> {
+    let mut merged = if replace {
+        BTreeMap::new()
+    } else {
+        conventions.clone()
+    };
+
+    for (name, rule) in update {
+        let (name, rule) = (name.trim().to_string(), rule.trim().to_string());
+
+        if rule.is_empty() {
+            merged.remove(&name);
+        } else {
+            merged.insert(name, rule);
+        }
+    }
+
+    merged
+}
+
+/// Checks the names and the size of `conventions` and returns their size in
+/// estimated tokens.
+pub fn validate(conventions: &BTreeMap<String, String>) -> Result<usize> {
+    for name in conventions.keys() {
+        if name.is_empty() || name.chars().count() > MAX_NAME_CHARS {
+            return Err(GameDesignerError::InvalidArguments(format!(
+                "Convention names must have 1 to {} characters: '{}'",
+                MAX_NAME_CHARS, name
+            ))
+            .into());
+        }
+    }
+
+    let tokens = context::estimate_tokens(&section(conventions));
+    let max_tokens = max_tokens();
+    if tokens > max_tokens {
+        return Err(GameDesignerError::InvalidArguments(format!(
+            "The conventions take about {} tokens, more than the {} tokens (1/{} of the prompt \
+             budget) they may add to every prompt; shorten or remove some",
+            tokens, max_tokens, MAX_BUDGET_SHARE
+        ))
+        .into());
+    }
+
+    Ok(tokens)
+}
+
+/// Renders the conventions as a prompt section; empty without conventions.
+pub fn section(conventions: &BTreeMap<String, String>) -> String {
+    if conventions.is_empty() {
+        return String::new();
+    }
+
+    let mut section =
+        "Project conventions (every feature must follow these and reviews must check them):\n"
+            .to_string();
+    for (name, rule) in conventions {
+        section.push_str(&format!("- {}: {}\n", name, rule));
+    }
+    section.push('\n');
+
+    section
+}
@@ -0,0 +1,93 @@
+//! Acceptance criteria of features and their check in reviews.
+//!
+//! Every proposed feature carries a list of verifiable acceptance criteria.
+//! Reviews check the implementation report against each of them and answer,
+//! after their verdict, with a [`CRITERIA_MARKER`] section holding one
+//! `- [pass]` or `- [fail]` line per criterion. A failed criterion keeps the
+//! feature from being approved.
+
+use serde::{Deserialize, Serialize};
+
+/// Line that starts the per-criterion results of a review.
+pub const CRITERIA_MARKER: &str = "CRITERIA:";
+
+/// Outcome of the check of one acceptance criterion.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CriterionResult {
+    pub criterion: String,
+    pub passed: bool,
+    /// What is missing, for failed criteria.
+    #[serde(default)]
+    pub note: String,
+}
+
+/// Review instruction to check the report against `criteria`.
+pub fn instruction(criteria: &[String]) -> String {
+    let mut instruction = String::from("Acceptance criteria of the feature:\n");
+    for (number, criterion) in criteria.iter().enumerate() {
+        instruction.push_str(&format!("{}. {}\n", number + 1, criterion));
+    }
+    instruction.push_str(&format!(
+        "\nCheck the implementation against each acceptance criterion. After your response, \
+         add a line '{}' followed by one line per criterion, in order, as '- [pass] 1' or \
+         '- [fail] 1: what is missing'. The feature can only be approved if every criterion \
+         passes.\n\n",
+        CRITERIA_MARKER
+    ));
+
+    instruction
+}
+
+/// Splits a review into the verdict and the results of `criteria`.
+/// Criteria the review did not report on count as failed.
+pub fn split<'a>(review: &'a str, criteria: &[String]) -> (&'a str, Vec<CriterionResult>) {
+    if criteria.is_empty() {
+        return (review, Vec::new());
+    }
+
+    let (verdict, section) = match review.find(CRITERIA_MARKER) {
+        Some(index) => (&review[..index], &review[index + CRITERIA_MARKER.len()..]),
+        None => (review, ""),
+    };
+
+    // Results by the number of the criterion they report on
+    let mut reported = Vec::new();
+    for line in section.lines() {
+        let line = line.trim().trim_start_matches(['-', '*']).trim();
+        let Some((tag, rest)) = line.strip_prefix('[').and_then(|rest| rest.split_once(']')) else {
+            continue;
+        };
+        let passed = match tag.trim().to_lowercase().as_str() {
+            "pass" => true,
+            "fail" => false,
+            _ => continue,
+        };
+
+        let (number, note) = rest.split_once(':').unwrap_or((rest, ""));
+        let Ok(number) = number.trim().trim_end_matches('.').parse::<usize>() else {
+            continue;
+        };
+        reported.push((number, passed, note.trim().to_string()));
+    }
+
+    let results = criteria
+        .iter()
+        .enumerate()
+        .map(
+            |(index, criterion)| match reported.iter().find(|(number, ..)| *number == index + 1) {
+                Some((_, passed, note)) => CriterionResult {
+                    criterion: criterion.clone(),
+                    passed: *passed,
+                    note: note.clone(),
+                },
+                None => CriterionResult {
+                    criterion: criterion.clone(),
+                    passed: false,
+                    note: "Not checked by the review".to_string(),
+                },
+            },
+        )
+        .collect();
+
+    (verdict.trim_end(), results)
+}
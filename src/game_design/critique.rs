@@ -0,0 +1,168 @@
+//! UI/UX critiques of screenshots.
+//!
+//! `uiCritique` shows a screenshot of a feature together with its
+//! specification to a model that can see images (see
+//! [`designer_llm::vision`]) and asks for the readability, hierarchy and
+//! affordance problems it shows. The critique is kept with the feature,
+//! next to the stored screenshot.
+
+use crate::game_design::{
+    DesignerLlmClient,
+    attachments::{Attachment, Upload},
+    designer_llm::{self, ChatMessage, extract_json},
+    lint::Severity,
+    memory, prompts,
+    state::{Feature, SessionState},
+};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// What a UI problem is about.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UiAspect {
+    /// Text, icons or numbers that are hard to read.
+    Readability,
+    /// Emphasis, grouping and order that don't match what matters.
+    Hierarchy,
+    /// Elements that don't show what they do or that they can be used.
+    Affordance,
+    #[serde(other)]
+    Other,
+}
+
+/// A problem found on a screenshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UiIssue {
+    pub aspect: UiAspect,
+    pub severity: Severity,
+    /// The element of the screen, e.g. the health bar.
+    pub element: String,
+    pub problem: String,
+    pub suggestion: String,
+}
+
+/// Critique of a screenshot of a feature.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UiCritique {
+    /// Overall impression in a sentence or two.
+    pub summary: String,
+    pub issues: Vec<UiIssue>,
+    /// The critiqued screenshot.
+    pub screenshot: Attachment,
+    /// What the screenshot shows, as described by the developer.
+    #[serde(default)]
+    pub context: Option<String>,
+    /// Agent or person who asked for the critique, if given.
+    #[serde(default)]
+    pub author: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl UiCritique {
+    /// Renders the critique as Markdown, most severe issues first.
+    pub fn markdown(&self) -> String {
+        let mut markdown = format!("{}\n", self.summary.trim());
+
+        let mut issues = self.issues.iter().collect::<Vec<_>>();
+        issues.sort_by_key(|issue| issue.severity);
+
+        if !issues.is_empty() {
+            markdown.push('\n');
+        }
+        for issue in issues {
+            markdown.push_str(&format!(
+                "- [{:?}] {:?} of {}: {} Suggestion: {}\n",
+                issue.severity,
+                issue.aspect,
+                issue.element.trim(),
+                issue.problem.trim(),
+                issue.suggestion.trim()
+            ));
+        }
+
+        markdown.trim_end().to_string()
+    }
+}
+
+/// Asks the model for a critique of `screenshot`, a screenshot of `feature`
+/// stored as `stored`. The critique is dated `now`.
+pub async fn critique(
+    llm_client: &DesignerLlmClient,
+    session: &SessionState,
+    feature: &Feature,
+    screenshot: &Upload,
+    stored: Attachment,
+    context: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<UiCritique> {
+    let image = screenshot
+        .image()
+        .ok_or_else(|| anyhow::anyhow!("{} is not an image type", screenshot.mime_type))?;
+
+    let mut prompt = memory::design_context(session);
+
+    prompt.push_str(&format!(
+        "Feature:\nName: {}\n{}\n\n",
+        feature.name,
+        feature.specification()
+    ));
+
+    if let Some(context) = context {
+        prompt.push_str(&format!(
+            "What the screenshot shows:\n{}\n\n",
+            context.trim()
+        ));
+    }
+
+    prompt.push_str(
+        "The attached screenshot shows this feature in the game. Critique its UI from the \
+         player's side: readability (text size, contrast, clutter), hierarchy (whether what \
+         matters most stands out and related elements are grouped) and affordance (whether \
+         interactive elements look usable and show what they do). Check it against the \
+         feature's specification and the project conventions. Format your response as a \
+         JSON object with a 'summary' string and an 'issues' array of objects with 'aspect' \
+         ('readability', 'hierarchy' or 'affordance'), 'severity' ('high', 'medium' or \
+         'low'), 'element', 'problem' and 'suggestion' fields. Only return the JSON, \
+         nothing else.",
+    );
+
+    let messages = vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: session.system_prompt(prompts::UI_CRITIQUE),
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content: prompt,
+        },
+    ];
+
+    let response = designer_llm::with_images(vec![image], llm_client.call_llm(messages)).await?;
+
+    #[derive(Deserialize)]
+    struct Response {
+        #[serde(default)]
+        summary: String,
+        #[serde(default)]
+        issues: Vec<UiIssue>,
+    }
+
+    let json = extract_json(&response)
+        .ok_or_else(|| anyhow::anyhow!("LLM response was not valid JSON: {}", response))?;
+    let response: Response = serde_json::from_str(json)?;
+
+    if response.summary.trim().is_empty() && response.issues.is_empty() {
+        return Err(anyhow::anyhow!("LLM response contained an empty critique"));
+    }
+
+    Ok(UiCritique {
+        summary: response.summary,
+        issues: response.issues,
+        screenshot: stored,
+        context: context.map(str::to_string),
+        author: None,
+        created_at: now,
+    })
+}
@@ -0,0 +1,269 @@
+//! Technical debt surfaced by feature reviews.
+//!
+//! A review may accept an implementation that works but is messy. The
+//! designer then answers with [`CAVEATS_VERDICT`] followed by its caveats,
+//! and each caveat is kept as a debt item instead of vanishing with the
+//! review. Every few features, `nextFeature` is asked to pay down the open
+//! high-priority items; approving the feature that does settles them.
+
+use crate::game_design::state::SessionState;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// First line of a review that accepts an implementation with caveats.
+pub const CAVEATS_VERDICT: &str = "SATISFIED WITH CAVEATS";
+
+/// Features proposed since high-priority debt was last addressed before
+/// `nextFeature` is asked to pay it down.
+pub const DEBT_PAYDOWN_INTERVAL: usize = 3;
+
+/// How urgently a debt item should be paid down.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum DebtPriority {
+    High,
+    Medium,
+    Low,
+}
+
+impl DebtPriority {
+    pub const ALL: [Self; 3] = [Self::High, Self::Medium, Self::Low];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::High => "high",
+            Self::Medium => "medium",
+            Self::Low => "low",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|priority| priority.name() == name)
+    }
+}
+
+/// Where a debt item stands.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DebtStatus {
+    #[default]
+    Open,
+    /// A proposed feature pays it down.
+    Scheduled,
+    /// The feature paying it down was approved.
+    Paid,
+}
+
+impl DebtStatus {
+    pub const ALL: [Self; 3] = [Self::Open, Self::Scheduled, Self::Paid];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Scheduled => "scheduled",
+            Self::Paid => "paid",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        Self::ALL.into_iter().find(|status| status.name() == name)
+    }
+}
+
+/// A caveat of an accepted review, kept until it is paid down.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebtItem {
+    /// Number of the item within the session, starting at `1`.
+    pub id: u32,
+    pub description: String,
+    pub priority: DebtPriority,
+    /// Feature whose review surfaced the item.
+    pub feature: String,
+    #[serde(default)]
+    pub status: DebtStatus,
+    /// Feature that pays the item down, once scheduled.
+    #[serde(default)]
+    pub paid_by: Option<String>,
+    pub recorded_at: DateTime<Utc>,
+    #[serde(default)]
+    pub paid_at: Option<DateTime<Utc>>,
+}
+
+/// Reads the caveats of a review that starts with [`CAVEATS_VERDICT`], one
+/// per line as `- [high] description`. Caveats without a priority count as
+/// medium. Returns `None` for any other review.
+pub fn caveats(review: &str) -> Option<Vec<(DebtPriority, String)>> {
+    let mut lines = review.trim().lines();
+    let verdict = lines.next()?.trim().trim_end_matches(':').trim();
+    if !verdict.eq_ignore_ascii_case(CAVEATS_VERDICT) {
+        return None;
+    }
+
+    let caveats = lines
+        .map(|line| line.trim().trim_start_matches(['-', '*']).trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let tagged = line
+                .strip_prefix('[')
+                .and_then(|rest| rest.split_once(']'))
+                .and_then(|(tag, rest)| Some((DebtPriority::parse(tag)?, rest.trim())));
+            match tagged {
+                Some((priority, description)) => (priority, description.to_string()),
+                None => (DebtPriority::Medium, line.to_string()),
+            }
+        })
+        .filter(|(_, description)| !description.is_empty())
+        .collect();
+
+    Some(caveats)
+}
+
+/// Adds the caveats of the review of `feature` to the session's debt,
+/// recorded at `recorded_at`, and returns the new items.
+pub fn record(
+    session: &mut SessionState,
+    feature: &str,
+    caveats: Vec<(DebtPriority, String)>,
+    recorded_at: DateTime<Utc>,
+) -> Vec<DebtItem> {
+    let mut next_id = session
+        .tech_debt
+        .iter()
+        .map(|item| item.id)
+        .max()
+        .unwrap_or(0)
+        + 1;
+
+    let items = caveats
+        .into_iter()
+        .map(|(priority, description)| {
+            let item = DebtItem {
+                id: next_id,
+                description,
+                priority,
+                feature: feature.to_string(),
+                status: DebtStatus::Open,
+                paid_by: None,
+                recorded_at,
+                paid_at: None,
+            };
+            next_id += 1;
+            item
+        })
+        .collect::<Vec<_>>();
+
+    session.tech_debt.extend(items.iter().cloned());
+    items
+}
+
+/// Returns the open high-priority items once at least
+/// [`DEBT_PAYDOWN_INTERVAL`] features were proposed since the oldest of them
+/// was recorded and since debt was last scheduled, or nothing while a
+/// paydown is still pending.
+pub fn paydown_due(session: &SessionState) -> Vec<&DebtItem> {
+    let position = |name: &str| {
+        session
+            .planned_features
+            .iter()
+            .position(|feature| feature.name == name)
+    };
+
+    if session
+        .tech_debt
+        .iter()
+        .any(|item| item.status == DebtStatus::Scheduled)
+    {
+        return Vec::new();
+    }
+
+    let open = session
+        .tech_debt
+        .iter()
+        .filter(|item| item.status == DebtStatus::Open && item.priority == DebtPriority::High)
+        .collect::<Vec<_>>();
+
+    let since = open
+        .iter()
+        .filter_map(|item| position(&item.feature))
+        .min()
+        .into_iter()
+        .chain(
+            session
+                .tech_debt
+                .iter()
+                .filter_map(|item| item.paid_by.as_deref().and_then(position)),
+        )
+        .max();
+
+    let proposed_since = match since {
+        Some(index) => session.planned_features.len() - index - 1,
+        None => session.planned_features.len(),
+    };
+
+    if proposed_since < DEBT_PAYDOWN_INTERVAL {
+        return Vec::new();
+    }
+
+    open
+}
+
+/// Instruction asking `nextFeature` to pay down one of `items`.
+pub fn paydown_instruction(items: &[&DebtItem]) -> String {
+    let mut instruction = String::from(
+        "\n\nThe following high-priority technical debt was accepted in earlier reviews and is \
+         due to be paid down:\n",
+    );
+    for item in items {
+        instruction.push_str(&format!(
+            "- #{} (from {}): {}\n",
+            item.id, item.feature, item.description
+        ));
+    }
+    instruction.push_str(
+        "Instead of new gameplay, propose a focused refactoring feature that pays down one or \
+         more of these items, and add a 'debt' field with the array of their numbers.",
+    );
+
+    instruction
+}
+
+/// Marks the open items numbered `ids` as paid down by `feature`.
+pub fn schedule(session: &mut SessionState, ids: &[u32], feature: &str) {
+    for item in &mut session.tech_debt {
+        if ids.contains(&item.id) && item.status == DebtStatus::Open {
+            item.status = DebtStatus::Scheduled;
+            item.paid_by = Some(feature.to_string());
+        }
+    }
+}
+
+/// Returns the items scheduled for `feature` to the open debt, e.g. when
+/// the feature is rejected.
+pub fn unschedule(session: &mut SessionState, feature: &str) {
+    for item in &mut session.tech_debt {
+        if item.status == DebtStatus::Scheduled && item.paid_by.as_deref() == Some(feature) {
+            item.status = DebtStatus::Open;
+            item.paid_by = None;
+        }
+    }
+}
+
+/// Settles the items scheduled for the approved `feature`, paid at
+/// `paid_at`, and returns their numbers.
+pub fn settle(session: &mut SessionState, feature: &str, paid_at: DateTime<Utc>) -> Vec<u32> {
+    let mut settled = Vec::new();
+
+    for item in &mut session.tech_debt {
+        if item.status == DebtStatus::Scheduled && item.paid_by.as_deref() == Some(feature) {
+            item.status = DebtStatus::Paid;
+            item.paid_at = Some(paid_at);
+            settled.push(item.id);
+        }
+    }
+
+    settled
+}
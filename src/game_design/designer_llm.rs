@@ -1,9 +1,316 @@
 //! Handles communication with the underlying Game Designer LLM API (e.g., OpenRouter).
 
+use crate::{
+    error::GameDesignerError,
+    game_design::{
+        cache,
+        config::{self, Config},
+        context,
+        mock_llm::MockLlmProvider,
+        recording::{self, ReplayProvider},
+        redact,
+        session::persistence_dir,
+    },
+};
 use anyhow::Result;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::env;
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    sync::{
+        Arc, OnceLock, RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Instant,
+};
+use tracing::field::Empty;
+
+tokio::task_local! {
+    /// Counts the tokens of the LLM calls made within [`metered`].
+    static METER: Arc<AtomicU64>;
+
+    /// Tool and sampling overrides of the LLM calls made within [`sampled`].
+    static SAMPLING: (String, Sampling);
+
+    /// Images shown with the LLM calls made within [`with_images`].
+    static IMAGES: Arc<Vec<Image>>;
+}
+
+/// Runs `future`, adding the prompt and completion tokens of its LLM calls
+/// to `meter` as each call completes.
+pub async fn metered<F: Future>(meter: Arc<AtomicU64>, future: F) -> F::Output {
+    METER.scope(meter, future).await
+}
+
+/// Runs `future`, the call of `tool`, sampling its LLM calls with
+/// `overrides` where they are set.
+pub async fn sampled<F: Future>(tool: String, overrides: Sampling, future: F) -> F::Output {
+    SAMPLING.scope((tool, overrides), future).await
+}
+
+/// Runs `future`, showing `images` to the model with the last user message
+/// of each of its LLM calls.
+pub async fn with_images<F: Future>(images: Vec<Image>, future: F) -> F::Output {
+    IMAGES.scope(Arc::new(images), future).await
+}
+
+/// An image shown to the model.
+#[derive(Debug, Clone)]
+pub struct Image {
+    pub mime_type: String,
+    /// Contents, base64-encoded.
+    pub data: String,
+}
+
+/// Whether the model can see images. Read from `GAMEDESIGNER_VISION`
+/// (`true`/`false`), falling back to the configuration file; off by
+/// default, since models without vision reject requests with images, unless
+/// a [`vision_model`] is set.
+pub fn vision() -> bool {
+    env::var("GAMEDESIGNER_VISION")
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .or(config::current().llm.vision)
+        .unwrap_or_else(|| vision_model().is_some())
+}
+
+/// Model of the LLM calls that show images, if it differs from the main
+/// model. Read from `GAMEDESIGNER_VISION_MODEL`, falling back to the
+/// configuration file.
+pub fn vision_model() -> Option<String> {
+    env::var("GAMEDESIGNER_VISION_MODEL")
+        .ok()
+        .filter(|model| !model.trim().is_empty())
+        .or_else(|| config::current().llm.vision_model.clone())
+}
+
+/// Sampling seed for reproducible runs, set once at startup.
+static SEED: OnceLock<u64> = OnceLock::new();
+
+/// Enables deterministic mode for every LLM call of this process:
+/// temperature is pinned to 0 and `seed` is forwarded to the provider.
+pub fn set_seed(seed: u64) {
+    if SEED.set(seed).is_err() {
+        tracing::warn!("Sampling seed was already set, ignoring {}", seed);
+    }
+}
+
+/// Returns the sampling seed if deterministic mode is enabled.
+pub fn seed() -> Option<u64> {
+    SEED.get().copied()
+}
+
+/// Backend answering the designer's LLM calls.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LlmProvider {
+    /// OpenRouter, authenticated with `OPENROUTER_API_KEY`.
+    #[default]
+    Openrouter,
+    /// Deterministic canned responses for offline use and tests.
+    Mock,
+    /// Responses from a recording made with `--record`.
+    Replay,
+}
+
+/// Provider selected at startup.
+static PROVIDER: OnceLock<LlmProvider> = OnceLock::new();
+
+/// Selects the LLM provider for every client created afterwards.
+pub fn set_provider(provider: LlmProvider) {
+    if PROVIDER.set(provider).is_err() {
+        tracing::warn!("LLM provider was already set, ignoring {:?}", provider);
+    }
+}
+
+/// Returns the selected LLM provider, falling back to the configured one.
+pub fn provider() -> LlmProvider {
+    provider_of(&config::current())
+}
+
+fn provider_of(config: &Config) -> LlmProvider {
+    PROVIDER
+        .get()
+        .copied()
+        .or(config.llm.provider)
+        .unwrap_or_default()
+}
+
+/// Returns the OpenRouter API key: `OPENROUTER_API_KEY`, then the contents
+/// of the file named by `OPENROUTER_API_KEY_FILE` or `[llm] api_key_file`.
+/// The file is read on every call, so that replacing it and reloading the
+/// configuration rotates the key.
+pub fn api_key(config: &Config) -> Result<String> {
+    if let Ok(api_key) = env::var("OPENROUTER_API_KEY") {
+        return Ok(api_key);
+    }
+
+    let path = env::var_os("OPENROUTER_API_KEY_FILE")
+        .map(Into::into)
+        .or_else(|| config.llm.api_key_file.clone())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "neither OPENROUTER_API_KEY nor OPENROUTER_API_KEY_FILE nor [llm] api_key_file is set"
+            )
+        })?;
+    let api_key = fs::read_to_string(&path)
+        .map_err(|e| anyhow::anyhow!("failed to read API key file {}: {}", path.display(), e))?
+        .trim()
+        .to_string();
+
+    if api_key.is_empty() {
+        anyhow::bail!("API key file {} is empty", path.display());
+    }
+
+    Ok(api_key)
+}
+
+/// Sampling temperature used when neither the command line nor the
+/// configuration sets one.
+pub const DEFAULT_TEMPERATURE: f32 = 0.7;
+
+/// Sampling temperature selected on the command line.
+static TEMPERATURE: OnceLock<f32> = OnceLock::new();
+
+/// Overrides the configured sampling temperature.
+pub fn set_temperature(temperature: f32) {
+    if TEMPERATURE.set(temperature).is_err() {
+        tracing::warn!("Temperature was already set, ignoring {}", temperature);
+    }
+}
+
+/// Returns the sampling temperature: 0 in deterministic mode, otherwise the
+/// command-line value, then the configured one, then the default.
+pub fn temperature() -> f32 {
+    if seed().is_some() {
+        return 0.0;
+    }
+
+    TEMPERATURE
+        .get()
+        .copied()
+        .or(config::current().llm.temperature)
+        .unwrap_or(DEFAULT_TEMPERATURE)
+}
+
+/// Completion length used when nothing else sets one.
+pub const DEFAULT_MAX_TOKENS: u32 = 4000;
+
+/// Longest completion a call may request.
+const MAX_TOKENS_LIMIT: u32 = 32_000;
+
+/// Sampling parameters of an LLM call. Unset values fall back to the next
+/// layer; an unset `top_p` is left to the provider.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Sampling {
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    pub max_tokens: Option<u32>,
+}
+
+impl Sampling {
+    /// Fills the values unset in `self` from `fallback`.
+    pub fn or(self, fallback: Sampling) -> Sampling {
+        Sampling {
+            temperature: self.temperature.or(fallback.temperature),
+            top_p: self.top_p.or(fallback.top_p),
+            max_tokens: self.max_tokens.or(fallback.max_tokens),
+        }
+    }
+
+    /// Built-in defaults of `tool`: brainstorming tools sample more freely
+    /// than those judging an implementation.
+    pub fn tool_default(tool: &str) -> Sampling {
+        let temperature = match tool {
+            "designNew" | "nextFeature" | "designInterview" | "devlogGenerate" => Some(0.9),
+            "designBrainstorm" => Some(1.0),
+            "featureReview"
+            | "reviewReply"
+            | "designReviewRequest"
+            | "designLint"
+            | "specCheck"
+            | "uiCritique" => Some(0.3),
+            "backlogImport" | "designImport" | "commitMessage" => Some(0.2),
+            _ => None,
+        };
+
+        Sampling {
+            temperature,
+            ..Sampling::default()
+        }
+    }
+
+    /// Clamps the values into the ranges providers accept.
+    fn clamped(self) -> Sampling {
+        Sampling {
+            temperature: self.temperature.map(|t| t.clamp(0.0, 2.0)),
+            top_p: self.top_p.map(|p| p.clamp(0.0, 1.0)),
+            max_tokens: self.max_tokens.map(|n| n.clamp(1, MAX_TOKENS_LIMIT)),
+        }
+    }
+}
+
+/// Returns the sampling parameters of an LLM call, from highest to lowest
+/// precedence: the tool call's arguments, the `[llm.tools.<tool>]`
+/// configuration, the global settings, the built-in defaults of the tool
+/// and the defaults. Deterministic mode pins the temperature to 0.
+pub fn sampling() -> Sampling {
+    let (tool, overrides) = SAMPLING.try_with(|scope| scope.clone()).unwrap_or_default();
+    let llm = &config::current().llm;
+
+    let global = Sampling {
+        temperature: TEMPERATURE.get().copied().or(llm.temperature),
+        top_p: llm.top_p,
+        max_tokens: llm.max_tokens,
+    };
+    let defaults = Sampling {
+        temperature: Some(DEFAULT_TEMPERATURE),
+        top_p: None,
+        max_tokens: Some(DEFAULT_MAX_TOKENS),
+    };
+
+    let mut sampling = overrides
+        .or(llm.tools.get(&tool).copied().unwrap_or_default())
+        .or(global)
+        .or(Sampling::tool_default(&tool))
+        .or(defaults)
+        .clamped();
+
+    if seed().is_some() {
+        sampling.temperature = Some(0.0);
+    }
+
+    sampling
+}
+
+/// Model selected on the command line.
+static MODEL: OnceLock<String> = OnceLock::new();
+
+/// Overrides the model of every LLM call of this process.
+pub fn set_model(model: String) {
+    if let Err(model) = MODEL.set(model) {
+        tracing::warn!("Model was already set, ignoring {}", model);
+    }
+}
+
+/// Why the LLM client could not be created, if it could not.
+static UNAVAILABLE_REASON: RwLock<Option<String>> = RwLock::new(None);
+
+/// Records why the process runs without an LLM provider, for the
+/// `llm_unavailable` errors of the tools that need one. `None` once a
+/// provider is configured.
+pub fn set_unavailable_reason(reason: Option<String>) {
+    *UNAVAILABLE_REASON.write().unwrap() = reason;
+}
+
+/// Returns why the process runs without an LLM provider, if it does.
+pub fn unavailable_reason() -> Option<String> {
+    UNAVAILABLE_REASON.read().unwrap().clone()
+}
 
 /// A chat message in the conversation with the LLM.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,15 +323,181 @@ pub struct ChatMessage {
 #[derive(Debug, Serialize)]
 struct LlmRequest {
     model: String,
-    messages: Vec<ChatMessage>,
+    messages: Vec<WireMessage>,
     temperature: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
     max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+}
+
+/// A message as sent to the provider.
+#[derive(Debug, Serialize)]
+struct WireMessage {
+    role: String,
+    content: WireContent,
+}
+
+/// Plain text, or text parts ending at prompt caching breakpoints.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum WireContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+#[derive(Debug, Serialize)]
+struct ContentPart {
+    r#type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image_url: Option<ImageUrl>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cache_control: Option<CacheControl>,
+}
+
+impl ContentPart {
+    fn text(text: &str, breakpoint: bool) -> Self {
+        Self {
+            r#type: "text",
+            text: Some(text.to_string()),
+            image_url: None,
+            cache_control: breakpoint.then_some(CacheControl {
+                r#type: "ephemeral",
+            }),
+        }
+    }
+
+    fn image(image: &Image) -> Self {
+        Self {
+            r#type: "image_url",
+            text: None,
+            image_url: Some(ImageUrl {
+                url: format!("data:{};base64,{}", image.mime_type, image.data),
+            }),
+            cache_control: None,
+        }
+    }
+}
+
+/// An image as a data URL.
+#[derive(Debug, Serialize)]
+struct ImageUrl {
+    url: String,
+}
+
+/// Asks the provider to cache the prompt up to the end of the part.
+#[derive(Debug, Serialize)]
+struct CacheControl {
+    r#type: &'static str,
+}
+
+/// Reads whether prompt caching breakpoints are sent from
+/// `GAMEDESIGNER_PROMPT_CACHING` (`off` disables them), falling back to the
+/// configuration file. On by default.
+pub fn prompt_caching() -> bool {
+    match env::var("GAMEDESIGNER_PROMPT_CACHING") {
+        Ok(value) => !matches!(value.trim(), "off" | "false" | "0"),
+        Err(_) => config::current().llm.prompt_caching.unwrap_or(true),
+    }
+}
+
+/// Whether the model only caches prompts at explicit breakpoints. OpenAI
+/// and DeepSeek models cache repeated prefixes on their own.
+fn takes_cache_breakpoints(model: &str) -> bool {
+    model.starts_with("anthropic/") || model.starts_with("google/gemini")
+}
+
+/// Converts the conversation into the messages sent to the provider. With
+/// `breakpoints`, the system prompt and the `prefix` the first user message
+/// starts with are marked for the provider to cache, since they repeat
+/// across the calls of a session. The `images` follow the text of the last
+/// user message.
+fn wire_messages(
+    messages: &[ChatMessage],
+    prefix: &str,
+    breakpoints: bool,
+    images: &[Image],
+) -> Vec<WireMessage> {
+    let mut prefix = Some(prefix).filter(|prefix| breakpoints && !prefix.is_empty());
+
+    let mut wire = messages
+        .iter()
+        .map(|message| {
+            let content = match message.role.as_str() {
+                "system" if breakpoints => {
+                    WireContent::Parts(vec![ContentPart::text(&message.content, true)])
+                }
+                "user" => match prefix.take().and_then(|prefix| {
+                    message
+                        .content
+                        .strip_prefix(prefix)
+                        .filter(|rest| !rest.is_empty())
+                        .map(|rest| (prefix, rest))
+                }) {
+                    Some((prefix, rest)) => WireContent::Parts(vec![
+                        ContentPart::text(prefix, true),
+                        ContentPart::text(rest, false),
+                    ]),
+                    None => WireContent::Text(message.content.clone()),
+                },
+                _ => WireContent::Text(message.content.clone()),
+            };
+
+            WireMessage {
+                role: message.role.clone(),
+                content,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if !images.is_empty()
+        && let Some(message) = wire.iter_mut().rev().find(|message| message.role == "user")
+    {
+        let mut parts =
+            match std::mem::replace(&mut message.content, WireContent::Parts(Vec::new())) {
+                WireContent::Text(text) => vec![ContentPart::text(&text, false)],
+                WireContent::Parts(parts) => parts,
+            };
+        parts.extend(images.iter().map(ContentPart::image));
+        message.content = WireContent::Parts(parts);
+    }
+
+    wire
 }
 
 /// Response structure from the LLM API.
 #[derive(Debug, Deserialize)]
 struct LlmResponse {
     choices: Vec<LlmChoice>,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
+    /// Identifies the provider's backend configuration; runs are only
+    /// reproducible while it stays the same.
+    #[serde(default)]
+    system_fingerprint: Option<String>,
+    #[serde(default)]
+    usage: Option<LlmUsage>,
+}
+
+/// Token counts reported by the provider.
+#[derive(Debug, Deserialize)]
+struct LlmUsage {
+    prompt_tokens: u64,
+    completion_tokens: u64,
+    #[serde(default)]
+    prompt_tokens_details: Option<PromptTokensDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PromptTokensDetails {
+    /// Prompt tokens read from the provider's prompt cache.
+    #[serde(default)]
+    cached_tokens: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -32,72 +505,366 @@ struct LlmChoice {
     message: ChatMessage,
 }
 
+/// Extracts the outermost JSON object or array from an LLM response.
+/// Models occasionally wrap JSON in prose or code fences.
+pub fn extract_json(response: &str) -> Option<&str> {
+    let start = response.find(['{', '['])?;
+    let close = if response[start..].starts_with('{') {
+        '}'
+    } else {
+        ']'
+    };
+    let end = response.rfind(close)?;
+
+    (start < end).then(|| &response[start..=end])
+}
+
 /// Client for interacting with the Game Designer LLM.
 pub struct DesignerLlmClient {
-    client: Client,
-    api_key: String,
-    model: String, // e.g., "tngtech/deepseek-r1t2-chimera:free"
+    backend: Backend,
+}
+
+enum Backend {
+    OpenRouter { client: Client, api_key: String },
+    Mock(MockLlmProvider),
+    Replay(ReplayProvider),
+}
+
+impl Backend {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::OpenRouter { .. } => "openrouter",
+            Self::Mock(_) => "mock",
+            Self::Replay(_) => "replay",
+        }
+    }
 }
 
 pub const CHIMERA_MODEL: &str = "tngtech/deepseek-r1t2-chimera:free";
 
 impl DesignerLlmClient {
-    /// Creates a new `DesignerLlmClient`.
-    /// Expects `OPENROUTER_API_KEY` environment variable to be set.
+    /// Creates a new `DesignerLlmClient` for the selected provider.
+    /// OpenRouter expects an API key, see [`api_key`].
     pub fn new() -> Result<Self> {
-        let api_key = env::var("OPENROUTER_API_KEY")
-            .map_err(|_| anyhow::anyhow!("OPENROUTER_API_KEY environment variable not set"))?;
+        Self::from_config(&config::current())
+    }
 
-        let model = env::var("GAMEDESIGNER_MODEL").unwrap_or_else(|_| CHIMERA_MODEL.to_owned());
+    /// Creates a client for the provider and credentials of `config`.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let backend = match provider_of(config) {
+            LlmProvider::Mock => Backend::Mock(MockLlmProvider::new()),
+            LlmProvider::Replay => Backend::Replay(ReplayProvider::new()?),
+            LlmProvider::Openrouter => Backend::OpenRouter {
+                client: Client::new(),
+                api_key: api_key(config)?,
+            },
+        };
 
-        Ok(Self {
-            client: Client::new(),
-            api_key,
-            model,
-        })
+        Ok(Self { backend })
+    }
+
+    /// Creates a client answering with the mock fixtures, preferring the
+    /// overrides in `fixtures_dir`, whatever provider is selected.
+    pub fn mock(fixtures_dir: PathBuf) -> Self {
+        Self::from_mock(MockLlmProvider::with_fixtures(fixtures_dir))
+    }
+
+    /// Creates a client answering with `mock`, whatever provider is selected.
+    pub fn from_mock(mock: MockLlmProvider) -> Self {
+        Self {
+            backend: Backend::Mock(mock),
+        }
+    }
+
+    /// Name of the provider the client calls.
+    pub fn provider_name(&self) -> &'static str {
+        self.backend.name()
+    }
+
+    /// Whether both clients call the same provider with the same
+    /// credentials.
+    pub fn same_credentials(&self, other: &Self) -> bool {
+        match (&self.backend, &other.backend) {
+            (
+                Backend::OpenRouter { api_key, .. },
+                Backend::OpenRouter {
+                    api_key: other_key, ..
+                },
+            ) => api_key == other_key,
+            (Backend::Mock(_), Backend::Mock(_)) | (Backend::Replay(_), Backend::Replay(_)) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns the model to call: `--model`, then `GAMEDESIGNER_MODEL`, then
+    /// the configured model, then the default. Resolved per call so
+    /// configuration reloads take effect immediately.
+    pub fn model() -> String {
+        MODEL
+            .get()
+            .cloned()
+            .or_else(|| env::var("GAMEDESIGNER_MODEL").ok())
+            .or_else(|| config::current().llm.model.clone())
+            .unwrap_or_else(|| CHIMERA_MODEL.to_owned())
     }
 
     /// Calls the LLM with a series of messages and returns the response.
     pub async fn call_llm(&self, messages: Vec<ChatMessage>) -> Result<String> {
+        self.call_llm_with_prefix(messages, "").await
+    }
+
+    /// Calls the LLM with a series of messages whose first user message
+    /// starts with `prefix`, a part of the prompt that repeats across the
+    /// calls of a session, and returns the response. Providers that need
+    /// explicit prompt caching breakpoints are asked to cache the prompt up
+    /// to the end of `prefix`.
+    ///
+    /// Runs in an `llm_call` span carrying the provider, the model and the
+    /// token counts; the counts are estimated unless the provider reports them.
+    #[tracing::instrument(
+        name = "llm_call",
+        skip_all,
+        fields(
+            provider = self.backend.name(),
+            model = Empty,
+            prompt_tokens = Empty,
+            completion_tokens = Empty,
+            cached_prompt_tokens = Empty,
+            cached = false,
+        )
+    )]
+    pub async fn call_llm_with_prefix(
+        &self,
+        messages: Vec<ChatMessage>,
+        prefix: &str,
+    ) -> Result<String> {
+        let started_at = Instant::now();
+
+        // Nothing pasted by accident leaves the process, including diffs
+        // and earlier transcripts in the design context
+        let mut findings = Vec::new();
+        let messages: Vec<ChatMessage> = messages
+            .into_iter()
+            .map(|mut message| {
+                let redacted = redact::redact(&message.content);
+                findings.extend(redacted.findings);
+                message.content = redacted.text;
+                message
+            })
+            .collect();
+        if !findings.is_empty() {
+            tracing::warn!(
+                "Redacted {} from the LLM request",
+                redact::summary(&findings)
+            );
+        }
+
+        let messages = context::fit_to_budget(messages, context::prompt_budget());
+        let prefix = redact::redact(prefix).text;
+        let model = match IMAGES.try_with(|images| !images.is_empty()) {
+            Ok(true) => vision_model().unwrap_or_else(Self::model),
+            _ => Self::model(),
+        };
+
+        let provider = self.backend.name();
+
+        let span = tracing::Span::current();
+        span.record("model", model.as_str());
+
+        let response = match &self.backend {
+            Backend::Mock(mock) => {
+                tokio::time::sleep(mock.latency()).await;
+                Ok((mock.respond(&messages), None))
+            }
+            Backend::Replay(replay) => replay.respond(&messages).map(|response| (response, None)),
+            Backend::OpenRouter { client, api_key } => {
+                self.call_openrouter(client, api_key, &model, &messages, &prefix)
+                    .await
+            }
+        };
+
+        let elapsed = started_at.elapsed();
+        let duration_ms = elapsed.as_millis() as u64;
+        metrics::histogram!("gamedesignerd_llm_request_duration_seconds", "provider" => provider)
+            .record(elapsed.as_secs_f64());
+
+        let (response, usage) = match response {
+            Ok(response) => response,
+            Err(e) => {
+                metrics::counter!("gamedesignerd_llm_errors_total", "provider" => provider)
+                    .increment(1);
+                tracing::warn!(duration_ms, error = %e, "LLM call failed");
+                return Err(e);
+            }
+        };
+
+        let usage = usage.unwrap_or_else(|| LlmUsage {
+            prompt_tokens: messages
+                .iter()
+                .map(|m| context::estimate_tokens(&m.content) as u64)
+                .sum(),
+            completion_tokens: context::estimate_tokens(&response) as u64,
+            prompt_tokens_details: None,
+        });
+        span.record("prompt_tokens", usage.prompt_tokens);
+        span.record("completion_tokens", usage.completion_tokens);
+        if let Some(details) = &usage.prompt_tokens_details {
+            span.record("cached_prompt_tokens", details.cached_tokens);
+            metrics::counter!("gamedesignerd_llm_cached_prompt_tokens_total", "provider" => provider)
+                .increment(details.cached_tokens);
+        }
+        metrics::counter!("gamedesignerd_llm_tokens_total", "provider" => provider, "kind" => "prompt")
+            .increment(usage.prompt_tokens);
+        metrics::counter!("gamedesignerd_llm_tokens_total", "provider" => provider, "kind" => "completion")
+            .increment(usage.completion_tokens);
+        let _ = METER.try_with(|meter| {
+            meter.fetch_add(
+                usage.prompt_tokens + usage.completion_tokens,
+                Ordering::Relaxed,
+            )
+        });
+
+        tracing::info!(duration_ms, "LLM call finished");
+
+        if !matches!(self.backend, Backend::Replay(_)) {
+            recording::record(&model, &messages, &response);
+        }
+        Ok(response)
+    }
+
+    /// Checks that the provider is reachable and accepts the API key, without
+    /// spending tokens.
+    pub async fn ping(&self) -> Result<()> {
+        let Backend::OpenRouter { client, api_key } = &self.backend else {
+            return Ok(());
+        };
+
+        let response = client
+            .get("https://openrouter.ai/api/v1/auth/key")
+            .header("Authorization", format!("Bearer {}", api_key))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "LLM provider answered with status {}",
+                response.status()
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Sends the conversation to OpenRouter, or answers it from the cache.
+    /// Returns the token counts if the provider reported them; a cached
+    /// response consumes no tokens.
+    async fn call_openrouter(
+        &self,
+        client: &Client,
+        api_key: &str,
+        model: &str,
+        messages: &[ChatMessage],
+        prefix: &str,
+    ) -> Result<(String, Option<LlmUsage>)> {
+        let sampling = sampling();
+        let breakpoints = prompt_caching() && takes_cache_breakpoints(model);
+        let images = IMAGES.try_with(Arc::clone).unwrap_or_default();
         let request = LlmRequest {
-            model: self.model.clone(),
-            messages,
-            temperature: 0.7, // TODO: Make configurable
-            max_tokens: 4000, // TODO: Make configurable
+            model: model.to_string(),
+            messages: wire_messages(messages, prefix, breakpoints, &images),
+            temperature: sampling.temperature.unwrap_or(DEFAULT_TEMPERATURE),
+            top_p: sampling.top_p,
+            max_tokens: sampling.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            seed: seed(),
         };
 
-        let response = self
-            .client
+        if let Some(response) = cache::lookup(&request) {
+            tracing::Span::current().record("cached", true);
+            let usage = LlmUsage {
+                prompt_tokens: 0,
+                completion_tokens: 0,
+                prompt_tokens_details: None,
+            };
+            return Ok((response, Some(usage)));
+        }
+
+        let response = client
             .post("https://openrouter.ai/api/v1/chat/completions")
-            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
             // OpenRouter specific headers
             .header("HTTP-Referer", "game_designer_mcp")
             .header("X-Title", "Game Designer MCP")
             .json(&request)
             .send()
-            .await?;
+            .await
+            .map_err(|e| GameDesignerError::LlmFailed {
+                status: None,
+                message: e.to_string(),
+            })?;
 
         if !response.status().is_success() {
             let status = response.status();
-            let error_text = response.text().await?;
+            let error_text = response.text().await.unwrap_or_default();
 
-            return Err(anyhow::anyhow!(
-                "LLM API request failed with status {}: {}",
-                status,
-                error_text
-            ));
+            return Err(GameDesignerError::LlmFailed {
+                status: Some(status.as_u16()),
+                message: format!("status {}: {}", status, error_text),
+            }
+            .into());
         }
 
-        let api_response: LlmResponse = response.json().await?;
+        let api_response: LlmResponse =
+            response
+                .json()
+                .await
+                .map_err(|e| GameDesignerError::LlmFailed {
+                    status: None,
+                    message: format!("unreadable response: {}", e),
+                })?;
+
+        tracing::debug!(
+            id = ?api_response.id,
+            model = ?api_response.model,
+            system_fingerprint = ?api_response.system_fingerprint,
+            "LLM response received"
+        );
+
+        if let Some(seed) = seed()
+            && let Err(e) = self.record_fingerprint(seed, model, &api_response)
+        {
+            tracing::warn!("Failed to record provider fingerprint: {}", e);
+        }
 
         if let Some(choice) = api_response.choices.first() {
-            Ok(choice.message.content.clone())
+            cache::store(&request, &choice.message.content);
+            Ok((choice.message.content.clone(), api_response.usage))
         } else {
             Err(anyhow::anyhow!("LLM API returned no choices"))
         }
     }
 
+    /// Appends the provider fingerprint of a seeded call to
+    /// `llm-fingerprints.jsonl`, so that reproducibility of a run can be checked.
+    fn record_fingerprint(&self, seed: u64, model: &str, response: &LlmResponse) -> Result<()> {
+        let line = serde_json::json!({
+            "timestamp": chrono::Utc::now(),
+            "seed": seed,
+            "requested_model": model,
+            "model": response.model,
+            "response_id": response.id,
+            "system_fingerprint": response.system_fingerprint,
+        });
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(persistence_dir().join("llm-fingerprints.jsonl"))?;
+        writeln!(file, "{}", line)?;
+
+        Ok(())
+    }
+
     // TODO: Add specific methods for different tasks like `get_next_feature_prompt`, `review_implementation_prompt`, etc.
     // These would construct the appropriate `Vec<ChatMessage>` for the `call_llm` function.
 }
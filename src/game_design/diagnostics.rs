@@ -0,0 +1,265 @@
+//! Environment checks of `gamedesignerd doctor`: the data directory, the
+//! configuration files, the LLM provider and files left behind by deleted
+//! sessions. Every check that does not pass says how to fix it.
+
+use crate::game_design::{
+    DesignerLlmClient,
+    config::{self, Config},
+    designer_llm::{self, LlmProvider},
+    integrity, project,
+    session::persistence_dir,
+};
+use std::{fmt, fs, time::Duration};
+
+/// Time the LLM provider has to answer the ping.
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Outcome of a check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    /// Works, but needs attention.
+    Warning,
+    Failed,
+    Skipped,
+}
+
+impl Status {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Warning => "warn",
+            Self::Failed => "FAIL",
+            Self::Skipped => "skip",
+        }
+    }
+}
+
+/// Result of one environment check.
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+    /// What to do about a warning or a failure.
+    pub fix: Option<String>,
+}
+
+impl Check {
+    pub fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Ok,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    pub fn skipped(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Skipped,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    pub fn warning(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Warning,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    pub fn failed(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Failed,
+            detail: detail.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{:>4}] {}: {}",
+            self.status.label(),
+            self.name,
+            self.detail
+        )?;
+        if let Some(fix) = &self.fix {
+            write!(f, "\n       fix: {}", fix)?;
+        }
+        Ok(())
+    }
+}
+
+/// Verifies that the persistence directory is writable by creating and
+/// removing a probe file.
+pub fn persistence_writable() -> Result<(), String> {
+    let dir = persistence_dir();
+    let probe = dir.join(format!(".probe-{:016x}", rand::random::<u64>()));
+
+    fs::create_dir_all(dir)
+        .and_then(|()| fs::write(&probe, b"ok"))
+        .and_then(|()| fs::remove_file(&probe))
+        .map_err(|e| format!("{} is not writable: {}", dir.display(), e))
+}
+
+pub fn check_data_dir() -> Check {
+    match persistence_writable() {
+        Ok(()) => Check::ok(
+            "Data directory",
+            format!("{} is writable", persistence_dir().display()),
+        ),
+        Err(e) => Check::failed(
+            "Data directory",
+            e,
+            "Fix the permissions of the directory, or choose a writable one with --data-dir, \
+             GAMEDESIGNERD_DATA_DIR or [storage] path",
+        ),
+    }
+}
+
+/// Parses every configuration file on its own, so that a broken file is
+/// named, and then the merged configuration.
+pub fn check_config() -> Check {
+    let sources = config::sources();
+
+    for path in &sources {
+        let parsed = fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| toml::from_str::<Config>(&text).map_err(|e| e.to_string()));
+
+        if let Err(e) = parsed {
+            return Check::failed(
+                "Configuration",
+                format!("{}: {}", path.display(), e),
+                format!(
+                    "Correct or remove {}; `gamedesignerd config show` prints the settings \
+                     in effect",
+                    path.display()
+                ),
+            );
+        }
+    }
+
+    if let Err(e) = Config::load() {
+        return Check::failed(
+            "Configuration",
+            e.to_string(),
+            "The files are valid on their own but not merged; check the settings they share",
+        );
+    }
+
+    if sources.is_empty() {
+        return Check::ok("Configuration", "no configuration files, using defaults");
+    }
+
+    let paths = sources
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>();
+    Check::ok("Configuration", format!("valid: {}", paths.join(", ")))
+}
+
+pub fn check_api_key() -> Check {
+    let provider = match designer_llm::provider() {
+        LlmProvider::Openrouter => None,
+        LlmProvider::Mock => Some("mock"),
+        LlmProvider::Replay => Some("replay"),
+    };
+    if let Some(provider) = provider {
+        return Check::skipped(
+            "API key",
+            format!("not needed by the {} provider", provider),
+        );
+    }
+
+    match designer_llm::api_key(&config::current()) {
+        Ok(_) => Check::ok("API key", "OpenRouter API key found"),
+        Err(e) => Check::failed(
+            "API key",
+            e.to_string(),
+            "Export OPENROUTER_API_KEY, point OPENROUTER_API_KEY_FILE or [llm] api_key_file \
+             at a file holding the key, or run with --llm-provider mock to work offline",
+        ),
+    }
+}
+
+/// Pings the LLM provider, which costs no tokens.
+pub async fn check_provider() -> Check {
+    let client = match DesignerLlmClient::new() {
+        Ok(client) => client,
+        Err(e) => {
+            return Check::skipped("LLM provider", format!("no client to ping: {}", e));
+        }
+    };
+
+    match tokio::time::timeout(PING_TIMEOUT, client.ping()).await {
+        Ok(Ok(())) => Check::ok(
+            "LLM provider",
+            format!(
+                "{} answered, model '{}'",
+                client.provider_name(),
+                DesignerLlmClient::model()
+            ),
+        ),
+        Ok(Err(e)) => Check::failed(
+            "LLM provider",
+            format!("{}: {}", client.provider_name(), e),
+            "Check the API key and that it has credits left; a status of 401 means the key \
+             was rejected",
+        ),
+        Err(_) => Check::failed(
+            "LLM provider",
+            format!(
+                "{} did not answer within {} seconds",
+                client.provider_name(),
+                PING_TIMEOUT.as_secs()
+            ),
+            "Check the network connection and proxy settings (HTTPS_PROXY)",
+        ),
+    }
+}
+
+/// Looks for event logs and snapshots of sessions that no longer exist.
+pub fn check_orphans() -> Check {
+    let orphans = project::all_dirs().and_then(|dirs| {
+        dirs.iter()
+            .map(|dir| integrity::orphans(dir))
+            .collect::<anyhow::Result<Vec<_>>>()
+    });
+
+    match orphans.map(|orphans| orphans.concat()) {
+        Ok(orphans) if orphans.is_empty() => Check::ok(
+            "Orphaned files",
+            "every event log and snapshot has a session",
+        ),
+        Ok(orphans) => Check::warning(
+            "Orphaned files",
+            format!(
+                "{} event log(s) or snapshot director(ies) without a session",
+                orphans.len()
+            ),
+            format!(
+                "Delete them if their sessions are gone for good: {}",
+                orphans
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        ),
+        Err(e) => Check::failed(
+            "Orphaned files",
+            format!("failed to list the data directory: {}", e),
+            "Check the permissions of the data directory",
+        ),
+    }
+}
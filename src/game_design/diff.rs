@@ -0,0 +1,265 @@
+//! Structured comparison of two design sessions: pillars and decisions,
+//! features added, removed and changed, and features moved between
+//! milestones.
+//!
+//! Features are matched by name, ignoring case and surrounding whitespace.
+
+use crate::game_design::state::{Feature, SessionState};
+use serde::Serialize;
+
+/// Entries present in only one of the compared lists.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ListDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl ListDiff {
+    fn new(base: &[&str], other: &[&str]) -> Self {
+        let missing = |from: &[&str], list: &[&str]| -> Vec<String> {
+            from.iter()
+                .filter(|entry| !list.iter().any(|e| same(e, entry)))
+                .map(|entry| entry.to_string())
+                .collect()
+        };
+
+        Self {
+            added: missing(other, base),
+            removed: missing(base, other),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// A feature field whose value differs between the sessions.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub base: String,
+    pub other: String,
+}
+
+/// A feature present in both sessions with different contents.
+#[derive(Debug, Clone, Serialize)]
+pub struct FeatureChange {
+    pub name: String,
+    pub changes: Vec<FieldChange>,
+}
+
+/// A feature assigned to different milestones in the two sessions.
+#[derive(Debug, Clone, Serialize)]
+pub struct MilestoneShift {
+    pub feature: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// Differences from a base session to another session.
+#[derive(Debug, Clone, Serialize)]
+pub struct DesignDiff {
+    pub base: String,
+    pub other: String,
+    /// Whether the design documents differ.
+    pub document_changed: bool,
+    /// Pillars, i.e. decisions that don't stem from a feature review.
+    pub pillars: ListDiff,
+    /// Decisions established in feature reviews.
+    pub decisions: ListDiff,
+    pub features_added: Vec<String>,
+    pub features_removed: Vec<String>,
+    pub features_changed: Vec<FeatureChange>,
+    pub milestone_shifts: Vec<MilestoneShift>,
+}
+
+fn same(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+/// Returns the session's pillars, or its decisions from feature reviews.
+fn decisions(session: &SessionState, pillars: bool) -> Vec<&str> {
+    session
+        .design_memory
+        .iter()
+        .filter(|decision| decision.source_feature.is_none() == pillars)
+        .map(|decision| decision.decision.as_str())
+        .collect()
+}
+
+fn names(session: &SessionState) -> Vec<&str> {
+    session
+        .planned_features
+        .iter()
+        .map(|feature| feature.name.as_str())
+        .collect()
+}
+
+/// Compares `other` against `base`.
+pub fn diff(base: &SessionState, other: &SessionState) -> DesignDiff {
+    let features = ListDiff::new(&names(base), &names(other));
+
+    let mut features_changed = Vec::new();
+    let mut milestone_shifts = Vec::new();
+    for base_feature in &base.planned_features {
+        let Some(other_feature) = other
+            .planned_features
+            .iter()
+            .find(|feature| same(&feature.name, &base_feature.name))
+        else {
+            continue;
+        };
+
+        let changes = feature_changes(base_feature, other_feature);
+        if !changes.is_empty() {
+            features_changed.push(FeatureChange {
+                name: base_feature.name.clone(),
+                changes,
+            });
+        }
+
+        if base_feature.milestone != other_feature.milestone {
+            milestone_shifts.push(MilestoneShift {
+                feature: base_feature.name.clone(),
+                from: base_feature.milestone.clone(),
+                to: other_feature.milestone.clone(),
+            });
+        }
+    }
+
+    DesignDiff {
+        base: base.id.clone(),
+        other: other.id.clone(),
+        document_changed: base.initial_description.trim() != other.initial_description.trim(),
+        pillars: ListDiff::new(&decisions(base, true), &decisions(other, true)),
+        decisions: ListDiff::new(&decisions(base, false), &decisions(other, false)),
+        features_added: features.added,
+        features_removed: features.removed,
+        features_changed,
+        milestone_shifts,
+    }
+}
+
+/// Lists the compared fields of two versions of a feature that differ.
+fn feature_changes(base: &Feature, other: &Feature) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    let mut compare = |field, base: String, other: String| {
+        if base.trim() != other.trim() {
+            changes.push(FieldChange { field, base, other });
+        }
+    };
+
+    compare(
+        "description",
+        base.description.clone(),
+        other.description.clone(),
+    );
+    compare(
+        "status",
+        format!("{:?}", base.status),
+        format!("{:?}", other.status),
+    );
+    compare(
+        "estimated_effort",
+        optional(base.estimated_effort),
+        optional(other.estimated_effort),
+    );
+    compare("risk", optional(base.risk), optional(other.risk));
+
+    changes
+}
+
+fn optional(value: Option<impl std::fmt::Debug>) -> String {
+    value.map_or_else(|| "none".to_string(), |value| format!("{:?}", value))
+}
+
+impl DesignDiff {
+    /// Whether the sessions have the same design.
+    pub fn is_empty(&self) -> bool {
+        !self.document_changed
+            && self.pillars.is_empty()
+            && self.decisions.is_empty()
+            && self.features_added.is_empty()
+            && self.features_removed.is_empty()
+            && self.features_changed.is_empty()
+            && self.milestone_shifts.is_empty()
+    }
+
+    /// Renders the diff as a Markdown report.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("# Design diff: {} → {}\n\n", self.base, self.other);
+
+        if self.is_empty() {
+            markdown.push_str("The designs are identical.\n");
+            return markdown;
+        }
+
+        if self.document_changed {
+            markdown.push_str("The design documents differ.\n\n");
+        }
+
+        for (title, list) in [("Pillars", &self.pillars), ("Decisions", &self.decisions)] {
+            if list.is_empty() {
+                continue;
+            }
+
+            markdown.push_str(&format!("## {}\n\n", title));
+            for entry in &list.added {
+                markdown.push_str(&format!("- ➕ {}\n", entry));
+            }
+            for entry in &list.removed {
+                markdown.push_str(&format!("- ➖ {}\n", entry));
+            }
+            markdown.push('\n');
+        }
+
+        if !self.features_added.is_empty() || !self.features_removed.is_empty() {
+            markdown.push_str("## Features\n\n");
+            for name in &self.features_added {
+                markdown.push_str(&format!("- ➕ {}\n", name));
+            }
+            for name in &self.features_removed {
+                markdown.push_str(&format!("- ➖ {}\n", name));
+            }
+            markdown.push('\n');
+        }
+
+        if !self.features_changed.is_empty() {
+            markdown.push_str("## Changed Features\n\n");
+            for feature in &self.features_changed {
+                markdown.push_str(&format!("### {}\n\n", feature.name));
+                for change in &feature.changes {
+                    if change.field == "description" {
+                        markdown.push_str(&format!(
+                            "- description:\n  - before: {}\n  - after: {}\n",
+                            change.base.trim().replace('\n', " "),
+                            change.other.trim().replace('\n', " ")
+                        ));
+                    } else {
+                        markdown.push_str(&format!(
+                            "- {}: {} → {}\n",
+                            change.field, change.base, change.other
+                        ));
+                    }
+                }
+                markdown.push('\n');
+            }
+        }
+
+        if !self.milestone_shifts.is_empty() {
+            markdown.push_str("## Milestone Shifts\n\n");
+            for shift in &self.milestone_shifts {
+                markdown.push_str(&format!(
+                    "- {}: {} → {}\n",
+                    shift.feature,
+                    shift.from.as_deref().unwrap_or("unassigned"),
+                    shift.to.as_deref().unwrap_or("unassigned")
+                ));
+            }
+            markdown.push('\n');
+        }
+
+        markdown
+    }
+}
@@ -0,0 +1,167 @@
+//! Existing design documents imported into new sessions.
+//!
+//! A team's own Markdown design document is split into its sections by
+//! heading, and the bullets of sections about design pillars become the
+//! session's pillars. The original is kept next to the session as
+//! `documents/<session>.md` and served as an MCP resource.
+
+use crate::game_design::storage::Storage;
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+/// Directory below a session directory holding the original documents.
+pub const DOCUMENTS_DIR: &str = "documents";
+
+/// A section of a Markdown document.
+#[derive(Debug, Clone)]
+pub struct Section {
+    /// Heading level, `1` for `#`.
+    pub level: usize,
+    pub heading: String,
+    /// Text between the heading and the next one.
+    pub body: String,
+}
+
+/// A Markdown design document split into sections.
+#[derive(Debug, Clone, Default)]
+pub struct Document {
+    /// Text before the first heading.
+    pub preamble: String,
+    pub sections: Vec<Section>,
+}
+
+impl Document {
+    /// Splits `markdown` at its ATX headings (`#` to `######`), ignoring
+    /// lines inside code blocks.
+    pub fn parse(markdown: &str) -> Self {
+        let mut document = Self::default();
+        let mut in_code = false;
+
+        for line in markdown.lines() {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_code = !in_code;
+            }
+
+            let heading = (!in_code).then(|| heading(line)).flatten();
+
+            match (heading, document.sections.last_mut()) {
+                (Some((level, heading)), _) => document.sections.push(Section {
+                    level,
+                    heading,
+                    body: String::new(),
+                }),
+                (None, Some(section)) => {
+                    section.body.push_str(line);
+                    section.body.push('\n');
+                }
+                (None, None) => {
+                    document.preamble.push_str(line);
+                    document.preamble.push('\n');
+                }
+            }
+        }
+
+        document
+    }
+
+    /// The first top-level heading, usually the game's name.
+    pub fn title(&self) -> Option<&str> {
+        self.sections
+            .iter()
+            .find(|section| section.level == 1)
+            .map(|section| section.heading.as_str())
+    }
+
+    /// The list items of sections whose heading mentions pillars, such as
+    /// `Design Pillars` or `Core pillars`.
+    pub fn pillars(&self) -> Vec<String> {
+        self.sections
+            .iter()
+            .filter(|section| section.heading.to_lowercase().contains("pillar"))
+            .flat_map(|section| section.body.lines())
+            .filter_map(|line| {
+                let line = line.trim_start();
+                line.strip_prefix("- ")
+                    .or_else(|| line.strip_prefix("* "))
+                    .or_else(|| line.strip_prefix("+ "))
+                    .or_else(|| {
+                        let (number, rest) = line.split_once(". ")?;
+                        number.chars().all(|c| c.is_ascii_digit()).then_some(rest)
+                    })
+            })
+            .map(|pillar| pillar.trim().to_string())
+            .filter(|pillar| !pillar.is_empty())
+            .collect()
+    }
+
+    /// The headings as an indented outline.
+    pub fn outline(&self) -> String {
+        self.sections
+            .iter()
+            .map(|section| {
+                format!(
+                    "{}- {}\n",
+                    "  ".repeat(section.level.saturating_sub(1)),
+                    section.heading
+                )
+            })
+            .collect()
+    }
+}
+
+/// Parses an ATX heading into its level and text.
+fn heading(line: &str) -> Option<(usize, String)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let rest = &line[level..];
+
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+
+    // Closing hashes are not part of the heading
+    let text = rest.trim().trim_end_matches('#').trim_end();
+    Some((level, text.to_string()))
+}
+
+/// Path of the original document of `session_id` in the session directory
+/// `dir`.
+pub fn path(dir: &Path, session_id: &str) -> PathBuf {
+    dir.join(DOCUMENTS_DIR).join(format!("{}.md", session_id))
+}
+
+/// Stores the original document of `session_id` in `storage`.
+pub fn save(storage: &dyn Storage, dir: &Path, session_id: &str, markdown: &str) -> Result<()> {
+    storage.create_dir_all(&dir.join(DOCUMENTS_DIR))?;
+    storage.write(&path(dir, session_id), markdown)?;
+    Ok(())
+}
+
+/// Reads the original document of `session_id` from `storage`, if it has
+/// one.
+pub fn read(storage: &dyn Storage, dir: &Path, session_id: &str) -> Result<Option<String>> {
+    let path = path(dir, session_id);
+
+    if !storage.exists(&path) {
+        return Ok(None);
+    }
+
+    Ok(Some(storage.read_to_string(&path)?))
+}
+
+/// Returns the sessions in `dir` of `storage` with an original document,
+/// sorted.
+pub fn sessions(storage: &dyn Storage, dir: &Path) -> Vec<String> {
+    let Ok(paths) = storage.list(&dir.join(DOCUMENTS_DIR)) else {
+        return Vec::new();
+    };
+
+    let mut sessions = paths
+        .into_iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+        .collect::<Vec<_>>();
+    sessions.sort();
+
+    sessions
+}
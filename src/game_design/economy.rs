@@ -0,0 +1,294 @@
+//! The game's economy: its resources, where they come from and where they
+//! go, and the progressions they pay for.
+//!
+//! The economy is declared as structured data. The LLM simulates it roughly,
+//! estimating how long each step of a progression takes to unlock, and flags
+//! the planned features whose rewards break it.
+
+use crate::game_design::{
+    DesignerLlmClient,
+    designer_llm::{ChatMessage, extract_json},
+    memory, prompts,
+    state::SessionState,
+};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A currency, material or other quantity the player earns and spends.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Resource {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Most the player can hold at once.
+    #[serde(default)]
+    pub cap: Option<u64>,
+}
+
+/// Where a resource enters the economy (a source) or leaves it (a sink).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Flow {
+    pub name: String,
+    pub resource: String,
+    /// Amount per hour of typical play.
+    pub rate_per_hour: f64,
+    /// Planned feature the flow belongs to.
+    #[serde(default)]
+    pub feature: Option<String>,
+}
+
+/// Costs of the successive steps of a progression, e.g. weapon upgrades.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgressionCurve {
+    pub name: String,
+    pub resource: String,
+    pub costs: Vec<u64>,
+}
+
+/// Estimated play time until a step of a progression is unlocked.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnlockTime {
+    pub curve: String,
+    /// 1-based step of the curve.
+    pub step: u32,
+    pub cost: u64,
+    /// Hours of play from the start of the game.
+    pub hours: f64,
+}
+
+/// A planned feature whose rewards break the declared economy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EconomyFlag {
+    pub feature: String,
+    #[serde(default)]
+    pub resource: String,
+    pub issue: String,
+}
+
+/// Outcome of the last balance simulation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Simulation {
+    pub time_to_unlock: Vec<UnlockTime>,
+    pub flags: Vec<EconomyFlag>,
+    /// The designer's overall verdict on the balance.
+    pub summary: String,
+    pub simulated_at: DateTime<Utc>,
+}
+
+/// The declared economy of the game and its last simulation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Economy {
+    #[serde(default)]
+    pub resources: Vec<Resource>,
+    #[serde(default)]
+    pub sources: Vec<Flow>,
+    #[serde(default)]
+    pub sinks: Vec<Flow>,
+    #[serde(default)]
+    pub curves: Vec<ProgressionCurve>,
+    /// Cleared whenever the economy changes, as it no longer applies.
+    #[serde(default)]
+    pub simulation: Option<Simulation>,
+}
+
+impl Economy {
+    /// Whether nothing has been declared yet.
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty()
+            && self.sources.is_empty()
+            && self.sinks.is_empty()
+            && self.curves.is_empty()
+    }
+
+    /// Amount of `resource` the player gains per hour, once sinks are paid.
+    pub fn net_rate(&self, resource: &str) -> f64 {
+        let total = |flows: &[Flow]| {
+            flows
+                .iter()
+                .filter(|flow| flow.resource.eq_ignore_ascii_case(resource))
+                .map(|flow| flow.rate_per_hour)
+                .sum::<f64>()
+        };
+
+        total(&self.sources) - total(&self.sinks)
+    }
+
+    /// Renders the economy and its last simulation as text.
+    pub fn render(&self) -> String {
+        let mut text = String::new();
+
+        if !self.resources.is_empty() {
+            text.push_str("Resources:\n");
+            for resource in &self.resources {
+                text.push_str(&format!(
+                    "- {} ({:+}/h net)",
+                    resource.name,
+                    self.net_rate(&resource.name)
+                ));
+                if let Some(cap) = resource.cap {
+                    text.push_str(&format!(", capped at {}", cap));
+                }
+                if !resource.description.is_empty() {
+                    text.push_str(&format!(": {}", resource.description));
+                }
+                text.push('\n');
+            }
+            text.push('\n');
+        }
+
+        for (label, flows) in [("Sources", &self.sources), ("Sinks", &self.sinks)] {
+            if flows.is_empty() {
+                continue;
+            }
+
+            text.push_str(&format!("{}:\n", label));
+            for flow in flows {
+                text.push_str(&format!(
+                    "- {}: {}/h {}",
+                    flow.name, flow.rate_per_hour, flow.resource
+                ));
+                if let Some(feature) = &flow.feature {
+                    text.push_str(&format!(" (feature: {})", feature));
+                }
+                text.push('\n');
+            }
+            text.push('\n');
+        }
+
+        if !self.curves.is_empty() {
+            text.push_str("Progression curves:\n");
+            for curve in &self.curves {
+                let costs = curve
+                    .costs
+                    .iter()
+                    .map(|cost| cost.to_string())
+                    .collect::<Vec<_>>();
+                text.push_str(&format!(
+                    "- {} ({}): {}\n",
+                    curve.name,
+                    curve.resource,
+                    costs.join(", ")
+                ));
+            }
+            text.push('\n');
+        }
+
+        if let Some(simulation) = &self.simulation {
+            text.push_str(&format!(
+                "Simulation ({}):\n{}\n\n",
+                simulation.simulated_at.format("%Y-%m-%d %H:%M"),
+                simulation.summary.trim()
+            ));
+
+            if !simulation.time_to_unlock.is_empty() {
+                text.push_str("| Curve | Step | Cost | Hours |\n");
+                text.push_str("| --- | --- | --- | --- |\n");
+                for unlock in &simulation.time_to_unlock {
+                    text.push_str(&format!(
+                        "| {} | {} | {} | {:.1} |\n",
+                        unlock.curve, unlock.step, unlock.cost, unlock.hours
+                    ));
+                }
+                text.push('\n');
+            }
+
+            if !simulation.flags.is_empty() {
+                text.push_str("Flagged features:\n");
+                for flag in &simulation.flags {
+                    text.push_str(&format!("- {}", flag.feature));
+                    if !flag.resource.is_empty() {
+                        text.push_str(&format!(" [{}]", flag.resource));
+                    }
+                    text.push_str(&format!(": {}\n", flag.issue));
+                }
+            }
+        }
+
+        text.trim_end().to_string()
+    }
+}
+
+/// Asks the LLM to simulate the session's economy: how long each step of
+/// every progression takes to unlock, and which planned features reward
+/// more or less than the economy can bear. Flags on features that are not
+/// planned are dropped. The simulation is dated `now`.
+pub async fn simulate(
+    llm_client: &DesignerLlmClient,
+    session: &SessionState,
+    now: DateTime<Utc>,
+) -> Result<Simulation> {
+    let economy = &session.economy;
+    let mut prompt = memory::design_context(session);
+
+    if !session.planned_features.is_empty() {
+        prompt.push_str("Planned features:\n");
+        for feature in &session.planned_features {
+            prompt.push_str(&format!("- {}: {}\n", feature.name, feature.description));
+        }
+        prompt.push('\n');
+    }
+
+    prompt.push_str(&format!("Declared economy:\n{}\n\n", economy.render()));
+
+    prompt.push_str(
+        "Simulate this economy roughly for a typical player. For every step of every \
+         progression curve, estimate the hours of play from the start of the game until the \
+         player can afford it, taking the sources, sinks and caps into account. Then check the \
+         planned features: flag each one whose rewards or costs break the declared economy, \
+         e.g. by handing out a resource faster than its sinks can absorb or by trivializing a \
+         progression. Format your response as a JSON object with a 'summary' string, a \
+         'time_to_unlock' array of objects with 'curve', 'step', 'cost' and 'hours' fields, \
+         and a 'flags' array of objects with 'feature', 'resource' and 'issue' fields. Only \
+         return the JSON, nothing else.",
+    );
+
+    let messages = vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: session.system_prompt(prompts::ECONOMY_SIMULATE),
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content: prompt,
+        },
+    ];
+
+    let response = llm_client.call_llm(messages).await?;
+
+    #[derive(Deserialize)]
+    struct Response {
+        #[serde(default)]
+        summary: String,
+        #[serde(default)]
+        time_to_unlock: Vec<UnlockTime>,
+        #[serde(default)]
+        flags: Vec<EconomyFlag>,
+    }
+
+    let json = extract_json(&response)
+        .ok_or_else(|| anyhow::anyhow!("LLM response was not valid JSON: {}", response))?;
+    let response: Response = serde_json::from_str(json)?;
+
+    let flags = response
+        .flags
+        .into_iter()
+        .filter_map(|flag| {
+            let feature = session
+                .planned_features
+                .iter()
+                .find(|feature| feature.name.eq_ignore_ascii_case(flag.feature.trim()))?;
+            Some(EconomyFlag {
+                feature: feature.name.clone(),
+                ..flag
+            })
+        })
+        .collect();
+
+    Ok(Simulation {
+        time_to_unlock: response.time_to_unlock,
+        flags,
+        summary: response.summary.trim().to_string(),
+        simulated_at: now,
+    })
+}
@@ -0,0 +1,534 @@
+//! Semantic search over the history of a session.
+//!
+//! Features, implementation reports, reviews, replies and `featureAsk`
+//! answers are taken from the event log, split into snippets and embedded.
+//! The vectors are kept in `<session>/embeddings.json` in the persistence
+//! directory and brought up to date with the log before every search, so
+//! only new events are embedded. A query is embedded the same way and the
+//! snippets are ranked by cosine similarity, each linking back to the event
+//! it came from.
+//!
+//! The embedding provider is pluggable: the default `local` provider hashes
+//! words and their trigrams into a fixed-size vector, runs offline and costs
+//! nothing; `openai` calls an OpenAI-compatible `/embeddings` endpoint. An
+//! index built with another provider or model is rebuilt.
+
+use crate::game_design::{
+    config,
+    events::{EventKind, SessionEvent},
+    redact, search,
+    storage::Storage,
+};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, env, fs, path::Path};
+
+/// Dimensions of the vectors of the local provider.
+const LOCAL_DIMENSIONS: usize = 512;
+
+/// Weight of a word trigram relative to the whole word in local vectors.
+const TRIGRAM_WEIGHT: f32 = 0.3;
+
+/// Snippets are cut from paragraphs up to about this many characters.
+pub const SNIPPET_CHARS: usize = 800;
+
+/// Texts sent to a remote provider per request.
+const REMOTE_BATCH_SIZE: usize = 64;
+
+/// Endpoint of the `openai` provider unless `[embeddings] url` is set.
+pub const DEFAULT_URL: &str = "https://api.openai.com/v1/embeddings";
+
+/// Model of the `openai` provider unless `[embeddings] model` is set.
+pub const DEFAULT_MODEL: &str = "text-embedding-3-small";
+
+/// Backend computing the embeddings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingProvider {
+    /// Hashed words and trigrams, computed in process.
+    #[default]
+    Local,
+    /// An OpenAI-compatible embeddings endpoint.
+    Openai,
+}
+
+/// Returns the provider: `GAMEDESIGNER_EMBEDDINGS_PROVIDER`, then the
+/// configured one, then `local`.
+pub fn provider() -> EmbeddingProvider {
+    env::var("GAMEDESIGNER_EMBEDDINGS_PROVIDER")
+        .ok()
+        .and_then(|name| match name.trim().to_lowercase().as_str() {
+            "local" => Some(EmbeddingProvider::Local),
+            "openai" => Some(EmbeddingProvider::Openai),
+            _ => {
+                tracing::warn!("Unknown embeddings provider '{}'", name);
+                None
+            }
+        })
+        .or(config::current().embeddings.provider)
+        .unwrap_or_default()
+}
+
+/// Returns the API key of the `openai` provider:
+/// `GAMEDESIGNER_EMBEDDINGS_API_KEY`, then the contents of the file named by
+/// `[embeddings] api_key_file`.
+fn api_key() -> Result<String> {
+    if let Ok(api_key) = env::var("GAMEDESIGNER_EMBEDDINGS_API_KEY") {
+        return Ok(api_key);
+    }
+
+    let path = config::current()
+        .embeddings
+        .api_key_file
+        .clone()
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "neither GAMEDESIGNER_EMBEDDINGS_API_KEY nor [embeddings] api_key_file is set"
+            )
+        })?;
+    let api_key = fs::read_to_string(&path)
+        .map_err(|e| anyhow::anyhow!("failed to read API key file {}: {}", path.display(), e))?
+        .trim()
+        .to_string();
+
+    if api_key.is_empty() {
+        anyhow::bail!("API key file {} is empty", path.display());
+    }
+
+    Ok(api_key)
+}
+
+/// Computes embeddings with the selected provider.
+pub enum Embedder {
+    Local,
+    Remote {
+        client: Client,
+        url: String,
+        model: String,
+        api_key: String,
+    },
+}
+
+#[derive(Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl Embedder {
+    /// Creates the embedder of the configured provider.
+    pub fn new() -> Result<Self> {
+        Ok(match provider() {
+            EmbeddingProvider::Local => Self::Local,
+            EmbeddingProvider::Openai => {
+                let embeddings = &config::current().embeddings;
+                Self::Remote {
+                    client: Client::new(),
+                    url: embeddings
+                        .url
+                        .clone()
+                        .unwrap_or_else(|| DEFAULT_URL.to_string()),
+                    model: embeddings
+                        .model
+                        .clone()
+                        .unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+                    api_key: api_key()?,
+                }
+            }
+        })
+    }
+
+    /// Identifies the vector space; vectors of different embedders cannot
+    /// be compared.
+    pub fn id(&self) -> String {
+        match self {
+            Self::Local => format!("local-{}", LOCAL_DIMENSIONS),
+            Self::Remote { url, model, .. } => format!("{}@{}", model, url),
+        }
+    }
+
+    /// Embeds `texts` into unit vectors, in order.
+    pub async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        match self {
+            Self::Local => Ok(texts.iter().map(|text| local_embedding(text)).collect()),
+            Self::Remote {
+                client,
+                url,
+                model,
+                api_key,
+            } => {
+                let mut vectors = Vec::with_capacity(texts.len());
+
+                for batch in texts.chunks(REMOTE_BATCH_SIZE) {
+                    // Nothing pasted by accident leaves the process
+                    let input = batch
+                        .iter()
+                        .map(|text| redact::redact(text).text)
+                        .collect::<Vec<_>>();
+
+                    let response = client
+                        .post(url)
+                        .header("Authorization", format!("Bearer {}", api_key))
+                        .json(&EmbeddingRequest {
+                            model,
+                            input: &input,
+                        })
+                        .send()
+                        .await?;
+                    let status = response.status();
+                    if !status.is_success() {
+                        return Err(anyhow::anyhow!(
+                            "Embeddings provider answered with status {}: {}",
+                            status,
+                            response.text().await.unwrap_or_default()
+                        ));
+                    }
+
+                    let mut data = response.json::<EmbeddingResponse>().await?.data;
+                    if data.len() != batch.len() {
+                        return Err(anyhow::anyhow!(
+                            "Embeddings provider returned {} vectors for {} texts",
+                            data.len(),
+                            batch.len()
+                        ));
+                    }
+                    data.sort_by_key(|data| data.index);
+                    vectors.extend(data.into_iter().map(|data| normalized(data.embedding)));
+                }
+
+                Ok(vectors)
+            }
+        }
+    }
+}
+
+/// FNV-1a, stable across platforms and compiler versions, unlike the
+/// standard library's hasher, so that stored local vectors stay valid.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Embeds `text` by hashing its words and their trigrams, so that related
+/// word forms such as "save" and "saving" share dimensions.
+fn local_embedding(text: &str) -> Vec<f32> {
+    let mut vector = vec![0.0; LOCAL_DIMENSIONS];
+    let mut add = |feature: &str, weight: f32| {
+        let hash = fnv1a(feature.as_bytes());
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[(hash % LOCAL_DIMENSIONS as u64) as usize] += sign * weight;
+    };
+
+    for word in search::terms(text) {
+        add(&word, 1.0);
+
+        let chars = format!("<{}>", word).chars().collect::<Vec<_>>();
+        for trigram in chars.windows(3) {
+            add(&trigram.iter().collect::<String>(), TRIGRAM_WEIGHT);
+        }
+    }
+
+    normalized(vector)
+}
+
+/// Scales `vector` to unit length; the zero vector stays zero.
+fn normalized(mut vector: Vec<f32>) -> Vec<f32> {
+    let length = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if length > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= length);
+    }
+    vector
+}
+
+/// What a snippet was taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SnippetKind {
+    /// The specification of a proposed feature.
+    Feature,
+    /// An implementation report of `featureReview`.
+    Report,
+    /// The designer's review of a report or a human decision.
+    Review,
+    /// A `reviewReply` and the designer's response.
+    Reply,
+    /// A `featureAsk` question and its answer.
+    Question,
+}
+
+impl SnippetKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Feature => "feature",
+            Self::Report => "report",
+            Self::Review => "review",
+            Self::Reply => "reply",
+            Self::Question => "question",
+        }
+    }
+}
+
+/// The event a snippet came from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventLink {
+    pub seq: u64,
+    pub id: String,
+    pub kind: EventKind,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// An embedded snippet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedSnippet {
+    pub kind: SnippetKind,
+    pub feature: Option<String>,
+    pub text: String,
+    pub event: EventLink,
+    pub vector: Vec<f32>,
+}
+
+/// Snippet matching a query.
+#[derive(Debug, Clone, Serialize)]
+pub struct SemanticMatch {
+    pub kind: SnippetKind,
+    pub feature: Option<String>,
+    pub snippet: String,
+    /// Cosine similarity to the query in `-1.0..=1.0`.
+    pub score: f32,
+    pub event: EventLink,
+}
+
+/// The stored embeddings of a session.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EmbeddingIndex {
+    /// [`Embedder::id`] of the vectors.
+    pub embedder: String,
+    pub snippets: Vec<IndexedSnippet>,
+}
+
+impl EmbeddingIndex {
+    /// Reads the index at `path`. A missing or unreadable index is empty,
+    /// since it can always be rebuilt from the event log.
+    pub fn load(storage: &dyn Storage, path: &Path) -> Self {
+        match storage.read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                tracing::warn!("Rebuilding the unreadable index {}: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self, storage: &dyn Storage, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            storage.create_dir_all(parent)?;
+        }
+        storage.write(path, &serde_json::to_string(self)?)?;
+
+        Ok(())
+    }
+
+    /// Brings the index up to date with `events`: snippets of events no
+    /// longer in the log, e.g. after `designUndo`, are dropped and the
+    /// snippets of new events embedded. Returns whether anything changed.
+    pub async fn update(&mut self, embedder: &Embedder, events: &[SessionEvent]) -> Result<bool> {
+        let embedder_id = embedder.id();
+        let mut changed = false;
+        if self.embedder != embedder_id {
+            *self = Self {
+                embedder: embedder_id,
+                snippets: Vec::new(),
+            };
+            changed = true;
+        }
+
+        let ids = events
+            .iter()
+            .map(|event| event.id.as_str())
+            .collect::<HashSet<_>>();
+        let before = self.snippets.len();
+        self.snippets
+            .retain(|snippet| ids.contains(snippet.event.id.as_str()));
+        changed |= self.snippets.len() != before;
+
+        let indexed = self
+            .snippets
+            .iter()
+            .map(|snippet| snippet.event.id.clone())
+            .collect::<HashSet<_>>();
+        let mut pending = Vec::new();
+        for event in events.iter().filter(|event| !indexed.contains(&event.id)) {
+            pending.extend(snippets(event));
+        }
+        if pending.is_empty() {
+            return Ok(changed);
+        }
+
+        // The feature name gives short snippets the context of the feature
+        let texts = pending
+            .iter()
+            .map(|(kind, feature, text, _)| match feature {
+                Some(feature) => format!("{} ({}): {}", feature, kind.name(), text),
+                None => text.clone(),
+            })
+            .collect::<Vec<_>>();
+        let vectors = embedder.embed(&texts).await?;
+
+        for ((kind, feature, text, event), vector) in pending.into_iter().zip(vectors) {
+            self.snippets.push(IndexedSnippet {
+                kind,
+                feature,
+                text,
+                event,
+                vector,
+            });
+        }
+
+        Ok(true)
+    }
+
+    /// Returns up to `limit` snippets most similar to the embedded query,
+    /// best first, skipping those unrelated to it.
+    pub fn search(&self, query: &[f32], limit: usize) -> Vec<SemanticMatch> {
+        let mut matches = self
+            .snippets
+            .iter()
+            .map(|snippet| SemanticMatch {
+                kind: snippet.kind,
+                feature: snippet.feature.clone(),
+                snippet: snippet.text.clone(),
+                score: dot(query, &snippet.vector),
+                event: snippet.event.clone(),
+            })
+            .filter(|candidate| candidate.score > 0.0)
+            .collect::<Vec<_>>();
+
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+        matches.truncate(limit);
+        matches
+    }
+}
+
+/// Cosine similarity of two unit vectors.
+pub fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+/// Snippets of an event: its kind, feature, text and link, one per chunk
+/// of its text.
+fn snippets(event: &SessionEvent) -> Vec<(SnippetKind, Option<String>, String, EventLink)> {
+    let field = |name: &str| {
+        event
+            .data
+            .get(name)
+            .and_then(|v| v.as_str())
+            .filter(|text| !text.trim().is_empty())
+    };
+
+    let (kind, text) = match event.kind {
+        EventKind::FeatureProposed => (
+            SnippetKind::Feature,
+            field("description").map(str::to_string),
+        ),
+        EventKind::ReviewSubmitted => (SnippetKind::Report, field("report").map(str::to_string)),
+        EventKind::FeatureApproved
+        | EventKind::FeatureNeedsRework
+        | EventKind::FeatureAwaitingApproval => (
+            SnippetKind::Review,
+            field("review").or(field("comment")).map(str::to_string),
+        ),
+        EventKind::ReviewReplied => (
+            SnippetKind::Reply,
+            field("reply").map(|reply| match field("response") {
+                Some(response) => format!("{}\n\n{}", reply, response),
+                None => reply.to_string(),
+            }),
+        ),
+        EventKind::QuestionAnswered => (
+            SnippetKind::Question,
+            field("question").map(|question| match field("answer") {
+                Some(answer) => format!("{}\n\n{}", question, answer),
+                None => question.to_string(),
+            }),
+        ),
+        _ => return Vec::new(),
+    };
+    let Some(text) = text else {
+        return Vec::new();
+    };
+
+    let feature = field("feature").map(str::to_string);
+    let link = EventLink {
+        seq: event.seq,
+        id: event.id.clone(),
+        kind: event.kind,
+        timestamp: event.timestamp,
+    };
+
+    chunks(&text)
+        .into_iter()
+        .map(|chunk| (kind, feature.clone(), chunk, link.clone()))
+        .collect()
+}
+
+/// Splits `text` into chunks of whole paragraphs up to [`SNIPPET_CHARS`];
+/// longer paragraphs are split between words.
+fn chunks(text: &str) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    let pieces = text
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|paragraph| !paragraph.is_empty())
+        .flat_map(|paragraph| {
+            if paragraph.chars().count() <= SNIPPET_CHARS {
+                return vec![paragraph.to_string()];
+            }
+
+            let mut pieces = vec![String::new()];
+            for word in paragraph.split_whitespace() {
+                let piece = pieces.last_mut().expect("pieces start with one");
+                if !piece.is_empty()
+                    && piece.chars().count() + word.chars().count() >= SNIPPET_CHARS
+                {
+                    pieces.push(word.to_string());
+                } else {
+                    if !piece.is_empty() {
+                        piece.push(' ');
+                    }
+                    piece.push_str(word);
+                }
+            }
+            pieces
+        });
+
+    for piece in pieces {
+        if !current.is_empty() && current.chars().count() + piece.chars().count() > SNIPPET_CHARS {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push_str("\n\n");
+        }
+        current.push_str(&piece);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
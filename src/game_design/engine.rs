@@ -0,0 +1,159 @@
+//! Target game engine of a session, used to tailor implementation hints of
+//! feature specifications and to point them at the engine's real API.
+
+use crate::game_design::{
+    DesignerLlmClient,
+    designer_llm::ChatMessage,
+    prompts,
+    state::{Feature, SessionState},
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Most engine API paths looked up for a feature.
+pub const MAX_API_REFERENCES: usize = 5;
+
+/// A module or type of the engine's API that a feature uses, checked to
+/// exist in the engine's documentation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApiReference {
+    /// Full path, e.g. `bevy::input::keyboard::KeyCode`.
+    pub path: String,
+    /// First sentence of its documentation.
+    pub summary: String,
+}
+
+/// A game engine a session targets.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Engine {
+    Bevy,
+    /// Godot 4, including Rust via godot-rust (gdext).
+    Godot,
+    Unity,
+    /// A custom or unlisted engine.
+    Custom,
+}
+
+impl Engine {
+    pub const ALL: [Self; 4] = [Self::Bevy, Self::Godot, Self::Unity, Self::Custom];
+
+    /// Returns the identifier used in tool arguments.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Bevy => "bevy",
+            Self::Godot => "godot",
+            Self::Unity => "unity",
+            Self::Custom => "custom",
+        }
+    }
+
+    /// Returns the description used in prompts and listings.
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Bevy => "Bevy (Rust, ECS with systems, components, resources and plugins)",
+            Self::Godot => "Godot 4 (scenes and nodes, GDScript or Rust via godot-rust gdext)",
+            Self::Unity => "Unity (C#, GameObjects, MonoBehaviours and ScriptableObjects)",
+            Self::Custom => "A custom engine (keep hints engine-neutral and architectural)",
+        }
+    }
+
+    /// Returns the crate documenting the engine's API on docs.rs, if there
+    /// is one.
+    pub fn docs_crate(self) -> Option<&'static str> {
+        match self {
+            Self::Bevy => Some("bevy"),
+            Self::Godot | Self::Unity | Self::Custom => None,
+        }
+    }
+
+    /// Parses an engine name, accepting common aliases such as `gdext`.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "gdext" | "godot-rust" | "godot_rust" | "godot4" => Some(Self::Godot),
+            name => Self::ALL.into_iter().find(|engine| engine.name() == name),
+        }
+    }
+}
+
+/// Asks the LLM for implementation hints of `feature` tailored to `engine`.
+pub async fn implementation_hints(
+    llm_client: &DesignerLlmClient,
+    session: &SessionState,
+    engine: Engine,
+    feature: &Feature,
+) -> Result<String> {
+    let prompt = format!(
+        "Target engine: {}\n\nFeature: {}\nSpecification: {}\n\n\
+         Write 3-6 concise bullet points on how to implement this feature in the target engine: \
+         which engine systems, APIs, node or component types to use, how to structure the code, \
+         and pitfalls to avoid. Only return the bullet points, nothing else.",
+        engine.description(),
+        feature.name,
+        feature.description
+    );
+
+    let messages = vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: session.system_prompt(prompts::IMPLEMENTATION_HINTS),
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content: prompt,
+        },
+    ];
+
+    Ok(llm_client.call_llm(messages).await?.trim().to_string())
+}
+
+/// Asks the LLM for the paths of the modules and types of `crate_name` that
+/// implementing `feature` uses. Paths outside the crate are dropped; the
+/// caller still has to check that the rest exist.
+pub async fn api_paths(
+    llm_client: &DesignerLlmClient,
+    session: &SessionState,
+    crate_name: &str,
+    feature: &Feature,
+) -> Result<Vec<String>> {
+    let prompt = format!(
+        "Engine crate: {}\n\nFeature: {}\nSpecification: {}\n\n\
+         List up to {} modules or types of the `{}` crate that implementing this feature uses, \
+         by their full paths as documented on docs.rs, e.g. `{}::input::keyboard::KeyCode`. \
+         Prefer the most specific path and only name paths you are sure exist. \
+         Format your response as a JSON array of strings. Only return the JSON, nothing else.",
+        crate_name,
+        feature.name,
+        feature.specification(),
+        MAX_API_REFERENCES,
+        crate_name,
+        crate_name
+    );
+
+    let messages = vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: session.system_prompt(prompts::API_REFERENCES),
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content: prompt,
+        },
+    ];
+
+    let response = llm_client.call_llm(messages).await?;
+    let paths: Vec<String> = serde_json::from_str(response.trim())
+        .map_err(|_| anyhow::anyhow!("LLM response was not a JSON array: {}", response))?;
+
+    let prefix = format!("{}::", crate_name);
+    let mut unique = Vec::new();
+    for path in paths {
+        let path = path.trim().trim_matches('`').to_string();
+        if path.starts_with(&prefix) && !unique.contains(&path) {
+            unique.push(path);
+        }
+    }
+    unique.truncate(MAX_API_REFERENCES);
+
+    Ok(unique)
+}
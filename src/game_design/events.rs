@@ -0,0 +1,340 @@
+//! Defines the state-change events emitted by design sessions and the
+//! append-only log they are recorded in.
+
+use crate::game_design::{session::check_session_id, storage::Storage};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// Declares [`EventKind`] from the dotted name of every kind, which its
+/// serialization, [`EventKind::ALL`] and [`EventKind::as_str`] all take from
+/// this one list.
+macro_rules! event_kinds {
+    ($($kind:ident => $name:literal,)*) => {
+        /// Kind of a session state change.
+        #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+        pub enum EventKind {
+            $(
+                #[serde(rename = $name)]
+                $kind,
+            )*
+        }
+
+        impl EventKind {
+            /// Every event kind, in the order of the enum.
+            pub const ALL: &[EventKind] = &[$(Self::$kind,)*];
+
+            /// Returns the dotted event name, e.g. `feature.approved`.
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $(Self::$kind => $name,)*
+                }
+            }
+        }
+    };
+}
+
+event_kinds! {
+    SessionCreated => "session.created",
+    FeatureProposed => "feature.proposed",
+    SpecChecked => "feature.spec_checked",
+    CandidatesProposed => "feature.candidates_proposed",
+    DuplicateSkipped => "feature.duplicate_skipped",
+    FeatureRejected => "feature.rejected",
+    ReviewSubmitted => "review.submitted",
+    ReviewReplied => "review.replied",
+    FeatureApproved => "feature.approved",
+    FeatureNeedsRework => "feature.needs_rework",
+    FeatureAwaitingApproval => "feature.awaiting_approval",
+    MilestoneCompleted => "milestone.completed",
+    DesignRevised => "design.revised",
+    DesignEditRejected => "design.edit_rejected",
+    QuestionAnswered => "question.answered",
+    PersonaChanged => "persona.changed",
+    FeatureWorkStarted => "feature.work_started",
+    FeatureWorkStopped => "feature.work_stopped",
+    SessionConfigured => "session.configured",
+    SessionUndone => "session.undone",
+    BoardSynced => "board.synced",
+    IdeasShortlisted => "ideas.shortlisted",
+    NarrativeChanged => "narrative.changed",
+    LevelBriefed => "level.briefed",
+    LevelStatusChanged => "level.status_changed",
+    EconomyChanged => "economy.changed",
+    MonetizationReviewed => "monetization.reviewed",
+    DebtRecorded => "debt.recorded",
+    DebtPaid => "debt.paid",
+    FeatureTestPlanned => "feature.test_planned",
+    FeatureUiCritiqued => "feature.ui_critiqued",
+    SessionFrozen => "session.frozen",
+    SessionUnfrozen => "session.unfrozen",
+}
+
+impl EventKind {
+    /// Checks the event against a filter such as `feature.approved`,
+    /// `feature.*` or `*`.
+    pub fn matches(self, filter: &str) -> bool {
+        let name = self.as_str();
+
+        match filter.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => name == filter,
+        }
+    }
+}
+
+/// Selects events of a session's history by kind and time range.
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    /// Kind filters such as `feature.approved` or `feature.*`; empty matches
+    /// every kind.
+    pub kinds: Vec<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl EventFilter {
+    /// Fails on kind filters that match no event kind, which are most likely
+    /// typos.
+    pub fn validate(&self) -> Result<()> {
+        for filter in &self.kinds {
+            if !EventKind::ALL.iter().any(|kind| kind.matches(filter)) {
+                return Err(anyhow::anyhow!(
+                    "Unknown event type '{}'. Known types: {}",
+                    filter,
+                    EventKind::ALL
+                        .iter()
+                        .map(|kind| kind.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn matches(&self, event: &SessionEvent) -> bool {
+        (self.kinds.is_empty() || self.kinds.iter().any(|f| event.kind.matches(f)))
+            && self.since.is_none_or(|since| event.timestamp >= since)
+            && self.until.is_none_or(|until| event.timestamp <= until)
+    }
+}
+
+/// Parses an RFC 3339 timestamp or a `YYYY-MM-DD` date. A date stands for
+/// its first moment, or its last one with `end_of_day`, in UTC.
+pub fn parse_time(value: &str, end_of_day: bool) -> Result<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+        anyhow::anyhow!(
+            "Invalid time '{}': use YYYY-MM-DD or an RFC 3339 timestamp",
+            value
+        )
+    })?;
+    let time = if end_of_day {
+        date.and_hms_milli_opt(23, 59, 59, 999)
+    } else {
+        date.and_hms_opt(0, 0, 0)
+    };
+
+    Ok(time.expect("valid time of day").and_utc())
+}
+
+/// A single state change of a design session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionEvent {
+    /// Position of the event in the session's log, used as a read cursor.
+    #[serde(default)]
+    pub seq: u64,
+    pub id: String,
+    pub kind: EventKind,
+    pub session_id: String,
+    pub timestamp: DateTime<Utc>,
+    /// Event-specific payload, e.g. the feature name and review verdict.
+    pub data: Value,
+}
+
+impl SessionEvent {
+    /// Creates a new event that happened at `timestamp`.
+    pub fn new(
+        kind: EventKind,
+        session_id: impl Into<String>,
+        data: Value,
+        timestamp: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            seq: 0,
+            id: format!("{:032x}", rand::random::<u128>()),
+            kind,
+            session_id: session_id.into(),
+            timestamp,
+            data,
+        }
+    }
+}
+
+/// Append-only event log stored as `<session>.events.jsonl` next to the
+/// session files.
+///
+/// Where each event starts in its file is kept in memory, indexed once per
+/// session and then only for the lines appended since, by this log or by
+/// another process. Appends and reads from a cursor therefore don't rescan
+/// the whole file.
+#[derive(Clone)]
+pub struct EventLog {
+    dir: PathBuf,
+    storage: Arc<dyn Storage>,
+    indexes: Arc<Mutex<HashMap<String, LineIndex>>>,
+}
+
+/// Byte offsets of the lines of a log file, one per event.
+#[derive(Debug, Default)]
+struct LineIndex {
+    starts: Vec<u64>,
+    /// Bytes of the file covered by `starts`, up to the end of the last
+    /// complete line.
+    indexed: u64,
+}
+
+impl LineIndex {
+    /// Indexes the lines added to the file at `path` since the last call.
+    /// A file that shrank, e.g. because it was removed, is indexed anew.
+    fn refresh(&mut self, storage: &dyn Storage, path: &Path) -> Result<()> {
+        if !storage.exists(path) {
+            *self = Self::default();
+            return Ok(());
+        }
+        if storage.size(path)? < self.indexed {
+            *self = Self::default();
+        }
+
+        let tail = storage.read_from(path, self.indexed)?;
+        // A line still being written is indexed once it is complete
+        for line in tail.split_inclusive('\n') {
+            if !line.ends_with('\n') {
+                break;
+            }
+
+            self.starts.push(self.indexed);
+            self.indexed += line.len() as u64;
+        }
+
+        Ok(())
+    }
+}
+
+impl EventLog {
+    /// Creates a log rooted at the persistence directory.
+    pub fn new(dir: impl AsRef<Path>, storage: Arc<dyn Storage>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            storage,
+            indexes: Arc::default(),
+        }
+    }
+
+    /// Path of the session's log. Fails for names that don't pass
+    /// [`check_session_id`].
+    fn path(&self, session_id: &str) -> Result<PathBuf> {
+        check_session_id(session_id)?;
+        Ok(self.dir.join(format!("{}.events.jsonl", session_id)))
+    }
+
+    /// Runs `f` on the up-to-date line index of the session's log.
+    fn with_index<T>(
+        &self,
+        session_id: &str,
+        f: impl FnOnce(&mut LineIndex, &Path) -> Result<T>,
+    ) -> Result<T> {
+        let path = self.path(session_id)?;
+        let mut indexes = self.indexes.lock().unwrap();
+        let index = indexes.entry(session_id.to_string()).or_default();
+
+        index.refresh(self.storage.as_ref(), &path)?;
+        f(index, &path)
+    }
+
+    /// Returns the sequence number the next event of the session will get.
+    pub fn next_seq(&self, session_id: &str) -> Result<u64> {
+        self.with_index(session_id, |index, _| Ok(index.starts.len() as u64))
+    }
+
+    /// Appends the event, assigning it the next sequence number.
+    pub fn append(&self, event: &mut SessionEvent) -> Result<()> {
+        let session_id = event.session_id.clone();
+
+        self.with_index(&session_id, |index, path| {
+            event.seq = index.starts.len() as u64;
+            let line = format!("{}\n", serde_json::to_string(event)?);
+
+            self.storage.append(path, &line)?;
+            index.starts.push(index.indexed);
+            index.indexed += line.len() as u64;
+
+            Ok(())
+        })
+    }
+
+    /// Reads up to `limit` events with a sequence number of at least `cursor`.
+    /// Only the lines of those events are read from the log.
+    pub fn read_since(
+        &self,
+        session_id: &str,
+        cursor: u64,
+        limit: usize,
+    ) -> Result<Vec<SessionEvent>> {
+        let range = self.with_index(session_id, |index, path| {
+            let first = usize::try_from(cursor).unwrap_or(usize::MAX);
+            let Some(&start) = index.starts.get(first) else {
+                return Ok(None);
+            };
+            // Up to the start of the first event not asked for, or the end of
+            // the last complete line
+            let end = index
+                .starts
+                .get(first.saturating_add(limit))
+                .copied()
+                .unwrap_or(index.indexed);
+
+            Ok(Some((path.to_path_buf(), start, end)))
+        })?;
+        let Some((path, start, end)) = range else {
+            return Ok(Vec::new());
+        };
+
+        self.storage
+            .read_range(&path, start, end - start)?
+            .lines()
+            .map(|line| Ok(serde_json::from_str(line)?))
+            .collect()
+    }
+
+    /// Reads the events matching `filter`, oldest first. With more than
+    /// `limit` matches only the most recent `limit` are returned.
+    pub fn history(
+        &self,
+        session_id: &str,
+        filter: &EventFilter,
+        limit: usize,
+    ) -> Result<Vec<SessionEvent>> {
+        let mut events = self
+            .read_since(session_id, 0, usize::MAX)?
+            .into_iter()
+            .filter(|event| filter.matches(event))
+            .collect::<Vec<_>>();
+
+        let skip = events.len().saturating_sub(limit);
+        events.drain(..skip);
+
+        Ok(events)
+    }
+}
@@ -0,0 +1,241 @@
+//! Renders a session as a Markdown game design document (GDD) and keeps
+//! configured export files in sync with the session state.
+
+use crate::game_design::{
+    assets, narrative, session,
+    state::{FeatureStatus, SessionState},
+    storage::Storage,
+};
+use anyhow::Result;
+use std::{fs, path::Path, sync::Arc};
+use tokio::sync::Mutex;
+
+/// Renders the session as a Markdown game design document.
+pub fn render_markdown(session: &SessionState) -> String {
+    let mut markdown = format!("# {} — Game Design Document\n\n", session.id);
+
+    markdown.push_str(&format!(
+        "_Design version {} · persona `{}`",
+        session.design_version(),
+        session.persona.name()
+    ));
+    if let Some(template) = &session.template {
+        markdown.push_str(&format!(" · template `{}`", template));
+    }
+    markdown.push_str("_\n\n");
+
+    let metadata = &session.metadata;
+    if let Some(studio) = &metadata.studio {
+        markdown.push_str(&format!("**Studio:** {}  \n", studio));
+    }
+    if !metadata.authors.is_empty() {
+        markdown.push_str(&format!("**Authors:** {}  \n", metadata.authors.join(", ")));
+    }
+    if let Some(license) = &metadata.license {
+        markdown.push_str(&format!("**License:** {}  \n", license));
+    }
+    if !metadata.is_empty() {
+        markdown.push('\n');
+    }
+
+    markdown.push_str("## Design\n\n");
+    markdown.push_str(session.initial_description.trim());
+    markdown.push_str("\n\n");
+
+    if !session.design_memory.is_empty() {
+        markdown.push_str("## Design Decisions\n\n");
+        for decision in &session.design_memory {
+            match &decision.source_feature {
+                Some(feature) => {
+                    markdown.push_str(&format!("- {} _(from {})_\n", decision.decision, feature))
+                }
+                None => markdown.push_str(&format!("- {}\n", decision.decision)),
+            }
+        }
+        markdown.push('\n');
+    }
+
+    if !session.narrative.is_empty() {
+        markdown.push_str("## Story\n\n");
+        markdown.push_str(&narrative::outline(&session.narrative));
+        markdown.push_str("\n\n");
+    }
+
+    if !session.review_criteria.is_empty() {
+        markdown.push_str("## Review Criteria\n\n");
+        for criterion in &session.review_criteria {
+            markdown.push_str(&format!("- {}\n", criterion));
+        }
+        markdown.push('\n');
+    }
+
+    if !session.planned_features.is_empty() {
+        markdown.push_str("## Features\n\n");
+
+        for status in [
+            FeatureStatus::InProgress,
+            FeatureStatus::NeedsRework,
+            FeatureStatus::Planned,
+            FeatureStatus::Implemented,
+            FeatureStatus::AwaitingApproval,
+            FeatureStatus::Reviewed,
+        ] {
+            let features: Vec<_> = session
+                .planned_features
+                .iter()
+                .filter(|feature| feature.status == status)
+                .collect();
+
+            if features.is_empty() {
+                continue;
+            }
+
+            markdown.push_str(&format!("### {:?}\n\n", status));
+            for feature in features {
+                let next = session.next_feature_to_implement.as_deref() == Some(&feature.name);
+                markdown.push_str(&format!(
+                    "#### {}{}\n\n{}\n\n",
+                    feature.name,
+                    if next { " (next)" } else { "" },
+                    feature.description.trim()
+                ));
+                if let Some(test_plan) = &feature.test_plan {
+                    markdown.push_str(&format!("{}\n\n", test_plan.markdown()));
+                }
+            }
+        }
+    }
+
+    if !session.levels.is_empty() {
+        markdown.push_str("## Levels\n\n");
+        for level in &session.levels {
+            // Level briefs start with their own top-level heading
+            let brief = level.brief();
+            let brief = brief.strip_prefix("# ").unwrap_or(&brief);
+            markdown.push_str(&format!("### {}\n\n", brief));
+        }
+    }
+
+    if !session.economy.is_empty() {
+        markdown.push_str(&format!("## Economy\n\n{}\n\n", session.economy.render()));
+    }
+
+    if let Some(report) = &session.monetization_review {
+        markdown.push_str(&format!(
+            "## Monetization Review\n\n{}\n\n",
+            report.render()
+        ));
+    }
+
+    let asset_entries = assets::collect(session, None);
+    if !asset_entries.is_empty() {
+        markdown.push_str("## Asset Requirements\n\n");
+        markdown.push_str("| Kind | Asset | Feature | Description |\n");
+        markdown.push_str("| --- | --- | --- | --- |\n");
+        for entry in asset_entries {
+            markdown.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                entry.asset.kind.name(),
+                table_cell(&entry.asset.name),
+                table_cell(&entry.feature),
+                table_cell(&entry.asset.description)
+            ));
+        }
+        markdown.push('\n');
+    }
+
+    if !session.design_history.is_empty() {
+        markdown.push_str("## Revision History\n\n");
+        for version in &session.design_history {
+            markdown.push_str(&format!(
+                "- v{} → v{} ({}): {}\n",
+                version.version,
+                version.version + 1,
+                version.superseded_at.format("%Y-%m-%d"),
+                version.superseded_by_change
+            ));
+        }
+        markdown.push('\n');
+    }
+
+    if let Some(attribution) = metadata.attribution() {
+        markdown.push_str(&format!("---\n\n{}\n", attribution));
+    }
+
+    markdown
+}
+
+/// Escapes a value for use inside a Markdown table cell.
+fn table_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Writes the rendered document of the session to `path`, creating parent
+/// directories as needed.
+pub fn write(session: &SessionState, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, render_markdown(session))?;
+    Ok(())
+}
+
+/// Regenerates the export file of sessions with automatic export enabled
+/// in the background after every state change.
+#[derive(Clone)]
+pub struct GddExporter {
+    persistence_path: String,
+    /// Storage of the session files.
+    storage: Arc<dyn Storage>,
+    /// Serializes regenerations so that the last one always writes the
+    /// latest persisted state.
+    lock: Arc<Mutex<()>>,
+}
+
+impl GddExporter {
+    pub fn new(persistence_path: &str, storage: Arc<dyn Storage>) -> Self {
+        Self {
+            persistence_path: persistence_path.to_string(),
+            storage,
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Spawns a regeneration of the session's export file if automatic
+    /// export is enabled for it. Failures are logged.
+    pub fn regenerate(&self, session_id: &str) {
+        let exporter = self.clone();
+        let session_id = session_id.to_string();
+
+        tokio::spawn(async move {
+            let _guard = exporter.lock.lock().await;
+
+            if let Err(e) = exporter.regenerate_now(&session_id) {
+                tracing::warn!(
+                    "Failed to regenerate design export of '{}': {}",
+                    session_id,
+                    e
+                );
+            }
+        });
+    }
+
+    fn regenerate_now(&self, session_id: &str) -> Result<()> {
+        session::check_session_id(session_id)?;
+        let session_file_path = format!("{}/{}.json", self.persistence_path, session_id);
+        if !self.storage.exists(Path::new(&session_file_path)) {
+            return Ok(());
+        }
+
+        let session: SessionState =
+            serde_json::from_str(&self.storage.read_to_string(Path::new(&session_file_path))?)?;
+
+        match &session.export_path {
+            Some(path) => write(&session, Path::new(path)),
+            None => Ok(()),
+        }
+    }
+}
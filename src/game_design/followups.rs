@@ -0,0 +1,73 @@
+//! Limit of the follow-up rounds of a review.
+//!
+//! Every `reviewReply` to a review that did not approve the feature is a
+//! round. The reply that reaches the limit escalates: the designer may not
+//! ask further questions and must either accept with caveats, which become
+//! technical debt, or reject with [`REWORK_VERDICT`] and a concrete rework
+//! list. Replies beyond the limit are refused until the reworked feature is
+//! submitted again with `featureReview`, which starts the count over.
+
+use crate::game_design::{
+    config,
+    debt::CAVEATS_VERDICT,
+    state::{ReviewEntryKind, SessionState},
+};
+use std::env;
+
+/// Follow-up rounds of a review without configuration.
+pub const DEFAULT_MAX_FOLLOW_UPS: usize = 3;
+
+/// First line of an escalated review that rejects the implementation.
+pub const REWORK_VERDICT: &str = "REWORK";
+
+/// Reads the limit of follow-up rounds from `GAMEDESIGNER_MAX_FOLLOW_UPS`,
+/// falling back to the configuration file. `0` disables the limit.
+pub fn max_follow_ups() -> usize {
+    env::var("GAMEDESIGNER_MAX_FOLLOW_UPS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .or(config::current().review.max_follow_ups)
+        .unwrap_or(DEFAULT_MAX_FOLLOW_UPS)
+}
+
+/// Replies to the last review report of `feature`.
+pub fn rounds(session: &SessionState, feature: &str) -> usize {
+    session
+        .review_log
+        .iter()
+        .rev()
+        .filter(|entry| entry.feature == feature)
+        .take_while(|entry| entry.kind == ReviewEntryKind::Reply)
+        .count()
+}
+
+/// Instruction of the reply that reaches the limit of `max` rounds.
+pub fn escalation_instruction(max: usize) -> String {
+    format!(
+        "\n\nThis review has reached its limit of {} follow-up rounds. Do not ask any further \
+         questions; decide now. If the implementation can be accepted, respond with 'SATISFIED' \
+         or with '{}' on the first line followed by one caveat per line as \
+         '- [high|medium|low] description'. Otherwise respond with '{}' on the first line \
+         followed by a concrete rework list, one change the developer has to make per line as \
+         '- description'.",
+        max, CAVEATS_VERDICT, REWORK_VERDICT
+    )
+}
+
+/// Reads the rework list of a review that starts with [`REWORK_VERDICT`],
+/// one item per line. Returns `None` for any other review.
+pub fn rework_list(review: &str) -> Option<Vec<String>> {
+    let mut lines = review.trim().lines();
+    let verdict = lines.next()?.trim().trim_end_matches(':').trim();
+    if !verdict.eq_ignore_ascii_case(REWORK_VERDICT) {
+        return None;
+    }
+
+    Some(
+        lines
+            .map(|line| line.trim().trim_start_matches(['-', '*']).trim())
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect(),
+    )
+}
@@ -0,0 +1,62 @@
+//! Reads the game repository with the `git` CLI so reviews can check
+//! implementation reports against the actual changes.
+
+use anyhow::Result;
+use std::path::Path;
+use tokio::process::Command;
+
+/// Runs `git` in `repo` and returns its stdout.
+async fn git(repo: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to run git: {}", e))?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Returns the root directory of the repository containing `path`.
+pub async fn toplevel(path: &Path) -> Result<String> {
+    Ok(git(path, &["rev-parse", "--show-toplevel"])
+        .await?
+        .trim()
+        .to_string())
+}
+
+/// Returns the hash of the commit checked out in `repo`.
+pub async fn head(repo: &Path) -> Result<String> {
+    Ok(git(repo, &["rev-parse", "HEAD"]).await?.trim().to_string())
+}
+
+/// Returns the changes of the working tree since `base`: a `--stat`
+/// summary followed by the full diff.
+pub async fn diff_since(repo: &Path, base: &str) -> Result<String> {
+    let stat = git(repo, &["diff", "--stat", base]).await?;
+    let diff = git(repo, &["diff", base]).await?;
+
+    Ok(format!("{}\n{}", stat.trim_end(), diff))
+}
+
+/// Returns the staged changes of `repo`: a `--stat` summary followed by the
+/// full diff. Empty if nothing is staged.
+pub async fn staged_diff(repo: &Path) -> Result<String> {
+    let stat = git(repo, &["diff", "--cached", "--stat"]).await?;
+    if stat.trim().is_empty() {
+        return Ok(String::new());
+    }
+
+    let diff = git(repo, &["diff", "--cached"]).await?;
+
+    Ok(format!("{}\n{}", stat.trim_end(), diff))
+}
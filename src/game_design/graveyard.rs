@@ -0,0 +1,239 @@
+//! The idea graveyard: features the developer turned down.
+//!
+//! A proposal rejected with `featureReject` and the candidates passed over in
+//! `featureSelect` are buried here. New `nextFeature` proposals are compared
+//! against them by their words and their embeddings (see
+//! [`embeddings`](crate::game_design::embeddings)): a proposal that repeats a
+//! buried feature is skipped, one that resembles it is handed out with a note
+//! naming the rejected feature and what the proposal does differently.
+
+use crate::game_design::{
+    config,
+    embeddings::{self, Embedder},
+    search,
+    state::{Feature, RejectedSimilarity, SessionState},
+};
+use std::collections::HashSet;
+
+/// Proposals at least this similar to a buried feature are skipped, unless
+/// `[features] duplicate_threshold` is set.
+pub const DUPLICATE_THRESHOLD: f32 = 0.85;
+
+/// Proposals at least this similar to a buried feature are annotated, unless
+/// `[features] similar_threshold` is set.
+pub const SIMILAR_THRESHOLD: f32 = 0.6;
+
+/// Acceptance criteria sharing at least this fraction of their terms are
+/// taken to ask for the same thing.
+const CRITERION_OVERLAP: f32 = 0.5;
+
+/// Reason given for candidates passed over in `featureSelect`.
+const PASSED_OVER: &str = "passed over for another candidate";
+
+pub fn duplicate_threshold() -> f32 {
+    config::current()
+        .features
+        .duplicate_threshold
+        .unwrap_or(DUPLICATE_THRESHOLD)
+}
+
+pub fn similar_threshold() -> f32 {
+    config::current()
+        .features
+        .similar_threshold
+        .unwrap_or(SIMILAR_THRESHOLD)
+}
+
+/// Whether `similarity` is close enough for the proposal to be skipped.
+pub fn is_duplicate(similarity: &RejectedSimilarity) -> bool {
+    similarity.score >= duplicate_threshold()
+}
+
+/// The buried features of `session` with the reason they were turned down:
+/// rejected proposals first, then passed over candidates.
+pub fn buried(session: &SessionState) -> Vec<(&Feature, &str)> {
+    session
+        .graveyard
+        .iter()
+        .map(|rejected| {
+            let reason = rejected.reason.as_deref().unwrap_or("rejected");
+            (&rejected.feature, reason)
+        })
+        .chain(
+            session
+                .passed_over_candidates
+                .iter()
+                .map(|candidate| (&candidate.feature, PASSED_OVER)),
+        )
+        .collect()
+}
+
+/// Names of the buried features, lowercased.
+pub fn buried_names(session: &SessionState) -> Vec<String> {
+    buried(session)
+        .into_iter()
+        .map(|(feature, _)| feature.name.to_lowercase())
+        .collect()
+}
+
+/// Finds, for each of `proposals`, the most similar buried feature of
+/// `session`, if it is at least as similar as [`similar_threshold`].
+/// Without embeddings, e.g. when the provider is unreachable, the proposals
+/// are compared by their words alone.
+pub async fn compare(
+    session: &SessionState,
+    proposals: &[Feature],
+) -> Vec<Option<RejectedSimilarity>> {
+    let buried = buried(session);
+    if buried.is_empty() || proposals.is_empty() {
+        return vec![None; proposals.len()];
+    }
+
+    let texts = proposals
+        .iter()
+        .chain(buried.iter().map(|(feature, _)| *feature))
+        .map(text)
+        .collect::<Vec<_>>();
+
+    let vectors = match embed(&texts).await {
+        Ok(vectors) => Some(vectors),
+        Err(e) => {
+            tracing::warn!("Comparing proposals by their words only: {}", e);
+            None
+        }
+    };
+
+    let threshold = similar_threshold();
+
+    proposals
+        .iter()
+        .enumerate()
+        .map(|(index, proposal)| {
+            buried
+                .iter()
+                .enumerate()
+                .map(|(buried_index, (feature, reason))| {
+                    let other = proposals.len() + buried_index;
+
+                    let mut score = lexical_similarity(&texts[index], &texts[other]);
+                    if proposal
+                        .name
+                        .trim()
+                        .eq_ignore_ascii_case(feature.name.trim())
+                    {
+                        score = 1.0;
+                    }
+                    if let Some(vectors) = &vectors {
+                        score = score.max(embeddings::dot(&vectors[index], &vectors[other]));
+                    }
+
+                    (score, feature, reason)
+                })
+                .filter(|(score, _, _)| *score >= threshold)
+                .max_by(|a, b| a.0.total_cmp(&b.0))
+                .map(|(score, feature, reason)| RejectedSimilarity {
+                    feature: feature.name.clone(),
+                    reason: reason.to_string(),
+                    score,
+                    differences: differences(proposal, feature),
+                })
+        })
+        .collect()
+}
+
+/// Finds the buried feature most similar to `proposal`, see [`compare`].
+pub async fn closest(session: &SessionState, proposal: &Feature) -> Option<RejectedSimilarity> {
+    compare(session, std::slice::from_ref(proposal))
+        .await
+        .pop()
+        .flatten()
+}
+
+/// Asks for another feature than `proposal`, which repeats a buried one.
+pub fn skip_instruction(proposal: &Feature, similarity: &RejectedSimilarity) -> String {
+    format!(
+        "\n\nThe proposal '{}' repeats '{}', which the developer rejected before ({}). \
+         Propose a different feature, in the same JSON format.",
+        proposal.name, similarity.feature, similarity.reason
+    )
+}
+
+/// Renders the buried features as a prompt section; empty without any.
+pub fn section(session: &SessionState) -> String {
+    let buried = buried(session);
+    if buried.is_empty() {
+        return String::new();
+    }
+
+    let mut section =
+        "Features proposed before but rejected by the developer (do not propose these again):\n"
+            .to_string();
+    for (feature, reason) in buried {
+        section.push_str(&format!("- {} ({})\n", feature.name, reason));
+    }
+    section.push('\n');
+
+    section
+}
+
+async fn embed(texts: &[String]) -> anyhow::Result<Vec<Vec<f32>>> {
+    Embedder::new()?.embed(texts).await
+}
+
+/// Text a feature is compared by.
+fn text(feature: &Feature) -> String {
+    format!("{}. {}", feature.name, feature.description)
+}
+
+/// Share of terms two texts have in common, in `0.0..=1.0`.
+fn lexical_similarity(a: &str, b: &str) -> f32 {
+    overlap(&search::terms(a), &search::terms(b))
+}
+
+fn overlap(a: &HashSet<String>, b: &HashSet<String>) -> f32 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+
+    a.intersection(b).count() as f32 / union as f32
+}
+
+/// What `proposal` does differently from the buried `feature`: acceptance
+/// criteria it adds and drops, and a different effort or milestone.
+fn differences(proposal: &Feature, feature: &Feature) -> Vec<String> {
+    let unmatched = |criteria: &[String], others: &[String]| {
+        criteria
+            .iter()
+            .filter(|criterion| {
+                let terms = search::terms(criterion);
+                others
+                    .iter()
+                    .all(|other| overlap(&terms, &search::terms(other)) < CRITERION_OVERLAP)
+            })
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+
+    let mut differences = Vec::new();
+
+    for criterion in unmatched(&proposal.acceptance_criteria, &feature.acceptance_criteria) {
+        differences.push(format!("adds \"{}\"", criterion));
+    }
+    for criterion in unmatched(&feature.acceptance_criteria, &proposal.acceptance_criteria) {
+        differences.push(format!("drops \"{}\"", criterion));
+    }
+
+    if let (Some(effort), Some(before)) = (proposal.estimated_effort, feature.estimated_effort)
+        && effort != before
+    {
+        differences.push(format!("effort {:?} instead of {:?}", effort, before));
+    }
+    if let (Some(milestone), Some(before)) = (&proposal.milestone, &feature.milestone)
+        && !milestone.eq_ignore_ascii_case(before)
+    {
+        differences.push(format!("milestone {} instead of {}", milestone, before));
+    }
+
+    differences
+}
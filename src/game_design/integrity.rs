@@ -0,0 +1,280 @@
+//! Validates stored session files so problems surface at startup instead of
+//! at the first tool call.
+//!
+//! Repairable problems are fixed in place. Files that cannot be parsed are
+//! moved to `quarantine/` in the persistence directory.
+
+use crate::game_design::{
+    project,
+    session::RESERVED_SESSION_NAMES,
+    state::{SESSION_SCHEMA_VERSION, SessionState},
+};
+use anyhow::Result;
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// A problem found in a session file.
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub file: PathBuf,
+    pub description: String,
+}
+
+/// Result of checking all session files.
+#[derive(Debug, Clone, Default)]
+pub struct IntegrityReport {
+    pub checked: usize,
+    /// Problems that were (or, in a dry run, would be) fixed in place.
+    pub repaired: Vec<Problem>,
+    /// Problems that need attention but were left as they are.
+    pub warnings: Vec<Problem>,
+    /// Files moved to the quarantine directory, with the reason.
+    pub quarantined: Vec<Problem>,
+}
+
+impl IntegrityReport {
+    pub fn is_clean(&self) -> bool {
+        self.repaired.is_empty() && self.warnings.is_empty() && self.quarantined.is_empty()
+    }
+
+    /// Logs every problem of the report.
+    pub fn log(&self) {
+        for problem in &self.repaired {
+            tracing::warn!(
+                "Repaired {}: {}",
+                problem.file.display(),
+                problem.description
+            );
+        }
+        for problem in &self.warnings {
+            tracing::warn!("{}: {}", problem.file.display(), problem.description);
+        }
+        for problem in &self.quarantined {
+            tracing::error!(
+                "Quarantined {}: {}",
+                problem.file.display(),
+                problem.description
+            );
+        }
+
+        tracing::info!(
+            "Checked {} session files: {} repaired, {} warnings, {} quarantined",
+            self.checked,
+            self.repaired.len(),
+            self.warnings.len(),
+            self.quarantined.len()
+        );
+    }
+}
+
+/// Directory irreparable session files are moved to.
+pub fn quarantine_dir(persistence_path: &Path) -> PathBuf {
+    persistence_path.join("quarantine")
+}
+
+/// Checks the session files of every project, see [`check`].
+pub fn check_all(dry_run: bool) -> Result<IntegrityReport> {
+    let mut report = IntegrityReport::default();
+
+    for dir in project::all_dirs()? {
+        let project_report = check(&dir, dry_run)?;
+
+        report.checked += project_report.checked;
+        report.repaired.extend(project_report.repaired);
+        report.warnings.extend(project_report.warnings);
+        report.quarantined.extend(project_report.quarantined);
+    }
+
+    Ok(report)
+}
+
+/// Checks every `<id>.json` session file in the persistence directory.
+/// Without `dry_run`, repairs are written back and unparseable files are
+/// quarantined.
+pub fn check(persistence_path: &Path, dry_run: bool) -> Result<IntegrityReport> {
+    let mut report = IntegrityReport::default();
+
+    if !persistence_path.exists() {
+        return Ok(report);
+    }
+
+    for entry in fs::read_dir(persistence_path)? {
+        let path = entry?.path();
+
+        let Some(id) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".json"))
+            .map(str::to_string)
+        else {
+            continue;
+        };
+
+        // Sessions are stored as `<id>.json`; configuration and sidecar
+        // files either have more dots or are not sessions at all
+        if id.contains('.') || RESERVED_SESSION_NAMES.contains(&id) || !path.is_file() {
+            continue;
+        }
+
+        report.checked += 1;
+        check_file(&path, &id, persistence_path, dry_run, &mut report)?;
+    }
+
+    Ok(report)
+}
+
+fn check_file(
+    path: &Path,
+    id: &str,
+    persistence_path: &Path,
+    dry_run: bool,
+    report: &mut IntegrityReport,
+) -> Result<()> {
+    let problem = |description: String| Problem {
+        file: path.to_path_buf(),
+        description,
+    };
+
+    let mut session = match fs::read_to_string(path)
+        .map_err(anyhow::Error::from)
+        .and_then(|json| Ok(serde_json::from_str::<SessionState>(&json)?))
+    {
+        Ok(session) => session,
+        Err(e) => {
+            if !dry_run {
+                quarantine(path, persistence_path)?;
+            }
+            report
+                .quarantined
+                .push(problem(format!("not a valid session file: {}", e)));
+            return Ok(());
+        }
+    };
+
+    let mut repaired = false;
+
+    if session.schema_version > SESSION_SCHEMA_VERSION {
+        report.warnings.push(problem(format!(
+            "written by a newer version (schema {}, supported {}); left unchanged",
+            session.schema_version, SESSION_SCHEMA_VERSION
+        )));
+        return Ok(());
+    }
+
+    if session.schema_version < SESSION_SCHEMA_VERSION {
+        report.repaired.push(problem(format!(
+            "upgraded schema version {} to {}",
+            session.schema_version, SESSION_SCHEMA_VERSION
+        )));
+        session.schema_version = SESSION_SCHEMA_VERSION;
+        repaired = true;
+    }
+
+    if session.id != id {
+        report.repaired.push(problem(format!(
+            "session id '{}' did not match the file name",
+            session.id
+        )));
+        session.id = id.to_string();
+        repaired = true;
+    }
+
+    if let Some(next) = &session.next_feature_to_implement
+        && !session.planned_features.iter().any(|f| &f.name == next)
+    {
+        report.repaired.push(problem(format!(
+            "next feature '{}' is not a planned feature; cleared it",
+            next
+        )));
+        session.next_feature_to_implement = None;
+        repaired = true;
+    }
+
+    let mut names = HashSet::new();
+    for feature in &session.planned_features {
+        if !names.insert(feature.name.as_str()) {
+            report.warnings.push(problem(format!(
+                "feature '{}' is planned more than once",
+                feature.name
+            )));
+        }
+    }
+
+    for name in session.implemented_features_reports.keys() {
+        if !names.contains(name.as_str()) {
+            report.warnings.push(problem(format!(
+                "implementation report for unknown feature '{}'",
+                name
+            )));
+        }
+    }
+
+    if repaired && !dry_run {
+        fs::write(path, serde_json::to_string_pretty(&session)?)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the event logs and snapshot directories in the persistence
+/// directory whose session file is missing, e.g. of deleted sessions.
+pub fn orphans(persistence_path: &Path) -> Result<Vec<PathBuf>> {
+    let mut orphans = Vec::new();
+
+    if !persistence_path.exists() {
+        return Ok(orphans);
+    }
+
+    let has_session = |id: &str| persistence_path.join(format!("{}.json", id)).is_file();
+
+    for entry in fs::read_dir(persistence_path)? {
+        let path = entry?.path();
+        let id = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".events.jsonl"));
+
+        if let Some(id) = id
+            && !has_session(id)
+        {
+            orphans.push(path);
+        }
+    }
+
+    let snapshots = persistence_path.join("snapshots");
+    if snapshots.is_dir() {
+        for entry in fs::read_dir(snapshots)? {
+            let path = entry?.path();
+            let id = path.file_name().and_then(|name| name.to_str());
+
+            if let Some(id) = id
+                && path.is_dir()
+                && !has_session(id)
+            {
+                orphans.push(path);
+            }
+        }
+    }
+
+    orphans.sort();
+    Ok(orphans)
+}
+
+/// Moves a file into the quarantine directory under a timestamped name.
+fn quarantine(path: &Path, persistence_path: &Path) -> Result<()> {
+    let dir = quarantine_dir(persistence_path);
+    fs::create_dir_all(&dir)?;
+
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let target = dir.join(format!(
+        "{}.{}",
+        file_name,
+        chrono::Utc::now().format("%Y%m%dT%H%M%S")
+    ));
+
+    fs::rename(path, target)?;
+    Ok(())
+}
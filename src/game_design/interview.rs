@@ -0,0 +1,172 @@
+//! Cold-start onboarding interview that gathers the basics of a game over
+//! several tool calls and synthesizes the initial design document from them.
+
+use crate::game_design::{
+    DesignerLlmClient, designer_llm::ChatMessage, persona::Persona, prompts, storage::Storage,
+};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// The interview topics and their questions, asked in order.
+pub const QUESTIONS: &[(&str, &str)] = &[
+    (
+        "Genre",
+        "What genre is the game, and what does the player do from moment to moment?",
+    ),
+    (
+        "Platform",
+        "Which platforms and input devices should the game target?",
+    ),
+    (
+        "Scope",
+        "How big is the project: team size, time budget and expected play time?",
+    ),
+    (
+        "Inspiration",
+        "Which existing games, films or books inspire it, and what should it take from them?",
+    ),
+    (
+        "Constraints",
+        "Are there any hard constraints, such as engine, art style, budget or content rating?",
+    ),
+];
+
+/// An interview in progress, stored next to the session files until the
+/// session is created.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interview {
+    pub session_id: String,
+    pub persona: Persona,
+    /// Answers to `QUESTIONS`, in order.
+    pub answers: Vec<String>,
+    pub started_at: DateTime<Utc>,
+}
+
+/// What the interview needs next.
+#[derive(Debug, Clone)]
+pub enum InterviewStep {
+    /// The next question to put to the user; `index` starts at 1.
+    Question {
+        index: usize,
+        total: usize,
+        topic: &'static str,
+        question: &'static str,
+    },
+    /// All questions are answered and the session was created.
+    Completed { document: String },
+}
+
+impl Interview {
+    pub fn new(session_id: String, persona: Persona, started_at: DateTime<Utc>) -> Self {
+        Self {
+            session_id,
+            persona,
+            answers: Vec::new(),
+            started_at,
+        }
+    }
+
+    /// Path of the file holding an unfinished interview.
+    pub fn file_path(persistence_path: &str, session_id: &str) -> PathBuf {
+        PathBuf::from(format!(
+            "{}/{}.interview.json",
+            persistence_path, session_id
+        ))
+    }
+
+    /// Loads an unfinished interview, if there is one.
+    pub fn load(
+        storage: &dyn Storage,
+        persistence_path: &str,
+        session_id: &str,
+    ) -> Result<Option<Self>> {
+        let path = Self::file_path(persistence_path, session_id);
+
+        if !storage.exists(&path) {
+            return Ok(None);
+        }
+
+        Ok(Some(serde_json::from_str(&storage.read_to_string(&path)?)?))
+    }
+
+    pub fn save(&self, storage: &dyn Storage, persistence_path: &str) -> Result<()> {
+        let path = Self::file_path(persistence_path, &self.session_id);
+        storage.write(&path, &serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Returns the question awaiting an answer, if any.
+    pub fn next_question(&self) -> Option<InterviewStep> {
+        let index = self.answers.len();
+        let (topic, question) = QUESTIONS.get(index)?;
+
+        Some(InterviewStep::Question {
+            index: index + 1,
+            total: QUESTIONS.len(),
+            topic,
+            question,
+        })
+    }
+
+    /// Renders the answers as a topic-per-line brief.
+    pub fn brief(&self) -> String {
+        QUESTIONS
+            .iter()
+            .zip(&self.answers)
+            .map(|((topic, _), answer)| format!("{}: {}", topic, answer))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Asks the LLM for the initial design document. Without an LLM client,
+    /// or if the call fails, the brief itself becomes the document.
+    pub async fn synthesize(&self, llm_client: Option<&DesignerLlmClient>) -> String {
+        let brief = self.brief();
+
+        let Some(llm_client) = llm_client else {
+            tracing::warn!("No LLM client available. Using interview answers as the design.");
+            return brief;
+        };
+
+        let prompt = format!(
+            r"Create a comprehensive game design document from this onboarding interview:
+{}
+
+Include the following sections:
+1. Core Concept: A brief summary of the game's main idea
+2. Gameplay Mechanics: Key gameplay systems and interactions
+3. Story and Setting: The narrative context and world
+4. Target Audience: Who the game is designed for
+5. Unique Features: What makes this game stand out
+6. Technical Considerations: Any important technical aspects, respecting the stated platform and constraints
+7. Development Milestones: Major phases of development, sized to the stated scope
+
+Provide detailed but concise information for each section.",
+            brief
+        );
+
+        let messages = vec![
+            ChatMessage {
+                role: "system".to_string(),
+                content: self.persona.system_prompt(prompts::DESIGN_NEW),
+            },
+            ChatMessage {
+                role: "user".to_string(),
+                content: prompt,
+            },
+        ];
+
+        match llm_client.call_llm(messages).await {
+            Ok(document) => document,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to synthesize design from interview: {}. Using interview answers.",
+                    e
+                );
+                brief
+            }
+        }
+    }
+}
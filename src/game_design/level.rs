@@ -0,0 +1,264 @@
+//! Levels as first-class design entities with structured briefs.
+//!
+//! A brief describes a level's layout, its difficulty curve section by
+//! section, the mechanics it exercises (planned features by name) and the
+//! assets it needs. Levels move through their own statuses, independent of
+//! the features they use.
+
+use crate::game_design::{
+    DesignerLlmClient,
+    assets::AssetRequirement,
+    designer_llm::{ChatMessage, extract_json},
+    memory, prompts,
+    state::SessionState,
+};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Highest difficulty of a level section.
+pub const MAX_DIFFICULTY: u32 = 10;
+
+/// Production status of a level.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LevelStatus {
+    /// Briefed, not yet built.
+    #[default]
+    Briefed,
+    /// Greybox geometry to test the layout.
+    Blockout,
+    /// Playable with its mechanics, being tuned through playtests.
+    Playtesting,
+    /// Art and audio pass done.
+    Polished,
+    /// Shipped or locked.
+    Final,
+}
+
+impl LevelStatus {
+    pub const ALL: [Self; 5] = [
+        Self::Briefed,
+        Self::Blockout,
+        Self::Playtesting,
+        Self::Polished,
+        Self::Final,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Briefed => "briefed",
+            Self::Blockout => "blockout",
+            Self::Playtesting => "playtesting",
+            Self::Polished => "polished",
+            Self::Final => "final",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        Self::ALL.into_iter().find(|status| status.name() == name)
+    }
+}
+
+/// Difficulty of one section of a level.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DifficultySection {
+    pub section: String,
+    /// From `1` to [`MAX_DIFFICULTY`].
+    pub difficulty: u32,
+    /// What makes the section as hard as it is.
+    #[serde(default)]
+    pub note: String,
+}
+
+/// A level of the game with its brief.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Level {
+    pub name: String,
+    /// What the level must achieve, as given when it was briefed.
+    pub goals: Vec<String>,
+    pub layout: String,
+    pub difficulty_curve: Vec<DifficultySection>,
+    /// Names of the planned features the level exercises.
+    pub mechanics: Vec<String>,
+    /// Mechanics the level needs that are not planned features yet.
+    #[serde(default)]
+    pub missing_mechanics: Vec<String>,
+    #[serde(default)]
+    pub required_assets: Vec<AssetRequirement>,
+    #[serde(default)]
+    pub status: LevelStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Level {
+    /// Renders the brief as handed to level designers.
+    pub fn brief(&self) -> String {
+        let mut brief = format!("# {} ({})\n", self.name, self.status.name());
+
+        if !self.goals.is_empty() {
+            brief.push_str("\nGoals:\n");
+            for goal in &self.goals {
+                brief.push_str(&format!("- {}\n", goal));
+            }
+        }
+
+        brief.push_str(&format!("\nLayout:\n{}\n", self.layout.trim()));
+
+        if !self.difficulty_curve.is_empty() {
+            brief.push_str("\nDifficulty curve:\n");
+            for section in &self.difficulty_curve {
+                brief.push_str(&format!(
+                    "- {} [{}] {}/{}",
+                    section.section,
+                    "#".repeat(section.difficulty as usize),
+                    section.difficulty,
+                    MAX_DIFFICULTY
+                ));
+                if !section.note.is_empty() {
+                    brief.push_str(&format!(": {}", section.note));
+                }
+                brief.push('\n');
+            }
+        }
+
+        if !self.mechanics.is_empty() {
+            brief.push_str(&format!("\nMechanics: {}\n", self.mechanics.join(", ")));
+        }
+        if !self.missing_mechanics.is_empty() {
+            brief.push_str(&format!(
+                "Mechanics not planned yet: {}\n",
+                self.missing_mechanics.join(", ")
+            ));
+        }
+
+        if !self.required_assets.is_empty() {
+            brief.push_str("\nRequired assets:\n");
+            for asset in &self.required_assets {
+                brief.push_str(&format!("- [{}] {}", asset.kind.name(), asset.name));
+                if !asset.description.is_empty() {
+                    brief.push_str(&format!(": {}", asset.description));
+                }
+                brief.push('\n');
+            }
+        }
+
+        brief.trim_end().to_string()
+    }
+}
+
+/// Asks the LLM for the brief of the level `name` with `goals`. Mechanics
+/// that match a planned feature are stored by the feature's name; the others
+/// are reported as missing. The level is created `now`.
+pub async fn brief(
+    llm_client: &DesignerLlmClient,
+    session: &SessionState,
+    name: &str,
+    goals: &[String],
+    now: DateTime<Utc>,
+) -> Result<Level> {
+    let mut prompt = memory::design_context(session);
+
+    if !session.planned_features.is_empty() {
+        prompt.push_str("Planned features (the game's mechanics):\n");
+        for feature in &session.planned_features {
+            prompt.push_str(&format!("- {}: {}\n", feature.name, feature.description));
+        }
+        prompt.push('\n');
+    }
+
+    let others = session
+        .levels
+        .iter()
+        .filter(|level| !level.name.eq_ignore_ascii_case(name))
+        .collect::<Vec<_>>();
+    if !others.is_empty() {
+        prompt.push_str("Other levels:\n");
+        for level in others {
+            prompt.push_str(&format!("- {}: {}\n", level.name, level.goals.join("; ")));
+        }
+        prompt.push('\n');
+    }
+
+    prompt.push_str(&format!("Level: {}\nGoals:\n", name));
+    for goal in goals {
+        prompt.push_str(&format!("- {}\n", goal));
+    }
+    prompt.push('\n');
+
+    prompt.push_str(&format!(
+        "Write a structured brief for this level. Describe its layout (spaces, routes and \
+         landmarks in the order the player meets them), its difficulty curve as a sequence \
+         of sections rated from 1 to {}, the mechanics it requires, using the exact names of \
+         planned features where they exist, and the art and audio assets it needs. \
+         Format your response as a JSON object with 'layout' (string), 'difficulty_curve' \
+         (array of objects with 'section', 'difficulty' and 'note'), 'mechanics' (array of \
+         names) and 'required_assets' (array of objects with 'kind' \
+         (sprite|animation|sfx|music|shader|model|font|other), 'name' and 'description') \
+         fields. Only return the JSON, nothing else.",
+        MAX_DIFFICULTY
+    ));
+
+    let messages = vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: session.system_prompt(prompts::LEVEL_BRIEF),
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content: prompt,
+        },
+    ];
+
+    let response = llm_client.call_llm(messages).await?;
+
+    #[derive(Deserialize)]
+    struct Brief {
+        layout: String,
+        #[serde(default)]
+        difficulty_curve: Vec<DifficultySection>,
+        #[serde(default)]
+        mechanics: Vec<String>,
+        #[serde(default)]
+        required_assets: Vec<AssetRequirement>,
+    }
+
+    let json = extract_json(&response)
+        .ok_or_else(|| anyhow::anyhow!("LLM response was not valid JSON: {}", response))?;
+    let brief: Brief = serde_json::from_str(json)?;
+
+    let mut mechanics = Vec::new();
+    let mut missing_mechanics = Vec::new();
+    for mechanic in brief.mechanics {
+        match session
+            .planned_features
+            .iter()
+            .find(|feature| feature.name.eq_ignore_ascii_case(mechanic.trim()))
+        {
+            Some(feature) => mechanics.push(feature.name.clone()),
+            None => missing_mechanics.push(mechanic.trim().to_string()),
+        }
+    }
+
+    Ok(Level {
+        name: name.to_string(),
+        goals: goals.to_vec(),
+        layout: brief.layout,
+        difficulty_curve: brief
+            .difficulty_curve
+            .into_iter()
+            .map(|section| DifficultySection {
+                difficulty: section.difficulty.clamp(1, MAX_DIFFICULTY),
+                ..section
+            })
+            .collect(),
+        mechanics,
+        missing_mechanics,
+        required_assets: brief.required_assets,
+        status: LevelStatus::Briefed,
+        created_at: now,
+        updated_at: now,
+    })
+}
@@ -0,0 +1,82 @@
+//! Checks the planned and implemented features of a session against its
+//! design pillars and reports contradictions.
+
+use crate::game_design::{
+    DesignerLlmClient,
+    designer_llm::{ChatMessage, extract_json},
+    memory, prompts,
+    state::SessionState,
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// How strongly a finding undermines the intended experience.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    High,
+    Medium,
+    Low,
+}
+
+/// A contradiction between the feature set and a design pillar.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintFinding {
+    pub severity: Severity,
+    /// The pillar or design goal that is contradicted.
+    pub pillar: String,
+    /// Names of the features involved.
+    #[serde(default)]
+    pub features: Vec<String>,
+    pub description: String,
+    pub suggestion: String,
+}
+
+/// Asks the LLM to evaluate the session's features against its design
+/// pillars. Findings are sorted by severity, most severe first.
+pub async fn lint(
+    llm_client: &DesignerLlmClient,
+    session: &SessionState,
+) -> Result<Vec<LintFinding>> {
+    let mut prompt = memory::design_context(session);
+
+    prompt.push_str("Planned and implemented features:\n");
+    for feature in &session.planned_features {
+        prompt.push_str(&format!(
+            "- {} ({:?}): {}\n",
+            feature.name, feature.status, feature.description
+        ));
+    }
+    prompt.push('\n');
+
+    prompt.push_str(
+        "Identify the design pillars of this game (its stated core goals and binding \
+         decisions) and check the feature set against them. Flag every contradiction \
+         between features and pillars, or between features that pull the design in opposite \
+         directions (e.g. a fast-paced action pillar vs. a turn-based inventory feature). \
+         Format your response as a JSON array of objects with 'severity' ('high', 'medium' \
+         or 'low'), 'pillar', 'features' (array of feature names), 'description' and \
+         'suggestion' (a concrete resolution) fields, or [] if the design is consistent. \
+         Only return the JSON, nothing else.",
+    );
+
+    let messages = vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: session.system_prompt(prompts::DESIGN_LINT),
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content: prompt,
+        },
+    ];
+
+    let response = llm_client.call_llm(messages).await?;
+
+    let json = extract_json(&response)
+        .ok_or_else(|| anyhow::anyhow!("LLM response was not valid JSON: {}", response))?;
+    let mut findings: Vec<LintFinding> = serde_json::from_str(json)?;
+    findings.sort_by_key(|finding| finding.severity);
+
+    Ok(findings)
+}
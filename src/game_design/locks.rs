@@ -0,0 +1,106 @@
+//! Locks of the sessions that record how long calls wait for them, so that
+//! contention between connections shows up in the metrics and in
+//! `gamedesignerd bench`.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+/// Wait times of the session locks of one service.
+#[derive(Debug, Default)]
+pub struct LockStats {
+    acquisitions: AtomicU64,
+    contended: AtomicU64,
+    wait_nanos: AtomicU64,
+    max_wait_nanos: AtomicU64,
+}
+
+/// Totals of [`LockStats`] at one point in time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LockSnapshot {
+    /// Times a lock was taken.
+    pub acquisitions: u64,
+    /// Times a lock was held by another call and had to be waited for.
+    pub contended: u64,
+    /// Total time spent waiting.
+    pub wait: Duration,
+    /// Longest single wait.
+    pub max_wait: Duration,
+}
+
+impl LockStats {
+    fn record(&self, contended: bool, wait: Duration) {
+        let nanos = wait.as_nanos() as u64;
+
+        self.acquisitions.fetch_add(1, Ordering::Relaxed);
+        if contended {
+            self.contended.fetch_add(1, Ordering::Relaxed);
+        }
+        self.wait_nanos.fetch_add(nanos, Ordering::Relaxed);
+        self.max_wait_nanos.fetch_max(nanos, Ordering::Relaxed);
+
+        metrics::histogram!("gamedesignerd_session_lock_wait_seconds").record(wait.as_secs_f64());
+    }
+
+    pub fn snapshot(&self) -> LockSnapshot {
+        LockSnapshot {
+            acquisitions: self.acquisitions.load(Ordering::Relaxed),
+            contended: self.contended.load(Ordering::Relaxed),
+            wait: Duration::from_nanos(self.wait_nanos.load(Ordering::Relaxed)),
+            max_wait: Duration::from_nanos(self.max_wait_nanos.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// Locks of the sessions of one session manager.
+///
+/// A change of a session holds its lock from reading the session until the
+/// changed session is written, LLM calls in between included, so that
+/// changes of the same session wait for each other instead of overwriting
+/// each other. Calls on other sessions and reads don't wait.
+pub struct SessionLocks {
+    locks: std::sync::Mutex<HashMap<String, Arc<Mutex<()>>>>,
+    stats: Arc<LockStats>,
+}
+
+impl SessionLocks {
+    pub fn new(stats: Arc<LockStats>) -> Self {
+        Self {
+            locks: Default::default(),
+            stats,
+        }
+    }
+
+    /// Wait times of the locks, shared with the session managers of the
+    /// projects.
+    pub fn stats(&self) -> &Arc<LockStats> {
+        &self.stats
+    }
+
+    /// Locks the session, waiting for the call holding it if any.
+    pub async fn lock(&self, session_id: &str) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap();
+            // Locks nobody holds or waits for are dropped
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+            locks.entry(session_id.to_string()).or_default().clone()
+        };
+
+        if let Ok(guard) = lock.clone().try_lock_owned() {
+            self.stats.record(false, Duration::ZERO);
+            return guard;
+        }
+
+        let started_at = Instant::now();
+        let guard = lock.lock_owned().await;
+        self.stats.record(true, started_at.elapsed());
+
+        guard
+    }
+}
@@ -0,0 +1,103 @@
+//! Long-term designer memory: durable design decisions extracted from reviews
+//! and injected into every subsequent prompt.
+
+use crate::game_design::{
+    DesignerLlmClient, conventions,
+    designer_llm::{ChatMessage, extract_json},
+    narrative, prompts,
+    state::{DesignDecision, SessionState},
+};
+use anyhow::Result;
+
+/// Renders the design document and the established decisions as the common
+/// prefix of every designer prompt.
+pub fn design_context(session: &SessionState) -> String {
+    let mut context = format!(
+        "Based on this game design document:\n{}\n\n",
+        session.initial_description
+    );
+
+    if !session.design_memory.is_empty() {
+        context.push_str("Established design decisions (treat these as binding):\n");
+        for decision in &session.design_memory {
+            context.push_str(&format!("- {}\n", decision.decision));
+        }
+        context.push('\n');
+    }
+
+    context.push_str(&conventions::section(&session.conventions));
+
+    if !session.narrative.is_empty() {
+        context.push_str(&format!(
+            "Story outline:\n{}\n\n",
+            narrative::outline(&session.narrative)
+        ));
+    }
+
+    context
+}
+
+/// Asks the LLM for durable decisions implied by a finished review exchange.
+pub async fn extract_decisions(
+    llm_client: &DesignerLlmClient,
+    session: &SessionState,
+    feature_name: &str,
+    exchange: &str,
+) -> Result<Vec<String>> {
+    let mut prompt = design_context(session);
+
+    prompt.push_str(&format!(
+        "Review exchange for feature '{}':\n{}\n\n",
+        feature_name, exchange
+    ));
+    prompt.push_str(
+        "List any new durable design or technical decisions established by this exchange \
+         that future features must respect (e.g. \"we use Bevy 0.16\", \"pixel-art style\", \
+         \"no RNG in combat\"). Skip decisions already listed above and anything specific \
+         to this feature only. Respond with a JSON array of short strings, or [] if there \
+         are none. Only return the JSON, nothing else.",
+    );
+
+    let messages = vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: prompts::localized(
+                prompts::system_prompt(prompts::MEMORY_EXTRACT),
+                session.language.as_deref(),
+            ),
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content: prompt,
+        },
+    ];
+
+    let response = llm_client.call_llm(messages).await?;
+
+    let json = extract_json(&response).ok_or_else(|| {
+        anyhow::anyhow!("LLM response did not contain a JSON array: {}", response)
+    })?;
+
+    Ok(serde_json::from_str(json)?)
+}
+
+/// Adds decisions that are not already remembered, ignoring case.
+pub fn remember(session: &mut SessionState, decisions: Vec<String>, source_feature: &str) {
+    for decision in decisions {
+        let decision = decision.trim();
+
+        if decision.is_empty()
+            || session
+                .design_memory
+                .iter()
+                .any(|known| known.decision.eq_ignore_ascii_case(decision))
+        {
+            continue;
+        }
+
+        session.design_memory.push(DesignDecision {
+            decision: decision.to_string(),
+            source_feature: Some(source_feature.to_string()),
+        });
+    }
+}
@@ -0,0 +1,282 @@
+//! Offline LLM provider returning deterministic canned responses, so the
+//! tool workflow can be exercised without an API key, e.g. in CI.
+//!
+//! Each prompt template has a fixture in the crate's `fixtures/mock-llm/`
+//! directory, embedded at compile time. A file named `<prompt>.txt` in the
+//! directory given by `GAMEDESIGNER_MOCK_FIXTURES` (default
+//! `.gamedesignerd/mock-llm/`) replaces the embedded fixture. The placeholder
+//! `{{n}}` is replaced with the number of features already planned plus one,
+//! so that consecutive `nextFeature` calls yield distinct features. Sessions
+//! with a language prefer an override named `<prompt>.<language>.txt`, e.g.
+//! `feature_review.german.txt`, so localized workflows can be exercised too.
+
+use crate::game_design::{designer_llm::ChatMessage, prompts, session::persistence_dir};
+use std::{env, fs, path::PathBuf, time::Duration};
+
+/// Embedded fixtures by prompt name.
+const FIXTURES: &[(&str, &str)] = &[
+    (
+        prompts::DESIGN_NEW,
+        include_str!("../../fixtures/mock-llm/design_new.txt"),
+    ),
+    (
+        prompts::NEXT_FEATURE,
+        include_str!("../../fixtures/mock-llm/next_feature.txt"),
+    ),
+    (
+        prompts::SPEC_CHECK,
+        include_str!("../../fixtures/mock-llm/spec_check.txt"),
+    ),
+    (
+        prompts::FEATURE_CANDIDATES,
+        include_str!("../../fixtures/mock-llm/feature_candidates.txt"),
+    ),
+    (
+        prompts::FEATURE_REVIEW,
+        include_str!("../../fixtures/mock-llm/feature_review.txt"),
+    ),
+    (
+        prompts::FEATURE_REVIEW_QUICK,
+        include_str!("../../fixtures/mock-llm/feature_review.txt"),
+    ),
+    (
+        prompts::FEATURE_REVIEW_THOROUGH,
+        include_str!("../../fixtures/mock-llm/feature_review.txt"),
+    ),
+    (
+        prompts::REVIEW_PANEL_DESIGN,
+        include_str!("../../fixtures/mock-llm/feature_review.txt"),
+    ),
+    (
+        prompts::REVIEW_PANEL_TECHNICAL,
+        include_str!("../../fixtures/mock-llm/feature_review.txt"),
+    ),
+    (
+        prompts::REVIEW_PANEL_UX,
+        include_str!("../../fixtures/mock-llm/feature_review.txt"),
+    ),
+    (
+        prompts::REVIEW_CONSENSUS,
+        include_str!("../../fixtures/mock-llm/feature_review.txt"),
+    ),
+    (
+        prompts::REVIEW_REPLY,
+        include_str!("../../fixtures/mock-llm/review_reply.txt"),
+    ),
+    (
+        prompts::FEATURE_ASK,
+        include_str!("../../fixtures/mock-llm/feature_ask.txt"),
+    ),
+    (
+        prompts::MEMORY_EXTRACT,
+        include_str!("../../fixtures/mock-llm/memory_extract.txt"),
+    ),
+    (
+        prompts::REPORT_DIGEST,
+        include_str!("../../fixtures/mock-llm/report_digest.txt"),
+    ),
+    (
+        prompts::INPUT_SUMMARY,
+        include_str!("../../fixtures/mock-llm/input_summary.txt"),
+    ),
+    (
+        prompts::BACKLOG_IMPORT,
+        include_str!("../../fixtures/mock-llm/backlog_import.txt"),
+    ),
+    (
+        prompts::DESIGN_IMPORT,
+        include_str!("../../fixtures/mock-llm/design_import.txt"),
+    ),
+    (
+        prompts::DESIGN_EDIT_REVIEW,
+        include_str!("../../fixtures/mock-llm/design_edit_review.txt"),
+    ),
+    (
+        prompts::DOCS_SUMMARIZER,
+        include_str!("../../fixtures/mock-llm/docs_summarizer.txt"),
+    ),
+    (
+        prompts::DESIGN_LINT,
+        include_str!("../../fixtures/mock-llm/design_lint.txt"),
+    ),
+    (
+        prompts::DESIGN_BRAINSTORM,
+        include_str!("../../fixtures/mock-llm/design_brainstorm.txt"),
+    ),
+    (
+        prompts::STORY_OUTLINE,
+        include_str!("../../fixtures/mock-llm/story_outline.txt"),
+    ),
+    (
+        prompts::LEVEL_BRIEF,
+        include_str!("../../fixtures/mock-llm/level_brief.txt"),
+    ),
+    (
+        prompts::ECONOMY_SIMULATE,
+        include_str!("../../fixtures/mock-llm/economy_simulate.txt"),
+    ),
+    (
+        prompts::MONETIZATION_REVIEW,
+        include_str!("../../fixtures/mock-llm/monetization_review.txt"),
+    ),
+    (
+        prompts::TEST_PLAN,
+        include_str!("../../fixtures/mock-llm/test_plan.txt"),
+    ),
+    (
+        prompts::UI_CRITIQUE,
+        include_str!("../../fixtures/mock-llm/ui_critique.txt"),
+    ),
+    (
+        prompts::SESSION_REUSE,
+        include_str!("../../fixtures/mock-llm/session_reuse.txt"),
+    ),
+    (
+        prompts::DEVLOG,
+        include_str!("../../fixtures/mock-llm/devlog.txt"),
+    ),
+    (
+        prompts::DESIGN_RETRO,
+        include_str!("../../fixtures/mock-llm/design_retro.txt"),
+    ),
+    (
+        prompts::COMMIT_MESSAGE,
+        include_str!("../../fixtures/mock-llm/commit_message.txt"),
+    ),
+    (
+        prompts::IMPLEMENTATION_HINTS,
+        include_str!("../../fixtures/mock-llm/implementation_hints.txt"),
+    ),
+    (
+        prompts::API_REFERENCES,
+        include_str!("../../fixtures/mock-llm/api_references.txt"),
+    ),
+];
+
+/// Response for prompts without a fixture.
+const FALLBACK_RESPONSE: &str = "OK";
+
+/// Canned-response provider used by `--llm-provider mock`.
+#[derive(Debug, Clone)]
+pub struct MockLlmProvider {
+    fixtures_dir: PathBuf,
+    /// Time each response takes, to simulate a real provider under load.
+    latency: Duration,
+}
+
+impl Default for MockLlmProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockLlmProvider {
+    /// Creates a provider reading overrides from `GAMEDESIGNER_MOCK_FIXTURES`
+    /// and answering after `GAMEDESIGNER_MOCK_LATENCY_MS` (default `0`).
+    pub fn new() -> Self {
+        let fixtures_dir = env::var("GAMEDESIGNER_MOCK_FIXTURES")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| persistence_dir().join("mock-llm"));
+        let latency = env::var("GAMEDESIGNER_MOCK_LATENCY_MS")
+            .ok()
+            .and_then(|ms| ms.parse().ok())
+            .map_or(Duration::ZERO, Duration::from_millis);
+
+        Self::with_fixtures(fixtures_dir).with_latency(latency)
+    }
+
+    /// Creates a provider reading overrides from `fixtures_dir`.
+    pub fn with_fixtures(fixtures_dir: PathBuf) -> Self {
+        Self {
+            fixtures_dir,
+            latency: Duration::ZERO,
+        }
+    }
+
+    /// Makes every response take `latency`.
+    pub fn with_latency(self, latency: Duration) -> Self {
+        Self { latency, ..self }
+    }
+
+    /// Time each response takes.
+    pub fn latency(&self) -> Duration {
+        self.latency
+    }
+
+    /// Returns the canned response for the conversation. The prompt is
+    /// identified by the system message it starts with.
+    pub fn respond(&self, messages: &[ChatMessage]) -> String {
+        let system = messages
+            .iter()
+            .find(|message| message.role == "system")
+            .map_or("", |message| message.content.as_str());
+        let user = messages
+            .iter()
+            .rev()
+            .find(|message| message.role == "user")
+            .map_or("", |message| message.content.as_str());
+
+        let Some(name) = FIXTURES
+            .iter()
+            .map(|(name, _)| *name)
+            .find(|name| system.starts_with(&prompts::system_prompt(name)))
+        else {
+            tracing::debug!("No mock fixture matches the system prompt");
+            return FALLBACK_RESPONSE.to_string();
+        };
+
+        tracing::debug!("Answering '{}' prompt with mock fixture", name);
+
+        let localized = prompts::language_of(system)
+            .map(|language| format!("{}.{}", name, language.trim().to_lowercase()))
+            .and_then(|name| self.fixture_override(&name));
+
+        localized
+            .unwrap_or_else(|| self.fixture(name))
+            .trim()
+            .replace("{{n}}", &(planned_features(user) + 1).to_string())
+    }
+
+    /// Loads the fixture of the named prompt, preferring an override.
+    fn fixture(&self, name: &str) -> String {
+        if let Some(fixture) = self.fixture_override(name) {
+            return fixture;
+        }
+
+        FIXTURES
+            .iter()
+            .find(|(fixture_name, _)| *fixture_name == name)
+            .map_or(FALLBACK_RESPONSE, |(_, fixture)| *fixture)
+            .to_string()
+    }
+
+    /// Reads the override `<name>.txt` from the fixtures directory.
+    fn fixture_override(&self, name: &str) -> Option<String> {
+        let override_path = self.fixtures_dir.join(format!("{}.txt", name));
+
+        if !override_path.exists() {
+            return None;
+        }
+
+        fs::read_to_string(&override_path)
+            .inspect_err(|e| {
+                tracing::warn!(
+                    "Failed to read mock fixture {}: {}",
+                    override_path.display(),
+                    e
+                )
+            })
+            .ok()
+    }
+}
+
+/// Counts the entries of the "Already planned features" list of a prompt.
+fn planned_features(prompt: &str) -> usize {
+    let Some((_, list)) = prompt.split_once("Already planned features:\n") else {
+        return 0;
+    };
+
+    list.lines()
+        .take_while(|line| line.starts_with("- "))
+        .count()
+}
@@ -3,13 +3,179 @@
 /// Manages individual design sessions, including state loading/saving.
 pub mod session;
 
+/// Storage backends of session files: the file system or memory.
+pub mod storage;
+
+/// Sessions kept in memory, evicting the least recently used ones.
+pub mod residency;
+
+/// Locks of the sessions that record how long calls wait for them.
+pub mod locks;
+
+/// Source of the current time for session timestamps.
+pub mod clock;
+
+/// Project namespaces that keep sessions of different projects apart.
+pub mod project;
+
 /// Handles communication with the underlying Game Designer LLM API.
 pub mod designer_llm;
 
+/// Project configuration file with runtime reload.
+pub mod config;
+
+/// Content-addressed cache of LLM responses.
+pub mod cache;
+
+/// Offline LLM provider with deterministic canned responses.
+pub mod mock_llm;
+
+/// Recording and replay of LLM interactions.
+pub mod recording;
+
 /// Defines data structures for session state, features, chat messages, etc.
 pub mod state;
 
+/// Defines the state-change events emitted by design sessions.
+pub mod events;
+
+/// Pre-change snapshots of sessions for undo.
+pub mod snapshots;
+
+/// Delivers session events to configured outbound webhooks.
+pub mod webhooks;
+
+/// HMAC-SHA256 signatures of webhook deliveries and human approvals.
+pub mod signature;
+
+/// Long-term designer memory of durable design decisions.
+pub mod memory;
+
+/// Keeps designer prompts within the model's context window.
+pub mod context;
+
+/// Fetches open issues from external issue trackers (GitHub, Linear).
+pub mod trackers;
+
+/// Mirrors planned features to GitHub Issues or a Trello board.
+pub mod board;
+
+/// System prompt templates with per-project overrides.
+pub mod prompts;
+
+/// Designer persona presets.
+pub mod persona;
+
+/// Target game engine and engine-specific implementation hints.
+pub mod engine;
+
+/// Cold-start onboarding interview that produces the initial design document.
+pub mod interview;
+
+/// Genre templates with pillars, a starter backlog and review criteria.
+pub mod templates;
+
+/// Art and audio requirements of planned features.
+pub mod assets;
+
+/// Markdown export of the game design document.
+pub mod export;
+
+/// Existing Markdown design documents imported into new sessions.
+pub mod document;
+
+/// Confinement of client-chosen file paths to the repository and data
+/// directory.
+pub mod paths;
+
+/// Structured comparison of two design sessions.
+pub mod diff;
+
+/// Design-pillar consistency checks of the feature set.
+pub mod lint;
+
+/// Brainstorming of ideas scored against the design pillars.
+pub mod brainstorm;
+
+/// Characters, acts, beats and dialogue style of the story.
+pub mod narrative;
+
+/// Levels with structured briefs and their own production status.
+pub mod level;
+
+/// Resources, sources, sinks and progressions of the game's economy.
+pub mod economy;
+
+/// Review of monetization features against policy rules and regional laws.
+pub mod monetization;
+
+/// Technical debt accepted in reviews and its paydown.
+pub mod debt;
+
+/// Playtest steps and automated test cases of reviewed features.
+pub mod testplan;
+
+/// Project conventions injected into every feature and review prompt.
+pub mod conventions;
+
+/// Checks of new feature specifications for duplicates and contradictions.
+pub mod spec_check;
+
+/// Rejected features and the detection of proposals repeating them.
+pub mod graveyard;
+
+/// Acceptance criteria of features and their per-criterion review.
+pub mod criteria;
+
+/// Review depths from a quick sign-off to a thorough review.
+pub mod review_depth;
+
+/// Panels of design, technical and UX reviewers reaching a consensus.
+pub mod panel;
+
+/// Screenshots and captures attached to feature reviews.
+pub mod attachments;
+
+/// UI/UX critiques of screenshots by a vision-capable model.
+pub mod critique;
+
+/// Limit of follow-up rounds of a review and its escalation.
+pub mod followups;
+
+/// Human approval required before features count as reviewed.
+pub mod approval;
+
+/// Stand-up status reports built from the event log.
+pub mod status;
+
+/// Retrospectives of finished sessions built from the event log.
+pub mod retro;
+
+/// Retrieval of features and decisions across sessions.
+pub mod search;
+
+/// Embedding-based semantic search over the history of a session.
+pub mod embeddings;
+
+/// Effort and risk estimates and the effort budget.
+pub mod scope;
+
+/// Reads diffs from the game repository for reviews.
+pub mod git;
+
+/// Startup validation of stored session files.
+pub mod integrity;
+
+/// Environment checks of `gamedesignerd doctor`.
+pub mod diagnostics;
+
+/// Redaction of secrets in transcripts and LLM requests.
+pub mod redact;
+
 // Re-export key items for easier access
 pub use designer_llm::DesignerLlmClient;
-pub use session::SessionManager;
+pub use persona::Persona;
+pub use session::{
+    DesignEditOutcome, ReuseOutcome, ReviewSubmission, SessionManager, SessionSetup, UndoOutcome,
+};
 pub use state::SessionState;
@@ -0,0 +1,243 @@
+//! Review of the game's monetization against policy rules.
+//!
+//! The designer picks out the features that make money, such as shops,
+//! premium currencies, randomized rewards or subscriptions, and checks them
+//! against the built-in rules, the rules of the `[monetization]`
+//! configuration and the laws of the target regions. The resulting risk
+//! report is attached to the session.
+
+use crate::game_design::{
+    DesignerLlmClient,
+    config::MonetizationConfig,
+    designer_llm::{ChatMessage, extract_json},
+    lint::Severity,
+    memory, prompts,
+    state::SessionState,
+};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Rules every review applies unless the configuration turns them off.
+pub const DEFAULT_RULES: &[&str] = &[
+    "No paid randomized rewards (loot boxes) where the audience may include minors.",
+    "Flag paid randomized rewards in target regions that regulate or ban them as gambling.",
+    "Disclose the odds of every randomized reward.",
+    "Show prices in real money; premium currency bundles must not hide the cost of items.",
+    "No dark patterns: artificial urgency, designed-in frustration sold back as a skip, or \
+     nudges aimed at vulnerable players.",
+];
+
+/// The rules and market a review checks against.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonetizationPolicy {
+    pub rules: Vec<String>,
+    /// Markets the game ships in, checked for their gambling and consumer
+    /// laws.
+    pub regions: Vec<String>,
+    /// Whether players may be minors.
+    pub minors: bool,
+}
+
+impl MonetizationPolicy {
+    /// Builds the policy of the `[monetization]` configuration: the built-in
+    /// rules, unless turned off, followed by the configured ones. Players are
+    /// assumed to possibly be minors unless configured otherwise.
+    pub fn from_config(config: &MonetizationConfig) -> Self {
+        let rules = config
+            .default_rules
+            .unwrap_or(true)
+            .then_some(DEFAULT_RULES)
+            .into_iter()
+            .flatten()
+            .map(|rule| rule.to_string())
+            .chain(config.rules.iter().cloned())
+            .collect();
+
+        Self {
+            rules,
+            regions: config.regions.clone(),
+            minors: config.audience_includes_minors.unwrap_or(true),
+        }
+    }
+}
+
+/// A monetization feature that breaks or risks breaking a rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyFinding {
+    pub severity: Severity,
+    pub feature: String,
+    /// The rule or law at stake.
+    pub rule: String,
+    /// Region the finding is specific to.
+    #[serde(default)]
+    pub region: Option<String>,
+    pub issue: String,
+    /// A concrete change that removes or reduces the risk.
+    pub mitigation: String,
+}
+
+/// Structured risk report of a monetization review.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonetizationReport {
+    /// Features the designer identified as monetization.
+    pub monetization_features: Vec<String>,
+    /// Most severe first.
+    pub findings: Vec<PolicyFinding>,
+    pub summary: String,
+    pub policy: MonetizationPolicy,
+    pub reviewed_at: DateTime<Utc>,
+}
+
+impl MonetizationReport {
+    /// Renders the report as text.
+    pub fn render(&self) -> String {
+        let mut text = format!(
+            "Reviewed {} against {} rule(s){}{}.\n\n{}\n",
+            self.reviewed_at.format("%Y-%m-%d %H:%M"),
+            self.policy.rules.len(),
+            if self.policy.regions.is_empty() {
+                String::new()
+            } else {
+                format!(" for {}", self.policy.regions.join(", "))
+            },
+            if self.policy.minors {
+                ", audience may include minors"
+            } else {
+                ""
+            },
+            self.summary.trim()
+        );
+
+        if self.monetization_features.is_empty() {
+            text.push_str("\nNo monetization features found.\n");
+        } else {
+            text.push_str(&format!(
+                "\nMonetization features: {}\n",
+                self.monetization_features.join(", ")
+            ));
+        }
+
+        if !self.findings.is_empty() {
+            text.push_str("\nFindings:\n");
+            for finding in &self.findings {
+                text.push_str(&format!("- [{:?}] {}", finding.severity, finding.feature));
+                if let Some(region) = &finding.region {
+                    text.push_str(&format!(" ({})", region));
+                }
+                text.push_str(&format!(
+                    ": {}\n  Rule: {}\n  Mitigation: {}\n",
+                    finding.issue, finding.rule, finding.mitigation
+                ));
+            }
+        }
+
+        text.trim_end().to_string()
+    }
+}
+
+/// Asks the LLM to review the session's monetization against `policy`.
+/// Findings are sorted by severity, most severe first. The report is dated
+/// `now`.
+pub async fn review(
+    llm_client: &DesignerLlmClient,
+    session: &SessionState,
+    policy: MonetizationPolicy,
+    now: DateTime<Utc>,
+) -> Result<MonetizationReport> {
+    let mut prompt = memory::design_context(session);
+
+    if !session.planned_features.is_empty() {
+        prompt.push_str("Planned and implemented features:\n");
+        for feature in &session.planned_features {
+            prompt.push_str(&format!("- {}: {}\n", feature.name, feature.description));
+        }
+        prompt.push('\n');
+    }
+
+    prompt.push_str("Policy rules:\n");
+    for rule in &policy.rules {
+        prompt.push_str(&format!("- {}\n", rule));
+    }
+    prompt.push('\n');
+
+    if !policy.regions.is_empty() {
+        prompt.push_str(&format!("Target regions: {}\n", policy.regions.join(", ")));
+    }
+    prompt.push_str(if policy.minors {
+        "The audience may include minors.\n\n"
+    } else {
+        "The audience is adults only.\n\n"
+    });
+
+    prompt.push_str(
+        "Identify the features that monetize the game (shops, premium currencies, randomized \
+         rewards, battle passes, subscriptions, ads, paid skips) and review them against the \
+         policy rules and the gambling and consumer protection laws of the target regions. \
+         Flag every rule a feature breaks or risks breaking, naming the region where the risk \
+         is specific to one. Format your response as a JSON object with a 'summary' string, a \
+         'monetization_features' array of feature names and a 'findings' array of objects \
+         with 'severity' ('high', 'medium' or 'low'), 'feature', 'rule', 'region' (or null), \
+         'issue' and 'mitigation' (a concrete change) fields. Only return the JSON, nothing \
+         else.",
+    );
+
+    let messages = vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: session.system_prompt(prompts::MONETIZATION_REVIEW),
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content: prompt,
+        },
+    ];
+
+    let response = llm_client.call_llm(messages).await?;
+
+    #[derive(Deserialize)]
+    struct Response {
+        #[serde(default)]
+        summary: String,
+        #[serde(default)]
+        monetization_features: Vec<String>,
+        #[serde(default)]
+        findings: Vec<PolicyFinding>,
+    }
+
+    let json = extract_json(&response)
+        .ok_or_else(|| anyhow::anyhow!("LLM response was not valid JSON: {}", response))?;
+    let response: Response = serde_json::from_str(json)?;
+
+    // Findings name features as planned where they are
+    let feature_name = |name: String| {
+        session
+            .planned_features
+            .iter()
+            .find(|feature| feature.name.eq_ignore_ascii_case(name.trim()))
+            .map(|feature| feature.name.clone())
+            .unwrap_or(name)
+    };
+
+    let mut findings = response
+        .findings
+        .into_iter()
+        .map(|finding| PolicyFinding {
+            feature: feature_name(finding.feature),
+            ..finding
+        })
+        .collect::<Vec<_>>();
+    findings.sort_by_key(|finding| finding.severity);
+
+    Ok(MonetizationReport {
+        monetization_features: response
+            .monetization_features
+            .into_iter()
+            .map(feature_name)
+            .collect(),
+        findings,
+        summary: response.summary.trim().to_string(),
+        policy,
+        reviewed_at: now,
+    })
+}
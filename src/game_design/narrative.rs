@@ -0,0 +1,157 @@
+//! Narrative design of story-driven games: characters, acts and their beats,
+//! and the dialogue style guide.
+//!
+//! The story is designed in the same session as the mechanics. Its outline
+//! is part of every designer prompt, so that features are proposed and
+//! reviewed with the story in mind, and beats can name the feature through
+//! which the player experiences them.
+
+use crate::game_design::{
+    DesignerLlmClient,
+    designer_llm::{ChatMessage, extract_json},
+    memory, prompts,
+    state::{Character, Narrative, SessionState},
+};
+use anyhow::Result;
+
+/// Renders the story as an outline: the style guide, the cast and the acts
+/// with their numbered beats.
+pub fn outline(narrative: &Narrative) -> String {
+    let mut outline = String::new();
+
+    if let Some(style_guide) = &narrative.style_guide {
+        outline.push_str(&format!("Dialogue style guide: {}\n\n", style_guide.trim()));
+    }
+
+    if !narrative.characters.is_empty() {
+        outline.push_str("Characters:\n");
+        for character in &narrative.characters {
+            outline.push_str(&format!("- {}", character.name));
+            if let Some(role) = &character.role {
+                outline.push_str(&format!(" ({})", role));
+            }
+            if let Some(description) = &character.description {
+                outline.push_str(&format!(": {}", description));
+            }
+            outline.push('\n');
+        }
+        outline.push('\n');
+    }
+
+    for (index, act) in narrative.acts.iter().enumerate() {
+        outline.push_str(&format!("Act {}: {}\n", index + 1, act.name));
+        if !act.summary.trim().is_empty() {
+            outline.push_str(&format!("{}\n", act.summary.trim()));
+        }
+
+        for (number, beat) in act.beats.iter().enumerate() {
+            outline.push_str(&format!("  {}. {}", number + 1, beat.title));
+            if !beat.characters.is_empty() {
+                outline.push_str(&format!(" [{}]", beat.characters.join(", ")));
+            }
+            if let Some(feature) = &beat.feature {
+                outline.push_str(&format!(" (feature: {})", feature));
+            }
+            outline.push_str(&format!(": {}\n", beat.description));
+        }
+        outline.push('\n');
+    }
+
+    outline.trim_end().to_string()
+}
+
+/// Renders a character and the beats they take part in.
+pub fn sheet(narrative: &Narrative, character: &Character) -> String {
+    let mut sheet = format!("# {}\n", character.name);
+
+    let fields = [
+        ("Role", &character.role),
+        ("Description", &character.description),
+        ("Motivation", &character.motivation),
+        ("Arc", &character.arc),
+        ("Voice", &character.voice),
+    ];
+    for (label, value) in fields {
+        if let Some(value) = value {
+            sheet.push_str(&format!("\n{}: {}", label, value.trim()));
+        }
+    }
+
+    let beats = narrative
+        .acts
+        .iter()
+        .enumerate()
+        .flat_map(|(index, act)| act.beats.iter().map(move |beat| (index, beat)))
+        .filter(|(_, beat)| {
+            beat.characters
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(&character.name))
+        })
+        .collect::<Vec<_>>();
+
+    if !beats.is_empty() {
+        sheet.push_str("\n\nAppears in:");
+        for (index, beat) in beats {
+            sheet.push_str(&format!("\n- Act {}: {}", index + 1, beat.title));
+        }
+    }
+
+    sheet
+}
+
+/// Asks the LLM to draft the story of the session's game: a style guide,
+/// the main characters and acts with their beats, linked to the planned
+/// features where they fit.
+pub async fn draft(llm_client: &DesignerLlmClient, session: &SessionState) -> Result<Narrative> {
+    let mut prompt = memory::design_context(session);
+
+    if !session.planned_features.is_empty() {
+        prompt.push_str("Planned features:\n");
+        for feature in &session.planned_features {
+            prompt.push_str(&format!("- {}: {}\n", feature.name, feature.description));
+        }
+        prompt.push('\n');
+    }
+
+    prompt.push_str(
+        "Draft the narrative design of this game: a dialogue style guide (tone, vocabulary \
+         and what to avoid), the main characters and a story outline of acts, each with a \
+         short summary and the beats that move the story forward. Name the characters taking \
+         part in each beat and, where the player experiences a beat through one of the \
+         planned features, that feature's exact name. Format your response as a JSON object \
+         with a 'style_guide' string, a 'characters' array of objects with 'name', 'role', \
+         'description', 'motivation', 'arc' and 'voice' fields, and an 'acts' array of objects \
+         with 'name', 'summary' and 'beats' (array of objects with 'title', 'description', \
+         'characters' and optional 'feature') fields. Only return the JSON, nothing else.",
+    );
+
+    let messages = vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: session.system_prompt(prompts::STORY_OUTLINE),
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content: prompt,
+        },
+    ];
+
+    let response = llm_client.call_llm(messages).await?;
+
+    let json = extract_json(&response)
+        .ok_or_else(|| anyhow::anyhow!("LLM response was not valid JSON: {}", response))?;
+    let mut narrative: Narrative = serde_json::from_str(json)?;
+
+    // Links to features that don't exist would dangle
+    for beat in narrative.acts.iter_mut().flat_map(|act| &mut act.beats) {
+        beat.feature = beat.feature.take().and_then(|name| {
+            session
+                .planned_features
+                .iter()
+                .find(|feature| feature.name.eq_ignore_ascii_case(name.trim()))
+                .map(|feature| feature.name.clone())
+        });
+    }
+
+    Ok(narrative)
+}
@@ -0,0 +1,116 @@
+//! Review panels of `featureReview`. Instead of a single designer, the
+//! implementation report goes to a design, a technical and a UX reviewer in
+//! parallel, each with its own system prompt. A consensus prompt then merges
+//! their reviews into one verdict in the usual format, so the rest of the
+//! review flow is the same as for a single reviewer. The individual reviews
+//! are kept with the review log.
+
+use crate::game_design::{
+    DesignerLlmClient, designer_llm::ChatMessage, prompts, state::SessionState,
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// A reviewer persona of the panel.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Reviewer {
+    /// Fun, core loop and fit with the design document.
+    Design,
+    /// Architecture, correctness, performance and tests.
+    Technical,
+    /// Controls, feedback, readability and accessibility.
+    Ux,
+}
+
+impl Reviewer {
+    pub const ALL: [Self; 3] = [Self::Design, Self::Technical, Self::Ux];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Design => "design",
+            Self::Technical => "technical",
+            Self::Ux => "ux",
+        }
+    }
+
+    /// Name of this reviewer's system prompt.
+    pub fn prompt_name(self) -> &'static str {
+        match self {
+            Self::Design => prompts::REVIEW_PANEL_DESIGN,
+            Self::Technical => prompts::REVIEW_PANEL_TECHNICAL,
+            Self::Ux => prompts::REVIEW_PANEL_UX,
+        }
+    }
+}
+
+/// The review of one panel member.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PanelReview {
+    pub reviewer: Reviewer,
+    pub review: String,
+}
+
+/// Sends `prompt` to every reviewer of the panel at the same time. A
+/// reviewer whose call fails is left out; fails only if all of them fail.
+pub async fn convene(
+    llm_client: &DesignerLlmClient,
+    session: &SessionState,
+    prompt: &str,
+) -> Result<Vec<PanelReview>> {
+    let calls = Reviewer::ALL.into_iter().map(|reviewer| {
+        let messages = vec![
+            ChatMessage {
+                role: "system".to_string(),
+                content: session.system_prompt(reviewer.prompt_name()),
+            },
+            ChatMessage {
+                role: "user".to_string(),
+                content: prompt.to_string(),
+            },
+        ];
+
+        async move { (reviewer, llm_client.call_llm(messages).await) }
+    });
+
+    let mut reviews = Vec::new();
+    let mut last_error = None;
+    for (reviewer, result) in futures::future::join_all(calls).await {
+        match result {
+            Ok(review) => reviews.push(PanelReview { reviewer, review }),
+            Err(e) => {
+                tracing::warn!(
+                    "The {} reviewer of the panel failed: {}",
+                    reviewer.name(),
+                    e
+                );
+                last_error = Some(e);
+            }
+        }
+    }
+
+    match last_error {
+        Some(e) if reviews.is_empty() => Err(e),
+        _ => Ok(reviews),
+    }
+}
+
+/// Builds the prompt asking for the consensus of `reviews` of the report
+/// given by `prompt`.
+pub fn consensus_prompt(prompt: &str, reviews: &[PanelReview]) -> String {
+    let mut text = format!("{}\n\nReviews of the panel:\n\n", prompt);
+    for review in reviews {
+        text.push_str(&format!(
+            "### {} reviewer\n{}\n\n",
+            review.reviewer.name(),
+            review.review.trim()
+        ));
+    }
+    text.push_str(
+        "Merge these reviews into a single verdict in the format asked for above. Approve only \
+         if no reviewer raises a problem that blocks the feature; carry over every open \
+         question and every caveat, and settle disagreements between the reviewers.",
+    );
+
+    text
+}
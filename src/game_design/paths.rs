@@ -0,0 +1,75 @@
+//! Confinement of file paths chosen by clients.
+//!
+//! Tools that read or write a file the client names, such as the design
+//! document of `designNew` or the Markdown of `designExport`, only touch
+//! files inside the directories the session allows: its repository and the
+//! data directory, or the working directory where a session has none. A
+//! client can't read or overwrite other files of the host.
+
+use crate::error::GameDesignerError;
+use anyhow::Result;
+use std::path::{Component, Path, PathBuf};
+
+/// Resolves `path` inside one of `roots` and returns it with the symlinks of
+/// its existing part resolved. Relative paths are resolved against the first
+/// root. Paths with `..` are refused, as are paths that leave every root,
+/// also through symlinks; the file itself need not exist.
+pub fn confine(path: &str, roots: &[&Path]) -> Result<PathBuf> {
+    let outside = || -> anyhow::Error {
+        GameDesignerError::InvalidArguments(format!(
+            "Path {} is outside {}",
+            path,
+            roots
+                .iter()
+                .map(|root| root.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" and ")
+        ))
+        .into()
+    };
+
+    let relative = Path::new(path);
+    if path.is_empty() || relative.components().any(|c| c == Component::ParentDir) {
+        return Err(outside());
+    }
+    let Some(base) = roots.first() else {
+        return Err(outside());
+    };
+
+    let resolved = resolve_existing(&base.join(relative))?;
+    let inside = roots
+        .iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .any(|root| resolved.starts_with(root));
+
+    if inside { Ok(resolved) } else { Err(outside()) }
+}
+
+/// Canonicalizes the longest existing ancestor of `path` and appends the
+/// rest of it.
+fn resolve_existing(path: &Path) -> Result<PathBuf> {
+    let mut existing = path;
+    let mut rest = Vec::new();
+
+    loop {
+        match existing.canonicalize() {
+            Ok(resolved) => {
+                return Ok(rest
+                    .iter()
+                    .rev()
+                    .fold(resolved, |path, name| path.join(name)));
+            }
+            Err(e) => match (existing.parent(), existing.file_name()) {
+                (Some(parent), Some(name)) => {
+                    rest.push(name.to_os_string());
+                    existing = if parent.as_os_str().is_empty() {
+                        Path::new(".")
+                    } else {
+                        parent
+                    };
+                }
+                _ => return Err(e.into()),
+            },
+        }
+    }
+}
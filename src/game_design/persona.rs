@@ -0,0 +1,77 @@
+//! Designer persona presets that flavour every system prompt and set how
+//! strict reviews are.
+
+use crate::game_design::prompts;
+use serde::{Deserialize, Serialize};
+
+/// A designer persona chosen per session.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Persona {
+    /// The plain designer prompts without any preset.
+    #[default]
+    Default,
+    IndieMinimalist,
+    AaaSystems,
+    MobileF2p,
+    NarrativeFirst,
+}
+
+impl Persona {
+    pub const ALL: [Self; 5] = [
+        Self::Default,
+        Self::IndieMinimalist,
+        Self::AaaSystems,
+        Self::MobileF2p,
+        Self::NarrativeFirst,
+    ];
+
+    /// Returns the identifier used in tool arguments, e.g. `indie_minimalist`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::IndieMinimalist => "indie_minimalist",
+            Self::AaaSystems => "aaa_systems",
+            Self::MobileF2p => "mobile_f2p",
+            Self::NarrativeFirst => "narrative_first",
+        }
+    }
+
+    /// Returns a one-line description for listings.
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Default => "Balanced game designer and software architect",
+            Self::IndieMinimalist => "Small, deep core loop; pragmatic, scope-cutting reviews",
+            Self::AaaSystems => "Scalable data-driven systems; strict, thorough reviews",
+            Self::MobileF2p => "Retention, onboarding and economy balance",
+            Self::NarrativeFirst => "Mechanics in service of story and characters",
+        }
+    }
+
+    /// Parses a persona name, accepting `-` or `_` as separators.
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase().replace(['-', ' '], "_");
+        Self::ALL.into_iter().find(|persona| persona.name() == name)
+    }
+
+    /// Name of the prompt template holding this persona's preamble.
+    fn prompt_name(self) -> Option<&'static str> {
+        match self {
+            Self::Default => None,
+            Self::IndieMinimalist => Some(prompts::PERSONA_INDIE_MINIMALIST),
+            Self::AaaSystems => Some(prompts::PERSONA_AAA_SYSTEMS),
+            Self::MobileF2p => Some(prompts::PERSONA_MOBILE_F2P),
+            Self::NarrativeFirst => Some(prompts::PERSONA_NARRATIVE_FIRST),
+        }
+    }
+
+    /// Loads the named system prompt with this persona's preamble appended.
+    pub fn system_prompt(self, name: &str) -> String {
+        let base = prompts::system_prompt(name);
+
+        match self.prompt_name() {
+            Some(persona) => format!("{}\n\n{}", base, prompts::system_prompt(persona)),
+            None => base,
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! Project namespaces, so that sessions of the same name from different
+//! projects don't collide.
+//!
+//! Sessions without a project are stored directly in the data directory,
+//! sessions of project `<name>` in `projects/<name>/` below it. Tools take
+//! an optional `project` argument; without one the default project applies:
+//! `--project` (or `GAMEDESIGNERD_PROJECT`), else the name of the working
+//! directory when the data directory is shared between projects. A
+//! per-project data directory inside the working directory needs no
+//! namespace. The empty name selects the unscoped sessions.
+
+use crate::game_design::session::persistence_dir;
+use anyhow::Result;
+use std::{
+    env, fs,
+    path::PathBuf,
+    sync::{LazyLock, OnceLock},
+};
+
+/// Directory below the data directory holding the project namespaces.
+pub const PROJECTS_DIR: &str = "projects";
+
+/// Project selected with `--project`; `None` inside selects the unscoped
+/// sessions.
+static PROJECT: OnceLock<Option<String>> = OnceLock::new();
+
+/// Selects the default project of every tool call. Fails on invalid names.
+pub fn set_default(project: &str) -> Result<()> {
+    let project = normalize(project)?;
+
+    if PROJECT.set(project).is_err() {
+        tracing::warn!("Default project was already set");
+    }
+
+    Ok(())
+}
+
+static DEFAULT_PROJECT: LazyLock<Option<String>> = LazyLock::new(|| match PROJECT.get() {
+    Some(project) => project.clone(),
+    None => derive_from_cwd(),
+});
+
+/// Returns the project of tool calls that don't name one.
+pub fn default_project() -> Option<&'static str> {
+    DEFAULT_PROJECT.as_deref()
+}
+
+/// Derives a project name from the working directory, unless the data
+/// directory lives inside it and is therefore specific to this project.
+fn derive_from_cwd() -> Option<String> {
+    let cwd = env::current_dir().ok()?;
+
+    let data_dir = persistence_dir();
+    let data_dir = if data_dir.is_absolute() {
+        data_dir.to_path_buf()
+    } else {
+        cwd.join(data_dir)
+    };
+
+    if data_dir.starts_with(&cwd) {
+        return None;
+    }
+
+    let name = slug(cwd.file_name()?.to_str()?);
+    (!name.is_empty()).then_some(name)
+}
+
+/// Lowercases `name` and replaces every run of characters that are not
+/// allowed in project names with a dash.
+fn slug(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Validates a project name given by a client. The empty name selects the
+/// unscoped sessions and yields `None`.
+pub fn normalize(project: &str) -> Result<Option<String>> {
+    let project = project.trim();
+
+    if project.is_empty() {
+        return Ok(None);
+    }
+
+    if !project
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(anyhow::anyhow!(
+            "Invalid project name '{}': use letters, digits, '-' and '_'",
+            project
+        ));
+    }
+
+    Ok(Some(project.to_string()))
+}
+
+/// Directory holding the sessions of `project`.
+pub fn dir(project: Option<&str>) -> PathBuf {
+    match project {
+        Some(project) => persistence_dir().join(PROJECTS_DIR).join(project),
+        None => persistence_dir().to_path_buf(),
+    }
+}
+
+/// Returns the names of all projects with stored sessions, sorted.
+pub fn list() -> Result<Vec<String>> {
+    let root = persistence_dir().join(PROJECTS_DIR);
+
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut projects = fs::read_dir(root)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect::<Vec<_>>();
+    projects.sort();
+
+    Ok(projects)
+}
+
+/// Returns the session directories of the unscoped sessions and of every
+/// project.
+pub fn all_dirs() -> Result<Vec<PathBuf>> {
+    Ok(std::iter::once(dir(None))
+        .chain(list()?.iter().map(|project| dir(Some(project))))
+        .collect())
+}
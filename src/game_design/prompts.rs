@@ -0,0 +1,271 @@
+//! System prompt templates with per-project overrides.
+//!
+//! Defaults live in the crate's `prompts/` directory and are embedded at
+//! compile time. A file named `<name>.md` in `.gamedesignerd/prompts/` replaces
+//! the embedded default of the same name, so the designer persona can be
+//! tailored without recompiling.
+
+use crate::game_design::session::persistence_dir;
+use anyhow::Result;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub const DESIGN_NEW: &str = "design_new";
+pub const NEXT_FEATURE: &str = "next_feature";
+pub const SPEC_CHECK: &str = "spec_check";
+pub const FEATURE_CANDIDATES: &str = "feature_candidates";
+pub const FEATURE_REVIEW: &str = "feature_review";
+pub const FEATURE_REVIEW_QUICK: &str = "feature_review_quick";
+pub const FEATURE_REVIEW_THOROUGH: &str = "feature_review_thorough";
+pub const REVIEW_PANEL_DESIGN: &str = "review_panel_design";
+pub const REVIEW_PANEL_TECHNICAL: &str = "review_panel_technical";
+pub const REVIEW_PANEL_UX: &str = "review_panel_ux";
+pub const REVIEW_CONSENSUS: &str = "review_consensus";
+pub const REVIEW_REPLY: &str = "review_reply";
+pub const FEATURE_ASK: &str = "feature_ask";
+pub const MEMORY_EXTRACT: &str = "memory_extract";
+pub const REPORT_DIGEST: &str = "report_digest";
+pub const INPUT_SUMMARY: &str = "input_summary";
+pub const BACKLOG_IMPORT: &str = "backlog_import";
+pub const DESIGN_IMPORT: &str = "design_import";
+pub const DESIGN_EDIT_REVIEW: &str = "design_edit_review";
+pub const DOCS_SUMMARIZER: &str = "docs_summarizer";
+pub const DESIGN_LINT: &str = "design_lint";
+pub const DESIGN_BRAINSTORM: &str = "design_brainstorm";
+pub const STORY_OUTLINE: &str = "story_outline";
+pub const LEVEL_BRIEF: &str = "level_brief";
+pub const ECONOMY_SIMULATE: &str = "economy_simulate";
+pub const MONETIZATION_REVIEW: &str = "monetization_review";
+pub const TEST_PLAN: &str = "test_plan";
+pub const UI_CRITIQUE: &str = "ui_critique";
+pub const SESSION_REUSE: &str = "session_reuse";
+pub const DEVLOG: &str = "devlog";
+pub const DESIGN_RETRO: &str = "design_retro";
+pub const COMMIT_MESSAGE: &str = "commit_message";
+pub const IMPLEMENTATION_HINTS: &str = "implementation_hints";
+pub const API_REFERENCES: &str = "api_references";
+pub const PERSONA_INDIE_MINIMALIST: &str = "persona_indie_minimalist";
+pub const PERSONA_AAA_SYSTEMS: &str = "persona_aaa_systems";
+pub const PERSONA_MOBILE_F2P: &str = "persona_mobile_f2p";
+pub const PERSONA_NARRATIVE_FIRST: &str = "persona_narrative_first";
+
+/// Embedded default templates by name.
+pub const DEFAULT_PROMPTS: &[(&str, &str)] = &[
+    (DESIGN_NEW, include_str!("../../prompts/design_new.md")),
+    (NEXT_FEATURE, include_str!("../../prompts/next_feature.md")),
+    (SPEC_CHECK, include_str!("../../prompts/spec_check.md")),
+    (
+        FEATURE_CANDIDATES,
+        include_str!("../../prompts/feature_candidates.md"),
+    ),
+    (
+        FEATURE_REVIEW,
+        include_str!("../../prompts/feature_review.md"),
+    ),
+    (
+        FEATURE_REVIEW_QUICK,
+        include_str!("../../prompts/feature_review_quick.md"),
+    ),
+    (
+        FEATURE_REVIEW_THOROUGH,
+        include_str!("../../prompts/feature_review_thorough.md"),
+    ),
+    (
+        REVIEW_PANEL_DESIGN,
+        include_str!("../../prompts/review_panel_design.md"),
+    ),
+    (
+        REVIEW_PANEL_TECHNICAL,
+        include_str!("../../prompts/review_panel_technical.md"),
+    ),
+    (
+        REVIEW_PANEL_UX,
+        include_str!("../../prompts/review_panel_ux.md"),
+    ),
+    (
+        REVIEW_CONSENSUS,
+        include_str!("../../prompts/review_consensus.md"),
+    ),
+    (REVIEW_REPLY, include_str!("../../prompts/review_reply.md")),
+    (FEATURE_ASK, include_str!("../../prompts/feature_ask.md")),
+    (
+        MEMORY_EXTRACT,
+        include_str!("../../prompts/memory_extract.md"),
+    ),
+    (
+        REPORT_DIGEST,
+        include_str!("../../prompts/report_digest.md"),
+    ),
+    (
+        INPUT_SUMMARY,
+        include_str!("../../prompts/input_summary.md"),
+    ),
+    (
+        BACKLOG_IMPORT,
+        include_str!("../../prompts/backlog_import.md"),
+    ),
+    (
+        DESIGN_IMPORT,
+        include_str!("../../prompts/design_import.md"),
+    ),
+    (
+        DESIGN_EDIT_REVIEW,
+        include_str!("../../prompts/design_edit_review.md"),
+    ),
+    (
+        DOCS_SUMMARIZER,
+        include_str!("../../prompts/docs_summarizer.md"),
+    ),
+    (DESIGN_LINT, include_str!("../../prompts/design_lint.md")),
+    (
+        DESIGN_BRAINSTORM,
+        include_str!("../../prompts/design_brainstorm.md"),
+    ),
+    (
+        STORY_OUTLINE,
+        include_str!("../../prompts/story_outline.md"),
+    ),
+    (LEVEL_BRIEF, include_str!("../../prompts/level_brief.md")),
+    (
+        ECONOMY_SIMULATE,
+        include_str!("../../prompts/economy_simulate.md"),
+    ),
+    (
+        MONETIZATION_REVIEW,
+        include_str!("../../prompts/monetization_review.md"),
+    ),
+    (TEST_PLAN, include_str!("../../prompts/test_plan.md")),
+    (UI_CRITIQUE, include_str!("../../prompts/ui_critique.md")),
+    (
+        SESSION_REUSE,
+        include_str!("../../prompts/session_reuse.md"),
+    ),
+    (DEVLOG, include_str!("../../prompts/devlog.md")),
+    (DESIGN_RETRO, include_str!("../../prompts/design_retro.md")),
+    (
+        COMMIT_MESSAGE,
+        include_str!("../../prompts/commit_message.md"),
+    ),
+    (
+        IMPLEMENTATION_HINTS,
+        include_str!("../../prompts/implementation_hints.md"),
+    ),
+    (
+        API_REFERENCES,
+        include_str!("../../prompts/api_references.md"),
+    ),
+    (
+        PERSONA_INDIE_MINIMALIST,
+        include_str!("../../prompts/persona_indie_minimalist.md"),
+    ),
+    (
+        PERSONA_AAA_SYSTEMS,
+        include_str!("../../prompts/persona_aaa_systems.md"),
+    ),
+    (
+        PERSONA_MOBILE_F2P,
+        include_str!("../../prompts/persona_mobile_f2p.md"),
+    ),
+    (
+        PERSONA_NARRATIVE_FIRST,
+        include_str!("../../prompts/persona_narrative_first.md"),
+    ),
+];
+
+/// Directory searched for per-project overrides.
+pub fn override_dir() -> PathBuf {
+    persistence_dir().join("prompts")
+}
+
+/// Returns the names of the templates overridden in [`override_dir`].
+pub fn overrides() -> Vec<String> {
+    let mut names: Vec<String> = DEFAULT_PROMPTS
+        .iter()
+        .map(|(name, _)| *name)
+        .filter(|name| override_dir().join(format!("{}.md", name)).exists())
+        .map(str::to_string)
+        .collect();
+    names.sort();
+    names
+}
+
+/// Returns the embedded default of the named template.
+pub fn default_prompt(name: &str) -> Option<&'static str> {
+    DEFAULT_PROMPTS
+        .iter()
+        .find(|(prompt_name, _)| *prompt_name == name)
+        .map(|(_, prompt)| *prompt)
+}
+
+/// Loads the named system prompt, preferring a project override.
+///
+/// Overrides are re-read on every call so edits take effect immediately.
+/// An unreadable override is logged and the embedded default is used.
+pub fn system_prompt(name: &str) -> String {
+    let override_path = override_dir().join(format!("{}.md", name));
+
+    if override_path.exists() {
+        match fs::read_to_string(&override_path) {
+            Ok(prompt) => return prompt.trim().to_string(),
+            Err(e) => tracing::warn!(
+                "Failed to read prompt override {}: {}",
+                override_path.display(),
+                e
+            ),
+        }
+    }
+
+    default_prompt(name)
+        .unwrap_or_else(|| panic!("no default prompt named '{}'", name))
+        .trim()
+        .to_string()
+}
+
+/// Start of the language instruction of [`localized`] prompts.
+const LANGUAGE_INSTRUCTION: &str = "Write everything meant to be read by people \
+     (specifications, reviews, questions, answers and documents) in ";
+
+/// Appends the instruction to write in `language`, if one is set, to a
+/// system prompt. Keywords and JSON field names stay in English, since
+/// responses are parsed by them.
+pub fn localized(system_prompt: String, language: Option<&str>) -> String {
+    match language {
+        Some(language) => format!(
+            "{}\n\n{}{}. Keep JSON field names, the exact keywords you are asked to respond \
+             with (such as SATISFIED) and code identifiers in English, exactly as specified.",
+            system_prompt, LANGUAGE_INSTRUCTION, language
+        ),
+        None => system_prompt,
+    }
+}
+
+/// Returns the language a [`localized`] system prompt asks for.
+pub fn language_of(system_prompt: &str) -> Option<&str> {
+    let (_, rest) = system_prompt.rsplit_once(LANGUAGE_INSTRUCTION)?;
+    let (language, _) = rest.split_once(". Keep JSON field names")?;
+    Some(language)
+}
+
+/// Writes every embedded default into `dir` as `<name>.md`.
+/// Existing files are kept unless `force` is set.
+/// Returns the paths of the files that were written.
+pub fn dump(dir: &Path, force: bool) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+
+    let mut written = Vec::new();
+
+    for (name, prompt) in DEFAULT_PROMPTS {
+        let path = dir.join(format!("{}.md", name));
+
+        if path.exists() && !force {
+            continue;
+        }
+
+        fs::write(&path, prompt)?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
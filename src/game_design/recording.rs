@@ -0,0 +1,195 @@
+//! Records LLM interactions and replays them, for reproducible bug reports
+//! and snapshot tests of the session workflow without network access.
+//!
+//! With `--record`, every request/response pair of the process is appended to
+//! `recordings/<timestamp>.jsonl` in the persistence directory. The replay
+//! provider (`--llm-provider replay`) loads such a file and answers each
+//! request with the recorded response of an identical conversation, in
+//! recording order when the same conversation occurs more than once.
+
+use crate::game_design::{designer_llm::ChatMessage, session::persistence_dir};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+};
+
+/// A recorded request/response pair.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub timestamp: DateTime<Utc>,
+    /// Hash of the conversation, used to match requests on replay.
+    pub request_hash: String,
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    pub response: String,
+}
+
+/// Directory holding the recordings.
+pub fn dir() -> PathBuf {
+    persistence_dir().join("recordings")
+}
+
+/// Hashes the conversation. The model is left out so that a recording can
+/// be replayed after switching models.
+pub fn request_hash(messages: &[ChatMessage]) -> String {
+    let mut hasher = Sha256::new();
+    for message in messages {
+        hasher.update(message.role.as_bytes());
+        hasher.update([0]);
+        hasher.update(message.content.as_bytes());
+        hasher.update([0]);
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// File the interactions of this process are recorded to.
+static RECORDING: OnceLock<Mutex<PathBuf>> = OnceLock::new();
+
+/// Starts recording every LLM interaction of this process to a new file.
+/// Returns the path of the file.
+pub fn start() -> Result<PathBuf> {
+    fs::create_dir_all(dir())?;
+    let path = dir().join(format!("{}.jsonl", Utc::now().format("%Y%m%dT%H%M%S")));
+
+    if RECORDING.set(Mutex::new(path.clone())).is_err() {
+        tracing::warn!("Recording was already started");
+    }
+
+    Ok(path)
+}
+
+/// Appends the interaction to the recording, if one was started. Failures
+/// are logged.
+pub fn record(model: &str, messages: &[ChatMessage], response: &str) {
+    let Some(path) = RECORDING.get() else {
+        return;
+    };
+
+    let interaction = Interaction {
+        timestamp: Utc::now(),
+        request_hash: request_hash(messages),
+        model: model.to_string(),
+        messages: messages.to_vec(),
+        response: response.to_string(),
+    };
+
+    // Held while writing so that concurrent calls don't interleave lines
+    let path = path.lock().unwrap();
+    let result = serde_json::to_string(&interaction)
+        .map_err(anyhow::Error::from)
+        .and_then(|line| {
+            let mut file = OpenOptions::new().create(true).append(true).open(&*path)?;
+            writeln!(file, "{}", line)?;
+            Ok(())
+        });
+
+    if let Err(e) = result {
+        tracing::warn!("Failed to record LLM interaction: {}", e);
+    }
+}
+
+/// Returns the most recent recording, if any.
+pub fn latest() -> Result<Option<PathBuf>> {
+    if !dir().exists() {
+        return Ok(None);
+    }
+
+    let mut recordings = fs::read_dir(dir())?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+        .collect::<Vec<_>>();
+    recordings.sort();
+
+    Ok(recordings.pop())
+}
+
+/// Recording the replay provider serves, set at startup.
+static REPLAY_SOURCE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Selects the recording to replay instead of the most recent one.
+pub fn set_replay_source(path: PathBuf) {
+    if REPLAY_SOURCE.set(path).is_err() {
+        tracing::warn!("Replay source was already set");
+    }
+}
+
+/// LLM provider answering requests with recorded responses.
+#[derive(Debug)]
+pub struct ReplayProvider {
+    path: PathBuf,
+    /// Recorded responses by request hash, in recording order.
+    responses: Mutex<HashMap<String, VecDeque<String>>>,
+}
+
+impl ReplayProvider {
+    /// Loads the selected recording, or the most recent one.
+    pub fn new() -> Result<Self> {
+        let path = match REPLAY_SOURCE.get() {
+            Some(path) => path.clone(),
+            None => latest()?
+                .ok_or_else(|| anyhow::anyhow!("No recordings found in {}", dir().display()))?,
+        };
+
+        Self::load(&path)
+    }
+
+    /// Loads the recording at `path`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut responses: HashMap<String, VecDeque<String>> = HashMap::new();
+
+        for (index, line) in fs::read_to_string(path)?.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let interaction: Interaction = serde_json::from_str(line).map_err(|e| {
+                anyhow::anyhow!(
+                    "Invalid recording line {} in {}: {}",
+                    index + 1,
+                    path.display(),
+                    e
+                )
+            })?;
+
+            responses
+                .entry(interaction.request_hash)
+                .or_default()
+                .push_back(interaction.response);
+        }
+
+        tracing::info!("Replaying LLM interactions from {}", path.display());
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            responses: Mutex::new(responses),
+        })
+    }
+
+    /// Returns the next recorded response to an identical conversation. The
+    /// last response to a conversation is repeated once the others are used up.
+    pub fn respond(&self, messages: &[ChatMessage]) -> Result<String> {
+        let hash = request_hash(messages);
+        let mut responses = self.responses.lock().unwrap();
+
+        let queue = responses.get_mut(&hash).ok_or_else(|| {
+            anyhow::anyhow!(
+                "No recorded response in {} for this request (hash {})",
+                self.path.display(),
+                hash
+            )
+        })?;
+
+        match queue.len() {
+            0 => unreachable!("recorded response queues are never empty"),
+            1 => Ok(queue[0].clone()),
+            _ => Ok(queue.pop_front().unwrap()),
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! Redaction of secrets pasted into reports by accident.
+//!
+//! Before a report, reply or question is stored in a transcript, and before
+//! any message is sent to the LLM, [`redact`] replaces what looks like a
+//! credential with `[REDACTED:<kind>]`: well-known token formats (keys of
+//! OpenRouter, OpenAI, Anthropic, GitHub, AWS, Slack and Google, JWTs,
+//! private key blocks and bearer tokens), values assigned to names such as
+//! `api_key` or `password`, and long random-looking strings found by their
+//! entropy.
+//!
+//! Redaction is on unless `[redaction] enabled = false` or
+//! `GAMEDESIGNER_REDACTION=off`; `[redaction] patterns` adds regular
+//! expressions of project-specific secrets.
+
+use crate::game_design::config;
+use regex::Regex;
+use serde::Serialize;
+use std::{collections::BTreeMap, env, sync::LazyLock};
+
+/// Shortest string the entropy heuristic considers.
+const MIN_ENTROPY_LEN: usize = 24;
+
+/// Entropy in bits per character from which a string is taken for a
+/// secret. Identifiers, paths, commit hashes and UUIDs stay below it.
+const ENTROPY_THRESHOLD: f64 = 4.2;
+
+/// Known token formats. A `secret` group limits the redaction to the value.
+static PATTERNS: LazyLock<Vec<(&'static str, Regex)>> = LazyLock::new(|| {
+    [
+        (
+            "private_key",
+            r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z ]*PRIVATE KEY-----",
+        ),
+        ("api_key", r"\bsk-[A-Za-z0-9_-]{20,}"),
+        (
+            "github_token",
+            r"\b(?:gh[pousr]_[A-Za-z0-9]{36,}|github_pat_[A-Za-z0-9_]{22,})",
+        ),
+        ("aws_access_key", r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b"),
+        ("slack_token", r"\bxox[abprs]-[A-Za-z0-9-]{10,}"),
+        ("google_api_key", r"\bAIza[0-9A-Za-z_-]{35}"),
+        (
+            "jwt",
+            r"\beyJ[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}",
+        ),
+        (
+            "bearer_token",
+            r"(?i)\bbearer\s+(?P<secret>[A-Za-z0-9._~+/-]{16,}=*)",
+        ),
+    ]
+    .into_iter()
+    .map(|(kind, pattern)| (kind, Regex::new(pattern).expect("valid redaction pattern")))
+    .collect()
+});
+
+/// Values assigned to names of credentials, e.g. `password = hunter2`.
+static ASSIGNMENT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?i)\b(?:api[_-]?key|secret(?:[_-]?key)?|client[_-]?secret|access[_-]?token|auth[_-]?token|token|password|passwd)\b["']?\s*[:=]\s*["']?(?P<secret>[^\s"',;$<{\[]{8,})"#,
+    )
+    .expect("valid redaction pattern")
+});
+
+/// Candidates of the entropy heuristic.
+static CANDIDATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[A-Za-z0-9+/=_-]{24,}").expect("valid redaction pattern"));
+
+/// A redacted secret.
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    /// What the secret looked like, e.g. `api_key` or `high_entropy`.
+    pub kind: String,
+    /// First characters and length of the secret, enough to recognize it.
+    pub hint: String,
+}
+
+impl Finding {
+    fn new(kind: &str, secret: &str) -> Self {
+        let prefix: String = secret.chars().take(4).collect();
+
+        Self {
+            kind: kind.to_string(),
+            hint: format!("{}… ({} chars)", prefix, secret.chars().count()),
+        }
+    }
+}
+
+/// Text with its secrets replaced and what was replaced.
+#[derive(Debug, Clone)]
+pub struct Redacted {
+    pub text: String,
+    pub findings: Vec<Finding>,
+}
+
+/// Whether redaction is on: `GAMEDESIGNER_REDACTION`, then `[redaction]
+/// enabled`, then on.
+pub fn enabled() -> bool {
+    match env::var("GAMEDESIGNER_REDACTION") {
+        Ok(value) => !matches!(value.to_lowercase().as_str(), "off" | "false" | "0"),
+        Err(_) => config::current().redaction.enabled.unwrap_or(true),
+    }
+}
+
+/// Replaces the secrets in `text`. Returns `text` unchanged if redaction is
+/// off.
+pub fn redact(text: &str) -> Redacted {
+    let mut redacted = Redacted {
+        text: text.to_string(),
+        findings: Vec::new(),
+    };
+    if !enabled() {
+        return redacted;
+    }
+
+    for (kind, pattern) in PATTERNS.iter() {
+        replace(&mut redacted, kind, pattern, |_| true);
+    }
+    replace(
+        &mut redacted,
+        "assigned_secret",
+        &ASSIGNMENT,
+        has_letters_and_digits,
+    );
+
+    for pattern in &config::current().redaction.patterns {
+        match Regex::new(pattern) {
+            Ok(pattern) => replace(&mut redacted, "custom", &pattern, |_| true),
+            Err(e) => tracing::warn!("Invalid redaction pattern '{}': {}", pattern, e),
+        }
+    }
+
+    replace(&mut redacted, "high_entropy", &CANDIDATE, |candidate| {
+        candidate.len() >= MIN_ENTROPY_LEN
+            && has_letters_and_digits(candidate)
+            && entropy(candidate) >= ENTROPY_THRESHOLD
+    });
+
+    redacted
+}
+
+/// Counts the findings by kind, e.g. `2 api_key, 1 high_entropy`.
+pub fn summary(findings: &[Finding]) -> String {
+    let mut counts = BTreeMap::new();
+    for finding in findings {
+        *counts.entry(finding.kind.as_str()).or_insert(0) += 1;
+    }
+
+    counts
+        .into_iter()
+        .map(|(kind, count)| format!("{} {}", count, kind))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Replaces the matches of `pattern` accepted by `filter`, or their `secret`
+// group, with a marker of `kind`
+fn replace(redacted: &mut Redacted, kind: &str, pattern: &Regex, filter: impl Fn(&str) -> bool) {
+    let text = &redacted.text;
+    let mut replaced = String::with_capacity(text.len());
+    let mut last = 0;
+
+    for captures in pattern.captures_iter(text) {
+        let Some(secret) = captures.name("secret").or_else(|| captures.get(0)) else {
+            continue;
+        };
+        if !filter(secret.as_str()) {
+            continue;
+        }
+
+        replaced.push_str(&text[last..secret.start()]);
+        replaced.push_str(&format!("[REDACTED:{}]", kind));
+        redacted.findings.push(Finding::new(kind, secret.as_str()));
+        last = secret.end();
+    }
+
+    if last > 0 {
+        replaced.push_str(&text[last..]);
+        redacted.text = replaced;
+    }
+}
+
+fn has_letters_and_digits(text: &str) -> bool {
+    text.chars().any(|c| c.is_ascii_alphabetic()) && text.chars().any(|c| c.is_ascii_digit())
+}
+
+/// Shannon entropy of `text` in bits per character.
+fn entropy(text: &str) -> f64 {
+    let mut counts = [0usize; 256];
+    for byte in text.bytes() {
+        counts[byte as usize] += 1;
+    }
+
+    let len = text.len() as f64;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
@@ -0,0 +1,262 @@
+//! The sessions a session manager keeps in memory.
+//!
+//! Sessions are loaded from storage on first use and kept for the calls that
+//! follow. At most [`capacity`] of them stay resident: admitting another one
+//! evicts the least recently used session whose file holds its current
+//! state, and it is loaded again when it is next used. Sessions with changes
+//! that are not written yet are never evicted, so a manager may hold more
+//! sessions than its capacity for a while.
+
+use crate::game_design::{config, state::SessionState};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    env, fmt, io,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Sessions kept in memory per session manager unless configured otherwise.
+pub const DEFAULT_CAPACITY: usize = 64;
+
+/// Reads the capacity from `GAMEDESIGNER_MAX_RESIDENT_SESSIONS`, falling
+/// back to the configuration file. Zero keeps every session in memory.
+pub fn capacity() -> usize {
+    env::var("GAMEDESIGNER_MAX_RESIDENT_SESSIONS")
+        .ok()
+        .and_then(|sessions| sessions.parse().ok())
+        .or(config::current().storage.max_resident_sessions)
+        .unwrap_or(DEFAULT_CAPACITY)
+}
+
+struct Resident {
+    state: SessionState,
+    /// Use counter value of the last access.
+    last_used: AtomicU64,
+    /// Whether the state was handed out for changes since it was loaded.
+    touched: bool,
+}
+
+/// The sessions in memory, most recently used ones kept on eviction.
+pub struct ResidentSessions {
+    sessions: HashMap<String, Resident>,
+    capacity: usize,
+    uses: AtomicU64,
+    hits: AtomicU64,
+    loads: u64,
+    evictions: u64,
+}
+
+impl ResidentSessions {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            sessions: HashMap::new(),
+            capacity,
+            uses: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            loads: 0,
+            evictions: 0,
+        }
+    }
+
+    fn tick(&self) -> u64 {
+        self.uses.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    pub fn contains_key(&self, session_id: &str) -> bool {
+        self.sessions.contains_key(session_id)
+    }
+
+    pub fn get(&self, session_id: &str) -> Option<&SessionState> {
+        let resident = self.sessions.get(session_id)?;
+        resident.last_used.store(self.tick(), Ordering::Relaxed);
+        self.hits.fetch_add(1, Ordering::Relaxed);
+
+        Some(&resident.state)
+    }
+
+    pub fn get_mut(&mut self, session_id: &str) -> Option<&mut SessionState> {
+        let tick = self.tick();
+        let resident = self.sessions.get_mut(session_id)?;
+        resident.last_used.store(tick, Ordering::Relaxed);
+        resident.touched = true;
+        self.hits.fetch_add(1, Ordering::Relaxed);
+
+        Some(&mut resident.state)
+    }
+
+    /// Keeps `state` in memory, replacing the state of the session if it is
+    /// resident. Call [`Self::evict`] afterwards to stay within capacity.
+    pub fn insert(&mut self, session_id: String, state: SessionState) {
+        let resident = Resident {
+            state,
+            last_used: AtomicU64::new(self.tick()),
+            touched: false,
+        };
+
+        if self.sessions.insert(session_id, resident).is_none() {
+            self.loads += 1;
+        }
+    }
+
+    /// Evicts the least recently used sessions other than `keep` until at
+    /// most the capacity is resident. Sessions changed since they were
+    /// loaded are only evicted if `is_stored` confirms that storage holds
+    /// their current state. Returns the evicted sessions.
+    pub fn evict(&mut self, keep: &str, is_stored: impl Fn(&SessionState) -> bool) -> Vec<String> {
+        if self.capacity == 0 || self.sessions.len() <= self.capacity {
+            return Vec::new();
+        }
+
+        let mut candidates = self
+            .sessions
+            .iter()
+            .filter(|(id, _)| id.as_str() != keep)
+            .map(|(id, resident)| (resident.last_used.load(Ordering::Relaxed), id.clone()))
+            .collect::<Vec<_>>();
+        candidates.sort();
+
+        let mut evicted = Vec::new();
+        for (_, id) in candidates {
+            if self.sessions.len() <= self.capacity {
+                break;
+            }
+
+            let resident = &self.sessions[&id];
+            if resident.touched && !is_stored(&resident.state) {
+                continue;
+            }
+
+            self.sessions.remove(&id);
+            self.evictions += 1;
+            evicted.push(id);
+        }
+
+        evicted
+    }
+
+    /// Describes the resident sessions, most recently used first.
+    /// `is_stored` tells whether storage holds the current state of a
+    /// session changed since it was loaded.
+    pub fn residency(&self, is_stored: impl Fn(&SessionState) -> bool) -> Residency {
+        let mut sessions = self
+            .sessions
+            .iter()
+            .map(|(id, resident)| {
+                let session = ResidentSession {
+                    id: id.clone(),
+                    bytes: serialized_len(&resident.state),
+                    unsaved: resident.touched && !is_stored(&resident.state),
+                };
+                (resident.last_used.load(Ordering::Relaxed), session)
+            })
+            .collect::<Vec<_>>();
+        sessions.sort_by_key(|(last_used, _)| std::cmp::Reverse(*last_used));
+
+        Residency {
+            capacity: self.capacity,
+            loads: self.loads,
+            hits: self.hits.load(Ordering::Relaxed),
+            evictions: self.evictions,
+            sessions: sessions.into_iter().map(|(_, session)| session).collect(),
+        }
+    }
+}
+
+/// Counts the bytes written to it.
+struct ByteCounter(u64);
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Size of the session as compact JSON, an estimate of its size in memory.
+fn serialized_len(session: &SessionState) -> u64 {
+    let mut counter = ByteCounter(0);
+    serde_json::to_writer(&mut counter, session).map_or(0, |()| counter.0)
+}
+
+/// Memory residency of the sessions of one session manager.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Residency {
+    /// Most sessions kept in memory; zero for no limit.
+    pub capacity: usize,
+    /// Sessions loaded from storage or created.
+    pub loads: u64,
+    /// Accesses to sessions in memory.
+    pub hits: u64,
+    /// Sessions dropped from memory to stay within the capacity.
+    pub evictions: u64,
+    /// Sessions in memory, most recently used first.
+    pub sessions: Vec<ResidentSession>,
+}
+
+/// A session kept in memory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResidentSession {
+    pub id: String,
+    /// Size of the session as compact JSON.
+    pub bytes: u64,
+    /// Whether the session has changes that storage does not hold.
+    pub unsaved: bool,
+}
+
+/// Residency of the session manager of a project.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectResidency {
+    /// The project; `None` for the sessions without a project.
+    pub project: Option<String>,
+    #[serde(flatten)]
+    pub residency: Residency,
+}
+
+impl fmt::Display for ProjectResidency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let residency = &self.residency;
+        let bytes = residency
+            .sessions
+            .iter()
+            .map(|session| session.bytes)
+            .sum::<u64>();
+
+        match &self.project {
+            Some(project) => write!(f, "Project '{}': ", project)?,
+            None => write!(f, "Sessions without a project: ")?,
+        }
+        write!(f, "{} resident", residency.sessions.len())?;
+        if residency.capacity != 0 {
+            write!(f, " of {}", residency.capacity)?;
+        }
+        writeln!(
+            f,
+            ", {:.1} KB, {} loads, {} hits, {} evictions",
+            bytes as f64 / 1024.0,
+            residency.loads,
+            residency.hits,
+            residency.evictions
+        )?;
+
+        for session in &residency.sessions {
+            write!(
+                f,
+                "  {:<32} {:>10.1} KB",
+                session.id,
+                session.bytes as f64 / 1024.0
+            )?;
+            if session.unsaved {
+                write!(f, "  unsaved changes")?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
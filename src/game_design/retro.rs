@@ -0,0 +1,350 @@
+//! Retrospectives of finished sessions, built from the event log.
+//!
+//! The log tells how the scope moved over the whole session: features that
+//! were designed, imported or reused, candidates passed over, revisions of
+//! the design document and edits the designer rejected. Review events show
+//! where reviews churned, and the working time of reviewed features is
+//! compared with their estimates. The lessons learned are written by the
+//! designer LLM from the rest of the retrospective.
+
+use crate::game_design::{
+    events::{EventKind, SessionEvent},
+    scope::{self, Effort},
+    state::{FeatureStatus, SessionState},
+    status::BLOCKED_ROUNDS,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Factor by which the tracked time of a feature may differ from the time
+/// its estimate predicts before the estimate counts as missed.
+pub const MISSED_ESTIMATE_FACTOR: f64 = 2.0;
+
+/// How the scope of the session changed.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScopeChanges {
+    /// Features specified by the designer LLM.
+    pub designed: usize,
+    /// Features imported from issue trackers or design documents.
+    pub imported: usize,
+    /// Features reused from other sessions.
+    pub reused: usize,
+    /// Candidates offered by `nextFeature` that were never picked.
+    pub passed_over: Vec<String>,
+    /// Proposals the developer rejected with `featureReject`.
+    pub rejected: Vec<String>,
+    /// Summaries of the revisions of the design document.
+    pub revisions: Vec<String>,
+    /// Edits of the design document the designer objected to.
+    pub rejected_edits: usize,
+    /// Planned features that were never approved.
+    pub unfinished: Vec<String>,
+}
+
+/// Review history of one feature.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewChurn {
+    pub feature: String,
+    /// Reviews and replies over the whole session.
+    pub rounds: usize,
+    /// Reviews that sent the feature back for rework.
+    pub reworks: usize,
+    pub approved: bool,
+}
+
+/// Estimate of a reviewed feature against its tracked working time.
+#[derive(Debug, Clone, Serialize)]
+pub struct EstimateAccuracy {
+    pub feature: String,
+    pub effort: Effort,
+    pub minutes: u64,
+    /// Minutes the estimate predicts at the session's calibrated pace.
+    pub expected_minutes: Option<u64>,
+}
+
+impl EstimateAccuracy {
+    /// Whether the tracked time is off by [`MISSED_ESTIMATE_FACTOR`] or more.
+    pub fn missed(&self) -> bool {
+        self.expected_minutes.is_some_and(|expected| {
+            let (minutes, expected) = (self.minutes.max(1) as f64, expected.max(1) as f64);
+            minutes / expected >= MISSED_ESTIMATE_FACTOR
+                || expected / minutes >= MISSED_ESTIMATE_FACTOR
+        })
+    }
+}
+
+/// Retrospective of a session.
+#[derive(Debug, Clone, Serialize)]
+pub struct Retrospective {
+    pub started_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Every planned feature was approved.
+    pub finished: bool,
+    pub scope: ScopeChanges,
+    /// Features with at least one review, most reworked first.
+    pub reviews: Vec<ReviewChurn>,
+    pub estimates: Vec<EstimateAccuracy>,
+    /// Tracked minutes per effort point, once estimates are calibrated.
+    pub minutes_per_point: Option<f64>,
+    /// Written by the designer LLM; `None` without one.
+    pub lessons: Option<String>,
+}
+
+impl Retrospective {
+    /// Renders the retrospective as Markdown, without the lessons learned.
+    pub fn render_facts(&self) -> String {
+        let mut text = String::new();
+
+        let scope = &self.scope;
+        text.push_str(&format!(
+            "## Scope\n\n- Features designed: {}, imported: {}, reused: {}\n",
+            scope.designed, scope.imported, scope.reused
+        ));
+        if !scope.passed_over.is_empty() {
+            text.push_str(&format!(
+                "- Candidates passed over ({}): {}\n",
+                scope.passed_over.len(),
+                scope.passed_over.join(", ")
+            ));
+        }
+        if !scope.rejected.is_empty() {
+            text.push_str(&format!(
+                "- Proposals rejected ({}): {}\n",
+                scope.rejected.len(),
+                scope.rejected.join(", ")
+            ));
+        }
+        text.push_str(&format!(
+            "- Design revisions: {}, rejected edits: {}\n",
+            scope.revisions.len(),
+            scope.rejected_edits
+        ));
+        for summary in &scope.revisions {
+            text.push_str(&format!("  - {}\n", summary));
+        }
+        if !scope.unfinished.is_empty() {
+            text.push_str(&format!(
+                "- Never approved ({}): {}\n",
+                scope.unfinished.len(),
+                scope.unfinished.join(", ")
+            ));
+        }
+
+        text.push_str("\n## Reviews\n\n");
+        if self.reviews.is_empty() {
+            text.push_str("- No feature was reviewed.\n");
+        }
+        for review in &self.reviews {
+            text.push_str(&format!(
+                "- {}: {} round(s), {} rework(s){}{}\n",
+                review.feature,
+                review.rounds,
+                review.reworks,
+                if review.approved {
+                    ""
+                } else {
+                    ", not approved"
+                },
+                if review.rounds >= BLOCKED_ROUNDS {
+                    " **(churned)**"
+                } else {
+                    ""
+                }
+            ));
+        }
+
+        text.push_str("\n## Estimates\n\n");
+        if self.estimates.is_empty() {
+            text.push_str("- No reviewed feature has both an estimate and tracked time.\n");
+        }
+        for estimate in &self.estimates {
+            text.push_str(&format!(
+                "- {}: {:?}, took {}",
+                estimate.feature,
+                estimate.effort,
+                scope::format_minutes(estimate.minutes)
+            ));
+            if let Some(expected) = estimate.expected_minutes {
+                text.push_str(&format!(", expected {}", scope::format_minutes(expected)));
+            }
+            if estimate.missed() {
+                text.push_str(" **(missed)**");
+            }
+            text.push('\n');
+        }
+        match self.minutes_per_point {
+            Some(minutes_per_point) => {
+                let missed = self.estimates.iter().filter(|e| e.missed()).count();
+                text.push_str(&format!(
+                    "- {} of {} estimates missed by {}x or more, at {:.0} minutes per effort point\n",
+                    missed,
+                    self.estimates.len(),
+                    MISSED_ESTIMATE_FACTOR,
+                    minutes_per_point
+                ));
+            }
+            None => text.push_str(&format!(
+                "- Fewer than {} samples, estimates are not calibrated\n",
+                scope::MIN_CALIBRATION_SAMPLES
+            )),
+        }
+
+        text.trim_end().to_string()
+    }
+
+    /// Renders the retrospective as Markdown.
+    pub fn render(&self, session_id: &str) -> String {
+        let mut text = format!("# Retrospective of {}\n\n", session_id);
+
+        if let (Some(started_at), Some(ended_at)) = (self.started_at, self.ended_at) {
+            text.push_str(&format!(
+                "{} to {}",
+                started_at.format("%Y-%m-%d"),
+                ended_at.format("%Y-%m-%d")
+            ));
+            if !self.finished {
+                text.push_str(", not finished");
+            }
+            text.push_str("\n\n");
+        }
+
+        text.push_str(&self.render_facts());
+
+        text.push_str("\n\n## Lessons learned\n\n");
+        match &self.lessons {
+            Some(lessons) => text.push_str(lessons.trim()),
+            None => text.push_str("_The designer LLM is not available to draw lessons._"),
+        }
+
+        text
+    }
+}
+
+/// Builds the retrospective of `session` from all of its `events`, without
+/// the lessons learned.
+pub fn retrospective(session: &SessionState, events: &[SessionEvent]) -> Retrospective {
+    let feature_of = |event: &SessionEvent| {
+        event
+            .data
+            .get("feature")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+    let flag = |event: &SessionEvent, name: &str| {
+        event
+            .data
+            .get(name)
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    };
+
+    let mut scope = ScopeChanges::default();
+    let mut candidates: Vec<String> = Vec::new();
+    let mut proposed: Vec<String> = Vec::new();
+    let mut reviews: Vec<ReviewChurn> = Vec::new();
+
+    for event in events {
+        match event.kind {
+            EventKind::FeatureProposed => {
+                if flag(event, "imported") {
+                    scope.imported += 1;
+                } else if flag(event, "reused") {
+                    scope.reused += 1;
+                } else {
+                    scope.designed += 1;
+                }
+                proposed.extend(feature_of(event));
+            }
+            EventKind::CandidatesProposed => {
+                let names = event.data.get("candidates").and_then(|v| v.as_array());
+                for name in names.into_iter().flatten().filter_map(|v| v.as_str()) {
+                    if !candidates.iter().any(|candidate| candidate == name) {
+                        candidates.push(name.to_string());
+                    }
+                }
+            }
+            EventKind::FeatureRejected => scope.rejected.extend(feature_of(event)),
+            EventKind::DesignRevised => {
+                scope.revisions.extend(
+                    event
+                        .data
+                        .get("summary")
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string),
+                );
+            }
+            EventKind::DesignEditRejected => scope.rejected_edits += 1,
+            EventKind::ReviewSubmitted
+            | EventKind::ReviewReplied
+            | EventKind::FeatureNeedsRework
+            | EventKind::FeatureApproved => {
+                let Some(feature) = feature_of(event) else {
+                    continue;
+                };
+                let index = match reviews.iter().position(|review| review.feature == feature) {
+                    Some(index) => index,
+                    None => {
+                        reviews.push(ReviewChurn {
+                            feature,
+                            rounds: 0,
+                            reworks: 0,
+                            approved: false,
+                        });
+                        reviews.len() - 1
+                    }
+                };
+
+                let review = &mut reviews[index];
+                match event.kind {
+                    EventKind::FeatureNeedsRework => review.reworks += 1,
+                    EventKind::FeatureApproved => review.approved = true,
+                    _ => review.rounds += 1,
+                }
+            }
+            _ => {}
+        }
+    }
+
+    scope.passed_over = candidates
+        .into_iter()
+        .filter(|candidate| !proposed.contains(candidate))
+        .collect();
+    scope.unfinished = session
+        .planned_features
+        .iter()
+        .filter(|feature| feature.status != FeatureStatus::Reviewed)
+        .map(|feature| feature.name.clone())
+        .collect();
+
+    // Stable, so features with the same churn stay in review order
+    reviews.sort_by(|a, b| (b.reworks, b.rounds).cmp(&(a.reworks, a.rounds)));
+
+    let minutes_per_point = scope::calibration(session).map(|c| c.minutes_per_point);
+    let estimates = session
+        .planned_features
+        .iter()
+        .filter(|feature| feature.status == FeatureStatus::Reviewed)
+        .filter(|feature| feature.time_spent_minutes > 0)
+        .filter_map(|feature| {
+            let effort = feature.estimated_effort?;
+            Some(EstimateAccuracy {
+                feature: feature.name.clone(),
+                effort,
+                minutes: feature.time_spent_minutes,
+                expected_minutes: minutes_per_point
+                    .map(|pace| (pace * effort.points() as f64).round() as u64),
+            })
+        })
+        .collect();
+
+    Retrospective {
+        started_at: events.first().map(|event| event.timestamp),
+        ended_at: events.last().map(|event| event.timestamp),
+        finished: scope.unfinished.is_empty(),
+        scope,
+        reviews,
+        estimates,
+        minutes_per_point,
+        lessons: None,
+    }
+}
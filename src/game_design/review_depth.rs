@@ -0,0 +1,77 @@
+//! Review depths of `featureReview`. A small or low-risk feature gets a
+//! quick rubber-stamp, a core system a thorough review. Each depth has its
+//! own system prompt and its own checks before the verdict; the verdict
+//! keywords and the check of acceptance criteria are the same at every depth.
+
+use crate::game_design::prompts;
+use serde::{Deserialize, Serialize};
+
+/// How deeply a feature is reviewed.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewDepth {
+    /// Approves unless the report shows a clear problem.
+    Quick,
+    #[default]
+    Standard,
+    /// Also checks edge cases, performance and tests, and asks about
+    /// anything left unverified.
+    Thorough,
+}
+
+impl ReviewDepth {
+    pub const ALL: [Self; 3] = [Self::Quick, Self::Standard, Self::Thorough];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Quick => "quick",
+            Self::Standard => "standard",
+            Self::Thorough => "thorough",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        Self::ALL.into_iter().find(|depth| depth.name() == name)
+    }
+
+    /// Name of the system prompt of reviews at this depth.
+    pub fn prompt_name(self) -> &'static str {
+        match self {
+            Self::Quick => prompts::FEATURE_REVIEW_QUICK,
+            Self::Standard => prompts::FEATURE_REVIEW,
+            Self::Thorough => prompts::FEATURE_REVIEW_THOROUGH,
+        }
+    }
+
+    /// What the review checks before its verdict.
+    pub fn checks(self) -> &'static str {
+        match self {
+            Self::Quick => {
+                "This is a quick review. Check only if:\n\
+                 1. The implementation matches the feature specification\n\
+                 2. Nothing in the report is obviously broken or missing\n\n\
+                 Do not ask about details, style or polish; approve unless the report shows a \
+                 clear problem.\n"
+            }
+            Self::Standard => {
+                "Please review this implementation report. Check if:\n\
+                 1. The implementation matches the feature specification\n\
+                 2. There are any potential issues or missing elements\n\
+                 3. The implementation aligns with the overall game design\n\
+                 4. The report matches the actual repository changes, if a diff is attached\n\n"
+            }
+            Self::Thorough => {
+                "This is a thorough review. Check if:\n\
+                 1. The implementation matches the feature specification in every detail\n\
+                 2. There are any potential issues or missing elements\n\
+                 3. The implementation aligns with the overall game design\n\
+                 4. The report matches the actual repository changes, if a diff is attached\n\
+                 5. Edge cases, failure modes and performance are handled\n\
+                 6. The feature is covered by tests and leaves no hidden technical debt\n\n\
+                 Ask about anything the report leaves unverified, and do not approve while any \
+                 of these points is open.\n"
+            }
+        }
+    }
+}
@@ -0,0 +1,297 @@
+//! Effort and risk estimates of features, aggregated per milestone and
+//! checked against the session's effort budget.
+//!
+//! Estimates are calibrated with the working time tracked on reviewed
+//! features: the designer is shown how long features of each size actually
+//! took before it estimates new ones, and the remaining roadmap is projected
+//! in hours.
+
+use crate::game_design::{
+    config,
+    state::{FeatureStatus, SessionState},
+};
+use serde::{Deserialize, Serialize};
+use std::env;
+
+/// T-shirt size of the work a feature takes.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Effort {
+    #[serde(alias = "s")]
+    S,
+    #[serde(alias = "m")]
+    M,
+    #[serde(alias = "l")]
+    L,
+    #[serde(rename = "XL", alias = "xl")]
+    Xl,
+}
+
+impl Effort {
+    /// Effort points used for totals; each size roughly doubles the previous.
+    pub fn points(self) -> u32 {
+        match self {
+            Self::S => 1,
+            Self::M => 2,
+            Self::L => 4,
+            Self::Xl => 8,
+        }
+    }
+}
+
+/// Likelihood that a feature takes much longer than estimated or fails.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Risk {
+    #[serde(alias = "Low")]
+    Low,
+    #[serde(alias = "Medium")]
+    Medium,
+    #[serde(alias = "High")]
+    High,
+}
+
+/// Milestone name used for features without one.
+pub const UNASSIGNED_MILESTONE: &str = "Unassigned";
+
+/// Reviewed features with tracked time needed before estimates are
+/// calibrated.
+pub const MIN_CALIBRATION_SAMPLES: usize = 3;
+
+/// Most recent reviewed features the designer is shown for calibration.
+const CALIBRATION_HISTORY: usize = 10;
+
+/// Effort totals of a single milestone.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MilestoneScope {
+    pub milestone: String,
+    pub features: usize,
+    /// Effort points of all estimated features.
+    pub points: u32,
+    /// Effort points of features not yet reviewed.
+    pub remaining_points: u32,
+    pub unestimated: usize,
+    pub high_risk: Vec<String>,
+}
+
+/// Tracked working time of the reviewed features of one effort size.
+#[derive(Debug, Clone, Serialize)]
+pub struct EffortActuals {
+    pub effort: Effort,
+    pub features: usize,
+    pub average_minutes: u64,
+}
+
+/// Estimated effort against tracked working time of reviewed features.
+#[derive(Debug, Clone, Serialize)]
+pub struct Calibration {
+    /// Reviewed features with an estimate and tracked time.
+    pub samples: usize,
+    pub by_effort: Vec<EffortActuals>,
+    /// Tracked minutes per effort point over all samples.
+    pub minutes_per_point: f64,
+}
+
+/// Effort totals of the whole roadmap.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScopeReport {
+    pub milestones: Vec<MilestoneScope>,
+    pub total_points: u32,
+    pub remaining_points: u32,
+    pub budget: Option<u32>,
+    pub warnings: Vec<String>,
+    pub calibration: Option<Calibration>,
+    /// Working time the remaining points take at the calibrated pace.
+    pub projected_minutes: Option<u64>,
+}
+
+/// Reads the default effort budget from `GAMEDESIGNER_EFFORT_BUDGET`, falling
+/// back to the configuration file.
+pub fn default_budget() -> Option<u32> {
+    env::var("GAMEDESIGNER_EFFORT_BUDGET")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .or(config::current().scope.effort_budget)
+}
+
+/// Aggregates the session's estimates per milestone, in order of first
+/// appearance, and warns when the roadmap exceeds `budget` effort points.
+pub fn report(session: &SessionState, budget: Option<u32>) -> ScopeReport {
+    let mut milestones: Vec<MilestoneScope> = Vec::new();
+
+    for feature in &session.planned_features {
+        let name = feature.milestone.as_deref().unwrap_or(UNASSIGNED_MILESTONE);
+
+        let index = match milestones.iter().position(|m| m.milestone == name) {
+            Some(index) => index,
+            None => {
+                milestones.push(MilestoneScope {
+                    milestone: name.to_string(),
+                    ..Default::default()
+                });
+                milestones.len() - 1
+            }
+        };
+        let scope = &mut milestones[index];
+
+        scope.features += 1;
+
+        match feature.estimated_effort {
+            Some(effort) => {
+                scope.points += effort.points();
+                if feature.status != FeatureStatus::Reviewed {
+                    scope.remaining_points += effort.points();
+                }
+            }
+            None => scope.unestimated += 1,
+        }
+
+        if feature.risk == Some(Risk::High) {
+            scope.high_risk.push(feature.name.clone());
+        }
+    }
+
+    let total_points = milestones.iter().map(|m| m.points).sum();
+    let remaining_points = milestones.iter().map(|m| m.remaining_points).sum();
+
+    let mut warnings = Vec::new();
+
+    if let Some(budget) = budget
+        && total_points > budget
+    {
+        warnings.push(format!(
+            "The roadmap needs {} effort points, {} over the budget of {}. Cut or simplify features.",
+            total_points,
+            total_points - budget,
+            budget
+        ));
+    }
+
+    for feature in &session.planned_features {
+        if feature.estimated_effort == Some(Effort::Xl) && feature.status != FeatureStatus::Reviewed
+        {
+            warnings.push(format!(
+                "Feature '{}' is estimated XL; consider splitting it.",
+                feature.name
+            ));
+        }
+    }
+
+    let high_risk: usize = milestones.iter().map(|m| m.high_risk.len()).sum();
+    if high_risk > 0 {
+        warnings.push(format!("{} features are rated high risk.", high_risk));
+    }
+
+    let calibration = calibration(session);
+    let projected_minutes = calibration.as_ref().map(|calibration| {
+        (remaining_points as f64 * calibration.minutes_per_point).round() as u64
+    });
+
+    ScopeReport {
+        milestones,
+        total_points,
+        remaining_points,
+        budget,
+        warnings,
+        calibration,
+        projected_minutes,
+    }
+}
+
+/// Reviewed features with an estimate and tracked working time, in the
+/// order they were planned.
+fn calibration_samples(session: &SessionState) -> Vec<(&str, Effort, u64)> {
+    session
+        .planned_features
+        .iter()
+        .filter(|feature| feature.status == FeatureStatus::Reviewed)
+        .filter(|feature| feature.time_spent_minutes > 0)
+        .filter_map(|feature| {
+            let effort = feature.estimated_effort?;
+            Some((feature.name.as_str(), effort, feature.time_spent_minutes))
+        })
+        .collect()
+}
+
+/// Compares the session's estimates with the time the reviewed features
+/// took, once there are [`MIN_CALIBRATION_SAMPLES`] of them.
+pub fn calibration(session: &SessionState) -> Option<Calibration> {
+    let samples = calibration_samples(session);
+    if samples.len() < MIN_CALIBRATION_SAMPLES {
+        return None;
+    }
+
+    let by_effort = [Effort::S, Effort::M, Effort::L, Effort::Xl]
+        .into_iter()
+        .filter_map(|effort| {
+            let minutes = samples
+                .iter()
+                .filter(|(_, sample, _)| *sample == effort)
+                .map(|(_, _, minutes)| *minutes)
+                .collect::<Vec<_>>();
+            if minutes.is_empty() {
+                return None;
+            }
+
+            Some(EffortActuals {
+                effort,
+                features: minutes.len(),
+                average_minutes: minutes.iter().sum::<u64>() / minutes.len() as u64,
+            })
+        })
+        .collect();
+
+    let points: u32 = samples.iter().map(|(_, effort, _)| effort.points()).sum();
+    let minutes: u64 = samples.iter().map(|(_, _, minutes)| minutes).sum();
+
+    Some(Calibration {
+        samples: samples.len(),
+        by_effort,
+        minutes_per_point: minutes as f64 / points as f64,
+    })
+}
+
+/// Formats minutes as hours and minutes, e.g. `3h 20m`.
+pub fn format_minutes(minutes: u64) -> String {
+    format!("{}h {}m", minutes / 60, minutes % 60)
+}
+
+/// Instruction showing the designer its past estimates against the time the
+/// features took, so that it adjusts the estimate of the next ones. `None`
+/// until the session has enough history.
+pub fn calibration_instruction(session: &SessionState) -> Option<String> {
+    let calibration = calibration(session)?;
+    let samples = calibration_samples(session);
+
+    let mut instruction = String::from(
+        "\n\nYour past effort estimates for this game, against the working time the features \
+         actually took:\n",
+    );
+    for (name, effort, minutes) in samples.iter().rev().take(CALIBRATION_HISTORY).rev() {
+        instruction.push_str(&format!(
+            "- {}: estimated {:?}, took {}\n",
+            name,
+            effort,
+            format_minutes(*minutes)
+        ));
+    }
+
+    instruction.push_str("On average:");
+    for actuals in &calibration.by_effort {
+        instruction.push_str(&format!(
+            " {:?} took {} ({} features);",
+            actuals.effort,
+            format_minutes(actuals.average_minutes),
+            actuals.features
+        ));
+    }
+    instruction.pop();
+
+    instruction.push_str(
+        ".\nCalibrate your effort estimates against this history: if features of a size took \
+         about as long as those of a larger size, estimate similar work larger, and smaller if \
+         they took less than their size suggests. Keep the sizes consistent, so that one size \
+         stands for the same amount of work across the game.",
+    );
+
+    Some(instruction)
+}
@@ -0,0 +1,101 @@
+//! Lexical retrieval of features and design decisions across sessions.
+//!
+//! Items are ranked by the fraction of query terms they contain, with terms
+//! in the item name weighted higher than terms in its description.
+
+use crate::game_design::state::SessionState;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Words too common to tell designs apart.
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "be", "by", "for", "from", "in", "is", "it", "of", "on", "or",
+    "the", "to", "with", "system",
+];
+
+/// Kind of a retrieved item.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchKind {
+    Feature,
+    Decision,
+}
+
+/// A feature or decision of another session that matches a query.
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchMatch {
+    pub session_id: String,
+    pub kind: MatchKind,
+    pub name: String,
+    pub text: String,
+    pub score: f32,
+}
+
+/// Splits text into lowercase search terms, dropping stop words and
+/// single characters.
+pub fn terms(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|term| term.len() > 1 && !STOP_WORDS.contains(&term.as_str()))
+        .collect()
+}
+
+/// Scores an item against the query terms in `0.0..=1.0`.
+fn score(query: &HashSet<String>, name: &str, text: &str) -> f32 {
+    if query.is_empty() {
+        return 0.0;
+    }
+
+    let name_terms = terms(name);
+    let text_terms = terms(text);
+
+    let hits: f32 = query
+        .iter()
+        .map(|term| {
+            if name_terms.contains(term) {
+                1.0
+            } else if text_terms.contains(term) {
+                0.5
+            } else {
+                0.0
+            }
+        })
+        .sum();
+
+    hits / query.len() as f32
+}
+
+/// Returns up to `limit` features and decisions of `sessions` matching the
+/// query, best first.
+pub fn search(sessions: &[SessionState], query: &str, limit: usize) -> Vec<SearchMatch> {
+    let query = terms(query);
+    let mut matches = Vec::new();
+
+    for session in sessions {
+        for feature in &session.planned_features {
+            matches.push(SearchMatch {
+                session_id: session.id.clone(),
+                kind: MatchKind::Feature,
+                score: score(&query, &feature.name, &feature.description),
+                name: feature.name.clone(),
+                text: feature.description.clone(),
+            });
+        }
+
+        for decision in &session.design_memory {
+            matches.push(SearchMatch {
+                session_id: session.id.clone(),
+                kind: MatchKind::Decision,
+                score: score(&query, "", &decision.decision),
+                name: decision.source_feature.clone().unwrap_or_default(),
+                text: decision.decision.clone(),
+            });
+        }
+    }
+
+    matches.retain(|m| m.score > 0.0);
+    matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+    matches.truncate(limit);
+
+    matches
+}
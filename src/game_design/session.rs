@@ -1,68 +1,783 @@
 //! Manages loading, saving, and manipulating individual game design sessions.
 
-use crate::game_design::state::SessionState;
+use crate::{
+    error::GameDesignerError,
+    game_design::{
+        approval,
+        assets::{self, AssetEntry, AssetKind, AssetRequirement},
+        attachments::{self, Upload},
+        board::BoardSync,
+        brainstorm::{self, Brainstorm},
+        clock::{Clock, SystemClock},
+        config, context, conventions, criteria,
+        critique::{self, UiCritique},
+        debt::{self, DebtItem},
+        document,
+        economy::{self, Economy, Simulation},
+        embeddings::{Embedder, EmbeddingIndex, SemanticMatch},
+        engine::{self, ApiReference, Engine},
+        events::{EventFilter, EventKind, EventLog, SessionEvent},
+        export::{self, GddExporter},
+        followups, git, graveyard,
+        interview::{Interview, InterviewStep},
+        level::{self, Level, LevelStatus},
+        lint::{self, LintFinding, Severity},
+        locks::{LockSnapshot, LockStats, SessionLocks},
+        memory,
+        monetization::{self, MonetizationPolicy, MonetizationReport},
+        narrative, panel, paths,
+        persona::Persona,
+        project, prompts,
+        residency::{self, Residency, ResidentSessions},
+        retro::{self, Retrospective},
+        review_depth::ReviewDepth,
+        scope::{self, ScopeReport},
+        search::{self, SearchMatch},
+        snapshots::SnapshotStore,
+        spec_check,
+        state::{
+            Act, Approval, Beat, Character, DesignDecision, DesignVersion, Feature,
+            FeatureCandidate, FeatureStatus, Freeze, Narrative, ProjectMetadata, QaEntry,
+            RejectedFeature, ReviewEntry, ReviewEntryKind, SessionState,
+        },
+        status::{self, StatusReport},
+        storage::{FsStorage, Storage},
+        templates::GenreTemplate,
+        testplan::{self, TestPlan},
+        trackers::TrackerIssue,
+        webhooks::WebhookOutbox,
+    },
+};
 use anyhow::Result;
-use std::{collections::HashMap, fs, path::Path, sync::Arc};
-use tokio::sync::RwLock;
+use serde_json::json;
+use std::{
+    collections::{BTreeMap, HashMap},
+    env, fs,
+    ops::{Deref, DerefMut},
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock, Mutex, OnceLock},
+};
+use tokio::sync::OwnedMutexGuard;
+
+/// Per-project data directory in the working directory. Earlier versions
+/// always stored sessions there, so it is still used when it exists.
+pub const LOCAL_PERSISTENCE_PATH: &str = ".gamedesignerd";
+
+/// Returns the per-user data directory: `$XDG_DATA_HOME/gamedesignerd`,
+/// falling back to `~/.local/share` (or `%LOCALAPPDATA%` on Windows).
+pub fn default_data_dir() -> Option<PathBuf> {
+    let base = env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share"))
+        })
+        .or_else(|| env::var_os("LOCALAPPDATA").map(PathBuf::from))?;
+
+    Some(base.join("gamedesignerd"))
+}
+
+/// Data directory selected with `--data-dir` or `GAMEDESIGNERD_DATA_DIR`.
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Selects the data directory, overriding the configured storage path.
+/// Must be called before the directory is first used.
+pub fn set_persistence_dir(dir: PathBuf) {
+    if let Err(dir) = DATA_DIR.set(dir) {
+        tracing::warn!("Data directory was already set, ignoring {}", dir.display());
+    }
+}
+
+static PERSISTENCE_DIR: LazyLock<PathBuf> = LazyLock::new(|| {
+    DATA_DIR
+        .get()
+        .cloned()
+        .or_else(|| config::current().storage.path.clone())
+        .or_else(|| {
+            let local = PathBuf::from(LOCAL_PERSISTENCE_PATH);
+            local.is_dir().then_some(local)
+        })
+        .or_else(default_data_dir)
+        .unwrap_or_else(|| PathBuf::from(LOCAL_PERSISTENCE_PATH))
+});
+
+/// Returns the directory holding sessions, caches, recordings and exports:
+/// `--data-dir`, then the configured storage path, then `.gamedesignerd` in
+/// the working directory if it exists, then [`default_data_dir`]. Resolved
+/// once per process, so a configuration reload doesn't move it.
+pub fn persistence_dir() -> &'static Path {
+    &PERSISTENCE_DIR
+}
+
+/// Files and directories the data directory keeps next to the session
+/// files, which sessions therefore can't be named after.
+pub const RESERVED_SESSION_NAMES: &[&str] = &[
+    "webhooks",
+    "snapshots",
+    "projects",
+    "cache",
+    "recordings",
+    "prompts",
+    "usage",
+    "inputs",
+    "jobs",
+    "logs",
+    "quarantine",
+    "doc-cache",
+    "mock-llm",
+];
+
+/// Validates the name of a new session. Sessions are stored as
+/// `<name>.json` with their files in `<name>/`, so names consist of letters,
+/// digits, '-' and '_' and may not be one of [`RESERVED_SESSION_NAMES`].
+pub fn validate_session_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(GameDesignerError::InvalidArguments(format!(
+            "Invalid session name '{}': use letters, digits, '-' and '_'",
+            name
+        ))
+        .into());
+    }
+
+    if RESERVED_SESSION_NAMES.contains(&name.to_lowercase().as_str()) {
+        return Err(GameDesignerError::InvalidArguments(format!(
+            "Session name '{}' is reserved for files of the data directory",
+            name
+        ))
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Checks that `session_id` names a session file directly inside the data
+/// directory. Looser than [`validate_session_name`], so that sessions
+/// created before names were checked stay reachable, but names that would
+/// leave the directory or hit its other files are refused.
+pub fn check_session_id(session_id: &str) -> Result<()> {
+    let outside = session_id.is_empty()
+        || session_id.starts_with('.')
+        || session_id
+            .chars()
+            .any(|c| c == '/' || c == '\\' || c.is_control());
+
+    if outside || RESERVED_SESSION_NAMES.contains(&session_id.to_lowercase().as_str()) {
+        return Err(GameDesignerError::InvalidArguments(format!(
+            "Invalid session name '{}'",
+            session_id
+        ))
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Number of past Q&A entries included when answering a new question.
+const QA_CONTEXT_ENTRIES: usize = 10;
+
+/// Number of matches from other sessions offered to the LLM for reuse.
+const REUSE_CANDIDATES: usize = 15;
+
+/// Tokens of repository diff attached to a feature review.
+const REVIEW_DIFF_TOKENS: usize = 6_000;
+
+/// Tokens reserved for the instructions that follow the session context in a prompt.
+const PROMPT_INSTRUCTIONS_TOKENS: usize = 1_000;
+
+/// Most alternatives a single `nextFeature` call may ask for.
+pub const MAX_FEATURE_CANDIDATES: usize = 5;
+
+/// Asks feature proposals to name the pooled idea they build on.
+const IDEA_INSTRUCTION: &str = "\n\nWhere it fits the next step, build on one of the \
+     shortlisted ideas and add an 'idea' string field with its name.";
+
+/// Features and decisions imported from other sessions.
+#[derive(Debug, Clone, Default)]
+pub struct ReuseOutcome {
+    /// The matches the LLM could choose from.
+    pub candidates: Vec<SearchMatch>,
+    pub features: Vec<Feature>,
+    pub decisions: Vec<String>,
+}
+
+/// Result of reviewing a proposed edit of the design document.
+#[derive(Debug, Clone)]
+pub enum DesignEditOutcome {
+    /// The edit was applied and the document is now at `version`.
+    Merged { version: u32, summary: String },
+    /// The edit was rejected for the listed reasons.
+    Objected {
+        summary: String,
+        objections: Vec<String>,
+    },
+}
+
+/// Settings a session is created with, see [`SessionManager::create_session`].
+#[derive(Debug, Clone, Default)]
+pub struct SessionSetup {
+    /// Language the designer writes in, `None` for English.
+    pub language: Option<String>,
+    /// Design document the session is imported from, with the pillars
+    /// found in it.
+    pub document: Option<(String, Vec<String>)>,
+    pub metadata: ProjectMetadata,
+    pub engine: Option<Engine>,
+    /// Root of the git repository the session's game is built in.
+    pub repo: Option<String>,
+}
+
+/// A feature implementation report submitted with
+/// [`SessionManager::submit_feature_review`].
+#[derive(Debug, Clone, Default)]
+pub struct ReviewSubmission {
+    /// What the developer changed, as reported to the reviewers.
+    pub changes_made: String,
+    /// Commits that implemented the feature.
+    pub commits: Vec<String>,
+    pub time_spent_minutes: Option<u64>,
+    /// Attaches the repository diff since the feature's base commit.
+    pub include_diff: bool,
+    /// Review depth, instead of the session's.
+    pub depth: Option<ReviewDepth>,
+    /// Whether a panel reviews, instead of the session's setting.
+    pub panel: Option<bool>,
+    pub attachments: Vec<Upload>,
+    pub author: Option<String>,
+}
+
+/// A change reverted by [`SessionManager::undo`].
+#[derive(Debug, Clone)]
+pub struct UndoOutcome {
+    /// Sequence number of the first event of the change.
+    pub seq: u64,
+    /// Events recorded by the change.
+    pub events: Vec<SessionEvent>,
+}
+
+/// A copy of a session taken out of the sessions in memory for a change.
+/// The session stays locked until the copy is dropped; persisting the copy
+/// puts it back.
+struct Checkout {
+    session: SessionState,
+    _lock: OwnedMutexGuard<()>,
+}
+
+impl Deref for Checkout {
+    type Target = SessionState;
+
+    fn deref(&self) -> &SessionState {
+        &self.session
+    }
+}
+
+impl DerefMut for Checkout {
+    fn deref_mut(&mut self) -> &mut SessionState {
+        &mut self.session
+    }
+}
 
 /// Manages sessions in memory and handles persistence.
+///
+/// The sessions in memory are only locked to read or write them. A change
+/// of a session works on a copy, see [`Self::checkout`], so that LLM calls
+/// made for one session don't hold up calls on the others.
 pub struct SessionManager {
-    sessions: Arc<RwLock<HashMap<String, SessionState>>>,
+    sessions: Mutex<ResidentSessions>,
+    /// Locks of the sessions being changed.
+    locks: SessionLocks,
+    /// Project namespace of the sessions, `None` for those without one.
+    project: Option<String>,
     persistence_path: String,
+    /// Where session files, event logs, snapshots and interviews are kept.
+    storage: Arc<dyn Storage>,
+    /// Time source of every timestamp the manager records.
+    clock: Arc<dyn Clock>,
+    events: EventLog,
+    snapshots: SnapshotStore,
+    webhooks: WebhookOutbox,
+    exporter: GddExporter,
+    /// Feature context of each session and the prompt budget it was built
+    /// for, dropped whenever the session changes.
+    feature_contexts: Mutex<HashMap<String, (usize, String)>>,
 }
 
 impl SessionManager {
-    /// Creates a new `SessionManager`.
-    pub fn new() -> Self {
-        // Use .gamedesignerd directory in the current working directory
-        let persistence_path = ".gamedesignerd".to_string();
-
-        // Ensure the persistence directory exists
-        if !Path::new(&persistence_path).exists() {
-            fs::create_dir_all(&persistence_path).unwrap_or_else(|_| {
-                panic!(
-                    "Failed to create .gamedesignerd directory: {}",
-                    persistence_path
-                );
-            });
-        }
+    /// Creates a `SessionManager` for the sessions without a project,
+    /// creating the data directory if needed.
+    pub fn new() -> Result<Self> {
+        Self::in_dir(persistence_dir())
+    }
+
+    /// Creates a `SessionManager` storing its sessions in `dir` instead of
+    /// the data directory, e.g. a temporary directory in tests.
+    pub fn in_dir(dir: &Path) -> Result<Self> {
+        Self::with_backends(dir, Arc::new(FsStorage), Arc::new(SystemClock))
+    }
+
+    /// Creates a `SessionManager` for the sessions in `dir` of `storage`,
+    /// timestamping changes with `clock`, e.g. in-memory storage and a
+    /// frozen clock in tests.
+    pub fn with_backends(
+        dir: &Path,
+        storage: Arc<dyn Storage>,
+        clock: Arc<dyn Clock>,
+    ) -> Result<Self> {
+        Self::open(
+            None,
+            dir,
+            WebhookOutbox::new(dir, storage.clone()),
+            storage,
+            clock,
+            Arc::new(LockStats::default()),
+        )
+    }
 
-        Self {
-            sessions: Arc::new(RwLock::new(HashMap::new())),
+    /// Creates a `SessionManager` for the sessions of `project`, sharing the
+    /// webhook endpoints, storage, clock and lock wait times of this one.
+    pub fn for_project(&self, project: &str) -> Result<Self> {
+        Self::open(
+            Some(project.to_string()),
+            &project::dir(Some(project)),
+            self.webhooks.clone(),
+            self.storage.clone(),
+            self.clock.clone(),
+            self.locks.stats().clone(),
+        )
+    }
+
+    fn open(
+        project: Option<String>,
+        dir: &Path,
+        webhooks: WebhookOutbox,
+        storage: Arc<dyn Storage>,
+        clock: Arc<dyn Clock>,
+        lock_stats: Arc<LockStats>,
+    ) -> Result<Self> {
+        let persistence_path = dir.to_string_lossy().into_owned();
+
+        storage.create_dir_all(dir).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to create the data directory {}: {}. Choose a writable directory \
+                 with --data-dir or GAMEDESIGNERD_DATA_DIR.",
+                persistence_path,
+                e
+            )
+        })?;
+
+        let events = EventLog::new(&persistence_path, storage.clone());
+        let snapshots = SnapshotStore::new(&persistence_path, storage.clone());
+        let exporter = GddExporter::new(&persistence_path, storage.clone());
+
+        Ok(Self {
+            sessions: Mutex::new(ResidentSessions::new(residency::capacity())),
+            locks: SessionLocks::new(lock_stats),
+            project,
             persistence_path,
+            storage,
+            clock,
+            events,
+            snapshots,
+            webhooks,
+            exporter,
+            feature_contexts: Default::default(),
+        })
+    }
+
+    /// Path of the file of a session. Fails for names that don't pass
+    /// [`check_session_id`].
+    fn session_file_path(&self, session_id: &str) -> Result<String> {
+        check_session_id(session_id)?;
+        Ok(format!("{}/{}.json", self.persistence_path, session_id))
+    }
+
+    /// Returns whether the session has a file.
+    pub fn session_exists(&self, session_id: &str) -> bool {
+        self.session_file_path(session_id)
+            .is_ok_and(|path| self.storage.exists(Path::new(&path)))
+    }
+
+    /// Resolves a file the client wants written for `session` inside its
+    /// repository or the data directory, see [`paths::confine`]. Relative
+    /// paths are resolved against the repository, or the data directory
+    /// without one.
+    fn output_path(&self, session: &SessionState, path: &str) -> Result<PathBuf> {
+        let data_dir = Path::new(&self.persistence_path);
+        match session.repo_path.as_deref() {
+            Some(repo) => paths::confine(path, &[Path::new(repo), data_dir]),
+            None => paths::confine(path, &[data_dir]),
         }
     }
 
-    /// Creates a new session with the given ID and initial description.
-    pub async fn create_session(&self, session_id: String, description: String) -> Result<()> {
-        let mut sessions = self.sessions.write().await;
+    /// Reads and parses the file of a session, if it has one.
+    fn read_session_file(&self, session_id: &str) -> Result<Option<SessionState>> {
+        let session_file_path = self.session_file_path(session_id)?;
+        if !self.storage.exists(Path::new(&session_file_path)) {
+            return Ok(None);
+        }
 
-        // Check if session already exists in memory
-        if sessions.contains_key(&session_id) {
-            return Err(anyhow::anyhow!("Session '{}' already exists", session_id));
+        let session_json = self.storage.read_to_string(Path::new(&session_file_path))?;
+        Ok(Some(parse_session(&session_file_path, &session_json)?))
+    }
+
+    /// Returns the session from the in-memory map, loading it from disk first
+    /// if necessary.
+    fn session_mut<'a>(
+        &self,
+        sessions: &'a mut ResidentSessions,
+        session_id: &str,
+    ) -> Result<&'a mut SessionState> {
+        if !sessions.contains_key(session_id) {
+            let session = self
+                .read_session_file(session_id)?
+                .ok_or_else(|| GameDesignerError::SessionNotFound(session_id.to_string()))?;
+            self.admit(sessions, session_id.to_string(), session);
+        }
+
+        Ok(sessions.get_mut(session_id).unwrap())
+    }
+
+    /// Locks the session and returns a copy of it to change, loading it
+    /// from disk first if necessary. Waits for the call changing the
+    /// session if any.
+    async fn checkout(&self, session_id: &str) -> Result<Checkout> {
+        let lock = self.locks.lock(session_id).await;
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = self.session_mut(&mut sessions, session_id)?.clone();
+
+        Ok(Checkout {
+            session,
+            _lock: lock,
+        })
+    }
+
+    /// The project namespace of the manager's sessions, `None` for the
+    /// sessions without a project.
+    pub fn project(&self) -> Option<&str> {
+        self.project.as_deref()
+    }
+
+    /// The current time of the manager's clock.
+    pub fn now(&self) -> chrono::DateTime<chrono::Utc> {
+        self.clock.now()
+    }
+
+    /// The log of the state changes of the manager's sessions.
+    pub fn event_log(&self) -> &EventLog {
+        &self.events
+    }
+
+    /// The manager's clock, e.g. to date the token usage of its sessions.
+    pub fn clock(&self) -> Arc<dyn Clock> {
+        self.clock.clone()
+    }
+
+    /// Storage of the session files, e.g. to serve the imported design
+    /// documents.
+    pub fn storage(&self) -> &dyn Storage {
+        self.storage.as_ref()
+    }
+
+    /// How long calls waited for the session locks so far, of this manager
+    /// and those of the projects opened from it.
+    pub fn lock_stats(&self) -> LockSnapshot {
+        self.locks.stats().snapshot()
+    }
+
+    /// Writes the session to its file, keeping the previous contents as a
+    /// snapshot for [`Self::undo`], and to the sessions in memory.
+    fn persist(&self, session: &SessionState) -> Result<()> {
+        self.forget_feature_context(&session.id);
+
+        let session_file_path = self.session_file_path(&session.id)?;
+        let session_json = serde_json::to_string_pretty(session)?;
+
+        if let Ok(previous) = self.storage.read_to_string(Path::new(&session_file_path))
+            && previous != session_json
+        {
+            let result = self
+                .events
+                .next_seq(&session.id)
+                .and_then(|seq| self.snapshots.save(&session.id, seq, &previous));
+
+            if let Err(e) = result {
+                tracing::warn!("Failed to snapshot session '{}': {}", session.id, e);
+            }
+        }
+
+        self.storage
+            .write(Path::new(&session_file_path), &session_json)?;
+
+        let mut sessions = self.sessions.lock().unwrap();
+        self.admit(&mut sessions, session.id.clone(), session.clone());
+
+        Ok(())
+    }
+
+    /// Keeps a loaded, created or restored session in memory, evicting the
+    /// least recently used sessions beyond the capacity.
+    fn admit(&self, sessions: &mut ResidentSessions, session_id: String, state: SessionState) {
+        self.forget_feature_context(&session_id);
+        sessions.insert(session_id.clone(), state);
+
+        for evicted in sessions.evict(&session_id, |session| self.is_stored(session)) {
+            tracing::debug!("Evicted session '{}' from memory", evicted);
+        }
+    }
+
+    fn forget_feature_context(&self, session_id: &str) {
+        self.feature_contexts.lock().unwrap().remove(session_id);
+    }
+
+    /// Returns the [`feature_context`] of the session, built again only if
+    /// the session or the prompt budget changed since the last time.
+    async fn feature_context(
+        &self,
+        session: &mut SessionState,
+        llm_client: &crate::game_design::DesignerLlmClient,
+    ) -> String {
+        let budget = context::prompt_budget();
+
+        if let Some((built_for, prompt)) = self.feature_contexts.lock().unwrap().get(&session.id)
+            && *built_for == budget
+        {
+            return prompt.clone();
+        }
+
+        let prompt = feature_context(session, llm_client).await;
+        self.feature_contexts
+            .lock()
+            .unwrap()
+            .insert(session.id.clone(), (budget, prompt.clone()));
+
+        prompt
+    }
+
+    /// Returns whether the session's file holds its current state.
+    fn is_stored(&self, session: &SessionState) -> bool {
+        let Ok(path) = self.session_file_path(&session.id) else {
+            return false;
+        };
+        let Ok(stored) = self.storage.read_to_string(Path::new(&path)) else {
+            return false;
+        };
+
+        serde_json::to_string_pretty(session).is_ok_and(|json| json == stored)
+    }
+
+    /// Describes the sessions kept in memory.
+    pub fn residency(&self) -> Residency {
+        self.sessions
+            .lock()
+            .unwrap()
+            .residency(|session| self.is_stored(session))
+    }
+
+    /// Records a state-change event in the session's event log, publishes
+    /// it to the configured webhooks and refreshes the automatic design export.
+    fn emit(&self, kind: EventKind, session_id: &str, data: serde_json::Value) {
+        let mut event = SessionEvent::new(kind, session_id, data, self.clock.now());
+
+        if let Err(e) = self.events.append(&mut event) {
+            tracing::warn!("Failed to append to event log of '{}': {}", session_id, e);
+        }
+
+        self.webhooks.dispatch(event);
+        self.exporter.regenerate(session_id);
+    }
+
+    /// Re-reads the webhook configuration. Returns the number of endpoints.
+    pub fn reload_webhooks(&self) -> Result<usize> {
+        self.webhooks.reload()
+    }
+
+    /// Reads up to `limit` events of a session starting at `cursor`.
+    /// Returns the events and the cursor to pass on the next read.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn session_events(
+        &self,
+        session_id: &str,
+        cursor: u64,
+        limit: usize,
+    ) -> Result<(Vec<SessionEvent>, u64)> {
+        if self.with_session(session_id, |_| ()).await?.is_none() {
+            return Err(GameDesignerError::SessionNotFound(session_id.to_string()).into());
+        }
+
+        let events = self.events.read_since(session_id, cursor, limit)?;
+        let next_cursor = events.last().map_or(cursor, |event| event.seq + 1);
+
+        Ok((events, next_cursor))
+    }
+
+    /// Reads the events of a session matching `filter`, oldest first, at
+    /// most the `limit` most recent ones.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn session_history(
+        &self,
+        session_id: &str,
+        filter: &EventFilter,
+        limit: usize,
+    ) -> Result<Vec<SessionEvent>> {
+        filter.validate()?;
+
+        if self.with_session(session_id, |_| ()).await?.is_none() {
+            return Err(GameDesignerError::SessionNotFound(session_id.to_string()).into());
+        }
+
+        self.events.history(session_id, filter, limit)
+    }
+
+    /// Restores the session to its state before the most recent change that
+    /// was not undone yet. With `expected_seq`, the undo is refused unless
+    /// that change starts at this event, so that a client doesn't revert a
+    /// change another client made in the meantime.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn undo(&self, session_id: &str, expected_seq: Option<u64>) -> Result<UndoOutcome> {
+        let _session = self.checkout(session_id).await?;
+
+        let (seq, snapshot) = self
+            .snapshots
+            .latest(session_id)?
+            .ok_or_else(|| anyhow::anyhow!("Nothing to undo in session '{}'", session_id))?;
+
+        if let Some(expected_seq) = expected_seq
+            && expected_seq != seq
+        {
+            return Err(anyhow::anyhow!(
+                "The last change of session '{}' starts at event {}, not {}: the session was \
+                 changed in the meantime. Check sessionHistory and retry.",
+                session_id,
+                seq,
+                expected_seq
+            ));
         }
 
-        // Check if session file already exists
-        let session_file_path = format!("{}/{}.json", self.persistence_path, session_id);
-        if Path::new(&session_file_path).exists() {
+        // Events of changes that were undone before are not part of this one
+        let mut events = self.events.read_since(session_id, seq, usize::MAX)?;
+        let undone_ranges = events
+            .iter()
+            .filter(|event| event.kind == EventKind::SessionUndone)
+            .filter_map(|event| Some((event.data.get("from")?.as_u64()?, event.seq)))
+            .collect::<Vec<_>>();
+        events.retain(|event| {
+            !undone_ranges
+                .iter()
+                .any(|(from, to)| (*from..=*to).contains(&event.seq))
+        });
+
+        let restored: SessionState = serde_json::from_str(&snapshot)?;
+        self.storage
+            .write(Path::new(&self.session_file_path(session_id)?), &snapshot)?;
+        self.admit(
+            &mut self.sessions.lock().unwrap(),
+            session_id.to_string(),
+            restored,
+        );
+        self.snapshots.remove(session_id, seq)?;
+
+        self.emit(
+            EventKind::SessionUndone,
+            session_id,
+            json!({
+                "from": seq,
+                "events": events.iter().map(|event| event.kind).collect::<Vec<_>>(),
+            }),
+        );
+
+        Ok(UndoOutcome { seq, events })
+    }
+
+    /// Creates a new session with the given ID, initial description and
+    /// designer persona, optionally seeded from a genre template, with the
+    /// settings of `setup`. An imported design document is kept and its
+    /// pillars are added to the design memory. The session is written once,
+    /// so a failed setup leaves no session behind.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn create_session(
+        &self,
+        session_id: String,
+        description: String,
+        persona: Persona,
+        template: Option<&GenreTemplate>,
+        setup: SessionSetup,
+    ) -> Result<()> {
+        validate_session_name(&session_id)?;
+
+        let _lock = self.locks.lock(&session_id).await;
+
+        // Check if session already exists in memory or has a file
+        if self.sessions.lock().unwrap().contains_key(&session_id)
+            || self.session_exists(&session_id)
+        {
             return Err(anyhow::anyhow!("Session '{}' already exists", session_id));
         }
 
-        let new_session = SessionState::new(session_id.clone(), description);
+        let mut new_session = SessionState::new(session_id.clone(), description);
+        new_session.persona = persona;
+        if let Some(template) = template {
+            template.apply(&mut new_session);
+        }
+        new_session.language = setup.language.clone();
+        new_session.metadata = setup.metadata.clone();
+        new_session.engine = setup.engine;
+
+        let pillar_count = setup.document.as_ref().map(|(_, pillars)| pillars.len());
+        if let Some((markdown, pillars)) = setup.document {
+            document::save(
+                self.storage.as_ref(),
+                Path::new(&self.persistence_path),
+                &session_id,
+                &markdown,
+            )?;
+            new_session
+                .design_memory
+                .extend(pillars.into_iter().map(|pillar| DesignDecision {
+                    decision: pillar,
+                    source_feature: None,
+                }));
+        }
+
+        new_session.repo_path = setup.repo.clone();
+        if let Some(feature_name) = new_session.next_feature_to_implement.clone() {
+            record_base_commit(&mut new_session, &feature_name).await;
+        }
 
         // Save to file
         let session_json = serde_json::to_string_pretty(&new_session)?;
-        fs::write(&session_file_path, session_json)?;
+        self.storage.write(
+            Path::new(&self.session_file_path(&session_id)?),
+            &session_json,
+        )?;
+
+        self.emit(
+            EventKind::SessionCreated,
+            &session_id,
+            json!({
+                "persona": persona.name(),
+                "template": template.map(|template| template.name.as_str()),
+                "language": setup.language,
+                "document": pillar_count.is_some(),
+                "pillars": pillar_count,
+                "metadata": setup.metadata,
+                "engine": setup.engine,
+                "repo": setup.repo,
+            }),
+        );
 
         // Add to memory
-        sessions.insert(session_id, new_session);
+        self.admit(&mut self.sessions.lock().unwrap(), session_id, new_session);
 
         Ok(())
     }
 
     /// Loads a session by ID.
+    #[tracing::instrument(skip_all, level = "debug", fields(session_id = %session_id))]
     pub async fn load_session(&self, session_id: &str) -> Result<Option<SessionState>> {
-        let mut sessions = self.sessions.write().await;
+        let mut sessions = self.sessions.lock().unwrap();
 
         // Check if session is already in memory
         if let Some(session) = sessions.get(session_id) {
@@ -70,32 +785,54 @@ impl SessionManager {
         }
 
         // Try to load from file
-        let session_file_path = format!("{}/{}.json", self.persistence_path, session_id);
+        let session = self.read_session_file(session_id)?;
+        if let Some(session) = &session {
+            self.admit(&mut sessions, session_id.to_string(), session.clone());
+        }
 
-        if Path::new(&session_file_path).exists() {
-            let session_json = fs::read_to_string(&session_file_path)?;
-            let session: SessionState = serde_json::from_str(&session_json)?;
-            sessions.insert(session_id.to_string(), session.clone());
-            Ok(Some(session))
-        } else {
-            Ok(None)
+        Ok(session)
+    }
+
+    /// Calls `f` with the session, loading it from disk first if necessary,
+    /// without copying it out of the sessions in memory. Returns `None` if
+    /// the session does not exist.
+    pub async fn with_session<T>(
+        &self,
+        session_id: &str,
+        f: impl FnOnce(&SessionState) -> T,
+    ) -> Result<Option<T>> {
+        let mut sessions = self.sessions.lock().unwrap();
+        if !sessions.contains_key(session_id) {
+            let Some(session) = self.read_session_file(session_id)? else {
+                return Ok(None);
+            };
+            self.admit(&mut sessions, session_id.to_string(), session);
         }
+
+        Ok(sessions.get(session_id).map(f))
     }
 
-    /// Saves a session state.
-    pub async fn save_session(&self, session_state: SessionState) -> Result<()> {
-        let mut sessions = self.sessions.write().await;
+    /// Reads a session from its file, bypassing the sessions in memory, so
+    /// that readers in another process see every persisted change.
+    pub fn read_session(&self, session_id: &str) -> Result<Option<SessionState>> {
+        let path = self.session_file_path(session_id)?;
 
-        // Save to file
-        let session_file_path = format!("{}/{}.json", self.persistence_path, session_state.id);
-        let session_json = serde_json::to_string_pretty(&session_state)?;
+        if !self.storage.exists(Path::new(&path)) {
+            return Ok(None);
+        }
 
-        fs::write(&session_file_path, session_json)?;
+        Ok(Some(serde_json::from_str(
+            &self.storage.read_to_string(Path::new(&path))?,
+        )?))
+    }
 
-        // Update in memory
-        sessions.insert(session_state.id.clone(), session_state);
+    /// Saves a session state.
+    #[tracing::instrument(skip_all, level = "debug", fields(session_id = %session_state.id))]
+    pub async fn save_session(&self, session_state: SessionState) -> Result<()> {
+        let _lock = self.locks.lock(&session_state.id).await;
 
-        Ok(())
+        // Also updates the session in memory
+        self.persist(&session_state)
     }
 
     // TODO: Add methods for getting next feature, submitting reviews, etc.
@@ -103,68 +840,73 @@ impl SessionManager {
 
     /// Gets the next feature to implement for a session.
     /// If a next feature is already determined, returns it.
-    /// Otherwise, asks the LLM to determine the next feature.
+    /// Otherwise, asks the LLM to determine the next feature, or without an
+    /// LLM client picks the first unfinished planned feature.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
     pub async fn get_next_feature(
         &self,
         session_id: &str,
         llm_client: Option<&crate::game_design::DesignerLlmClient>,
     ) -> Result<String> {
-        let mut sessions = self.sessions.write().await;
-
-        // Try to load from file if not in memory
-        if !sessions.contains_key(session_id) {
-            let session_file_path = format!("{}/{}.json", self.persistence_path, session_id);
-            if Path::new(&session_file_path).exists() {
-                let session_json = fs::read_to_string(&session_file_path)?;
-                let session: SessionState = serde_json::from_str(&session_json)?;
-                sessions.insert(session_id.to_string(), session);
-            } else {
-                return Err(anyhow::anyhow!("Session '{}' not found", session_id));
-            }
-        }
-
-        // Get the session
-        let session = sessions.get_mut(session_id).unwrap();
+        // Get a copy of the session, loading it from file if not in memory
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
 
         // If we already have a next feature determined, return it
-        if let Some(feature_name) = &session.next_feature_to_implement {
+        if let Some(feature_name) = session.next_feature_to_implement.clone() {
             // Find the feature in planned_features
-            if let Some(feature) = session
+            if let Some(index) = session
                 .planned_features
                 .iter()
-                .find(|f| &f.name == feature_name)
+                .position(|f| f.name == feature_name)
             {
-                return Ok(feature.description.clone());
-            }
-        }
+                if session.planned_features[index].status == FeatureStatus::AwaitingApproval {
+                    return Err(anyhow::anyhow!(
+                        "Feature '{}' was accepted by the designer and waits for human approval \
+                         with humanApprove",
+                        feature_name
+                    ));
+                }
 
-        // If we don't have an LLM client, we can't generate a new feature
-        let llm_client = llm_client
-            .ok_or_else(|| anyhow::anyhow!("LLM client not available to generate next feature"))?;
+                let hints_added = fill_implementation_hints(session, index, llm_client).await;
+                let base_recorded = record_base_commit(session, &feature_name).await;
 
-        // Generate a prompt for the LLM to determine the next feature
-        let mut prompt = format!(
-            "Based on this game design document:\n{}\n\n",
-            session.initial_description
-        );
+                if hints_added || base_recorded {
+                    self.persist(session)?;
+                }
 
-        // Add information about already planned features
-        if !session.planned_features.is_empty() {
-            prompt.push_str("Already planned features:\n");
-            for feature in &session.planned_features {
-                prompt.push_str(&format!("- {} ({:?})\n", feature.name, feature.status));
+                return Ok(session.planned_features[index].specification());
             }
-            prompt.push('\n');
         }
 
-        // Add information about implemented features
-        if !session.implemented_features_reports.is_empty() {
-            prompt.push_str("Already implemented features with their implementation reports:\n");
-            for (feature_name, report) in &session.implemented_features_reports {
-                prompt.push_str(&format!("- {}: {}\n", feature_name, report));
-            }
-            prompt.push('\n');
-        }
+        // Without an LLM client, hand out the next already planned feature
+        let Some(llm_client) = llm_client else {
+            let feature = session
+                .planned_features
+                .iter()
+                .find(|f| {
+                    matches!(
+                        f.status,
+                        FeatureStatus::Planned
+                            | FeatureStatus::InProgress
+                            | FeatureStatus::NeedsRework
+                    )
+                })
+                .ok_or(GameDesignerError::LlmUnavailable)?;
+
+            let feature_name = feature.name.clone();
+            let specification = feature.specification();
+
+            record_base_commit(session, &feature_name).await;
+            session.next_feature_to_implement = Some(feature_name);
+            self.persist(session)?;
+
+            return Ok(specification);
+        };
+
+        // Generate a prompt for the LLM to determine the next feature
+        let prefix = self.feature_context(session, llm_client).await;
+        let mut prompt = prefix.clone();
 
         prompt.push_str(
             "Please provide the next small, focused feature that should be implemented. \
@@ -172,21 +914,49 @@ impl SessionManager {
              (e.g., a single function, a small component, a basic UI element). \
              Include a brief title and a concise specification (2-3 sentences) that explains \
              what needs to be implemented and why it's important. \
-             Format your response as JSON with 'name' and 'description' fields:\n\
-             {\n  \"name\": \"Feature Title\",\n  \"description\": \"Concise specification...\"\n}\n\
+             Also list the art and audio assets the feature needs (sprites, animations, \
+             sound effects, music, shaders, 3D models, fonts), or an empty list if none. \
+             You must estimate the effort (S, M, L or XL) and risk (low, medium or high) of the \
+             feature and name the development milestone of the design document it belongs to. \
+             You must list 2-5 acceptance criteria: concrete, verifiable statements that a review \
+             can check the implementation against. \
+             Format your response as JSON with 'name', 'description', 'acceptance_criteria', \
+             'estimated_effort', 'risk', 'milestone' and 'required_assets' fields:\n\
+             {\n  \"name\": \"Feature Title\",\n  \"description\": \"Concise specification...\",\n  \
+             \"acceptance_criteria\": [\"Verifiable criterion\"],\n  \
+             \"estimated_effort\": \"S|M|L|XL\",\n  \"risk\": \"low|medium|high\",\n  \
+             \"milestone\": \"Milestone name\",\n  \
+             \"required_assets\": [{\"kind\": \"sprite|animation|sfx|music|shader|model|font|other\", \
+             \"name\": \"Asset name\", \"description\": \"What it must show or sound like\"}]\n}\n\
              Only return the JSON, nothing else."
         );
 
-        let messages = vec![
+        if session.ideas.iter().any(|idea| idea.feature.is_none()) {
+            prompt.push_str(IDEA_INSTRUCTION);
+        }
+
+        let due_debt = debt::paydown_due(session);
+        if !due_debt.is_empty() {
+            prompt.push_str(&debt::paydown_instruction(&due_debt));
+        }
+
+        if let Some(instruction) = scope::calibration_instruction(session) {
+            prompt.push_str(&instruction);
+        }
+
+        if let Some(engine) = session.engine {
+            prompt.push_str(&format!(
+                "\n\nThe game is built with {}. Add an 'implementation_hints' string field with \
+                 3-6 concise bullet points on how to implement the feature in this engine: which \
+                 engine systems and APIs to use, how to structure the code and pitfalls to avoid.",
+                engine.description()
+            ));
+        }
+
+        let mut messages = vec![
             crate::game_design::designer_llm::ChatMessage {
                 role: "system".to_string(),
-                content: "You are an expert game designer and software architect. \
-                         Your task is to determine the next small, focused feature to implement in a \
-                         game development project. The feature should be something that can be \
-                         completed quickly (like a single function, small component, or basic UI element). \
-                         You will be given the game design document and information about what has \
-                         already been planned and implemented. Respond with a JSON object containing \
-                         the feature name and a concise description (2-3 sentences).".to_string(),
+                content: session.system_prompt(prompts::NEXT_FEATURE),
             },
             crate::game_design::designer_llm::ChatMessage {
                 role: "user".to_string(),
@@ -195,101 +965,536 @@ impl SessionManager {
         ];
 
         // Call the LLM to get the next feature
-        let response = llm_client.call_llm(messages).await?;
+        let response = llm_client
+            .call_llm_with_prefix(messages.clone(), &prefix)
+            .await?;
+        let (mut new_feature, mut json_value) = parse_next_feature(&response)?;
 
-        // Try to parse the response as JSON
-        match serde_json::from_str::<serde_json::Value>(&response) {
-            Ok(json_value) => {
-                if let (Some(name), Some(description)) = (
-                    json_value.get("name").and_then(|v| v.as_str()),
-                    json_value.get("description").and_then(|v| v.as_str()),
-                ) {
-                    let feature_name = name.to_string();
-                    let feature_description = description.to_string();
-
-                    // Add the feature to planned features
-                    let new_feature = crate::game_design::state::Feature {
-                        name: feature_name.clone(),
-                        description: feature_description.clone(),
-                        status: crate::game_design::state::FeatureStatus::Planned,
-                    };
-
-                    session.planned_features.push(new_feature);
-                    session.next_feature_to_implement = Some(feature_name.clone());
-
-                    // Save the updated session
-                    let session_file_path =
-                        format!("{}/{}.json", self.persistence_path, session.id);
-                    let session_json = serde_json::to_string_pretty(&*session)?;
-                    fs::write(&session_file_path, session_json)?;
-
-                    Ok(feature_description)
-                } else {
-                    Err(anyhow::anyhow!(
-                        "LLM response did not contain expected 'name' and 'description' fields"
-                    ))
+        // Skip a proposal repeating a rejected feature, asking once for
+        // another one
+        let mut skipped = None;
+        let mut similar = graveyard::closest(session, &new_feature).await;
+        if let Some(duplicate) = similar.clone().filter(graveyard::is_duplicate) {
+            tracing::info!(
+                "Skipping '{}', which repeats the rejected '{}'",
+                new_feature.name,
+                duplicate.feature
+            );
+
+            messages[1]
+                .content
+                .push_str(&graveyard::skip_instruction(&new_feature, &duplicate));
+            let retry = llm_client
+                .call_llm_with_prefix(messages.clone(), &prefix)
+                .await
+                .and_then(|response| parse_next_feature(&response));
+            skipped = Some((new_feature.name.clone(), duplicate));
+
+            match retry {
+                Ok((feature, json)) => {
+                    new_feature = feature;
+                    json_value = json;
+                    similar = graveyard::closest(session, &new_feature).await;
                 }
+                Err(e) => tracing::warn!(
+                    "Failed to replace '{}', keeping it: {}",
+                    new_feature.name,
+                    e
+                ),
             }
-            Err(_) => Err(anyhow::anyhow!(
-                "LLM response was not valid JSON: {}",
-                response
-            )),
         }
-    }
 
-    /// Submits a feature implementation report for review.
-    /// Returns the LLM's review, which may include questions or feedback.
-    pub async fn submit_feature_review(
-        &self,
-        session_id: &str,
-        changes_made: &str,
-        llm_client: Option<&crate::game_design::DesignerLlmClient>,
-    ) -> Result<String> {
-        let mut sessions = self.sessions.write().await;
+        // Check the specification against the design before handing it out,
+        // and regenerate it once if the check finds problems
+        let mut checked = None;
+        if spec_check::enabled() {
+            match spec_check::check(llm_client, session, &new_feature).await {
+                Ok(check) => {
+                    let mut regenerated = false;
+                    if !check.consistent() {
+                        tracing::info!(
+                            "Regenerating '{}': {}",
+                            new_feature.name,
+                            check.problems.join("; ")
+                        );
 
-        // Try to load from file if not in memory
-        if !sessions.contains_key(session_id) {
-            let session_file_path = format!("{}/{}.json", self.persistence_path, session_id);
-            if Path::new(&session_file_path).exists() {
-                let session_json = fs::read_to_string(&session_file_path)?;
-                let session: SessionState = serde_json::from_str(&session_json)?;
-                sessions.insert(session_id.to_string(), session);
-            } else {
-                return Err(anyhow::anyhow!("Session '{}' not found", session_id));
+                        let mut messages = messages;
+                        messages[1]
+                            .content
+                            .push_str(&spec_check::regeneration_instruction(
+                                &new_feature,
+                                &check.problems,
+                            ));
+                        let retry = llm_client
+                            .call_llm_with_prefix(messages, &prefix)
+                            .await
+                            .and_then(|response| parse_next_feature(&response));
+                        match retry {
+                            Ok((feature, json)) => {
+                                new_feature = feature;
+                                json_value = json;
+                                regenerated = true;
+                            }
+                            Err(e) => tracing::warn!(
+                                "Failed to regenerate '{}', keeping it: {}",
+                                new_feature.name,
+                                e
+                            ),
+                        }
+                    }
+                    checked = Some((check, regenerated));
+                }
+                Err(e) => tracing::warn!("Failed to check '{}': {}", new_feature.name, e),
             }
         }
 
-        // Get the session
-        let session = sessions.get_mut(session_id).unwrap();
+        if matches!(checked, Some((_, true))) {
+            similar = graveyard::closest(session, &new_feature).await;
+        }
+        new_feature.similar_rejected = similar;
 
-        // If we don't have an LLM client, we can't perform a review
-        let llm_client = llm_client
-            .ok_or_else(|| anyhow::anyhow!("LLM client not available to perform feature review"))?;
+        let feature_name = new_feature.name.clone();
+        let feature_description = new_feature.description.clone();
 
-        // Get the current feature being implemented
-        let current_feature_name = session
-            .next_feature_to_implement
-            .clone()
-            .ok_or_else(|| anyhow::anyhow!("No next feature to implement"))?;
+        let idea = json_value.get("idea").and_then(|v| v.as_str());
+        mark_idea_used(session, idea, &feature_name);
 
-        // Find the feature in planned_features
-        let feature_index = session
-            .planned_features
-            .iter()
-            .position(|f| f.name == current_feature_name)
-            .ok_or_else(|| {
+        let debt_ids = json_value
+            .get("debt")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|id| id.as_u64())
+            .filter_map(|id| u32::try_from(id).ok())
+            .collect::<Vec<_>>();
+        debt::schedule(session, &debt_ids, &feature_name);
+
+        // Add the feature to planned features
+        session.planned_features.push(new_feature);
+        session.next_feature_to_implement = Some(feature_name.clone());
+        let index = session.planned_features.len() - 1;
+        fill_implementation_hints(session, index, Some(llm_client)).await;
+        record_base_commit(session, &feature_name).await;
+        let specification = session.planned_features[index].specification();
+
+        // Save the updated session
+        self.persist(session)?;
+
+        if let Some((proposal, duplicate)) = skipped {
+            self.emit(
+                EventKind::DuplicateSkipped,
+                session_id,
+                json!({
+                    "feature": proposal,
+                    "rejected": duplicate.feature,
+                    "score": duplicate.score,
+                }),
+            );
+        }
+
+        if let Some((check, regenerated)) = checked {
+            self.emit(
+                EventKind::SpecChecked,
+                session_id,
+                json!({
+                    "feature": feature_name,
+                    "problems": check.problems,
+                    "regenerated": regenerated,
+                }),
+            );
+        }
+
+        self.emit(
+            EventKind::FeatureProposed,
+            session_id,
+            json!({ "feature": feature_name, "description": feature_description }),
+        );
+
+        Ok(specification)
+    }
+
+    /// Asks the LLM for `count` alternative next features, ranked with a
+    /// rationale, and keeps them until one is picked with
+    /// [`Self::select_candidate`]. Candidates of an earlier proposal that
+    /// were not picked count as passed over, and proposals repeating a
+    /// planned feature or one in the [`graveyard`] are dropped.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn propose_candidates(
+        &self,
+        session_id: &str,
+        count: usize,
+        llm_client: Option<&crate::game_design::DesignerLlmClient>,
+    ) -> Result<Vec<FeatureCandidate>> {
+        let llm_client = llm_client.ok_or(GameDesignerError::LlmUnavailable)?;
+        let count = count.clamp(1, MAX_FEATURE_CANDIDATES);
+
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        if let Some(feature_name) = &session.next_feature_to_implement {
+            return Err(anyhow::anyhow!(
+                "'{}' is still the next feature to implement; submit it for review before \
+                 asking for candidates",
+                feature_name
+            ));
+        }
+
+        let prefix = self.feature_context(session, llm_client).await;
+        let mut prompt = prefix.clone();
+
+        prompt.push_str(&format!(
+            "Please propose {} alternative small, focused features that could be implemented \
+             next, each completable in a short amount of time, ranked from the one you \
+             recommend most to the one you recommend least. Give each a brief title, a concise \
+             specification (2-3 sentences), 2-5 verifiable acceptance criteria, a one-sentence \
+             rationale for its rank, the art and audio assets it needs, an effort estimate (S, M, \
+             L or XL), a risk (low, medium or high) and the development milestone of the design \
+             document it belongs to. \
+             Format your response as a JSON array of objects with 'name', 'description', \
+             'acceptance_criteria', 'rationale', 'estimated_effort', 'risk', 'milestone' and \
+             'required_assets' fields, best first:\n\
+             [{{\"name\": \"Feature Title\", \"description\": \"Concise specification...\", \
+             \"acceptance_criteria\": [\"Verifiable criterion\"], \
+             \"rationale\": \"Why it ranks here\", \"estimated_effort\": \"S|M|L|XL\", \
+             \"risk\": \"low|medium|high\", \"milestone\": \"Milestone name\", \
+             \"required_assets\": [{{\"kind\": \"sprite|animation|sfx|music|shader|model|font|other\", \
+             \"name\": \"Asset name\", \"description\": \"What it must show or sound like\"}}]}}]\n\
+             Only return the JSON, nothing else.",
+            count
+        ));
+
+        if session.ideas.iter().any(|idea| idea.feature.is_none()) {
+            prompt.push_str(IDEA_INSTRUCTION);
+        }
+
+        if let Some(instruction) = scope::calibration_instruction(session) {
+            prompt.push_str(&instruction);
+        }
+
+        let messages = vec![
+            crate::game_design::designer_llm::ChatMessage {
+                role: "system".to_string(),
+                content: session.system_prompt(prompts::FEATURE_CANDIDATES),
+            },
+            crate::game_design::designer_llm::ChatMessage {
+                role: "user".to_string(),
+                content: prompt,
+            },
+        ];
+
+        let response = llm_client.call_llm_with_prefix(messages, &prefix).await?;
+
+        let json = crate::game_design::designer_llm::extract_json(&response)
+            .ok_or_else(|| anyhow::anyhow!("LLM response was not valid JSON: {}", response))?;
+        let proposals = match serde_json::from_str::<serde_json::Value>(json)? {
+            serde_json::Value::Array(proposals) => proposals,
+            serde_json::Value::Object(mut object) => match object.remove("candidates") {
+                Some(serde_json::Value::Array(proposals)) => proposals,
+                // A single feature instead of a list
+                _ => vec![serde_json::Value::Object(object)],
+            },
+            _ => Vec::new(),
+        };
+
+        let mut known = session
+            .planned_features
+            .iter()
+            .map(|feature| feature.name.to_lowercase())
+            .chain(graveyard::buried_names(session))
+            .collect::<Vec<_>>();
+
+        let mut parsed = Vec::new();
+
+        for proposal in proposals {
+            let Some(feature) = parse_feature(&proposal) else {
+                tracing::debug!("Dropping candidate without name, description or criteria");
+                continue;
+            };
+
+            let name = feature.name.to_lowercase();
+            if known.contains(&name) {
+                tracing::debug!("Dropping candidate '{}' proposed before", feature.name);
+                continue;
+            }
+            known.push(name);
+
+            parsed.push((feature, proposal));
+        }
+
+        let features = parsed
+            .iter()
+            .map(|(feature, _)| feature.clone())
+            .collect::<Vec<_>>();
+        let similarities = graveyard::compare(session, &features).await;
+
+        let mut candidates = Vec::new();
+        let mut skipped = Vec::new();
+
+        for ((mut feature, proposal), similar) in parsed.into_iter().zip(similarities) {
+            if let Some(duplicate) = similar.clone().filter(graveyard::is_duplicate) {
+                tracing::debug!(
+                    "Dropping candidate '{}' repeating the rejected '{}'",
+                    feature.name,
+                    duplicate.feature
+                );
+                skipped.push((feature.name, duplicate));
+                continue;
+            }
+            feature.similar_rejected = similar;
+
+            candidates.push(FeatureCandidate {
+                rank: candidates.len() as u32 + 1,
+                rationale: proposal
+                    .get("rationale")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string(),
+                idea: proposal
+                    .get("idea")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string),
+                feature,
+            });
+
+            if candidates.len() == count {
+                break;
+            }
+        }
+
+        if candidates.is_empty() {
+            return Err(anyhow::anyhow!(
+                "LLM proposed no features that were not planned, passed over or rejected before: {}",
+                response
+            ));
+        }
+
+        let unpicked = std::mem::replace(&mut session.feature_candidates, candidates.clone());
+        session.passed_over_candidates.extend(unpicked);
+        self.persist(session)?;
+
+        for (proposal, duplicate) in skipped {
+            self.emit(
+                EventKind::DuplicateSkipped,
+                session_id,
+                json!({
+                    "feature": proposal,
+                    "rejected": duplicate.feature,
+                    "score": duplicate.score,
+                }),
+            );
+        }
+
+        self.emit(
+            EventKind::CandidatesProposed,
+            session_id,
+            json!({
+                "candidates": candidates
+                    .iter()
+                    .map(|candidate| candidate.feature.name.as_str())
+                    .collect::<Vec<_>>(),
+            }),
+        );
+
+        Ok(candidates)
+    }
+
+    /// Plans the candidate named `choice`, or ranked `choice`, as the next
+    /// feature. The other candidates are passed over and not proposed again.
+    /// Returns the feature's specification.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn select_candidate(
+        &self,
+        session_id: &str,
+        choice: &str,
+        llm_client: Option<&crate::game_design::DesignerLlmClient>,
+    ) -> Result<String> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        if session.feature_candidates.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Session '{}' has no feature candidates; call nextFeature with candidates first",
+                session_id
+            ));
+        }
+
+        let choice = choice.trim();
+        let index = session
+            .feature_candidates
+            .iter()
+            .position(|candidate| {
+                candidate.feature.name.eq_ignore_ascii_case(choice)
+                    || choice.parse() == Ok(candidate.rank)
+            })
+            .ok_or_else(|| {
+                let names = session
+                    .feature_candidates
+                    .iter()
+                    .map(|candidate| format!("{}. {}", candidate.rank, candidate.feature.name))
+                    .collect::<Vec<_>>();
                 anyhow::anyhow!(
-                    "Feature '{}' not found in planned features",
-                    current_feature_name
+                    "'{}' is not a candidate. Candidates: {}",
+                    choice,
+                    names.join(", ")
                 )
             })?;
 
-        // Generate a prompt for the LLM to review the implementation
-        let mut prompt = format!(
-            "Based on this game design document:\n{}\n\n",
-            session.initial_description
+        let mut candidates = std::mem::take(&mut session.feature_candidates);
+        let picked = candidates.remove(index);
+        session.passed_over_candidates.extend(candidates);
+
+        let feature_name = picked.feature.name.clone();
+        let feature_description = picked.feature.description.clone();
+        mark_idea_used(session, picked.idea.as_deref(), &feature_name);
+
+        session.planned_features.push(picked.feature);
+        session.next_feature_to_implement = Some(feature_name.clone());
+        let index = session.planned_features.len() - 1;
+        fill_implementation_hints(session, index, llm_client).await;
+        record_base_commit(session, &feature_name).await;
+        let specification = session.planned_features[index].specification();
+
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::FeatureProposed,
+            session_id,
+            json!({
+                "feature": feature_name,
+                "description": feature_description,
+                "rank": picked.rank,
+                "rationale": picked.rationale,
+            }),
+        );
+
+        Ok(specification)
+    }
+
+    /// Rejects the feature proposed to be implemented next before any work
+    /// on it, burying it in the [`graveyard`] so that later proposals
+    /// repeating it are skipped. Technical debt scheduled for it is open
+    /// again. Returns the buried feature.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn reject_feature(
+        &self,
+        session_id: &str,
+        reason: Option<&str>,
+        author: Option<&str>,
+    ) -> Result<RejectedFeature> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        let feature_name = session.next_feature_to_implement.clone().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Session '{}' has no proposed feature to reject; call nextFeature first",
+                session_id
+            )
+        })?;
+        let index = session
+            .planned_features
+            .iter()
+            .position(|feature| feature.name == feature_name)
+            .ok_or_else(|| anyhow::anyhow!("Feature '{}' is not planned", feature_name))?;
+
+        let feature = &session.planned_features[index];
+        if feature.status != FeatureStatus::Planned
+            || feature.work_started_at.is_some()
+            || feature.time_spent_minutes > 0
+            || !feature.commits.is_empty()
+        {
+            return Err(anyhow::anyhow!(
+                "Work on '{}' has already started; only proposals nobody worked on can be \
+                 rejected",
+                feature_name
+            ));
+        }
+
+        let feature = session.planned_features.remove(index);
+        session.next_feature_to_implement = None;
+        debt::unschedule(session, &feature_name);
+
+        let rejected = RejectedFeature {
+            feature,
+            reason: reason
+                .map(str::trim)
+                .filter(|reason| !reason.is_empty())
+                .map(str::to_string),
+            author: author.map(str::to_string),
+            rejected_at: self.clock.now(),
+        };
+        session.graveyard.push(rejected.clone());
+
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::FeatureRejected,
+            session_id,
+            json!({
+                "feature": feature_name,
+                "reason": rejected.reason,
+                "author": author,
+            }),
         );
 
+        Ok(rejected)
+    }
+
+    /// Submits a feature implementation report for review, recording the
+    /// commits that implemented it and the time spent. A running work timer
+    /// is stopped. With `include_diff` the repository diff since the feature's
+    /// base commit is attached to the review prompt. The review goes as deep
+    /// as `depth`, or the session's review depth, which is recorded with the
+    /// feature. With `panel`, or the session's review panel setting, the
+    /// report goes to a panel of reviewers whose consensus is the review.
+    /// The `attachments` are stored with the session and, if the model can
+    /// see images, shown to the reviewers.
+    /// Returns the LLM's review, which may include questions or feedback.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn submit_feature_review(
+        &self,
+        session_id: &str,
+        submission: ReviewSubmission,
+        llm_client: Option<&crate::game_design::DesignerLlmClient>,
+    ) -> Result<String> {
+        let changes_made = submission.changes_made.as_str();
+        let commits = submission.commits.as_slice();
+        let time_spent_minutes = submission.time_spent_minutes;
+        let attachments = submission.attachments.as_slice();
+        let author = submission.author.as_deref();
+
+        // Get a copy of the session, loading it from file if not in memory
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        // If we don't have an LLM client, we can't perform a review
+        let llm_client = llm_client.ok_or(GameDesignerError::LlmUnavailable)?;
+
+        // Get the current feature being implemented
+        let current_feature_name = session
+            .next_feature_to_implement
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("No next feature to implement"))?;
+
+        // Find the feature in planned_features
+        let feature_index = session
+            .planned_features
+            .iter()
+            .position(|f| f.name == current_feature_name)
+            .ok_or_else(|| GameDesignerError::FeatureNotFound(current_feature_name.to_string()))?;
+
+        // Another review would change the decision a person is asked to sign
+        if session.planned_features[feature_index].status == FeatureStatus::AwaitingApproval {
+            return Err(anyhow::anyhow!(
+                "Feature '{}' was accepted by the designer and waits for human approval with \
+                 humanApprove",
+                current_feature_name
+            ));
+        }
+
+        // Generate a prompt for the LLM to review the implementation
+        let mut prompt = memory::design_context(session);
+
         // Add information about the feature that was implemented
         let feature = &session.planned_features[feature_index];
         prompt.push_str(&format!(
@@ -303,26 +1508,88 @@ impl SessionManager {
             changes_made
         ));
 
+        // Add the actual repository changes so the report can be verified
+        let mut reviewed_commit = None;
+        if submission.include_diff {
+            let repo = session.repo_path.clone().ok_or_else(|| {
+                anyhow::anyhow!("No repository configured for session '{}'", session_id)
+            })?;
+            let base = feature.base_commit.clone().ok_or_else(|| {
+                anyhow::anyhow!("No base commit recorded for feature '{}'", feature.name)
+            })?;
+
+            let diff = git::diff_since(Path::new(&repo), &base).await?;
+            reviewed_commit = Some(git::head(Path::new(&repo)).await?);
+
+            prompt.push_str(&format!(
+                "Actual changes in the repository since {} (git diff, possibly truncated):\n```diff\n{}\n```\n\n",
+                base,
+                context::truncate_middle(&diff, REVIEW_DIFF_TOKENS)
+            ));
+        }
+
+        // List the attachments, showing the images among them to the
+        // reviewers if the model can see them. They are stored with the
+        // session once the review is in.
+        let session_dir = Path::new(&self.persistence_path).join(session_id);
+        let stored = attachments
+            .iter()
+            .map(|upload| attachments::describe(&session_dir, upload))
+            .collect::<Vec<_>>();
+        let images = attachments::images(attachments);
+        prompt.push_str(&attachments::section(&stored, !images.is_empty()));
+
+        // Add the genre-specific review criteria of the session's template
+        if !session.review_criteria.is_empty() {
+            prompt.push_str("Genre-specific review criteria (check each of these too):\n");
+            for criterion in &session.review_criteria {
+                prompt.push_str(&format!("- {}\n", criterion));
+            }
+            prompt.push('\n');
+        }
+
+        // Add the feature's acceptance criteria, checked one by one
+        let acceptance_criteria = session.planned_features[feature_index]
+            .acceptance_criteria
+            .clone();
+        if !acceptance_criteria.is_empty() {
+            prompt.push_str(&criteria::instruction(&acceptance_criteria));
+        }
+
+        let depth = submission.depth.unwrap_or(session.review_depth);
+        prompt.push_str(depth.checks());
         prompt.push_str(
-            "Please review this implementation report. Check if:\n\
-             1. The implementation matches the feature specification\n\
-             2. There are any potential issues or missing elements\n\
-             3. The implementation aligns with the overall game design\n\n\
-             If you have any questions about the implementation, please ask them clearly.\n\
+            "If you have any questions about the implementation, please ask them clearly.\n\
              If you're satisfied with the implementation, respond with 'SATISFIED'.\n\
+             If it works and can be accepted but leaves technical debt behind (shortcuts, \
+             duplication, missing tests, hard-coded values), respond with 'SATISFIED WITH \
+             CAVEATS' on the first line, followed by one caveat per line as \
+             '- [high|medium|low] description'.\n\
              Otherwise, provide your feedback and any specific questions.",
         );
 
+        // A panel reviews in parallel before its chair merges the reviews
+        let panel_reviews = if submission.panel.unwrap_or(session.review_panel) {
+            let reviews = crate::game_design::designer_llm::with_images(
+                images.clone(),
+                panel::convene(llm_client, session, &prompt),
+            )
+            .await?;
+            prompt = panel::consensus_prompt(&prompt, &reviews);
+            reviews
+        } else {
+            Vec::new()
+        };
+        let system_prompt = if panel_reviews.is_empty() {
+            depth.prompt_name()
+        } else {
+            prompts::REVIEW_CONSENSUS
+        };
+
         let messages = vec![
             crate::game_design::designer_llm::ChatMessage {
                 role: "system".to_string(),
-                content: "You are an expert game designer and software architect. \
-                         Your task is to review implementation reports for game features. \
-                         You will be given the game design document, the feature specification, \
-                         and the developer's implementation report. Review the implementation \
-                         and provide feedback. If you have questions, ask them clearly. \
-                         If you're satisfied, respond with 'SATISFIED'."
-                    .to_string(),
+                content: session.system_prompt(system_prompt),
             },
             crate::game_design::designer_llm::ChatMessage {
                 role: "user".to_string(),
@@ -331,62 +1598,158 @@ impl SessionManager {
         ];
 
         // Call the LLM to review the implementation
-        let review_response = llm_client.call_llm(messages).await?;
+        let review_response =
+            crate::game_design::designer_llm::with_images(images, llm_client.call_llm(messages))
+                .await?;
 
         // Check if the LLM is satisfied or has questions
-        let is_satisfied = review_response.trim().to_uppercase() == "SATISFIED";
+        let (verdict, criteria_results) = criteria::split(&review_response, &acceptance_criteria);
+        let caveats = debt::caveats(verdict);
+        let is_satisfied = (verdict.trim().to_uppercase() == "SATISFIED" || caveats.is_some())
+            && criteria_results.iter().all(|result| result.passed);
+
+        // Extract durable decisions established by the review before anything
+        // is changed, so that a call cancelled while waiting for the LLM
+        // leaves the session as it was
+        let exchange = format!(
+            "Implementation report:\n{}\n\nReview:\n{}",
+            changes_made, review_response
+        );
+        let decisions =
+            memory::extract_decisions(llm_client, session, &current_feature_name, &exchange).await;
 
         // Store the implementation report
         session
             .implemented_features_reports
             .insert(current_feature_name.clone(), changes_made.to_string());
+        session.report_digests.remove(&current_feature_name);
+        session.review_log.push(ReviewEntry {
+            feature: current_feature_name.clone(),
+            kind: ReviewEntryKind::Report,
+            author: author.map(str::to_string),
+            content: changes_made.to_string(),
+            response: review_response.clone(),
+            criteria: criteria_results,
+            panel: panel_reviews.clone(),
+            attachments: stored.clone(),
+            submitted_at: self.clock.now(),
+        });
+
+        let feature = &mut session.planned_features[feature_index];
+        feature.add_commits(commits);
+        feature.stop_work(self.clock.now());
+        feature.time_spent_minutes += time_spent_minutes.unwrap_or(0);
+        feature.review_depth = Some(depth);
+        feature.rework.clear();
+        if reviewed_commit.is_some() {
+            feature.reviewed_commit = reviewed_commit;
+        }
+
+        // Remember durable decisions established by the review
+        match decisions {
+            Ok(decisions) => memory::remember(session, decisions, &current_feature_name),
+            Err(e) => tracing::warn!("Failed to extract design decisions: {}", e),
+        }
 
         // Update the feature status based on the review
-        if is_satisfied {
+        let mut recorded_debt = Vec::new();
+        let mut paid_debt = Vec::new();
+        let awaiting_approval = is_satisfied && approval::required();
+        if awaiting_approval {
+            // The feature stays the next one until a person signs it off,
+            // and its caveats become debt only then
+            let feature = &mut session.planned_features[feature_index];
+            feature.status = FeatureStatus::AwaitingApproval;
+            feature.pending_caveats = caveats.unwrap_or_default();
+        } else if is_satisfied {
             session.planned_features[feature_index].status =
                 crate::game_design::state::FeatureStatus::Reviewed;
             session.next_feature_to_implement = None; // Clear the next feature since this one is done
+            recorded_debt = debt::record(
+                session,
+                &current_feature_name,
+                caveats.unwrap_or_default(),
+                self.clock.now(),
+            );
+            paid_debt = debt::settle(session, &current_feature_name, self.clock.now());
         } else {
             session.planned_features[feature_index].status =
                 crate::game_design::state::FeatureStatus::NeedsRework;
         }
 
-        // Save the updated session
-        let session_file_path = format!("{}/{}.json", self.persistence_path, session.id);
-        let session_json = serde_json::to_string_pretty(&*session)?;
-        fs::write(&session_file_path, session_json)?;
+        // Save the attachments and the updated session
+        for upload in attachments {
+            attachments::store(self.storage.as_ref(), &session_dir, upload)?;
+        }
+        self.persist(session)?;
+        self.emit_debt(
+            session_id,
+            &current_feature_name,
+            &recorded_debt,
+            &paid_debt,
+        );
+
+        self.emit(
+            EventKind::ReviewSubmitted,
+            session_id,
+            json!({
+                "feature": current_feature_name,
+                "report": changes_made,
+                "commits": commits,
+                "minutes": time_spent_minutes,
+                "depth": depth,
+                "panel": panel_reviews,
+                "attachments": stored,
+                "author": author,
+            }),
+        );
+
+        let kind = if awaiting_approval {
+            EventKind::FeatureAwaitingApproval
+        } else if is_satisfied {
+            EventKind::FeatureApproved
+        } else {
+            EventKind::FeatureNeedsRework
+        };
+        self.emit(
+            kind,
+            session_id,
+            json!({
+                "feature": current_feature_name,
+                "review": review_response,
+                "commits": commits,
+                "author": author,
+            }),
+        );
+        if kind == EventKind::FeatureApproved {
+            self.emit_milestone_completed(session_id, session, &current_feature_name);
+        }
 
         Ok(review_response)
     }
 
-    /// Submits a reply to questions from a feature review.
+    /// Submits a reply to questions from a feature review, recording any
+    /// additional commits and time spent on the rework. A running work timer
+    /// is stopped. The reply that reaches the follow-up limit forces the
+    /// designer to accept or reject with a rework list, and further replies
+    /// are refused.
     /// Returns the LLM's response to the reply, which may include further questions or acceptance.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
     pub async fn submit_review_reply(
         &self,
         session_id: &str,
         content: &str,
+        commits: &[String],
+        time_spent_minutes: Option<u64>,
+        author: Option<&str>,
         llm_client: Option<&crate::game_design::DesignerLlmClient>,
     ) -> Result<String> {
-        let mut sessions = self.sessions.write().await;
-
-        // Try to load from file if not in memory
-        if !sessions.contains_key(session_id) {
-            let session_file_path = format!("{}/{}.json", self.persistence_path, session_id);
-            if Path::new(&session_file_path).exists() {
-                let session_json = fs::read_to_string(&session_file_path)?;
-                let session: SessionState = serde_json::from_str(&session_json)?;
-                sessions.insert(session_id.to_string(), session);
-            } else {
-                return Err(anyhow::anyhow!("Session '{}' not found", session_id));
-            }
-        }
-
-        // Get the session
-        let session = sessions.get_mut(session_id).unwrap();
+        // Get a copy of the session, loading it from file if not in memory
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
 
         // If we don't have an LLM client, we can't process the reply
-        let llm_client = llm_client
-            .ok_or_else(|| anyhow::anyhow!("LLM client not available to process review reply"))?;
+        let llm_client = llm_client.ok_or(GameDesignerError::LlmUnavailable)?;
 
         // Get the current feature being reviewed
         let current_feature_name = session
@@ -399,12 +1762,15 @@ impl SessionManager {
             .planned_features
             .iter()
             .position(|f| f.name == current_feature_name)
-            .ok_or_else(|| {
-                anyhow::anyhow!(
-                    "Feature '{}' not found in planned features",
-                    current_feature_name
-                )
-            })?;
+            .ok_or_else(|| GameDesignerError::FeatureNotFound(current_feature_name.to_string()))?;
+
+        if session.planned_features[feature_index].status == FeatureStatus::AwaitingApproval {
+            return Err(anyhow::anyhow!(
+                "Feature '{}' was accepted by the designer and waits for human approval with \
+                 humanApprove",
+                current_feature_name
+            ));
+        }
 
         // Check that the feature is in NeedsRework status
         if session.planned_features[feature_index].status
@@ -416,6 +1782,20 @@ impl SessionManager {
             ));
         }
 
+        // Stop a review that keeps asking questions: the last round must
+        // decide, and a rejection needs a new report
+        let max_follow_ups = followups::max_follow_ups();
+        let rounds = followups::rounds(session, &current_feature_name);
+        if max_follow_ups > 0 && rounds >= max_follow_ups {
+            return Err(GameDesignerError::InvalidArguments(format!(
+                "The review of '{}' reached its limit of {} follow-up rounds and was sent back \
+                 for rework; submit the reworked feature with featureReview",
+                current_feature_name, max_follow_ups
+            ))
+            .into());
+        }
+        let escalated = max_follow_ups > 0 && rounds + 1 >= max_follow_ups;
+
         // Get the previous implementation report
         let previous_report = session
             .implemented_features_reports
@@ -428,13 +1808,7 @@ impl SessionManager {
             })?;
 
         // Generate a prompt for the LLM to process the reply
-        let mut prompt = format!(
-            "Based on this game design document:
-{}
-
-",
-            session.initial_description
-        );
+        let mut prompt = memory::design_context(session);
 
         // Add information about the feature that was implemented
         let feature = &session.planned_features[feature_index];
@@ -465,22 +1839,31 @@ Description: {}
             content
         ));
 
+        // Add the feature's acceptance criteria, checked one by one
+        let acceptance_criteria = session.planned_features[feature_index]
+            .acceptance_criteria
+            .clone();
+        if !acceptance_criteria.is_empty() {
+            prompt.push_str(&criteria::instruction(&acceptance_criteria));
+        }
+
         prompt.push_str(
             "Please review the developer's reply to your previous questions. 
              If you're satisfied with the answers and the implementation, respond with 'SATISFIED'. 
+             If it can be accepted but leaves technical debt behind, respond with 'SATISFIED WITH \
+             CAVEATS' on the first line, followed by one caveat per line as \
+             '- [high|medium|low] description'. 
              If you have additional questions or concerns, please ask them clearly. 
              If the implementation still needs work, explain what is missing.",
         );
+        if escalated {
+            prompt.push_str(&followups::escalation_instruction(max_follow_ups));
+        }
 
         let messages = vec![
             crate::game_design::designer_llm::ChatMessage {
                 role: "system".to_string(),
-                content: "You are an expert game designer and software architect. 
-                         Your task is to review replies to your questions about feature implementations. 
-                         You will be given the game design document, the feature specification, 
-                         the previous implementation report, and the developer's reply to your questions. 
-                         Review the reply and provide feedback. If you're satisfied, respond with 'SATISFIED'. 
-                         If you have additional questions, ask them clearly.".to_string(),
+                content: session.system_prompt(prompts::REVIEW_REPLY),
             },
             crate::game_design::designer_llm::ChatMessage {
                 role: "user".to_string(),
@@ -492,53 +1875,260 @@ Description: {}
         let reply_response = llm_client.call_llm(messages).await?;
 
         // Check if the LLM is satisfied or has more questions
-        let is_satisfied = reply_response.to_uppercase().contains("SATISFIED");
+        let (verdict, criteria_results) = criteria::split(&reply_response, &acceptance_criteria);
+        let caveats = debt::caveats(verdict);
+        let rework = followups::rework_list(verdict);
+        let is_satisfied = rework.is_none()
+            && verdict.to_uppercase().contains("SATISFIED")
+            && criteria_results.iter().all(|result| result.passed);
+        // An escalated review that still asks questions counts as a rejection
+        let rework = match rework {
+            Some(rework) => rework,
+            None if escalated && !is_satisfied => vec![verdict.trim().to_string()],
+            None => Vec::new(),
+        };
+
+        // Remember durable decisions established by the reply
+        let exchange = format!(
+            "Developer's reply:\n{}\n\nReview:\n{}",
+            content, reply_response
+        );
+        match memory::extract_decisions(llm_client, session, &current_feature_name, &exchange).await
+        {
+            Ok(decisions) => memory::remember(session, decisions, &current_feature_name),
+            Err(e) => tracing::warn!("Failed to extract design decisions: {}", e),
+        }
+
+        session.review_log.push(ReviewEntry {
+            feature: current_feature_name.clone(),
+            kind: ReviewEntryKind::Reply,
+            author: author.map(str::to_string),
+            content: content.to_string(),
+            response: reply_response.clone(),
+            criteria: criteria_results,
+            panel: Vec::new(),
+            attachments: Vec::new(),
+            submitted_at: self.clock.now(),
+        });
+
+        let feature = &mut session.planned_features[feature_index];
+        feature.add_commits(commits);
+        feature.stop_work(self.clock.now());
+        feature.time_spent_minutes += time_spent_minutes.unwrap_or(0);
+        feature.rework = rework.clone();
 
         // Update the feature status based on the reply
-        if is_satisfied {
+        let mut recorded_debt = Vec::new();
+        let mut paid_debt = Vec::new();
+        let awaiting_approval = is_satisfied && approval::required();
+        if awaiting_approval {
+            // The feature stays the next one until a person signs it off,
+            // and its caveats become debt only then
+            let feature = &mut session.planned_features[feature_index];
+            feature.status = FeatureStatus::AwaitingApproval;
+            feature.pending_caveats = caveats.unwrap_or_default();
+        } else if is_satisfied {
             session.planned_features[feature_index].status =
                 crate::game_design::state::FeatureStatus::Reviewed;
             session.next_feature_to_implement = None; // Clear the next feature since this one is done
+            recorded_debt = debt::record(
+                session,
+                &current_feature_name,
+                caveats.unwrap_or_default(),
+                self.clock.now(),
+            );
+            paid_debt = debt::settle(session, &current_feature_name, self.clock.now());
         }
         // If not satisfied, the status remains NeedsRework
 
         // Save the updated session
-        let session_file_path = format!("{}/{}.json", self.persistence_path, session.id);
-        let session_json = serde_json::to_string_pretty(&*session)?;
-        fs::write(&session_file_path, session_json)?;
+        self.persist(session)?;
+        self.emit_debt(
+            session_id,
+            &current_feature_name,
+            &recorded_debt,
+            &paid_debt,
+        );
+
+        self.emit(
+            EventKind::ReviewReplied,
+            session_id,
+            json!({
+                "feature": current_feature_name,
+                "reply": content,
+                "response": reply_response,
+                "commits": commits,
+                "minutes": time_spent_minutes,
+                "author": author,
+            }),
+        );
+
+        if is_satisfied {
+            let kind = if awaiting_approval {
+                EventKind::FeatureAwaitingApproval
+            } else {
+                EventKind::FeatureApproved
+            };
+            self.emit(
+                kind,
+                session_id,
+                json!({
+                    "feature": current_feature_name,
+                    "review": reply_response,
+                    "commits": session.planned_features[feature_index].commits,
+                    "author": author,
+                }),
+            );
+            if kind == EventKind::FeatureApproved {
+                self.emit_milestone_completed(session_id, session, &current_feature_name);
+            }
+        } else if !rework.is_empty() {
+            self.emit(
+                EventKind::FeatureNeedsRework,
+                session_id,
+                json!({
+                    "feature": current_feature_name,
+                    "review": reply_response,
+                    "rework": rework,
+                    "escalated": escalated,
+                    "author": author,
+                }),
+            );
+        }
 
         Ok(reply_response)
     }
 
+    /// Records a person's decision on a feature the designer accepted while
+    /// human approval is required. Defaults to the feature being
+    /// implemented. An approved feature is reviewed, and the caveats of the
+    /// review that accepted it are recorded as debt; a rejected one needs
+    /// rework, with the comment as its rework list. The decision must carry
+    /// its `signature` under the approver secret, see [`approval::sign`].
+    /// Returns the feature and its new status.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn human_approve(
+        &self,
+        session_id: &str,
+        feature_name: Option<&str>,
+        approved: bool,
+        author: &str,
+        comment: Option<&str>,
+        signature: &str,
+    ) -> Result<(String, FeatureStatus)> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        let feature_name = feature_name
+            .map(str::to_string)
+            .or_else(|| session.next_feature_to_implement.clone())
+            .ok_or_else(|| {
+                GameDesignerError::InvalidArguments(
+                    "No feature is being implemented; name the feature to approve".to_string(),
+                )
+            })?;
+        let feature_index = session
+            .planned_features
+            .iter()
+            .position(|f| f.name == feature_name)
+            .ok_or_else(|| GameDesignerError::FeatureNotFound(feature_name.clone()))?;
+
+        if session.planned_features[feature_index].status != FeatureStatus::AwaitingApproval {
+            return Err(GameDesignerError::InvalidArguments(format!(
+                "Feature '{}' is not awaiting approval; it is {:?}",
+                feature_name, session.planned_features[feature_index].status
+            ))
+            .into());
+        }
+        if !approved && comment.is_none() {
+            return Err(GameDesignerError::InvalidArguments(format!(
+                "Say what has to change in 'comment' to send '{}' back for rework",
+                feature_name
+            ))
+            .into());
+        }
+        approval::verify(
+            self.project(),
+            session,
+            &feature_name,
+            approved,
+            author,
+            comment,
+            signature,
+        )?;
+
+        let feature = &mut session.planned_features[feature_index];
+        feature.approval = Some(Approval {
+            approved,
+            author: author.to_string(),
+            comment: comment.map(str::to_string),
+            decided_at: self.clock.now(),
+        });
+        let caveats = std::mem::take(&mut feature.pending_caveats);
+        let mut recorded_debt = Vec::new();
+        let mut paid_debt = Vec::new();
+        if approved {
+            feature.status = FeatureStatus::Reviewed;
+            if session.next_feature_to_implement.as_deref() == Some(feature_name.as_str()) {
+                session.next_feature_to_implement = None;
+            }
+            recorded_debt = debt::record(session, &feature_name, caveats, self.clock.now());
+            paid_debt = debt::settle(session, &feature_name, self.clock.now());
+        } else {
+            feature.status = FeatureStatus::NeedsRework;
+            feature.rework = comment.into_iter().map(str::to_string).collect();
+        }
+        let status = session.planned_features[feature_index].status.clone();
+
+        self.persist(session)?;
+        self.emit_debt(session_id, &feature_name, &recorded_debt, &paid_debt);
+
+        if approved {
+            self.emit(
+                EventKind::FeatureApproved,
+                session_id,
+                json!({
+                    "feature": feature_name,
+                    "review": comment,
+                    "commits": session.planned_features[feature_index].commits,
+                    "author": author,
+                    "human": true,
+                }),
+            );
+            self.emit_milestone_completed(session_id, session, &feature_name);
+        } else {
+            self.emit(
+                EventKind::FeatureNeedsRework,
+                session_id,
+                json!({
+                    "feature": feature_name,
+                    "review": comment,
+                    "rework": [comment],
+                    "author": author,
+                    "human": true,
+                }),
+            );
+        }
+
+        Ok((feature_name, status))
+    }
+
     /// Answers an ad-hoc question about the current feature or design.
     /// Returns the LLM's response to the question.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
     pub async fn answer_feature_question(
         &self,
         session_id: &str,
         question: &str,
+        author: Option<&str>,
         llm_client: Option<&crate::game_design::DesignerLlmClient>,
     ) -> Result<String> {
-        let mut sessions = self.sessions.write().await;
-
-        // Try to load from file if not in memory
-        if !sessions.contains_key(session_id) {
-            let session_file_path = format!("{}/{}.json", self.persistence_path, session_id);
-            if Path::new(&session_file_path).exists() {
-                let session_json = fs::read_to_string(&session_file_path)?;
-                let session: SessionState = serde_json::from_str(&session_json)?;
-                sessions.insert(session_id.to_string(), session);
-            } else {
-                return Err(anyhow::anyhow!("Session '{}' not found", session_id));
-            }
-        }
-
-        // Get the session
-        let session = sessions.get_mut(session_id).unwrap();
+        // Get a copy of the session, loading it from file if not in memory
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
 
         // If we don't have an LLM client, we can't answer the question
-        let llm_client = llm_client.ok_or_else(|| {
-            anyhow::anyhow!("LLM client not available to answer feature question")
-        })?;
+        let llm_client = llm_client.ok_or(GameDesignerError::LlmUnavailable)?;
 
         // Get information about the current feature if there is one
         let current_feature_info =
@@ -565,12 +2155,23 @@ Description: {}
             };
 
         // Generate a prompt for the LLM to answer the question
-        let mut prompt = format!(
-            "Based on this game design document:
-{}
-{}",
-            session.initial_description, current_feature_info
-        );
+        let mut prompt = memory::design_context(session);
+        prompt.push_str(&current_feature_info);
+
+        // Add earlier answers about the current feature and the design in
+        // general so that answers stay consistent
+        let current_feature = session.next_feature_to_implement.clone();
+        let related_qa: Vec<&QaEntry> = session
+            .qa_log
+            .iter()
+            .filter(|entry| entry.feature.is_none() || entry.feature == current_feature)
+            .collect();
+        if !related_qa.is_empty() {
+            prompt.push_str("\nPreviously answered questions:\n");
+            for entry in related_qa.iter().rev().take(QA_CONTEXT_ENTRIES).rev() {
+                prompt.push_str(&format!("Q: {}\nA: {}\n\n", entry.question, entry.answer));
+            }
+        }
 
         // Add the developer's question
         prompt.push_str(&format!(
@@ -589,13 +2190,7 @@ Please answer the developer's question based on the game design document
         let messages = vec![
             crate::game_design::designer_llm::ChatMessage {
                 role: "system".to_string(),
-                content: "You are an expert game designer and software architect. 
-                         Your task is to answer ad-hoc questions from developers about 
-                         the game design or current feature implementation. 
-                         You will be given the game design document, information about 
-                         the current feature (if any), and the developer's question. 
-                         Provide a clear and concise answer."
-                    .to_string(),
+                content: session.system_prompt(prompts::FEATURE_ASK),
             },
             crate::game_design::designer_llm::ChatMessage {
                 role: "user".to_string(),
@@ -606,18 +2201,2575 @@ Please answer the developer's question based on the game design document
         // Call the LLM to answer the question
         let answer_response = llm_client.call_llm(messages).await?;
 
-        // Save the updated session (in case the LLM added to the chat history)
-        let session_file_path = format!("{}/{}.json", self.persistence_path, session.id);
-        let session_json = serde_json::to_string_pretty(&*session)?;
-        fs::write(&session_file_path, session_json)?;
+        // Store the exchange so it can be retrieved and reused later
+        session.qa_log.push(QaEntry {
+            feature: current_feature.clone(),
+            question: question.to_string(),
+            answer: answer_response.clone(),
+            asked_at: self.clock.now(),
+            author: author.map(str::to_string),
+        });
+
+        // Save the updated session
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::QuestionAnswered,
+            session_id,
+            json!({
+                "feature": current_feature,
+                "question": question,
+                "answer": answer_response,
+                "author": author,
+            }),
+        );
 
         Ok(answer_response)
     }
-}
 
-// Add the Default implementation as suggested by Clippy
-impl Default for SessionManager {
-    fn default() -> Self {
-        Self::new()
+    /// Returns past `featureAsk` exchanges, optionally only those asked while
+    /// `feature` was being implemented.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn qa_history(
+        &self,
+        session_id: &str,
+        feature: Option<&str>,
+    ) -> Result<Vec<QaEntry>> {
+        self.with_session(session_id, |session| {
+            session
+                .qa_log
+                .iter()
+                .filter(|entry| feature.is_none() || entry.feature.as_deref() == feature)
+                .cloned()
+                .collect()
+        })
+        .await?
+        .ok_or_else(|| GameDesignerError::SessionNotFound(session_id.to_string()).into())
+    }
+
+    /// Returns the asset requirements of all planned features, optionally
+    /// restricted to one kind.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn asset_list(
+        &self,
+        session_id: &str,
+        kind: Option<AssetKind>,
+    ) -> Result<Vec<AssetEntry>> {
+        self.with_session(session_id, |session| assets::collect(session, kind))
+            .await?
+            .ok_or_else(|| GameDesignerError::SessionNotFound(session_id.to_string()).into())
+    }
+
+    /// Writes the asset list of a session to `<id>.assets.csv` in the
+    /// persistence directory and returns the file path and the CSV.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn export_assets_csv(
+        &self,
+        session_id: &str,
+        kind: Option<AssetKind>,
+    ) -> Result<(String, String)> {
+        let entries = self.asset_list(session_id, kind).await?;
+        let csv = assets::to_csv(&entries);
+
+        let path = format!("{}/{}.assets.csv", self.persistence_path, session_id);
+        self.storage.write(Path::new(&path), &csv)?;
+
+        Ok((path, csv))
+    }
+
+    /// Returns the features that have commits recorded or are reviewed,
+    /// in plan order.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn traceability(&self, session_id: &str) -> Result<Vec<Feature>> {
+        self.with_session(session_id, |session| {
+            session
+                .planned_features
+                .iter()
+                .filter(|feature| {
+                    !feature.commits.is_empty() || feature.status == FeatureStatus::Reviewed
+                })
+                .cloned()
+                .collect()
+        })
+        .await?
+        .ok_or_else(|| GameDesignerError::SessionNotFound(session_id.to_string()).into())
+    }
+
+    /// Starts the work timer of a feature, by default the next feature to
+    /// implement. Returns the feature name.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn start_work(&self, session_id: &str, feature_name: Option<&str>) -> Result<String> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        let feature_name = feature_name
+            .map(str::to_string)
+            .or_else(|| session.next_feature_to_implement.clone())
+            .ok_or_else(|| anyhow::anyhow!("No next feature to implement"))?;
+
+        let feature = session
+            .planned_features
+            .iter_mut()
+            .find(|f| f.name == feature_name)
+            .ok_or_else(|| GameDesignerError::FeatureNotFound(feature_name.to_string()))?;
+
+        if feature.work_started_at.is_some() {
+            return Err(anyhow::anyhow!(
+                "Work on feature '{}' is already in progress",
+                feature_name
+            ));
+        }
+
+        feature.work_started_at = Some(self.clock.now());
+        if feature.status == FeatureStatus::Planned {
+            feature.status = FeatureStatus::InProgress;
+        }
+
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::FeatureWorkStarted,
+            session_id,
+            json!({ "feature": feature_name }),
+        );
+
+        Ok(feature_name)
+    }
+
+    /// Stops the running work timer of a feature, by default the next feature
+    /// to implement. Returns the feature name, the minutes added and the
+    /// total time spent on it.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn stop_work(
+        &self,
+        session_id: &str,
+        feature_name: Option<&str>,
+    ) -> Result<(String, u64, u64)> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        let feature_name = feature_name
+            .map(str::to_string)
+            .or_else(|| session.next_feature_to_implement.clone())
+            .ok_or_else(|| anyhow::anyhow!("No next feature to implement"))?;
+
+        let feature = session
+            .planned_features
+            .iter_mut()
+            .find(|f| f.name == feature_name)
+            .ok_or_else(|| GameDesignerError::FeatureNotFound(feature_name.to_string()))?;
+
+        if feature.work_started_at.is_none() {
+            return Err(anyhow::anyhow!(
+                "Work on feature '{}' has not been started",
+                feature_name
+            ));
+        }
+
+        let minutes = feature.stop_work(self.clock.now());
+        let total = feature.time_spent_minutes;
+
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::FeatureWorkStopped,
+            session_id,
+            json!({ "feature": feature_name, "minutes": minutes, "total_minutes": total }),
+        );
+
+        Ok((feature_name, minutes, total))
+    }
+
+    /// Returns the ids of all stored sessions, sorted.
+    pub fn session_ids(&self) -> Result<Vec<String>> {
+        let mut ids = Vec::new();
+
+        for path in self.storage.list(Path::new(&self.persistence_path))? {
+            let Some(id) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix(".json"))
+            else {
+                continue;
+            };
+
+            // Sessions are stored as `<id>.json`, sidecar files have more dots
+            if id.contains('.') || RESERVED_SESSION_NAMES.contains(&id) {
+                continue;
+            }
+
+            ids.push(id.to_string());
+        }
+
+        ids.sort();
+        Ok(ids)
+    }
+
+    /// Loads every stored session except `except`. Files that are not
+    /// sessions are skipped.
+    fn other_sessions(&self, except: &str) -> Result<Vec<SessionState>> {
+        let mut sessions = Vec::new();
+
+        for id in self.session_ids()? {
+            if id == except {
+                continue;
+            }
+
+            let path = Path::new(&self.persistence_path).join(format!("{}.json", id));
+            match self
+                .storage
+                .read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|json| Ok(serde_json::from_str::<SessionState>(&json)?))
+            {
+                Ok(session) => sessions.push(session),
+                Err(e) => tracing::debug!("Skipping {}: {}", path.display(), e),
+            }
+        }
+
+        Ok(sessions)
+    }
+
+    /// Searches other sessions (or only `source_session`) for features and
+    /// decisions matching `query` and lets the LLM adapt the relevant ones
+    /// into this session.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn reuse_from_session(
+        &self,
+        session_id: &str,
+        query: &str,
+        source_session: Option<&str>,
+        llm_client: Option<&crate::game_design::DesignerLlmClient>,
+    ) -> Result<ReuseOutcome> {
+        let llm_client = llm_client.ok_or(GameDesignerError::LlmUnavailable)?;
+
+        let mut sources = self.other_sessions(session_id)?;
+        if let Some(source_session) = source_session {
+            sources.retain(|session| session.id == source_session);
+            if sources.is_empty() {
+                return Err(GameDesignerError::SessionNotFound(source_session.to_string()).into());
+            }
+        }
+
+        let candidates = search::search(&sources, query, REUSE_CANDIDATES);
+        if candidates.is_empty() {
+            return Ok(ReuseOutcome::default());
+        }
+
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        let mut prompt = memory::design_context(session);
+
+        if !session.planned_features.is_empty() {
+            prompt.push_str("Already planned features:\n");
+            for feature in &session.planned_features {
+                prompt.push_str(&format!("- {}\n", feature.name));
+            }
+            prompt.push('\n');
+        }
+
+        prompt.push_str(&format!(
+            "The team is looking to reuse this from earlier projects: '{}'.\n\
+             Matching features and decisions from other design sessions:\n{}\n\n",
+            query,
+            serde_json::to_string_pretty(&candidates)?
+        ));
+
+        prompt.push_str(
+            "Select the matches that are relevant to the request and fit this game, and adapt \
+             them to this game's design (names, tone, constraints). Skip anything that \
+             duplicates an already planned feature or an established decision. \
+             Format your response as JSON with 'features' (array of objects with 'name', \
+             'description' and 'source' (the source session) fields) and 'decisions' (array of \
+             short strings) fields. Only return the JSON, nothing else.",
+        );
+
+        let messages = vec![
+            crate::game_design::designer_llm::ChatMessage {
+                role: "system".to_string(),
+                content: session.system_prompt(prompts::SESSION_REUSE),
+            },
+            crate::game_design::designer_llm::ChatMessage {
+                role: "user".to_string(),
+                content: prompt,
+            },
+        ];
+
+        let response = llm_client.call_llm(messages).await?;
+
+        #[derive(serde::Deserialize)]
+        struct ReusedFeature {
+            name: String,
+            description: String,
+            #[serde(default)]
+            source: Option<String>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct ReusedDesign {
+            #[serde(default)]
+            features: Vec<ReusedFeature>,
+            #[serde(default)]
+            decisions: Vec<String>,
+        }
+
+        let json = crate::game_design::designer_llm::extract_json(&response)
+            .ok_or_else(|| anyhow::anyhow!("LLM response was not valid JSON: {}", response))?;
+        let reused: ReusedDesign = serde_json::from_str(json)?;
+
+        let mut features = Vec::new();
+
+        for ReusedFeature {
+            name,
+            description,
+            source,
+        } in reused.features
+        {
+            if session
+                .planned_features
+                .iter()
+                .any(|f| f.name.eq_ignore_ascii_case(&name))
+            {
+                continue;
+            }
+
+            let description = match &source {
+                Some(source) => format!("{}\n\nAdapted from session '{}'", description, source),
+                None => description,
+            };
+
+            let feature = Feature::new(name, description);
+
+            session.planned_features.push(feature.clone());
+            features.push(feature);
+        }
+
+        let known_decisions = session.design_memory.len();
+        memory::remember(session, reused.decisions, &format!("reuse: {}", query));
+        let decisions = session.design_memory[known_decisions..]
+            .iter()
+            .map(|decision| decision.decision.clone())
+            .collect();
+
+        self.persist(session)?;
+
+        for feature in &features {
+            self.emit(
+                EventKind::FeatureProposed,
+                session_id,
+                json!({ "feature": feature.name, "description": feature.description, "reused": true }),
+            );
+        }
+
+        Ok(ReuseOutcome {
+            candidates,
+            features,
+            decisions,
+        })
+    }
+
+    /// Aggregates effort estimates per milestone. A given `budget` is stored
+    /// as the session's effort budget; otherwise the stored budget or
+    /// `GAMEDESIGNER_EFFORT_BUDGET` is used.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn scope_report(&self, session_id: &str, budget: Option<u32>) -> Result<ScopeReport> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        if budget.is_some() && budget != session.effort_budget {
+            if session.freeze.is_some() {
+                return Err(GameDesignerError::SessionFrozen(session_id.to_string()).into());
+            }
+
+            session.effort_budget = budget;
+            self.persist(session)?;
+
+            self.emit(
+                EventKind::SessionConfigured,
+                session_id,
+                json!({ "effortBudget": budget }),
+            );
+        }
+
+        let budget = session.effort_budget.or_else(scope::default_budget);
+
+        Ok(scope::report(session, budget))
+    }
+
+    /// Summarizes the session's activity between `since` and `until` for
+    /// stand-up notes, from its event log.
+    pub async fn status_report(
+        &self,
+        session_id: &str,
+        since: chrono::DateTime<chrono::Utc>,
+        until: chrono::DateTime<chrono::Utc>,
+    ) -> Result<StatusReport> {
+        check_session_id(session_id)?;
+        let events = self.events.read_since(session_id, 0, usize::MAX)?;
+
+        self.with_session(session_id, |session| {
+            status::report(session, &events, since, until)
+        })
+        .await?
+        .ok_or_else(|| GameDesignerError::SessionNotFound(session_id.to_string()).into())
+    }
+
+    /// Writes a player-facing devlog entry covering the features accepted
+    /// between `since` and `until` (inclusive) and saves it under
+    /// `<session>/devlogs/` in the persistence directory.
+    /// Returns the file path and the devlog.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn generate_devlog(
+        &self,
+        session_id: &str,
+        since: chrono::NaiveDate,
+        until: chrono::NaiveDate,
+        llm_client: Option<&crate::game_design::DesignerLlmClient>,
+    ) -> Result<(String, String)> {
+        let llm_client = llm_client.ok_or(GameDesignerError::LlmUnavailable)?;
+
+        let session = self
+            .load_session(session_id)
+            .await?
+            .ok_or_else(|| GameDesignerError::SessionNotFound(session_id.to_string()))?;
+
+        // Acceptance dates are only recorded in the event log
+        let mut accepted: Vec<String> = Vec::new();
+        for event in self.events.read_since(session_id, 0, usize::MAX)? {
+            let date = event.timestamp.date_naive();
+            if event.kind != EventKind::FeatureApproved || date < since || date > until {
+                continue;
+            }
+
+            if let Some(feature) = event.data.get("feature").and_then(|v| v.as_str())
+                && !accepted.iter().any(|name| name == feature)
+            {
+                accepted.push(feature.to_string());
+            }
+        }
+
+        if accepted.is_empty() {
+            return Err(anyhow::anyhow!(
+                "No features were accepted between {} and {}",
+                since,
+                until
+            ));
+        }
+
+        let mut prompt = format!(
+            "Game design document:\n{}\n\nFeatures completed between {} and {}:\n\n",
+            session.initial_description, since, until
+        );
+
+        for name in &accepted {
+            let description = session
+                .planned_features
+                .iter()
+                .find(|f| &f.name == name)
+                .map_or("", |f| f.description.as_str());
+            let report = session
+                .implemented_features_reports
+                .get(name)
+                .map_or("", String::as_str);
+
+            prompt.push_str(&format!(
+                "Feature: {}\nSpecification: {}\nImplementation report:\n{}\n\n",
+                name,
+                description,
+                context::truncate_middle(report, 2_000)
+            ));
+        }
+
+        let next_up: Vec<&str> = session
+            .planned_features
+            .iter()
+            .filter(|f| f.status != FeatureStatus::Reviewed && !accepted.contains(&f.name))
+            .map(|f| f.name.as_str())
+            .take(3)
+            .collect();
+        if !next_up.is_empty() {
+            prompt.push_str(&format!("Coming next: {}\n\n", next_up.join(", ")));
+        }
+
+        prompt.push_str(
+            "Write a devlog entry in Markdown about this progress for players. Start with a \
+             '#' title and a short intro, give every completed feature its own '##' heading with \
+             a few sentences about what it means for players, and add a screenshot placeholder \
+             such as `![Screenshot: <what to capture>](screenshot.png)` under each heading. \
+             End with a '## Next up' teaser about what is coming next. \
+             Only return the Markdown, nothing else.",
+        );
+
+        let messages = vec![
+            crate::game_design::designer_llm::ChatMessage {
+                role: "system".to_string(),
+                content: session.system_prompt(prompts::DEVLOG),
+            },
+            crate::game_design::designer_llm::ChatMessage {
+                role: "user".to_string(),
+                content: prompt,
+            },
+        ];
+
+        let mut devlog = llm_client.call_llm(messages).await?;
+        if let Some(attribution) = session.metadata.attribution() {
+            devlog = format!("{}\n\n---\n\n{}\n", devlog.trim_end(), attribution);
+        }
+
+        let dir = format!("{}/{}/devlogs", self.persistence_path, session_id);
+        self.storage.create_dir_all(Path::new(&dir))?;
+        let path = format!("{}/{}_{}.md", dir, since, until);
+        self.storage.write(Path::new(&path), &devlog)?;
+
+        Ok((path, devlog))
+    }
+
+    /// Searches the history of a session for snippets of features, reports,
+    /// reviews, replies and answers similar in meaning to `query`, best
+    /// first. The session's embedding index is brought up to date with the
+    /// event log first.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn design_search(
+        &self,
+        session_id: &str,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SemanticMatch>> {
+        if self.load_session(session_id).await?.is_none() {
+            return Err(GameDesignerError::SessionNotFound(session_id.to_string()).into());
+        }
+
+        let embedder = Embedder::new()?;
+        let events = self.events.read_since(session_id, 0, usize::MAX)?;
+
+        let path = PathBuf::from(format!(
+            "{}/{}/embeddings.json",
+            self.persistence_path, session_id
+        ));
+        let mut index = EmbeddingIndex::load(self.storage.as_ref(), &path);
+        if index.update(&embedder, &events).await? {
+            index.save(self.storage.as_ref(), &path)?;
+        }
+
+        let query = embedder.embed(&[query.to_string()]).await?;
+        Ok(index.search(&query[0], limit))
+    }
+
+    /// Builds the retrospective of a session from its whole event log and
+    /// saves it as Markdown to `path` inside the session's repository or the
+    /// persistence directory, or under `<session>/retros/` in the latter. The
+    /// lessons learned are left out without an
+    /// LLM client. Returns the file path and the retrospective.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn design_retro(
+        &self,
+        session_id: &str,
+        path: Option<&str>,
+        llm_client: Option<&crate::game_design::DesignerLlmClient>,
+    ) -> Result<(String, Retrospective)> {
+        let session = self
+            .load_session(session_id)
+            .await?
+            .ok_or_else(|| GameDesignerError::SessionNotFound(session_id.to_string()))?;
+
+        // Refuse paths outside the repository before the LLM is asked
+        let path = path
+            .map(|path| self.output_path(&session, path))
+            .transpose()?;
+
+        let events = self.events.read_since(session_id, 0, usize::MAX)?;
+        let mut retrospective = retro::retrospective(&session, &events);
+
+        if let Some(llm_client) = llm_client {
+            let mut prompt = format!(
+                "Game design document:\n{}\n\nRetrospective of the project:\n\n{}\n\n",
+                context::truncate_middle(&session.initial_description, 4_000),
+                retrospective.render_facts()
+            );
+
+            // The reviews of the most reworked features tell why they churned
+            for review in retrospective
+                .reviews
+                .iter()
+                .filter(|r| r.reworks > 0)
+                .take(3)
+            {
+                let feedback = events
+                    .iter()
+                    .rev()
+                    .filter(|event| event.kind == EventKind::FeatureNeedsRework)
+                    .filter(|event| {
+                        event.data.get("feature").and_then(|v| v.as_str())
+                            == Some(review.feature.as_str())
+                    })
+                    .find_map(|event| event.data.get("review").and_then(|v| v.as_str()));
+                if let Some(feedback) = feedback {
+                    prompt.push_str(&format!(
+                        "Last rework review of {}:\n{}\n\n",
+                        review.feature,
+                        context::truncate_middle(feedback, 1_500)
+                    ));
+                }
+            }
+
+            prompt.push_str(
+                "Write the lessons learned from this project as a Markdown list of three to \
+                 five bullet points. Each lesson names what happened and what to do differently \
+                 next time. Only return the list, nothing else.",
+            );
+
+            let messages = vec![
+                crate::game_design::designer_llm::ChatMessage {
+                    role: "system".to_string(),
+                    content: session.system_prompt(prompts::DESIGN_RETRO),
+                },
+                crate::game_design::designer_llm::ChatMessage {
+                    role: "user".to_string(),
+                    content: prompt,
+                },
+            ];
+
+            retrospective.lessons = Some(llm_client.call_llm(messages).await?);
+        }
+
+        let mut markdown = retrospective.render(session_id);
+        if let Some(attribution) = session.metadata.attribution() {
+            markdown = format!("{}\n\n---\n\n{}", markdown, attribution);
+        }
+        let markdown = format!("{}\n", markdown);
+
+        // Retrospectives kept with the session go through the storage, those
+        // the client asked for to the file system
+        let path = match path {
+            Some(path) => {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&path, &markdown)?;
+                path.to_string_lossy().into_owned()
+            }
+            None => {
+                let dir = format!("{}/{}/retros", self.persistence_path, session_id);
+                self.storage.create_dir_all(Path::new(&dir))?;
+                let path = format!("{}/{}.md", dir, self.clock.now().date_naive());
+                self.storage.write(Path::new(&path), &markdown)?;
+                path
+            }
+        };
+
+        Ok((path, retrospective))
     }
+
+    /// Suggests a Conventional Commits message for the work on a feature,
+    /// based on the staged diff of the session's repository and the changes
+    /// report. Defaults to the feature currently being implemented. Without
+    /// an LLM client a plain `feat` message is derived from the report.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn commit_message(
+        &self,
+        session_id: &str,
+        feature_name: Option<&str>,
+        changes_made: Option<&str>,
+        llm_client: Option<&crate::game_design::DesignerLlmClient>,
+    ) -> Result<String> {
+        let session = self
+            .load_session(session_id)
+            .await?
+            .ok_or_else(|| GameDesignerError::SessionNotFound(session_id.to_string()))?;
+
+        let feature_name = feature_name
+            .map(str::to_string)
+            .or_else(|| session.next_feature_to_implement.clone())
+            .or_else(|| {
+                session
+                    .planned_features
+                    .iter()
+                    .find(|f| f.status == FeatureStatus::InProgress)
+                    .map(|f| f.name.clone())
+            })
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No feature is being implemented in session '{}'",
+                    session_id
+                )
+            })?;
+
+        let feature = session
+            .planned_features
+            .iter()
+            .find(|f| f.name == feature_name)
+            .ok_or_else(|| GameDesignerError::FeatureNotFound(feature_name.to_string()))?;
+
+        let diff = match &session.repo_path {
+            Some(repo) => git::staged_diff(Path::new(repo)).await?,
+            None => String::new(),
+        };
+
+        let changes = changes_made
+            .or_else(|| {
+                session
+                    .implemented_features_reports
+                    .get(&feature.name)
+                    .map(String::as_str)
+            })
+            .unwrap_or_default();
+
+        if diff.trim().is_empty() && changes.trim().is_empty() {
+            return Err(anyhow::anyhow!(
+                "Nothing to describe: stage changes in the session's repository or pass changesMade"
+            ));
+        }
+
+        let scope = commit_scope(&feature.name);
+        let trailer = format!("Feature: {}", feature.name);
+
+        let Some(llm_client) = llm_client else {
+            let summary = changes
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .map_or_else(|| format!("implement {}", feature.name), str::to_string);
+
+            return Ok(format!("feat({}): {}\n\n{}", scope, summary, trailer));
+        };
+
+        let mut prompt = format!(
+            "Feature: {}\nSpecification: {}\n\n",
+            feature.name, feature.description
+        );
+        if !changes.trim().is_empty() {
+            prompt.push_str(&format!("Changes report:\n{}\n\n", changes));
+        }
+        if !diff.trim().is_empty() {
+            prompt.push_str(&format!(
+                "Staged changes (git diff, possibly truncated):\n```diff\n{}\n```\n\n",
+                context::truncate_middle(&diff, REVIEW_DIFF_TOKENS)
+            ));
+        }
+        prompt.push_str(&format!(
+            "Write a Conventional Commits message for these changes. Use the type that fits \
+             (feat, fix, refactor, perf, test, docs or chore) with the scope '{}', an imperative \
+             subject line of at most 72 characters, a blank line, and a short body explaining \
+             what changed and why. End with the trailer line '{}'. \
+             Only return the commit message, nothing else.",
+            scope, trailer
+        ));
+
+        let messages = vec![
+            crate::game_design::designer_llm::ChatMessage {
+                role: "system".to_string(),
+                content: session.system_prompt(prompts::COMMIT_MESSAGE),
+            },
+            crate::game_design::designer_llm::ChatMessage {
+                role: "user".to_string(),
+                content: prompt,
+            },
+        ];
+
+        let response = llm_client.call_llm(messages).await?;
+        let mut message = response
+            .trim()
+            .trim_start_matches("```")
+            .trim_end_matches("```")
+            .trim()
+            .to_string();
+
+        if !message.contains(&trailer) {
+            message.push_str(&format!("\n\n{}", trailer));
+        }
+
+        Ok(message)
+    }
+
+    /// Stores the outcome of a board sync: the issue links, and the statuses
+    /// changed on the tracker. A status that changed in the session while
+    /// syncing is kept and pushed on the next sync.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn apply_board_sync(&self, session_id: &str, mut sync: BoardSync) -> Result<()> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        let mut pulled = Vec::new();
+        for (name, from, to) in &sync.pulled {
+            let Some(feature) = session
+                .planned_features
+                .iter_mut()
+                .find(|feature| &feature.name == name)
+            else {
+                continue;
+            };
+
+            if feature.status == *from {
+                feature.status = to.clone();
+                pulled.push(json!({ "feature": name, "from": from, "to": to }));
+            } else if let Some(link) = sync.board.links.get_mut(name) {
+                link.synced_status = from.clone();
+            }
+        }
+
+        let repository = sync.board.repository.clone();
+        session.board = Some(sync.board);
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::BoardSynced,
+            session_id,
+            json!({
+                "repository": repository,
+                "created": sync.created,
+                "pushed": sync.pushed,
+                "pulled": pulled,
+            }),
+        );
+
+        Ok(())
+    }
+
+    /// Evaluates the session's feature set against its design pillars.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn lint_design(
+        &self,
+        session_id: &str,
+        llm_client: Option<&crate::game_design::DesignerLlmClient>,
+    ) -> Result<Vec<LintFinding>> {
+        let llm_client = llm_client.ok_or(GameDesignerError::LlmUnavailable)?;
+
+        let session = self
+            .load_session(session_id)
+            .await?
+            .ok_or_else(|| GameDesignerError::SessionNotFound(session_id.to_string()))?;
+
+        if session.planned_features.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        lint::lint(llm_client, &session).await
+    }
+
+    /// Brainstorms `ideas` raw ideas on `topic` and adds the `shortlist`
+    /// strongest, scored against the design pillars, to the session's idea
+    /// pool. Ideas already in the pool are not added twice.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn brainstorm(
+        &self,
+        session_id: &str,
+        topic: &str,
+        ideas: usize,
+        shortlist: usize,
+        llm_client: Option<&crate::game_design::DesignerLlmClient>,
+    ) -> Result<Brainstorm> {
+        let llm_client = llm_client.ok_or(GameDesignerError::LlmUnavailable)?;
+
+        if topic.trim().is_empty() {
+            return Err(anyhow::anyhow!("The brainstorming topic cannot be empty"));
+        }
+
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        let mut outcome = brainstorm::brainstorm(
+            llm_client,
+            session,
+            topic,
+            ideas,
+            shortlist,
+            self.clock.now(),
+        )
+        .await?;
+
+        outcome.shortlist.retain(|idea| {
+            !session
+                .ideas
+                .iter()
+                .any(|pooled| pooled.name.eq_ignore_ascii_case(&idea.name))
+        });
+        session.ideas.extend(outcome.shortlist.iter().cloned());
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::IdeasShortlisted,
+            session_id,
+            json!({
+                "topic": topic.trim(),
+                "ideas": outcome
+                    .shortlist
+                    .iter()
+                    .map(|idea| idea.name.as_str())
+                    .collect::<Vec<_>>(),
+            }),
+        );
+
+        Ok(outcome)
+    }
+
+    /// Converts issues from an external tracker into planned features.
+    /// The LLM normalizes titles and descriptions and skips duplicates.
+    /// Returns the features that were added.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn import_backlog(
+        &self,
+        session_id: &str,
+        issues: Vec<TrackerIssue>,
+        llm_client: Option<&crate::game_design::DesignerLlmClient>,
+    ) -> Result<Vec<Feature>> {
+        let llm_client = llm_client.ok_or(GameDesignerError::LlmUnavailable)?;
+
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        if issues.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut prompt = memory::design_context(session);
+
+        if !session.planned_features.is_empty() {
+            prompt.push_str("Already planned features:\n");
+            for feature in &session.planned_features {
+                prompt.push_str(&format!("- {}\n", feature.name));
+            }
+            prompt.push('\n');
+        }
+
+        prompt.push_str(&format!(
+            "Open issues from the team's issue tracker:\n{}\n\n",
+            serde_json::to_string_pretty(&issues)?
+        ));
+
+        prompt.push_str(
+            "Convert every issue that describes game work into a small, focused feature with \
+             a short title and a concise specification (2-3 sentences). Skip issues that are \
+             not game work or that duplicate an already planned feature. \
+             Format your response as a JSON array of objects with 'name', 'description' and \
+             'source' (the issue url) fields. Only return the JSON, nothing else.",
+        );
+
+        let messages = vec![
+            crate::game_design::designer_llm::ChatMessage {
+                role: "system".to_string(),
+                content: session.system_prompt(prompts::BACKLOG_IMPORT),
+            },
+            crate::game_design::designer_llm::ChatMessage {
+                role: "user".to_string(),
+                content: prompt,
+            },
+        ];
+
+        let response = llm_client.call_llm(messages).await?;
+
+        #[derive(serde::Deserialize)]
+        struct ImportedFeature {
+            name: String,
+            description: String,
+            #[serde(default)]
+            source: Option<String>,
+        }
+
+        let json = crate::game_design::designer_llm::extract_json(&response)
+            .ok_or_else(|| anyhow::anyhow!("LLM response was not valid JSON: {}", response))?;
+        let imported: Vec<ImportedFeature> = serde_json::from_str(json)?;
+
+        let mut added = Vec::new();
+
+        for ImportedFeature {
+            name,
+            description,
+            source,
+        } in imported
+        {
+            if session
+                .planned_features
+                .iter()
+                .any(|f| f.name.eq_ignore_ascii_case(&name))
+            {
+                continue;
+            }
+
+            let description = match &source {
+                Some(source) => format!("{}\n\nImported from {}", description, source),
+                None => description,
+            };
+
+            let feature = Feature::new(name, description);
+
+            session.planned_features.push(feature.clone());
+            added.push(feature);
+        }
+
+        self.persist(session)?;
+
+        for feature in &added {
+            self.emit(
+                EventKind::FeatureProposed,
+                session_id,
+                json!({ "feature": feature.name, "description": feature.description, "imported": true }),
+            );
+        }
+
+        Ok(added)
+    }
+
+    /// Creates a session from a tracker's open issues: the LLM consolidates
+    /// them into a design document and a feature list, which the new session
+    /// starts with. Returns the planned features.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn import_design(
+        &self,
+        session_id: String,
+        issues: Vec<TrackerIssue>,
+        persona: Persona,
+        language: Option<String>,
+        llm_client: Option<&crate::game_design::DesignerLlmClient>,
+    ) -> Result<Vec<Feature>> {
+        let llm_client = llm_client.ok_or(GameDesignerError::LlmUnavailable)?;
+
+        // Checked before the LLM call as well as by `create_session`, so that
+        // no tokens are spent on a name that is taken
+        if self.session_exists(&session_id) {
+            return Err(anyhow::anyhow!("Session '{}' already exists", session_id));
+        }
+
+        if issues.is_empty() {
+            return Err(anyhow::anyhow!("There are no open issues to import"));
+        }
+
+        let prompt = format!(
+            "Open issues from the team's issue tracker:\n{}\n\n\
+             Consolidate them into a game design document with the sections Core Concept, \
+             Gameplay Mechanics, Story and Setting, Target Audience, Unique Features, \
+             Technical Considerations and Development Milestones, inferring what the issues \
+             imply and marking what they leave open. Then convert every issue that describes \
+             game work into a small, focused feature with a short title and a concise \
+             specification (2-3 sentences), merging duplicates and skipping issues that are \
+             not game work. Order the features so that each builds on the ones before. \
+             Format your response as a JSON object with a 'document' string and a 'features' \
+             array of objects with 'name', 'description' and 'sources' (the issue urls) \
+             fields. Only return the JSON, nothing else.",
+            serde_json::to_string_pretty(&issues)?
+        );
+
+        let messages = vec![
+            crate::game_design::designer_llm::ChatMessage {
+                role: "system".to_string(),
+                content: prompts::localized(
+                    persona.system_prompt(prompts::DESIGN_IMPORT),
+                    language.as_deref(),
+                ),
+            },
+            crate::game_design::designer_llm::ChatMessage {
+                role: "user".to_string(),
+                content: prompt,
+            },
+        ];
+
+        let response = llm_client.call_llm(messages).await?;
+
+        #[derive(serde::Deserialize)]
+        struct ImportedFeature {
+            name: String,
+            description: String,
+            #[serde(default)]
+            sources: Vec<String>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct ImportedDesign {
+            document: String,
+            #[serde(default)]
+            features: Vec<ImportedFeature>,
+        }
+
+        let json = crate::game_design::designer_llm::extract_json(&response)
+            .ok_or_else(|| anyhow::anyhow!("LLM response was not valid JSON: {}", response))?;
+        let imported: ImportedDesign = serde_json::from_str(json)?;
+
+        self.create_session(
+            session_id.clone(),
+            imported.document,
+            persona,
+            None,
+            SessionSetup {
+                language,
+                ..SessionSetup::default()
+            },
+        )
+        .await?;
+
+        let mut session = self.checkout(&session_id).await?;
+        let session = &mut *session;
+
+        let mut added = Vec::new();
+
+        for ImportedFeature {
+            name,
+            description,
+            sources,
+        } in imported.features
+        {
+            if session
+                .planned_features
+                .iter()
+                .any(|f| f.name.eq_ignore_ascii_case(&name))
+            {
+                continue;
+            }
+
+            let description = if sources.is_empty() {
+                description
+            } else {
+                format!("{}\n\nImported from {}", description, sources.join(", "))
+            };
+
+            let feature = Feature::new(name, description);
+
+            session.planned_features.push(feature.clone());
+            added.push(feature);
+        }
+
+        self.persist(session)?;
+
+        for feature in &added {
+            self.emit(
+                EventKind::FeatureProposed,
+                &session_id,
+                json!({ "feature": feature.name, "description": feature.description, "imported": true }),
+            );
+        }
+
+        Ok(added)
+    }
+
+    /// Reviews a proposed edit of the design document against the existing
+    /// design and planned features. A merged edit replaces the document and
+    /// archives the previous revision in the design history.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn review_design_edit(
+        &self,
+        session_id: &str,
+        proposed_edit: &str,
+        rationale: Option<&str>,
+        llm_client: Option<&crate::game_design::DesignerLlmClient>,
+    ) -> Result<DesignEditOutcome> {
+        let llm_client = llm_client.ok_or(GameDesignerError::LlmUnavailable)?;
+
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        let mut prompt = memory::design_context(session);
+
+        if !session.planned_features.is_empty() {
+            prompt.push_str("Planned and implemented features:\n");
+            for feature in &session.planned_features {
+                prompt.push_str(&format!("- {} ({:?})\n", feature.name, feature.status));
+            }
+            prompt.push('\n');
+        }
+
+        prompt.push_str(&format!(
+            "Proposed edit of the design document:\n{}\n\n",
+            proposed_edit
+        ));
+
+        if let Some(rationale) = rationale {
+            prompt.push_str(&format!("Rationale given for the edit:\n{}\n\n", rationale));
+        }
+
+        prompt.push_str(
+            "Review the proposed edit like a pull request. Check that it is consistent with the \
+             design pillars, the established decisions and the features already planned or \
+             implemented. Either merge it or object to it. \
+             Format your response as JSON:\n\
+             {\n  \"verdict\": \"merge\" or \"object\",\n  \"summary\": \"One sentence describing the change\",\n  \
+             \"objections\": [\"...\"],\n  \"revisedDocument\": \"The complete design document with the edit applied (only when merging)\"\n}\n\
+             Only return the JSON, nothing else.",
+        );
+
+        let messages = vec![
+            crate::game_design::designer_llm::ChatMessage {
+                role: "system".to_string(),
+                content: session.system_prompt(prompts::DESIGN_EDIT_REVIEW),
+            },
+            crate::game_design::designer_llm::ChatMessage {
+                role: "user".to_string(),
+                content: prompt,
+            },
+        ];
+
+        let response = llm_client.call_llm(messages).await?;
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct DesignEditVerdict {
+            verdict: String,
+            #[serde(default)]
+            summary: String,
+            #[serde(default)]
+            objections: Vec<String>,
+            #[serde(default)]
+            revised_document: Option<String>,
+        }
+
+        let json = crate::game_design::designer_llm::extract_json(&response)
+            .ok_or_else(|| anyhow::anyhow!("LLM response was not valid JSON: {}", response))?;
+        let verdict: DesignEditVerdict = serde_json::from_str(json)?;
+
+        let revised_document = verdict
+            .revised_document
+            .filter(|document| !document.trim().is_empty());
+
+        let outcome = match (verdict.verdict.to_lowercase().as_str(), revised_document) {
+            ("merge", Some(document)) => {
+                let previous_version = session.design_version();
+                let previous = std::mem::replace(&mut session.initial_description, document);
+                session.design_history.push(DesignVersion {
+                    version: previous_version,
+                    document: previous,
+                    superseded_by_change: verdict.summary.clone(),
+                    superseded_at: self.clock.now(),
+                });
+                // Digests were written against the old document
+                session.report_digests.clear();
+
+                self.persist(session)?;
+
+                DesignEditOutcome::Merged {
+                    version: session.design_version(),
+                    summary: verdict.summary,
+                }
+            }
+            ("merge", None) => {
+                return Err(anyhow::anyhow!(
+                    "LLM merged the edit but did not return the revised document"
+                ));
+            }
+            _ => DesignEditOutcome::Objected {
+                summary: verdict.summary,
+                objections: verdict.objections,
+            },
+        };
+
+        match &outcome {
+            DesignEditOutcome::Merged { version, summary } => self.emit(
+                EventKind::DesignRevised,
+                session_id,
+                json!({ "version": version, "summary": summary }),
+            ),
+            DesignEditOutcome::Objected {
+                summary,
+                objections,
+            } => self.emit(
+                EventKind::DesignEditRejected,
+                session_id,
+                json!({ "summary": summary, "objections": objections }),
+            ),
+        }
+
+        Ok(outcome)
+    }
+
+    /// Updates the session's project metadata with the fields set in
+    /// `update` and returns the result. An update that changes nothing
+    /// leaves the session untouched.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn configure_metadata(
+        &self,
+        session_id: &str,
+        update: ProjectMetadata,
+    ) -> Result<ProjectMetadata> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        let mut metadata = session.metadata.clone();
+        metadata.merge(update);
+
+        if metadata != session.metadata {
+            session.metadata = metadata.clone();
+            self.persist(session)?;
+
+            self.emit(
+                EventKind::SessionConfigured,
+                session_id,
+                json!({ "metadata": metadata }),
+            );
+        }
+
+        Ok(metadata)
+    }
+
+    /// Applies `update` to the session's conventions, see
+    /// [`conventions::merge`], and returns the result with its size in
+    /// estimated tokens. Conventions too large for the prompts are refused.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn set_conventions(
+        &self,
+        session_id: &str,
+        update: BTreeMap<String, String>,
+        replace: bool,
+    ) -> Result<(BTreeMap<String, String>, usize)> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        let merged = conventions::merge(&session.conventions, update, replace);
+        let tokens = conventions::validate(&merged)?;
+
+        if merged != session.conventions {
+            session.conventions = merged.clone();
+            self.persist(session)?;
+
+            self.emit(
+                EventKind::SessionConfigured,
+                session_id,
+                json!({ "conventions": merged }),
+            );
+        }
+
+        Ok((merged, tokens))
+    }
+
+    /// Sets the engine the session's game is built with and returns the
+    /// previous one. Implementation hints and API references written for
+    /// another engine are dropped so they get regenerated.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn set_engine(&self, session_id: &str, engine: Engine) -> Result<Option<Engine>> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        let previous = session.engine.replace(engine);
+        if previous != Some(engine) {
+            for feature in &mut session.planned_features {
+                feature.implementation_hints = None;
+                feature.api_references.clear();
+            }
+        }
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::SessionConfigured,
+            session_id,
+            json!({ "engine": engine }),
+        );
+
+        Ok(previous)
+    }
+
+    /// Stores the engine API references of a feature and returns its
+    /// updated specification.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn set_api_references(
+        &self,
+        session_id: &str,
+        feature_name: &str,
+        references: Vec<ApiReference>,
+    ) -> Result<String> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        let feature = session
+            .planned_features
+            .iter_mut()
+            .find(|feature| feature.name == feature_name)
+            .ok_or_else(|| GameDesignerError::FeatureNotFound(feature_name.to_string()))?;
+        feature.api_references = references.clone();
+        let specification = feature.specification();
+
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::SessionConfigured,
+            session_id,
+            json!({ "feature": feature_name, "apiReferences": references }),
+        );
+
+        Ok(specification)
+    }
+
+    /// Sets the language the designer writes in, `None` for English, and
+    /// returns the previous one.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn set_language(
+        &self,
+        session_id: &str,
+        language: Option<String>,
+    ) -> Result<Option<String>> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        let previous = std::mem::replace(&mut session.language, language.clone());
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::SessionConfigured,
+            session_id,
+            json!({ "language": language }),
+        );
+
+        Ok(previous)
+    }
+
+    /// Sets whether feature reviews that don't choose go to a review panel.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn set_review_panel(&self, session_id: &str, panel: bool) -> Result<()> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        session.review_panel = panel;
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::SessionConfigured,
+            session_id,
+            json!({ "reviewPanel": panel }),
+        );
+
+        Ok(())
+    }
+
+    /// Sets the depth of feature reviews that don't choose one.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn set_review_depth(&self, session_id: &str, depth: ReviewDepth) -> Result<()> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        session.review_depth = depth;
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::SessionConfigured,
+            session_id,
+            json!({ "reviewDepth": depth }),
+        );
+
+        Ok(())
+    }
+
+    /// Has the LLM draft the session's story from its design document and
+    /// planned features. A story that was already started is only replaced
+    /// with `replace`.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn draft_story(
+        &self,
+        session_id: &str,
+        replace: bool,
+        llm_client: Option<&crate::game_design::DesignerLlmClient>,
+    ) -> Result<Narrative> {
+        let llm_client = llm_client.ok_or(GameDesignerError::LlmUnavailable)?;
+
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        if !session.narrative.is_empty() && !replace {
+            return Err(anyhow::anyhow!(
+                "Session '{}' already has a story; pass replace to draft a new one",
+                session_id
+            ));
+        }
+
+        let narrative = narrative::draft(llm_client, session).await?;
+        session.narrative = narrative.clone();
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::NarrativeChanged,
+            session_id,
+            json!({
+                "change": "drafted",
+                "characters": narrative.characters.len(),
+                "acts": narrative.acts.len(),
+            }),
+        );
+
+        Ok(narrative)
+    }
+
+    /// Sets the dialogue style guide of the session's story, or clears it.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn set_style_guide(
+        &self,
+        session_id: &str,
+        style_guide: Option<String>,
+    ) -> Result<()> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        session.narrative.style_guide = style_guide.clone();
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::NarrativeChanged,
+            session_id,
+            json!({ "change": "style_guide", "styleGuide": style_guide }),
+        );
+
+        Ok(())
+    }
+
+    /// Creates the character named in `update`, or sets the fields given in
+    /// `update` on the existing one. Returns the character and whether it
+    /// was created.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn update_character(
+        &self,
+        session_id: &str,
+        update: Character,
+    ) -> Result<(Character, bool)> {
+        let name = update.name.trim();
+        if name.is_empty() {
+            return Err(anyhow::anyhow!("The character's name cannot be empty"));
+        }
+
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        let characters = &mut session.narrative.characters;
+        let existing = characters
+            .iter()
+            .position(|character| character.name.eq_ignore_ascii_case(name));
+
+        let created = existing.is_none();
+        let index = existing.unwrap_or_else(|| {
+            characters.push(Character {
+                name: name.to_string(),
+                ..Character::default()
+            });
+            characters.len() - 1
+        });
+
+        let character = &mut characters[index];
+        for (field, value) in [
+            (&mut character.role, update.role),
+            (&mut character.description, update.description),
+            (&mut character.motivation, update.motivation),
+            (&mut character.arc, update.arc),
+            (&mut character.voice, update.voice),
+        ] {
+            if value.is_some() {
+                *field = value;
+            }
+        }
+        let character = character.clone();
+
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::NarrativeChanged,
+            session_id,
+            json!({ "change": "character", "character": character.name, "created": created }),
+        );
+
+        Ok((character, created))
+    }
+
+    /// Adds `beat` to `act`, given by its number or name; a name that is no
+    /// act yet appends a new act. The beat goes to the 1-based `position`
+    /// within the act, or last. Characters and the feature must exist.
+    /// Returns the act's number and the beat's position.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn add_beat(
+        &self,
+        session_id: &str,
+        act: &str,
+        mut beat: Beat,
+        position: Option<usize>,
+    ) -> Result<(usize, usize)> {
+        let act = act.trim();
+        if act.is_empty() || beat.title.trim().is_empty() {
+            return Err(anyhow::anyhow!("The beat's act and title cannot be empty"));
+        }
+
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        for name in &mut beat.characters {
+            let character = session.narrative.character(name).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown character '{}'; add it with characterSheet first",
+                    name
+                )
+            })?;
+            *name = character.name.clone();
+        }
+
+        if let Some(feature) = &beat.feature {
+            let feature = session
+                .planned_features
+                .iter()
+                .find(|f| f.name.eq_ignore_ascii_case(feature.trim()))
+                .ok_or_else(|| GameDesignerError::FeatureNotFound(feature.to_string()))?;
+            beat.feature = Some(feature.name.clone());
+        }
+
+        let acts = &mut session.narrative.acts;
+        let index = match act.parse::<usize>() {
+            Ok(number) if (1..=acts.len()).contains(&number) => number - 1,
+            Ok(number) => {
+                return Err(anyhow::anyhow!(
+                    "Act {} does not exist; the story has {} acts",
+                    number,
+                    acts.len()
+                ));
+            }
+            Err(_) => acts
+                .iter()
+                .position(|a| a.name.eq_ignore_ascii_case(act))
+                .unwrap_or_else(|| {
+                    acts.push(Act {
+                        name: act.to_string(),
+                        summary: String::new(),
+                        beats: Vec::new(),
+                    });
+                    acts.len() - 1
+                }),
+        };
+
+        let beats = &mut acts[index].beats;
+        let position = position
+            .map(|position| position.clamp(1, beats.len() + 1))
+            .unwrap_or(beats.len() + 1);
+        let title = beat.title.clone();
+        beats.insert(position - 1, beat);
+
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::NarrativeChanged,
+            session_id,
+            json!({ "change": "beat", "act": index + 1, "beat": title, "position": position }),
+        );
+
+        Ok((index + 1, position))
+    }
+
+    /// Has the LLM write the brief of the level `name`. A new level needs
+    /// `goals`; an existing one is briefed again, with its previous goals if
+    /// `goals` is empty, and keeps its status. Returns the level and whether
+    /// it was created.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn brief_level(
+        &self,
+        session_id: &str,
+        name: &str,
+        goals: Vec<String>,
+        llm_client: Option<&crate::game_design::DesignerLlmClient>,
+    ) -> Result<(Level, bool)> {
+        let llm_client = llm_client.ok_or(GameDesignerError::LlmUnavailable)?;
+
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(anyhow::anyhow!("The level's name cannot be empty"));
+        }
+
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        let existing = session
+            .levels
+            .iter()
+            .position(|level| level.name.eq_ignore_ascii_case(name));
+
+        let goals = match (existing, goals.is_empty()) {
+            (Some(index), true) => session.levels[index].goals.clone(),
+            (None, true) => {
+                return Err(anyhow::anyhow!(
+                    "Level '{}' does not exist yet; give its goals to brief it",
+                    name
+                ));
+            }
+            (_, false) => goals,
+        };
+
+        let mut level = level::brief(llm_client, session, name, &goals, self.clock.now()).await?;
+
+        match existing {
+            Some(index) => {
+                let previous = &session.levels[index];
+                level.name = previous.name.clone();
+                level.status = previous.status;
+                level.created_at = previous.created_at;
+                session.levels[index] = level.clone();
+            }
+            None => session.levels.push(level.clone()),
+        }
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::LevelBriefed,
+            session_id,
+            json!({
+                "level": level.name,
+                "goals": level.goals,
+                "mechanics": level.mechanics,
+                "missingMechanics": level.missing_mechanics,
+            }),
+        );
+
+        Ok((level, existing.is_none()))
+    }
+
+    /// Moves the level `name` to `status`. Returns the level and its previous
+    /// status.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn set_level_status(
+        &self,
+        session_id: &str,
+        name: &str,
+        status: LevelStatus,
+    ) -> Result<(Level, LevelStatus)> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        let level = session
+            .levels
+            .iter_mut()
+            .find(|level| level.name.eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| GameDesignerError::NotFound(format!("Level '{}' not found", name)))?;
+
+        let previous = std::mem::replace(&mut level.status, status);
+        level.updated_at = self.clock.now();
+        let level = level.clone();
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::LevelStatusChanged,
+            session_id,
+            json!({ "level": level.name, "from": previous.name(), "to": status.name() }),
+        );
+
+        Ok((level, previous))
+    }
+
+    /// Declares the resources, flows and progression curves of `update` in
+    /// the session's economy, replacing those with the same name, and removes
+    /// the entries named in `remove`. Flows and curves must spend declared
+    /// resources, and flows can only belong to planned features. A change
+    /// discards the last simulation.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn update_economy(
+        &self,
+        session_id: &str,
+        update: Economy,
+        remove: Vec<String>,
+    ) -> Result<Economy> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        let mut economy = session.economy.clone();
+
+        for name in &remove {
+            let name = name.trim();
+            let before = economy.resources.len()
+                + economy.sources.len()
+                + economy.sinks.len()
+                + economy.curves.len();
+            economy
+                .resources
+                .retain(|resource| !resource.name.eq_ignore_ascii_case(name));
+            economy
+                .sources
+                .retain(|flow| !flow.name.eq_ignore_ascii_case(name));
+            economy
+                .sinks
+                .retain(|flow| !flow.name.eq_ignore_ascii_case(name));
+            economy
+                .curves
+                .retain(|curve| !curve.name.eq_ignore_ascii_case(name));
+            let after = economy.resources.len()
+                + economy.sources.len()
+                + economy.sinks.len()
+                + economy.curves.len();
+
+            if before == after {
+                return Err(anyhow::anyhow!("'{}' is not part of the economy", name));
+            }
+        }
+
+        for resource in update.resources {
+            match economy
+                .resources
+                .iter_mut()
+                .find(|r| r.name.eq_ignore_ascii_case(&resource.name))
+            {
+                Some(existing) => *existing = resource,
+                None => economy.resources.push(resource),
+            }
+        }
+        for (flows, updates) in [
+            (&mut economy.sources, update.sources),
+            (&mut economy.sinks, update.sinks),
+        ] {
+            for flow in updates {
+                match flows
+                    .iter_mut()
+                    .find(|f| f.name.eq_ignore_ascii_case(&flow.name))
+                {
+                    Some(existing) => *existing = flow,
+                    None => flows.push(flow),
+                }
+            }
+        }
+        for curve in update.curves {
+            match economy
+                .curves
+                .iter_mut()
+                .find(|c| c.name.eq_ignore_ascii_case(&curve.name))
+            {
+                Some(existing) => *existing = curve,
+                None => economy.curves.push(curve),
+            }
+        }
+
+        // Names are stored as declared, so lookups elsewhere can be exact
+        let resources = economy.resources.clone();
+        let resource_name = |name: &str| {
+            resources
+                .iter()
+                .find(|resource| resource.name.eq_ignore_ascii_case(name.trim()))
+                .map(|resource| resource.name.clone())
+                .ok_or_else(|| anyhow::anyhow!("Unknown resource '{}'; declare it first", name))
+        };
+        for flow in economy.sources.iter_mut().chain(&mut economy.sinks) {
+            flow.resource = resource_name(&flow.resource)?;
+            if let Some(feature) = &flow.feature {
+                let feature = session
+                    .planned_features
+                    .iter()
+                    .find(|f| f.name.eq_ignore_ascii_case(feature.trim()))
+                    .ok_or_else(|| GameDesignerError::FeatureNotFound(feature.to_string()))?;
+                flow.feature = Some(feature.name.clone());
+            }
+        }
+        for curve in &mut economy.curves {
+            curve.resource = resource_name(&curve.resource)?;
+        }
+
+        economy.simulation = None;
+        session.economy = economy.clone();
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::EconomyChanged,
+            session_id,
+            json!({
+                "change": "updated",
+                "resources": economy.resources.len(),
+                "sources": economy.sources.len(),
+                "sinks": economy.sinks.len(),
+                "curves": economy.curves.len(),
+            }),
+        );
+
+        Ok(economy)
+    }
+
+    /// Has the LLM simulate the session's economy and keeps the simulation
+    /// with it.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn simulate_economy(
+        &self,
+        session_id: &str,
+        llm_client: Option<&crate::game_design::DesignerLlmClient>,
+    ) -> Result<Simulation> {
+        let llm_client = llm_client.ok_or(GameDesignerError::LlmUnavailable)?;
+
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        if session.economy.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Session '{}' has no economy to simulate; declare its resources first",
+                session_id
+            ));
+        }
+
+        let simulation = economy::simulate(llm_client, session, self.clock.now()).await?;
+        session.economy.simulation = Some(simulation.clone());
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::EconomyChanged,
+            session_id,
+            json!({
+                "change": "simulated",
+                "flaggedFeatures": simulation
+                    .flags
+                    .iter()
+                    .map(|flag| flag.feature.as_str())
+                    .collect::<Vec<_>>(),
+            }),
+        );
+
+        Ok(simulation)
+    }
+
+    /// Has the LLM review the session's monetization against `policy` and
+    /// attaches the risk report to the session, replacing the previous one.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn review_monetization(
+        &self,
+        session_id: &str,
+        policy: MonetizationPolicy,
+        llm_client: Option<&crate::game_design::DesignerLlmClient>,
+    ) -> Result<MonetizationReport> {
+        let llm_client = llm_client.ok_or(GameDesignerError::LlmUnavailable)?;
+
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        let report = monetization::review(llm_client, session, policy, self.clock.now()).await?;
+        session.monetization_review = Some(report.clone());
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::MonetizationReviewed,
+            session_id,
+            json!({
+                "features": report.monetization_features,
+                "findings": report.findings.len(),
+                "high": report
+                    .findings
+                    .iter()
+                    .filter(|finding| finding.severity == Severity::High)
+                    .count(),
+            }),
+        );
+
+        Ok(report)
+    }
+
+    /// Returns the test plan of the reviewed `feature`, by default the
+    /// feature reviewed last. The LLM writes the plan if the feature has none
+    /// yet or `regenerate` is set. With a `path` inside the session's
+    /// repository or the persistence directory, the plan is also written
+    /// there as Markdown. Returns the feature's name, the plan, whether it
+    /// was written now and the file it was written to.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn test_plan(
+        &self,
+        session_id: &str,
+        feature: Option<&str>,
+        regenerate: bool,
+        path: Option<&str>,
+        llm_client: Option<&crate::game_design::DesignerLlmClient>,
+    ) -> Result<(String, TestPlan, bool, Option<String>)> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        let index = match feature {
+            Some(name) => session
+                .planned_features
+                .iter()
+                .position(|f| f.name.eq_ignore_ascii_case(name.trim()))
+                .ok_or_else(|| GameDesignerError::FeatureNotFound(name.to_string()))?,
+            None => session
+                .review_log
+                .iter()
+                .rev()
+                .find_map(|entry| {
+                    session.planned_features.iter().position(|f| {
+                        f.name == entry.feature && f.status == FeatureStatus::Reviewed
+                    })
+                })
+                .ok_or_else(|| anyhow::anyhow!("No feature has been reviewed yet"))?,
+        };
+
+        let feature = &session.planned_features[index];
+        if feature.status != FeatureStatus::Reviewed {
+            return Err(anyhow::anyhow!(
+                "Feature '{}' has not been reviewed yet; test plans are written for accepted \
+                 features",
+                feature.name
+            ));
+        }
+        let name = feature.name.clone();
+        let path = path
+            .map(|path| self.output_path(session, path))
+            .transpose()?;
+
+        let (test_plan, written) = match &feature.test_plan {
+            Some(test_plan) if !regenerate => (test_plan.clone(), false),
+            _ => {
+                let llm_client = llm_client.ok_or(GameDesignerError::LlmUnavailable)?;
+                let test_plan =
+                    testplan::generate(llm_client, session, feature, self.clock.now()).await?;
+
+                session.planned_features[index].test_plan = Some(test_plan.clone());
+                self.persist(session)?;
+
+                self.emit(
+                    EventKind::FeatureTestPlanned,
+                    session_id,
+                    json!({
+                        "feature": name,
+                        "playtestSteps": test_plan.playtest_steps.len(),
+                        "automatedTests": test_plan.automated_tests.len(),
+                    }),
+                );
+
+                (test_plan, true)
+            }
+        };
+
+        if let Some(path) = &path {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(
+                path,
+                format!("# Test plan: {}\n\n{}\n", name, test_plan.markdown()),
+            )?;
+        }
+        let path = path.map(|path| path.to_string_lossy().into_owned());
+
+        Ok((name, test_plan, written, path))
+    }
+
+    /// Has a model that can see images critique the UI of `screenshot`, a
+    /// screenshot of `feature`, by default the feature being implemented.
+    /// The screenshot is stored in the session's asset directory and the
+    /// critique with the feature. Returns the feature's name and the
+    /// critique.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn ui_critique(
+        &self,
+        session_id: &str,
+        feature: Option<&str>,
+        screenshot: &Upload,
+        context: Option<&str>,
+        author: Option<&str>,
+        llm_client: Option<&crate::game_design::DesignerLlmClient>,
+    ) -> Result<(String, UiCritique)> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        let llm_client = llm_client.ok_or(GameDesignerError::LlmUnavailable)?;
+        if !crate::game_design::designer_llm::vision() {
+            return Err(anyhow::anyhow!(
+                "The model can't see images; set a vision-capable model with [llm] vision_model \
+                 or GAMEDESIGNER_VISION_MODEL, or [llm] vision = true if the model is one"
+            ));
+        }
+        if screenshot.image().is_none() {
+            return Err(GameDesignerError::InvalidArguments(format!(
+                "The screenshot must be a PNG, JPEG, GIF or WebP image, got {}",
+                screenshot.mime_type
+            ))
+            .into());
+        }
+
+        let name = match feature {
+            Some(name) => name.trim().to_string(),
+            None => session
+                .next_feature_to_implement
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("No feature is being implemented"))?,
+        };
+        let index = session
+            .planned_features
+            .iter()
+            .position(|f| f.name.eq_ignore_ascii_case(&name))
+            .ok_or_else(|| GameDesignerError::FeatureNotFound(name.clone()))?;
+        let feature = &session.planned_features[index];
+        let name = feature.name.clone();
+
+        // Stored once the critique is in, like review attachments
+        let session_dir = Path::new(&self.persistence_path).join(session_id);
+        let stored = attachments::describe(&session_dir, screenshot);
+
+        let mut ui_critique = critique::critique(
+            llm_client,
+            session,
+            feature,
+            screenshot,
+            stored,
+            context,
+            self.clock.now(),
+        )
+        .await?;
+        ui_critique.author = author.map(str::to_string);
+
+        session.planned_features[index]
+            .ui_critiques
+            .push(ui_critique.clone());
+        attachments::store(self.storage.as_ref(), &session_dir, screenshot)?;
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::FeatureUiCritiqued,
+            session_id,
+            json!({
+                "feature": name,
+                "screenshot": ui_critique.screenshot,
+                "issues": ui_critique.issues.len(),
+                "author": author,
+            }),
+        );
+
+        Ok((name, ui_critique))
+    }
+
+    /// Emits the debt recorded by and paid down with the approval of
+    /// `feature`, if any.
+    fn emit_debt(&self, session_id: &str, feature: &str, recorded: &[DebtItem], paid: &[u32]) {
+        if !recorded.is_empty() {
+            self.emit(
+                EventKind::DebtRecorded,
+                session_id,
+                json!({ "feature": feature, "items": recorded }),
+            );
+        }
+
+        if !paid.is_empty() {
+            self.emit(
+                EventKind::DebtPaid,
+                session_id,
+                json!({ "feature": feature, "items": paid }),
+            );
+        }
+    }
+
+    /// Emits `milestone.completed` if the approval of `feature` left no
+    /// feature of its milestone unreviewed.
+    fn emit_milestone_completed(&self, session_id: &str, session: &SessionState, feature: &str) {
+        let Some(milestone) = session
+            .planned_features
+            .iter()
+            .find(|f| f.name == feature)
+            .and_then(|f| f.milestone.as_deref())
+        else {
+            return;
+        };
+
+        let features = session
+            .planned_features
+            .iter()
+            .filter(|f| f.milestone.as_deref() == Some(milestone))
+            .collect::<Vec<_>>();
+        if features.iter().any(|f| f.status != FeatureStatus::Reviewed) {
+            return;
+        }
+
+        self.emit(
+            EventKind::MilestoneCompleted,
+            session_id,
+            json!({
+                "milestone": milestone,
+                "features": features.iter().map(|f| &f.name).collect::<Vec<_>>(),
+            }),
+        );
+    }
+
+    /// Switches the session's designer persona and returns the previous one.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn set_persona(&self, session_id: &str, persona: Persona) -> Result<Persona> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        let previous = std::mem::replace(&mut session.persona, persona);
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::PersonaChanged,
+            session_id,
+            json!({ "from": previous.name(), "to": persona.name() }),
+        );
+
+        Ok(previous)
+    }
+
+    /// Makes the session read-only, e.g. after the game shipped. Returns the
+    /// freeze in effect, which is the earlier one if the session was frozen
+    /// already.
+    pub async fn freeze(&self, session_id: &str, reason: Option<String>) -> Result<Freeze> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        if let Some(freeze) = &session.freeze {
+            return Ok(freeze.clone());
+        }
+
+        let freeze = Freeze {
+            frozen_at: self.clock.now(),
+            reason,
+        };
+        session.freeze = Some(freeze.clone());
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::SessionFrozen,
+            session_id,
+            json!({ "reason": freeze.reason }),
+        );
+
+        Ok(freeze)
+    }
+
+    /// Lifts the freeze of the session. Returns the lifted freeze, `None` if
+    /// the session was not frozen.
+    pub async fn unfreeze(&self, session_id: &str) -> Result<Option<Freeze>> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        let Some(freeze) = session.freeze.take() else {
+            return Ok(None);
+        };
+        self.persist(session)?;
+
+        self.emit(
+            EventKind::SessionUnfrozen,
+            session_id,
+            json!({ "frozenAt": freeze.frozen_at }),
+        );
+
+        Ok(Some(freeze))
+    }
+
+    /// Fails with [`GameDesignerError::SessionFrozen`] if the session is
+    /// frozen. Unknown sessions pass, so that the tool reports them.
+    pub async fn ensure_not_frozen(&self, session_id: &str) -> Result<()> {
+        let frozen = self
+            .with_session(session_id, |session| session.freeze.is_some())
+            .await?
+            .unwrap_or(false);
+
+        if frozen {
+            return Err(GameDesignerError::SessionFrozen(session_id.to_string()).into());
+        }
+
+        Ok(())
+    }
+
+    /// Renders the session as a Markdown design document.
+    ///
+    /// The document is written to `path`, which must lie inside the session's
+    /// repository or the persistence directory, or to the session's automatic
+    /// export path if no path is given. `auto_update` enables (which requires a path)
+    /// or disables regeneration of that file after every state change.
+    /// Returns the document and the path it was written to, if any.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn export_design(
+        &self,
+        session_id: &str,
+        path: Option<&str>,
+        auto_update: Option<bool>,
+    ) -> Result<(String, Option<String>)> {
+        let mut session = self.checkout(session_id).await?;
+        let session = &mut *session;
+
+        // Exporting works on frozen sessions, changing the setting doesn't
+        if auto_update.is_some() && session.freeze.is_some() {
+            return Err(GameDesignerError::SessionFrozen(session_id.to_string()).into());
+        }
+
+        // Checked once here, as the automatic export keeps rewriting the file
+        let path = path
+            .map(|path| self.output_path(session, path))
+            .transpose()?
+            .map(|path| path.to_string_lossy().into_owned());
+
+        match auto_update {
+            Some(true) => {
+                let path = path
+                    .clone()
+                    .or_else(|| session.export_path.clone())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("A path is required to enable automatic export")
+                    })?;
+                session.export_path = Some(path);
+                self.persist(session)?;
+            }
+            Some(false) => {
+                session.export_path = None;
+                self.persist(session)?;
+            }
+            None => {}
+        }
+
+        let markdown = export::render_markdown(session);
+        let target = path.or_else(|| session.export_path.clone());
+
+        if let Some(target) = &target {
+            export::write(session, Path::new(target))?;
+        }
+
+        Ok((markdown, target))
+    }
+
+    /// Advances the onboarding interview of a session that does not exist yet.
+    ///
+    /// Without an `answer` the pending question is repeated (starting the
+    /// interview if needed). Once the last question is answered, the design
+    /// document is synthesized and the session is created.
+    #[tracing::instrument(skip_all, fields(session_id = %session_id))]
+    pub async fn interview_step(
+        &self,
+        session_id: &str,
+        answer: Option<String>,
+        persona: Option<Persona>,
+        llm_client: Option<&crate::game_design::DesignerLlmClient>,
+    ) -> Result<InterviewStep> {
+        validate_session_name(session_id)?;
+        if self.session_exists(session_id) {
+            return Err(anyhow::anyhow!("Session '{}' already exists", session_id));
+        }
+
+        let mut interview =
+            match Interview::load(self.storage.as_ref(), &self.persistence_path, session_id)? {
+                Some(interview) => interview,
+                None => Interview::new(
+                    session_id.to_string(),
+                    persona
+                        .or(config::current().default_persona)
+                        .unwrap_or_default(),
+                    self.clock.now(),
+                ),
+            };
+
+        if let Some(persona) = persona {
+            interview.persona = persona;
+        }
+
+        if let Some(answer) = answer {
+            let answer = answer.trim();
+            if answer.is_empty() {
+                return Err(anyhow::anyhow!("Answer must not be empty"));
+            }
+            interview.answers.push(answer.to_string());
+        }
+
+        if let Some(question) = interview.next_question() {
+            interview.save(self.storage.as_ref(), &self.persistence_path)?;
+            return Ok(question);
+        }
+
+        let document = interview.synthesize(llm_client).await;
+
+        self.create_session(
+            session_id.to_string(),
+            document.clone(),
+            interview.persona,
+            None,
+            SessionSetup::default(),
+        )
+        .await?;
+
+        self.storage
+            .remove_file(&Interview::file_path(&self.persistence_path, session_id))
+            .ok();
+
+        Ok(InterviewStep::Completed { document })
+    }
+}
+
+/// Builds the part of feature proposal prompts describing the game: the
+/// design context, the planned features, the rejected features and the
+/// implementation reports, digesting older reports if the prompt would not
+/// fit into the context window otherwise.
+async fn feature_context(
+    session: &mut SessionState,
+    llm_client: &crate::game_design::DesignerLlmClient,
+) -> String {
+    let mut prompt = memory::design_context(session);
+
+    // Add information about already planned features
+    if !session.planned_features.is_empty() {
+        prompt.push_str("Already planned features:\n");
+        for feature in &session.planned_features {
+            prompt.push_str(&format!("- {} ({:?})\n", feature.name, feature.status));
+        }
+        prompt.push('\n');
+    }
+
+    let ideas = session
+        .ideas
+        .iter()
+        .filter(|idea| idea.feature.is_none())
+        .collect::<Vec<_>>();
+
+    if !ideas.is_empty() {
+        prompt.push_str("Shortlisted ideas from brainstorming, best first within each topic:\n");
+        for idea in ideas {
+            prompt.push_str(&format!(
+                "- {} ({}, score {}): {}\n",
+                idea.name,
+                idea.topic,
+                idea.total(),
+                idea.description
+            ));
+        }
+        prompt.push('\n');
+    }
+
+    prompt.push_str(&graveyard::section(session));
+
+    let reports_budget = context::prompt_budget()
+        .saturating_sub(context::estimate_tokens(&prompt) + PROMPT_INSTRUCTIONS_TOKENS);
+    let implemented_section =
+        context::implemented_features_section(llm_client, session, reports_budget).await;
+    prompt.push_str(&implemented_section);
+
+    prompt
+}
+
+/// Records that `feature_name` builds on the pooled idea named `idea`, so
+/// that the idea is no longer offered.
+fn mark_idea_used(session: &mut SessionState, idea: Option<&str>, feature_name: &str) {
+    let Some(idea) = idea.map(str::trim) else {
+        return;
+    };
+
+    if let Some(idea) = session
+        .ideas
+        .iter_mut()
+        .find(|pooled| pooled.feature.is_none() && pooled.name.eq_ignore_ascii_case(idea))
+    {
+        idea.feature = Some(feature_name.to_string());
+    }
+}
+
+/// Parses the JSON `nextFeature` response into the feature and the raw
+/// JSON, which may also name the idea and debt the feature takes up.
+fn parse_next_feature(response: &str) -> Result<(Feature, serde_json::Value)> {
+    let json_value = serde_json::from_str::<serde_json::Value>(response)
+        .map_err(|_| anyhow::anyhow!("LLM response was not valid JSON: {}", response))?;
+
+    let feature = parse_feature(&json_value).ok_or_else(|| {
+        anyhow::anyhow!(
+            "LLM response did not contain expected 'name', 'description' and \
+             'acceptance_criteria' fields"
+        )
+    })?;
+
+    Ok((feature, json_value))
+}
+
+/// Reads a feature proposed by the LLM. The name, description and at least
+/// one acceptance criterion are required.
+fn parse_feature(json_value: &serde_json::Value) -> Option<Feature> {
+    let name = json_value.get("name").and_then(|v| v.as_str())?;
+    let description = json_value.get("description").and_then(|v| v.as_str())?;
+    let acceptance_criteria = json_value
+        .get("acceptance_criteria")
+        .and_then(|v| v.as_array())?
+        .iter()
+        .filter_map(|criterion| criterion.as_str())
+        .map(str::trim)
+        .filter(|criterion| !criterion.is_empty())
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if acceptance_criteria.is_empty() {
+        return None;
+    }
+
+    // Missing or malformed asset lists are not worth failing over
+    let required_assets: Vec<AssetRequirement> = json_value
+        .get("required_assets")
+        .cloned()
+        .and_then(|assets| serde_json::from_value(assets).ok())
+        .unwrap_or_default();
+
+    // Estimates are optional for older or sloppier responses too
+    let estimate = |field: &str| {
+        json_value
+            .get(field)
+            .cloned()
+            .and_then(|value| serde_json::from_value(value).ok())
+    };
+
+    Some(Feature {
+        acceptance_criteria,
+        required_assets,
+        estimated_effort: estimate("estimated_effort"),
+        risk: estimate("risk"),
+        milestone: json_value
+            .get("milestone")
+            .and_then(|v| v.as_str())
+            .map(str::to_string),
+        implementation_hints: json_value
+            .get("implementation_hints")
+            .and_then(|v| v.as_str())
+            .filter(|hints| !hints.trim().is_empty())
+            .map(str::to_string),
+        ..Feature::new(name.to_string(), description.to_string())
+    })
+}
+
+/// Parses the contents of a session file, reporting a file that holds no
+/// session as corrupt.
+fn parse_session(path: impl AsRef<Path>, json: &str) -> Result<SessionState, GameDesignerError> {
+    serde_json::from_str(json).map_err(|e| GameDesignerError::StorageCorrupt {
+        path: path.as_ref().to_path_buf(),
+        message: e.to_string(),
+    })
+}
+
+/// Generates implementation hints for the feature at `index` if the session
+/// has an engine, the feature has no hints yet and an LLM client is
+/// available. Returns whether hints were added.
+async fn fill_implementation_hints(
+    session: &mut SessionState,
+    index: usize,
+    llm_client: Option<&crate::game_design::DesignerLlmClient>,
+) -> bool {
+    let (Some(engine), Some(llm_client)) = (session.engine, llm_client) else {
+        return false;
+    };
+
+    let feature = &session.planned_features[index];
+    if feature.implementation_hints.is_some() {
+        return false;
+    }
+
+    match engine::implementation_hints(llm_client, session, engine, feature).await {
+        Ok(hints) => {
+            session.planned_features[index].implementation_hints = Some(hints);
+            true
+        }
+        Err(e) => {
+            tracing::warn!(
+                "Failed to generate implementation hints of '{}': {}",
+                feature.name,
+                e
+            );
+            false
+        }
+    }
+}
+
+/// Records the repository HEAD as the base commit of the feature if the
+/// session has a repository and the feature has none yet.
+/// Returns whether a base commit was recorded.
+async fn record_base_commit(session: &mut SessionState, feature_name: &str) -> bool {
+    let Some(repo) = session.repo_path.clone() else {
+        return false;
+    };
+
+    let Some(feature) = session
+        .planned_features
+        .iter_mut()
+        .find(|f| f.name == feature_name && f.base_commit.is_none())
+    else {
+        return false;
+    };
+
+    match git::head(Path::new(&repo)).await {
+        Ok(head) => {
+            feature.base_commit = Some(head);
+            true
+        }
+        Err(e) => {
+            tracing::warn!("Failed to record base commit of '{}': {}", feature_name, e);
+            false
+        }
+    }
+}
+
+/// Derives a Conventional Commits scope from a feature name, e.g.
+/// `Double Jump` becomes `double-jump`.
+fn commit_scope(feature_name: &str) -> String {
+    feature_name
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
 }
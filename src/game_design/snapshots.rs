@@ -0,0 +1,107 @@
+//! Pre-change snapshots of sessions, the basis of `designUndo`.
+//!
+//! Before a session file is overwritten, its previous contents are stored as
+//! `snapshots/<session>/<seq>.json`, where `seq` is the sequence number the
+//! next event of the session will get. A change that writes the session
+//! several times before emitting its events therefore keeps only the state
+//! from before the first write, and undoing restores the state from before
+//! the events starting at `seq`.
+
+use crate::game_design::storage::Storage;
+use anyhow::Result;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Snapshots kept per session; older ones are pruned.
+const MAX_SNAPSHOTS: usize = 50;
+
+/// Snapshot directory of the sessions in one persistence directory.
+#[derive(Clone)]
+pub struct SnapshotStore {
+    dir: PathBuf,
+    storage: Arc<dyn Storage>,
+}
+
+impl SnapshotStore {
+    /// Creates a store rooted at the persistence directory.
+    pub fn new(dir: impl AsRef<Path>, storage: Arc<dyn Storage>) -> Self {
+        Self {
+            dir: dir.as_ref().join("snapshots"),
+            storage,
+        }
+    }
+
+    fn session_dir(&self, session_id: &str) -> PathBuf {
+        self.dir.join(session_id)
+    }
+
+    fn path(&self, session_id: &str, seq: u64) -> PathBuf {
+        self.session_dir(session_id)
+            .join(format!("{:010}.json", seq))
+    }
+
+    /// Stores `contents` as the state before the change starting at event
+    /// `seq`, unless a snapshot for that change exists already.
+    pub fn save(&self, session_id: &str, seq: u64, contents: &str) -> Result<()> {
+        let path = self.path(session_id, seq);
+
+        if self.storage.exists(&path) {
+            return Ok(());
+        }
+
+        self.storage.create_dir_all(&self.session_dir(session_id))?;
+        self.storage.write(&path, contents)?;
+
+        let sequences = self.sequences(session_id)?;
+        for seq in &sequences[..sequences.len().saturating_sub(MAX_SNAPSHOTS)] {
+            self.storage.remove_file(&self.path(session_id, *seq))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the sequence numbers of the session's snapshots, ascending.
+    fn sequences(&self, session_id: &str) -> Result<Vec<u64>> {
+        let dir = self.session_dir(session_id);
+
+        if !self.storage.exists(&dir) {
+            return Ok(Vec::new());
+        }
+
+        let mut sequences = self
+            .storage
+            .list(&dir)?
+            .into_iter()
+            .filter_map(|path| {
+                path.file_name()?
+                    .to_str()?
+                    .strip_suffix(".json")?
+                    .parse::<u64>()
+                    .ok()
+            })
+            .collect::<Vec<_>>();
+        sequences.sort_unstable();
+
+        Ok(sequences)
+    }
+
+    /// Returns the most recent snapshot as its sequence number and contents.
+    pub fn latest(&self, session_id: &str) -> Result<Option<(u64, String)>> {
+        let Some(seq) = self.sequences(session_id)?.pop() else {
+            return Ok(None);
+        };
+
+        Ok(Some((
+            seq,
+            self.storage.read_to_string(&self.path(session_id, seq))?,
+        )))
+    }
+
+    /// Deletes the snapshot taken before event `seq`.
+    pub fn remove(&self, session_id: &str, seq: u64) -> Result<()> {
+        self.storage.remove_file(&self.path(session_id, seq))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+//! Self-consistency check of feature specifications.
+//!
+//! Before `nextFeature` hands out a newly generated specification, a short
+//! second LLM call checks it against the design document, the established
+//! decisions and the features already planned: does it duplicate one of
+//! them, or contradict a pillar or decision? A specification with problems is
+//! regenerated once with the problems attached; the check and whether the
+//! specification was regenerated are logged as a `feature.spec_checked`
+//! event.
+
+use crate::game_design::{
+    DesignerLlmClient, config,
+    designer_llm::{self, ChatMessage, Sampling, extract_json},
+    memory, prompts,
+    state::{Feature, SessionState},
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::env;
+
+/// Tool name the check's LLM call is sampled as, so `[llm.tools.specCheck]`
+/// can make it cheaper than `nextFeature` itself.
+pub const SAMPLING_TOOL: &str = "specCheck";
+
+/// Outcome of the check of one specification.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpecCheck {
+    /// Duplicates and contradictions found; empty if the specification is
+    /// consistent.
+    #[serde(default)]
+    pub problems: Vec<String>,
+}
+
+impl SpecCheck {
+    pub fn consistent(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Whether new specifications are checked. Read from
+/// `GAMEDESIGNER_SPEC_CHECK` (`true`/`false`), falling back to the
+/// configuration file; on by default.
+pub fn enabled() -> bool {
+    env::var("GAMEDESIGNER_SPEC_CHECK")
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .or(config::current().features.spec_check)
+        .unwrap_or(true)
+}
+
+/// Asks the LLM whether `feature` duplicates a feature of `session` or
+/// contradicts its design.
+pub async fn check(
+    llm_client: &DesignerLlmClient,
+    session: &SessionState,
+    feature: &Feature,
+) -> Result<SpecCheck> {
+    let mut prompt = memory::design_context(session);
+
+    if !session.planned_features.is_empty() {
+        prompt.push_str("Features already planned or implemented:\n");
+        for planned in &session.planned_features {
+            prompt.push_str(&format!(
+                "- {} ({:?}): {}\n",
+                planned.name, planned.status, planned.description
+            ));
+        }
+        prompt.push('\n');
+    }
+
+    prompt.push_str(&format!(
+        "Proposed next feature:\n{}\n\n",
+        feature.specification()
+    ));
+    prompt.push_str(
+        "Check the proposed feature for problems: does it duplicate or largely overlap a \
+         feature listed above, or contradict the design document, its pillars or an \
+         established decision? Respond with a JSON object with a 'problems' field listing \
+         each problem in one sentence, or an empty list if there are none: \
+         {\"problems\": [\"Problem\"]}. Only return the JSON, nothing else.",
+    );
+
+    let messages = vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: session.system_prompt(prompts::SPEC_CHECK),
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content: prompt,
+        },
+    ];
+
+    let response = designer_llm::sampled(
+        SAMPLING_TOOL.to_string(),
+        Sampling::default(),
+        llm_client.call_llm(messages),
+    )
+    .await?;
+
+    let json = extract_json(&response).ok_or_else(|| {
+        anyhow::anyhow!("LLM response did not contain a JSON object: {}", response)
+    })?;
+
+    Ok(serde_json::from_str(json)?)
+}
+
+/// Asks for a different specification than `feature`, avoiding `problems`.
+pub fn regeneration_instruction(feature: &Feature, problems: &[String]) -> String {
+    let mut text = format!(
+        "\n\nA previous proposal, '{}', was rejected for these problems:\n",
+        feature.name
+    );
+    for problem in problems {
+        text.push_str(&format!("- {}\n", problem));
+    }
+    text.push_str("Propose a feature that avoids them, in the same JSON format.");
+
+    text
+}
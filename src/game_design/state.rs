@@ -1,7 +1,26 @@
 //! Defines data structures for session state, features, chat messages, etc.
 
+use crate::game_design::{
+    assets::AssetRequirement,
+    attachments::Attachment,
+    brainstorm::Idea,
+    criteria::CriterionResult,
+    critique::UiCritique,
+    debt::{DebtItem, DebtPriority},
+    economy::Economy,
+    engine::{ApiReference, Engine},
+    level::Level,
+    monetization::MonetizationReport,
+    panel::PanelReview,
+    persona::Persona,
+    prompts,
+    review_depth::ReviewDepth,
+    scope::{Effort, Risk},
+    testplan::TestPlan,
+};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Represents a designed feature.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,10 +28,182 @@ pub struct Feature {
     pub name: String,
     pub description: String, // Detailed specification
     pub status: FeatureStatus,
+    /// Verifiable statements a review checks the implementation against.
+    #[serde(default)]
+    pub acceptance_criteria: Vec<String>,
+    /// Art and audio assets the feature needs.
+    #[serde(default)]
+    pub required_assets: Vec<AssetRequirement>,
+    /// Commits that implemented the feature, in submission order.
+    #[serde(default)]
+    pub commits: Vec<String>,
+    /// Actual working time spent on the feature, in minutes.
+    #[serde(default)]
+    pub time_spent_minutes: u64,
+    /// When the currently running work timer was started, if any.
+    #[serde(default)]
+    pub work_started_at: Option<DateTime<Utc>>,
+    /// Designer's estimate of the work the feature takes.
+    #[serde(default)]
+    pub estimated_effort: Option<Effort>,
+    #[serde(default)]
+    pub risk: Option<Risk>,
+    /// Development milestone of the design document the feature belongs to.
+    #[serde(default)]
+    pub milestone: Option<String>,
+    /// Repository HEAD when the feature was handed out, diffed against in
+    /// reviews.
+    #[serde(default)]
+    pub base_commit: Option<String>,
+    /// Repository HEAD at the last review that included the diff.
+    #[serde(default)]
+    pub reviewed_commit: Option<String>,
+    /// How to implement the feature in the session's engine.
+    #[serde(default)]
+    pub implementation_hints: Option<String>,
+    /// Modules and types of the engine's API the feature uses, looked up in
+    /// the engine's documentation.
+    #[serde(default)]
+    pub api_references: Vec<ApiReference>,
+    /// Playtest steps and automated tests, once the feature is reviewed.
+    #[serde(default)]
+    pub test_plan: Option<TestPlan>,
+    /// Depth of the feature's last review.
+    #[serde(default)]
+    pub review_depth: Option<ReviewDepth>,
+    /// Changes demanded by a review that reached its follow-up limit and
+    /// rejected the implementation; cleared by the next `featureReview`.
+    #[serde(default)]
+    pub rework: Vec<String>,
+    /// Last human decision on the feature, see `humanApprove`.
+    #[serde(default)]
+    pub approval: Option<Approval>,
+    /// Caveats of the review that accepted the feature, recorded as debt
+    /// once a person approves it.
+    #[serde(default)]
+    pub pending_caveats: Vec<(DebtPriority, String)>,
+    /// Rejected feature the proposal resembles, see `featureReject`.
+    #[serde(default)]
+    pub similar_rejected: Option<RejectedSimilarity>,
+    /// Critiques of screenshots of the feature, see `uiCritique`.
+    #[serde(default)]
+    pub ui_critiques: Vec<UiCritique>,
     // TODO: Add fields for implementation details/reports if needed directly here
     // or keep them separate in SessionState under `implemented_features_reports`.
 }
 
+impl Feature {
+    /// Creates a planned feature with no tracking data.
+    pub fn new(name: String, description: String) -> Self {
+        Self {
+            name,
+            description,
+            status: FeatureStatus::Planned,
+            acceptance_criteria: Vec::new(),
+            required_assets: Vec::new(),
+            commits: Vec::new(),
+            time_spent_minutes: 0,
+            work_started_at: None,
+            estimated_effort: None,
+            risk: None,
+            milestone: None,
+            base_commit: None,
+            reviewed_commit: None,
+            implementation_hints: None,
+            api_references: Vec::new(),
+            test_plan: None,
+            review_depth: None,
+            rework: Vec::new(),
+            approval: None,
+            pending_caveats: Vec::new(),
+            similar_rejected: None,
+            ui_critiques: Vec::new(),
+        }
+    }
+
+    /// Records commits that implemented the feature, skipping known ones.
+    pub fn add_commits(&mut self, commits: &[String]) {
+        for commit in commits {
+            if !self.commits.contains(commit) {
+                self.commits.push(commit.clone());
+            }
+        }
+    }
+
+    /// Stops the running work timer, if any, adding the elapsed time
+    /// (rounded up to whole minutes). Returns the minutes added.
+    pub fn stop_work(&mut self, now: DateTime<Utc>) -> u64 {
+        let Some(started_at) = self.work_started_at.take() else {
+            return 0;
+        };
+
+        let seconds = (now - started_at).num_seconds().max(0) as u64;
+        let minutes = seconds.div_ceil(60);
+        self.time_spent_minutes += minutes;
+
+        minutes
+    }
+
+    /// Renders the description followed by the estimates, required assets and
+    /// implementation hints, as handed to the implementing agent.
+    pub fn specification(&self) -> String {
+        let mut specification = self.description.clone();
+
+        if !self.acceptance_criteria.is_empty() {
+            specification.push_str("\n\nAcceptance criteria:");
+            for criterion in &self.acceptance_criteria {
+                specification.push_str(&format!("\n- {}", criterion));
+            }
+        }
+
+        if let Some(effort) = self.estimated_effort {
+            specification.push_str(&format!("\n\nEstimated effort: {:?}", effort));
+            if let Some(risk) = self.risk {
+                specification.push_str(&format!(", risk: {:?}", risk));
+            }
+        }
+
+        if !self.required_assets.is_empty() {
+            specification.push_str("\n\nRequired assets:");
+            for asset in &self.required_assets {
+                specification.push_str(&format!("\n- [{}] {}", asset.kind.name(), asset.name));
+                if !asset.description.is_empty() {
+                    specification.push_str(&format!(": {}", asset.description));
+                }
+            }
+        }
+
+        if let Some(hints) = &self.implementation_hints {
+            specification.push_str(&format!("\n\nImplementation hints:\n{}", hints.trim()));
+        }
+
+        if let Some(similar) = &self.similar_rejected {
+            specification.push_str(&format!(
+                "\n\nSimilar to previously rejected '{}' ({}), differences: {}",
+                similar.feature,
+                similar.reason,
+                if similar.differences.is_empty() {
+                    "none in its acceptance criteria, effort or milestone".to_string()
+                } else {
+                    similar.differences.join("; ")
+                }
+            ));
+        }
+
+        if !self.api_references.is_empty() {
+            specification.push_str("\n\nEngine API:");
+            for reference in &self.api_references {
+                specification.push_str(&format!("\n- `{}`", reference.path));
+                if !reference.summary.is_empty() {
+                    specification.push_str(&format!(": {}", reference.summary));
+                }
+            }
+        }
+
+        specification
+    }
+}
+
 /// Status of a feature.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum FeatureStatus {
@@ -21,11 +212,301 @@ pub enum FeatureStatus {
     Implemented,
     Reviewed,
     NeedsRework,
+    /// Accepted by the designer, waiting for `humanApprove` because the
+    /// configuration requires human approval.
+    AwaitingApproval,
+}
+
+/// A buried feature a new proposal resembles, see
+/// [`graveyard`](crate::game_design::graveyard).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RejectedSimilarity {
+    /// Name of the rejected feature.
+    pub feature: String,
+    /// Why it was rejected.
+    pub reason: String,
+    /// Similarity of the proposal to it in `0.0..=1.0`.
+    pub score: f32,
+    /// What the proposal does differently.
+    #[serde(default)]
+    pub differences: Vec<String>,
+}
+
+/// A proposed feature the developer rejected with `featureReject`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RejectedFeature {
+    pub feature: Feature,
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Person who rejected it.
+    #[serde(default)]
+    pub author: Option<String>,
+    pub rejected_at: DateTime<Utc>,
+}
+
+/// A human decision on a feature the designer accepted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Approval {
+    pub approved: bool,
+    /// Person who signed the decision.
+    pub author: String,
+    /// Why, required when the feature is sent back.
+    #[serde(default)]
+    pub comment: Option<String>,
+    pub decided_at: DateTime<Utc>,
+}
+
+/// Studio, authorship and license of a project, stamped into exports.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ProjectMetadata {
+    #[serde(default)]
+    pub studio: Option<String>,
+    #[serde(default)]
+    pub authors: Vec<String>,
+    /// License of the design documents, e.g. `CC-BY-4.0`.
+    #[serde(default)]
+    pub license: Option<String>,
+    #[serde(default)]
+    pub copyright_year: Option<i32>,
+}
+
+impl ProjectMetadata {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Overwrites the fields that are set in `update`.
+    pub fn merge(&mut self, update: ProjectMetadata) {
+        if update.studio.is_some() {
+            self.studio = update.studio;
+        }
+        if !update.authors.is_empty() {
+            self.authors = update.authors;
+        }
+        if update.license.is_some() {
+            self.license = update.license;
+        }
+        if update.copyright_year.is_some() {
+            self.copyright_year = update.copyright_year;
+        }
+    }
+
+    /// Renders a one-line attribution such as
+    /// `© 2025 Nine Lives Studio. Authors: Ada, Grace. Licensed under CC-BY-4.0.`,
+    /// or `None` if no metadata is set.
+    pub fn attribution(&self) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let mut parts = Vec::new();
+
+        let holder = self
+            .studio
+            .clone()
+            .or_else(|| (!self.authors.is_empty()).then(|| self.authors.join(", ")));
+        match (self.copyright_year, holder) {
+            (Some(year), Some(holder)) => parts.push(format!("© {} {}.", year, holder)),
+            (Some(year), None) => parts.push(format!("© {}.", year)),
+            (None, Some(holder)) => parts.push(format!("© {}.", holder)),
+            (None, None) => {}
+        }
+
+        if self.studio.is_some() && !self.authors.is_empty() {
+            parts.push(format!("Authors: {}.", self.authors.join(", ")));
+        }
+
+        if let Some(license) = &self.license {
+            parts.push(format!("Licensed under {}.", license));
+        }
+
+        Some(parts.join(" "))
+    }
+}
+
+/// A durable, project-wide design decision remembered across prompts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DesignDecision {
+    pub decision: String,
+    /// The feature whose review established the decision.
+    #[serde(default)]
+    pub source_feature: Option<String>,
+}
+
+/// A superseded revision of the game design document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DesignVersion {
+    pub version: u32,
+    pub document: String,
+    /// Summary of the edit that replaced this revision.
+    pub superseded_by_change: String,
+    pub superseded_at: DateTime<Utc>,
+}
+
+/// A question asked through `featureAsk` together with the designer's answer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QaEntry {
+    /// The feature being implemented when the question was asked, if any.
+    pub feature: Option<String>,
+    pub question: String,
+    pub answer: String,
+    pub asked_at: DateTime<Utc>,
+    /// Agent or person who asked, if given.
+    #[serde(default)]
+    pub author: Option<String>,
+}
+
+/// Kind of a submission in the review transcript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewEntryKind {
+    /// An implementation report submitted with `featureReview`.
+    Report,
+    /// An answer to review questions submitted with `reviewReply`.
+    Reply,
+}
+
+/// A submission to the designer's review together with its response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewEntry {
+    pub feature: String,
+    pub kind: ReviewEntryKind,
+    /// Agent or person who submitted, if given.
+    pub author: Option<String>,
+    pub content: String,
+    pub response: String,
+    /// Results of the feature's acceptance criteria, in order.
+    #[serde(default)]
+    pub criteria: Vec<CriterionResult>,
+    /// Individual reviews of a panel; `response` is their consensus.
+    #[serde(default)]
+    pub panel: Vec<PanelReview>,
+    /// Screenshots and captures submitted with the report.
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+    pub submitted_at: DateTime<Utc>,
+}
+
+/// Contributions of one author to a session.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AuthorStats {
+    pub reports: usize,
+    pub replies: usize,
+    pub questions: usize,
+}
+
+/// The issue mirroring a feature on the team's tracker.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardLink {
+    pub issue: u64,
+    pub url: String,
+    /// Status of the feature and of the issue after the last sync, which
+    /// tells the side that changed since.
+    pub synced_status: FeatureStatus,
+}
+
+/// The GitHub repository a session's features are mirrored to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Board {
+    /// Repository as `owner/name`.
+    pub repository: String,
+    /// Issues of the features, keyed by feature name.
+    pub links: BTreeMap<String, BoardLink>,
+    pub synced_at: DateTime<Utc>,
+}
+
+/// A character of the story.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Character {
+    pub name: String,
+    /// Part the character plays, e.g. `protagonist` or `mentor`.
+    #[serde(default)]
+    pub role: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// What the character wants.
+    #[serde(default)]
+    pub motivation: Option<String>,
+    /// How the character changes over the story.
+    #[serde(default)]
+    pub arc: Option<String>,
+    /// How the character speaks.
+    #[serde(default)]
+    pub voice: Option<String>,
+}
+
+/// A story beat: one event that moves the story forward.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Beat {
+    pub title: String,
+    pub description: String,
+    /// Names of the characters taking part.
+    #[serde(default)]
+    pub characters: Vec<String>,
+    /// The feature through which the player experiences the beat.
+    #[serde(default)]
+    pub feature: Option<String>,
 }
 
+/// An act of the story with its beats in story order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Act {
+    pub name: String,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub beats: Vec<Beat>,
+}
+
+/// Narrative design of a story-driven game, kept next to its features.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Narrative {
+    /// How dialogue is written: tone, vocabulary and what to avoid.
+    #[serde(default)]
+    pub style_guide: Option<String>,
+    #[serde(default)]
+    pub characters: Vec<Character>,
+    /// Acts in story order.
+    #[serde(default)]
+    pub acts: Vec<Act>,
+}
+
+impl Narrative {
+    /// Whether no part of the story has been designed yet.
+    pub fn is_empty(&self) -> bool {
+        self.style_guide.is_none() && self.characters.is_empty() && self.acts.is_empty()
+    }
+
+    /// Looks up a character by name, ignoring case.
+    pub fn character(&self, name: &str) -> Option<&Character> {
+        self.characters
+            .iter()
+            .find(|character| character.name.eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// A feature offered by `nextFeature` as one of several alternatives.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureCandidate {
+    /// Position in the designer's ranking, `1` for the recommended one.
+    pub rank: u32,
+    pub feature: Feature,
+    /// Why the designer ranks the candidate where it does.
+    pub rationale: String,
+    /// Pooled idea the candidate builds on.
+    #[serde(default)]
+    pub idea: Option<String>,
+}
+
+/// Version of the session file format written by this build.
+pub const SESSION_SCHEMA_VERSION: u32 = 1;
+
 /// Represents the state of a single game design session.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionState {
+    /// Version of the file format; files written before versioning are `0`.
+    #[serde(default)]
+    pub schema_version: u32,
     pub id: String,
     pub initial_description: String,
     /// The full conversation history with the designer LLM for this session.
@@ -38,19 +519,177 @@ pub struct SessionState {
     /// The name of the feature currently expected to be implemented next.
     /// This helps track the designer LLM's plan.
     pub next_feature_to_implement: Option<String>,
+    /// Durable decisions extracted from reviews, injected into every prompt.
+    #[serde(default)]
+    pub design_memory: Vec<DesignDecision>,
+    /// Compact digests of older implementation reports, keyed by feature name.
+    /// Used in place of the full report when prompts approach the context limit.
+    #[serde(default)]
+    pub report_digests: HashMap<String, String>,
+    /// Previous revisions of `initial_description`, oldest first.
+    /// The current document is version `design_history.len() + 1`.
+    #[serde(default)]
+    pub design_history: Vec<DesignVersion>,
+    /// Every `featureAsk` question and answer, oldest first.
+    #[serde(default)]
+    pub qa_log: Vec<QaEntry>,
+    /// Every `featureReview` report and `reviewReply`, oldest first.
+    #[serde(default)]
+    pub review_log: Vec<ReviewEntry>,
+    /// The designer persona that flavours prompts and review strictness.
+    #[serde(default)]
+    pub persona: Persona,
+    /// The genre template the session was created from, if any.
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Genre-specific criteria checked in every feature review.
+    #[serde(default)]
+    pub review_criteria: Vec<String>,
+    /// Path the Markdown design document is regenerated to after every
+    /// state change, if automatic export is enabled.
+    #[serde(default)]
+    pub export_path: Option<String>,
+    /// Effort points the roadmap may take, see `scopeReport`.
+    #[serde(default)]
+    pub effort_budget: Option<u32>,
+    /// Root of the game's git repository, used to attach diffs to reviews.
+    #[serde(default)]
+    pub repo_path: Option<String>,
+    /// Engine the game is built with; tailors implementation hints.
+    #[serde(default)]
+    pub engine: Option<Engine>,
+    /// Studio, authors and license stamped into exports.
+    #[serde(default)]
+    pub metadata: ProjectMetadata,
+    /// GitHub issues the features are mirrored to, see `boardSync`.
+    #[serde(default)]
+    pub board: Option<Board>,
+    /// Language the designer writes in, e.g. `German`; English if unset.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Alternatives of the last `nextFeature` call with candidates, waiting
+    /// for `featureSelect`.
+    #[serde(default)]
+    pub feature_candidates: Vec<FeatureCandidate>,
+    /// Candidates the developer picked another over, never proposed again.
+    #[serde(default)]
+    pub passed_over_candidates: Vec<FeatureCandidate>,
+    /// Proposals the developer rejected, see `featureReject`.
+    #[serde(default)]
+    pub graveyard: Vec<RejectedFeature>,
+    /// Shortlisted ideas of `designBrainstorm`, offered to `nextFeature`.
+    #[serde(default)]
+    pub ideas: Vec<Idea>,
+    /// Characters, acts and beats of the story, see `storyOutline`.
+    #[serde(default)]
+    pub narrative: Narrative,
+    /// Levels with their briefs, see `levelBrief`.
+    #[serde(default)]
+    pub levels: Vec<Level>,
+    /// Resources, flows and progressions of the economy, see `economyModel`.
+    #[serde(default)]
+    pub economy: Economy,
+    /// Risk report of the last `monetizationReview`.
+    #[serde(default)]
+    pub monetization_review: Option<MonetizationReport>,
+    /// Caveats of accepted reviews, kept until paid down, see `debtList`.
+    #[serde(default)]
+    pub tech_debt: Vec<DebtItem>,
+    /// Set by `designFreeze`; a frozen session only serves read tools and
+    /// exports.
+    #[serde(default)]
+    pub freeze: Option<Freeze>,
+    /// Depth of reviews that don't choose one, see `sessionConfigure`.
+    #[serde(default)]
+    pub review_depth: ReviewDepth,
+    /// Reviews go to a panel of reviewers unless `featureReview` says
+    /// otherwise, see `sessionConfigure`.
+    #[serde(default)]
+    pub review_panel: bool,
+    /// Named project conventions injected into every feature and review
+    /// prompt, see `conventionsSet`.
+    #[serde(default)]
+    pub conventions: BTreeMap<String, String>,
     // TODO: Add state for ongoing reviews (pending questions, feature under review)
 }
 
+/// Why and since when a session is read-only.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Freeze {
+    pub frozen_at: DateTime<Utc>,
+    /// E.g. "Shipped 1.0, kept for the post-mortem".
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
 impl SessionState {
     /// Creates a new `SessionState`.
     pub fn new(id: String, initial_description: String) -> Self {
         Self {
+            schema_version: SESSION_SCHEMA_VERSION,
             id,
             initial_description,
             llm_chat_history: Vec::new(),
             planned_features: Vec::new(),
             implemented_features_reports: HashMap::new(),
             next_feature_to_implement: None,
+            design_memory: Vec::new(),
+            report_digests: HashMap::new(),
+            design_history: Vec::new(),
+            qa_log: Vec::new(),
+            review_log: Vec::new(),
+            persona: Persona::default(),
+            template: None,
+            review_criteria: Vec::new(),
+            export_path: None,
+            effort_budget: None,
+            repo_path: None,
+            engine: None,
+            metadata: ProjectMetadata::default(),
+            board: None,
+            language: None,
+            feature_candidates: Vec::new(),
+            passed_over_candidates: Vec::new(),
+            graveyard: Vec::new(),
+            ideas: Vec::new(),
+            narrative: Narrative::default(),
+            levels: Vec::new(),
+            economy: Economy::default(),
+            monetization_review: None,
+            tech_debt: Vec::new(),
+            freeze: None,
+            review_depth: ReviewDepth::default(),
+            review_panel: false,
+            conventions: BTreeMap::new(),
+        }
+    }
+
+    /// Loads the named system prompt in the session's persona and language.
+    pub fn system_prompt(&self, name: &str) -> String {
+        prompts::localized(self.persona.system_prompt(name), self.language.as_deref())
+    }
+
+    /// Returns the version number of the current design document.
+    pub fn design_version(&self) -> u32 {
+        self.design_history.len() as u32 + 1
+    }
+
+    /// Counts reports, replies and questions per author. Submissions without
+    /// an author are counted under `None`.
+    pub fn author_stats(&self) -> BTreeMap<Option<String>, AuthorStats> {
+        let mut stats: BTreeMap<Option<String>, AuthorStats> = BTreeMap::new();
+
+        for entry in &self.review_log {
+            let author = stats.entry(entry.author.clone()).or_default();
+            match entry.kind {
+                ReviewEntryKind::Report => author.reports += 1,
+                ReviewEntryKind::Reply => author.replies += 1,
+            }
+        }
+        for entry in &self.qa_log {
+            stats.entry(entry.author.clone()).or_default().questions += 1;
         }
+
+        stats
     }
 }
@@ -0,0 +1,285 @@
+//! Status reports of a session's activity over a period, for stand-up notes.
+//!
+//! The event log is the source of what happened: which features were
+//! approved, how many review rounds a feature has gone through and how much
+//! work and designer time the period took. The session itself only adds what
+//! is true now, such as the features still under review and the milestone
+//! that comes next.
+
+use crate::game_design::{
+    events::{EventKind, SessionEvent},
+    scope,
+    state::{FeatureStatus, SessionState},
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Review rounds without approval after which a feature counts as blocked.
+pub const BLOCKED_ROUNDS: usize = 3;
+
+/// A feature under review.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewStatus {
+    pub feature: String,
+    /// Reviews and replies since the feature was last approved.
+    pub rounds: usize,
+    pub last_reviewed_at: Option<DateTime<Utc>>,
+}
+
+/// Work and designer usage of the period.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Usage {
+    /// Designer LLM calls, counted from the events they recorded.
+    pub llm_calls: usize,
+    /// Minutes of work tracked with timers or reported with reviews.
+    pub minutes_tracked: u64,
+}
+
+/// Progress of the next milestone that is not done yet.
+#[derive(Debug, Clone, Serialize)]
+pub struct MilestoneStatus {
+    pub milestone: String,
+    pub done: usize,
+    pub total: usize,
+    /// Effort points of the estimated features not yet reviewed.
+    pub remaining_points: u32,
+    pub remaining: Vec<String>,
+}
+
+/// Activity of a session between two points in time.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusReport {
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    /// Features approved in the period, in order of approval.
+    pub completed: Vec<String>,
+    /// The feature being implemented, if it has not been reviewed yet.
+    pub in_progress: Option<String>,
+    pub in_review: Vec<ReviewStatus>,
+    /// Features under review for [`BLOCKED_ROUNDS`] rounds or more.
+    pub blocked: Vec<ReviewStatus>,
+    pub usage: Usage,
+    pub upcoming_milestone: Option<MilestoneStatus>,
+}
+
+impl StatusReport {
+    /// Renders the report as Markdown.
+    pub fn render(&self, session_id: &str) -> String {
+        let mut text = format!(
+            "## Status of {}, {} to {}\n\n",
+            session_id,
+            self.since.format("%Y-%m-%d"),
+            self.until.format("%Y-%m-%d")
+        );
+
+        text.push_str(&format!("**Completed ({})**\n", self.completed.len()));
+        if self.completed.is_empty() {
+            text.push_str("- Nothing was approved.\n");
+        }
+        for feature in &self.completed {
+            text.push_str(&format!("- {}\n", feature));
+        }
+
+        if let Some(feature) = &self.in_progress {
+            text.push_str(&format!("\n**In progress**\n- {}\n", feature));
+        }
+
+        for (label, features) in [("In review", &self.in_review), ("Blocked", &self.blocked)] {
+            if features.is_empty() {
+                continue;
+            }
+
+            text.push_str(&format!("\n**{} ({})**\n", label, features.len()));
+            for status in features {
+                text.push_str(&format!(
+                    "- {}: {} review round(s) without approval",
+                    status.feature, status.rounds
+                ));
+                if let Some(reviewed_at) = status.last_reviewed_at {
+                    text.push_str(&format!(", last {}", reviewed_at.format("%Y-%m-%d")));
+                }
+                text.push('\n');
+            }
+        }
+
+        text.push_str(&format!(
+            "\n**Usage**\n- Designer LLM calls: {}\n- Work tracked: {}h {}m\n",
+            self.usage.llm_calls,
+            self.usage.minutes_tracked / 60,
+            self.usage.minutes_tracked % 60
+        ));
+
+        if let Some(milestone) = &self.upcoming_milestone {
+            text.push_str(&format!(
+                "\n**Upcoming milestone: {}**\n- {} of {} features done, {} effort points remaining\n",
+                milestone.milestone, milestone.done, milestone.total, milestone.remaining_points
+            ));
+            if !milestone.remaining.is_empty() {
+                text.push_str(&format!(
+                    "- Remaining: {}\n",
+                    milestone.remaining.join(", ")
+                ));
+            }
+        }
+
+        text.trim_end().to_string()
+    }
+}
+
+/// Whether the event was recorded by a change that called the designer LLM.
+/// Features that were imported or reused were not designed by it.
+fn called_llm(event: &SessionEvent) -> bool {
+    match event.kind {
+        EventKind::FeatureProposed => {
+            let flag = |name: &str| event.data.get(name).and_then(|v| v.as_bool());
+            !flag("imported").unwrap_or(false) && !flag("reused").unwrap_or(false)
+        }
+        EventKind::CandidatesProposed
+        | EventKind::ReviewSubmitted
+        | EventKind::ReviewReplied
+        | EventKind::DesignRevised
+        | EventKind::DesignEditRejected
+        | EventKind::QuestionAnswered
+        | EventKind::IdeasShortlisted
+        | EventKind::LevelBriefed
+        | EventKind::MonetizationReviewed
+        | EventKind::FeatureTestPlanned => true,
+        _ => false,
+    }
+}
+
+/// Builds the status report of `session` from its `events` between `since`
+/// and `until`.
+pub fn report(
+    session: &SessionState,
+    events: &[SessionEvent],
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> StatusReport {
+    let feature_of = |event: &SessionEvent| {
+        event
+            .data
+            .get("feature")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+
+    let mut completed: Vec<String> = Vec::new();
+    let mut usage = Usage::default();
+
+    for event in events
+        .iter()
+        .filter(|event| event.timestamp >= since && event.timestamp <= until)
+    {
+        if called_llm(event) {
+            usage.llm_calls += 1;
+        }
+
+        match event.kind {
+            EventKind::FeatureApproved => {
+                if let Some(feature) = feature_of(event)
+                    && !completed.contains(&feature)
+                {
+                    completed.push(feature);
+                }
+            }
+            EventKind::FeatureWorkStopped
+            | EventKind::ReviewSubmitted
+            | EventKind::ReviewReplied => {
+                usage.minutes_tracked += event
+                    .data
+                    .get("minutes")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0);
+            }
+            _ => {}
+        }
+    }
+
+    // Review rounds are counted over the whole log, as a feature can be
+    // stuck for longer than the period
+    let review_status = |name: &str| {
+        let mut status = ReviewStatus {
+            feature: name.to_string(),
+            rounds: 0,
+            last_reviewed_at: None,
+        };
+
+        for event in events
+            .iter()
+            .filter(|event| event.timestamp <= until)
+            .filter(|event| feature_of(event).as_deref() == Some(name))
+        {
+            match event.kind {
+                EventKind::FeatureApproved => status.rounds = 0,
+                EventKind::ReviewSubmitted | EventKind::ReviewReplied => {
+                    status.rounds += 1;
+                    status.last_reviewed_at = Some(event.timestamp);
+                }
+                _ => {}
+            }
+        }
+
+        status
+    };
+
+    let (blocked, in_review) = session
+        .planned_features
+        .iter()
+        .filter(|feature| feature.status == FeatureStatus::NeedsRework)
+        .map(|feature| review_status(&feature.name))
+        .partition::<Vec<_>, _>(|status| status.rounds >= BLOCKED_ROUNDS);
+
+    let in_progress = session
+        .next_feature_to_implement
+        .as_ref()
+        .filter(|name| {
+            session.planned_features.iter().any(|feature| {
+                &feature.name == *name
+                    && matches!(
+                        feature.status,
+                        FeatureStatus::Planned | FeatureStatus::InProgress
+                    )
+            })
+        })
+        .cloned();
+
+    let upcoming_milestone = scope::report(session, None)
+        .milestones
+        .into_iter()
+        .filter(|scope| scope.milestone != scope::UNASSIGNED_MILESTONE)
+        .find_map(|scope| {
+            let features = session
+                .planned_features
+                .iter()
+                .filter(|feature| feature.milestone.as_deref() == Some(scope.milestone.as_str()))
+                .collect::<Vec<_>>();
+            let remaining = features
+                .iter()
+                .filter(|feature| feature.status != FeatureStatus::Reviewed)
+                .map(|feature| feature.name.clone())
+                .collect::<Vec<_>>();
+            if remaining.is_empty() {
+                return None;
+            }
+
+            Some(MilestoneStatus {
+                milestone: scope.milestone,
+                done: features.len() - remaining.len(),
+                total: features.len(),
+                remaining_points: scope.remaining_points,
+                remaining,
+            })
+        });
+
+    StatusReport {
+        since,
+        until,
+        completed,
+        in_progress,
+        in_review,
+        blocked,
+        usage,
+        upcoming_milestone,
+    }
+}
@@ -0,0 +1,235 @@
+//! Storage of the files a session manager keeps: session states, event
+//! logs, undo snapshots, unfinished interviews, review attachments, search
+//! indexes, imported design documents, devlogs, asset lists,
+//! retrospectives, and the webhook configuration and undelivered webhooks.
+//!
+//! [`FsStorage`] keeps them in the data directory. [`MemoryStorage`] keeps
+//! them in memory, so that tests leave nothing behind and other backends can
+//! be swapped in. Files written where the client asks, such as design
+//! exports and test plans, always go to the file system.
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// File operations used by the session manager, modelled on `std::fs`.
+pub trait Storage: Send + Sync {
+    /// Returns whether a file or directory exists at `path`.
+    fn exists(&self, path: &Path) -> bool;
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String>;
+
+    /// Reads the file at `path` from byte `offset` to its end; empty if
+    /// `offset` is past the end.
+    fn read_from(&self, path: &Path, offset: u64) -> io::Result<String>;
+
+    /// Reads up to `len` bytes of the file at `path` from byte `offset`;
+    /// shorter at the end of the file.
+    fn read_range(&self, path: &Path, offset: u64, len: u64) -> io::Result<String>;
+
+    /// Returns the size of the file at `path` in bytes.
+    fn size(&self, path: &Path) -> io::Result<u64>;
+
+    /// Replaces the contents of the file at `path`, creating it if needed.
+    fn write(&self, path: &Path, contents: &str) -> io::Result<()> {
+        self.write_bytes(path, contents.as_bytes())
+    }
+
+    /// Replaces the contents of the file at `path` with raw bytes, creating
+    /// it if needed.
+    fn write_bytes(&self, path: &Path, contents: &[u8]) -> io::Result<()>;
+
+    /// Appends to the file at `path`, creating it if needed.
+    fn append(&self, path: &Path, contents: &str) -> io::Result<()>;
+
+    fn remove_file(&self, path: &Path) -> io::Result<()>;
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+
+    /// Returns the paths of the files directly inside `dir`.
+    fn list(&self, dir: &Path) -> io::Result<Vec<PathBuf>>;
+}
+
+/// Files on the local file system.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FsStorage;
+
+impl Storage for FsStorage {
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        fs::read_to_string(path)
+    }
+
+    fn read_from(&self, path: &Path, offset: u64) -> io::Result<String> {
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(offset))?;
+
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+        Ok(contents)
+    }
+
+    fn read_range(&self, path: &Path, offset: u64, len: u64) -> io::Result<String> {
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(offset))?;
+
+        let mut contents = String::new();
+        file.take(len).read_to_string(&mut contents)?;
+        Ok(contents)
+    }
+
+    fn size(&self, path: &Path) -> io::Result<u64> {
+        Ok(fs::metadata(path)?.len())
+    }
+
+    fn write_bytes(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        fs::write(path, contents)
+    }
+
+    fn append(&self, path: &Path, contents: &str) -> io::Result<()> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?
+            .write_all(contents.as_bytes())
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        fs::remove_file(path)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        fs::create_dir_all(path)
+    }
+
+    fn list(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_file() {
+                paths.push(path);
+            }
+        }
+
+        Ok(paths)
+    }
+}
+
+/// Files held in memory. Directories exist implicitly as the parents of
+/// the files.
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    files: Mutex<BTreeMap<PathBuf, Vec<u8>>>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{} does not exist", path.display()),
+    )
+}
+
+impl Storage for MemoryStorage {
+    fn exists(&self, path: &Path) -> bool {
+        self.files
+            .lock()
+            .unwrap()
+            .keys()
+            .any(|file| file.starts_with(path))
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        let contents = self
+            .files
+            .lock()
+            .unwrap()
+            .get(path)
+            .cloned()
+            .ok_or_else(|| not_found(path))?;
+
+        String::from_utf8(contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn read_from(&self, path: &Path, offset: u64) -> io::Result<String> {
+        let files = self.files.lock().unwrap();
+        let contents = files.get(path).ok_or_else(|| not_found(path))?;
+        let tail = contents.get(offset as usize..).unwrap_or_default();
+
+        String::from_utf8(tail.to_vec()).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn read_range(&self, path: &Path, offset: u64, len: u64) -> io::Result<String> {
+        let files = self.files.lock().unwrap();
+        let contents = files.get(path).ok_or_else(|| not_found(path))?;
+        let start = (offset as usize).min(contents.len());
+        let end = offset.saturating_add(len).min(contents.len() as u64) as usize;
+
+        String::from_utf8(contents[start..end].to_vec())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn size(&self, path: &Path) -> io::Result<u64> {
+        self.files
+            .lock()
+            .unwrap()
+            .get(path)
+            .map(|contents| contents.len() as u64)
+            .ok_or_else(|| not_found(path))
+    }
+
+    fn write_bytes(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        self.files
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf(), contents.to_vec());
+        Ok(())
+    }
+
+    fn append(&self, path: &Path, contents: &str) -> io::Result<()> {
+        self.files
+            .lock()
+            .unwrap()
+            .entry(path.to_path_buf())
+            .or_default()
+            .extend_from_slice(contents.as_bytes());
+        Ok(())
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        self.files
+            .lock()
+            .unwrap()
+            .remove(path)
+            .map(|_| ())
+            .ok_or_else(|| not_found(path))
+    }
+
+    fn create_dir_all(&self, _path: &Path) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn list(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        Ok(self
+            .files
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|file| file.parent() == Some(dir))
+            .cloned()
+            .collect())
+    }
+}
@@ -0,0 +1,120 @@
+//! Genre templates that seed a new session with design pillars, a starter
+//! feature backlog and genre-specific review criteria.
+//!
+//! Templates are JSON files in the crate's `templates/` directory, embedded
+//! at compile time.
+
+use crate::game_design::{
+    assets::AssetRequirement,
+    scope::Effort,
+    state::{DesignDecision, Feature, SessionState},
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Embedded template files by name.
+const TEMPLATES: &[(&str, &str)] = &[
+    (
+        "platformer",
+        include_str!("../../templates/platformer.json"),
+    ),
+    ("roguelike", include_str!("../../templates/roguelike.json")),
+    ("puzzle", include_str!("../../templates/puzzle.json")),
+    (
+        "visual-novel",
+        include_str!("../../templates/visual-novel.json"),
+    ),
+    (
+        "city-builder",
+        include_str!("../../templates/city-builder.json"),
+    ),
+];
+
+/// A starter feature of a genre template.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StarterFeature {
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub required_assets: Vec<AssetRequirement>,
+    #[serde(default)]
+    pub estimated_effort: Option<Effort>,
+}
+
+/// A genre template.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenreTemplate {
+    pub name: String,
+    pub title: String,
+    /// Design pillars, remembered as binding design decisions.
+    pub pillars: Vec<String>,
+    /// Features planned before the designer proposes its own.
+    pub starter_features: Vec<StarterFeature>,
+    /// Extra criteria the designer checks in every feature review.
+    pub review_criteria: Vec<String>,
+}
+
+/// Names of all embedded templates.
+pub fn names() -> Vec<&'static str> {
+    TEMPLATES.iter().map(|(name, _)| *name).collect()
+}
+
+/// Loads the named template, accepting `-` or `_` as separators.
+pub fn load(name: &str) -> Result<GenreTemplate> {
+    let name = name.trim().to_lowercase().replace(['_', ' '], "-");
+
+    let (_, json) = TEMPLATES
+        .iter()
+        .find(|(template_name, _)| *template_name == name)
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unknown template '{}'. Available templates: {}",
+                name,
+                names().join(", ")
+            )
+        })?;
+
+    Ok(serde_json::from_str(json)?)
+}
+
+impl GenreTemplate {
+    /// Renders the pillars for inclusion in the design document prompt.
+    pub fn pillars_section(&self) -> String {
+        let mut section = format!("Genre: {}\nDesign pillars:\n", self.title);
+        for pillar in &self.pillars {
+            section.push_str(&format!("- {}\n", pillar));
+        }
+        section
+    }
+
+    /// Seeds a freshly created session with the template's pillars, starter
+    /// backlog and review criteria. The first starter feature becomes the
+    /// next feature to implement.
+    pub fn apply(&self, session: &mut SessionState) {
+        session.template = Some(self.name.clone());
+
+        session
+            .design_memory
+            .extend(self.pillars.iter().map(|pillar| DesignDecision {
+                decision: pillar.clone(),
+                source_feature: None,
+            }));
+
+        session
+            .planned_features
+            .extend(self.starter_features.iter().map(|feature| Feature {
+                required_assets: feature.required_assets.clone(),
+                estimated_effort: feature.estimated_effort,
+                ..Feature::new(feature.name.clone(), feature.description.clone())
+            }));
+
+        if session.next_feature_to_implement.is_none() {
+            session.next_feature_to_implement = self
+                .starter_features
+                .first()
+                .map(|feature| feature.name.clone());
+        }
+
+        session.review_criteria = self.review_criteria.clone();
+    }
+}
@@ -0,0 +1,150 @@
+//! Test plans of accepted features: manual playtest steps and suggested
+//! automated test cases.
+//!
+//! A plan is written once a feature is reviewed, from its specification and
+//! the developer's implementation report, and kept with the feature.
+
+use crate::game_design::{
+    DesignerLlmClient,
+    designer_llm::{ChatMessage, extract_json},
+    memory, prompts,
+    state::{Feature, SessionState},
+};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A step of a manual playtest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaytestStep {
+    /// What the tester does.
+    pub action: String,
+    /// What the tester should observe.
+    pub expected: String,
+}
+
+/// A suggested automated test.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestCase {
+    pub name: String,
+    /// Level of the test, e.g. unit, integration or end-to-end.
+    pub kind: String,
+    /// What the test sets up and asserts.
+    pub description: String,
+}
+
+/// Test plan of a feature.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestPlan {
+    pub playtest_steps: Vec<PlaytestStep>,
+    pub automated_tests: Vec<TestCase>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TestPlan {
+    /// Renders the plan as Markdown checklists.
+    pub fn markdown(&self) -> String {
+        let mut markdown = String::new();
+
+        if !self.playtest_steps.is_empty() {
+            markdown.push_str("Manual playtest:\n\n");
+            for (number, step) in self.playtest_steps.iter().enumerate() {
+                markdown.push_str(&format!(
+                    "{}. [ ] {} Expected: {}\n",
+                    number + 1,
+                    step.action.trim(),
+                    step.expected.trim()
+                ));
+            }
+            markdown.push('\n');
+        }
+
+        if !self.automated_tests.is_empty() {
+            markdown.push_str("Automated tests:\n\n");
+            for test in &self.automated_tests {
+                markdown.push_str(&format!(
+                    "- [ ] `{}` ({}): {}\n",
+                    test.name,
+                    test.kind,
+                    test.description.trim()
+                ));
+            }
+        }
+
+        markdown.trim_end().to_string()
+    }
+}
+
+/// Asks the LLM for the test plan of `feature`, based on its specification
+/// and the developer's implementation report. The plan is dated `now`.
+pub async fn generate(
+    llm_client: &DesignerLlmClient,
+    session: &SessionState,
+    feature: &Feature,
+    now: DateTime<Utc>,
+) -> Result<TestPlan> {
+    let mut prompt = memory::design_context(session);
+
+    prompt.push_str(&format!(
+        "Feature:\nName: {}\n{}\n\n",
+        feature.name,
+        feature.specification()
+    ));
+
+    if let Some(report) = session.implemented_features_reports.get(&feature.name) {
+        prompt.push_str(&format!("Implementation report:\n{}\n\n", report.trim()));
+    }
+
+    if let Some(engine) = session.engine {
+        prompt.push_str(&format!(
+            "The game is built with {}; suggest automated tests in its testing tools.\n\n",
+            engine.description()
+        ));
+    }
+
+    prompt.push_str(
+        "Write a test plan for this accepted feature. List the steps of a manual playtest \
+         that exercises it, including edge cases and how it interacts with other features, \
+         each with the action of the tester and what they should observe. Then suggest \
+         automated test cases (unit, integration or end-to-end) that guard its behaviour \
+         against regressions. Format your response as a JSON object with a 'playtest_steps' \
+         array of objects with 'action' and 'expected' fields, and an 'automated_tests' array \
+         of objects with 'name', 'kind' and 'description' fields. Only return the JSON, \
+         nothing else.",
+    );
+
+    let messages = vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: session.system_prompt(prompts::TEST_PLAN),
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content: prompt,
+        },
+    ];
+
+    let response = llm_client.call_llm(messages).await?;
+
+    #[derive(Deserialize)]
+    struct Response {
+        #[serde(default)]
+        playtest_steps: Vec<PlaytestStep>,
+        #[serde(default)]
+        automated_tests: Vec<TestCase>,
+    }
+
+    let json = extract_json(&response)
+        .ok_or_else(|| anyhow::anyhow!("LLM response was not valid JSON: {}", response))?;
+    let response: Response = serde_json::from_str(json)?;
+
+    if response.playtest_steps.is_empty() && response.automated_tests.is_empty() {
+        return Err(anyhow::anyhow!("LLM response contained an empty test plan"));
+    }
+
+    Ok(TestPlan {
+        playtest_steps: response.playtest_steps,
+        automated_tests: response.automated_tests,
+        created_at: now,
+    })
+}
@@ -0,0 +1,140 @@
+//! Fetches open issues from external issue trackers (GitHub, Linear).
+
+use anyhow::Result;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::env;
+
+pub const USER_AGENT: &str = "gamedesignerd (https://github.com/hack3rmann/gamedesignerd-mcp)";
+
+/// An open issue pulled from an external tracker.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackerIssue {
+    /// Tracker-specific identifier, e.g. `#42` or `GAME-42`.
+    pub identifier: String,
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+    pub url: String,
+}
+
+/// Fetches up to `limit` open issues (excluding pull requests) of
+/// `owner/name` from GitHub. Uses `GITHUB_TOKEN` if it is set.
+pub async fn fetch_github_issues(repository: &str, limit: u32) -> Result<Vec<TrackerIssue>> {
+    if repository.split('/').count() != 2 {
+        return Err(anyhow::anyhow!(
+            "Invalid GitHub repository '{}'. Expected format: owner/name",
+            repository
+        ));
+    }
+
+    let url = format!(
+        "https://api.github.com/repos/{}/issues?state=open&per_page={}",
+        repository,
+        limit.min(100)
+    );
+
+    let mut request = Client::new()
+        .get(&url)
+        .header("User-Agent", USER_AGENT)
+        .header("Accept", "application/vnd.github+json");
+
+    if let Ok(token) = env::var("GITHUB_TOKEN") {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+
+    let response = request.send().await?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().await?;
+
+        return Err(anyhow::anyhow!(
+            "GitHub API request failed with status {}: {}",
+            status,
+            error_text
+        ));
+    }
+
+    let issues: Vec<Value> = response.json().await?;
+
+    Ok(issues
+        .into_iter()
+        // The issues endpoint also lists pull requests
+        .filter(|issue| issue.get("pull_request").is_none())
+        .map(|issue| TrackerIssue {
+            identifier: format!("#{}", issue["number"]),
+            title: issue["title"].as_str().unwrap_or_default().to_string(),
+            body: issue["body"].as_str().unwrap_or_default().to_string(),
+            url: issue["html_url"].as_str().unwrap_or_default().to_string(),
+        })
+        .collect())
+}
+
+/// Fetches up to `limit` issues of the Linear team with key `team` that are
+/// neither completed nor canceled. Requires `LINEAR_API_KEY`.
+pub async fn fetch_linear_issues(team: &str, limit: u32) -> Result<Vec<TrackerIssue>> {
+    let api_key = env::var("LINEAR_API_KEY")
+        .map_err(|_| anyhow::anyhow!("LINEAR_API_KEY environment variable not set"))?;
+
+    let query = r#"
+        query OpenIssues($team: String!, $first: Int!) {
+            issues(
+                first: $first,
+                filter: {
+                    team: { key: { eq: $team } },
+                    state: { type: { nin: ["completed", "canceled"] } }
+                }
+            ) {
+                nodes { identifier title description url }
+            }
+        }
+    "#;
+
+    let response = Client::new()
+        .post("https://api.linear.app/graphql")
+        .header("User-Agent", USER_AGENT)
+        .header("Authorization", api_key)
+        .json(&json!({
+            "query": query,
+            "variables": { "team": team, "first": limit.min(250) },
+        }))
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().await?;
+
+        return Err(anyhow::anyhow!(
+            "Linear API request failed with status {}: {}",
+            status,
+            error_text
+        ));
+    }
+
+    let body: Value = response.json().await?;
+
+    if let Some(errors) = body.get("errors") {
+        return Err(anyhow::anyhow!("Linear API returned errors: {}", errors));
+    }
+
+    let nodes = body["data"]["issues"]["nodes"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+
+    Ok(nodes
+        .into_iter()
+        .map(|issue| TrackerIssue {
+            identifier: issue["identifier"].as_str().unwrap_or_default().to_string(),
+            title: issue["title"].as_str().unwrap_or_default().to_string(),
+            body: issue["description"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            url: issue["url"].as_str().unwrap_or_default().to_string(),
+        })
+        .collect())
+}
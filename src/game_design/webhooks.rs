@@ -0,0 +1,235 @@
+//! Delivers session events to configured outbound webhooks.
+//!
+//! Endpoints are read from `webhooks.json` in the persistence directory:
+//!
+//! ```json
+//! {
+//!   "endpoints": [
+//!     { "url": "https://example.com/hook", "events": ["feature.*"], "secret": "s3cr3t" }
+//!   ],
+//!   "max_retries": 3
+//! }
+//! ```
+//!
+//! Each event is POSTed as JSON. When a secret is configured the body is signed
+//! with HMAC-SHA256 and the hex digest is sent in `X-Gamedesignerd-Signature`.
+//! Deliveries that still fail after all retries are appended to
+//! `webhooks-failed.jsonl` so they can be inspected or replayed.
+
+use crate::game_design::{events::SessionEvent, signature, storage::Storage};
+use anyhow::Result;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+/// A single webhook receiver.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEndpoint {
+    pub url: String,
+    /// Event filters such as `feature.approved` or `feature.*`.
+    /// An empty list subscribes to every event.
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// Shared secret used to sign the request body.
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+impl WebhookEndpoint {
+    /// Checks whether this endpoint is subscribed to the event.
+    pub fn accepts(&self, event: &SessionEvent) -> bool {
+        self.events.is_empty() || self.events.iter().any(|f| event.kind.matches(f))
+    }
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+/// Webhook configuration loaded from `webhooks.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    #[serde(default)]
+    pub endpoints: Vec<WebhookEndpoint>,
+    /// Number of retries after the first failed delivery attempt.
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            endpoints: Vec::new(),
+            max_retries: default_max_retries(),
+        }
+    }
+}
+
+impl WebhookConfig {
+    /// Loads the configuration from `<dir>/webhooks.json` of `storage`.
+    /// A missing file yields an empty configuration.
+    pub fn load(storage: &dyn Storage, dir: &Path) -> Result<Self> {
+        let path = dir.join("webhooks.json");
+
+        if !storage.exists(&path) {
+            return Ok(Self::default());
+        }
+
+        let json = storage.read_to_string(&path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+}
+
+/// Asynchronous outbox that fans events out to the configured endpoints.
+#[derive(Clone)]
+pub struct WebhookOutbox {
+    client: Client,
+    config: Arc<RwLock<Arc<WebhookConfig>>>,
+    storage: Arc<dyn Storage>,
+    dir: PathBuf,
+    failed_log_path: PathBuf,
+}
+
+impl WebhookOutbox {
+    /// Creates an outbox with the configuration found in `dir` of `storage`,
+    /// which also keeps the undelivered events.
+    /// An invalid configuration is logged and treated as empty.
+    pub fn new(dir: &Path, storage: Arc<dyn Storage>) -> Self {
+        let config = WebhookConfig::load(storage.as_ref(), dir).unwrap_or_else(|e| {
+            tracing::warn!("Failed to load webhook configuration: {}", e);
+            WebhookConfig::default()
+        });
+
+        Self {
+            client: Client::new(),
+            config: Arc::new(RwLock::new(Arc::new(config))),
+            storage,
+            dir: dir.to_path_buf(),
+            failed_log_path: dir.join("webhooks-failed.jsonl"),
+        }
+    }
+
+    fn config(&self) -> Arc<WebhookConfig> {
+        self.config.read().unwrap().clone()
+    }
+
+    /// Re-reads `webhooks.json`. Deliveries already in flight finish with
+    /// the old configuration. On error the old configuration is kept.
+    /// Returns the number of configured endpoints.
+    pub fn reload(&self) -> Result<usize> {
+        let config = WebhookConfig::load(self.storage.as_ref(), &self.dir)?;
+        let endpoints = config.endpoints.len();
+
+        *self.config.write().unwrap() = Arc::new(config);
+        Ok(endpoints)
+    }
+
+    /// Queues the event for delivery to every subscribed endpoint.
+    /// Delivery happens in the background and never blocks the caller.
+    pub fn dispatch(&self, event: SessionEvent) {
+        let config = self.config();
+
+        for endpoint in config.endpoints.iter().filter(|e| e.accepts(&event)) {
+            let outbox = self.clone();
+            let endpoint = endpoint.clone();
+            let event = event.clone();
+
+            tokio::spawn(async move {
+                outbox.deliver(&endpoint, &event).await;
+            });
+        }
+    }
+
+    /// Delivers a single event with exponential backoff.
+    async fn deliver(&self, endpoint: &WebhookEndpoint, event: &SessionEvent) {
+        let body = match serde_json::to_vec(event) {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::error!("Failed to serialize webhook event: {}", e);
+                return;
+            }
+        };
+
+        let mut last_error = String::new();
+        let max_retries = self.config().max_retries;
+
+        for attempt in 0..=max_retries {
+            if attempt > 0 {
+                tokio::time::sleep(Duration::from_secs(1 << (attempt - 1).min(6))).await;
+            }
+
+            match self.post(endpoint, event, &body).await {
+                Ok(()) => return,
+                Err(e) => {
+                    tracing::warn!(
+                        "Webhook delivery of {} to {} failed (attempt {}): {}",
+                        event.kind.as_str(),
+                        endpoint.url,
+                        attempt + 1,
+                        e
+                    );
+                    last_error = e.to_string();
+                }
+            }
+        }
+
+        if let Err(e) = self.record_failure(endpoint, event, &last_error) {
+            tracing::error!("Failed to record undelivered webhook: {}", e);
+        }
+    }
+
+    async fn post(
+        &self,
+        endpoint: &WebhookEndpoint,
+        event: &SessionEvent,
+        body: &[u8],
+    ) -> Result<()> {
+        let mut request = self
+            .client
+            .post(&endpoint.url)
+            .header("Content-Type", "application/json")
+            .header("X-Gamedesignerd-Event", event.kind.as_str())
+            .header("X-Gamedesignerd-Delivery", event.id.as_str())
+            .timeout(Duration::from_secs(10));
+
+        if let Some(secret) = &endpoint.secret {
+            request = request.header(
+                "X-Gamedesignerd-Signature",
+                format!("sha256={}", signature::sign(secret, body)),
+            );
+        }
+
+        let response = request.body(body.to_vec()).send().await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "endpoint responded with status {}",
+                response.status()
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn record_failure(
+        &self,
+        endpoint: &WebhookEndpoint,
+        event: &SessionEvent,
+        error: &str,
+    ) -> Result<()> {
+        let line = serde_json::json!({
+            "url": endpoint.url,
+            "error": error,
+            "event": event,
+        });
+
+        self.storage
+            .append(&self.failed_log_path, &format!("{}\n", line))?;
+
+        Ok(())
+    }
+}
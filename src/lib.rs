@@ -0,0 +1,11 @@
+//! Game Design MCP server: design sessions driven by a designer LLM and the
+//! MCP tools, resources and transports that expose them.
+//!
+//! The `game-designer-mcp` binary is a thin command-line front end over this
+//! library; the end-to-end tests in `tests/` drive the same router in process.
+
+pub mod error;
+pub mod game_design;
+pub mod tools;
+pub mod transport;
+pub mod update;
@@ -1,40 +1,137 @@
-pub mod game_design;
-pub mod tools;
-pub mod transport;
+use game_designer_mcp::{game_design, tools, transport, update};
 
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
+use game_design::config::{self, LogFormat};
 use mcp_core::Content;
 use mcp_server::{ByteTransport, Router, Server, router::RouterService};
 use serde_json::json;
-use std::net::SocketAddr;
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
 use tokio::io::{stdin, stdout};
-use tools::GameToolsRouter;
+use tools::{GameDesignerService, GameToolsRouter};
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
-use tracing_subscriber::{self, EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{
+    self, EnvFilter, filter::Directive, layer::SubscriberExt, util::SubscriberInitExt,
+};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 #[command(propagate_version = true)]
 #[command(disable_version_flag = true)]
 struct Cli {
+    /// Directory for sessions, caches, recordings and exports [default:
+    /// ./.gamedesignerd if it exists, else the user data directory]
+    #[arg(long, global = true, env = "GAMEDESIGNERD_DATA_DIR")]
+    data_dir: Option<PathBuf>,
+
+    /// Project namespace of tool calls that don't name one; empty for the
+    /// sessions without a project [default: derived from the working
+    /// directory when the data directory is shared]
+    #[arg(long, global = true, env = "GAMEDESIGNERD_PROJECT")]
+    project: Option<String>,
+
+    /// Make LLM calls reproducible: pins temperature to 0, forwards the seed to
+    /// the provider and records provider fingerprints
+    #[arg(long, global = true, env = "GAMEDESIGNER_SEED")]
+    seed: Option<u64>,
+
+    /// LLM provider answering the designer's calls; `mock` returns canned
+    /// responses and needs no API key [default: openrouter]
+    #[arg(long, global = true, env = "GAMEDESIGNER_LLM_PROVIDER", value_enum)]
+    llm_provider: Option<game_design::designer_llm::LlmProvider>,
+
+    /// Model of the designer's LLM calls (overrides GAMEDESIGNER_MODEL)
+    #[arg(long, global = true)]
+    model: Option<String>,
+
+    /// Sampling temperature of the designer's LLM calls [default: 0.7]
+    #[arg(long, global = true, env = "GAMEDESIGNER_TEMPERATURE")]
+    temperature: Option<f32>,
+
+    /// Recording served by `--llm-provider replay` (defaults to the most
+    /// recent one in the data directory's recordings)
+    #[arg(long, global = true, env = "GAMEDESIGNER_REPLAY")]
+    replay: Option<PathBuf>,
+
+    /// Record every LLM request/response pair to the data directory's recordings
+    #[arg(long, global = true)]
+    record: bool,
+
+    /// Always call the LLM provider instead of reusing cached responses
+    #[arg(long, global = true)]
+    no_cache: bool,
+
+    /// Print the MCP protocol versions the server speaks, newest first, and
+    /// exit
+    #[arg(long, exclusive = true)]
+    print_protocol_version: bool,
+
     #[command(subcommand)]
-    command: Commands,
+    command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Run the server in stdin/stdout mode
     Stdio {
+        /// Serve the byte transport over a Unix domain socket (or a Windows
+        /// named pipe) at this path instead of stdin/stdout
+        #[arg(long)]
+        socket: Option<PathBuf>,
+
         /// Enable debug logging
         #[arg(short, long)]
         debug: bool,
+
+        /// Log line format [default: text]
+        #[arg(long, value_enum)]
+        log_format: Option<LogFormat>,
+
+        /// Serve Prometheus metrics at http://<ADDRESS>/metrics
+        #[arg(long, value_name = "ADDRESS")]
+        metrics_address: Option<SocketAddr>,
     },
     /// Run the server with HTTP/SSE interface
     Http {
-        /// Address to bind the HTTP server to
-        #[arg(short, long, default_value = "127.0.0.1:8080")]
-        address: String,
+        /// Address to bind the HTTP server to [default: 127.0.0.1:8080]
+        #[arg(short, long)]
+        address: Option<String>,
+
+        /// Enable debug logging
+        #[arg(short, long)]
+        debug: bool,
+
+        /// Log line format [default: text]
+        #[arg(long, value_enum)]
+        log_format: Option<LogFormat>,
+    },
+    /// Serve a read-only web dashboard of the sessions
+    Dashboard {
+        /// Address to bind the dashboard to
+        #[arg(short, long, default_value = DEFAULT_DASHBOARD_ADDRESS)]
+        address: SocketAddr,
+
+        /// Enable debug logging
+        #[arg(short, long)]
+        debug: bool,
+    },
+    /// Run a Discord bot exposing /nextfeature, /review, /reply and /ask
+    Discord {
+        /// Bot token from the Discord developer portal
+        #[arg(long, env = "DISCORD_TOKEN", hide_env_values = true)]
+        token: String,
+
+        /// Session the commands work on
+        #[arg(long)]
+        session: String,
+
+        /// Register the commands in this guild only, where they are
+        /// available right away (global commands can take an hour)
+        #[arg(long, value_name = "GUILD_ID")]
+        guild: Option<u64>,
 
         /// Enable debug logging
         #[arg(short, long)]
@@ -54,6 +151,35 @@ enum Commands {
         #[arg(long)]
         game_description: Option<String>,
 
+        /// Existing Markdown design document to import with designNew
+        #[arg(long)]
+        document_path: Option<PathBuf>,
+
+        /// Designer persona for designNew
+        #[arg(long)]
+        persona: Option<String>,
+
+        /// Genre template for designNew
+        #[arg(long)]
+        template: Option<String>,
+
+        /// Path inside the game's git repository for designNew, or the
+        /// GitHub repository as owner/name for designImport
+        #[arg(long)]
+        repo: Option<PathBuf>,
+
+        /// Issue tracker for designImport: github or linear
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Linear team key for designImport --from linear
+        #[arg(long)]
+        team: Option<String>,
+
+        /// Attach the repository diff to featureReview
+        #[arg(long)]
+        include_diff: bool,
+
         /// Changes made report for featureReview
         #[arg(long)]
         changes_made: Option<String>,
@@ -66,87 +192,1014 @@ enum Commands {
         #[arg(long)]
         question: Option<String>,
 
+        /// Event cursor for sessionEvents
+        #[arg(long)]
+        cursor: Option<u64>,
+
         /// Enable debug logging
         #[arg(short, long)]
         debug: bool,
     },
+    /// Print the audit trail of a session's state changes
+    History {
+        /// Session to show
+        session_name: String,
+
+        /// Only events of this type, e.g. review.submitted or feature.*
+        /// (repeatable)
+        #[arg(long = "type", value_name = "TYPE")]
+        types: Vec<String>,
+
+        /// Earliest event time (YYYY-MM-DD or RFC 3339)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Latest event time (YYYY-MM-DD, inclusive, or RFC 3339)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Show at most this many of the most recent events
+        #[arg(long, default_value_t = 100)]
+        limit: usize,
+
+        /// Print the full events as JSON lines
+        #[arg(long)]
+        json: bool,
+    },
+    /// Revert the most recent change of a session
+    Undo {
+        /// Session to revert
+        session_name: String,
+
+        /// Refuse unless the change starts at this event sequence number
+        #[arg(long, value_name = "SEQ")]
+        expect: Option<u64>,
+    },
+    /// Sign a decision on a feature awaiting human approval and print the
+    /// signature to pass to humanApprove
+    SignApproval {
+        /// Session of the feature
+        session_name: String,
+
+        /// Feature awaiting approval; defaults to the feature being
+        /// implemented
+        #[arg(long)]
+        feature: Option<String>,
+
+        /// Send the feature back for rework instead of approving it
+        #[arg(long)]
+        reject: bool,
+
+        /// Person signing the decision
+        #[arg(long)]
+        author: String,
+
+        /// Reason of the decision; required with --reject
+        #[arg(long)]
+        comment: Option<String>,
+    },
+    /// Check the environment and the stored sessions for problems, repairing
+    /// what can be repaired
+    Doctor {
+        /// Only report problems, do not modify or quarantine any files
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Do not ping the LLM provider
+        #[arg(long)]
+        offline: bool,
+    },
+    /// Manage the designer's system prompt templates
+    Prompts {
+        #[command(subcommand)]
+        command: PromptsCommand,
+    },
+    /// Inspect the layered configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Inspect or empty the LLM response and documentation caches
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+    /// Inspect the sessions a running HTTP server keeps in memory
+    Sessions {
+        #[command(subcommand)]
+        command: SessionsCommand,
+    },
+    /// Print the completion script of a shell, e.g. `gamedesignerd
+    /// completions bash > /etc/bash_completion.d/gamedesignerd`
+    Completions {
+        /// Shell to complete in
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+    /// Print the man page, or write the pages of every subcommand into a
+    /// directory
+    Man {
+        /// Write gamedesignerd.1 and gamedesignerd-<subcommand>.1 into this
+        /// directory instead
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+    /// Replace this binary with the one of the latest GitHub release
+    SelfUpdate {
+        /// Only report whether a newer release exists
+        #[arg(long)]
+        check: bool,
+    },
+    /// Load-test the HTTP transport with concurrent MCP clients and report
+    /// call latencies and session lock contention
+    Bench {
+        /// MCP clients calling tools at the same time
+        #[arg(long, default_value_t = 8)]
+        clients: usize,
+
+        /// Tool calls each client makes
+        #[arg(long, default_value_t = 20)]
+        calls: usize,
+
+        /// Design sessions the clients are spread over [default: one per
+        /// client]
+        #[arg(long)]
+        sessions: Option<usize>,
+
+        /// Benchmark the server running at this URL, e.g.
+        /// http://127.0.0.1:8080, instead of one started in process with the
+        /// mock LLM provider
+        #[arg(long)]
+        url: Option<String>,
+
+        /// Time each response of the in-process mock LLM provider takes
+        #[arg(long, default_value_t = 200)]
+        llm_latency_ms: u64,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheCommand {
+    /// Print the entries and size of each cache
+    Stats,
+    /// Remove every cached entry
+    Clear {
+        /// Clear only this cache [default: both]
+        #[arg(long, value_enum)]
+        only: Option<CacheKind>,
+    },
+}
+
+/// A cache kept in the data directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum CacheKind {
+    /// Responses of the designer's LLM calls
+    Llm,
+    /// Documentation looked up on docs.rs
+    Docs,
+}
+
+#[derive(Subcommand)]
+enum SessionsCommand {
+    /// Print the sessions each project keeps in memory, their size and how
+    /// often sessions were loaded, reused and evicted
+    Stats {
+        /// URL of the server [default: the configured HTTP address]
+        #[arg(long)]
+        url: Option<String>,
+    },
 }
 
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Print the effective configuration and the files it was read from
+    Show,
+}
+
+#[derive(Subcommand)]
+enum PromptsCommand {
+    /// Write the built-in prompts to the override directory for editing
+    Dump {
+        /// Directory to write to (defaults to prompts in the data directory)
+        #[arg(long)]
+        dir: Option<PathBuf>,
+
+        /// Overwrite prompt files that already exist
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// Name of the installed binary in completions and man pages.
+const BIN_NAME: &str = "gamedesignerd";
+
+/// Address of the HTTP server when neither the command line nor the
+/// configuration sets one.
+const DEFAULT_HTTP_ADDRESS: &str = "127.0.0.1:8080";
+
+/// Address of the dashboard unless `--address` is given.
+const DEFAULT_DASHBOARD_ADDRESS: &str = "127.0.0.1:8081";
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    match cli.command {
-        Commands::Stdio { debug } => run_stdio_server(debug).await,
-        Commands::Http { address, debug } => run_http_server(address, debug).await,
+    if cli.print_protocol_version {
+        for version in transport::MCP_PROTOCOL_VERSIONS {
+            println!("{}", version);
+        }
+        return Ok(());
+    }
+    let Some(command) = cli.command else {
+        Cli::command()
+            .error(
+                clap::error::ErrorKind::MissingSubcommand,
+                "a subcommand is required",
+            )
+            .exit();
+    };
+    let custom_data_dir = cli.data_dir.is_some();
+
+    if let Some(data_dir) = cli.data_dir {
+        game_design::session::set_persistence_dir(data_dir);
+    }
+
+    if let Some(project) = cli.project {
+        game_design::project::set_default(&project)?;
+    }
+
+    if let Some(seed) = cli.seed {
+        game_design::designer_llm::set_seed(seed);
+    }
+
+    if let Some(provider) = cli.llm_provider {
+        game_design::designer_llm::set_provider(provider);
+    }
+
+    if let Some(model) = cli.model {
+        game_design::designer_llm::set_model(model);
+    }
+
+    if let Some(temperature) = cli.temperature {
+        game_design::designer_llm::set_temperature(temperature);
+    }
+
+    if let Some(replay) = cli.replay {
+        game_design::recording::set_replay_source(replay);
+    }
+
+    if cli.record {
+        let path = game_design::recording::start()?;
+        eprintln!("Recording LLM interactions to {}", path.display());
+    }
+
+    if cli.no_cache {
+        game_design::cache::disable();
+    }
+
+    match command {
+        Commands::Stdio {
+            socket,
+            debug,
+            log_format,
+            metrics_address,
+        } => {
+            let settings = config::current();
+            run_stdio_server(
+                socket.or_else(|| settings.transport.socket.clone()),
+                debug,
+                log_format
+                    .or(settings.log.format)
+                    .unwrap_or(LogFormat::Text),
+                metrics_address.or(settings.transport.metrics_address),
+            )
+            .await
+        }
+        Commands::Http {
+            address,
+            debug,
+            log_format,
+        } => {
+            let settings = config::current();
+            let address = address
+                .or_else(|| settings.transport.address.clone())
+                .unwrap_or_else(|| DEFAULT_HTTP_ADDRESS.to_string());
+            run_http_server(
+                address,
+                debug,
+                log_format
+                    .or(settings.log.format)
+                    .unwrap_or(LogFormat::Text),
+            )
+            .await
+        }
+        Commands::Dashboard { address, debug } => run_dashboard(address, debug).await,
+        Commands::Discord {
+            token,
+            session,
+            guild,
+            debug,
+        } => run_discord_bot(&token, session, guild, debug).await,
         Commands::Test {
             tool,
             session_name,
             game_description,
+            document_path,
+            persona,
+            template,
+            repo,
+            from,
+            team,
+            include_diff,
             changes_made,
             content,
             question,
+            cursor,
             debug,
         } => {
             run_test_tool(TestToolConfig {
                 tool,
                 session_name,
                 game_description,
+                document_path,
+                persona,
+                template,
+                repo,
+                from,
+                team,
+                include_diff,
                 changes_made,
                 content,
                 question,
+                cursor,
                 debug,
             })
             .await
         }
+        Commands::History {
+            session_name,
+            types,
+            since,
+            until,
+            limit,
+            json,
+        } => history(&session_name, types, since, until, limit, json).await,
+        Commands::Undo {
+            session_name,
+            expect,
+        } => undo(&session_name, expect).await,
+        Commands::SignApproval {
+            session_name,
+            feature,
+            reject,
+            author,
+            comment,
+        } => sign_approval(&session_name, feature, reject, &author, comment.as_deref()),
+        Commands::Doctor { dry_run, offline } => doctor(dry_run, offline).await,
+        Commands::Prompts { command } => match command {
+            PromptsCommand::Dump { dir, force } => dump_prompts(dir, force),
+        },
+        Commands::Config { command } => match command {
+            ConfigCommand::Show => show_config(),
+        },
+        Commands::Cache { command } => match command {
+            CacheCommand::Stats => cache_stats(),
+            CacheCommand::Clear { only } => clear_cache(only),
+        },
+        Commands::Completions { shell } => {
+            clap_complete::generate(shell, &mut Cli::command(), BIN_NAME, &mut std::io::stdout());
+            Ok(())
+        }
+        Commands::Man { dir } => man_pages(dir),
+        Commands::SelfUpdate { check } => self_update(check).await,
+        Commands::Sessions { command } => match command {
+            SessionsCommand::Stats { url } => session_stats(url).await,
+        },
+        Commands::Bench {
+            clients,
+            calls,
+            sessions,
+            url,
+            llm_latency_ms,
+        } => {
+            let config = transport::bench::BenchConfig {
+                clients,
+                calls,
+                sessions: sessions.unwrap_or(clients),
+            };
+            bench(config, url, llm_latency_ms, custom_data_dir).await
+        }
     }
 }
 
-async fn run_stdio_server(debug: bool) -> Result<()> {
-    // Set up file appender for logging
-    let file_appender = RollingFileAppender::new(Rotation::DAILY, "logs", "stdio-server.log");
+/// Runs the load test against `url`, or against a server started in
+/// process in a temporary directory, which also becomes the data directory
+/// unless one was chosen.
+async fn bench(
+    config: transport::bench::BenchConfig,
+    url: Option<String>,
+    llm_latency_ms: u64,
+    custom_data_dir: bool,
+) -> Result<()> {
+    let report = match url {
+        Some(url) => transport::bench::run(&url, config).await?,
+        None => {
+            let dir =
+                std::env::temp_dir().join(format!("gamedesignerd-bench-{}", std::process::id()));
+            if !custom_data_dir {
+                game_design::session::set_persistence_dir(dir.clone());
+            }
+
+            let report = transport::bench::run_in_process(
+                &dir,
+                config,
+                std::time::Duration::from_millis(llm_latency_ms),
+            )
+            .await;
+            std::fs::remove_dir_all(&dir).ok();
 
-    // Initialize the tracing subscriber with file logging
-    let level = if debug {
-        tracing::Level::DEBUG
+            report?
+        }
+    };
+
+    print!("{}", report);
+    Ok(())
+}
+
+fn cache_stats() -> Result<()> {
+    let docs = tools::DocCache::new();
+
+    println!(
+        "LLM responses ({}): {}",
+        game_design::cache::dir().display(),
+        game_design::cache::stats()?
+    );
+    println!(
+        "Documentation ({}): {}",
+        docs.dir().display(),
+        docs.stats()?
+    );
+
+    Ok(())
+}
+
+/// Prints the man page of the command, or writes the pages of the command
+/// and of every subcommand into `dir`.
+fn man_pages(dir: Option<PathBuf>) -> Result<()> {
+    let mut command = Cli::command().name(BIN_NAME);
+
+    let Some(dir) = dir else {
+        clap_mangen::Man::new(command).render(&mut std::io::stdout())?;
+        return Ok(());
+    };
+
+    command.build();
+    std::fs::create_dir_all(&dir)?;
+    let written = write_man_pages(&command, BIN_NAME.to_string(), &dir)?;
+    println!("Wrote {} man page(s) to {}", written, dir.display());
+
+    Ok(())
+}
+
+/// Writes the page of `command` as `<name>.1` and those of its subcommands
+/// as `<name>-<subcommand>.1`. Returns the number of pages written.
+fn write_man_pages(command: &clap::Command, name: String, dir: &Path) -> Result<usize> {
+    let mut page = Vec::new();
+    clap_mangen::Man::new(command.clone().name(name.clone())).render(&mut page)?;
+    std::fs::write(dir.join(format!("{}.1", name)), page)?;
+
+    let mut written = 1;
+    for subcommand in command
+        .get_subcommands()
+        .filter(|subcommand| subcommand.get_name() != "help" && !subcommand.is_hide_set())
+    {
+        let name = format!("{}-{}", name, subcommand.get_name());
+        written += write_man_pages(subcommand, name, dir)?;
+    }
+
+    Ok(written)
+}
+
+/// Prints the memory residency reported by the HTTP server at `url`.
+async fn session_stats(url: Option<String>) -> Result<()> {
+    let url = url.unwrap_or_else(|| {
+        let address = config::current()
+            .transport
+            .address
+            .clone()
+            .unwrap_or_else(|| DEFAULT_HTTP_ADDRESS.to_string());
+        format!("http://{}", address)
+    });
+
+    let response = reqwest::get(format!("{}/sessions/stats", url.trim_end_matches('/')))
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to reach the server at {}: {}", url, e))?
+        .error_for_status()?;
+    let projects: Vec<game_design::residency::ProjectResidency> = response.json().await?;
+
+    for project in projects {
+        print!("{}", project);
+    }
+
+    Ok(())
+}
+
+fn clear_cache(only: Option<CacheKind>) -> Result<()> {
+    if only.is_none_or(|kind| kind == CacheKind::Llm) {
+        let removed = game_design::cache::clear()?;
+        println!("Removed {} cached LLM response(s)", removed);
+    }
+    if only.is_none_or(|kind| kind == CacheKind::Docs) {
+        let removed = tools::DocCache::new().clear()?;
+        println!("Removed {} cached documentation page(s)", removed);
+    }
+
+    Ok(())
+}
+
+/// Prints the configuration the server would run with, every layer applied.
+fn show_config() -> Result<()> {
+    use game_design::config::{
+        Config, DocsConfig, EmbeddingsConfig, FeaturesConfig, GuardLimits, LlmConfig, LogConfig,
+        MonetizationConfig, ReviewConfig, ScopeConfig, StorageConfig, ToolsConfig, TransportConfig,
+    };
+
+    let file = config::current();
+    let queue = tools::queue::QueueConfig::from_env();
+    let guard = tools::guard::GuardConfig::from_env();
+    let timeouts = tools::cancellation::TimeoutConfig::from_env();
+    let secs = |timeout: Option<std::time::Duration>| timeout.map_or(0, |t| t.as_secs());
+
+    let effective = Config {
+        default_persona: Some(file.default_persona.unwrap_or_default()),
+        storage: StorageConfig {
+            path: Some(game_design::session::persistence_dir().to_path_buf()),
+            max_resident_sessions: Some(game_design::residency::capacity()),
+        },
+        llm: LlmConfig {
+            provider: Some(game_design::designer_llm::provider()),
+            api_key_file: file.llm.api_key_file.clone(),
+            model: Some(game_design::DesignerLlmClient::model()),
+            temperature: Some(game_design::designer_llm::temperature()),
+            top_p: file.llm.top_p,
+            max_tokens: Some(
+                file.llm
+                    .max_tokens
+                    .unwrap_or(game_design::designer_llm::DEFAULT_MAX_TOKENS),
+            ),
+            prompt_budget: Some(game_design::context::prompt_budget()),
+            concurrency: Some(queue.concurrency),
+            queue_capacity: Some(queue.capacity),
+            cache_ttl_secs: Some(game_design::cache::ttl().as_secs()),
+            prompt_caching: Some(game_design::designer_llm::prompt_caching()),
+            vision: Some(game_design::designer_llm::vision()),
+            vision_model: game_design::designer_llm::vision_model(),
+            tools: file.llm.tools.clone(),
+        },
+        embeddings: EmbeddingsConfig {
+            provider: Some(game_design::embeddings::provider()),
+            ..file.embeddings.clone()
+        },
+        scope: ScopeConfig {
+            effort_budget: game_design::scope::default_budget(),
+        },
+        features: FeaturesConfig {
+            spec_check: Some(game_design::spec_check::enabled()),
+            duplicate_threshold: Some(game_design::graveyard::duplicate_threshold()),
+            similar_threshold: Some(game_design::graveyard::similar_threshold()),
+        },
+        review: ReviewConfig {
+            max_follow_ups: Some(game_design::followups::max_follow_ups()),
+            require_human_approval: Some(game_design::approval::required()),
+            approver_secret_file: file.review.approver_secret_file.clone(),
+        },
+        monetization: MonetizationConfig {
+            audience_includes_minors: Some(
+                file.monetization.audience_includes_minors.unwrap_or(true),
+            ),
+            default_rules: Some(file.monetization.default_rules.unwrap_or(true)),
+            ..file.monetization.clone()
+        },
+        tools: ToolsConfig {
+            timeout_secs: Some(secs(timeouts.default)),
+            timeouts: timeouts
+                .tools
+                .into_iter()
+                .map(|(tool, timeout)| (tool, secs(timeout)))
+                .collect(),
+        },
+        docs: DocsConfig {
+            cache_ttl_secs: Some(tools::docs::cache::ttl().as_secs()),
+            cache_max_mb: Some(tools::docs::cache::max_bytes() / (1024 * 1024)),
+        },
+        guard: GuardLimits {
+            max_calls_per_minute: Some(guard.max_calls_per_minute),
+            max_consecutive_next_feature: Some(guard.max_consecutive_next_feature),
+            cooldown_secs: Some(guard.cooldown.as_secs()),
+            daily_token_budget: Some(guard.daily_token_budget.unwrap_or(0)),
+        },
+        log: LogConfig {
+            level: Some(log_level(false)),
+            format: Some(file.log.format.unwrap_or(LogFormat::Text)),
+        },
+        transport: TransportConfig {
+            address: Some(
+                file.transport
+                    .address
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_HTTP_ADDRESS.to_string()),
+            ),
+            socket: file.transport.socket.clone(),
+            metrics_address: file.transport.metrics_address,
+        },
+    };
+
+    let sources = config::sources();
+    if sources.is_empty() {
+        println!("# No configuration files found, using defaults");
     } else {
-        tracing::Level::INFO
+        println!("# Configuration files, lowest precedence first:");
+        for path in &sources {
+            println!("#   {}", path.display());
+        }
+    }
+    println!("# Values include environment variables and command-line flags.\n");
+    print!("{}", toml::to_string_pretty(&effective)?);
+
+    Ok(())
+}
+
+/// Log level of the servers: `--debug`, then the configured level, then
+/// `info`. `RUST_LOG` is applied on top by the log filter.
+fn log_level(debug: bool) -> String {
+    if debug {
+        return "debug".to_string();
+    }
+
+    config::current()
+        .log
+        .level
+        .clone()
+        .unwrap_or_else(|| "info".to_string())
+}
+
+fn dump_prompts(dir: Option<PathBuf>, force: bool) -> Result<()> {
+    let dir = dir.unwrap_or_else(game_design::prompts::override_dir);
+    let written = game_design::prompts::dump(&dir, force)?;
+
+    for path in &written {
+        println!("Wrote {}", path.display());
+    }
+
+    let skipped = game_design::prompts::DEFAULT_PROMPTS.len() - written.len();
+    if skipped > 0 {
+        println!(
+            "Kept {} existing prompt file(s) in {} (use --force to overwrite)",
+            skipped,
+            dir.display()
+        );
+    }
+
+    Ok(())
+}
+
+/// Opens the sessions of the default project.
+fn session_manager() -> Result<game_design::SessionManager> {
+    let session_manager = game_design::SessionManager::new()?;
+
+    match game_design::project::default_project() {
+        Some(project) => session_manager.for_project(project),
+        None => Ok(session_manager),
+    }
+}
+
+async fn undo(session_name: &str, expect: Option<u64>) -> Result<()> {
+    let outcome = session_manager()?.undo(session_name, expect).await?;
+    println!("{}", tools::game_tools::describe_undo(&outcome));
+    Ok(())
+}
+
+/// Prints the signature of a humanApprove decision. The signed details must
+/// match the arguments of the humanApprove call.
+fn sign_approval(
+    session_name: &str,
+    feature: Option<String>,
+    reject: bool,
+    author: &str,
+    comment: Option<&str>,
+) -> Result<()> {
+    let session_manager = session_manager()?;
+    let session = session_manager
+        .read_session(session_name)?
+        .ok_or_else(|| anyhow::anyhow!("Session '{}' not found", session_name))?;
+    let feature = feature
+        .or_else(|| session.next_feature_to_implement.clone())
+        .ok_or_else(|| {
+            anyhow::anyhow!("No feature is being implemented; name the feature with --feature")
+        })?;
+
+    // Trimmed the same way humanApprove trims its arguments
+    let author = author.trim();
+    if author.is_empty() {
+        anyhow::bail!("--author cannot be empty");
+    }
+    let comment = comment.map(str::trim).filter(|comment| !comment.is_empty());
+
+    let signature = game_design::approval::sign(
+        session_manager.project(),
+        &session,
+        &feature,
+        !reject,
+        author,
+        comment,
+    )?;
+    println!("{}", signature);
+
+    Ok(())
+}
+
+async fn history(
+    session_name: &str,
+    types: Vec<String>,
+    since: Option<String>,
+    until: Option<String>,
+    limit: usize,
+    json: bool,
+) -> Result<()> {
+    use game_design::events::{self, EventFilter};
+
+    let filter = EventFilter {
+        kinds: types,
+        since: since
+            .map(|since| events::parse_time(&since, false))
+            .transpose()?,
+        until: until
+            .map(|until| events::parse_time(&until, true))
+            .transpose()?,
     };
 
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env().add_directive(level.into()))
+    let events = session_manager()?
+        .session_history(session_name, &filter, limit)
+        .await?;
+
+    if events.is_empty() {
+        eprintln!("No matching events.");
+    }
+
+    for event in &events {
+        if json {
+            println!("{}", serde_json::to_string(event)?);
+            continue;
+        }
+
+        let mut line = format!(
+            "{}  {:<22}",
+            event.timestamp.format("%Y-%m-%d %H:%M:%S"),
+            event.kind.as_str()
+        );
+        for key in ["feature", "author"] {
+            if let Some(value) = event.data.get(key).and_then(|v| v.as_str()) {
+                line.push_str(&format!("  {}={}", key, value));
+            }
+        }
+        println!("{}", line.trim_end());
+    }
+
+    Ok(())
+}
+
+async fn self_update(check: bool) -> Result<()> {
+    let current = env!("CARGO_PKG_VERSION");
+    let release = update::latest_release().await?;
+
+    if !update::is_newer(release.version(), current) {
+        println!(
+            "gamedesignerd {} is up to date (latest release: {})",
+            current, release.tag_name
+        );
+        return Ok(());
+    }
+
+    if check {
+        println!(
+            "gamedesignerd {} is available (installed: {}): {}",
+            release.version(),
+            current,
+            release.html_url
+        );
+        return Ok(());
+    }
+
+    let exe = update::install(&release).await?;
+    println!(
+        "Updated {} from {} to {}; restart running servers to use it",
+        exe.display(),
+        current,
+        release.version()
+    );
+
+    Ok(())
+}
+
+async fn doctor(dry_run: bool, offline: bool) -> Result<()> {
+    use game_design::diagnostics::{self, Check, Status};
+
+    let provider = if offline {
+        Check::skipped("LLM provider", "not pinged with --offline")
+    } else {
+        diagnostics::check_provider().await
+    };
+    let checks = [
+        diagnostics::check_data_dir(),
+        diagnostics::check_config(),
+        diagnostics::check_api_key(),
+        provider,
+        diagnostics::check_orphans(),
+        Check::ok(
+            "MCP protocol",
+            format!(
+                "speaks {}, gamedesignerd {}",
+                transport::MCP_PROTOCOL_VERSIONS.join(", "),
+                env!("CARGO_PKG_VERSION")
+            ),
+        ),
+    ];
+    for check in &checks {
+        println!("{}", check);
+    }
+    println!();
+
+    let report = game_design::integrity::check_all(dry_run)?;
+
+    let (repaired, quarantined) = if dry_run {
+        ("Would repair", "Would quarantine")
+    } else {
+        ("Repaired", "Quarantined")
+    };
+
+    for problem in &report.repaired {
+        println!(
+            "{} {}: {}",
+            repaired,
+            problem.file.display(),
+            problem.description
+        );
+    }
+    for problem in &report.warnings {
+        println!(
+            "Warning {}: {}",
+            problem.file.display(),
+            problem.description
+        );
+    }
+    for problem in &report.quarantined {
+        println!(
+            "{} {}: {}",
+            quarantined,
+            problem.file.display(),
+            problem.description
+        );
+    }
+
+    if report.is_clean() {
+        println!(
+            "Checked {} session files, no problems found",
+            report.checked
+        );
+    } else if !dry_run && !report.quarantined.is_empty() {
+        println!("Quarantined files were moved to the quarantine directory next to them");
+    }
+
+    let failed = checks
+        .iter()
+        .filter(|check| check.status == Status::Failed)
+        .count();
+    if failed > 0 {
+        anyhow::bail!("{} check(s) failed", failed);
+    }
+
+    Ok(())
+}
+
+/// Reloads the configuration whenever the process receives `SIGHUP`.
+#[cfg(unix)]
+fn spawn_reload_on_sighup(service: GameDesignerService) {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            tracing::warn!("Failed to listen for SIGHUP: {}", e);
+            return;
+        }
+    };
+
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            if let Err(e) = service.reload().await {
+                tracing::error!("Failed to reload configuration: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(not(unix))]
+fn spawn_reload_on_sighup(_service: GameDesignerService) {}
+
+/// Validates the stored sessions before serving, so broken files are
+/// reported and quarantined up front.
+fn run_startup_checks() {
+    match game_design::integrity::check_all(false) {
+        Ok(report) => report.log(),
+        Err(e) => tracing::error!("Session integrity check failed: {}", e),
+    }
+}
+
+async fn run_stdio_server(
+    socket: Option<PathBuf>,
+    debug: bool,
+    log_format: LogFormat,
+    metrics_address: Option<SocketAddr>,
+) -> Result<()> {
+    // Set up file appender for logging; the working directory may be read-only
+    let file_appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("stdio-server.log")
+        .build(game_design::session::persistence_dir().join("logs"))?;
+
+    // Initialize the tracing subscriber with file logging
+    let level: Directive = log_level(debug).parse()?;
+
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env().add_directive(level))
         .with_writer(file_appender)
         .with_target(false)
         .with_thread_ids(true)
         .with_file(true)
-        .with_line_number(true)
-        .init();
+        .with_line_number(true);
+
+    match log_format {
+        LogFormat::Text => subscriber.init(),
+        LogFormat::Json => subscriber.json().with_current_span(true).init(),
+    }
+
+    run_startup_checks();
+    transport::telemetry::install()?;
+
+    if let Some(address) = metrics_address {
+        tokio::spawn(async move {
+            if let Err(e) = transport::telemetry::serve(address).await {
+                tracing::error!("Metrics server failed: {}", e);
+            }
+        });
+    }
+
+    if let Some(socket) = socket {
+        tracing::info!("Starting Game Designer MCP server in local socket mode");
+        let service = GameDesignerService::new()?;
+        spawn_reload_on_sighup(service.clone());
+
+        return transport::local_socket::serve(&socket, service).await;
+    }
 
     tracing::info!("Starting Game Designer MCP server in STDIN/STDOUT mode");
 
     // Create an instance of our game tools router
-    let router = RouterService(GameToolsRouter::new()?); // Handle potential API key error
+    let service = GameDesignerService::new()?;
+    spawn_reload_on_sighup(service.clone());
+
+    let router = GameToolsRouter::new(service);
+    let stdin = transport::cancellation::watch(stdin(), router.cancellations());
 
     // Create and run the server
-    let server = Server::new(router);
-    let transport = ByteTransport::new(stdin(), stdout());
+    let server = Server::new(RouterService(router));
+    let _active = transport::telemetry::ActiveSession::start();
+    let transport = ByteTransport::new(stdin, stdout());
 
     tracing::info!("Game Designer MCP server initialized and ready to handle requests");
     Ok(server.run(transport).await?)
 }
 
-async fn run_http_server(address: String, debug: bool) -> Result<()> {
+async fn run_http_server(address: String, debug: bool, log_format: LogFormat) -> Result<()> {
     // Setup tracing
-    let level = if debug { "debug" } else { "info" };
+    let level = log_level(debug);
 
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| format!("{},{}", level, env!("CARGO_CRATE_NAME")).into()),
         )
-        .with(tracing_subscriber::fmt::layer())
+        .with((log_format == LogFormat::Text).then(tracing_subscriber::fmt::layer))
+        .with((log_format == LogFormat::Json).then(|| {
+            tracing_subscriber::fmt::layer()
+                .json()
+                .with_current_span(true)
+        }))
         .init();
 
+    run_startup_checks();
+    transport::telemetry::install()?;
+
     // Parse socket address
     let addr: SocketAddr = address.parse()?;
     let listener = tokio::net::TcpListener::bind(addr).await?;
@@ -157,23 +1210,78 @@ async fn run_http_server(address: String, debug: bool) -> Result<()> {
     );
     tracing::info!("Access the Game Designer MCP Server at http://{}/sse", addr);
 
-    // Note: The transport module might need updates if it's specific to docs.
-    // For now, we'll assume a generic HTTP SSE setup or that the transport module is adaptable.
-    // Create app and run server
-    let app = transport::http_sse_server::App::new(); // This path might need adjustment
+    // Create app and run server; all connections share one service
+    let service = GameDesignerService::new()?;
+    spawn_reload_on_sighup(service.clone());
+
+    let app = transport::http_sse_server::App::new(service);
     axum::serve(listener, app.router()).await?;
 
     Ok(())
 }
 
+async fn run_dashboard(address: SocketAddr, debug: bool) -> Result<()> {
+    let level = log_level(debug);
+
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| format!("{},{}", level, env!("CARGO_CRATE_NAME")).into()),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    let listener = tokio::net::TcpListener::bind(address).await?;
+    tracing::info!("Game Designer dashboard at http://{}/", address);
+
+    let service = GameDesignerService::new()?;
+    axum::serve(listener, transport::dashboard::router(service)).await?;
+
+    Ok(())
+}
+
+async fn run_discord_bot(
+    token: &str,
+    session: String,
+    guild: Option<u64>,
+    debug: bool,
+) -> Result<()> {
+    let level = log_level(debug);
+
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| format!("{},{}", level, env!("CARGO_CRATE_NAME")).into()),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    run_startup_checks();
+
+    tracing::info!("Starting Game Designer Discord bot");
+
+    let service = GameDesignerService::new()?;
+    spawn_reload_on_sighup(service.clone());
+
+    transport::discord::serve(token, session, guild, service).await
+}
+
 /// Configuration for the test tool
 struct TestToolConfig {
     tool: String,
     session_name: Option<String>,
     game_description: Option<String>,
+    document_path: Option<PathBuf>,
+    persona: Option<String>,
+    template: Option<String>,
+    repo: Option<PathBuf>,
+    from: Option<String>,
+    team: Option<String>,
+    include_diff: bool,
     changes_made: Option<String>,
     content: Option<String>,
     question: Option<String>,
+    cursor: Option<u64>,
     debug: bool,
 }
 
@@ -183,9 +1291,17 @@ async fn run_test_tool(config: TestToolConfig) -> Result<()> {
         tool,
         session_name,
         game_description,
+        document_path,
+        persona,
+        template,
+        repo,
+        from,
+        team,
+        include_diff,
         changes_made,
         content,
         question,
+        cursor,
         debug,
     } = config;
 
@@ -196,6 +1312,9 @@ async fn run_test_tool(config: TestToolConfig) -> Result<()> {
         println!(
             "  cargo run --bin gamedesignerd -- test --tool designNew --session-name my_game --game-description \"A 2D platformer about cats in space\""
         );
+        println!(
+            "  cargo run --bin gamedesignerd -- test --tool designImport --session-name my_game --from github --repo owner/name"
+        );
         println!(
             "  cargo run --bin gamedesignerd -- test --tool designOverview --session-name my_game"
         );
@@ -211,14 +1330,19 @@ async fn run_test_tool(config: TestToolConfig) -> Result<()> {
         println!(
             "  cargo run --bin gamedesignerd -- test --tool featureAsk --session-name my_game --question \"How should the player interact with collectible items?\""
         );
+        println!(
+            "  cargo run --bin gamedesignerd -- test --tool sessionEvents --session-name my_game --cursor 0"
+        );
 
         println!("\nAvailable tools:");
         println!("  designNew      - Create a new game design session");
+        println!("  designImport   - Create a session from a tracker's open issues");
         println!("  designOverview - Get the initial game design goals");
         println!("  nextFeature    - Get the next feature specification");
         println!("  featureReview  - Submit a feature implementation for review");
         println!("  reviewReply    - Reply to questions from the review process");
         println!("  featureAsk     - Ask an ad-hoc question about the design");
+        println!("  sessionEvents  - Read the session's state-change events");
         println!("  help           - Show this help information");
 
         return Ok(());
@@ -238,7 +1362,7 @@ async fn run_test_tool(config: TestToolConfig) -> Result<()> {
         .init();
 
     // Create router instance
-    let router = GameToolsRouter::new()?; // Handle potential API key error
+    let router = GameToolsRouter::new(GameDesignerService::new()?);
 
     tracing::info!("Testing tool: {}", tool);
 
@@ -248,14 +1372,56 @@ async fn run_test_tool(config: TestToolConfig) -> Result<()> {
             let session_name = session_name
                 .clone()
                 .ok_or_else(|| anyhow::anyhow!("--session-name is required for designNew tool"))?;
-            let game_description = game_description.clone().ok_or_else(|| {
-                anyhow::anyhow!("--game-description is required for designNew tool")
+            if game_description.is_none() && document_path.is_none() {
+                return Err(anyhow::anyhow!(
+                    "--game-description or --document-path is required for designNew tool"
+                ));
+            }
+
+            let mut arguments = json!({
+                "sessionName": session_name,
+            });
+            if let Some(game_description) = game_description {
+                arguments["gameDescription"] = json!(game_description);
+            }
+            if let Some(document_path) = document_path {
+                arguments["documentPath"] = json!(document_path);
+            }
+            if let Some(persona) = persona {
+                arguments["persona"] = json!(persona);
+            }
+            if let Some(template) = template {
+                arguments["template"] = json!(template);
+            }
+            if let Some(repo) = repo {
+                arguments["repo"] = json!(repo);
+            }
+
+            arguments
+        }
+        "designImport" => {
+            let session_name = session_name.clone().ok_or_else(|| {
+                anyhow::anyhow!("--session-name is required for designImport tool")
+            })?;
+            let from = from.clone().ok_or_else(|| {
+                anyhow::anyhow!("--from is required for designImport tool (github or linear)")
             })?;
 
-            json!({
+            let mut arguments = json!({
                 "sessionName": session_name,
-                "gameDescription": game_description,
-            })
+                "source": from,
+            });
+            if let Some(repo) = repo {
+                arguments["repository"] = json!(repo);
+            }
+            if let Some(team) = team {
+                arguments["team"] = json!(team);
+            }
+            if let Some(persona) = persona {
+                arguments["persona"] = json!(persona);
+            }
+
+            arguments
         }
         "designOverview" | "nextFeature" => {
             let session_name = session_name
@@ -277,6 +1443,7 @@ async fn run_test_tool(config: TestToolConfig) -> Result<()> {
             json!({
                 "sessionName": session_name,
                 "changesMade": changes_made,
+                "includeDiff": include_diff,
             })
         }
         "reviewReply" => {
@@ -305,11 +1472,25 @@ async fn run_test_tool(config: TestToolConfig) -> Result<()> {
                 "question": question,
             })
         }
+        "sessionEvents" => {
+            let session_name = session_name.clone().ok_or_else(|| {
+                anyhow::anyhow!("--session-name is required for sessionEvents tool")
+            })?;
+
+            json!({
+                "sessionName": session_name,
+                "cursor": cursor.unwrap_or(0),
+            })
+        }
         _ => return Err(anyhow::anyhow!("Unknown tool: {}", tool)),
     };
 
     // Call the tool and get results
-    tracing::debug!("Calling {} with arguments: {}", tool, arguments);
+    tracing::debug!(
+        "Calling {} with arguments: {}",
+        tool,
+        game_design::redact::redact(&arguments.to_string()).text
+    );
     println!("Executing {} tool...", tool);
 
     let result = match router.call_tool(&tool, arguments).await {
@@ -0,0 +1,70 @@
+//! Legacy tool names that keep working after a tool was renamed or
+//! replaced.
+//!
+//! A call of an alias is routed to its current tool, with the arguments
+//! renamed to the current names. The result carries a deprecation notice in
+//! its `_meta`, so that agents and their users learn about the new name
+//! before the alias is removed. Aliases are not listed in `tools/list`.
+
+use serde_json::{Value, json};
+
+/// A legacy tool name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alias {
+    /// The legacy name.
+    pub name: &'static str,
+    /// The tool calls are routed to.
+    pub tool: &'static str,
+    /// Arguments renamed since, legacy name first.
+    pub arguments: &'static [(&'static str, &'static str)],
+}
+
+/// Every alias. Tests check that each one routes to a listed tool and only
+/// renames arguments that tool accepts.
+pub const ALIASES: &[Alias] = &[
+    // Documentation tools of the original server template, replaced by
+    // crateDoc
+    Alias {
+        name: "lookup_crate",
+        tool: "crateDoc",
+        arguments: &[("crate_name", "crateName")],
+    },
+    Alias {
+        name: "lookup_item",
+        tool: "crateDoc",
+        arguments: &[("crate_name", "crateName"), ("item_path", "itemPath")],
+    },
+];
+
+/// Finds the alias of a legacy tool name.
+pub fn find(name: &str) -> Option<&'static Alias> {
+    ALIASES.iter().find(|alias| alias.name == name)
+}
+
+impl Alias {
+    /// Renames the legacy arguments of a call. Arguments already given under
+    /// their current name win.
+    pub fn rename_arguments(&self, mut arguments: Value) -> Value {
+        if let Some(object) = arguments.as_object_mut() {
+            for &(legacy, current) in self.arguments {
+                if let Some(value) = object.remove(legacy) {
+                    object.entry(current).or_insert(value);
+                }
+            }
+        }
+
+        arguments
+    }
+
+    /// The deprecation notice sent in the `_meta` of the call's result.
+    pub fn deprecation(&self) -> Value {
+        json!({
+            "tool": self.name,
+            "replacedBy": self.tool,
+            "message": format!(
+                "The tool '{}' is deprecated and will be removed; call '{}' instead",
+                self.name, self.tool
+            ),
+        })
+    }
+}
@@ -0,0 +1,128 @@
+//! Behavior hints of the tools, sent as the `annotations` of every tool in
+//! `tools/list`, so that clients can ask the user before running a tool that
+//! spends LLM tokens, takes minutes or overwrites data.
+//!
+//! Besides the hints of the MCP specification, every tool carries a
+//! `costHint` (`llm` if it calls the designer LLM, `local` otherwise) and a
+//! `longRunningHint`.
+
+use crate::tools::queue::LLM_TOOLS;
+use serde::Serialize;
+
+/// Tools that change neither sessions nor files.
+const READ_ONLY_TOOLS: &[&str] = &[
+    "designOverview",
+    "sessionEvents",
+    "qaHistory",
+    "traceabilityReport",
+    "designLint",
+    "debtList",
+    "statusReport",
+    "crateDoc",
+    "featureList",
+    "commitMessage",
+    "sessionList",
+    "sessionHistory",
+    "designSearch",
+    "designDiff",
+    "designUsage",
+    "jobStatus",
+    "jobResult",
+];
+
+/// Tools that may overwrite or revert data rather than only add to it.
+const DESTRUCTIVE_TOOLS: &[&str] = &[
+    "designUndo",
+    "boardSync",
+    "designExport",
+    "characterSheet",
+    "economyModel",
+    "designPersona",
+    "designEngine",
+    "sessionConfigure",
+    "conventionsSet",
+    "featureReject",
+];
+
+/// Tools that change data but have no further effect when called again with
+/// the same arguments.
+const IDEMPOTENT_TOOLS: &[&str] = &[
+    "assetList",
+    "designExport",
+    "boardSync",
+    "designPersona",
+    "designEngine",
+    "sessionConfigure",
+    "conventionsSet",
+    "configReload",
+    "scopeReport",
+    "designFreeze",
+    "designUnfreeze",
+];
+
+/// Tools that write exports of a session without changing it.
+const EXPORT_TOOLS: &[&str] = &["assetList", "designExport", "devlogGenerate", "designRetro"];
+
+/// Tools that reach services other than the LLM provider: GitHub, Linear,
+/// docs.rs and the embeddings provider.
+const EXTERNAL_TOOLS: &[&str] = &[
+    "backlogImport",
+    "designImport",
+    "boardSync",
+    "crateDoc",
+    "designSearch",
+];
+
+/// What a call of a tool costs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Cost {
+    /// Calls the designer LLM and spends tokens.
+    Llm,
+    /// Runs on the server only.
+    Local,
+}
+
+/// Annotations of a tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolAnnotations {
+    /// Changes neither sessions nor files.
+    pub read_only_hint: bool,
+    /// May overwrite or revert data; only meaningful if not read-only.
+    pub destructive_hint: bool,
+    /// Calling again with the same arguments has no further effect.
+    pub idempotent_hint: bool,
+    /// Reaches the LLM provider or other services outside the server.
+    pub open_world_hint: bool,
+    pub cost_hint: Cost,
+    /// May take minutes; such tools accept `background: true`.
+    pub long_running_hint: bool,
+}
+
+impl ToolAnnotations {
+    /// Annotations of the named tool.
+    pub fn of(tool: &str) -> Self {
+        let read_only = READ_ONLY_TOOLS.contains(&tool);
+        let calls_llm = LLM_TOOLS.contains(&tool);
+
+        Self {
+            read_only_hint: read_only,
+            destructive_hint: !read_only && DESTRUCTIVE_TOOLS.contains(&tool),
+            idempotent_hint: read_only || IDEMPOTENT_TOOLS.contains(&tool),
+            open_world_hint: calls_llm || EXTERNAL_TOOLS.contains(&tool),
+            cost_hint: if calls_llm { Cost::Llm } else { Cost::Local },
+            long_running_hint: calls_llm,
+        }
+    }
+}
+
+/// Whether the tool may run on a session frozen with `designFreeze`: read
+/// tools, exports and the freeze tools themselves.
+pub fn allowed_when_frozen(tool: &str) -> bool {
+    READ_ONLY_TOOLS.contains(&tool)
+        || EXPORT_TOOLS.contains(&tool)
+        || matches!(tool, "designFreeze" | "designUnfreeze")
+        // Refuses to change the effort budget of a frozen session
+        || tool == "scopeReport"
+}
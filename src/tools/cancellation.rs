@@ -0,0 +1,188 @@
+//! Timeouts and client cancellation of tool calls.
+//!
+//! A call that runs past its timeout, or that the client cancels with a
+//! `notifications/cancelled` notification, is dropped: the pending LLM
+//! request is aborted with it and the session keeps its state from before
+//! the call, as sessions are only written once every LLM response is in.
+
+use crate::game_design::config;
+use serde_json::Value;
+use std::{
+    collections::VecDeque,
+    env,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio_util::sync::CancellationToken;
+
+/// Limits of the duration of tool calls.
+#[derive(Debug, Clone)]
+pub struct TimeoutConfig {
+    /// Timeout of tools without one of their own; `None` for no limit.
+    pub default: Option<Duration>,
+    /// Timeouts of single tools; `None` for no limit.
+    pub tools: Vec<(String, Option<Duration>)>,
+}
+
+impl Default for TimeoutConfig {
+    fn default() -> Self {
+        Self {
+            default: Some(Duration::from_secs(300)),
+            tools: Vec::new(),
+        }
+    }
+}
+
+impl TimeoutConfig {
+    /// Reads the default timeout from `GAMEDESIGNER_TOOL_TIMEOUT_SECS`,
+    /// falling back to the configuration file and then to the default. The
+    /// timeouts of single tools come from the `[tools.timeouts]` table. A
+    /// timeout of `0` disables the limit.
+    pub fn from_env() -> Self {
+        fn limit(secs: u64) -> Option<Duration> {
+            (secs > 0).then(|| Duration::from_secs(secs))
+        }
+
+        let tools = config::current().tools.clone();
+
+        let default = env::var("GAMEDESIGNER_TOOL_TIMEOUT_SECS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .or(tools.timeout_secs)
+            .map_or(Self::default().default, limit);
+
+        Self {
+            default,
+            tools: tools
+                .timeouts
+                .into_iter()
+                .map(|(tool, secs)| (tool, limit(secs)))
+                .collect(),
+        }
+    }
+
+    /// Returns the timeout of `tool`.
+    pub fn for_tool(&self, tool: &str) -> Option<Duration> {
+        self.tools
+            .iter()
+            .find(|(name, _)| name == tool)
+            .map_or(self.default, |(_, timeout)| *timeout)
+    }
+}
+
+#[derive(Debug)]
+struct InFlight {
+    /// JSON-RPC id of the `tools/call` request.
+    id: Value,
+    tool: String,
+    /// Whether the router has started running the call.
+    claimed: bool,
+    token: CancellationToken,
+}
+
+/// The `tools/call` requests of one connection that have been read from the
+/// client and not answered yet.
+///
+/// The transport registers each request as it reads it and cancels it when
+/// the client asks to; the router claims the requests in the order it runs
+/// them, as the tool handler does not see the request ids.
+#[derive(Debug, Clone, Default)]
+pub struct Cancellations {
+    calls: Arc<Mutex<VecDeque<InFlight>>>,
+}
+
+impl Cancellations {
+    /// Records a `tools/call` request read from the client.
+    pub fn register(&self, id: Value, tool: String) {
+        self.calls.lock().unwrap().push_back(InFlight {
+            id,
+            tool,
+            claimed: false,
+            token: CancellationToken::new(),
+        });
+    }
+
+    /// Cancels the request `id`, whether it is running or still waiting.
+    /// Returns `false` if there is no such request, e.g. because it has
+    /// already been answered.
+    pub fn cancel(&self, id: &Value) -> bool {
+        let calls = self.calls.lock().unwrap();
+
+        match calls.iter().find(|call| &call.id == id) {
+            Some(call) => {
+                call.token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Claims the oldest unclaimed request of `tool`. Unclaimed requests
+    /// read before it were answered without reaching the router and are
+    /// forgotten. Without a registered request the returned handle is never
+    /// cancelled.
+    pub fn claim(&self, tool: &str) -> CallHandle {
+        let mut calls = self.calls.lock().unwrap();
+
+        let Some(index) = calls
+            .iter()
+            .position(|call| !call.claimed && call.tool == tool)
+        else {
+            return CallHandle::detached();
+        };
+
+        let mut position = 0;
+        calls.retain(|call| {
+            let keep = call.claimed || position >= index;
+            position += 1;
+            keep
+        });
+
+        let call = calls
+            .iter_mut()
+            .find(|call| !call.claimed && call.tool == tool)
+            .expect("the claimed call is kept");
+        call.claimed = true;
+
+        CallHandle {
+            id: Some(call.id.clone()),
+            token: call.token.clone(),
+            calls: self.calls.clone(),
+        }
+    }
+}
+
+/// A claimed call, forgotten by the registry on drop.
+#[derive(Debug)]
+pub struct CallHandle {
+    id: Option<Value>,
+    token: CancellationToken,
+    calls: Arc<Mutex<VecDeque<InFlight>>>,
+}
+
+impl CallHandle {
+    /// A handle of a call that no client request can cancel.
+    pub fn detached() -> Self {
+        Self {
+            id: None,
+            token: CancellationToken::new(),
+            calls: Arc::default(),
+        }
+    }
+
+    /// Completes when the client cancels the call.
+    pub async fn cancelled(&self) {
+        self.token.cancelled().await
+    }
+}
+
+impl Drop for CallHandle {
+    fn drop(&mut self) {
+        if let Some(id) = &self.id {
+            self.calls
+                .lock()
+                .unwrap()
+                .retain(|call| !(call.claimed && &call.id == id));
+        }
+    }
+}
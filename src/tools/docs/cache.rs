@@ -0,0 +1,242 @@
+//! Disk-backed cache of looked up documentation, so that restarting the
+//! server does not fetch and summarize the same pages again.
+//!
+//! Entries are stored as `<sha256 of the key>.json` in `doc-cache/` of the
+//! data directory. Each entry carries its own expiry: documentation of a
+//! pinned crate version never changes and is kept for [`PINNED_TTL`], the
+//! latest version for the configured TTL (`GAMEDESIGNER_DOC_CACHE_TTL_SECS`,
+//! default a week). Once the entries take more than the configured size
+//! (`GAMEDESIGNER_DOC_CACHE_MAX_MB`, default 100 MB), the oldest are evicted.
+
+use crate::game_design::{cache::CacheStats, config, session::persistence_dir};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{env, fs, path::PathBuf, sync::Arc, time::Duration};
+use tokio::sync::Mutex;
+
+/// TTL used when neither the environment nor the configuration sets one.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Lifetime of the documentation of a pinned crate version, unless the
+/// configured TTL is longer.
+pub const PINNED_TTL: Duration = Duration::from_secs(90 * 24 * 60 * 60);
+
+/// Size limit used when neither the environment nor the configuration sets
+/// one, in megabytes.
+pub const DEFAULT_MAX_MB: u64 = 100;
+
+/// Reads the TTL from `GAMEDESIGNER_DOC_CACHE_TTL_SECS`, falling back to
+/// the configuration file. A TTL of zero disables the cache.
+pub fn ttl() -> Duration {
+    env::var("GAMEDESIGNER_DOC_CACHE_TTL_SECS")
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .or(config::current().docs.cache_ttl_secs)
+        .map_or(DEFAULT_TTL, Duration::from_secs)
+}
+
+/// Reads the size limit from `GAMEDESIGNER_DOC_CACHE_MAX_MB`, falling back
+/// to the configuration file, in bytes.
+pub fn max_bytes() -> u64 {
+    env::var("GAMEDESIGNER_DOC_CACHE_MAX_MB")
+        .ok()
+        .and_then(|mb| mb.parse().ok())
+        .or(config::current().docs.cache_max_mb)
+        .unwrap_or(DEFAULT_MAX_MB)
+        * 1024
+        * 1024
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    key: String,
+    created_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+    value: String,
+}
+
+// Cache for documentation lookups to avoid repeated requests
+#[derive(Clone)]
+pub struct DocCache {
+    dir: PathBuf,
+    // Serializes writes, so that eviction sees every entry
+    lock: Arc<Mutex<()>>,
+}
+
+impl Default for DocCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DocCache {
+    /// Opens the cache in the data directory.
+    pub fn new() -> Self {
+        Self::open(persistence_dir().join("doc-cache"))
+    }
+
+    /// Opens the cache stored in `dir`.
+    pub fn open(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            lock: Arc::default(),
+        }
+    }
+
+    /// Directory holding the cache entries.
+    pub fn dir(&self) -> &PathBuf {
+        &self.dir
+    }
+
+    /// Key of the documentation of `item_path` in a crate, or of the crate
+    /// itself, e.g. `bevy@0.14.0::ecs::system::Query`.
+    pub fn key(crate_name: &str, version: Option<&str>, item_path: Option<&str>) -> String {
+        let key = format!("{}@{}", crate_name, version.unwrap_or("latest"));
+
+        match item_path {
+            Some(item_path) => format!("{}::{}", key, item_path),
+            None => key,
+        }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        let digest = Sha256::digest(key.as_bytes());
+        self.dir.join(format!("{}.json", hex::encode(digest)))
+    }
+
+    fn read(path: &PathBuf) -> Option<CacheEntry> {
+        serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+    }
+
+    /// Returns the cached value of `key` if it has not expired. Expired
+    /// entries are removed.
+    pub async fn get(&self, key: &str) -> Option<String> {
+        let path = self.path(key);
+        let entry = Self::read(&path).filter(|entry| entry.key == key)?;
+
+        if entry.expires_at < Utc::now() {
+            let _guard = self.lock.lock().await;
+            fs::remove_file(&path).ok();
+            return None;
+        }
+
+        Some(entry.value)
+    }
+
+    /// Caches `value` for the configured TTL.
+    pub async fn set(&self, key: String, value: String) {
+        self.set_with_ttl(key, value, ttl()).await;
+    }
+
+    /// Caches `value` for `ttl`, evicting the oldest entries if the cache
+    /// grows past its size limit. Failures are logged.
+    pub async fn set_with_ttl(&self, key: String, value: String, ttl: Duration) {
+        if ttl.is_zero() {
+            return;
+        }
+
+        let _guard = self.lock.lock().await;
+        let created_at = Utc::now();
+        let entry = CacheEntry {
+            expires_at: chrono::Duration::from_std(ttl)
+                .ok()
+                .and_then(|ttl| created_at.checked_add_signed(ttl))
+                .unwrap_or(DateTime::<Utc>::MAX_UTC),
+            created_at,
+            key,
+            value,
+        };
+
+        let result = serde_json::to_string(&entry)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| {
+                fs::create_dir_all(&self.dir)?;
+                fs::write(self.path(&entry.key), json)?;
+                self.evict(max_bytes())
+            });
+
+        if let Err(e) = result {
+            tracing::warn!("Failed to cache documentation: {}", e);
+        }
+    }
+
+    // Removes expired entries, then the oldest ones until the cache fits in
+    // `max_bytes`
+    fn evict(&self, max_bytes: u64) -> Result<()> {
+        let now = Utc::now();
+        let mut entries = Vec::new();
+
+        for file in fs::read_dir(&self.dir)? {
+            let path = file?.path();
+            let size = fs::metadata(&path)?.len();
+
+            match Self::read(&path) {
+                Some(entry) if entry.expires_at >= now => {
+                    entries.push((entry.created_at, size, path))
+                }
+                _ => fs::remove_file(&path)?,
+            }
+        }
+
+        let mut total: u64 = entries.iter().map(|(_, size, _)| size).sum();
+        entries.sort_by_key(|(created_at, _, _)| *created_at);
+
+        for (_, size, path) in entries {
+            if total <= max_bytes {
+                break;
+            }
+            fs::remove_file(&path)?;
+            total -= size;
+        }
+
+        Ok(())
+    }
+
+    /// Counts the entries and their size.
+    pub fn stats(&self) -> Result<CacheStats> {
+        let mut stats = CacheStats::default();
+        if !self.dir.exists() {
+            return Ok(stats);
+        }
+
+        let now = Utc::now();
+        for file in fs::read_dir(&self.dir)? {
+            let path = file?.path();
+            stats.entries += 1;
+            stats.bytes += fs::metadata(&path)?.len();
+
+            match Self::read(&path) {
+                Some(entry) => {
+                    if entry.expires_at < now {
+                        stats.expired += 1;
+                    }
+                    stats.oldest = Some(
+                        stats
+                            .oldest
+                            .map_or(entry.created_at, |oldest| oldest.min(entry.created_at)),
+                    );
+                }
+                None => stats.expired += 1,
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Removes every entry and returns how many there were.
+    pub fn clear(&self) -> Result<usize> {
+        if !self.dir.exists() {
+            return Ok(0);
+        }
+
+        let mut removed = 0;
+        for file in fs::read_dir(&self.dir)? {
+            fs::remove_file(file?.path())?;
+            removed += 1;
+        }
+
+        Ok(removed)
+    }
+}
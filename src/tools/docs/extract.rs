@@ -0,0 +1,319 @@
+//! Extraction of the parts of a rustdoc page worth summarizing: the item's
+//! signature, its doc comment with examples, the signatures of its methods
+//! and the items of a module.
+//!
+//! Rustdoc marks these parts with stable class names, so they are picked out
+//! of the HTML directly and everything else on the page is dropped before
+//! the summarizer sees it.
+
+use crate::tools::docs::strip_rustdoc_chrome;
+use html2md::parse_html;
+
+// Methods listed with their signature and first doc paragraph
+const MAX_METHODS: usize = 60;
+
+// Items of a module listed with their short description
+const MAX_ITEMS: usize = 200;
+
+// Section ids of implementations that are the same on every type
+const GENERATED_IMPLS: &[&str] = &[
+    "id=\"synthetic-implementations\"",
+    "id=\"blanket-implementations\"",
+];
+
+// An element of the page: its opening tag and its inner HTML
+struct Element<'a> {
+    start: usize,
+    end: usize,
+    open_tag: &'a str,
+    inner: &'a str,
+}
+
+// Whether `open_tag` opens a `tag` element, not e.g. a `<details>` for `<d`
+fn is_tag(open_tag: &str, tag: &str) -> bool {
+    open_tag[1 + tag.len()..]
+        .chars()
+        .next()
+        .is_some_and(|c| c == '>' || c == '/' || c.is_whitespace())
+}
+
+// Whether the class attribute of `open_tag` has all of `classes`
+fn has_classes(open_tag: &str, classes: &[&str]) -> bool {
+    let Some(start) = open_tag
+        .find("class=\"")
+        .map(|index| index + "class=\"".len())
+    else {
+        return classes.is_empty();
+    };
+    let Some(end) = open_tag[start..].find('"') else {
+        return false;
+    };
+    let class = &open_tag[start..start + end];
+
+    classes
+        .iter()
+        .all(|wanted| class.split_whitespace().any(|name| name == *wanted))
+}
+
+// Finds where the `tag` element whose content starts at `from` ends,
+// skipping nested elements of the same tag. Returns the end of its content
+// and the end of its closing tag.
+fn closing(html: &str, tag: &str, from: usize) -> Option<(usize, usize)> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let mut depth = 1;
+    let mut position = from;
+
+    loop {
+        let next_close = position + html[position..].find(&close)?;
+        let next_open = html[position..next_close]
+            .match_indices(&open)
+            .map(|(index, _)| position + index)
+            .find(|&index| is_tag(&html[index..], tag));
+
+        match next_open {
+            Some(index) => {
+                depth += 1;
+                position = index + open.len();
+            }
+            None => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((next_close, next_close + close.len()));
+                }
+                position = next_close + close.len();
+            }
+        }
+    }
+}
+
+// Finds the `tag` elements of the page with all of `classes`, in order of
+// the page. Elements nested in a found one are not returned.
+fn elements<'a>(html: &'a str, tag: &str, classes: &[&str]) -> Vec<Element<'a>> {
+    let open = format!("<{}", tag);
+    let mut found = Vec::new();
+    let mut from = 0;
+
+    while let Some(offset) = html[from..].find(&open) {
+        let start = from + offset;
+        from = start + open.len();
+
+        let Some(tag_end) = html[start..].find('>').map(|index| start + index + 1) else {
+            break;
+        };
+        let open_tag = &html[start..tag_end];
+        if !is_tag(open_tag, tag) || !has_classes(open_tag, classes) {
+            continue;
+        }
+
+        let Some((inner_end, end)) = closing(html, tag, tag_end) else {
+            continue;
+        };
+        found.push(Element {
+            start,
+            end,
+            open_tag,
+            inner: &html[tag_end..inner_end],
+        });
+        from = end;
+    }
+
+    found
+}
+
+// Decodes the entities rustdoc writes
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+// Text of an HTML fragment, keeping its line breaks
+fn text(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    decode_entities(&text)
+}
+
+// Text of an HTML fragment on a single line
+fn line(html: &str) -> String {
+    text(html).split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// The top doc comment is the first full docblock; the short descriptions of
+// module items and old item declarations are docblocks too
+fn is_doc_comment(element: &Element) -> bool {
+    !has_classes(element.open_tag, &["docblock-short"])
+        && !has_classes(element.open_tag, &["item-decl"])
+}
+
+/// Extracts the item signature, doc comment, method signatures and module
+/// items of a rustdoc page as Markdown. Returns `None` for pages that are
+/// not rustdoc output.
+pub fn extract(html: &str) -> Option<String> {
+    // The page title names the item without the buttons of the heading,
+    // e.g. `Query in bevy::ecs::system - Rust`
+    let title = elements(html, "title", &[])
+        .into_iter()
+        .next()
+        .map(|title| line(title.inner).trim_end_matches(" - Rust").to_string());
+
+    let main = strip_rustdoc_chrome(html);
+    let main = main.as_str();
+
+    let declaration = elements(main, "pre", &["item-decl"])
+        .into_iter()
+        .chain(elements(main, "div", &["item-decl"]))
+        .next()
+        .map(|declaration| text(declaration.inner).trim().to_string());
+    let docs = elements(main, "div", &["docblock"])
+        .into_iter()
+        .find(is_doc_comment)
+        .map(|docblock| parse_html(docblock.inner).trim().to_string());
+
+    // Trait and generated implementations are listed by name only
+    let traits_start = main.find("id=\"trait-implementations\"");
+    let generated_start = GENERATED_IMPLS.iter().filter_map(|id| main.find(id)).min();
+    let own_end = traits_start.or(generated_start).unwrap_or(main.len());
+    let own = &main[..own_end];
+
+    let methods = elements(own, "h4", &["code-header"])
+        .into_iter()
+        .take(MAX_METHODS)
+        .map(|header| {
+            let signature = line(header.inner);
+            let rest = &own[header.end..];
+            let next_header = rest.find("<h4").unwrap_or(rest.len());
+            let summary = elements(&rest[..next_header], "div", &["docblock"])
+                .first()
+                .and_then(|docblock| elements(docblock.inner, "p", &[]).into_iter().next())
+                .map(|paragraph| line(paragraph.inner));
+
+            match summary {
+                Some(summary) => format!("- `{}`: {}", signature, summary),
+                None => format!("- `{}`", signature),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let traits = match traits_start {
+        Some(start) => {
+            let end = generated_start
+                .filter(|&end| end > start)
+                .unwrap_or(main.len());
+            elements(&main[start..end], "h3", &["code-header"])
+                .into_iter()
+                .map(|header| format!("`{}`", line(header.inner)))
+                .collect::<Vec<_>>()
+        }
+        None => Vec::new(),
+    };
+
+    // Items of a module, under the heading of their kind
+    let headings = elements(own, "h2", &["section-header"]);
+    let mut items = Vec::new();
+    for table in elements(own, "dl", &["item-table"])
+        .into_iter()
+        .chain(elements(own, "ul", &["item-table"]))
+        .chain(elements(own, "div", &["item-table"]))
+    {
+        if items.len() >= MAX_ITEMS {
+            break;
+        }
+
+        if let Some(heading) = headings
+            .iter()
+            .rev()
+            .find(|heading| heading.start < table.start)
+        {
+            items.push(format!(
+                "\n### {}",
+                line(heading.inner).trim_end_matches('§')
+            ));
+        }
+
+        let names = elements(table.inner, "dt", &[]);
+        let (names, descriptions) = if names.is_empty() {
+            (
+                elements(table.inner, "div", &["item-name"]),
+                elements(table.inner, "div", &["desc"]),
+            )
+        } else {
+            (names, elements(table.inner, "dd", &[]))
+        };
+
+        for (index, name) in names.iter().enumerate() {
+            match descriptions
+                .get(index)
+                .map(|description| line(description.inner))
+            {
+                Some(description) if !description.is_empty() => {
+                    items.push(format!("- `{}`: {}", line(name.inner), description))
+                }
+                _ => items.push(format!("- `{}`", line(name.inner))),
+            }
+        }
+    }
+
+    if declaration.is_none() && docs.is_none() && methods.is_empty() && items.is_empty() {
+        return None;
+    }
+
+    let mut markdown = String::new();
+    if let Some(title) = title {
+        markdown.push_str(&format!("# {}\n\n", title));
+    }
+    if let Some(declaration) = declaration {
+        markdown.push_str(&format!("```rust\n{}\n```\n\n", declaration));
+    }
+    if let Some(docs) = docs {
+        markdown.push_str(&format!("{}\n\n", docs));
+    }
+    if !methods.is_empty() {
+        markdown.push_str(&format!("## Methods\n\n{}\n\n", methods.join("\n")));
+    }
+    if !traits.is_empty() {
+        markdown.push_str(&format!(
+            "## Trait implementations\n\n{}\n\n",
+            traits.join(", ")
+        ));
+    }
+    if !items.is_empty() {
+        markdown.push_str(&format!("## Items\n{}\n", items.join("\n")));
+    }
+
+    Some(markdown.trim_end().to_string())
+}
+
+/// Extracts the first sentence of the doc comment of a rustdoc page, or an
+/// empty string for undocumented items.
+pub fn synopsis(html: &str) -> String {
+    let main = strip_rustdoc_chrome(html);
+    let Some(paragraph) = elements(&main, "div", &["docblock"])
+        .into_iter()
+        .find(is_doc_comment)
+        .and_then(|docblock| elements(docblock.inner, "p", &[]).into_iter().next())
+        .map(|paragraph| line(paragraph.inner))
+    else {
+        return String::new();
+    };
+
+    match paragraph.find(". ") {
+        Some(end) => paragraph[..=end].to_string(),
+        None => paragraph,
+    }
+}
@@ -1,6 +1,10 @@
+pub mod cache;
+pub mod extract;
 pub mod summarizer;
 
-use crate::tools::docs::summarizer::AiSummarizer;
+pub use cache::DocCache;
+
+use crate::{game_design::context, tools::docs::summarizer::AiSummarizer};
 use html2md::parse_html;
 use mcp_core::{
     Content, Resource, Tool, ToolError,
@@ -11,44 +15,119 @@ use mcp_core::{
 use mcp_server::router::CapabilitiesBuilder;
 use reqwest::Client;
 use serde_json::{Value, json};
-use std::{future::Future, pin::Pin, sync::Arc};
-use tokio::sync::Mutex;
+use std::{future::Future, pin::Pin};
+
+// Documentation pages are cut to about this many tokens before they are
+// summarized
+const MAX_DOC_TOKENS: usize = 12_000;
+
+// Elements of rustdoc and docs.rs pages that hold no documentation
+const CHROME_ELEMENTS: &[&str] = &[
+    "script",
+    "style",
+    "noscript",
+    "nav",
+    "form",
+    "button",
+    "rustdoc-toolbar",
+    "rustdoc-search",
+];
+
+// Lines the remaining chrome leaves in the Markdown
+const CHROME_LINES: &[&str] = &["Expand description", "Copy item path", "Source", "§", "ⓘ"];
+
+// Removes every `tag` element, with its content, from the HTML
+fn remove_elements(html: &str, tag: &str) -> String {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let mut html = html.to_string();
+    let mut from = 0;
+
+    while let Some(offset) = html[from..].find(&open) {
+        let start = from + offset;
+
+        // Only the tag itself, not e.g. `<navigation>` for `<nav`
+        let after = html[start + open.len()..].chars().next();
+        if !after.is_some_and(|c| c == '>' || c == '/' || c.is_whitespace()) {
+            from = start + open.len();
+            continue;
+        }
 
-// Cache for documentation lookups to avoid repeated requests
-#[derive(Clone)]
-pub struct DocCache {
-    cache: Arc<Mutex<std::collections::HashMap<String, String>>>,
+        let end = match html[start..].find(&close) {
+            Some(end) => start + end + close.len(),
+            None => match html[start..].find('>') {
+                Some(end) => start + end + 1,
+                None => html.len(),
+            },
+        };
+        html.replace_range(start..end, "");
+        from = start;
+    }
+
+    html
 }
 
-impl Default for DocCache {
-    fn default() -> Self {
-        Self::new()
-    }
+/// Cuts the chrome of a rustdoc page, such as the docs.rs header, the
+/// sidebar, the search bar and the settings, keeping the documentation of
+/// the crate or item.
+pub fn strip_rustdoc_chrome(html: &str) -> String {
+    // The documentation itself is the main content section
+    let content = match html.find("id=\"main-content\"") {
+        Some(index) => {
+            let start = html[..index].rfind('<').unwrap_or(index);
+            let end = html[start..]
+                .find("</main>")
+                .or_else(|| html[start..].find("<footer"))
+                .map_or(html.len(), |end| start + end);
+            &html[start..end]
+        }
+        None => html,
+    };
+
+    CHROME_ELEMENTS
+        .iter()
+        .fold(content.to_string(), |html, tag| remove_elements(&html, tag))
 }
 
-impl DocCache {
-    pub fn new() -> Self {
-        Self {
-            cache: Arc::new(Mutex::new(std::collections::HashMap::new())),
+// Drops the lines of leftover chrome and runs of blank lines from the
+// converted Markdown
+fn clean_markdown(markdown: &str) -> String {
+    let mut cleaned = String::new();
+    let mut blank = false;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        if CHROME_LINES.contains(&trimmed) {
+            continue;
+        }
+        if trimmed.is_empty() {
+            if !blank && !cleaned.is_empty() {
+                cleaned.push('\n');
+            }
+            blank = true;
+            continue;
         }
-    }
 
-    pub async fn get(&self, key: &str) -> Option<String> {
-        let cache = self.cache.lock().await;
-        cache.get(key).cloned()
+        blank = false;
+        cleaned.push_str(line.trim_end());
+        cleaned.push('\n');
     }
 
-    pub async fn set(&self, key: String, value: String) {
-        let mut cache = self.cache.lock().await;
-        cache.insert(key, value);
-    }
+    cleaned.trim_end().to_string()
+}
+
+/// Converts a documentation page to the Markdown the summarizer gets: the
+/// extracted signatures, doc comments and examples of rustdoc pages, or the
+/// page without its chrome otherwise.
+pub fn page_markdown(html: &str) -> String {
+    extract::extract(html)
+        .unwrap_or_else(|| clean_markdown(&parse_html(&strip_rustdoc_chrome(html))))
 }
 
 #[derive(Clone)]
 pub struct DocRouter {
     pub client: Client,
     pub cache: DocCache,
-    pub summarizer: AiSummarizer,
 }
 
 impl Default for DocRouter {
@@ -62,10 +141,188 @@ impl DocRouter {
         Self {
             client: Client::new(),
             cache: DocCache::new(),
-            summarizer: AiSummarizer::new().unwrap(),
         }
     }
 
+    // Without an API key, documentation is returned without a summary.
+    // Resolved per lookup, so that rotated keys take effect on reload
+    fn summarizer(&self) -> Option<AiSummarizer> {
+        AiSummarizer::new().ok()
+    }
+
+    // Fetch the HTML of a docs.rs page
+    async fn fetch_html(&self, url: &str) -> Result<String, ToolError> {
+        let response = self
+            .client
+            .get(url)
+            .header(
+                "User-Agent",
+                "CrateDocs/0.1.0 (https://github.com/d6e/cratedocs-mcp)",
+            )
+            .send()
+            .await
+            .map_err(|e| {
+                ToolError::ExecutionError(format!("Failed to fetch documentation: {}", e))
+            })?;
+
+        if !response.status().is_success() {
+            return Err(ToolError::ExecutionError(format!(
+                "Failed to fetch documentation. Status: {}",
+                response.status()
+            )));
+        }
+
+        response
+            .text()
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to read response body: {}", e)))
+    }
+
+    // Fetch the HTML of the docs.rs page of an item, trying each item type
+    // (module, struct, enum, trait, fn, macro) in turn
+    async fn fetch_item_html(
+        &self,
+        crate_name: &str,
+        item_path: &str,
+        version: Option<&str>,
+    ) -> Result<String, ToolError> {
+        // Format: module::path::ItemName
+        // Need to split into module path and item name, and guess item type
+        let parts: Vec<&str> = item_path.split("::").collect();
+
+        if parts.iter().any(|part| part.is_empty()) {
+            return Err(ToolError::InvalidParameters(
+                "Invalid item path. Expected format: module::path::ItemName".to_string(),
+            ));
+        }
+
+        let item_name = parts.last().unwrap().to_string();
+        let module_path = if parts.len() > 1 {
+            format!("{}/", parts[..parts.len() - 1].join("/"))
+        } else {
+            String::new()
+        };
+
+        // Crate names with dashes are documented under underscores
+        let root = format!(
+            "https://docs.rs/{}/{}/{}/{}",
+            crate_name,
+            version.unwrap_or("latest"),
+            crate_name.replace('-', "_"),
+            module_path
+        );
+
+        let item_types = ["", "struct", "enum", "trait", "fn", "macro"];
+        let mut last_error = None;
+
+        for item_type in item_types.iter() {
+            let file = if item_type.is_empty() {
+                format!("{item_name}/index.html")
+            } else {
+                format!("{item_type}.{item_name}.html")
+            };
+
+            match self.fetch_html(&format!("{}{}", root, file)).await {
+                Ok(html) => return Ok(html),
+                Err(e) => last_error = Some(e.to_string()),
+            }
+        }
+
+        Err(ToolError::ExecutionError(format!(
+            "Failed to fetch item documentation. No matching item found. Last error: {}",
+            last_error.unwrap_or_else(|| "Unknown error".to_string())
+        )))
+    }
+
+    /// Fetches the documentation of a crate, or of an item in it, from
+    /// docs.rs, strips the rustdoc chrome and summarizes it. Results are
+    /// cached by `crate@version` and item path. Returns the documentation
+    /// and whether it was summarized.
+    pub async fn crate_doc(
+        &self,
+        crate_name: &str,
+        item_path: Option<&str>,
+        version: Option<&str>,
+    ) -> Result<(String, bool), ToolError> {
+        // Strip crate name prefix from the item path if it exists
+        let crate_prefix = format!("{}::", crate_name.replace('-', "_"));
+        let item_path = item_path
+            .map(|path| path.trim())
+            .map(|path| path.strip_prefix(&crate_prefix).unwrap_or(path))
+            .filter(|path| !path.is_empty());
+
+        let cache_key = DocCache::key(crate_name, version, item_path);
+        let summarizer = self.summarizer();
+        let summarized = summarizer.is_some();
+
+        if let Some(doc) = self.cache.get(&cache_key).await {
+            return Ok((doc, summarized));
+        }
+
+        let html = match item_path {
+            Some(item_path) => self.fetch_item_html(crate_name, item_path, version).await?,
+            None => {
+                self.fetch_html(&format!(
+                    "https://docs.rs/{}/{}/{}/index.html",
+                    crate_name,
+                    version.unwrap_or("latest"),
+                    crate_name.replace('-', "_")
+                ))
+                .await?
+            }
+        };
+
+        let markdown = context::truncate_middle(&page_markdown(&html), MAX_DOC_TOKENS);
+
+        let doc = match &summarizer {
+            Some(summarizer) => {
+                summarizer
+                    .summarize_docs_with_ai(crate_name, &markdown)
+                    .await?
+            }
+            None => markdown,
+        };
+
+        // Documentation of a pinned version does not change
+        let ttl = cache::ttl();
+        let ttl = if ttl.is_zero() || version.is_none_or(|version| version == "latest") {
+            ttl
+        } else {
+            cache::PINNED_TTL.max(ttl)
+        };
+        self.cache.set_with_ttl(cache_key, doc.clone(), ttl).await;
+
+        Ok((doc, summarized))
+    }
+
+    /// Looks up an item or module of a crate on docs.rs and returns the first
+    /// sentence of its documentation, without summarizing it. Fails if the
+    /// item does not exist.
+    pub async fn item_synopsis(
+        &self,
+        crate_name: &str,
+        item_path: &str,
+        version: Option<&str>,
+    ) -> Result<String, ToolError> {
+        let crate_prefix = format!("{}::", crate_name.replace('-', "_"));
+        let item_path = item_path.strip_prefix(&crate_prefix).unwrap_or(item_path);
+        let cache_key = format!(
+            "{}#synopsis",
+            DocCache::key(crate_name, version, Some(item_path))
+        );
+
+        if let Some(synopsis) = self.cache.get(&cache_key).await {
+            return Ok(synopsis);
+        }
+
+        let html = self.fetch_item_html(crate_name, item_path, version).await?;
+        let synopsis = extract::synopsis(&html);
+
+        self.cache.set(cache_key, synopsis.clone()).await;
+
+        Ok(synopsis)
+    }
+
     // Fetch crate documentation from docs.rs
     async fn lookup_crate(
         &self,
@@ -91,29 +348,7 @@ impl DocRouter {
         };
 
         // Fetch the documentation page
-        let response = self
-            .client
-            .get(&url)
-            .header(
-                "User-Agent",
-                "CrateDocs/0.1.0 (https://github.com/d6e/cratedocs-mcp)",
-            )
-            .send()
-            .await
-            .map_err(|e| {
-                ToolError::ExecutionError(format!("Failed to fetch documentation: {}", e))
-            })?;
-
-        if !response.status().is_success() {
-            return Err(ToolError::ExecutionError(format!(
-                "Failed to fetch documentation. Status: {}",
-                response.status()
-            )));
-        }
-
-        let html_body = response.text().await.map_err(|e| {
-            ToolError::ExecutionError(format!("Failed to read response body: {}", e))
-        })?;
+        let html_body = self.fetch_html(&url).await?;
 
         // Convert HTML to markdown
         let markdown_body = parse_html(&html_body);
@@ -189,107 +424,24 @@ impl DocRouter {
             return Ok(doc);
         }
 
-        // Process the item path to determine the item type
-        // Format: module::path::ItemName
-        // Need to split into module path and item name, and guess item type
-        let parts: Vec<&str> = item_path.split("::").collect();
-
-        if parts.is_empty() {
-            return Err(ToolError::InvalidParameters(
-                "Invalid item path. Expected format: module::path::ItemName".to_string(),
-            ));
-        }
-
-        let item_name = parts.last().unwrap().to_string();
-        let module_path = if parts.len() > 1 {
-            parts[..parts.len() - 1].join("/")
-        } else {
-            String::new()
-        };
+        let html_body = self
+            .fetch_item_html(&crate_name, &item_path, version.as_deref())
+            .await?;
 
-        // Try different item types (struct, enum, trait, fn)
-        let item_types = ["", "struct", "enum", "trait", "fn", "macro"];
-        let mut last_error = None;
+        // Keep the signatures, doc comments and examples of the page
+        let markdown_body = page_markdown(&html_body);
 
-        for item_type in item_types.iter() {
-            let file = if item_type.is_empty() {
-                format!("{item_name}/index.html")
-            } else {
-                format!("{item_type}.{item_name}.html")
-            };
-
-            // Construct the docs.rs URL for the specific item
-            let url = if let Some(ver) = version.clone() {
-                if module_path.is_empty() {
-                    format!(
-                        "https://docs.rs/{}/{}/{}/{file}",
-                        crate_name, ver, crate_name
-                    )
-                } else {
-                    format!(
-                        "https://docs.rs/{}/{}/{}/{}/{file}",
-                        crate_name, ver, crate_name, module_path
-                    )
-                }
-            } else if module_path.is_empty() {
-                format!(
-                    "https://docs.rs/{}/latest/{}/{file}",
-                    crate_name, crate_name
-                )
-            } else {
-                format!(
-                    "https://docs.rs/{}/latest/{}/{}/{file}",
-                    crate_name, crate_name, module_path
-                )
-            };
-
-            // Try to fetch the documentation page
-            let response = match self
-                .client
-                .get(&url)
-                .header(
-                    "User-Agent",
-                    "CrateDocs/0.1.0 (https://github.com/d6e/cratedocs-mcp)",
-                )
-                .send()
-                .await
-            {
-                Ok(resp) => resp,
-                Err(e) => {
-                    last_error = Some(e.to_string());
-                    continue;
-                }
-            };
-
-            // If found, process and return
-            if response.status().is_success() {
-                let html_body = response.text().await.map_err(|e| {
-                    ToolError::ExecutionError(format!("Failed to read response body: {}", e))
-                })?;
-
-                // Convert HTML to markdown
-                let markdown_body = parse_html(&html_body);
-
-                // Cache the markdown result
-                self.cache.set(cache_key, markdown_body.clone()).await;
+        // Cache the markdown result
+        self.cache.set(cache_key, markdown_body.clone()).await;
 
-                let result_text = self
-                    .summarizer
+        match self.summarizer() {
+            Some(summarizer) => {
+                summarizer
                     .summarize_docs_with_ai(&crate_name, &markdown_body)
                     .await
-                    .unwrap();
-
-                return Ok(result_text);
             }
-
-            last_error = Some(format!("Status code: {}", response.status()));
+            None => Ok(markdown_body),
         }
-
-        // If we got here, none of the item types worked
-        Err(ToolError::ExecutionError(format!(
-            "Failed to fetch item documentation. No matching item found. Last error: {}",
-            last_error.unwrap_or_else(|| "Unknown error".to_string())
-        )))
     }
 }
 
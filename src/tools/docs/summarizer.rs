@@ -1,5 +1,8 @@
-use std::env::{self, VarError};
-
+use crate::game_design::{
+    cache, config,
+    designer_llm::{self, seed},
+    prompts,
+};
 use mcp_core::ToolError;
 use serde::Deserialize;
 
@@ -24,9 +27,9 @@ pub struct AiSummarizer {
 }
 
 impl AiSummarizer {
-    pub fn new() -> Result<Self, VarError> {
+    pub fn new() -> anyhow::Result<Self> {
         Ok(Self {
-            api_key: env::var("OPENROUTER_API_KEY")?,
+            api_key: designer_llm::api_key(&config::current())?,
         })
     }
 
@@ -35,6 +38,28 @@ impl AiSummarizer {
         crate_name: &str,
         text: &str,
     ) -> Result<String, ToolError> {
+        let request = serde_json::json!({
+            "model": "tngtech/deepseek-r1t2-chimera:free",
+            "messages": [
+                {
+                    "role": "system",
+                    "content": prompts::system_prompt(prompts::DOCS_SUMMARIZER)
+                },
+                {
+                    "role": "user",
+                    "content": format!("Please analyze the following Rust crate documentation for the crate `{crate_name}` and provide a structured overview as requested:\n\n{}", text)
+                }
+            ],
+            // Low temperature for factual output, pinned to 0 in deterministic mode
+            "temperature": if seed().is_some() { 0.0 } else { 0.2 },
+            "max_tokens": 10_000,
+            "seed": seed(),
+        });
+
+        if let Some(summary) = cache::lookup(&request) {
+            return Ok(summary);
+        }
+
         let response = reqwest::Client::new()
             .post("https://openrouter.ai/api/v1/chat/completions")
             .header("Authorization", format!("Bearer {}", self.api_key))
@@ -42,36 +67,10 @@ impl AiSummarizer {
             // OpenRouter requires this header to identify your application
             .header("HTTP-Referer", "cratedoc") // Replace with your URL or app name
             .header("X-Title", "Rust Docs MCP Server") // Replace with your app name
-            .json(&serde_json::json!({
-                "model": "tngtech/deepseek-r1t2-chimera:free",
-                "messages": [
-                    {
-                        "role": "system",
-                        "content": r"You are an expert technical writer for Rust documentation. Your task is to create a detailed yet structured overview of the provided crate documentation.
-
-Please organize your summary into the following sections. You MUST use markdown formatting for readability:
-
-1.  **Overview:** A 1-2 sentence description of the crate's purpose.
-2.  **Key Features:** A bulleted list of its main features and capabilities.
-3.  **Core API:** Describe the most important structs, enums, traits, and functions. Focus on what a user would need to know to get started.
-4.  **Usage Example:** Provide a simple, practical code example showing how to use the crate in a common scenario.
-5.  **Notable Considerations:** Mention any important details like safety warnings, async support, common dependencies, or configuration needs.
-
-Be comprehensive but avoid simply listing everything. Curate the information for maximum usefulness to a Rust developer."
-                    },
-                    {
-                        "role": "user",
-                        "content": format!("Please analyze the following Rust crate documentation for the crate `{crate_name}` and provide a structured overview as requested:\n\n{}", text)
-                    }
-                ],
-                "temperature": 0.2, // Low temperature for factual, deterministic output
-                "max_tokens": 10_000,
-            }))
+            .json(&request)
             .send()
             .await
-            .map_err(|e| {
-                ToolError::ExecutionError(format!("Failed to summarize input: {e}"))
-            })?;
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to summarize input: {e}")))?;
 
         // 5. Check for errors and parse the response
         if !response.status().is_success() {
@@ -91,7 +90,9 @@ Be comprehensive but avoid simply listing everything. Curate the information for
 
         // 6. Extract the summary text
         if let Some(first_choice) = api_response.choices.first() {
-            Ok(first_choice.message.content.trim().to_string())
+            let summary = first_choice.message.content.trim().to_string();
+            cache::store(&request, &summary);
+            Ok(summary)
         } else {
             Err(ToolError::ExecutionError(
                 "No choices returned from OpenRouter API".to_owned(),
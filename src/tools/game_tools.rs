@@ -1,207 +1,597 @@
 //! Implements the MCP tools for interacting with the Game Designer.
 
-use crate::game_design::{DesignerLlmClient, SessionManager};
+use crate::{
+    error::{self, GameDesignerError, tool_error},
+    game_design::{
+        DesignEditOutcome, DesignerLlmClient, Persona, ReuseOutcome, ReviewSubmission,
+        SessionManager, SessionSetup, UndoOutcome,
+        assets::AssetKind,
+        attachments::{self, Upload},
+        board, brainstorm,
+        config::{self, Config},
+        context, conventions,
+        debt::{DebtPriority, DebtStatus},
+        designer_llm::{self, Sampling},
+        diff,
+        document::{self, Document},
+        economy::{Economy, Flow, ProgressionCurve, Resource},
+        engine::{self, ApiReference, Engine},
+        events::EventFilter,
+        followups, git,
+        interview::InterviewStep,
+        level::LevelStatus,
+        locks::LockSnapshot,
+        monetization::MonetizationPolicy,
+        narrative, paths, project, prompts, redact,
+        residency::ProjectResidency,
+        review_depth::ReviewDepth,
+        scope, session,
+        state::{Beat, Character, FeatureStatus, ProjectMetadata, SessionState},
+        templates,
+        trackers::{self, TrackerIssue},
+    },
+    tools::{
+        aliases,
+        annotations::{self, ToolAnnotations},
+        cancellation::{CallHandle, Cancellations, TimeoutConfig},
+        docs::DocRouter,
+        guard::{GuardConfig, LoopGuard},
+        jobs::{JobStatus, JobStore},
+        limits::{self, Shortened},
+        output::{self, OutputFormat, ToolOutput},
+        pagination::paginate,
+        params::{
+            self, AssetListFormat, AssetListParams, AttachmentParams, BacklogImportParams,
+            BeatAddParams, BoardSyncParams, BoardTarget, CharacterSheetParams, CommitMessageParams,
+            ConventionsSetParams, CrateDocParams, DebtListParams, DesignBrainstormParams,
+            DesignDiffParams, DesignEngineParams, DesignExportParams, DesignFreezeParams,
+            DesignImportParams, DesignInterviewParams, DesignNewParams, DesignPersonaParams,
+            DesignRetroParams, DesignReviewRequestParams, DesignSearchParams, DesignUndoParams,
+            DesignUnfreezeParams, DevlogGenerateParams, DiffFormat, EconomyFlowParams,
+            EconomyModelParams, FeatureAskParams, FeatureListParams, FeatureRejectParams,
+            FeatureReviewParams, FeatureSelectParams, HumanApproveParams, JobParams,
+            LevelBriefParams, MetadataParams, MonetizationReviewParams, NextFeatureParams,
+            NoParams, QaHistoryParams, ReuseFromSessionParams, ReviewReplyParams,
+            ScopeReportParams, SessionConfigureParams, SessionEventsParams, SessionHistoryParams,
+            SessionListParams, SessionParams, StatusReportParams, StoryOutlineParams,
+            TestPlanParams, TraceabilityReportParams, TrackerSource, UiCritiqueParams, WorkParams,
+            parse_params,
+        },
+        queue::{LLM_REQUIRED_TOOLS, LLM_TOOLS, LlmQueue, QueueConfig},
+        quota::TokenLedger,
+    },
+    transport,
+};
 use anyhow::Result;
-use mcp_core::{Content, Resource, Tool, handler::ToolError, protocol::ServerCapabilities};
-use mcp_server::{Router, router::CapabilitiesBuilder};
+use mcp_core::{
+    Content, Resource, Tool,
+    handler::ToolError,
+    protocol::{
+        CallToolResult, Implementation, InitializeResult, JsonRpcRequest, JsonRpcResponse,
+        ServerCapabilities,
+    },
+};
+use mcp_server::{Router, RouterError, router::CapabilitiesBuilder};
 use serde_json::{Value, json};
-use std::{pin::Pin, sync::Arc};
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Instant,
+};
 use tokio::sync::Mutex;
+use tracing::Instrument;
 
-/// The main router for game design tools.
+/// State shared by every router instance: the session manager, the LLM
+/// client and the circuit breakers.
+///
+/// Construct it once at startup and hand clones to each transport
+/// connection, so that all connections observe the same in-memory sessions.
 #[derive(Clone)]
-pub struct GameToolsRouter {
-    // We'll need access to the session manager and LLM client
-    session_manager: Arc<Mutex<SessionManager>>,
-    // Make LLM client optional
-    // TODO: Add any other necessary state or configuration
-    #[allow(dead_code)]
-    llm_client: Arc<Option<DesignerLlmClient>>,
+pub struct GameDesignerService {
+    // Sessions without a project
+    session_manager: Arc<SessionManager>,
+    // Session managers of the projects, opened on first use
+    projects: Arc<Mutex<HashMap<String, Arc<SessionManager>>>>,
+    // Make LLM client optional; replaced when a reload changes the credentials
+    llm_client: Arc<RwLock<Option<Arc<DesignerLlmClient>>>>,
+    // Per-session circuit breakers
+    guard: Arc<LoopGuard>,
+    // Limits concurrent LLM-backed tool calls
+    llm_queue: Arc<LlmQueue>,
+    // LLM tokens consumed per session today
+    tokens: Arc<TokenLedger>,
+    // Tool calls running in the background and their results
+    jobs: Arc<JobStore>,
+    // Fetches and summarizes crate documentation from docs.rs
+    docs: Arc<DocRouter>,
 }
 
-impl GameToolsRouter {
-    /// Creates a new `GameToolsRouter`.
+impl GameDesignerService {
+    /// Creates the shared service. Fails if the data directory can't be
+    /// created.
     pub fn new() -> Result<Self> {
-        let session_manager = Arc::new(Mutex::new(SessionManager::new()));
         // Try to create the LLM client, but don't fail if the API key is missing
         // It will only be required for tools that actually need the LLM
         let llm_client = match DesignerLlmClient::new() {
-            Ok(client) => Arc::new(Some(client)),
-            Err(_) => Arc::new(None), // LLM client is not available
+            Ok(client) => Some(client),
+            // LLM client is not available
+            Err(e) => {
+                tracing::warn!(
+                    "Running without an LLM provider, {} tools are unavailable and {} degraded: {}",
+                    LLM_REQUIRED_TOOLS.len(),
+                    LLM_TOOLS.len() - LLM_REQUIRED_TOOLS.len(),
+                    e
+                );
+                designer_llm::set_unavailable_reason(Some(e.to_string()));
+                None
+            }
         };
 
-        Ok(Self {
-            session_manager,
-            llm_client,
-        })
+        Ok(Self::with(SessionManager::new()?, llm_client))
     }
-}
 
-impl Router for GameToolsRouter {
-    fn name(&self) -> String {
-        "game-designer".to_owned()
+    /// Creates the shared service on top of `session_manager` and
+    /// `llm_client`, e.g. sessions in a temporary directory and the mock
+    /// provider in tests.
+    pub fn with(session_manager: SessionManager, llm_client: Option<DesignerLlmClient>) -> Self {
+        let tokens = TokenLedger::in_data_dir(session_manager.clock());
+
+        Self {
+            session_manager: Arc::new(session_manager),
+            projects: Arc::default(),
+            llm_client: Arc::new(RwLock::new(llm_client.map(Arc::new))),
+            guard: Arc::new(LoopGuard::default()),
+            llm_queue: Arc::new(LlmQueue::default()),
+            tokens: Arc::new(tokens),
+            jobs: Arc::new(JobStore::default()),
+            docs: Arc::new(DocRouter::new()),
+        }
     }
 
-    fn instructions(&self) -> String {
-        "This server provides tools for managing a game design process. \
-        You can create design sessions, get an overview, receive the next feature to implement, \
-        submit a review of implemented features, reply to questions from the review, \
-        and ask ad-hoc questions about the current feature or design."
-            .to_owned()
+    /// Re-reads the configuration files, the API key and `webhooks.json` and
+    /// applies them without dropping connections: model and budgets are
+    /// resolved per call, and the LLM client, the queue and circuit breaker
+    /// limits and the webhook endpoints are replaced. Calls in progress
+    /// finish with the settings they started with.
+    ///
+    /// Everything is read before anything is applied, so a failed reload
+    /// keeps the active settings. Changed settings are logged. Returns a
+    /// summary of the active settings.
+    pub async fn reload(&self) -> Result<String> {
+        let config = Config::load()?;
+
+        // Losing a working provider to a bad key file would break every
+        // LLM-backed tool, so that fails the reload
+        let previous = self.llm();
+        let client = match DesignerLlmClient::from_config(&config) {
+            Err(e) if previous.is_some() => {
+                anyhow::bail!("failed to set up the LLM provider: {}", e)
+            }
+            client => client,
+        };
+
+        let endpoints = self.session_manager.reload_webhooks()?;
+
+        let previous_config = config::replace(config);
+        let config = config::current();
+        for change in config::changes(&previous_config, &config) {
+            tracing::info!("Configuration changed: {}", change);
+        }
+
+        match client {
+            Ok(client) => match previous.as_deref() {
+                Some(previous) if previous.same_credentials(&client) => {}
+                previous => {
+                    match previous {
+                        Some(previous) if previous.provider_name() == client.provider_name() => {
+                            tracing::info!(
+                                "Credentials of the {} provider rotated",
+                                client.provider_name()
+                            )
+                        }
+                        Some(previous) => tracing::info!(
+                            "LLM provider changed from {} to {}",
+                            previous.provider_name(),
+                            client.provider_name()
+                        ),
+                        None => tracing::info!("LLM provider {} enabled", client.provider_name()),
+                    }
+                    designer_llm::set_unavailable_reason(None);
+                    *self.llm_client.write().unwrap() = Some(Arc::new(client));
+                }
+            },
+            Err(e) => designer_llm::set_unavailable_reason(Some(e.to_string())),
+        }
+
+        let queue = QueueConfig::from_env();
+        if queue != self.llm_queue.config() {
+            tracing::info!(
+                "LLM queue changed to {} concurrent and {} waiting calls",
+                queue.concurrency,
+                queue.capacity
+            );
+            self.llm_queue.set_config(queue);
+        }
+        self.guard.set_config(GuardConfig::from_env());
+
+        let summary = format!(
+            "Configuration reloaded: provider {}, model '{}', prompt budget {} tokens, \
+             effort budget {}, default persona '{}', {} concurrent LLM calls, \
+             {} prompt override(s), {} webhook endpoint(s).",
+            self.llm().map_or("none", |client| client.provider_name()),
+            DesignerLlmClient::model(),
+            context::prompt_budget(),
+            scope::default_budget().map_or_else(|| "unset".to_string(), |b| b.to_string()),
+            config.default_persona.unwrap_or_default().name(),
+            queue.concurrency,
+            prompts::overrides().len(),
+            endpoints
+        );
+        tracing::info!("{}", summary);
+
+        Ok(summary)
     }
 
-    fn capabilities(&self) -> ServerCapabilities {
-        CapabilitiesBuilder::new()
-            .with_tools(true)
-            .with_resources(false, false)
-            .with_prompts(false)
-            .build()
+    /// Returns the session manager of `project`, or of the sessions without
+    /// a project.
+    pub async fn sessions(&self, project: Option<&str>) -> Result<Arc<SessionManager>> {
+        let Some(project) = project else {
+            return Ok(self.session_manager.clone());
+        };
+
+        if let Some(manager) = self.projects.lock().await.get(project) {
+            return Ok(manager.clone());
+        }
+
+        // Opening creates the project's directory, so it happens without
+        // holding up the calls of other projects; of two calls opening the
+        // same project, the first to finish wins
+        let manager = Arc::new(self.session_manager.for_project(project)?);
+        let mut projects = self.projects.lock().await;
+
+        Ok(projects
+            .entry(project.to_string())
+            .or_insert(manager)
+            .clone())
     }
 
-    fn list_tools(&self) -> Vec<Tool> {
-        vec![
-            Tool::new(
-                "designNew".to_string(),
-                "Create a new game design session with a provided description.".to_string(),
-                json!({
-                    "type": "object",
-                    "properties": {
-                        "sessionName": {
-                            "type": "string",
-                            "description": "Unique identifier for the design session"
-                        },
-                        "gameDescription": {
-                            "type": "string",
-                            "description": "Initial description of the game to be designed"
-                        }
-                    },
-                    "required": ["sessionName", "gameDescription"]
-                }),
-            ),
-            Tool::new(
-                "designOverview".to_string(),
-                "Get the initial game design goals for a session.".to_string(),
-                json!({
-                    "type": "object",
-                    "properties": {
-                        "sessionName": {
-                            "type": "string",
-                            "description": "Unique identifier for the design session"
-                        }
-                    },
-                    "required": ["sessionName"]
-                }),
-            ),
-            Tool::new(
-                "nextFeature".to_string(),
-                "Get the detailed specification for the next feature to implement.".to_string(),
-                json!({
-                    "type": "object",
-                    "properties": {
-                        "sessionName": {
-                            "type": "string",
-                            "description": "Unique identifier for the design session"
-                        }
-                    },
-                    "required": ["sessionName"]
-                }),
-            ),
-            Tool::new(
-                "featureReview".to_string(),
-                "Submit a comprehensive report of changes made for review by the designer LLM."
-                    .to_string(),
-                json!({
-                    "type": "object",
-                    "properties": {
-                        "sessionName": {
-                            "type": "string",
-                            "description": "Unique identifier for the design session"
-                        },
-                        "changesMade": {
-                            "type": "string",
-                            "description": "A detailed report of the changes implemented, potentially including code snippets."
-                        }
-                    },
-                    "required": ["sessionName", "changesMade"]
-                }),
-            ),
-            Tool::new(
-                "reviewReply".to_string(),
-                "Reply to questions raised by the designer LLM during a feature review."
-                    .to_string(),
-                json!({
-                    "type": "object",
-                    "properties": {
-                        "sessionName": {
-                            "type": "string",
-                            "description": "Unique identifier for the design session"
-                        },
-                        "content": {
-                            "type": "string",
-                            "description": "The answer or information provided in response to the LLM's questions."
-                        }
-                    },
-                    "required": ["sessionName", "content"]
-                }),
-            ),
-            Tool::new(
-                "featureAsk".to_string(),
-                "Ask an ad-hoc question about the current feature or design.".to_string(),
-                json!({
-                    "type": "object",
-                    "properties": {
-                        "sessionName": {
-                            "type": "string",
-                            "description": "Unique identifier for the design session"
-                        },
-                        "question": {
-                            "type": "string",
-                            "description": "The question to ask the designer LLM."
-                        }
-                    },
-                    "required": ["sessionName", "question"]
-                }),
-            ),
-        ]
+    /// Describes the sessions each session manager keeps in memory, the
+    /// sessions without a project first.
+    pub async fn residency(&self) -> Vec<ProjectResidency> {
+        let projects = {
+            let projects = self.projects.lock().await;
+            let mut projects = projects
+                .iter()
+                .map(|(project, manager)| (project.clone(), manager.clone()))
+                .collect::<Vec<_>>();
+            projects.sort_by(|(a, _), (b, _)| a.cmp(b));
+            projects
+        };
+
+        let mut residency = vec![ProjectResidency {
+            project: None,
+            residency: self.session_manager.residency(),
+        }];
+        for (project, manager) in projects {
+            residency.push(ProjectResidency {
+                project: Some(project),
+                residency: manager.residency(),
+            });
+        }
+
+        residency
     }
 
-    fn call_tool(
+    /// How long tool calls waited for the session locks so far.
+    pub fn lock_stats(&self) -> LockSnapshot {
+        self.session_manager.lock_stats()
+    }
+
+    /// Returns the LLM tokens a session consumed today, which the calls of
+    /// servers in other processes count towards too.
+    pub fn tokens_today(&self, project: Option<&str>, session_name: &str) -> u64 {
+        self.tokens.used_today(&session_key(project, session_name))
+    }
+
+    /// The LLM client, if a provider is configured.
+    pub fn llm(&self) -> Option<Arc<DesignerLlmClient>> {
+        self.llm_client.read().unwrap().clone()
+    }
+
+    /// Whether an LLM provider is configured.
+    pub fn has_llm(&self) -> bool {
+        self.llm_client.read().unwrap().is_some()
+    }
+
+    /// Checks that the LLM provider is configured and reachable.
+    pub async fn ping_llm(&self) -> Result<()> {
+        match self.llm() {
+            Some(client) => client.ping().await,
+            None => Err(GameDesignerError::LlmUnavailable.into()),
+        }
+    }
+}
+
+/// The main router for game design tools.
+#[derive(Clone)]
+pub struct GameToolsRouter {
+    service: GameDesignerService,
+    // Tool calls of this connection that the client may cancel
+    cancellations: Cancellations,
+}
+
+impl GameToolsRouter {
+    /// Creates a router on top of the shared service.
+    pub fn new(service: GameDesignerService) -> Self {
+        Self {
+            service,
+            cancellations: Cancellations::default(),
+        }
+    }
+
+    /// Returns the registry of cancellable calls, to be fed by the
+    /// connection's transport with [`crate::transport::cancellation::watch`].
+    pub fn cancellations(&self) -> Cancellations {
+        self.cancellations.clone()
+    }
+
+    /// Checks the arguments against the advertised schema of the tool, so
+    /// that every offending field is reported at once.
+    fn check_arguments(&self, tool_name: &str, arguments: &Value) -> Result<(), ToolError> {
+        let Some(tool) = self
+            .list_tools()
+            .into_iter()
+            .find(|tool| tool.name == tool_name)
+        else {
+            return Ok(());
+        };
+
+        let errors = params::validate(&tool.input_schema, arguments);
+        if errors.is_empty() {
+            return Ok(());
+        }
+
+        Err(ToolError::InvalidParameters(format!(
+            "Invalid arguments for {}: {}",
+            tool_name,
+            errors.join("; ")
+        )))
+    }
+
+    /// Starts the call as a background job and answers with the job's id
+    /// right away.
+    fn start_job(
         &self,
         tool_name: &str,
         arguments: Value,
     ) -> Pin<Box<dyn futures::Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>>
     {
-        // This is an async function signature, so we need to box a future.
-        // We'll implement the logic for each tool call here.
-        // For now, we'll return a stub response or an error indicating it's not yet implemented.
+        let checked = self.check_arguments(tool_name, &arguments);
+        let tool_name = tool_name.to_string();
+        let this = self.clone();
+
+        Box::pin(async move {
+            checked.map_err(error::normalize)?;
+
+            let output_format = OutputFormat::from_arguments(&arguments);
+            let argument = |name: &str| {
+                arguments
+                    .get(name)
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+            };
+
+            let jobs = this.service.jobs.clone();
+            let job = jobs.start(&tool_name, argument("project"), argument("sessionName"));
 
+            // Jobs outlive the request, so the client can't cancel them
+            let run = this.run_tool(&tool_name, arguments, CallHandle::detached());
+            let id = job.id.clone();
+            tokio::spawn(async move {
+                let result = run.await;
+                jobs.finish(&id, result.map_err(|e| e.to_string()));
+            });
+
+            Ok(ToolOutput::new(
+                format!(
+                    "Started job {} running {}. Poll jobStatus with this jobId and fetch the \
+                     output with jobResult once it has succeeded.",
+                    job.id, tool_name
+                ),
+                json!({ "jobId": job.id, "tool": tool_name, "status": job.status }),
+            )
+            .into_content(output_format))
+        })
+    }
+
+    /// Runs a tool call. `handle` cancels it when the client gives up on it.
+    fn run_tool(
+        &self,
+        tool_name: &str,
+        arguments: Value,
+        handle: CallHandle,
+    ) -> Pin<Box<dyn futures::Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>>
+    {
         let tool_name = tool_name.to_string();
         let arguments = arguments.clone();
         let this = self.clone(); // Clone the Arc references
 
-        Box::pin(async move {
-            match tool_name.as_str() {
+        // Every log line of the call carries these fields, so that the lines
+        // of one call can be correlated in the log store
+        let span = tracing::info_span!(
+            "tool_call",
+            tool = %tool_name,
+            session_id = arguments
+                .get("sessionName")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default(),
+            call_id = %format!("{:016x}", rand::random::<u64>()),
+        );
+
+        let checked = self.check_arguments(&tool_name, &arguments);
+        let timeout = TimeoutConfig::from_env().for_tool(&tool_name);
+
+        // Resolved before the call runs, so that the tokens it consumes are
+        // charged to its session even if it is aborted
+        let project = match arguments.get("project").and_then(|v| v.as_str()) {
+            Some(project) => {
+                project::normalize(project).map_err(|e| ToolError::InvalidParameters(e.to_string()))
+            }
+            None => Ok(project::default_project().map(str::to_string)),
+        };
+        let key = match (
+            &project,
+            arguments.get("sessionName").and_then(|v| v.as_str()),
+        ) {
+            (Ok(project), Some(session_name)) => {
+                Some(session_key(project.as_deref(), session_name))
+            }
+            _ => None,
+        };
+        let charged_key = key.clone();
+        let ledger = self.service.tokens.clone();
+        let meter = Arc::new(AtomicU64::new(0));
+        let sampling = sampling_overrides(&arguments);
+
+        let metric_tool = tool_name.clone();
+        let call = async move {
+            checked?;
+
+            // Taken once, so that a reload during the call doesn't switch
+            // credentials halfway
+            let llm_client = this.service.llm();
+
+            // Fail with the setup instructions before anything is loaded
+            if LLM_REQUIRED_TOOLS.contains(&tool_name.as_str()) && llm_client.is_none() {
+                return Err(GameDesignerError::LlmUnavailable.into());
+            }
+
+            let project = project?;
+
+            if let Some(key) = &key {
+                this.service.guard.check(key, &tool_name)?;
+
+                // Only tools that reach the LLM spend tokens
+                if LLM_TOOLS.contains(&tool_name.as_str()) {
+                    let budget = this.service.guard.config().daily_token_budget;
+                    this.service.tokens.check(key, budget)?;
+                }
+            }
+
+            let session_manager = this
+                .service
+                .sessions(project.as_deref())
+                .await
+                .map_err(|e| tool_error("open project", e))?;
+
+            // Session names become file names, so none may leave the data
+            // directory, whichever tool they are given to
+            for name in ["sessionName", "sourceSession", "otherSession"] {
+                if let Some(session_name) = arguments.get(name).and_then(Value::as_str) {
+                    session::check_session_id(session_name)
+                        .map_err(|e| tool_error("find session", e))?;
+                }
+            }
+
+            // Frozen sessions only serve read tools and exports
+            if !annotations::allowed_when_frozen(&tool_name)
+                && let Some(session_name) = arguments.get("sessionName").and_then(Value::as_str)
+            {
+                session_manager
+                    .ensure_not_frozen(session_name)
+                    .await
+                    .map_err(|e| tool_error("change session", e))?;
+            }
+
+            // Held until the call completes; without a provider these tools
+            // don't reach the LLM and need no slot
+            let _ticket = if LLM_TOOLS.contains(&tool_name.as_str()) && llm_client.is_some() {
+                Some(
+                    this.service
+                        .llm_queue
+                        .enter()
+                        .await
+                        .map_err(GameDesignerError::from)?,
+                )
+            } else {
+                None
+            };
+
+            let output_format = OutputFormat::from_arguments(&arguments);
+
+            // Secrets pasted into a submission are neither stored nor sent
+            let mut arguments = arguments;
+            let redactions = redact_arguments(&tool_name, &mut arguments);
+            // Oversized arguments are shortened with a warning, not rejected
+            let shortened =
+                limits::enforce(&tool_name, &mut arguments, llm_client.as_deref()).await;
+
+            let output = match tool_name.as_str() {
                 "designNew" => {
-                    let session_name = arguments
-                        .get("sessionName")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| {
-                            ToolError::InvalidParameters(
-                                "sessionName is required for designNew".to_string(),
-                            )
-                        })?;
-                    let game_description = arguments
-                        .get("gameDescription")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| {
-                            ToolError::InvalidParameters(
-                                "gameDescription is required for designNew".to_string(),
-                            )
-                        })?;
+                    let params: DesignNewParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    // Validate the repository before anything is created
+                    let repo = match params.repo.as_deref() {
+                        Some(path) => Some(
+                            git::toplevel(std::path::Path::new(path))
+                                .await
+                                .map_err(|e| {
+                                    ToolError::InvalidParameters(format!(
+                                        "repo is not a git repository: {}",
+                                        e
+                                    ))
+                                })?,
+                        ),
+                        None => None,
+                    };
+
+                    let markdown = match (params.document, params.document_path.as_deref()) {
+                        (Some(_), Some(_)) => {
+                            return Err(ToolError::InvalidParameters(
+                                "Pass either document or documentPath, not both".to_string(),
+                            ));
+                        }
+                        (Some(markdown), None) => Some(markdown),
+                        (None, Some(path)) => Some(read_document(path, repo.as_deref())?),
+                        (None, None) => None,
+                    };
+                    let document = markdown.as_deref().map(Document::parse);
+
+                    let game_description = match (&params.game_description, &markdown) {
+                        (Some(description), _) => description.clone(),
+                        (None, Some(markdown)) => markdown.clone(),
+                        (None, None) => {
+                            return Err(ToolError::InvalidParameters(
+                                "gameDescription is required unless document or documentPath \
+                                 is given"
+                                    .to_string(),
+                            ));
+                        }
+                    };
+                    let game_description = game_description.as_str();
+
+                    let persona = parse_persona(params.persona.as_deref())?
+                        .or(config::current().default_persona)
+                        .unwrap_or_default();
+
+                    let engine = parse_engine(params.engine.as_deref())?;
+                    let language = parse_language(params.language.as_deref());
+                    let metadata = parse_metadata(params.metadata);
+
+                    let template = params
+                        .template
+                        .as_deref()
+                        .map(templates::load)
+                        .transpose()
+                        .map_err(|e| ToolError::InvalidParameters(e.to_string()))?;
 
                     // Check if we have an LLM client available
-                    let comprehensive_description = if let Some(llm_client) =
-                        this.llm_client.as_ref()
+                    let comprehensive_description = if let Some(llm_client) = llm_client.as_deref()
                     {
                         // Ask the LLM to create a comprehensive game design document
                         let prompt = format!(
@@ -219,10 +609,54 @@ Provide detailed but concise information for each section.",
                             game_description
                         );
 
+                        // An imported document is normalized into the same sections
+                        let prompt = match (&document, &markdown) {
+                            (Some(document), Some(markdown)) => format!(
+                                r"Normalize the team's existing game design document into a comprehensive game design document with these sections:
+1. Core Concept
+2. Gameplay Mechanics
+3. Story and Setting
+4. Target Audience
+5. Unique Features
+6. Technical Considerations
+7. Development Milestones
+
+Keep every decision and detail of the original, reorganized under these sections. Where the original says nothing about a section, write 'To be defined' instead of inventing content.{}
+
+Outline of the original:
+{}
+Original document:
+{}",
+                                params
+                                    .game_description
+                                    .as_ref()
+                                    .map(|description| format!(
+                                        "\n\nThe team describes the game as: '{}'.",
+                                        description
+                                    ))
+                                    .unwrap_or_default(),
+                                document.outline(),
+                                markdown
+                            ),
+                            _ => prompt,
+                        };
+
+                        let prompt = match &template {
+                            Some(template) => format!(
+                                "{}\n\nThe game follows this genre template; build on its pillars:\n{}",
+                                prompt,
+                                template.pillars_section()
+                            ),
+                            None => prompt,
+                        };
+
                         let messages = vec![
                             crate::game_design::designer_llm::ChatMessage {
                                 role: "system".to_string(),
-                                content: "You are an expert game designer. Your task is to create detailed and comprehensive game design documents based on brief descriptions.".to_string(),
+                                content: prompts::localized(
+                                    persona.system_prompt(prompts::DESIGN_NEW),
+                                    language.as_deref(),
+                                ),
                             },
                             crate::game_design::designer_llm::ChatMessage {
                                 role: "user".to_string(),
@@ -248,90 +682,243 @@ Provide detailed but concise information for each section.",
                     };
 
                     // Logic to create a new session with the comprehensive description
-                    let session_manager = this.session_manager.lock().await;
+                    let setup = SessionSetup {
+                        language: language.clone(),
+                        document: document
+                            .as_ref()
+                            .zip(markdown.clone())
+                            .map(|(document, markdown)| (markdown, document.pillars())),
+                        metadata,
+                        engine,
+                        repo: repo.clone(),
+                    };
                     session_manager
-                        .create_session(session_name.to_string(), comprehensive_description)
+                        .create_session(
+                            session_name.to_string(),
+                            comprehensive_description,
+                            persona,
+                            template.as_ref(),
+                            setup,
+                        )
                         .await
                         .map_err(|e| {
                             ToolError::ExecutionError(format!("Failed to create session: {}", e))
                         })?;
 
-                    Ok(vec![Content::text(format!(
+                    let mut message = format!(
                         "Session '{}' created successfully with comprehensive game design.",
                         session_name
-                    ))])
+                    );
+                    if let Some(document) = &document {
+                        message.push_str(&format!(
+                            " Imported from the design document ({} sections, {} pillars); \
+                             the original is kept as resource {}.",
+                            document.sections.len(),
+                            document.pillars().len(),
+                            document_uri(project.as_deref(), session_name)
+                        ));
+                    }
+                    if let Some(template) = &template {
+                        message.push_str(&format!(
+                            " Seeded from the '{}' template with {} starter features.",
+                            template.name,
+                            template.starter_features.len()
+                        ));
+                    }
+
+                    Ok(ToolOutput::new(
+                        message,
+                        json!({
+                            "session": session_name,
+                            "persona": persona.name(),
+                            "template": template.as_ref().map(|template| &template.name),
+                            "starterFeatures": template
+                                .as_ref()
+                                .map_or(0, |template| template.starter_features.len()),
+                            "engine": engine.map(Engine::name),
+                            "language": language,
+                            "repo": repo,
+                            "document": document.as_ref().map(|document| json!({
+                                "uri": document_uri(project.as_deref(), session_name),
+                                "sections": document.sections.len(),
+                                "pillars": document.pillars(),
+                            })),
+                        }),
+                    ))
                 }
                 "designOverview" => {
-                    let session_name = arguments
-                        .get("sessionName")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| {
-                            ToolError::InvalidParameters(
-                                "sessionName is required for designOverview".to_string(),
-                            )
-                        })?;
+                    let params: SessionParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
 
                     // Logic to get design overview
-                    let session_manager = this.session_manager.lock().await;
                     if let Some(session) = session_manager
                         .load_session(session_name)
                         .await
-                        .map_err(|e| {
-                            ToolError::ExecutionError(format!("Failed to load session: {}", e))
-                        })?
+                        .map_err(|e| tool_error("load session", e))?
                     {
-                        Ok(vec![Content::text(session.initial_description)])
+                        let mut overview = session.initial_description.clone();
+                        overview.push_str(&author_summary(&session));
+                        Ok(ToolOutput::new(
+                            overview,
+                            json!({
+                                "session": session.id,
+                                "description": session.initial_description,
+                                "persona": session.persona.name(),
+                                "engine": session.engine.map(Engine::name),
+                                "designVersion": session.design_version(),
+                                "nextFeature": session.next_feature_to_implement,
+                                "contributors": contributors(&session),
+                            }),
+                        ))
                     } else {
-                        Err(ToolError::ExecutionError(format!(
-                            "Session '{}' not found.",
-                            session_name
-                        )))
+                        Err(GameDesignerError::SessionNotFound(session_name.to_string()).into())
                     }
                 }
                 "nextFeature" => {
-                    let session_name = arguments
-                        .get("sessionName")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| {
-                            ToolError::InvalidParameters(
-                                "sessionName is required for nextFeature".to_string(),
-                            )
-                        })?;
+                    let params: NextFeatureParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
 
                     // Logic to get the next feature
-                    let session_manager = this.session_manager.lock().await;
-
                     // Get the LLM client reference if available
-                    let llm_client_ref = this.llm_client.as_ref().as_ref();
+                    let llm_client_ref = llm_client.as_deref();
+
+                    if let Some(count) = params.candidates.filter(|&count| count > 1) {
+                        return match session_manager
+                            .propose_candidates(session_name, count, llm_client_ref)
+                            .await
+                        {
+                            Ok(candidates) => {
+                                let mut text = format!(
+                                    "Candidates for the next feature of '{}', best first:\n",
+                                    session_name
+                                );
+                                for candidate in &candidates {
+                                    text.push_str(&format!(
+                                        "\n{}. {}\n{}\n",
+                                        candidate.rank,
+                                        candidate.feature.name,
+                                        candidate.feature.specification()
+                                    ));
+                                    if !candidate.rationale.is_empty() {
+                                        text.push_str(&format!(
+                                            "Rationale: {}\n",
+                                            candidate.rationale
+                                        ));
+                                    }
+                                }
+                                text.push_str(
+                                    "\nPick one with featureSelect, by name or rank. The others \
+                                     will not be proposed again.",
+                                );
+
+                                Ok(ToolOutput::new(
+                                    text,
+                                    json!({
+                                        "candidates": candidates
+                                            .iter()
+                                            .map(|candidate| {
+                                                json!({
+                                                    "rank": candidate.rank,
+                                                    "rationale": candidate.rationale,
+                                                    "feature": output::feature_json(&candidate.feature),
+                                                })
+                                            })
+                                            .collect::<Vec<_>>(),
+                                    }),
+                                ))
+                            }
+                            Err(e) => Err(tool_error("propose feature candidates", e)),
+                        };
+                    }
 
                     match session_manager
                         .get_next_feature(session_name, llm_client_ref)
                         .await
                     {
-                        Ok(feature_description) => Ok(vec![Content::text(feature_description)]),
-                        Err(e) => Err(ToolError::ExecutionError(format!(
-                            "Failed to get next feature: {}",
-                            e
-                        ))),
+                        Ok(specification) => {
+                            let specification = match params.api_references {
+                                Some(true) => attach_api_references(
+                                    &this.service.docs,
+                                    &session_manager,
+                                    session_name,
+                                    llm_client_ref,
+                                )
+                                .await
+                                .unwrap_or(specification),
+                                _ => specification,
+                            };
+                            let feature =
+                                with_session(&session_manager, session_name, next_feature_json)
+                                    .await?;
+                            Ok(ToolOutput::new(
+                                specification,
+                                json!({ "feature": feature }),
+                            ))
+                        }
+                        Err(e) => Err(tool_error("get next feature", e)),
+                    }
+                }
+                "featureSelect" => {
+                    let params: FeatureSelectParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    let llm_client_ref = llm_client.as_deref();
+
+                    match session_manager
+                        .select_candidate(session_name, &params.feature, llm_client_ref)
+                        .await
+                    {
+                        Ok(specification) => {
+                            let feature =
+                                with_session(&session_manager, session_name, next_feature_json)
+                                    .await?;
+                            Ok(ToolOutput::new(
+                                specification,
+                                json!({ "feature": feature }),
+                            ))
+                        }
+                        Err(e) => Err(tool_error("select feature", e)),
+                    }
+                }
+                "featureReject" => {
+                    let params: FeatureRejectParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let author = parse_author(params.author.as_deref());
+
+                    match session_manager
+                        .reject_feature(session_name, params.reason.as_deref(), author)
+                        .await
+                    {
+                        Ok(rejected) => {
+                            let mut text = format!(
+                                "Rejected '{}' and buried it in the idea graveyard of '{}'.",
+                                rejected.feature.name, session_name
+                            );
+                            if let Some(reason) = &rejected.reason {
+                                text.push_str(&format!(" Reason: {}", reason));
+                            }
+                            text.push_str(
+                                "\nnextFeature skips proposals repeating it and notes the \
+                                 differences of proposals resembling it.",
+                            );
+
+                            Ok(ToolOutput::new(
+                                text,
+                                json!({
+                                    "feature": output::feature_json(&rejected.feature),
+                                    "reason": rejected.reason,
+                                    "author": rejected.author,
+                                    "rejectedAt": rejected.rejected_at,
+                                }),
+                            ))
+                        }
+                        Err(e) => Err(tool_error("reject feature", e)),
                     }
                 }
                 "featureReview" => {
-                    let session_name = arguments
-                        .get("sessionName")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| {
-                            ToolError::InvalidParameters(
-                                "sessionName is required for featureReview".to_string(),
-                            )
-                        })?;
-                    let changes_made = arguments
-                        .get("changesMade")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| {
-                            ToolError::InvalidParameters(
-                                "changesMade is required for featureReview".to_string(),
-                            )
-                        })?;
+                    let params: FeatureReviewParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let changes_made = params.changes_made.as_str();
 
                     // Validate that changes_made is not empty
                     if changes_made.trim().is_empty() {
@@ -340,40 +927,39 @@ Provide detailed but concise information for each section.",
                         ));
                     }
 
-                    // Logic to submit feature review
-                    let session_manager = this.session_manager.lock().await;
+                    let submission = ReviewSubmission {
+                        changes_made: changes_made.to_string(),
+                        commits: parse_commits(&params.commits)?,
+                        time_spent_minutes: params.time_spent_minutes,
+                        include_diff: params.include_diff,
+                        depth: parse_review_depth(params.review_depth.as_deref())?,
+                        panel: params.review_panel,
+                        attachments: parse_attachments(&params.attachments)?,
+                        author: parse_author(params.author.as_deref()).map(str::to_string),
+                    };
 
+                    // Logic to submit feature review
                     // Get the LLM client reference if available
-                    let llm_client_ref = this.llm_client.as_ref().as_ref();
+                    let llm_client_ref = llm_client.as_deref();
 
                     match session_manager
-                        .submit_feature_review(session_name, changes_made, llm_client_ref)
+                        .submit_feature_review(session_name, submission, llm_client_ref)
                         .await
                     {
-                        Ok(review_response) => Ok(vec![Content::text(review_response)]),
-                        Err(e) => Err(ToolError::ExecutionError(format!(
-                            "Failed to submit feature review: {}",
-                            e
-                        ))),
+                        Ok(review_response) => {
+                            let data = with_session(&session_manager, session_name, |session| {
+                                review_outcome(session, &review_response)
+                            })
+                            .await?;
+                            Ok(ToolOutput::new(review_response, data))
+                        }
+                        Err(e) => Err(tool_error("submit feature review", e)),
                     }
                 }
                 "reviewReply" => {
-                    let session_name = arguments
-                        .get("sessionName")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| {
-                            ToolError::InvalidParameters(
-                                "sessionName is required for reviewReply".to_string(),
-                            )
-                        })?;
-                    let content = arguments
-                        .get("content")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| {
-                            ToolError::InvalidParameters(
-                                "content is required for reviewReply".to_string(),
-                            )
-                        })?;
+                    let params: ReviewReplyParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let content = params.content.as_str();
 
                     // Validate that content is not empty
                     if content.trim().is_empty() {
@@ -382,40 +968,84 @@ Provide detailed but concise information for each section.",
                         ));
                     }
 
-                    // Logic to reply to review questions
-                    let session_manager = this.session_manager.lock().await;
+                    let commits = parse_commits(&params.commits)?;
+                    let time_spent_minutes = params.time_spent_minutes;
+                    let author = parse_author(params.author.as_deref());
 
+                    // Logic to reply to review questions
                     // Get the LLM client reference if available
-                    let llm_client_ref = this.llm_client.as_ref().as_ref();
+                    let llm_client_ref = llm_client.as_deref();
 
                     match session_manager
-                        .submit_review_reply(session_name, content, llm_client_ref)
+                        .submit_review_reply(
+                            session_name,
+                            content,
+                            &commits,
+                            time_spent_minutes,
+                            author,
+                            llm_client_ref,
+                        )
                         .await
                     {
-                        Ok(reply_response) => Ok(vec![Content::text(reply_response)]),
-                        Err(e) => Err(ToolError::ExecutionError(format!(
-                            "Failed to submit review reply: {}",
-                            e
-                        ))),
+                        Ok(reply_response) => {
+                            let data = with_session(&session_manager, session_name, |session| {
+                                review_outcome(session, &reply_response)
+                            })
+                            .await?;
+                            Ok(ToolOutput::new(reply_response, data))
+                        }
+                        Err(e) => Err(tool_error("submit review reply", e)),
+                    }
+                }
+                "humanApprove" => {
+                    let params: HumanApproveParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let author = parse_author(Some(&params.author)).ok_or_else(|| {
+                        ToolError::InvalidParameters(
+                            "author cannot be empty for humanApprove".to_string(),
+                        )
+                    })?;
+                    let comment = params
+                        .comment
+                        .as_deref()
+                        .map(str::trim)
+                        .filter(|comment| !comment.is_empty());
+
+                    match session_manager
+                        .human_approve(
+                            session_name,
+                            params.feature.as_deref(),
+                            params.approve,
+                            author,
+                            comment,
+                            &params.signature,
+                        )
+                        .await
+                    {
+                        Ok((feature, status)) => Ok(ToolOutput::new(
+                            if params.approve {
+                                format!("Feature '{}' was approved by {}.", feature, author)
+                            } else {
+                                format!(
+                                    "Feature '{}' was sent back for rework by {}.",
+                                    feature, author
+                                )
+                            },
+                            json!({
+                                "feature": feature,
+                                "status": status,
+                                "approved": params.approve,
+                                "author": author,
+                                "comment": comment,
+                            }),
+                        )),
+                        Err(e) => Err(tool_error("record approval", e)),
                     }
                 }
                 "featureAsk" => {
-                    let session_name = arguments
-                        .get("sessionName")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| {
-                            ToolError::InvalidParameters(
-                                "sessionName is required for featureAsk".to_string(),
-                            )
-                        })?;
-                    let question = arguments
-                        .get("question")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| {
-                            ToolError::InvalidParameters(
-                                "question is required for featureAsk".to_string(),
-                            )
-                        })?;
+                    let params: FeatureAskParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let question = params.question.as_str();
 
                     // Validate that question is not empty
                     if question.trim().is_empty() {
@@ -424,53 +1054,2856 @@ Provide detailed but concise information for each section.",
                         ));
                     }
 
-                    // Logic to ask a question about the current feature or design
-                    let session_manager = this.session_manager.lock().await;
+                    let author = parse_author(params.author.as_deref());
 
+                    // Logic to ask a question about the current feature or design
                     // Get the LLM client reference if available
-                    let llm_client_ref = this.llm_client.as_ref().as_ref();
+                    let llm_client_ref = llm_client.as_deref();
 
                     match session_manager
-                        .answer_feature_question(session_name, question, llm_client_ref)
+                        .answer_feature_question(session_name, question, author, llm_client_ref)
                         .await
                     {
-                        Ok(answer_response) => Ok(vec![Content::text(answer_response)]),
-                        Err(e) => Err(ToolError::ExecutionError(format!(
-                            "Failed to answer feature question: {}",
-                            e
-                        ))),
+                        Ok(answer_response) => {
+                            let data = json!({
+                                "question": question,
+                                "answer": answer_response,
+                                "author": author,
+                                "followUpQuestions": output::questions(&answer_response),
+                            });
+                            Ok(ToolOutput::new(answer_response, data))
+                        }
+                        Err(e) => Err(tool_error("answer feature question", e)),
                     }
                 }
-                _ => Err(ToolError::NotFound(format!(
-                    "Tool '{}' not found.",
-                    tool_name
-                ))),
-            }
-        })
-    }
+                "sessionEvents" => {
+                    let params: SessionEventsParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let cursor = params.cursor.unwrap_or(0);
+                    let limit = params.limit.unwrap_or(100).min(1000) as usize;
 
-    // --- Resources and Prompts are not implemented for this router ---
-    fn list_resources(&self) -> Vec<Resource> {
-        vec![]
-    }
+                    match session_manager
+                        .session_events(session_name, cursor, limit)
+                        .await
+                    {
+                        Ok((events, next_cursor)) => {
+                            let data = json!({
+                                "events": events,
+                                "nextCursor": next_cursor,
+                            });
+                            Ok(ToolOutput::new(data.to_string(), data))
+                        }
+                        Err(e) => Err(tool_error("read session events", e)),
+                    }
+                }
+                "backlogImport" => {
+                    let params: BacklogImportParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let issues = fetch_issues(
+                        params.source,
+                        params.repository.as_deref(),
+                        params.team.as_deref(),
+                        params.limit.unwrap_or(50),
+                    )
+                    .await?;
 
-    fn read_resource(
-        &self,
-        _uri: &str,
-    ) -> Pin<
-        Box<
-            dyn futures::Future<Output = Result<String, mcp_core::handler::ResourceError>>
+                    let issue_count = issues.len();
+                    let llm_client_ref = llm_client.as_deref();
+
+                    match session_manager
+                        .import_backlog(session_name, issues, llm_client_ref)
+                        .await
+                    {
+                        Ok(features) => {
+                            let mut text = format!(
+                                "Imported {} of {} open issues into session '{}' as planned features.",
+                                features.len(),
+                                issue_count,
+                                session_name
+                            );
+                            for feature in &features {
+                                text.push_str(&format!("\n- {}", feature.name));
+                            }
+                            Ok(ToolOutput::new(
+                                text,
+                                json!({
+                                    "issues": issue_count,
+                                    "features": features
+                                        .iter()
+                                        .map(output::feature_json)
+                                        .collect::<Vec<_>>(),
+                                }),
+                            ))
+                        }
+                        Err(e) => Err(tool_error("import backlog", e)),
+                    }
+                }
+                "boardSync" => {
+                    let params: BoardSyncParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    let session = load_session(&session_manager, session_name).await?;
+
+                    match params.target {
+                        BoardTarget::Trello => {
+                            let export = board::trello_board(&session);
+                            let text = serde_json::to_string_pretty(&export).map_err(|e| {
+                                ToolError::ExecutionError(format!(
+                                    "Failed to serialize the board: {}",
+                                    e
+                                ))
+                            })?;
+
+                            Ok(ToolOutput::new(text, export))
+                        }
+                        BoardTarget::Github => {
+                            let repository = params
+                                .repository
+                                .or_else(|| {
+                                    session.board.as_ref().map(|board| board.repository.clone())
+                                })
+                                .ok_or_else(|| {
+                                    ToolError::InvalidParameters(
+                                        "repository is required for the first sync with github"
+                                            .to_string(),
+                                    )
+                                })?;
+
+                            let sync =
+                                board::sync_github(&session, &repository, session_manager.now())
+                                    .await
+                                    .map_err(|e| {
+                                        ToolError::ExecutionError(format!(
+                                            "Failed to sync with GitHub: {}",
+                                            e
+                                        ))
+                                    })?;
+
+                            let mut text = format!(
+                                "Synced {} features of session '{}' with {}: {} issues created, \
+                                 {} updated, {} statuses taken over from GitHub.",
+                                sync.board.links.len(),
+                                session_name,
+                                repository,
+                                sync.created.len(),
+                                sync.pushed.len(),
+                                sync.pulled.len()
+                            );
+                            for (feature, from, to) in &sync.pulled {
+                                text.push_str(&format!(
+                                    "\n- {}: {} -> {}",
+                                    feature,
+                                    board::column_name(from),
+                                    board::column_name(to)
+                                ));
+                            }
+                            for (feature, error) in &sync.failed {
+                                text.push_str(&format!("\n- {} failed: {}", feature, error));
+                            }
+
+                            let data = json!({
+                                "repository": repository,
+                                "created": sync.created,
+                                "pushed": sync.pushed,
+                                "pulled": sync
+                                    .pulled
+                                    .iter()
+                                    .map(|(feature, from, to)| {
+                                        json!({ "feature": feature, "from": from, "to": to })
+                                    })
+                                    .collect::<Vec<_>>(),
+                                "failed": sync
+                                    .failed
+                                    .iter()
+                                    .map(|(feature, error)| {
+                                        json!({ "feature": feature, "error": error })
+                                    })
+                                    .collect::<Vec<_>>(),
+                                "issues": sync.board.links,
+                            });
+
+                            session_manager
+                                .apply_board_sync(session_name, sync)
+                                .await
+                                .map_err(|e| {
+                                    ToolError::ExecutionError(format!(
+                                        "Failed to store the board sync: {}",
+                                        e
+                                    ))
+                                })?;
+
+                            Ok(ToolOutput::new(text, data))
+                        }
+                    }
+                }
+                "designImport" => {
+                    let params: DesignImportParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    let persona = parse_persona(params.persona.as_deref())?
+                        .or(config::current().default_persona)
+                        .unwrap_or_default();
+
+                    let language = parse_language(params.language.as_deref());
+
+                    let issues = fetch_issues(
+                        params.source,
+                        params.repository.as_deref(),
+                        params.team.as_deref(),
+                        params.limit.unwrap_or(100),
+                    )
+                    .await?;
+
+                    let issue_count = issues.len();
+                    let llm_client_ref = llm_client.as_deref();
+
+                    match session_manager
+                        .import_design(
+                            session_name.to_string(),
+                            issues,
+                            persona,
+                            language,
+                            llm_client_ref,
+                        )
+                        .await
+                    {
+                        Ok(features) => {
+                            let mut text = format!(
+                                "Session '{}' created from {} open issues, with a design document \
+                                 and {} planned features. Review it with designOverview.",
+                                session_name,
+                                issue_count,
+                                features.len()
+                            );
+                            for feature in &features {
+                                text.push_str(&format!("\n- {}", feature.name));
+                            }
+                            Ok(ToolOutput::new(
+                                text,
+                                json!({
+                                    "session": session_name,
+                                    "persona": persona.name(),
+                                    "issues": issue_count,
+                                    "features": features
+                                        .iter()
+                                        .map(output::feature_json)
+                                        .collect::<Vec<_>>(),
+                                }),
+                            ))
+                        }
+                        Err(e) => Err(tool_error("import design", e)),
+                    }
+                }
+                "designReviewRequest" => {
+                    let params: DesignReviewRequestParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let proposed_edit = params.proposed_edit.as_str();
+                    let rationale = params.rationale.as_deref();
+
+                    if proposed_edit.trim().is_empty() {
+                        return Err(ToolError::InvalidParameters(
+                            "proposedEdit cannot be empty for designReviewRequest".to_string(),
+                        ));
+                    }
+
+                    let llm_client_ref = llm_client.as_deref();
+
+                    match session_manager
+                        .review_design_edit(session_name, proposed_edit, rationale, llm_client_ref)
+                        .await
+                    {
+                        Ok(DesignEditOutcome::Merged { version, summary }) => Ok(ToolOutput::new(
+                            format!("MERGED as design version {}: {}", version, summary),
+                            json!({
+                                "verdict": "merged",
+                                "version": version,
+                                "summary": summary,
+                                "objections": [],
+                            }),
+                        )),
+                        Ok(DesignEditOutcome::Objected {
+                            summary,
+                            objections,
+                        }) => {
+                            let mut text = format!("OBJECTED: {}", summary);
+                            for objection in &objections {
+                                text.push_str(&format!("\n- {}", objection));
+                            }
+                            Ok(ToolOutput::new(
+                                text,
+                                json!({
+                                    "verdict": "objected",
+                                    "summary": summary,
+                                    "objections": objections,
+                                }),
+                            ))
+                        }
+                        Err(e) => Err(tool_error("review design edit", e)),
+                    }
+                }
+                "qaHistory" => {
+                    let params: QaHistoryParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let feature = params.feature.as_deref();
+                    let range = params.range.parse()?;
+
+                    match session_manager.qa_history(session_name, feature).await {
+                        Ok(entries) if entries.is_empty() => Ok(ToolOutput::new(
+                            "No questions have been answered yet.",
+                            json!({ "entries": [], "total": 0, "nextCursor": null }),
+                        )),
+                        Ok(entries) => {
+                            let page = paginate(
+                                entries
+                                    .into_iter()
+                                    .filter(|entry| range.contains(entry.asked_at)),
+                                &params.page,
+                            );
+                            let text = page
+                                .items
+                                .iter()
+                                .map(|entry| {
+                                    format!(
+                                        "[{}] Feature: {}\nQ: {}\nA: {}",
+                                        entry.asked_at.format("%Y-%m-%d %H:%M"),
+                                        entry.feature.as_deref().unwrap_or("(none)"),
+                                        entry.question,
+                                        entry.answer
+                                    )
+                                })
+                                .collect::<Vec<_>>()
+                                .join("\n\n");
+                            Ok(ToolOutput::new(
+                                page.annotate(text),
+                                json!({
+                                    "entries": page.items,
+                                    "total": page.total,
+                                    "nextCursor": page.next_cursor,
+                                }),
+                            ))
+                        }
+                        Err(e) => Err(tool_error("read Q&A history", e)),
+                    }
+                }
+                "designPersona" => {
+                    let params: DesignPersonaParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    let Some(persona) = parse_persona(params.persona.as_deref())? else {
+                        let session = session_manager
+                            .load_session(session_name)
+                            .await
+                            .map_err(|e| tool_error("load session", e))?
+                            .ok_or_else(|| {
+                                GameDesignerError::SessionNotFound(session_name.to_string())
+                            })?;
+
+                        let mut text = format!(
+                            "Current persona: {}\n\nAvailable personas:",
+                            session.persona.name()
+                        );
+                        for persona in Persona::ALL {
+                            text.push_str(&format!(
+                                "\n- {}: {}",
+                                persona.name(),
+                                persona.description()
+                            ));
+                        }
+                        let data = json!({
+                            "persona": session.persona.name(),
+                            "available": persona_names(),
+                        });
+                        return Ok(ToolOutput::new(text, data).into_content(output_format));
+                    };
+
+                    match session_manager.set_persona(session_name, persona).await {
+                        Ok(previous) => Ok(ToolOutput::new(
+                            format!(
+                                "Persona of session '{}' changed from '{}' to '{}'.",
+                                session_name,
+                                previous.name(),
+                                persona.name()
+                            ),
+                            json!({ "persona": persona.name(), "previous": previous.name() }),
+                        )),
+                        Err(e) => Err(tool_error("change persona", e)),
+                    }
+                }
+                "designInterview" => {
+                    let params: DesignInterviewParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let persona = parse_persona(params.persona.as_deref())?;
+                    let answer = params.answer;
+
+                    let llm_client_ref = llm_client.as_deref();
+
+                    match session_manager
+                        .interview_step(session_name, answer, persona, llm_client_ref)
+                        .await
+                    {
+                        Ok(InterviewStep::Question {
+                            index,
+                            total,
+                            topic,
+                            question,
+                        }) => Ok(ToolOutput::new(
+                            format!(
+                                "Question {}/{} ({}): {}\n\nReply with designInterview and the answer.",
+                                index, total, topic, question
+                            ),
+                            json!({
+                                "status": "question",
+                                "index": index,
+                                "total": total,
+                                "topic": topic,
+                                "question": question,
+                            }),
+                        )),
+                        Ok(InterviewStep::Completed { document }) => Ok(ToolOutput::new(
+                            format!(
+                                "Interview complete. Session '{}' created with this design:\n\n{}",
+                                session_name, document
+                            ),
+                            json!({
+                                "status": "completed",
+                                "session": session_name,
+                                "document": document,
+                            }),
+                        )),
+                        Err(e) => Err(tool_error("run design interview", e)),
+                    }
+                }
+                "assetList" => {
+                    let params: AssetListParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let kind = match params.kind.as_deref() {
+                        Some(name) => Some(AssetKind::parse(name).ok_or_else(|| {
+                            ToolError::InvalidParameters(format!("Unknown asset kind '{}'", name))
+                        })?),
+                        None => None,
+                    };
+
+                    match params.format {
+                        AssetListFormat::Csv => {
+                            match session_manager.export_assets_csv(session_name, kind).await {
+                                Ok((path, csv)) => Ok(ToolOutput::new(
+                                    format!("Asset list written to {}\n\n{}", path, csv),
+                                    json!({ "path": path, "csv": csv }),
+                                )),
+                                Err(e) => Err(tool_error("export asset list", e)),
+                            }
+                        }
+                        AssetListFormat::Text => {
+                            match session_manager.asset_list(session_name, kind).await {
+                                Ok(entries) if entries.is_empty() => Ok(ToolOutput::new(
+                                    "No asset requirements have been recorded yet.",
+                                    json!({ "assets": [], "total": 0, "nextCursor": null }),
+                                )),
+                                Ok(entries) => {
+                                    let page = paginate(entries, &params.page);
+                                    let text = page
+                                        .items
+                                        .iter()
+                                        .map(|entry| {
+                                            format!(
+                                                "- [{}] {} ({}, {:?}): {}",
+                                                entry.asset.kind.name(),
+                                                entry.asset.name,
+                                                entry.feature,
+                                                entry.feature_status,
+                                                entry.asset.description
+                                            )
+                                        })
+                                        .collect::<Vec<_>>()
+                                        .join("\n");
+                                    Ok(ToolOutput::new(
+                                        page.annotate(text),
+                                        json!({
+                                            "assets": page.items,
+                                            "total": page.total,
+                                            "nextCursor": page.next_cursor,
+                                        }),
+                                    ))
+                                }
+                                Err(e) => Err(tool_error("list assets", e)),
+                            }
+                        }
+                    }
+                }
+                "designExport" => {
+                    let params: DesignExportParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let path = params.path.as_deref();
+                    let auto_update = params.auto_update;
+
+                    match session_manager
+                        .export_design(session_name, path, auto_update)
+                        .await
+                    {
+                        Ok((_, Some(path))) => {
+                            let mut text = format!("Design document written to {}.", path);
+                            match auto_update {
+                                Some(true) => text.push_str(
+                                    " It will be regenerated after every change to the session.",
+                                ),
+                                Some(false) => text.push_str(" Automatic export is disabled."),
+                                None => {}
+                            }
+                            Ok(ToolOutput::new(
+                                text,
+                                json!({ "path": path, "autoUpdate": auto_update }),
+                            ))
+                        }
+                        Ok((markdown, None)) => {
+                            let data = json!({ "markdown": markdown });
+                            Ok(ToolOutput::new(markdown, data))
+                        }
+                        Err(e) => Err(tool_error("export design", e)),
+                    }
+                }
+                "traceabilityReport" => {
+                    let params: TraceabilityReportParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    match session_manager.traceability(session_name).await {
+                        Ok(features) if features.is_empty() => Ok(ToolOutput::new(
+                            "No features have been reviewed or linked to commits yet.",
+                            json!({ "features": [], "total": 0, "nextCursor": null }),
+                        )),
+                        Ok(features) => {
+                            let page = paginate(
+                                features
+                                    .into_iter()
+                                    .filter(|feature| params.filter.matches(feature)),
+                                &params.page,
+                            );
+                            let text = page
+                                .items
+                                .iter()
+                                .map(|feature| {
+                                    let commits = if feature.commits.is_empty() {
+                                        "  (no commits recorded)".to_string()
+                                    } else {
+                                        feature
+                                            .commits
+                                            .iter()
+                                            .map(|commit| format!("  - {}", commit))
+                                            .collect::<Vec<_>>()
+                                            .join("\n")
+                                    };
+                                    format!("{} ({:?})\n{}", feature.name, feature.status, commits)
+                                })
+                                .collect::<Vec<_>>()
+                                .join("\n\n");
+                            let features = page
+                                .items
+                                .iter()
+                                .map(|feature| {
+                                    json!({
+                                        "name": feature.name,
+                                        "status": feature.status,
+                                        "commits": feature.commits,
+                                    })
+                                })
+                                .collect::<Vec<_>>();
+                            Ok(ToolOutput::new(
+                                page.annotate(text),
+                                json!({
+                                    "features": features,
+                                    "total": page.total,
+                                    "nextCursor": page.next_cursor,
+                                }),
+                            ))
+                        }
+                        Err(e) => Err(tool_error("build traceability report", e)),
+                    }
+                }
+                "designLint" => {
+                    let params: SessionParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    let llm_client_ref = llm_client.as_deref();
+
+                    match session_manager
+                        .lint_design(session_name, llm_client_ref)
+                        .await
+                    {
+                        Ok(findings) if findings.is_empty() => Ok(ToolOutput::new(
+                            "No contradictions with the design pillars found.",
+                            json!({ "findings": [] }),
+                        )),
+                        Ok(findings) => {
+                            let text = serde_json::to_string_pretty(&findings).map_err(|e| {
+                                ToolError::ExecutionError(format!(
+                                    "Failed to serialize findings: {}",
+                                    e
+                                ))
+                            })?;
+                            Ok(ToolOutput::new(text, json!({ "findings": findings })))
+                        }
+                        Err(e) => Err(tool_error("lint design", e)),
+                    }
+                }
+                "designBrainstorm" => {
+                    let params: DesignBrainstormParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let ideas = params.ideas.unwrap_or(12).clamp(5, 30);
+                    let shortlist = params.shortlist.unwrap_or(3).clamp(1, 10);
+
+                    let llm_client_ref = llm_client.as_deref();
+
+                    match session_manager
+                        .brainstorm(
+                            session_name,
+                            &params.topic,
+                            ideas,
+                            shortlist,
+                            llm_client_ref,
+                        )
+                        .await
+                    {
+                        Ok(outcome) => {
+                            let mut text = format!("Ideas on '{}':\n", params.topic.trim());
+                            for idea in &outcome.ideas {
+                                text.push_str(&format!("- {}: {}\n", idea.name, idea.description));
+                            }
+
+                            if outcome.shortlist.is_empty() {
+                                text.push_str("\nNo new ideas were shortlisted.");
+                            } else {
+                                text.push_str(
+                                    "\nShortlist, added to the idea pool for nextFeature:\n",
+                                );
+                            }
+                            for (index, idea) in outcome.shortlist.iter().enumerate() {
+                                let scores = idea
+                                    .scores
+                                    .iter()
+                                    .map(|score| {
+                                        format!(
+                                            "{} {}/{}",
+                                            score.pillar,
+                                            score.score,
+                                            brainstorm::MAX_PILLAR_SCORE
+                                        )
+                                    })
+                                    .collect::<Vec<_>>();
+                                text.push_str(&format!(
+                                    "\n{}. {} (score {})\n{}\nPillars: {}\nRationale: {}\n",
+                                    index + 1,
+                                    idea.name,
+                                    idea.total(),
+                                    idea.description,
+                                    scores.join(", "),
+                                    idea.rationale
+                                ));
+                            }
+
+                            Ok(ToolOutput::new(
+                                text,
+                                json!({
+                                    "topic": params.topic.trim(),
+                                    "ideas": outcome.ideas,
+                                    "shortlist": outcome
+                                        .shortlist
+                                        .iter()
+                                        .map(|idea| {
+                                            json!({
+                                                "name": idea.name,
+                                                "description": idea.description,
+                                                "scores": idea.scores,
+                                                "total": idea.total(),
+                                                "rationale": idea.rationale,
+                                            })
+                                        })
+                                        .collect::<Vec<_>>(),
+                                }),
+                            ))
+                        }
+                        Err(e) => Err(tool_error("brainstorm", e)),
+                    }
+                }
+                "storyOutline" => {
+                    let params: StoryOutlineParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    let llm_client_ref = llm_client.as_deref();
+
+                    if let Some(style_guide) = &params.style_guide {
+                        let style_guide = Some(style_guide.trim())
+                            .filter(|style_guide| !style_guide.is_empty())
+                            .map(str::to_string);
+                        session_manager
+                            .set_style_guide(session_name, style_guide)
+                            .await
+                            .map_err(|e| {
+                                ToolError::ExecutionError(format!(
+                                    "Failed to set the style guide: {}",
+                                    e
+                                ))
+                            })?;
+                    }
+
+                    if params.generate {
+                        session_manager
+                            .draft_story(session_name, params.replace, llm_client_ref)
+                            .await
+                            .map_err(|e| {
+                                ToolError::ExecutionError(format!(
+                                    "Failed to draft the story: {}",
+                                    e
+                                ))
+                            })?;
+                    }
+
+                    with_session(&session_manager, session_name, |session| {
+                        let narrative = &session.narrative;
+                        let text = if narrative.is_empty() {
+                            format!(
+                                "Session '{}' has no story yet. Draft one with generate, or \
+                                 add characters with characterSheet and beats with beatAdd.",
+                                session_name
+                            )
+                        } else {
+                            narrative::outline(narrative)
+                        };
+
+                        ToolOutput::new(text, json!({ "narrative": narrative }))
+                    })
+                    .await
+                }
+                "characterSheet" => {
+                    let params: CharacterSheetParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    let update = Character {
+                        name: params.name,
+                        role: params.role,
+                        description: params.description,
+                        motivation: params.motivation,
+                        arc: params.arc,
+                        voice: params.voice,
+                    };
+                    let changes = [
+                        &update.role,
+                        &update.description,
+                        &update.motivation,
+                        &update.arc,
+                        &update.voice,
+                    ];
+
+                    // Only a name shows the sheet of an existing character
+                    let created = if changes.iter().all(|field| field.is_none()) {
+                        false
+                    } else {
+                        let (_, created) = session_manager
+                            .update_character(session_name, update.clone())
+                            .await
+                            .map_err(|e| {
+                                ToolError::ExecutionError(format!(
+                                    "Failed to update character: {}",
+                                    e
+                                ))
+                            })?;
+                        created
+                    };
+
+                    with_session(&session_manager, session_name, |session| {
+                        let character =
+                            session.narrative.character(&update.name).ok_or_else(|| {
+                                ToolError::NotFound(format!(
+                                    "Character '{}' not found. Pass any of its fields to add it.",
+                                    update.name
+                                ))
+                            })?;
+
+                        let mut text = narrative::sheet(&session.narrative, character);
+                        if created {
+                            text = format!("Added character '{}'.\n\n{}", character.name, text);
+                        }
+
+                        Ok(ToolOutput::new(
+                            text,
+                            json!({ "character": character, "created": created }),
+                        ))
+                    })
+                    .await?
+                }
+                "beatAdd" => {
+                    let params: BeatAddParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    let beat = Beat {
+                        title: params.title.trim().to_string(),
+                        description: params.description.trim().to_string(),
+                        characters: params.characters,
+                        feature: params.feature,
+                    };
+
+                    match session_manager
+                        .add_beat(session_name, &params.act, beat, params.position)
+                        .await
+                    {
+                        Ok((act, position)) => {
+                            with_session(&session_manager, session_name, |session| {
+                                ToolOutput::new(
+                                    format!(
+                                        "Added beat '{}' to act {} at position {}.\n\n{}",
+                                        params.title.trim(),
+                                        act,
+                                        position,
+                                        narrative::outline(&session.narrative)
+                                    ),
+                                    json!({
+                                        "act": act,
+                                        "position": position,
+                                        "narrative": session.narrative,
+                                    }),
+                                )
+                            })
+                            .await
+                        }
+                        Err(e) => Err(tool_error("add beat", e)),
+                    }
+                }
+                "levelBrief" => {
+                    let params: LevelBriefParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    let status = params
+                        .status
+                        .as_deref()
+                        .map(|name| {
+                            LevelStatus::parse(name).ok_or_else(|| {
+                                ToolError::InvalidParameters(format!(
+                                    "Unknown level status '{}'",
+                                    name
+                                ))
+                            })
+                        })
+                        .transpose()?;
+                    let goals = params
+                        .goals
+                        .iter()
+                        .map(|goal| goal.trim())
+                        .filter(|goal| !goal.is_empty())
+                        .map(str::to_string)
+                        .collect::<Vec<_>>();
+
+                    let llm_client_ref = llm_client.as_deref();
+
+                    let exists = with_session(&session_manager, session_name, |session| {
+                        session
+                            .levels
+                            .iter()
+                            .any(|level| level.name.eq_ignore_ascii_case(params.name.trim()))
+                    })
+                    .await?;
+
+                    let mut notes = Vec::new();
+
+                    // A status change alone keeps the brief as it is
+                    if !exists || !goals.is_empty() || params.regenerate {
+                        let (level, created) = session_manager
+                            .brief_level(session_name, &params.name, goals, llm_client_ref)
+                            .await
+                            .map_err(|e| {
+                                ToolError::ExecutionError(format!("Failed to brief level: {}", e))
+                            })?;
+                        notes.push(if created {
+                            format!("Briefed new level '{}'.", level.name)
+                        } else {
+                            format!("Briefed level '{}' again.", level.name)
+                        });
+                    }
+
+                    if let Some(status) = status {
+                        let (level, previous) = session_manager
+                            .set_level_status(session_name, &params.name, status)
+                            .await
+                            .map_err(|e| {
+                                ToolError::ExecutionError(format!(
+                                    "Failed to set level status: {}",
+                                    e
+                                ))
+                            })?;
+                        notes.push(format!(
+                            "Level '{}' moved from {} to {}.",
+                            level.name,
+                            previous.name(),
+                            status.name()
+                        ));
+                    }
+
+                    with_session(&session_manager, session_name, |session| {
+                        let level = session
+                            .levels
+                            .iter()
+                            .find(|level| level.name.eq_ignore_ascii_case(params.name.trim()))
+                            .ok_or_else(|| {
+                                ToolError::NotFound(format!("Level '{}' not found", params.name))
+                            })?;
+
+                        let mut text = level.brief();
+                        if !notes.is_empty() {
+                            text = format!("{}\n\n{}", notes.join(" "), text);
+                        }
+
+                        Ok(ToolOutput::new(text, json!({ "level": level })))
+                    })
+                    .await?
+                }
+                "economyModel" => {
+                    let params: EconomyModelParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    let flow = |flow: EconomyFlowParams| Flow {
+                        name: flow.name.trim().to_string(),
+                        resource: flow.resource,
+                        rate_per_hour: flow.rate_per_hour,
+                        feature: flow.feature,
+                    };
+                    let update = Economy {
+                        resources: params
+                            .resources
+                            .into_iter()
+                            .map(|resource| Resource {
+                                name: resource.name.trim().to_string(),
+                                description: resource.description.unwrap_or_default(),
+                                cap: resource.cap,
+                            })
+                            .collect(),
+                        sources: params.sources.into_iter().map(flow).collect(),
+                        sinks: params.sinks.into_iter().map(flow).collect(),
+                        curves: params
+                            .curves
+                            .into_iter()
+                            .map(|curve| ProgressionCurve {
+                                name: curve.name.trim().to_string(),
+                                resource: curve.resource,
+                                costs: curve.costs,
+                            })
+                            .collect(),
+                        simulation: None,
+                    };
+
+                    let names = update
+                        .resources
+                        .iter()
+                        .map(|resource| &resource.name)
+                        .chain(update.sources.iter().map(|flow| &flow.name))
+                        .chain(update.sinks.iter().map(|flow| &flow.name))
+                        .chain(update.curves.iter().map(|curve| &curve.name));
+                    for name in names {
+                        if name.is_empty() {
+                            return Err(ToolError::InvalidParameters(
+                                "Economy entries need a name".to_string(),
+                            ));
+                        }
+                    }
+
+                    let llm_client_ref = llm_client.as_deref();
+
+                    let mut notes = Vec::new();
+
+                    if !update.is_empty() || !params.remove.is_empty() {
+                        session_manager
+                            .update_economy(session_name, update, params.remove)
+                            .await
+                            .map_err(|e| {
+                                ToolError::ExecutionError(format!(
+                                    "Failed to update economy: {}",
+                                    e
+                                ))
+                            })?;
+                        notes.push("Economy updated.".to_string());
+                    }
+
+                    if params.simulate {
+                        let simulation = session_manager
+                            .simulate_economy(session_name, llm_client_ref)
+                            .await
+                            .map_err(|e| {
+                                ToolError::ExecutionError(format!(
+                                    "Failed to simulate economy: {}",
+                                    e
+                                ))
+                            })?;
+                        notes.push(match simulation.flags.len() {
+                            0 => "Simulated; no feature breaks the economy.".to_string(),
+                            flags => format!("Simulated; {} feature(s) flagged.", flags),
+                        });
+                    }
+
+                    with_session(&session_manager, session_name, |session| {
+                        let economy = &session.economy;
+
+                        let mut text = if economy.is_empty() {
+                            "No economy declared yet. Declare resources, sources, sinks and \
+                             progression curves to model it."
+                                .to_string()
+                        } else {
+                            economy.render()
+                        };
+                        if !notes.is_empty() {
+                            text = format!("{}\n\n{}", notes.join(" "), text);
+                        }
+
+                        ToolOutput::new(text, json!({ "economy": economy }))
+                    })
+                    .await
+                }
+                "monetizationReview" => {
+                    let params: MonetizationReviewParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    let mut policy =
+                        MonetizationPolicy::from_config(&config::current().monetization);
+                    let regions = params
+                        .regions
+                        .iter()
+                        .map(|region| region.trim())
+                        .filter(|region| !region.is_empty())
+                        .map(str::to_string)
+                        .collect::<Vec<_>>();
+                    if !regions.is_empty() {
+                        policy.regions = regions;
+                    }
+                    if let Some(minors) = params.minors {
+                        policy.minors = minors;
+                    }
+                    policy.rules.extend(
+                        params
+                            .rules
+                            .iter()
+                            .map(|rule| rule.trim())
+                            .filter(|rule| !rule.is_empty())
+                            .map(str::to_string),
+                    );
+                    if policy.rules.is_empty() {
+                        return Err(ToolError::InvalidParameters(
+                            "No policy rules to review against; the built-in rules are turned \
+                             off in the configuration"
+                                .to_string(),
+                        ));
+                    }
+
+                    let llm_client_ref = llm_client.as_deref();
+
+                    match session_manager
+                        .review_monetization(session_name, policy, llm_client_ref)
+                        .await
+                    {
+                        Ok(report) => Ok(ToolOutput::new(
+                            report.render(),
+                            json!({ "report": report }),
+                        )),
+                        Err(e) => Err(tool_error("review monetization", e)),
+                    }
+                }
+                "debtList" => {
+                    let params: DebtListParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    let status = params
+                        .status
+                        .as_deref()
+                        .map(|name| {
+                            DebtStatus::parse(name).ok_or_else(|| {
+                                ToolError::InvalidParameters(format!(
+                                    "Unknown debt status '{}'",
+                                    name
+                                ))
+                            })
+                        })
+                        .transpose()?;
+                    let priority = params
+                        .priority
+                        .as_deref()
+                        .map(|name| {
+                            DebtPriority::parse(name).ok_or_else(|| {
+                                ToolError::InvalidParameters(format!(
+                                    "Unknown debt priority '{}'",
+                                    name
+                                ))
+                            })
+                        })
+                        .transpose()?;
+
+                    // Only the matching items are copied out of the session
+                    let mut items = with_session(&session_manager, session_name, |session| {
+                        session
+                            .tech_debt
+                            .iter()
+                            .filter(|item| status.is_none_or(|status| item.status == status))
+                            .filter(|item| {
+                                priority.is_none_or(|priority| item.priority == priority)
+                            })
+                            .filter(|item| {
+                                params.feature.as_deref().is_none_or(|feature| {
+                                    item.feature.eq_ignore_ascii_case(feature.trim())
+                                })
+                            })
+                            .cloned()
+                            .collect::<Vec<_>>()
+                    })
+                    .await?;
+
+                    // Unpaid debt first, most urgent first
+                    items.sort_by_key(|item| {
+                        (item.status == DebtStatus::Paid, item.priority, item.id)
+                    });
+
+                    if items.is_empty() {
+                        return Ok(ToolOutput::new(
+                            "No technical debt recorded.",
+                            json!({ "items": [], "total": 0, "nextCursor": null }),
+                        ));
+                    }
+
+                    let page = paginate(items.into_iter(), &params.page);
+                    let text = page
+                        .items
+                        .iter()
+                        .map(|item| {
+                            let mut line = format!(
+                                "#{} [{}] {} ({}, from {})",
+                                item.id,
+                                item.priority.name(),
+                                item.description,
+                                item.status.name(),
+                                item.feature
+                            );
+                            if let Some(feature) = &item.paid_by {
+                                line.push_str(&format!(", paid down by {}", feature));
+                            }
+                            line
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    Ok(ToolOutput::new(
+                        page.annotate(text),
+                        json!({
+                            "items": page.items,
+                            "total": page.total,
+                            "nextCursor": page.next_cursor,
+                        }),
+                    ))
+                }
+                "testPlan" => {
+                    let params: TestPlanParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    let llm_client_ref = llm_client.as_deref();
+
+                    match session_manager
+                        .test_plan(
+                            session_name,
+                            params.feature.as_deref(),
+                            params.regenerate,
+                            params.path.as_deref(),
+                            llm_client_ref,
+                        )
+                        .await
+                    {
+                        Ok((feature, test_plan, written, path)) => {
+                            let mut text =
+                                format!("# Test plan: {}\n\n{}", feature, test_plan.markdown());
+                            if let Some(path) = &path {
+                                text.push_str(&format!("\n\nWritten to {}.", path));
+                            }
+                            Ok(ToolOutput::new(
+                                text,
+                                json!({
+                                    "feature": feature,
+                                    "testPlan": test_plan,
+                                    "generated": written,
+                                    "path": path,
+                                }),
+                            ))
+                        }
+                        Err(e) => Err(tool_error("get test plan", e)),
+                    }
+                }
+                "uiCritique" => {
+                    let params: UiCritiqueParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let screenshot = parse_attachment(&params.screenshot)?;
+                    let context = params
+                        .context
+                        .as_deref()
+                        .map(str::trim)
+                        .filter(|context| !context.is_empty());
+                    let author = parse_author(params.author.as_deref());
+
+                    let llm_client_ref = llm_client.as_deref();
+
+                    match session_manager
+                        .ui_critique(
+                            session_name,
+                            params.feature.as_deref(),
+                            &screenshot,
+                            context,
+                            author,
+                            llm_client_ref,
+                        )
+                        .await
+                    {
+                        Ok((feature, critique)) => Ok(ToolOutput::new(
+                            format!(
+                                "# UI critique: {}\n\n{}\n\nScreenshot stored as {}.",
+                                feature,
+                                critique.markdown(),
+                                critique.screenshot.path
+                            ),
+                            json!({
+                                "feature": feature,
+                                "critique": critique,
+                            }),
+                        )),
+                        Err(e) => Err(tool_error("critique the UI", e)),
+                    }
+                }
+                "startWork" => {
+                    let params: WorkParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let feature = params.feature.as_deref();
+
+                    match session_manager.start_work(session_name, feature).await {
+                        Ok(feature) => Ok(ToolOutput::new(
+                            format!("Started work on feature '{}'.", feature),
+                            json!({ "feature": feature }),
+                        )),
+                        Err(e) => Err(tool_error("start work", e)),
+                    }
+                }
+                "stopWork" => {
+                    let params: WorkParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let feature = params.feature.as_deref();
+
+                    match session_manager.stop_work(session_name, feature).await {
+                        Ok((feature, minutes, total)) => Ok(ToolOutput::new(
+                            format!(
+                                "Stopped work on feature '{}': {} minutes added, {} minutes in total.",
+                                feature, minutes, total
+                            ),
+                            json!({
+                                "feature": feature,
+                                "minutesAdded": minutes,
+                                "totalMinutes": total,
+                            }),
+                        )),
+                        Err(e) => Err(tool_error("stop work", e)),
+                    }
+                }
+                "reuseFromSession" => {
+                    let params: ReuseFromSessionParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let query = params.query.as_str();
+                    let source_session = params.source_session.as_deref();
+
+                    if query.trim().is_empty() {
+                        return Err(ToolError::InvalidParameters(
+                            "query cannot be empty for reuseFromSession".to_string(),
+                        ));
+                    }
+
+                    let llm_client_ref = llm_client.as_deref();
+
+                    match session_manager
+                        .reuse_from_session(session_name, query, source_session, llm_client_ref)
+                        .await
+                    {
+                        Ok(outcome) if outcome.candidates.is_empty() => Ok(ToolOutput::new(
+                            format!(
+                                "No features or decisions matching '{}' found in other sessions.",
+                                query
+                            ),
+                            json!({ "matches": 0, "features": [], "decisions": [] }),
+                        )),
+                        Ok(ReuseOutcome {
+                            candidates,
+                            features,
+                            decisions,
+                        }) => {
+                            let mut text = format!(
+                                "Reviewed {} matches; imported {} features and {} decisions.",
+                                candidates.len(),
+                                features.len(),
+                                decisions.len()
+                            );
+                            for feature in &features {
+                                text.push_str(&format!(
+                                    "\n\n{}\n{}",
+                                    feature.name, feature.description
+                                ));
+                            }
+                            if !decisions.is_empty() {
+                                text.push_str("\n\nDecisions:");
+                                for decision in &decisions {
+                                    text.push_str(&format!("\n- {}", decision));
+                                }
+                            }
+                            Ok(ToolOutput::new(
+                                text,
+                                json!({
+                                    "matches": candidates.len(),
+                                    "features": features
+                                        .iter()
+                                        .map(output::feature_json)
+                                        .collect::<Vec<_>>(),
+                                    "decisions": decisions,
+                                }),
+                            ))
+                        }
+                        Err(e) => Err(tool_error("reuse from other sessions", e)),
+                    }
+                }
+                "scopeReport" => {
+                    let params: ScopeReportParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let budget = params
+                        .budget
+                        .map(|budget| budget.min(u32::MAX as u64) as u32);
+
+                    match session_manager.scope_report(session_name, budget).await {
+                        Ok(report) => {
+                            let mut text = String::new();
+
+                            for scope in &report.milestones {
+                                text.push_str(&format!(
+                                    "{}: {} features, {} points ({} remaining)",
+                                    scope.milestone,
+                                    scope.features,
+                                    scope.points,
+                                    scope.remaining_points
+                                ));
+                                if scope.unestimated > 0 {
+                                    text.push_str(&format!(", {} unestimated", scope.unestimated));
+                                }
+                                if !scope.high_risk.is_empty() {
+                                    text.push_str(&format!(
+                                        ", high risk: {}",
+                                        scope.high_risk.join(", ")
+                                    ));
+                                }
+                                text.push('\n');
+                            }
+
+                            text.push_str(&format!(
+                                "\nTotal: {} points ({} remaining)",
+                                report.total_points, report.remaining_points
+                            ));
+                            match report.budget {
+                                Some(budget) => {
+                                    text.push_str(&format!(", budget: {} points", budget))
+                                }
+                                None => text.push_str(", no budget set"),
+                            }
+
+                            if let Some(calibration) = &report.calibration {
+                                text.push_str(&format!(
+                                    "\nCalibration: {:.0} minutes per point over {} reviewed features",
+                                    calibration.minutes_per_point, calibration.samples
+                                ));
+                                for actuals in &calibration.by_effort {
+                                    text.push_str(&format!(
+                                        ", {:?} {}",
+                                        actuals.effort,
+                                        scope::format_minutes(actuals.average_minutes)
+                                    ));
+                                }
+                                if let Some(minutes) = report.projected_minutes {
+                                    text.push_str(&format!(
+                                        "\nProjected remaining work: {}",
+                                        scope::format_minutes(minutes)
+                                    ));
+                                }
+                            }
+
+                            for warning in &report.warnings {
+                                text.push_str(&format!("\nWarning: {}", warning));
+                            }
+
+                            Ok(ToolOutput::new(text, json!(report)))
+                        }
+                        Err(e) => Err(tool_error("build scope report", e)),
+                    }
+                }
+                "devlogGenerate" => {
+                    let params: DevlogGenerateParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    let until = match parse_date(params.until.as_deref(), "until")? {
+                        Some(until) => until,
+                        None => session_manager.now().date_naive(),
+                    };
+                    let since = match parse_date(params.since.as_deref(), "since")? {
+                        Some(since) => since,
+                        None => until - chrono::Days::new(7),
+                    };
+
+                    if since > until {
+                        return Err(ToolError::InvalidParameters(
+                            "since must not be after until".to_string(),
+                        ));
+                    }
+
+                    let llm_client_ref = llm_client.as_deref();
+
+                    match session_manager
+                        .generate_devlog(session_name, since, until, llm_client_ref)
+                        .await
+                    {
+                        Ok((path, devlog)) => Ok(ToolOutput::new(
+                            format!("Devlog saved to {}\n\n{}", path, devlog),
+                            json!({ "path": path, "devlog": devlog }),
+                        )),
+                        Err(e) => Err(tool_error("generate devlog", e)),
+                    }
+                }
+                "designRetro" => {
+                    let params: DesignRetroParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    let llm_client_ref = llm_client.as_deref();
+
+                    match session_manager
+                        .design_retro(session_name, params.path.as_deref(), llm_client_ref)
+                        .await
+                    {
+                        Ok((path, retrospective)) => {
+                            let mut data = json!(retrospective);
+                            data["path"] = json!(path);
+
+                            Ok(ToolOutput::new(
+                                format!(
+                                    "{}\n\nSaved to {}",
+                                    retrospective.render(session_name),
+                                    path
+                                ),
+                                data,
+                            ))
+                        }
+                        Err(e) => Err(tool_error("build retrospective", e)),
+                    }
+                }
+                "statusReport" => {
+                    let params: StatusReportParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let range = params.range.parse()?;
+
+                    let until = range.until.unwrap_or_else(|| session_manager.now());
+                    let since = range.since.unwrap_or(until - chrono::Duration::days(7));
+                    if since > until {
+                        return Err(ToolError::InvalidParameters(
+                            "since must not be after until".to_string(),
+                        ));
+                    }
+
+                    // Token usage is only kept for the current day
+                    let tokens = this
+                        .service
+                        .tokens
+                        .used_today(&session_key(project.as_deref(), session_name));
+
+                    match session_manager
+                        .status_report(session_name, since, until)
+                        .await
+                    {
+                        Ok(report) => {
+                            let text = format!(
+                                "{}\n- LLM tokens today: {}",
+                                report.render(session_name),
+                                tokens
+                            );
+                            let mut data = json!(report);
+                            data["tokensToday"] = json!(tokens);
+
+                            Ok(ToolOutput::new(text, data))
+                        }
+                        Err(e) => Err(tool_error("build status report", e)),
+                    }
+                }
+                "crateDoc" => {
+                    let params: CrateDocParams = parse_params(&arguments)?;
+                    let crate_name = params.crate_name.trim();
+                    let item_path = params.item_path.as_deref();
+                    let version = params.version.as_deref();
+
+                    let (doc, summarized) = this
+                        .service
+                        .docs
+                        .crate_doc(crate_name, item_path, version)
+                        .await?;
+
+                    Ok(ToolOutput::new(
+                        doc.clone(),
+                        json!({
+                            "crate": crate_name,
+                            "version": version.unwrap_or("latest"),
+                            "itemPath": item_path,
+                            "summarized": summarized,
+                            "doc": doc,
+                        }),
+                    ))
+                }
+                "featureList" => {
+                    let params: FeatureListParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    let session = session_manager
+                        .load_session(session_name)
+                        .await
+                        .map_err(|e| tool_error("load session", e))?
+                        .ok_or_else(|| {
+                            GameDesignerError::SessionNotFound(session_name.to_string())
+                        })?;
+
+                    let page = paginate(
+                        session
+                            .planned_features
+                            .iter()
+                            .filter(|feature| params.filter.matches(feature)),
+                        &params.page,
+                    );
+
+                    let lines: Vec<String> = page
+                        .items
+                        .iter()
+                        .map(|feature| {
+                            let mut line = format!("- {} [{:?}]", feature.name, feature.status);
+                            if session.next_feature_to_implement.as_ref() == Some(&feature.name) {
+                                line.push_str(" (next)");
+                            }
+                            if let Some(effort) = feature.estimated_effort {
+                                line.push_str(&format!(", effort {:?}", effort));
+                            }
+                            if let Some(milestone) = &feature.milestone {
+                                line.push_str(&format!(", milestone '{}'", milestone));
+                            }
+                            line
+                        })
+                        .collect();
+
+                    let data = json!({
+                        "nextFeature": session.next_feature_to_implement,
+                        "features": page
+                            .items
+                            .iter()
+                            .map(|feature| output::feature_json(feature))
+                            .collect::<Vec<_>>(),
+                        "total": page.total,
+                        "nextCursor": page.next_cursor,
+                    });
+
+                    if page.total == 0 {
+                        Ok(ToolOutput::new("No features found.", data))
+                    } else {
+                        Ok(ToolOutput::new(page.annotate(lines.join("\n")), data))
+                    }
+                }
+                "commitMessage" => {
+                    let params: CommitMessageParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let feature_name = params.feature_name.as_deref();
+                    let changes_made = params.changes_made.as_deref();
+
+                    let llm_client_ref = llm_client.as_deref();
+
+                    match session_manager
+                        .commit_message(session_name, feature_name, changes_made, llm_client_ref)
+                        .await
+                    {
+                        Ok(message) => Ok(ToolOutput::message(message)),
+                        Err(e) => Err(tool_error("suggest commit message", e)),
+                    }
+                }
+                "designEngine" => {
+                    let params: DesignEngineParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    let Some(engine) = parse_engine(params.engine.as_deref())? else {
+                        let session = session_manager
+                            .load_session(session_name)
+                            .await
+                            .map_err(|e| tool_error("load session", e))?
+                            .ok_or_else(|| {
+                                GameDesignerError::SessionNotFound(session_name.to_string())
+                            })?;
+
+                        let mut text = format!(
+                            "Current engine: {}\n\nAvailable engines:",
+                            session.engine.map_or("none", Engine::name)
+                        );
+                        for engine in Engine::ALL {
+                            text.push_str(&format!(
+                                "\n- {}: {}",
+                                engine.name(),
+                                engine.description()
+                            ));
+                        }
+                        let data = json!({
+                            "engine": session.engine.map(Engine::name),
+                            "available": engine_names(),
+                        });
+                        return Ok(ToolOutput::new(text, data).into_content(output_format));
+                    };
+
+                    match session_manager.set_engine(session_name, engine).await {
+                        Ok(previous) => Ok(ToolOutput::new(
+                            format!(
+                                "Engine of session '{}' changed from '{}' to '{}'.",
+                                session_name,
+                                previous.map_or("none", Engine::name),
+                                engine.name()
+                            ),
+                            json!({
+                                "engine": engine.name(),
+                                "previous": previous.map(Engine::name),
+                            }),
+                        )),
+                        Err(e) => Err(tool_error("change engine", e)),
+                    }
+                }
+                "sessionConfigure" => {
+                    let params: SessionConfigureParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let update = parse_metadata(params.metadata);
+                    let review_depth = parse_review_depth(params.review_depth.as_deref())?;
+
+                    if let Some(language) = params.language.as_deref() {
+                        session_manager
+                            .set_language(session_name, parse_language(Some(language)))
+                            .await
+                            .map_err(|e| {
+                                ToolError::ExecutionError(format!("Failed to set language: {}", e))
+                            })?;
+                    }
+
+                    if let Some(depth) = review_depth {
+                        session_manager
+                            .set_review_depth(session_name, depth)
+                            .await
+                            .map_err(|e| {
+                                ToolError::ExecutionError(format!(
+                                    "Failed to set review depth: {}",
+                                    e
+                                ))
+                            })?;
+                    }
+
+                    if let Some(panel) = params.review_panel {
+                        session_manager
+                            .set_review_panel(session_name, panel)
+                            .await
+                            .map_err(|e| {
+                                ToolError::ExecutionError(format!(
+                                    "Failed to set review panel: {}",
+                                    e
+                                ))
+                            })?;
+                    }
+
+                    let (language, review_depth, review_panel) =
+                        with_session(&session_manager, session_name, |session| {
+                            (
+                                session.language.clone(),
+                                session.review_depth,
+                                session.review_panel,
+                            )
+                        })
+                        .await?;
+
+                    match session_manager
+                        .configure_metadata(session_name, update)
+                        .await
+                    {
+                        Ok(metadata) => Ok(ToolOutput::new(
+                            format!(
+                                "Project metadata of session '{}':\n- Studio: {}\n- Authors: {}\n- License: {}\n- Copyright year: {}\n- Language: {}\n- Review depth: {}\n- Review panel: {}",
+                                session_name,
+                                metadata.studio.as_deref().unwrap_or("not set"),
+                                if metadata.authors.is_empty() {
+                                    "not set".to_string()
+                                } else {
+                                    metadata.authors.join(", ")
+                                },
+                                metadata.license.as_deref().unwrap_or("not set"),
+                                metadata
+                                    .copyright_year
+                                    .map_or_else(|| "not set".to_string(), |year| year.to_string()),
+                                language.as_deref().unwrap_or("English"),
+                                review_depth.name(),
+                                if review_panel { "on" } else { "off" }
+                            ),
+                            json!({
+                                "metadata": metadata,
+                                "language": language,
+                                "reviewDepth": review_depth,
+                                "reviewPanel": review_panel,
+                            }),
+                        )),
+                        Err(e) => Err(tool_error("configure session", e)),
+                    }
+                }
+                "conventionsSet" => {
+                    let params: ConventionsSetParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    match session_manager
+                        .set_conventions(session_name, params.conventions, params.replace)
+                        .await
+                    {
+                        Ok((conventions, tokens)) => {
+                            let max_tokens = conventions::max_tokens();
+                            let mut text = format!(
+                                "Conventions of session '{}' (about {} of at most {} tokens per \
+                                 prompt):\n",
+                                session_name, tokens, max_tokens
+                            );
+                            if conventions.is_empty() {
+                                text.push_str("- none\n");
+                            }
+                            for (name, rule) in &conventions {
+                                text.push_str(&format!("- {}: {}\n", name, rule));
+                            }
+
+                            Ok(ToolOutput::new(
+                                text.trim_end().to_string(),
+                                json!({
+                                    "conventions": conventions,
+                                    "tokens": tokens,
+                                    "maxTokens": max_tokens,
+                                }),
+                            ))
+                        }
+                        Err(e) => Err(tool_error("set conventions", e)),
+                    }
+                }
+                "configReload" => match this.service.reload().await {
+                    Ok(summary) => Ok(ToolOutput::message(summary)),
+                    Err(e) => Err(tool_error("reload configuration", e)),
+                },
+                "sessionList" => {
+                    let params: SessionListParams = parse_params(&arguments)?;
+
+                    // Without an explicit project every namespace is listed
+                    let projects = if arguments.get("project").is_some() {
+                        vec![project.clone()]
+                    } else {
+                        let projects = project::list().map_err(|e| {
+                            ToolError::ExecutionError(format!("Failed to list projects: {}", e))
+                        })?;
+                        std::iter::once(None)
+                            .chain(projects.into_iter().map(Some))
+                            .collect()
+                    };
+
+                    // One entry per session; a project without sessions
+                    // is an entry too, so that it shows up in the listing
+                    let mut entries = Vec::new();
+                    for project in projects {
+                        let manager = this
+                            .service
+                            .sessions(project.as_deref())
+                            .await
+                            .map_err(|e| tool_error("open project", e))?;
+                        let ids = manager.session_ids().map_err(|e| {
+                            ToolError::ExecutionError(format!("Failed to list sessions: {}", e))
+                        })?;
+
+                        if ids.is_empty() {
+                            entries.push((project, None));
+                        } else {
+                            entries.extend(ids.into_iter().map(|id| (project.clone(), Some(id))));
+                        }
+                    }
+
+                    let page = paginate(entries, &params.page);
+
+                    // The page's sessions, grouped by project
+                    let mut namespaces: Vec<(Option<String>, Vec<String>)> = Vec::new();
+                    for (project, id) in &page.items {
+                        if namespaces.last().is_none_or(|(last, _)| last != project) {
+                            namespaces.push((project.clone(), Vec::new()));
+                        }
+                        if let (Some(id), Some((_, ids))) = (id, namespaces.last_mut()) {
+                            ids.push(id.clone());
+                        }
+                    }
+
+                    let mut listing = match project::default_project() {
+                        Some(project) => format!("Default project: {}\n", project),
+                        None => "Default project: none\n".to_string(),
+                    };
+                    for (project, ids) in &namespaces {
+                        listing.push_str(&format!(
+                            "\n## {}\n",
+                            project.as_deref().unwrap_or("(no project)")
+                        ));
+                        if ids.is_empty() {
+                            listing.push_str("No sessions.\n");
+                        }
+                        for id in ids {
+                            listing.push_str(&format!("- {}\n", id));
+                        }
+                    }
+
+                    let projects = namespaces
+                        .iter()
+                        .map(|(project, ids)| json!({ "project": project, "sessions": ids }))
+                        .collect::<Vec<_>>();
+
+                    Ok(ToolOutput::new(
+                        page.annotate(listing.trim_end().to_string()),
+                        json!({
+                            "defaultProject": project::default_project(),
+                            "projects": projects,
+                            "total": page.total,
+                            "nextCursor": page.next_cursor,
+                        }),
+                    ))
+                }
+                "sessionHistory" => {
+                    let params: SessionHistoryParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let filter = parse_event_filter(&params)?;
+
+                    match session_manager
+                        .session_history(session_name, &filter, usize::MAX)
+                        .await
+                    {
+                        Ok(events) => {
+                            let page = paginate(events, &params.page);
+                            let data = json!({
+                                "events": page.items,
+                                "total": page.total,
+                                "nextCursor": page.next_cursor,
+                            });
+                            Ok(ToolOutput::new(data.to_string(), data))
+                        }
+                        Err(e) => Err(tool_error("read session history", e)),
+                    }
+                }
+                "designSearch" => {
+                    let params: DesignSearchParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    if params.query.trim().is_empty() {
+                        return Err(ToolError::InvalidParameters(
+                            "query cannot be empty for designSearch".to_string(),
+                        ));
+                    }
+                    let limit = params.limit.unwrap_or(5).clamp(1, 50) as usize;
+
+                    match session_manager
+                        .design_search(session_name, params.query.trim(), limit)
+                        .await
+                    {
+                        Ok(matches) => {
+                            let mut text = String::new();
+                            if matches.is_empty() {
+                                text.push_str(&format!(
+                                    "Nothing in the history of '{}' matches the query.",
+                                    session_name
+                                ));
+                            }
+                            for (rank, found) in matches.iter().enumerate() {
+                                text.push_str(&format!(
+                                    "{}. [{}] {} (score {:.2}, event #{} {} at {})\n{}\n\n",
+                                    rank + 1,
+                                    found.kind.name(),
+                                    found.feature.as_deref().unwrap_or("no feature"),
+                                    found.score,
+                                    found.event.seq,
+                                    found.event.kind.as_str(),
+                                    found.event.timestamp.format("%Y-%m-%d %H:%M UTC"),
+                                    found.snippet
+                                ));
+                            }
+
+                            Ok(ToolOutput::new(
+                                text.trim_end().to_string(),
+                                json!({ "matches": matches }),
+                            ))
+                        }
+                        Err(e) => Err(tool_error("search session history", e)),
+                    }
+                }
+                "designDiff" => {
+                    let params: DesignDiffParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let other_session = params.other_session.as_str();
+                    let other_project = match params.other_project.as_deref() {
+                        Some(other_project) => project::normalize(other_project)
+                            .map_err(|e| ToolError::InvalidParameters(e.to_string()))?,
+                        None => project.clone(),
+                    };
+
+                    let load = |manager: Arc<SessionManager>, session_name: &str| {
+                        let session_name = session_name.to_string();
+                        async move {
+                            manager
+                                .load_session(&session_name)
+                                .await
+                                .map_err(|e| tool_error("load session", e))?
+                                .ok_or_else(|| {
+                                    ToolError::from(GameDesignerError::SessionNotFound(
+                                        session_name.clone(),
+                                    ))
+                                })
+                        }
+                    };
+
+                    let base = load(session_manager.clone(), session_name).await?;
+                    let other_manager = this
+                        .service
+                        .sessions(other_project.as_deref())
+                        .await
+                        .map_err(|e| tool_error("open project", e))?;
+                    let other = load(other_manager, other_session).await?;
+
+                    let diff = diff::diff(&base, &other);
+                    let data = serde_json::to_value(&diff).map_err(|e| {
+                        ToolError::ExecutionError(format!("Failed to serialize diff: {}", e))
+                    })?;
+
+                    match params.format {
+                        DiffFormat::Json => Ok(ToolOutput::new(
+                            serde_json::to_string_pretty(&data).map_err(|e| {
+                                ToolError::ExecutionError(format!(
+                                    "Failed to serialize diff: {}",
+                                    e
+                                ))
+                            })?,
+                            data,
+                        )),
+                        DiffFormat::Markdown => Ok(ToolOutput::new(diff.to_markdown(), data)),
+                    }
+                }
+                "designUndo" => {
+                    let params: DesignUndoParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+                    let expected_seq = params.expected_seq;
+
+                    match session_manager.undo(session_name, expected_seq).await {
+                        Ok(outcome) => Ok(ToolOutput::new(
+                            describe_undo(&outcome),
+                            json!({ "seq": outcome.seq, "events": outcome.events }),
+                        )),
+                        Err(e) => Err(tool_error("undo", e)),
+                    }
+                }
+                "designFreeze" => {
+                    let params: DesignFreezeParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    match session_manager.freeze(session_name, params.reason).await {
+                        Ok(freeze) => {
+                            let mut text = format!(
+                                "Session '{}' is frozen since {}. Read tools and exports still \
+                                 work; tools changing the session fail until designUnfreeze is \
+                                 called with confirm: true.",
+                                session_name,
+                                freeze.frozen_at.format("%Y-%m-%d %H:%M UTC")
+                            );
+                            if let Some(reason) = &freeze.reason {
+                                text.push_str(&format!("\nReason: {}", reason));
+                            }
+
+                            Ok(ToolOutput::new(
+                                text,
+                                json!({
+                                    "frozen": true,
+                                    "frozenAt": freeze.frozen_at,
+                                    "reason": freeze.reason,
+                                }),
+                            ))
+                        }
+                        Err(e) => Err(tool_error("freeze session", e)),
+                    }
+                }
+                "designUnfreeze" => {
+                    let params: DesignUnfreezeParams = parse_params(&arguments)?;
+                    let session_name = params.session_name.as_str();
+
+                    if !params.confirm {
+                        return Err(GameDesignerError::InvalidArguments(format!(
+                            "Unfreezing session '{}' makes it changeable again; call designUnfreeze \
+                             with confirm: true to do so",
+                            session_name
+                        ))
+                        .into());
+                    }
+
+                    match session_manager.unfreeze(session_name).await {
+                        Ok(Some(freeze)) => Ok(ToolOutput::new(
+                            format!(
+                                "Session '{}', frozen since {}, can be changed again.",
+                                session_name,
+                                freeze.frozen_at.format("%Y-%m-%d %H:%M UTC")
+                            ),
+                            json!({ "frozen": false, "wasFrozenAt": freeze.frozen_at }),
+                        )),
+                        Ok(None) => Ok(ToolOutput::new(
+                            format!("Session '{}' was not frozen.", session_name),
+                            json!({ "frozen": false, "wasFrozenAt": null }),
+                        )),
+                        Err(e) => Err(tool_error("unfreeze session", e)),
+                    }
+                }
+                "designUsage" => {
+                    let params: SessionParams = parse_params(&arguments)?;
+                    let key = session_key(project.as_deref(), &params.session_name);
+
+                    let limits = this.service.guard.config();
+                    let (calls, tripped) = this.service.guard.usage(&key);
+                    let tokens = this.service.tokens.used_today(&key);
+                    let remaining = limits
+                        .daily_token_budget
+                        .map(|budget| budget.saturating_sub(tokens));
+                    let resets_in = this.service.tokens.resets_in();
+
+                    let mut text = format!(
+                        "Usage of session '{}':\n\
+                         - Tool calls in the last minute: {} of {}\n",
+                        params.session_name, calls, limits.max_calls_per_minute
+                    );
+                    match tripped {
+                        Some(left) => text.push_str(&format!(
+                            "- Circuit breaker: tripped, calls are rejected for {} more seconds\n",
+                            left.as_secs() + 1
+                        )),
+                        None => text.push_str("- Circuit breaker: not tripped\n"),
+                    }
+                    match (limits.daily_token_budget, remaining) {
+                        (Some(budget), Some(remaining)) => text.push_str(&format!(
+                            "- LLM tokens today: {} of {} ({} remaining), resets in {}h {}m",
+                            tokens,
+                            budget,
+                            remaining,
+                            resets_in.as_secs() / 3600,
+                            resets_in.as_secs() % 3600 / 60
+                        )),
+                        _ => text
+                            .push_str(&format!("- LLM tokens today: {} (no daily budget)", tokens)),
+                    }
+
+                    Ok(ToolOutput::new(
+                        text,
+                        json!({
+                            "session": params.session_name,
+                            "callsLastMinute": calls,
+                            "maxCallsPerMinute": limits.max_calls_per_minute,
+                            "trippedForSecs": tripped.map(|left| left.as_secs() + 1),
+                            "tokensToday": tokens,
+                            "dailyTokenBudget": limits.daily_token_budget,
+                            "tokensRemaining": remaining,
+                            "resetsInSecs": resets_in.as_secs(),
+                        }),
+                    ))
+                }
+                "jobStatus" => {
+                    let params: JobParams = parse_params(&arguments)?;
+                    let job = this.service.jobs.get(&params.job_id).ok_or_else(|| {
+                        ToolError::InvalidParameters(format!("Unknown job '{}'", params.job_id))
+                    })?;
+
+                    let elapsed = job.finished_at.unwrap_or_else(chrono::Utc::now) - job.created_at;
+                    let subject = match &job.session {
+                        Some(session) => format!("{} of session '{}'", job.tool, session),
+                        None => job.tool.clone(),
+                    };
+                    let text = match job.status {
+                        JobStatus::Running => format!(
+                            "Job {} ({}) has been running for {} seconds.",
+                            job.id,
+                            subject,
+                            elapsed.num_seconds()
+                        ),
+                        JobStatus::Succeeded => format!(
+                            "Job {} ({}) succeeded after {} seconds. Fetch the output with \
+                             jobResult.",
+                            job.id,
+                            subject,
+                            elapsed.num_seconds()
+                        ),
+                        JobStatus::Failed => format!(
+                            "Job {} ({}) failed after {} seconds: {}",
+                            job.id,
+                            subject,
+                            elapsed.num_seconds(),
+                            job.error.as_deref().unwrap_or_default()
+                        ),
+                    };
+
+                    Ok(ToolOutput::new(
+                        text,
+                        json!({
+                            "jobId": job.id,
+                            "tool": job.tool,
+                            "project": job.project,
+                            "session": job.session,
+                            "status": job.status,
+                            "createdAt": job.created_at,
+                            "finishedAt": job.finished_at,
+                            "error": job.error,
+                        }),
+                    ))
+                }
+                "jobResult" => {
+                    let params: JobParams = parse_params(&arguments)?;
+                    let job = this.service.jobs.get(&params.job_id).ok_or_else(|| {
+                        ToolError::InvalidParameters(format!("Unknown job '{}'", params.job_id))
+                    })?;
+
+                    match job.status {
+                        // Returned in the format the job was started with
+                        JobStatus::Succeeded => return Ok(job.content),
+                        JobStatus::Running => Ok(ToolOutput::new(
+                            format!(
+                                "Job {} is still running; poll jobStatus until it has succeeded.",
+                                job.id
+                            ),
+                            json!({ "jobId": job.id, "status": job.status }),
+                        )),
+                        JobStatus::Failed => Err(ToolError::ExecutionError(format!(
+                            "Job {} ({}) failed: {}",
+                            job.id,
+                            job.tool,
+                            job.error.as_deref().unwrap_or_default()
+                        ))),
+                    }
+                }
+                _ => Err(GameDesignerError::ToolNotFound(tool_name.clone()).into()),
+            };
+
+            output.map(|mut output| {
+                if !redactions.is_empty() {
+                    note_redactions(&mut output, &redactions);
+                }
+                if !shortened.is_empty() {
+                    note_shortened(&mut output, &shortened);
+                }
+                output.into_content(output_format)
+            })
+        };
+
+        Box::pin(
+            async move {
+                let started_at = Instant::now();
+
+                // Dropping the call on timeout or cancellation aborts its
+                // pending LLM request and releases the session lock
+                let call = designer_llm::metered(meter.clone(), call);
+                let call = designer_llm::sampled(metric_tool.clone(), sampling, call);
+                let limited = async {
+                    match timeout {
+                        Some(timeout) => tokio::time::timeout(timeout, call).await.ok(),
+                        None => Some(call.await),
+                    }
+                };
+                let (result, outcome) = tokio::select! {
+                    result = limited => match result {
+                        Some(result) => {
+                            let outcome = if result.is_ok() { "ok" } else { "error" };
+                            (result, outcome)
+                        }
+                        None => (
+                            Err(GameDesignerError::Timeout {
+                                tool: metric_tool.clone(),
+                                after: timeout.unwrap_or_default(),
+                            }
+                            .into()),
+                            "timeout",
+                        ),
+                    },
+                    () = handle.cancelled() => (
+                        Err(GameDesignerError::Cancelled {
+                            tool: metric_tool.clone(),
+                        }
+                        .into()),
+                        "cancelled",
+                    ),
+                };
+                // Every failure reaches the client with a code
+                let result = result.map_err(error::normalize);
+                let elapsed = started_at.elapsed();
+
+                if let Some(key) = &charged_key {
+                    ledger.charge(key, meter.load(Ordering::Relaxed));
+                }
+
+                match &result {
+                    Ok(_) => tracing::info!(
+                        duration_ms = elapsed.as_millis() as u64,
+                        "Tool call succeeded"
+                    ),
+                    Err(e) => tracing::warn!(
+                        duration_ms = elapsed.as_millis() as u64,
+                        error = %e,
+                        "Tool call failed"
+                    ),
+                }
+
+                metrics::counter!(
+                    "gamedesignerd_tool_calls_total",
+                    "tool" => metric_tool.clone(),
+                    "outcome" => outcome
+                )
+                .increment(1);
+                metrics::histogram!(
+                    "gamedesignerd_tool_call_duration_seconds",
+                    "tool" => metric_tool
+                )
+                .record(elapsed.as_secs_f64());
+
+                result
+            }
+            .instrument(span),
+        )
+    }
+}
+
+impl Router for GameToolsRouter {
+    fn name(&self) -> String {
+        "game-designer".to_owned()
+    }
+
+    fn instructions(&self) -> String {
+        let mut instructions = "This server provides tools for managing a game design process. \
+        You can create design sessions, get an overview, receive the next feature to implement, \
+        submit a review of implemented features, reply to questions from the review, \
+        and ask ad-hoc questions about the current feature or design."
+            .to_owned();
+
+        // The capabilities of this SDK version can't describe a missing
+        // provider, so the instructions of the handshake do
+        if !self.service.has_llm() {
+            instructions.push_str(&format!(
+                " No LLM provider is configured, so the server runs in a degraded mode. \
+                 Unavailable tools, which fail with an llm_unavailable error: {}. \
+                 Degraded tools, which work without the designer: {}. \
+                 All other tools work as usual. To enable the designer: {}.",
+                LLM_REQUIRED_TOOLS.join(", "),
+                LLM_TOOLS
+                    .iter()
+                    .filter(|tool| !LLM_REQUIRED_TOOLS.contains(tool))
+                    .copied()
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                error::LLM_SETUP.join("; ")
+            ));
+        }
+
+        instructions
+    }
+
+    fn capabilities(&self) -> ServerCapabilities {
+        CapabilitiesBuilder::new()
+            .with_tools(true)
+            .with_resources(false, false)
+            .with_prompts(false)
+            .build()
+    }
+
+    fn list_tools(&self) -> Vec<Tool> {
+        let mut tools = vec![
+            Tool::new(
+                "designNew".to_string(),
+                "Create a new game design session with a provided description. Without an LLM \
+                 provider the description is stored as-is instead of being expanded."
+                    .to_string(),
+                params::schema::<DesignNewParams>(),
+            ),
+            Tool::new(
+                "designOverview".to_string(),
+                "Get the initial game design goals for a session.".to_string(),
+                params::schema::<SessionParams>(),
+            ),
+            Tool::new(
+                "nextFeature".to_string(),
+                "Get the detailed specification for the next feature to implement. Without an LLM \
+                 provider only already planned features (from templates, imports or reuse) are \
+                 handed out. With candidates, the designer proposes several ranked alternatives \
+                 instead, to be picked with featureSelect."
+                    .to_string(),
+                params::schema::<NextFeatureParams>(),
+            ),
+            Tool::new(
+                "featureSelect".to_string(),
+                "Pick one of the candidates proposed by nextFeature as the next feature to \
+                 implement and get its specification. The other candidates are passed over and \
+                 not proposed again."
+                    .to_string(),
+                params::schema::<FeatureSelectParams>(),
+            ),
+            Tool::new(
+                "featureReject".to_string(),
+                "Reject the feature proposed by nextFeature before working on it. It is buried in \
+                 the session's idea graveyard with the reason: later proposals repeating it are \
+                 skipped, and proposals resembling it note how they differ."
+                    .to_string(),
+                params::schema::<FeatureRejectParams>(),
+            ),
+            Tool::new(
+                "featureReview".to_string(),
+                "Submit a comprehensive report of changes made for review by the designer LLM. \
+                 Requires an LLM provider."
+                    .to_string(),
+                params::schema::<FeatureReviewParams>(),
+            ),
+            Tool::new(
+                "reviewReply".to_string(),
+                "Reply to questions raised by the designer LLM during a feature review. Requires an \
+                 LLM provider."
+                    .to_string(),
+                params::schema::<ReviewReplyParams>(),
+            ),
+            Tool::new(
+                "humanApprove".to_string(),
+                "Sign off a feature the designer accepted, or send it back for rework, when the \
+                 configuration requires human approval before features count as reviewed. The \
+                 decision needs the signature a person prints with `gamedesignerd sign-approval`."
+                    .to_string(),
+                params::schema::<HumanApproveParams>(),
+            ),
+            Tool::new(
+                "featureAsk".to_string(),
+                "Ask an ad-hoc question about the current feature or design. Requires an LLM provider."
+                    .to_string(),
+                params::schema::<FeatureAskParams>(),
+            ),
+            Tool::new(
+                "sessionEvents".to_string(),
+                "Read the session's state-change events incrementally, starting at a cursor."
+                    .to_string(),
+                params::schema::<SessionEventsParams>(),
+            ),
+            Tool::new(
+                "backlogImport".to_string(),
+                "Import open issues from GitHub or Linear as planned features, normalized by the designer LLM. Requires an LLM provider."
+                    .to_string(),
+                params::schema::<BacklogImportParams>(),
+            ),
+            Tool::new(
+                "designImport".to_string(),
+                "Create a new session from a project's existing backlog: pull the open issues \
+                 from GitHub or Linear and have the designer LLM consolidate them into a design \
+                 document and a planned feature list. Requires an LLM provider."
+                    .to_string(),
+                params::schema::<DesignImportParams>(),
+            ),
+            Tool::new(
+                "boardSync".to_string(),
+                "Mirror the planned features into the team's tracker. With target github every \
+                 feature gets an issue with a status label, and statuses changed on either side \
+                 since the last sync are carried over to the other (the session wins when both \
+                 changed). With target trello the features are returned as a Trello board export."
+                    .to_string(),
+                params::schema::<BoardSyncParams>(),
+            ),
+            Tool::new(
+                "designReviewRequest".to_string(),
+                "Propose an edit of the game design document. The designer LLM reviews it against the design pillars and existing features and either merges it as a new design version or returns objections. Requires an LLM provider."
+                    .to_string(),
+                params::schema::<DesignReviewRequestParams>(),
+            ),
+            Tool::new(
+                "qaHistory".to_string(),
+                "Retrieve previously answered featureAsk questions so they don't need to be asked again."
+                    .to_string(),
+                params::schema::<QaHistoryParams>(),
+            ),
+            Tool::new(
+                "designPersona".to_string(),
+                "Change the designer persona of a session, which swaps the designer's system prompts and review strictness. Call without persona to list the available presets."
+                    .to_string(),
+                params::schema::<DesignPersonaParams>(),
+            ),
+            Tool::new(
+                "designInterview".to_string(),
+                "Start a design session without a polished description: answers a short \
+                 onboarding interview (genre, platform, scope, inspiration, constraints) one \
+                 question per call, then synthesizes the initial design document. Without an LLM \
+                 provider the answers themselves become the design document."
+                    .to_string(),
+                params::schema::<DesignInterviewParams>(),
+            ),
+            Tool::new(
+                "assetList".to_string(),
+                "List the art and audio assets (sprites, SFX, shaders, ...) required by the \
+                 planned features of a session, optionally exported as CSV for artists."
+                    .to_string(),
+                params::schema::<AssetListParams>(),
+            ),
+            Tool::new(
+                "designExport".to_string(),
+                "Export the session as a Markdown game design document, optionally writing it \
+                 into the game repository and keeping it regenerated after every change."
+                    .to_string(),
+                params::schema::<DesignExportParams>(),
+            ),
+            Tool::new(
+                "traceabilityReport".to_string(),
+                "Map every reviewed feature of a session to the commits that implemented it."
+                    .to_string(),
+                params::schema::<TraceabilityReportParams>(),
+            ),
+            Tool::new(
+                "designLint".to_string(),
+                "Check the planned and implemented features against the session's design \
+                 pillars and report contradictions with severity and suggested resolutions. \
+                 Requires an LLM provider."
+                    .to_string(),
+                params::schema::<SessionParams>(),
+            ),
+            Tool::new(
+                "designBrainstorm".to_string(),
+                "Brainstorm mechanics and twists on a topic such as \"boss fight ideas\": many raw \
+                 ideas first, then a shortlist scored against the design pillars. Shortlisted ideas \
+                 join the session's idea pool, which nextFeature draws on. Requires an LLM \
+                 provider."
+                    .to_string(),
+                params::schema::<DesignBrainstormParams>(),
+            ),
+            Tool::new(
+                "storyOutline".to_string(),
+                "Show the story of a narrative-driven game: the dialogue style guide, the \
+                 characters and the acts with their beats. With generate, the designer drafts \
+                 the story from the design document and planned features first (requires an \
+                 LLM provider); styleGuide sets the dialogue style guide."
+                    .to_string(),
+                params::schema::<StoryOutlineParams>(),
+            ),
+            Tool::new(
+                "characterSheet".to_string(),
+                "Show a character's sheet, or add a character or update the fields given. The \
+                 sheet lists the beats the character appears in."
+                    .to_string(),
+                params::schema::<CharacterSheetParams>(),
+            ),
+            Tool::new(
+                "beatAdd".to_string(),
+                "Add a story beat to an act, optionally naming its characters and the planned \
+                 feature through which the player experiences it. A new act name appends an act."
+                    .to_string(),
+                params::schema::<BeatAddParams>(),
+            ),
+            Tool::new(
+                "levelBrief".to_string(),
+                "Write or show the structured brief of a level: layout, difficulty curve, the \
+                 required mechanics (referencing planned features) and asset needs. A new level \
+                 needs goals; status moves a level through briefed, blockout, playtesting, \
+                 polished and final. Briefing requires an LLM provider."
+                    .to_string(),
+                params::schema::<LevelBriefParams>(),
+            ),
+            Tool::new(
+                "economyModel".to_string(),
+                "Declare or show the game's economy: resources, their sources and sinks, and \
+                 the progression curves they pay for. simulate has the designer estimate \
+                 time-to-unlock tables and flag planned features whose rewards break the \
+                 economy; it requires an LLM provider."
+                    .to_string(),
+                params::schema::<EconomyModelParams>(),
+            ),
+            Tool::new(
+                "monetizationReview".to_string(),
+                "Review the game's monetization features against policy rules (no loot boxes \
+                 for minors, disclosed odds, no dark patterns, plus the configured rules) and \
+                 the gambling laws of the target regions. Returns a risk report with \
+                 mitigations, attached to the session. Requires an LLM provider."
+                    .to_string(),
+                params::schema::<MonetizationReviewParams>(),
+            ),
+            Tool::new(
+                "debtList".to_string(),
+                "List the technical debt of a session: caveats of reviews that accepted an \
+                 implementation which works but is messy, with their priority and whether a \
+                 feature pays them down. nextFeature periodically proposes paying down \
+                 high-priority items."
+                    .to_string(),
+                params::schema::<DebtListParams>(),
+            ),
+            Tool::new(
+                "uiCritique".to_string(),
+                "Have a vision-capable model critique a screenshot of a feature against its \
+                 specification: readability, hierarchy and affordance issues with their severity \
+                 and a suggested fix. The screenshot is stored with the session and the critique \
+                 with the feature. Requires an LLM provider with a vision model."
+                    .to_string(),
+                params::schema::<UiCritiqueParams>(),
+            ),
+            Tool::new(
+                "testPlan".to_string(),
+                "Get the test plan of a reviewed feature: a checklist of manual playtest steps \
+                 and suggested automated test cases, stored with the feature and optionally \
+                 written to a Markdown file. Writing a plan requires an LLM provider."
+                    .to_string(),
+                params::schema::<TestPlanParams>(),
+            ),
+            Tool::new(
+                "statusReport".to_string(),
+                "Summarize a session's activity since a date as a Markdown status report for \
+                 stand-up notes: features completed, in review and blocked, work and LLM usage, \
+                 and the upcoming milestone. Built from the session's event log."
+                    .to_string(),
+                params::schema::<StatusReportParams>(),
+            ),
+            Tool::new(
+                "crateDoc".to_string(),
+                "Look up the documentation of a Rust crate, or of an item in it, on docs.rs. \
+                 The rustdoc page is stripped of its navigation and summarized; results are \
+                 cached per crate version and item."
+                    .to_string(),
+                params::schema::<CrateDocParams>(),
+            ),
+            Tool::new(
+                "startWork".to_string(),
+                "Start the work timer of a feature to track the actual time spent on it."
+                    .to_string(),
+                params::schema::<WorkParams>(),
+            ),
+            Tool::new(
+                "stopWork".to_string(),
+                "Stop the work timer of a feature and add the elapsed time to it."
+                    .to_string(),
+                params::schema::<WorkParams>(),
+            ),
+            Tool::new(
+                "reuseFromSession".to_string(),
+                "Search other design sessions for an already-designed system (e.g. 'inventory \
+                 system') and import its features and decisions, adapted to this session. \
+                 Requires an LLM provider."
+                    .to_string(),
+                params::schema::<ReuseFromSessionParams>(),
+            ),
+            Tool::new(
+                "scopeReport".to_string(),
+                "Summarize the estimated effort and risk of the planned features per milestone \
+                 and warn when the roadmap exceeds the effort budget."
+                    .to_string(),
+                params::schema::<ScopeReportParams>(),
+            ),
+            Tool::new(
+                "devlogGenerate".to_string(),
+                "Turn the features accepted over a date range into a player-facing devlog entry \
+                 in Markdown, ready for itch.io or Steam. Requires an LLM provider."
+                    .to_string(),
+                params::schema::<DevlogGenerateParams>(),
+            ),
+            Tool::new(
+                "designRetro".to_string(),
+                "Write the retrospective of a finished session from its event log and review \
+                 history: how the scope changed, which features churned in review, how the \
+                 effort estimates held up and the lessons learned. Saved as Markdown."
+                    .to_string(),
+                params::schema::<DesignRetroParams>(),
+            ),
+            Tool::new(
+                "featureList".to_string(),
+                "List the features of a session with their status, estimates and milestone."
+                    .to_string(),
+                params::schema::<FeatureListParams>(),
+            ),
+            Tool::new(
+                "commitMessage".to_string(),
+                "Suggest a Conventional Commits message for the work on a feature, based on the \
+                 staged diff of the session's repository (see designNew 'repo') and the changes \
+                 report. The message ends with a 'Feature: <name>' trailer tying the commit to the \
+                 design session. Without an LLM provider a plain 'feat' message is returned."
+                    .to_string(),
+                params::schema::<CommitMessageParams>(),
+            ),
+            Tool::new(
+                "designEngine".to_string(),
+                "Set the engine a session's game is built with (Bevy, Godot/gdext, Unity or \
+                 custom), so that feature specifications include engine-specific implementation \
+                 hints. Call without engine to show the current one and the options."
+                    .to_string(),
+                params::schema::<DesignEngineParams>(),
+            ),
+            Tool::new(
+                "sessionConfigure".to_string(),
+                "Set the project metadata of a session (studio name, authors, license, copyright \
+                 year), stamped into every export such as the design document and devlogs, and \
+                 the language the designer writes in. Only the given fields change; call without \
+                 any to show the current settings."
+                    .to_string(),
+                params::schema::<SessionConfigureParams>(),
+            ),
+            Tool::new(
+                "conventionsSet".to_string(),
+                "Register project conventions of a session, such as naming rules, code style, \
+                 engine version or target FPS, by name. They are injected into every feature \
+                 specification and review prompt, so their total size is limited. An empty rule \
+                 removes a convention; call without conventions to show the current ones."
+                    .to_string(),
+                params::schema::<ConventionsSetParams>(),
+            ),
+            Tool::new(
+                "configReload".to_string(),
+                "Admin tool: re-read the configuration files and webhooks.json and apply model, \
+                 temperature, budgets, circuit breaker limits, the default persona and webhook \
+                 endpoints without restarting the server. Equivalent to sending SIGHUP."
+                    .to_string(),
+                params::schema::<NoParams>(),
+            ),
+            Tool::new(
+                "sessionList".to_string(),
+                "List the stored design sessions grouped by project. Pass `project` to list \
+                 only the sessions of that project."
+                    .to_string(),
+                params::schema::<SessionListParams>(),
+            ),
+            Tool::new(
+                "sessionHistory".to_string(),
+                "Audit trail of a session: every recorded state change (feature proposed, review \
+                 submitted, verdict, design revised, ...) with its timestamp, filtered by type \
+                 and time range."
+                    .to_string(),
+                params::schema::<SessionHistoryParams>(),
+            ),
+            Tool::new(
+                "designSearch".to_string(),
+                "Search the history of a session by meaning, e.g. \"that decision about save \
+                 files\": features, implementation reports, reviews, replies and featureAsk \
+                 answers, ranked by semantic similarity. Each snippet links back to the event it \
+                 came from (see sessionHistory)."
+                    .to_string(),
+                params::schema::<DesignSearchParams>(),
+            ),
+            Tool::new(
+                "designDiff".to_string(),
+                "Compare two sessions, e.g. a session and a variant of it: pillars, features \
+                 added, removed and changed, and milestone shifts."
+                    .to_string(),
+                params::schema::<DesignDiffParams>(),
+            ),
+            Tool::new(
+                "designUndo".to_string(),
+                "Revert the most recent change of a session (e.g. a mistaken review verdict), \
+                 restoring the state from before it. Repeat to undo further changes."
+                    .to_string(),
+                params::schema::<DesignUndoParams>(),
+            ),
+            Tool::new(
+                "designFreeze".to_string(),
+                "Make a session read-only, e.g. after the game shipped, for post-mortem \
+                 analysis. Read tools and exports keep working; tools that would change the \
+                 session fail with a session_frozen error."
+                    .to_string(),
+                params::schema::<DesignFreezeParams>(),
+            ),
+            Tool::new(
+                "designUnfreeze".to_string(),
+                "Make a frozen session changeable again. Requires confirm: true.".to_string(),
+                params::schema::<DesignUnfreezeParams>(),
+            ),
+            Tool::new(
+                "designUsage".to_string(),
+                "Show a session's remaining quota: tool calls in the last minute against the \
+                 rate limit, whether its circuit breaker is tripped, and the LLM tokens used \
+                 today against its daily token budget."
+                    .to_string(),
+                params::schema::<SessionParams>(),
+            ),
+            Tool::new(
+                "jobStatus".to_string(),
+                "Show the status of a background job started with `background: true`: running, \
+                 succeeded or failed, and for how long it has been running."
+                    .to_string(),
+                params::schema::<JobParams>(),
+            ),
+            Tool::new(
+                "jobResult".to_string(),
+                "Fetch the output of a succeeded background job, exactly as the call would have \
+                 returned it. Results are kept for 7 days, across server restarts."
+                    .to_string(),
+                params::schema::<JobParams>(),
+            ),
+        ];
+
+        // Every tool works within a project namespace and can return its
+        // result as JSON
+        for tool in &mut tools {
+            let background = LLM_TOOLS.contains(&tool.name.as_str());
+
+            if let Some(properties) = tool
+                .input_schema
+                .get_mut("properties")
+                .and_then(Value::as_object_mut)
+            {
+                properties.insert(
+                    "project".to_string(),
+                    json!({
+                        "type": "string",
+                        "description": "Project namespace of the session (optional). Defaults \
+                                        to the server's project, usually derived from its \
+                                        working directory; an empty string selects the sessions \
+                                        without a project."
+                    }),
+                );
+                properties.insert(
+                    "outputFormat".to_string(),
+                    json!({
+                        "type": "string",
+                        "enum": OutputFormat::NAMES,
+                        "description": "Output format (optional, defaults to 'text'). 'json' \
+                                        returns the result as a JSON block of machine-readable \
+                                        fields, 'both' returns the text followed by the JSON \
+                                        block."
+                    }),
+                );
+
+                // Tools calling the LLM may take minutes
+                if background {
+                    properties.insert(
+                        "background".to_string(),
+                        json!({
+                            "type": "boolean",
+                            "description": "Run the call as a background job (optional, \
+                                            defaults to false). The call returns a jobId right \
+                                            away; poll jobStatus and fetch the output with \
+                                            jobResult."
+                        }),
+                    );
+                    properties.insert(
+                        "temperature".to_string(),
+                        json!({
+                            "type": "number",
+                            "minimum": 0,
+                            "description": "Sampling temperature of the call's LLM requests \
+                                            (optional, clamped to 0-2). Defaults to the tool's \
+                                            configured temperature; higher values give more \
+                                            varied ideas."
+                        }),
+                    );
+                    properties.insert(
+                        "topP".to_string(),
+                        json!({
+                            "type": "number",
+                            "minimum": 0,
+                            "description": "Nucleus sampling cutoff of the call's LLM requests \
+                                            (optional, clamped to 0-1)."
+                        }),
+                    );
+                    properties.insert(
+                        "maxTokens".to_string(),
+                        json!({
+                            "type": "integer",
+                            "minimum": 1,
+                            "description": "Longest completion of each of the call's LLM \
+                                            requests (optional)."
+                        }),
+                    );
+                }
+            }
+        }
+
+        // Without a provider, clients see up front which tools can't help
+        if !self.service.has_llm() {
+            for tool in &mut tools {
+                let flag = if LLM_REQUIRED_TOOLS.contains(&tool.name.as_str()) {
+                    "[Unavailable: no LLM provider is configured] "
+                } else if LLM_TOOLS.contains(&tool.name.as_str()) {
+                    "[Degraded: no LLM provider is configured] "
+                } else {
+                    continue;
+                };
+                tool.description.insert_str(0, flag);
+            }
+        }
+
+        tools
+    }
+
+    fn call_tool(
+        &self,
+        tool_name: &str,
+        arguments: Value,
+    ) -> Pin<Box<dyn futures::Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>>
+    {
+        // Claimed before the call runs, so that the claims follow the order
+        // in which the requests were read
+        let handle = self.cancellations.claim(tool_name);
+
+        if arguments.get("background").and_then(Value::as_bool) == Some(true) {
+            return self.start_job(tool_name, arguments);
+        }
+
+        self.run_tool(tool_name, arguments, handle)
+    }
+
+    /// The design documents sessions were imported from.
+    fn list_resources(&self) -> Vec<Resource> {
+        let storage = self.service.session_manager.storage();
+        let projects = project::list().unwrap_or_else(|e| {
+            tracing::warn!("Failed to list projects: {}", e);
+            Vec::new()
+        });
+
+        std::iter::once(None)
+            .chain(projects.iter().map(|project| Some(project.as_str())))
+            .flat_map(|project| {
+                document::sessions(storage, &project::dir(project))
+                    .into_iter()
+                    .map(move |session| (project, session))
+            })
+            .filter_map(|(project, session)| {
+                let name = match project {
+                    Some(project) => format!("Design document of {}/{}", project, session),
+                    None => format!("Design document of {}", session),
+                };
+
+                Resource::new(
+                    document_uri(project, &session),
+                    Some("text".to_string()),
+                    Some(name),
+                )
+                .inspect_err(|e| tracing::warn!("Skipping document of '{}': {}", session, e))
+                .ok()
+            })
+            .collect()
+    }
+
+    fn read_resource(
+        &self,
+        uri: &str,
+    ) -> Pin<
+        Box<
+            dyn futures::Future<Output = Result<String, mcp_core::handler::ResourceError>>
                 + Send
                 + 'static,
         >,
     > {
-        Box::pin(async {
-            Err(mcp_core::handler::ResourceError::NotFound(
+        let result = match parse_document_uri(uri) {
+            Some((project, session)) => document::read(
+                self.service.session_manager.storage(),
+                &project::dir(project.as_deref()),
+                &session,
+            )
+            .map_err(|e| mcp_core::handler::ResourceError::ExecutionError(e.to_string()))
+            .and_then(|markdown| {
+                markdown.ok_or_else(|| {
+                    mcp_core::handler::ResourceError::NotFound(format!(
+                        "Session '{}' has no imported design document",
+                        session
+                    ))
+                })
+            }),
+            None => Err(mcp_core::handler::ResourceError::NotFound(
                 "Resource not found".to_string(),
-            ))
-        })
+            )),
+        };
+
+        Box::pin(async move { result })
     }
 
+    // --- Prompts are not implemented for this router ---
     fn list_prompts(&self) -> Vec<mcp_core::prompt::Prompt> {
         vec![]
     }
@@ -491,4 +3924,668 @@ Provide detailed but concise information for each section.",
             ))
         })
     }
+
+    // The tools of this SDK version carry no annotations, so they are added
+    // to the listed tools here
+    fn handle_tools_list(
+        &self,
+        req: JsonRpcRequest,
+    ) -> impl Future<Output = Result<JsonRpcResponse, RouterError>> + Send {
+        async move {
+            let tools = self
+                .list_tools()
+                .into_iter()
+                .map(|tool| {
+                    let annotations = ToolAnnotations::of(&tool.name);
+                    let mut tool = serde_json::to_value(tool)?;
+                    tool["annotations"] = serde_json::to_value(annotations)?;
+                    Ok::<_, serde_json::Error>(tool)
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| RouterError::Internal(format!("JSON serialization error: {}", e)))?;
+
+            let mut response = self.create_response(req.id);
+            response.result = Some(json!({ "tools": tools }));
+
+            Ok(response)
+        }
+    }
+
+    // Routes legacy tool names to their current tools, which the SDK's
+    // handler has no hook for
+    fn handle_tools_call(
+        &self,
+        req: JsonRpcRequest,
+    ) -> impl Future<Output = Result<JsonRpcResponse, RouterError>> + Send {
+        async move {
+            let params = req
+                .params
+                .ok_or_else(|| RouterError::InvalidParams("Missing parameters".to_string()))?;
+            let name = params
+                .get("name")
+                .and_then(Value::as_str)
+                .ok_or_else(|| RouterError::InvalidParams("Missing tool name".to_string()))?;
+            let arguments = params.get("arguments").cloned().unwrap_or(Value::Null);
+
+            let alias = aliases::find(name);
+            let (name, arguments) = match alias {
+                Some(alias) => {
+                    tracing::warn!(
+                        alias = alias.name,
+                        tool = alias.tool,
+                        "Deprecated tool name called"
+                    );
+                    metrics::counter!("gamedesignerd_deprecated_tool_calls_total", "alias" => alias.name)
+                        .increment(1);
+
+                    (alias.tool, alias.rename_arguments(arguments))
+                }
+                None => (name, arguments),
+            };
+
+            let result = match self.call_tool(name, arguments).await {
+                Ok(content) => CallToolResult {
+                    content,
+                    is_error: None,
+                },
+                Err(e) => CallToolResult {
+                    content: vec![Content::text(e.to_string())],
+                    is_error: Some(true),
+                },
+            };
+            let mut result = serde_json::to_value(result)
+                .map_err(|e| RouterError::Internal(format!("JSON serialization error: {}", e)))?;
+            if let Some(alias) = alias {
+                result["_meta"] = json!({ "deprecation": alias.deprecation() });
+            }
+
+            let mut response = self.create_response(req.id);
+            response.result = Some(result);
+
+            Ok(response)
+        }
+    }
+
+    // The SDK answers every client with the version it was written against
+    // and its own crate version, so the handshake is answered here
+    fn handle_initialize(
+        &self,
+        req: JsonRpcRequest,
+    ) -> impl Future<Output = Result<JsonRpcResponse, RouterError>> + Send {
+        async move {
+            let requested = req
+                .params
+                .as_ref()
+                .and_then(|params| params.get("protocolVersion"))
+                .and_then(Value::as_str);
+            let protocol_version = transport::negotiate_protocol_version(requested);
+
+            if requested.is_some_and(|requested| requested != protocol_version) {
+                tracing::warn!(
+                    requested = requested,
+                    offered = protocol_version,
+                    "Client requested an unsupported MCP protocol version"
+                );
+            }
+
+            let result = InitializeResult {
+                protocol_version: protocol_version.to_string(),
+                capabilities: self.capabilities(),
+                server_info: Implementation {
+                    name: self.name(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                },
+                instructions: Some(self.instructions()),
+            };
+
+            let mut response = self.create_response(req.id);
+            response.result =
+                Some(serde_json::to_value(result).map_err(|e| {
+                    RouterError::Internal(format!("JSON serialization error: {}", e))
+                })?);
+
+            Ok(response)
+        }
+    }
+}
+
+/// URI of the design document a session was imported from.
+fn document_uri(project: Option<&str>, session_name: &str) -> String {
+    match project {
+        Some(project) => format!(
+            "gamedesigner://projects/{}/sessions/{}/document",
+            project, session_name
+        ),
+        None => format!("gamedesigner://sessions/{}/document", session_name),
+    }
+}
+
+/// Parses a [`document_uri`] into its project and session.
+fn parse_document_uri(uri: &str) -> Option<(Option<String>, String)> {
+    let path = uri
+        .strip_prefix("gamedesigner://")?
+        .strip_suffix("/document")?;
+
+    let (project, session) = match path.strip_prefix("projects/") {
+        Some(path) => {
+            let (project, session) = path.split_once("/sessions/")?;
+            (project::normalize(project).ok()?, session)
+        }
+        None => (None, path.strip_prefix("sessions/")?),
+    };
+
+    // Session names end up in file paths
+    if session.is_empty() || session.contains(['/', '\\']) || session.starts_with('.') {
+        return None;
+    }
+
+    Some((project, session.to_string()))
+}
+
+/// Key of a session in the circuit breakers and the token ledger.
+fn session_key(project: Option<&str>, session_name: &str) -> String {
+    match project {
+        Some(project) => format!("{}/{}", project, session_name),
+        None => session_name.to_string(),
+    }
+}
+
+/// Names of all persona presets, for error messages.
+fn persona_names() -> Vec<&'static str> {
+    Persona::ALL.iter().map(|persona| persona.name()).collect()
+}
+
+/// Parses the optional `persona` argument.
+fn parse_persona(name: Option<&str>) -> Result<Option<Persona>, ToolError> {
+    let Some(name) = name else {
+        return Ok(None);
+    };
+
+    Persona::parse(name).map(Some).ok_or_else(|| {
+        ToolError::InvalidParameters(format!(
+            "Unknown persona '{}'. Available personas: {}",
+            name,
+            persona_names().join(", ")
+        ))
+    })
+}
+
+fn engine_names() -> Vec<&'static str> {
+    Engine::ALL.iter().map(|engine| engine.name()).collect()
+}
+
+/// Parses the optional `reviewDepth` argument.
+fn parse_review_depth(name: Option<&str>) -> Result<Option<ReviewDepth>, ToolError> {
+    let Some(name) = name else {
+        return Ok(None);
+    };
+
+    ReviewDepth::parse(name).map(Some).ok_or_else(|| {
+        ToolError::InvalidParameters(format!(
+            "Unknown review depth '{}'. Available depths: {}",
+            name,
+            ReviewDepth::ALL
+                .iter()
+                .map(|depth| depth.name())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    })
+}
+
+/// Reads the design document at `path`, which must lie inside `repo`, or
+/// inside the working directory without one. Relative paths are resolved
+/// against the same directory.
+fn read_document(path: &str, repo: Option<&str>) -> Result<String, ToolError> {
+    let invalid = |e: &dyn std::fmt::Display| {
+        ToolError::InvalidParameters(format!("Failed to read documentPath {}: {}", path, e))
+    };
+
+    let root = match repo {
+        Some(repo) => std::path::PathBuf::from(repo),
+        None => std::env::current_dir().map_err(|e| invalid(&e))?,
+    };
+    let document =
+        paths::confine(path, &[root.as_path()]).map_err(|e| tool_error("read documentPath", e))?;
+
+    std::fs::read_to_string(&document).map_err(|e| invalid(&e))
+}
+
+/// Reads the `language` argument; an empty one selects English.
+fn parse_language(language: Option<&str>) -> Option<String> {
+    language
+        .map(str::trim)
+        .filter(|language| !language.is_empty())
+        .map(str::to_string)
+}
+
+/// Reads the optional `temperature`, `topP` and `maxTokens` arguments of
+/// LLM-backed tools.
+fn sampling_overrides(arguments: &Value) -> Sampling {
+    Sampling {
+        temperature: arguments
+            .get("temperature")
+            .and_then(Value::as_f64)
+            .map(|t| t as f32),
+        top_p: arguments
+            .get("topP")
+            .and_then(Value::as_f64)
+            .map(|p| p as f32),
+        max_tokens: arguments
+            .get("maxTokens")
+            .and_then(Value::as_u64)
+            .map(|n| n.min(u32::MAX as u64) as u32),
+    }
+}
+
+/// Parses the optional `engine` argument.
+fn parse_engine(name: Option<&str>) -> Result<Option<Engine>, ToolError> {
+    let Some(name) = name else {
+        return Ok(None);
+    };
+
+    Engine::parse(name).map(Some).ok_or_else(|| {
+        ToolError::InvalidParameters(format!(
+            "Unknown engine '{}'. Available engines: {}",
+            name,
+            engine_names().join(", ")
+        ))
+    })
+}
+
+/// Converts the `studio`, `authors`, `license` and `copyrightYear`
+/// arguments; blank values count as unset.
+fn parse_metadata(metadata: MetadataParams) -> ProjectMetadata {
+    let text = |value: Option<String>| {
+        value
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+
+    ProjectMetadata {
+        studio: text(metadata.studio),
+        authors: metadata.authors,
+        license: text(metadata.license),
+        copyright_year: metadata.copyright_year,
+    }
+}
+
+/// Free-text arguments stored in the review and Q&A transcripts, by tool.
+const TRANSCRIPT_ARGUMENTS: &[(&str, &str)] = &[
+    ("featureReview", "changesMade"),
+    ("reviewReply", "content"),
+    ("featureAsk", "question"),
+];
+
+/// Redacts the secrets in the transcript arguments of `tool` in place and
+/// returns what was redacted.
+fn redact_arguments(tool: &str, arguments: &mut Value) -> Vec<redact::Finding> {
+    let mut findings = Vec::new();
+
+    for (_, name) in TRANSCRIPT_ARGUMENTS.iter().filter(|(t, _)| *t == tool) {
+        if let Some(Value::String(text)) = arguments.get_mut(*name) {
+            let redacted = redact::redact(text);
+            if !redacted.findings.is_empty() {
+                *text = redacted.text;
+                findings.extend(redacted.findings);
+            }
+        }
+    }
+
+    if !findings.is_empty() {
+        tracing::warn!(
+            "Redacted {} from the arguments of {}",
+            redact::summary(&findings),
+            tool
+        );
+    }
+
+    findings
+}
+
+/// Tells the caller what was redacted from its submission.
+fn note_redactions(output: &mut ToolOutput, findings: &[redact::Finding]) {
+    output.text.push_str(&format!(
+        "\n\nNote: redacted {} from the submission before storing it and sending it to the \
+         designer. Rotate any credential that was pasted.",
+        redact::summary(findings)
+    ));
+
+    if let Some(data) = output.data.as_object_mut() {
+        data.insert("redacted".to_string(), json!(findings));
+    }
+}
+
+/// Warns the caller about arguments that were longer than their limit.
+fn note_shortened(output: &mut ToolOutput, shortened: &[Shortened]) {
+    for argument in shortened {
+        output.text.push_str(&format!(
+            "\n\nWarning: `{}` had {} characters, over its limit of {}; {}",
+            argument.field,
+            argument.original_chars,
+            argument.limit,
+            if argument.summarized {
+                "the designer's summary of it was used instead"
+            } else {
+                "its middle was cut out"
+            }
+        ));
+        match &argument.original {
+            Some(original) => {
+                output.text.push_str(&format!(
+                    ". The original is kept in {}.",
+                    original.display()
+                ));
+            }
+            None => output.text.push('.'),
+        }
+    }
+
+    if let Some(data) = output.data.as_object_mut() {
+        data.insert("shortened".to_string(), json!(shortened));
+    }
+}
+
+/// Parses the optional `author` argument; blank names count as unattributed.
+fn parse_author(author: Option<&str>) -> Option<&str> {
+    author.map(str::trim).filter(|author| !author.is_empty())
+}
+
+/// Formats the per-author contribution counts of the session, or nothing if
+/// no contribution was attributed.
+fn author_summary(session: &SessionState) -> String {
+    let stats = session.author_stats();
+
+    if stats.keys().all(Option::is_none) {
+        return String::new();
+    }
+
+    let mut summary = "\n\n## Contributors\n".to_string();
+    for (author, stats) in stats {
+        summary.push_str(&format!(
+            "- {}: {} reports, {} replies, {} questions\n",
+            author.as_deref().unwrap_or("unattributed"),
+            stats.reports,
+            stats.replies,
+            stats.questions
+        ));
+    }
+
+    summary
+}
+
+/// Contribution counts per author, for machine-readable output.
+fn contributors(session: &SessionState) -> Vec<Value> {
+    session
+        .author_stats()
+        .into_iter()
+        .map(|(author, stats)| {
+            json!({
+                "author": author,
+                "reports": stats.reports,
+                "replies": stats.replies,
+                "questions": stats.questions,
+            })
+        })
+        .collect()
+}
+
+/// Fetches up to `limit` open issues from the tracker `source`.
+async fn fetch_issues(
+    source: TrackerSource,
+    repository: Option<&str>,
+    team: Option<&str>,
+    limit: u32,
+) -> Result<Vec<TrackerIssue>, ToolError> {
+    match source {
+        TrackerSource::Github => {
+            let repository = repository.ok_or_else(|| {
+                ToolError::InvalidParameters(
+                    "repository is required to import from github".to_string(),
+                )
+            })?;
+
+            trackers::fetch_github_issues(repository, limit).await
+        }
+        TrackerSource::Linear => {
+            let team = team.ok_or_else(|| {
+                ToolError::InvalidParameters("team is required to import from linear".to_string())
+            })?;
+
+            trackers::fetch_linear_issues(team, limit).await
+        }
+    }
+    .map_err(|e| ToolError::ExecutionError(format!("Failed to fetch issues: {}", e)))
+}
+
+/// Looks up the engine API the next feature of a session uses and stores the
+/// paths that exist on docs.rs with the feature, so that its specification
+/// names real modules and types. Returns the updated specification, or
+/// `None` if the engine has no documentation crate, the feature already has
+/// references, no LLM is configured or the lookup failed.
+async fn attach_api_references(
+    docs: &DocRouter,
+    session_manager: &SessionManager,
+    session_name: &str,
+    llm_client: Option<&DesignerLlmClient>,
+) -> Option<String> {
+    let session = session_manager.load_session(session_name).await.ok()??;
+    let llm_client = llm_client?;
+    let crate_name = session.engine?.docs_crate()?;
+    let feature = session
+        .next_feature_to_implement
+        .as_ref()
+        .and_then(|name| session.planned_features.iter().find(|f| &f.name == name))?;
+    if !feature.api_references.is_empty() {
+        return None;
+    }
+
+    let paths = match engine::api_paths(llm_client, &session, crate_name, feature).await {
+        Ok(paths) => paths,
+        Err(e) => {
+            tracing::warn!("Failed to list engine API of '{}': {}", feature.name, e);
+            return None;
+        }
+    };
+
+    // Paths the LLM made up have no documentation page
+    let lookups = paths
+        .iter()
+        .map(|path| docs.item_synopsis(crate_name, path, None));
+    let references = paths
+        .iter()
+        .zip(futures::future::join_all(lookups).await)
+        .filter_map(|(path, synopsis)| match synopsis {
+            Ok(summary) => Some(ApiReference {
+                path: path.clone(),
+                summary,
+            }),
+            Err(e) => {
+                tracing::debug!("Dropping engine API path '{}': {}", path, e);
+                None
+            }
+        })
+        .collect();
+
+    match session_manager
+        .set_api_references(session_name, &feature.name, references)
+        .await
+    {
+        Ok(specification) => Some(specification),
+        Err(e) => {
+            tracing::warn!("Failed to store engine API of '{}': {}", feature.name, e);
+            None
+        }
+    }
+}
+
+/// Loads a session for the machine-readable output of a tool that changed
+/// it.
+async fn load_session(
+    session_manager: &SessionManager,
+    session_name: &str,
+) -> Result<SessionState, ToolError> {
+    session_manager
+        .load_session(session_name)
+        .await
+        .map_err(|e| tool_error("load session", e))?
+        .ok_or_else(|| GameDesignerError::SessionNotFound(session_name.to_string()).into())
+}
+
+/// Calls `f` with the session without copying it out of the session manager.
+async fn with_session<T>(
+    session_manager: &SessionManager,
+    session_name: &str,
+    f: impl FnOnce(&SessionState) -> T,
+) -> Result<T, ToolError> {
+    session_manager
+        .with_session(session_name, f)
+        .await
+        .map_err(|e| tool_error("load session", e))?
+        .ok_or_else(|| GameDesignerError::SessionNotFound(session_name.to_string()).into())
+}
+
+/// Machine-readable summary of the feature to implement next, if any.
+fn next_feature_json(session: &SessionState) -> Option<Value> {
+    session
+        .next_feature_to_implement
+        .as_ref()
+        .and_then(|name| session.planned_features.iter().find(|f| &f.name == name))
+        .map(output::feature_json)
+}
+
+/// Machine-readable outcome of the session's latest review exchange, i.e. of
+/// a `featureReview` or `reviewReply` that returned `response`.
+fn review_outcome(session: &SessionState, response: &str) -> Value {
+    let entry = session.review_log.last();
+    let feature = entry.and_then(|entry| {
+        session
+            .planned_features
+            .iter()
+            .find(|feature| feature.name == entry.feature)
+    });
+    let verdict = match feature.map(|feature| &feature.status) {
+        Some(FeatureStatus::Reviewed) => "approved",
+        Some(FeatureStatus::AwaitingApproval) => "awaiting_approval",
+        _ => "needs_rework",
+    };
+
+    json!({
+        "feature": feature.map(|feature| &feature.name),
+        "status": feature.map(|feature| &feature.status),
+        "verdict": verdict,
+        "reviewDepth": feature.and_then(|feature| feature.review_depth),
+        "followUpRounds": feature.map(|feature| followups::rounds(session, &feature.name)),
+        "rework": feature.map(|feature| &feature.rework),
+        "criteria": entry.map(|entry| &entry.criteria),
+        "panel": entry.map(|entry| &entry.panel),
+        "review": response,
+        "questions": output::questions(response),
+    })
+}
+
+/// Parses the optional `commits` argument into lowercase commit SHAs.
+fn parse_commits(commits: &[String]) -> Result<Vec<String>, ToolError> {
+    commits
+        .iter()
+        .map(|commit| {
+            let sha = commit.trim().to_lowercase();
+
+            if (7..=64).contains(&sha.len()) && sha.chars().all(|c| c.is_ascii_hexdigit()) {
+                Ok(sha)
+            } else {
+                Err(ToolError::InvalidParameters(format!(
+                    "Invalid commit SHA: {}",
+                    commit
+                )))
+            }
+        })
+        .collect()
+}
+
+/// Decodes the attachments of a `featureReview`.
+fn parse_attachments(attachments: &[AttachmentParams]) -> Result<Vec<Upload>, ToolError> {
+    if attachments.len() > attachments::MAX_ATTACHMENTS {
+        return Err(ToolError::InvalidParameters(format!(
+            "At most {} attachments can be submitted with a review, got {}",
+            attachments::MAX_ATTACHMENTS,
+            attachments.len()
+        )));
+    }
+
+    attachments.iter().map(parse_attachment).collect()
+}
+
+/// Decodes an image or embedded resource content part.
+fn parse_attachment(attachment: &AttachmentParams) -> Result<Upload, ToolError> {
+    let upload = match attachment {
+        AttachmentParams::Image { data, mime_type } => Upload::decode(data, mime_type, None),
+        AttachmentParams::Resource { resource } => Upload::decode(
+            &resource.blob,
+            resource
+                .mime_type
+                .as_deref()
+                .unwrap_or("application/octet-stream"),
+            Some(resource.uri.clone()),
+        ),
+    };
+
+    upload.map_err(|e| ToolError::InvalidParameters(e.to_string()))
+}
+
+/// Describes a reverted change for `designUndo` and the `undo` command.
+pub fn describe_undo(outcome: &UndoOutcome) -> String {
+    if outcome.events.is_empty() {
+        return format!(
+            "Restored the session to its state before event {}.",
+            outcome.seq
+        );
+    }
+
+    let events = outcome
+        .events
+        .iter()
+        .map(|event| {
+            let feature = event.data.get("feature").and_then(|v| v.as_str());
+            match feature {
+                Some(feature) => format!("{} ({})", event.kind.as_str(), feature),
+                None => event.kind.as_str().to_string(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    format!(
+        "Undid the change starting at event {}: {}. The events stay in the session history.",
+        outcome.seq,
+        events.join(", ")
+    )
+}
+
+/// Parses the `types`, `since` and `until` arguments of `sessionHistory`.
+fn parse_event_filter(params: &SessionHistoryParams) -> Result<EventFilter, ToolError> {
+    let range = params.range.parse()?;
+
+    let filter = EventFilter {
+        kinds: params.types.clone(),
+        since: range.since,
+        until: range.until,
+    };
+    filter
+        .validate()
+        .map_err(|e| ToolError::InvalidParameters(e.to_string()))?;
+
+    Ok(filter)
+}
+
+/// Parses an optional `YYYY-MM-DD` date argument.
+fn parse_date(date: Option<&str>, name: &str) -> Result<Option<chrono::NaiveDate>, ToolError> {
+    let Some(date) = date else {
+        return Ok(None);
+    };
+
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map(Some)
+        .map_err(|_| {
+            ToolError::InvalidParameters(format!("{} must be a date in YYYY-MM-DD format", name))
+        })
 }
@@ -0,0 +1,217 @@
+//! Circuit breakers that stop a misconfigured agent from looping on the
+//! designer tools and burning through the LLM budget.
+
+use crate::{game_design::config, tools::quota::QuotaExceeded};
+use std::{
+    collections::{HashMap, VecDeque},
+    env,
+    sync::{Mutex, RwLock},
+    time::{Duration, Instant},
+};
+
+/// Limits enforced per session.
+#[derive(Debug, Clone)]
+pub struct GuardConfig {
+    /// Maximum tool calls within any 60-second window.
+    pub max_calls_per_minute: usize,
+    /// Maximum `nextFeature` calls without a `featureReview` or `reviewReply`
+    /// in between.
+    pub max_consecutive_next_feature: u32,
+    /// How long a tripped breaker rejects calls.
+    pub cooldown: Duration,
+    /// LLM tokens a session may consume per day (UTC); `None` for no limit.
+    pub daily_token_budget: Option<u64>,
+}
+
+impl Default for GuardConfig {
+    fn default() -> Self {
+        Self {
+            max_calls_per_minute: 30,
+            max_consecutive_next_feature: 5,
+            cooldown: Duration::from_secs(300),
+            daily_token_budget: Some(2_000_000),
+        }
+    }
+}
+
+impl GuardConfig {
+    /// Reads the limits from `GAMEDESIGNER_MAX_CALLS_PER_MINUTE`,
+    /// `GAMEDESIGNER_MAX_CONSECUTIVE_NEXT_FEATURE`,
+    /// `GAMEDESIGNER_COOLDOWN_SECS` and `GAMEDESIGNER_DAILY_TOKEN_BUDGET`,
+    /// falling back to the configuration file and then to the defaults. A
+    /// token budget of `0` disables the limit.
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+            env::var(name).ok().and_then(|value| value.parse().ok())
+        }
+
+        let defaults = Self::default();
+        let limits = config::current().guard.clone();
+
+        Self {
+            max_calls_per_minute: var("GAMEDESIGNER_MAX_CALLS_PER_MINUTE")
+                .or(limits.max_calls_per_minute)
+                .unwrap_or(defaults.max_calls_per_minute),
+            max_consecutive_next_feature: var("GAMEDESIGNER_MAX_CONSECUTIVE_NEXT_FEATURE")
+                .or(limits.max_consecutive_next_feature)
+                .unwrap_or(defaults.max_consecutive_next_feature),
+            cooldown: var("GAMEDESIGNER_COOLDOWN_SECS")
+                .or(limits.cooldown_secs)
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.cooldown),
+            daily_token_budget: var("GAMEDESIGNER_DAILY_TOKEN_BUDGET")
+                .or(limits.daily_token_budget)
+                .map_or(defaults.daily_token_budget, |budget| {
+                    (budget > 0).then_some(budget)
+                }),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct SessionActivity {
+    recent_calls: VecDeque<Instant>,
+    consecutive_next_feature: u32,
+    tripped_until: Option<Instant>,
+    /// The limit that tripped the breaker.
+    tripped_by: &'static str,
+}
+
+/// Tracks tool activity per session and trips into a cooldown when a limit
+/// is exceeded.
+#[derive(Debug)]
+pub struct LoopGuard {
+    config: RwLock<GuardConfig>,
+    sessions: Mutex<HashMap<String, SessionActivity>>,
+}
+
+impl LoopGuard {
+    pub fn new(config: GuardConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Replaces the limits. Breakers that are already tripped keep their
+    /// cooldown.
+    pub fn set_config(&self, config: GuardConfig) {
+        *self.config.write().unwrap() = config;
+    }
+
+    /// Returns the active limits.
+    pub fn config(&self) -> GuardConfig {
+        self.config.read().unwrap().clone()
+    }
+
+    /// Returns the calls the session made within the last minute and how
+    /// long its breaker still rejects calls, if it is tripped.
+    pub fn usage(&self, session_id: &str) -> (usize, Option<Duration>) {
+        let now = Instant::now();
+        let sessions = self.sessions.lock().unwrap();
+
+        let Some(activity) = sessions.get(session_id) else {
+            return (0, None);
+        };
+
+        let calls = activity
+            .recent_calls
+            .iter()
+            .filter(|&&call| now.duration_since(call) < Duration::from_secs(60))
+            .count();
+        let tripped = activity
+            .tripped_until
+            .filter(|&until| now < until)
+            .map(|until| until - now);
+
+        (calls, tripped)
+    }
+
+    /// Records a call of `tool_name` for the session, or fails if the
+    /// session's breaker is (or just became) tripped.
+    pub fn check(&self, session_id: &str, tool_name: &str) -> Result<(), QuotaExceeded> {
+        let now = Instant::now();
+        let config = self.config.read().unwrap().clone();
+        let mut sessions = self.sessions.lock().unwrap();
+        let activity = sessions.entry(session_id.to_string()).or_default();
+
+        if let Some(until) = activity.tripped_until {
+            if now < until {
+                return Err(QuotaExceeded {
+                    limit: activity.tripped_by,
+                    session: session_id.to_string(),
+                    message: format!(
+                        "Circuit breaker for session '{}' is tripped; retry in {} seconds.",
+                        session_id,
+                        (until - now).as_secs() + 1
+                    ),
+                    retry_after: until - now,
+                });
+            }
+
+            *activity = SessionActivity::default();
+        }
+
+        while activity
+            .recent_calls
+            .front()
+            .is_some_and(|&call| now.duration_since(call) >= Duration::from_secs(60))
+        {
+            activity.recent_calls.pop_front();
+        }
+        activity.recent_calls.push_back(now);
+
+        match tool_name {
+            "nextFeature" => activity.consecutive_next_feature += 1,
+            "featureReview" | "reviewReply" => activity.consecutive_next_feature = 0,
+            _ => {}
+        }
+
+        let (limit, reason) = if activity.recent_calls.len() > config.max_calls_per_minute {
+            (
+                "callsPerMinute",
+                format!(
+                    "more than {} tool calls within a minute",
+                    config.max_calls_per_minute
+                ),
+            )
+        } else if activity.consecutive_next_feature > config.max_consecutive_next_feature {
+            (
+                "consecutiveNextFeature",
+                format!(
+                    "more than {} consecutive nextFeature calls without a review",
+                    config.max_consecutive_next_feature
+                ),
+            )
+        } else {
+            return Ok(());
+        };
+
+        activity.tripped_until = Some(now + config.cooldown);
+        activity.tripped_by = limit;
+
+        tracing::warn!(
+            "Circuit breaker tripped for session '{}': {}",
+            session_id,
+            reason
+        );
+
+        Err(QuotaExceeded {
+            limit,
+            session: session_id.to_string(),
+            message: format!(
+                "Circuit breaker tripped for session '{}': {}. Cooling down for {} seconds.",
+                session_id,
+                reason,
+                config.cooldown.as_secs()
+            ),
+            retry_after: config.cooldown,
+        })
+    }
+}
+
+impl Default for LoopGuard {
+    fn default() -> Self {
+        Self::new(GuardConfig::from_env())
+    }
+}
@@ -0,0 +1,188 @@
+//! Background jobs for long-running LLM-backed tool calls.
+//!
+//! A call with `background: true` is started as a job and answered right
+//! away with the job's id; the client polls `jobStatus` and fetches the
+//! output with `jobResult`. Every job is stored in `.gamedesignerd/jobs/`,
+//! so finished results survive a restart. Jobs that were still running when
+//! the server stopped are marked as failed on the next start.
+
+use crate::game_design::session::persistence_dir;
+use chrono::{DateTime, Utc};
+use mcp_core::Content;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+/// Jobs finished longer ago than this are deleted on startup.
+const RETENTION_DAYS: i64 = 7;
+
+/// State of a job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// A tool call running, or having run, in the background.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Job {
+    pub id: String,
+    pub tool: String,
+    pub project: Option<String>,
+    pub session: Option<String>,
+    pub status: JobStatus,
+    pub created_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Output of a succeeded job, in the format the call asked for.
+    #[serde(default)]
+    pub content: Vec<Content>,
+    /// Error of a failed job.
+    pub error: Option<String>,
+}
+
+/// The jobs of this data directory.
+#[derive(Debug)]
+pub struct JobStore {
+    dir: PathBuf,
+    jobs: Mutex<HashMap<String, Job>>,
+}
+
+impl Default for JobStore {
+    fn default() -> Self {
+        Self::open(persistence_dir().join("jobs"))
+    }
+}
+
+impl JobStore {
+    /// Loads the jobs stored in `dir`, failing those interrupted by a
+    /// restart and deleting those past the retention period.
+    pub fn open(dir: PathBuf) -> Self {
+        let store = Self {
+            jobs: Mutex::new(load(&dir)),
+            dir,
+        };
+
+        let now = Utc::now();
+        let mut jobs = store.jobs.lock().unwrap();
+
+        jobs.retain(|id, job| {
+            let expired = job
+                .finished_at
+                .is_some_and(|at| now - at > chrono::Duration::days(RETENTION_DAYS));
+            if expired && let Err(e) = fs::remove_file(store.path(id)) {
+                tracing::warn!("Failed to delete job {}: {}", id, e);
+            }
+            !expired
+        });
+
+        for job in jobs.values_mut() {
+            if job.status == JobStatus::Running {
+                job.status = JobStatus::Failed;
+                job.finished_at = Some(now);
+                job.error = Some("interrupted by a server restart".to_string());
+                store.save(job);
+            }
+        }
+
+        drop(jobs);
+        store
+    }
+
+    /// Records a new running job of `tool` and returns it.
+    pub fn start(&self, tool: &str, project: Option<String>, session: Option<String>) -> Job {
+        let job = Job {
+            id: format!("{:016x}", rand::random::<u64>()),
+            tool: tool.to_string(),
+            project,
+            session,
+            status: JobStatus::Running,
+            created_at: Utc::now(),
+            finished_at: None,
+            content: Vec::new(),
+            error: None,
+        };
+
+        self.save(&job);
+        self.jobs
+            .lock()
+            .unwrap()
+            .insert(job.id.clone(), job.clone());
+
+        job
+    }
+
+    /// Stores the outcome of the job `id`.
+    pub fn finish(&self, id: &str, result: Result<Vec<Content>, String>) {
+        let mut jobs = self.jobs.lock().unwrap();
+        let Some(job) = jobs.get_mut(id) else {
+            return;
+        };
+
+        job.finished_at = Some(Utc::now());
+        match result {
+            Ok(content) => {
+                job.status = JobStatus::Succeeded;
+                job.content = content;
+            }
+            Err(e) => {
+                job.status = JobStatus::Failed;
+                job.error = Some(e);
+            }
+        }
+
+        self.save(job);
+    }
+
+    /// Returns the job `id`.
+    pub fn get(&self, id: &str) -> Option<Job> {
+        self.jobs.lock().unwrap().get(id).cloned()
+    }
+
+    fn path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", id))
+    }
+
+    fn save(&self, job: &Job) {
+        let result = fs::create_dir_all(&self.dir)
+            .map_err(anyhow::Error::from)
+            .and_then(|()| Ok(serde_json::to_string_pretty(job)?))
+            .and_then(|json| Ok(fs::write(self.path(&job.id), json)?));
+
+        if let Err(e) = result {
+            tracing::warn!("Failed to store job {}: {}", job.id, e);
+        }
+    }
+}
+
+/// Reads the jobs stored in `dir`, skipping unreadable files.
+fn load(dir: &Path) -> HashMap<String, Job> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return HashMap::new();
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let job = fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|json| Ok(serde_json::from_str::<Job>(&json)?));
+
+            match job {
+                Ok(job) => Some((job.id.clone(), job)),
+                Err(e) => {
+                    tracing::warn!("Skipping unreadable job {}: {}", path.display(), e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,159 @@
+//! Size limits of free-text tool arguments.
+//!
+//! A report pasted together with a whole build log would blow up the
+//! prompts and the session file. Arguments longer than their limit are not
+//! rejected: the original is kept in `.gamedesignerd/inputs/`, named by its
+//! SHA-256, and the argument is replaced with a summary written by the
+//! designer, or with its beginning and end if no LLM provider is configured,
+//! followed by a reference to the original. The caller is warned in the
+//! tool result.
+
+use crate::game_design::{DesignerLlmClient, config, context, session::persistence_dir};
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::{fs, path::PathBuf};
+
+/// Limits of the free-text arguments in characters, unless configured in
+/// `[limits.fields]`. Imported documents get a generous limit, since a
+/// summary loses what the import is for.
+pub const DEFAULT_LIMITS: &[(&str, usize)] = &[
+    ("changesMade", 24_000),
+    ("content", 16_000),
+    ("answer", 8_000),
+    ("question", 4_000),
+    ("gameDescription", 16_000),
+    ("description", 8_000),
+    ("proposedEdit", 16_000),
+    ("rationale", 4_000),
+    ("motivation", 4_000),
+    ("styleGuide", 8_000),
+    ("document", 200_000),
+];
+
+/// Returns the limit of the argument `field` in characters, if it has one.
+/// A configured limit of `0` disables it.
+pub fn limit(field: &str) -> Option<usize> {
+    let configured = config::current().limits.fields.get(field).copied();
+
+    configured
+        .or_else(|| {
+            DEFAULT_LIMITS
+                .iter()
+                .find(|(name, _)| *name == field)
+                .map(|(_, limit)| *limit)
+        })
+        .filter(|limit| *limit > 0)
+}
+
+/// An argument that was longer than its limit.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Shortened {
+    pub field: String,
+    pub original_chars: usize,
+    pub limit: usize,
+    /// Where the original is kept, if it could be stored.
+    pub original: Option<PathBuf>,
+    /// Whether the designer summarized it; otherwise its middle was cut out.
+    pub summarized: bool,
+}
+
+/// Shortens the arguments of `tool` that are longer than their limit in
+/// place and returns which.
+pub async fn enforce(
+    tool: &str,
+    arguments: &mut Value,
+    llm_client: Option<&DesignerLlmClient>,
+) -> Vec<Shortened> {
+    let Some(fields) = arguments.as_object_mut() else {
+        return Vec::new();
+    };
+    let mut shortened = Vec::new();
+
+    for (field, value) in fields.iter_mut() {
+        let Value::String(text) = value else {
+            continue;
+        };
+        let Some(limit) = limit(field) else {
+            continue;
+        };
+        let original_chars = text.chars().count();
+        if original_chars <= limit {
+            continue;
+        }
+
+        let original = match store_original(text) {
+            Ok(path) => Some(path),
+            Err(e) => {
+                tracing::warn!("Failed to store the original of {}: {}", field, e);
+                None
+            }
+        };
+
+        let summary = match llm_client {
+            Some(llm_client) => {
+                match context::summarize_input(llm_client, tool, field, text, limit).await {
+                    Ok(summary) => Some(summary),
+                    Err(e) => {
+                        tracing::warn!("Failed to summarize {} of {}: {}", field, tool, e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+        let summarized = summary.is_some();
+
+        // A reply longer than asked for is cut down all the same
+        let summary = summary.unwrap_or_else(|| text.clone());
+        let mut replacement = context::truncate_middle(&summary, limit / 4);
+        replacement.push_str(&format!(
+            "\n\n[{} from {} characters",
+            if summarized {
+                "Summarized"
+            } else {
+                "Shortened"
+            },
+            original_chars
+        ));
+        if let Some(original) = &original {
+            replacement.push_str(&format!("; the original is kept in {}", original.display()));
+        }
+        replacement.push(']');
+
+        tracing::warn!(
+            "{} of {} has {} characters, over its limit of {}",
+            field,
+            tool,
+            original_chars,
+            limit
+        );
+        *text = replacement;
+
+        shortened.push(Shortened {
+            field: field.clone(),
+            original_chars,
+            limit,
+            original,
+            summarized,
+        });
+    }
+
+    shortened
+}
+
+// Stores `text` under its digest, so that pasting the same blob twice keeps
+// one copy
+fn store_original(text: &str) -> Result<PathBuf> {
+    let dir = persistence_dir().join("inputs");
+    fs::create_dir_all(&dir)?;
+
+    let path = dir.join(format!("{}.txt", hex::encode(Sha256::digest(text))));
+    if !path.exists() {
+        fs::write(&path, text)?;
+    }
+
+    Ok(path)
+}
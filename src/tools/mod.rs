@@ -4,6 +4,40 @@ pub mod docs;
 // New module for game design tools
 pub mod game_tools;
 
+// Circuit breakers for runaway agent loops
+pub mod guard;
+
+// Typed tool parameters, their schemas and argument validation
+pub mod params;
+
+// Text and JSON representations of tool results
+pub mod output;
+
+// Cursor-based pagination and filters of the listing tools
+pub mod pagination;
+
+// Bounded queue for LLM-backed tool calls
+pub mod queue;
+
+// Daily token budgets of sessions
+pub mod quota;
+
+// Timeouts and client cancellation of tool calls
+pub mod cancellation;
+
+// Background jobs for long-running tool calls
+pub mod jobs;
+
+// Size limits of free-text arguments
+pub mod limits;
+
+// Behavior hints of the tools in tools/list
+pub mod annotations;
+
+// Legacy tool names routed to their current tools
+pub mod aliases;
+
 // Re-exports
+pub use cancellation::Cancellations;
 pub use docs::{DocCache, DocRouter};
-pub use game_tools::GameToolsRouter;
+pub use game_tools::{GameDesignerService, GameToolsRouter};
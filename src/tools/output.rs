@@ -0,0 +1,122 @@
+//! Output of the game design tools.
+//!
+//! Every tool produces prose for the agent to read and a JSON value with the
+//! same result as machine-readable fields (feature names, statuses, verdicts,
+//! open questions, ...). The `outputFormat` argument selects which of the two
+//! are returned: the prose alone, the JSON alone, or the prose followed by a
+//! JSON text block.
+
+use crate::game_design::state::Feature;
+use mcp_core::Content;
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+/// Which parts of a tool's output are returned.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Prose only.
+    #[default]
+    Text,
+    /// The JSON block only.
+    Json,
+    /// Prose followed by the JSON block.
+    Both,
+}
+
+impl OutputFormat {
+    pub const NAMES: [&'static str; 3] = ["text", "json", "both"];
+
+    /// Reads the optional `outputFormat` argument. Unknown values are
+    /// rejected by argument validation before this is reached.
+    pub fn from_arguments(arguments: &Value) -> Self {
+        arguments
+            .get("outputFormat")
+            .cloned()
+            .and_then(|format| serde_json::from_value(format).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// The result of a tool call in both representations.
+#[derive(Debug, Clone)]
+pub struct ToolOutput {
+    pub text: String,
+    pub data: Value,
+}
+
+impl ToolOutput {
+    pub fn new(text: impl Into<String>, data: Value) -> Self {
+        Self {
+            text: text.into(),
+            data,
+        }
+    }
+
+    /// Output whose only machine-readable field is the message itself.
+    pub fn message(text: impl Into<String>) -> Self {
+        let text = text.into();
+        let data = json!({ "message": text });
+        Self { text, data }
+    }
+
+    /// Converts the output into the content blocks of the requested format.
+    pub fn into_content(self, format: OutputFormat) -> Vec<Content> {
+        let json =
+            || serde_json::to_string_pretty(&self.data).unwrap_or_else(|_| self.data.to_string());
+
+        match format {
+            OutputFormat::Text => vec![Content::text(self.text)],
+            OutputFormat::Json => vec![Content::text(json())],
+            OutputFormat::Both => {
+                let json = json();
+                vec![Content::text(self.text), Content::text(json)]
+            }
+        }
+    }
+}
+
+/// Machine-readable summary of a feature.
+pub fn feature_json(feature: &Feature) -> Value {
+    json!({
+        "name": feature.name,
+        "description": feature.description,
+        "status": feature.status,
+        "estimatedEffort": feature.estimated_effort,
+        "risk": feature.risk,
+        "milestone": feature.milestone,
+        "implementationHints": feature.implementation_hints,
+        "apiReferences": feature.api_references,
+        "requiredAssets": feature.required_assets,
+        "commits": feature.commits,
+        "timeSpentMinutes": feature.time_spent_minutes,
+        "reviewDepth": feature.review_depth,
+        "rework": feature.rework,
+        "pendingCaveats": feature
+            .pending_caveats
+            .iter()
+            .map(|(priority, description)| {
+                json!({ "priority": priority, "description": description })
+            })
+            .collect::<Vec<_>>(),
+        "similarRejected": feature.similar_rejected,
+    })
+}
+
+/// Extracts the questions a designer response asks, one per sentence ending
+/// in a question mark.
+pub fn questions(text: &str) -> Vec<String> {
+    text.lines()
+        .flat_map(|line| line.split_inclusive(['.', '!', '?']))
+        .map(|sentence| {
+            sentence
+                .trim()
+                .trim_start_matches(|c: char| {
+                    c == '-' || c == '*' || c == '.' || c == ')' || c.is_ascii_digit()
+                })
+                .trim()
+        })
+        .filter(|sentence| sentence.len() > 1 && sentence.ends_with('?'))
+        .map(str::to_string)
+        .collect()
+}
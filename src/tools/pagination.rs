@@ -0,0 +1,173 @@
+//! Pagination and filters shared by the listing tools.
+//!
+//! Listings are filtered first and then cut into pages. A cursor is the
+//! position of the first entry of a page within the filtered listing, so a
+//! client continues by passing the `nextCursor` of the previous page along
+//! with the same filters. The last page has no `nextCursor`.
+
+use crate::game_design::{
+    events,
+    state::{Feature, FeatureStatus},
+};
+use chrono::{DateTime, Utc};
+use mcp_core::handler::ToolError;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// Entries per page unless the client asks for another `limit`.
+pub const DEFAULT_LIMIT: u64 = 50;
+
+/// Upper bound of `limit`.
+pub const MAX_LIMIT: u64 = 1000;
+
+/// The `cursor` and `limit` arguments of listing tools.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PageParams {
+    /// Position to continue from, the nextCursor of the previous page
+    /// (optional, defaults to the first entry)
+    pub cursor: Option<u64>,
+    /// Maximum number of entries to return (optional, defaults to 50, max
+    /// 1000)
+    pub limit: Option<u64>,
+}
+
+/// The `status` and `milestone` filters of feature listings.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FeatureFilterParams {
+    /// Only list features with this status (optional)
+    #[serde(default)]
+    #[schemars(schema_with = "crate::tools::params::feature_status_schema")]
+    pub status: Option<FeatureStatus>,
+    /// Only list features of this milestone, ignoring case (optional)
+    pub milestone: Option<String>,
+}
+
+impl FeatureFilterParams {
+    pub fn matches(&self, feature: &Feature) -> bool {
+        self.status
+            .as_ref()
+            .is_none_or(|status| &feature.status == status)
+            && self.milestone.as_deref().is_none_or(|milestone| {
+                feature
+                    .milestone
+                    .as_deref()
+                    .is_some_and(|m| m.trim().eq_ignore_ascii_case(milestone.trim()))
+            })
+    }
+}
+
+/// The `since` and `until` filters of listings of timestamped entries.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DateRangeParams {
+    /// Earliest time, as YYYY-MM-DD or an RFC 3339 timestamp (optional)
+    pub since: Option<String>,
+    /// Latest time, as YYYY-MM-DD (inclusive) or an RFC 3339 timestamp
+    /// (optional)
+    pub until: Option<String>,
+}
+
+/// A parsed `since`/`until` range; open ends match everything.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DateRange {
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl DateRangeParams {
+    pub fn parse(&self) -> Result<DateRange, ToolError> {
+        let time = |name: &str, value: Option<&str>, end_of_day: bool| {
+            value
+                .map(|value| events::parse_time(value, end_of_day))
+                .transpose()
+                .map_err(|e| ToolError::InvalidParameters(format!("{}: {}", name, e)))
+        };
+
+        let range = DateRange {
+            since: time("since", self.since.as_deref(), false)?,
+            until: time("until", self.until.as_deref(), true)?,
+        };
+
+        if let (Some(since), Some(until)) = (range.since, range.until)
+            && since > until
+        {
+            return Err(ToolError::InvalidParameters(
+                "since must not be after until".to_string(),
+            ));
+        }
+
+        Ok(range)
+    }
+}
+
+impl DateRange {
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        self.since.is_none_or(|since| time >= since) && self.until.is_none_or(|until| time <= until)
+    }
+}
+
+/// One page of a filtered listing.
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Position of the first item within the listing.
+    pub offset: usize,
+    /// Number of entries of the whole listing.
+    pub total: usize,
+    /// Cursor of the next page, if there is one.
+    pub next_cursor: Option<usize>,
+}
+
+/// Cuts the page selected by `params` out of a filtered listing.
+pub fn paginate<T>(items: impl IntoIterator<Item = T>, params: &PageParams) -> Page<T> {
+    let items = items.into_iter().collect::<Vec<_>>();
+    let total = items.len();
+    let offset = (params.cursor.unwrap_or(0) as usize).min(total);
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT) as usize;
+
+    let items = items
+        .into_iter()
+        .skip(offset)
+        .take(limit)
+        .collect::<Vec<_>>();
+    let end = offset + items.len();
+
+    Page {
+        items,
+        offset,
+        total,
+        next_cursor: (end < total).then_some(end),
+    }
+}
+
+impl<T> Page<T> {
+    /// Appends to the text of the page's items where the page is and how to
+    /// get the next one, unless the page is the whole listing.
+    pub fn annotate(&self, text: String) -> String {
+        if self.offset == 0 && self.next_cursor.is_none() {
+            return text;
+        }
+
+        let mut footer = if self.items.is_empty() {
+            format!("No entries past {} of {}.", self.offset, self.total)
+        } else {
+            format!(
+                "Showing entries {}-{} of {}.",
+                self.offset + 1,
+                self.offset + self.items.len(),
+                self.total
+            )
+        };
+        if let Some(cursor) = self.next_cursor {
+            footer.push_str(&format!(" Pass cursor {} for the next page.", cursor));
+        }
+
+        if text.is_empty() {
+            footer
+        } else {
+            format!("{}\n\n{}", text, footer)
+        }
+    }
+}
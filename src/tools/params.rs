@@ -0,0 +1,1231 @@
+//! Typed parameters of the game design tools.
+//!
+//! The input schema every tool advertises is generated from its parameter
+//! struct, field documentation included. Before a tool runs, its arguments
+//! are validated against that schema, so that misspelled or mistyped
+//! parameters are reported together, by name, instead of surfacing as a
+//! confusing "is required" error for the parameter the client meant.
+
+use crate::{
+    game_design::{
+        assets::AssetKind,
+        debt::{DebtPriority, DebtStatus},
+        engine::Engine,
+        level::LevelStatus,
+        persona::Persona,
+        review_depth::ReviewDepth,
+        templates,
+    },
+    tools::pagination::{DateRangeParams, FeatureFilterParams, PageParams},
+};
+use mcp_core::handler::ToolError;
+use schemars::{
+    JsonSchema,
+    r#gen::{SchemaGenerator, SchemaSettings},
+    schema::{InstanceType, Schema, SchemaObject},
+};
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// Generates the input schema of a tool from its parameter struct.
+pub fn schema<T: JsonSchema>() -> Value {
+    let generator = SchemaSettings::draft07()
+        .with(|settings| {
+            settings.inline_subschemas = true;
+            settings.option_add_null_type = false;
+            settings.meta_schema = None;
+        })
+        .into_generator();
+
+    let mut schema = serde_json::to_value(generator.into_root_schema_for::<T>())
+        .expect("schemas serialize to JSON");
+
+    if let Some(schema) = schema.as_object_mut() {
+        // Tools carry their own description
+        schema.remove("title");
+        schema.remove("description");
+        schema.remove("definitions");
+        schema
+            .entry("properties")
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+
+    schema
+}
+
+/// Deserializes validated arguments into the parameters of a tool. Explicit
+/// `null`s count as omitted parameters.
+pub fn parse_params<T: DeserializeOwned>(arguments: &Value) -> Result<T, ToolError> {
+    let mut arguments = arguments.clone();
+
+    match &mut arguments {
+        Value::Object(arguments) => arguments.retain(|_, value| !value.is_null()),
+        arguments @ Value::Null => *arguments = Value::Object(Map::new()),
+        _ => {}
+    }
+
+    serde_json::from_value(arguments)
+        .map_err(|e| ToolError::InvalidParameters(format!("Invalid arguments: {}", e)))
+}
+
+/// Checks the arguments of a tool call against the tool's input schema.
+/// Returns one message per offending field.
+pub fn validate(schema: &Value, arguments: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    match arguments {
+        // Clients may omit the arguments of tools without required ones
+        Value::Null => check(schema, &Value::Object(Map::new()), "", &mut errors),
+        arguments => check(schema, arguments, "", &mut errors),
+    }
+
+    errors
+}
+
+fn check(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let field = if path.is_empty() { "arguments" } else { path };
+
+    if let Some(expected) = schema.get("type")
+        && !matches_type(expected, value)
+    {
+        errors.push(format!(
+            "`{}` must be {}, got {}",
+            field,
+            describe_type(expected),
+            type_name(value)
+        ));
+        return;
+    }
+
+    if let Some(values) = schema.get("enum").and_then(Value::as_array)
+        && !values.contains(value)
+    {
+        let values = values
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>();
+        errors.push(format!(
+            "`{}` must be one of {}, got {}",
+            field,
+            values.join(", "),
+            value
+        ));
+    }
+
+    if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64)
+        && value.as_f64().is_some_and(|number| number < minimum)
+    {
+        errors.push(format!("`{}` must be at least {}", field, minimum));
+    }
+
+    match value {
+        Value::Object(object) => check_object(schema, object, path, errors),
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    check(item_schema, item, &format!("{}[{}]", field, index), errors);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_object(schema: &Value, object: &Map<String, Value>, path: &str, errors: &mut Vec<String>) {
+    let empty = Map::new();
+    let properties = schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    let join = |name: &str| match path {
+        "" => name.to_string(),
+        path => format!("{}.{}", path, name),
+    };
+
+    for name in schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+    {
+        if object.get(name).is_none_or(Value::is_null) {
+            errors.push(format!("`{}` is required", join(name)));
+        }
+    }
+
+    let closed = schema.get("additionalProperties") == Some(&Value::Bool(false));
+
+    for (name, value) in object {
+        match properties.get(name) {
+            // Explicit nulls count as omitted
+            Some(_) if value.is_null() => {}
+            Some(property) => check(property, value, &join(name), errors),
+            None if closed => {
+                let mut error = format!("`{}` is not a parameter", join(name));
+                if let Some(suggestion) = closest(name, properties.keys()) {
+                    error.push_str(&format!(" (did you mean `{}`?)", suggestion));
+                }
+                errors.push(error);
+            }
+            None => {}
+        }
+    }
+}
+
+fn matches_type(expected: &Value, value: &Value) -> bool {
+    match expected {
+        Value::String(expected) => is_type(expected, value),
+        Value::Array(expected) => expected
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|expected| is_type(expected, value)),
+        _ => true,
+    }
+}
+
+fn is_type(expected: &str, value: &Value) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn describe_type(expected: &Value) -> String {
+    let article = |name: &str| match name {
+        "array" | "integer" | "object" => format!("an {}", name),
+        name => format!("a {}", name),
+    };
+
+    match expected {
+        Value::String(name) => article(name),
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .map(article)
+            .collect::<Vec<_>>()
+            .join(" or "),
+        other => other.to_string(),
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(number) if number.is_f64() => "a number",
+        Value::Number(_) => "an integer",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+/// Returns the known parameter a misspelled one most likely meant.
+fn closest<'a>(name: &str, known: impl Iterator<Item = &'a String>) -> Option<&'a str> {
+    let lowercase = name.to_lowercase();
+
+    known
+        .map(|candidate| {
+            (
+                candidate,
+                edit_distance(&lowercase, &candidate.to_lowercase()),
+            )
+        })
+        .filter(|(candidate, distance)| *distance <= 2.max(candidate.len() / 4))
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(candidate, _)| candidate.as_str())
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+fn string_enum(values: impl IntoIterator<Item = &'static str>) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        enum_values: Some(values.into_iter().map(Value::from).collect()),
+        ..Default::default()
+    }
+    .into()
+}
+
+fn persona_schema(_: &mut SchemaGenerator) -> Schema {
+    string_enum(Persona::ALL.iter().map(|persona| persona.name()))
+}
+
+fn engine_schema(_: &mut SchemaGenerator) -> Schema {
+    string_enum(Engine::ALL.iter().map(|engine| engine.name()))
+}
+
+fn template_schema(_: &mut SchemaGenerator) -> Schema {
+    string_enum(templates::names())
+}
+
+fn asset_kind_schema(_: &mut SchemaGenerator) -> Schema {
+    string_enum(AssetKind::ALL.iter().map(|kind| kind.name()))
+}
+
+fn level_status_schema(_: &mut SchemaGenerator) -> Schema {
+    string_enum(LevelStatus::ALL.iter().map(|status| status.name()))
+}
+
+fn review_depth_schema(_: &mut SchemaGenerator) -> Schema {
+    string_enum(ReviewDepth::ALL.iter().map(|depth| depth.name()))
+}
+
+fn debt_status_schema(_: &mut SchemaGenerator) -> Schema {
+    string_enum(DebtStatus::ALL.iter().map(|status| status.name()))
+}
+
+fn debt_priority_schema(_: &mut SchemaGenerator) -> Schema {
+    string_enum(DebtPriority::ALL.iter().map(|priority| priority.name()))
+}
+
+pub(crate) fn feature_status_schema(_: &mut SchemaGenerator) -> Schema {
+    string_enum([
+        "Planned",
+        "InProgress",
+        "Implemented",
+        "Reviewed",
+        "NeedsRework",
+        "AwaitingApproval",
+    ])
+}
+
+/// Parameters of tools that only take the session.
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct SessionParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct NextFeatureParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Number of alternative features to propose, ranked with a rationale
+    /// (optional, 2-5). Pick one with featureSelect; without it a single
+    /// feature is planned right away
+    pub candidates: Option<usize>,
+    /// Look up the modules and types of the engine's API the feature uses on
+    /// docs.rs and add the ones that exist to the specification (optional,
+    /// Bevy sessions only, ignored with candidates)
+    pub api_references: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct DesignBrainstormParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// What to brainstorm, e.g. "boss fight ideas" or "meta-progression"
+    pub topic: String,
+    /// Number of raw ideas to generate (optional, 5-30, defaults to 12)
+    pub ideas: Option<usize>,
+    /// Number of ideas to shortlist into the session's idea pool (optional,
+    /// 1-10, defaults to 3)
+    pub shortlist: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct StoryOutlineParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Have the designer draft the story from the design document and the
+    /// planned features (optional, requires an LLM provider)
+    #[serde(default)]
+    pub generate: bool,
+    /// Replace a story that was already started when generating (optional)
+    #[serde(default)]
+    pub replace: bool,
+    /// Dialogue style guide to set: tone, vocabulary and what to avoid
+    /// (optional; an empty string clears it)
+    pub style_guide: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct CharacterSheetParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Name of the character; a new name adds the character
+    pub name: String,
+    /// Part the character plays, e.g. protagonist or mentor (optional)
+    pub role: Option<String>,
+    /// Appearance and background (optional)
+    pub description: Option<String>,
+    /// What the character wants (optional)
+    pub motivation: Option<String>,
+    /// How the character changes over the story (optional)
+    pub arc: Option<String>,
+    /// How the character speaks (optional)
+    pub voice: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct BeatAddParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Number or name of the act; a new name appends an act
+    pub act: String,
+    /// Short title of the beat
+    pub title: String,
+    /// What happens and what changes for the player
+    pub description: String,
+    /// Names of the characters taking part (optional, must have a
+    /// character sheet)
+    #[serde(default)]
+    pub characters: Vec<String>,
+    /// Planned feature through which the player experiences the beat
+    /// (optional)
+    pub feature: Option<String>,
+    /// 1-based position of the beat within the act (optional, defaults to
+    /// last)
+    pub position: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct LevelBriefParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Name of the level
+    pub name: String,
+    /// What the level must achieve, e.g. "introduce wall jumping"; required
+    /// for a new level, rebriefs an existing one (optional)
+    #[serde(default)]
+    pub goals: Vec<String>,
+    /// Brief an existing level again with its previous goals (optional)
+    #[serde(default)]
+    pub regenerate: bool,
+    /// Move the level to this production status (optional)
+    #[serde(default)]
+    #[schemars(schema_with = "level_status_schema")]
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct EconomyResourceParams {
+    /// Name of the resource, e.g. "Coins"
+    pub name: String,
+    /// What the resource is and what it is for (optional)
+    pub description: Option<String>,
+    /// Most the player can hold at once (optional)
+    pub cap: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct EconomyFlowParams {
+    /// Name of the source or sink, e.g. "Quest rewards"
+    pub name: String,
+    /// Declared resource the flow adds or drains
+    pub resource: String,
+    /// Amount per hour of typical play
+    pub rate_per_hour: f64,
+    /// Planned feature the flow belongs to (optional)
+    pub feature: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct EconomyCurveParams {
+    /// Name of the progression, e.g. "Weapon upgrades"
+    pub name: String,
+    /// Declared resource the progression is paid with
+    pub resource: String,
+    /// Cost of each successive step
+    pub costs: Vec<u64>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct EconomyModelParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Resources to declare, replacing those with the same name (optional)
+    #[serde(default)]
+    pub resources: Vec<EconomyResourceParams>,
+    /// Where resources come from, replacing those with the same name
+    /// (optional)
+    #[serde(default)]
+    pub sources: Vec<EconomyFlowParams>,
+    /// Where resources go, replacing those with the same name (optional)
+    #[serde(default)]
+    pub sinks: Vec<EconomyFlowParams>,
+    /// Progressions paid with resources, replacing those with the same name
+    /// (optional)
+    #[serde(default)]
+    pub curves: Vec<EconomyCurveParams>,
+    /// Names of resources, sources, sinks or curves to remove (optional)
+    #[serde(default)]
+    pub remove: Vec<String>,
+    /// Have the designer simulate the balance and flag features that break
+    /// it (optional, requires an LLM provider)
+    #[serde(default)]
+    pub simulate: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct MonetizationReviewParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Markets the game ships in, e.g. "BE" or "EU", replacing the
+    /// configured regions (optional)
+    #[serde(default)]
+    pub regions: Vec<String>,
+    /// Whether players may be minors, overriding the configuration
+    /// (optional)
+    pub minors: Option<bool>,
+    /// Rules checked in addition to the configured ones (optional)
+    #[serde(default)]
+    pub rules: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct FeatureSelectParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Name or rank of the candidate to implement next
+    pub feature: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct FeatureRejectParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Why the feature is not wanted, shown when a later proposal resembles
+    /// it (optional)
+    pub reason: Option<String>,
+    /// Agent or person rejecting the feature (optional)
+    pub author: Option<String>,
+}
+
+/// Parameters of tools without any.
+#[derive(Debug, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct NoParams {}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct SessionListParams {
+    #[serde(flatten)]
+    pub page: PageParams,
+}
+
+/// Project metadata stamped into exports.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataParams {
+    /// Studio name stamped into exports (optional)
+    pub studio: Option<String>,
+    /// Authors of the design stamped into exports (optional)
+    #[serde(default)]
+    pub authors: Vec<String>,
+    /// License of the design documents, e.g. CC-BY-4.0 (optional)
+    pub license: Option<String>,
+    /// Copyright year stamped into exports (optional)
+    pub copyright_year: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct DesignNewParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Initial description of the game to be designed (required unless an
+    /// existing design document is given)
+    pub game_description: Option<String>,
+    /// Existing design document in Markdown to import, e.g. exported from
+    /// Notion (optional)
+    pub document: Option<String>,
+    /// Path of an existing Markdown design document to import, inside the
+    /// repository or else the server's working directory (optional)
+    pub document_path: Option<String>,
+    /// Designer persona preset (optional, defaults to 'default')
+    #[serde(default)]
+    #[schemars(schema_with = "persona_schema")]
+    pub persona: Option<String>,
+    /// Genre template that seeds design pillars, a starter feature backlog
+    /// and review criteria (optional)
+    #[serde(default)]
+    #[schemars(schema_with = "template_schema")]
+    pub template: Option<String>,
+    /// Path inside the game's git repository, enabling includeDiff in
+    /// featureReview (optional)
+    pub repo: Option<String>,
+    /// Engine the game is built with; feature specifications then include
+    /// engine-specific implementation hints (optional)
+    #[serde(default)]
+    #[schemars(schema_with = "engine_schema")]
+    pub engine: Option<String>,
+    /// Language the designer writes specifications, reviews and documents
+    /// in, e.g. German or 日本語 (optional, defaults to English)
+    pub language: Option<String>,
+    #[serde(flatten)]
+    pub metadata: MetadataParams,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct FeatureReviewParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// A detailed report of the changes implemented, potentially including
+    /// code snippets.
+    pub changes_made: String,
+    /// SHAs of the commits that implemented the feature (optional)
+    #[serde(default)]
+    pub commits: Vec<String>,
+    /// Minutes spent implementing the feature, if not tracked with
+    /// startWork/stopWork (optional)
+    pub time_spent_minutes: Option<u64>,
+    /// Attach the repository diff since the feature was handed out to the
+    /// review (optional, requires a session repository)
+    #[serde(default)]
+    pub include_diff: bool,
+    /// How deeply to review: 'quick' for a sign-off of small features,
+    /// 'thorough' for core systems (optional, defaults to the session's
+    /// review depth)
+    #[serde(default)]
+    #[schemars(schema_with = "review_depth_schema")]
+    pub review_depth: Option<String>,
+    /// Have a panel of design, technical and UX reviewers review in parallel
+    /// and agree on the verdict (optional, defaults to the session's review
+    /// panel setting)
+    pub review_panel: Option<bool>,
+    /// Screenshots or short captures of the feature as MCP image or embedded
+    /// resource content, stored with the session and shown to vision-capable
+    /// reviewers (optional)
+    #[serde(default)]
+    pub attachments: Vec<AttachmentParams>,
+    /// Agent or person submitting, recorded in the transcript (optional)
+    pub author: Option<String>,
+}
+
+/// An attachment of a review, as an MCP content part.
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum AttachmentParams {
+    /// An image, e.g. a screenshot
+    Image {
+        /// Base64-encoded image data
+        data: String,
+        /// MIME type of the image, e.g. image/png
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+    },
+    /// A binary resource, e.g. a short capture
+    Resource { resource: ResourceParams },
+}
+
+/// Contents of an embedded resource.
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceParams {
+    /// URI of the resource, e.g. the file it was read from
+    pub uri: String,
+    /// MIME type of the resource, e.g. video/mp4
+    pub mime_type: Option<String>,
+    /// Base64-encoded contents
+    pub blob: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct ReviewReplyParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// The answer or information provided in response to the LLM's
+    /// questions.
+    pub content: String,
+    /// SHAs of additional commits made while addressing the review (optional)
+    #[serde(default)]
+    pub commits: Vec<String>,
+    /// Minutes spent on the rework, if not tracked with startWork/stopWork
+    /// (optional)
+    pub time_spent_minutes: Option<u64>,
+    /// Agent or person submitting, recorded in the transcript (optional)
+    pub author: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct HumanApproveParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Feature awaiting approval (optional, defaults to the feature being
+    /// implemented)
+    pub feature: Option<String>,
+    /// true to sign the feature off as reviewed, false to send it back for
+    /// rework
+    pub approve: bool,
+    /// Person signing the decision
+    pub author: String,
+    /// Reason of the decision; required when sending the feature back
+    /// (optional)
+    pub comment: Option<String>,
+    /// Signature of the decision printed by `gamedesignerd sign-approval`
+    pub signature: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct FeatureAskParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// The question to ask the designer LLM.
+    pub question: String,
+    /// Agent or person asking, recorded in the transcript (optional)
+    pub author: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct SessionEventsParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Sequence number to start reading from (optional, defaults to 0). Pass
+    /// the returned nextCursor to continue.
+    pub cursor: Option<u64>,
+    /// Maximum number of events to return (optional, defaults to 100, max
+    /// 1000)
+    pub limit: Option<u64>,
+}
+
+/// Issue tracker to import a backlog from.
+#[derive(Debug, Clone, Copy, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackerSource {
+    Github,
+    Linear,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct BacklogImportParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// The issue tracker to import from
+    pub source: TrackerSource,
+    /// GitHub repository as owner/name (required for github; uses
+    /// GITHUB_TOKEN if set)
+    pub repository: Option<String>,
+    /// Linear team key, e.g. GAME (required for linear; uses LINEAR_API_KEY)
+    pub team: Option<String>,
+    /// Maximum number of issues to import (optional, defaults to 50)
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct DesignImportParams {
+    /// Unique identifier for the new design session
+    pub session_name: String,
+    /// The issue tracker to import from
+    pub source: TrackerSource,
+    /// GitHub repository as owner/name (required for github; uses
+    /// GITHUB_TOKEN if set)
+    pub repository: Option<String>,
+    /// Linear team key, e.g. GAME (required for linear; uses LINEAR_API_KEY)
+    pub team: Option<String>,
+    /// Maximum number of issues to import (optional, defaults to 100)
+    pub limit: Option<u32>,
+    /// Designer persona preset (optional, defaults to 'default')
+    #[serde(default)]
+    #[schemars(schema_with = "persona_schema")]
+    pub persona: Option<String>,
+    /// Language the designer writes specifications, reviews and documents
+    /// in, e.g. German or 日本語 (optional, defaults to English)
+    pub language: Option<String>,
+}
+
+/// Tracker to mirror the features of a session to.
+#[derive(Debug, Clone, Copy, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BoardTarget {
+    Github,
+    Trello,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct BoardSyncParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// github syncs the features with issues both ways; trello returns a
+    /// Trello board export
+    pub target: BoardTarget,
+    /// GitHub repository as owner/name (github only; defaults to the
+    /// repository of the last sync; uses GITHUB_TOKEN, which needs write
+    /// access to issues)
+    pub repository: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct DesignReviewRequestParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// The proposed change: a revised section, a diff, or a description of
+    /// the edit.
+    pub proposed_edit: String,
+    /// Why the change is proposed (optional)
+    pub rationale: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct QaHistoryParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Only return questions asked while this feature was being implemented
+    /// (optional)
+    pub feature: Option<String>,
+    #[serde(flatten)]
+    pub range: DateRangeParams,
+    #[serde(flatten)]
+    pub page: PageParams,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct DesignPersonaParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// The persona preset to switch to (optional)
+    #[serde(default)]
+    #[schemars(schema_with = "persona_schema")]
+    pub persona: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct DesignInterviewParams {
+    /// Unique identifier for the design session to create
+    pub session_name: String,
+    /// Answer to the pending question (omit to start the interview or repeat
+    /// the question)
+    pub answer: Option<String>,
+    /// Designer persona preset for the new session (optional)
+    #[serde(default)]
+    #[schemars(schema_with = "persona_schema")]
+    pub persona: Option<String>,
+}
+
+/// Output format of `assetList`.
+#[derive(Debug, Clone, Copy, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetListFormat {
+    #[default]
+    Text,
+    Csv,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct AssetListParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Only list assets of this kind (optional)
+    #[serde(default)]
+    #[schemars(schema_with = "asset_kind_schema")]
+    pub kind: Option<String>,
+    /// Output format (optional, defaults to 'text'). 'csv' also writes
+    /// .gamedesignerd/<sessionName>.assets.csv
+    #[serde(default)]
+    pub format: AssetListFormat,
+    #[serde(flatten)]
+    pub page: PageParams,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct DesignExportParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// File to write the document to, e.g. 'docs/GDD.md', inside the
+    /// repository or the data directory (optional)
+    pub path: Option<String>,
+    /// Enable or disable regenerating the document at the path after every
+    /// session change (optional)
+    pub auto_update: Option<bool>,
+}
+
+/// Parameters of `startWork` and `stopWork`.
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct WorkParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Feature name (optional, defaults to the next feature to implement)
+    pub feature: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct ReuseFromSessionParams {
+    /// Session to import into
+    pub session_name: String,
+    /// The system or mechanic to reuse
+    pub query: String,
+    /// Only search this session (optional, defaults to all other sessions)
+    pub source_session: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct ScopeReportParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Effort budget in points (S=1, M=2, L=4, XL=8) to store for the session
+    /// (optional)
+    pub budget: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct DevlogGenerateParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// First day of the range, YYYY-MM-DD (optional, defaults to 7 days
+    /// before 'until')
+    pub since: Option<String>,
+    /// Last day of the range, YYYY-MM-DD (optional, defaults to today)
+    pub until: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct DesignRetroParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// File to write the retrospective to as Markdown, e.g.
+    /// 'docs/retro.md', inside the repository or the data directory
+    /// (optional, defaults to '.gamedesignerd/<session>/retros/<date>.md')
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct FeatureListParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    #[serde(flatten)]
+    pub filter: FeatureFilterParams,
+    #[serde(flatten)]
+    pub page: PageParams,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct DebtListParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Only list items with this status (optional)
+    #[serde(default)]
+    #[schemars(schema_with = "debt_status_schema")]
+    pub status: Option<String>,
+    /// Only list items with this priority (optional)
+    #[serde(default)]
+    #[schemars(schema_with = "debt_priority_schema")]
+    pub priority: Option<String>,
+    /// Only list items surfaced by the review of this feature (optional)
+    pub feature: Option<String>,
+    #[serde(flatten)]
+    pub page: PageParams,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct CrateDocParams {
+    /// Name of the crate, e.g. 'bevy'
+    pub crate_name: String,
+    /// Path of an item in the crate, e.g. 'ecs::system::Query' (optional,
+    /// defaults to the crate's root documentation; a leading crate name is
+    /// stripped)
+    pub item_path: Option<String>,
+    /// Version of the crate (optional, defaults to latest)
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct StatusReportParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    #[serde(flatten)]
+    pub range: DateRangeParams,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct TestPlanParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Reviewed feature to plan tests for (optional, defaults to the feature
+    /// reviewed last)
+    pub feature: Option<String>,
+    /// Write a new plan even if the feature has one (optional)
+    #[serde(default)]
+    pub regenerate: bool,
+    /// File to write the plan to as Markdown, e.g. 'docs/tests/jump.md',
+    /// inside the repository or the data directory (optional)
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct UiCritiqueParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Feature the screenshot shows (optional, defaults to the feature being
+    /// implemented)
+    pub feature: Option<String>,
+    /// Screenshot of the feature in the game as an MCP image or embedded
+    /// resource content part
+    pub screenshot: AttachmentParams,
+    /// What the screenshot shows, e.g. the pause menu on a phone in
+    /// portrait mode (optional)
+    pub context: Option<String>,
+    /// Agent or person asking, recorded with the critique (optional)
+    pub author: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct TraceabilityReportParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    #[serde(flatten)]
+    pub filter: FeatureFilterParams,
+    #[serde(flatten)]
+    pub page: PageParams,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct CommitMessageParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Feature the changes belong to (optional, defaults to the feature
+    /// currently being implemented)
+    pub feature_name: Option<String>,
+    /// Description of the changes (optional, defaults to the feature's latest
+    /// implementation report)
+    pub changes_made: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct DesignEngineParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// The engine to switch to (optional)
+    #[serde(default)]
+    #[schemars(schema_with = "engine_schema")]
+    pub engine: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct SessionConfigureParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Language the designer writes in from now on, e.g. German; empty for
+    /// English (optional)
+    pub language: Option<String>,
+    /// Depth of feature reviews that don't choose one (optional)
+    #[serde(default)]
+    #[schemars(schema_with = "review_depth_schema")]
+    pub review_depth: Option<String>,
+    /// Whether feature reviews that don't choose go to a panel of reviewers
+    /// (optional)
+    pub review_panel: Option<bool>,
+    #[serde(flatten)]
+    pub metadata: MetadataParams,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct ConventionsSetParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Conventions by name, e.g. {"engineVersion": "Bevy 0.16", "targetFps":
+    /// "60 on a Steam Deck"}; an empty rule removes the convention (optional)
+    #[serde(default)]
+    pub conventions: BTreeMap<String, String>,
+    /// Remove the conventions that are not given (optional)
+    #[serde(default)]
+    pub replace: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct DesignSearchParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// What to look for, e.g. "that decision about save files"
+    pub query: String,
+    /// Maximum number of snippets (optional, defaults to 5)
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct SessionHistoryParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Event types to include, e.g. ["review.submitted", "feature.*"]
+    /// (optional, defaults to all)
+    #[serde(default)]
+    pub types: Vec<String>,
+    #[serde(flatten)]
+    pub range: DateRangeParams,
+    #[serde(flatten)]
+    pub page: PageParams,
+}
+
+/// Output format of `designDiff`.
+#[derive(Debug, Clone, Copy, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffFormat {
+    #[default]
+    Markdown,
+    Json,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct DesignDiffParams {
+    /// Base session to compare against
+    pub session_name: String,
+    /// Session compared with the base session
+    pub other_session: String,
+    /// Project of the other session (optional, defaults to the base session's
+    /// project)
+    pub other_project: Option<String>,
+    /// Output format (optional, defaults to 'markdown'). 'json' returns the
+    /// structured diff.
+    #[serde(default)]
+    pub format: DiffFormat,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct DesignUndoParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Sequence number of the first event of the change to undo, as shown by
+    /// sessionHistory (optional). The undo is refused if the last change
+    /// starts elsewhere, i.e. the session was changed in the meantime.
+    pub expected_seq: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct JobParams {
+    /// Id of the job, as returned by the call that started it
+    pub job_id: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct DesignFreezeParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Why the session is frozen, e.g. 'Shipped 1.0' (optional)
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[schemars(deny_unknown_fields)]
+pub struct DesignUnfreezeParams {
+    /// Unique identifier for the design session
+    pub session_name: String,
+    /// Must be true; guards against unfreezing a shipped design by accident
+    #[serde(default)]
+    pub confirm: bool,
+}
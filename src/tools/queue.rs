@@ -0,0 +1,241 @@
+//! Bounded work queue for LLM-backed tool calls, so that bursts of calls
+//! are rejected with a retry hint instead of piling up against the
+//! provider's rate limits.
+
+use crate::game_design::config;
+use std::{
+    env,
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Tools that call the designer LLM and therefore go through the queue.
+pub const LLM_TOOLS: &[&str] = &[
+    "designNew",
+    "nextFeature",
+    "featureSelect",
+    "featureReview",
+    "reviewReply",
+    "featureAsk",
+    "backlogImport",
+    "designImport",
+    "designReviewRequest",
+    "designInterview",
+    "designLint",
+    "designBrainstorm",
+    "storyOutline",
+    "levelBrief",
+    "economyModel",
+    "monetizationReview",
+    "testPlan",
+    "uiCritique",
+    "reuseFromSession",
+    "devlogGenerate",
+    "designRetro",
+    "commitMessage",
+];
+
+/// Tools of [`LLM_TOOLS`] that do nothing without the designer LLM. The
+/// others fall back to a degraded mode, e.g. `nextFeature` hands out
+/// already planned features.
+pub const LLM_REQUIRED_TOOLS: &[&str] = &[
+    "featureReview",
+    "reviewReply",
+    "featureAsk",
+    "backlogImport",
+    "designImport",
+    "designReviewRequest",
+    "designLint",
+    "designBrainstorm",
+    "monetizationReview",
+    "uiCritique",
+    "reuseFromSession",
+    "devlogGenerate",
+];
+
+/// Limits of the queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueConfig {
+    /// LLM-backed calls running at the same time.
+    pub concurrency: usize,
+    /// Calls allowed to wait for a free slot before new ones are rejected.
+    pub capacity: usize,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            concurrency: 2,
+            capacity: 8,
+        }
+    }
+}
+
+impl QueueConfig {
+    /// Reads the limits from `GAMEDESIGNER_LLM_CONCURRENCY` and
+    /// `GAMEDESIGNER_LLM_QUEUE`, falling back to the configuration file and
+    /// then to the defaults.
+    pub fn from_env() -> Self {
+        fn var(name: &str) -> Option<usize> {
+            env::var(name).ok().and_then(|value| value.parse().ok())
+        }
+
+        let defaults = Self::default();
+        let llm = config::current().llm.clone();
+
+        Self {
+            concurrency: var("GAMEDESIGNER_LLM_CONCURRENCY")
+                .or(llm.concurrency)
+                .unwrap_or(defaults.concurrency)
+                .max(1),
+            capacity: var("GAMEDESIGNER_LLM_QUEUE")
+                .or(llm.queue_capacity)
+                .unwrap_or(defaults.capacity),
+        }
+    }
+}
+
+/// Returned when the queue is full.
+#[derive(Debug, Clone, Copy)]
+pub struct Busy {
+    pub retry_after: Duration,
+}
+
+impl std::fmt::Display for Busy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "busy: too many LLM-backed tool calls are in progress; retry in {} seconds.",
+            self.retry_after.as_secs().max(1)
+        )
+    }
+}
+
+impl std::error::Error for Busy {}
+
+/// Average duration assumed before any call has completed.
+const INITIAL_AVERAGE_MS: u64 = 20_000;
+
+/// Concurrency-limited queue with a bounded number of waiting calls.
+#[derive(Debug)]
+pub struct LlmQueue {
+    config: RwLock<QueueConfig>,
+    slots: Arc<Semaphore>,
+    /// Calls running or waiting for a slot.
+    pending: Arc<AtomicUsize>,
+    /// Moving average of call durations, used for the retry hint.
+    average_ms: Arc<AtomicU64>,
+}
+
+/// A call counted as pending, uncounted on drop, so that calls given up on
+/// while they wait for a slot don't keep their place in the queue.
+struct Pending(Arc<AtomicUsize>);
+
+impl Drop for Pending {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// A slot in the queue, released on drop.
+pub struct QueueTicket {
+    _permit: OwnedSemaphorePermit,
+    _pending: Pending,
+    average_ms: Arc<AtomicU64>,
+    started_at: Instant,
+}
+
+impl Drop for QueueTicket {
+    fn drop(&mut self) {
+        let elapsed = self.started_at.elapsed().as_millis() as u64;
+        let average = self.average_ms.load(Ordering::Relaxed);
+        self.average_ms
+            .store((average * 3 + elapsed) / 4, Ordering::Relaxed);
+    }
+}
+
+impl LlmQueue {
+    pub fn new(config: QueueConfig) -> Self {
+        Self {
+            slots: Arc::new(Semaphore::new(config.concurrency)),
+            pending: Arc::new(AtomicUsize::new(0)),
+            average_ms: Arc::new(AtomicU64::new(INITIAL_AVERAGE_MS)),
+            config: RwLock::new(config),
+        }
+    }
+
+    /// Active limits of the queue.
+    pub fn config(&self) -> QueueConfig {
+        *self.config.read().unwrap()
+    }
+
+    /// Replaces the limits. Calls already running keep their slots; with
+    /// fewer slots, new calls wait until enough of them are released.
+    pub fn set_config(&self, config: QueueConfig) {
+        let previous = std::mem::replace(&mut *self.config.write().unwrap(), config);
+
+        if config.concurrency > previous.concurrency {
+            self.slots
+                .add_permits(config.concurrency - previous.concurrency);
+        } else if config.concurrency < previous.concurrency {
+            let excess = previous.concurrency - config.concurrency;
+            let forgotten = self.slots.forget_permits(excess);
+
+            // The rest are taken as running calls release them
+            if forgotten < excess {
+                let slots = self.slots.clone();
+                tokio::spawn(async move {
+                    if let Ok(permits) = slots.acquire_many_owned((excess - forgotten) as u32).await
+                    {
+                        permits.forget();
+                    }
+                });
+            }
+        }
+    }
+
+    /// Waits for a free slot, or fails right away with [`Busy`] if the
+    /// queue is full.
+    pub async fn enter(&self) -> Result<QueueTicket, Busy> {
+        let config = self.config();
+        let limit = config.concurrency + config.capacity;
+
+        let pending = self.pending.fetch_add(1, Ordering::SeqCst);
+        let guard = Pending(self.pending.clone());
+        if pending >= limit {
+            drop(guard);
+
+            // Time until the calls ahead are through, one round of slots at a time
+            let rounds = (pending + 1 - config.concurrency).div_ceil(config.concurrency);
+            let average = self.average_ms.load(Ordering::Relaxed);
+
+            return Err(Busy {
+                retry_after: Duration::from_millis(average * rounds as u64),
+            });
+        }
+
+        let permit = self
+            .slots
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("queue semaphore is never closed");
+
+        Ok(QueueTicket {
+            _permit: permit,
+            _pending: guard,
+            average_ms: self.average_ms.clone(),
+            started_at: Instant::now(),
+        })
+    }
+}
+
+impl Default for LlmQueue {
+    fn default() -> Self {
+        Self::new(QueueConfig::from_env())
+    }
+}
@@ -0,0 +1,223 @@
+//! Daily token budgets of sessions and the error returned when a session has
+//! used up one of its quotas.
+//!
+//! The tokens each session consumed today (UTC) are kept in
+//! `.gamedesignerd/usage/tokens.json`, so that restarting the server does
+//! not reset the budget. The file is read again before every check and
+//! charge, so that servers in other processes share the budget. Charges
+//! hold an advisory lock on `tokens.lock` from reading the file to replacing
+//! it, so that charges made at the same time add up, and replace it by
+//! renaming a complete copy, so that no reader sees it half-written.
+
+use crate::{
+    error::GameDesignerError,
+    game_design::{clock::Clock, session::persistence_dir},
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use mcp_core::handler::ToolError;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Returned when a session exceeded a quota. Sent to the client as a
+/// `budget_exhausted` [`GameDesignerError`], so that agents can tell it apart
+/// from other failures and know when to retry.
+#[derive(Debug, Clone)]
+pub struct QuotaExceeded {
+    /// The exceeded limit: `callsPerMinute`, `consecutiveNextFeature` or
+    /// `dailyTokens`.
+    pub limit: &'static str,
+    pub session: String,
+    pub message: String,
+    pub retry_after: Duration,
+}
+
+impl std::fmt::Display for QuotaExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for QuotaExceeded {}
+
+impl From<QuotaExceeded> for ToolError {
+    fn from(exceeded: QuotaExceeded) -> Self {
+        GameDesignerError::from(exceeded).into()
+    }
+}
+
+/// Tokens a session consumed on one day.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct DailyUsage {
+    date: NaiveDate,
+    tokens: u64,
+}
+
+/// Tokens consumed per session today, the day told by the clock of the
+/// session manager.
+pub struct TokenLedger {
+    path: PathBuf,
+    clock: Arc<dyn Clock>,
+    usage: Mutex<HashMap<String, DailyUsage>>,
+}
+
+impl TokenLedger {
+    /// Opens the ledger in the data directory.
+    pub fn in_data_dir(clock: Arc<dyn Clock>) -> Self {
+        // Not next to the sessions, where it would be taken for one
+        Self::open(persistence_dir().join("usage").join("tokens.json"), clock)
+    }
+
+    /// Opens the ledger stored at `path`. An unreadable ledger is started
+    /// afresh.
+    pub fn open(path: PathBuf, clock: Arc<dyn Clock>) -> Self {
+        let usage = read_usage(&path).unwrap_or_default();
+
+        Self {
+            path,
+            clock,
+            usage: Mutex::new(usage),
+        }
+    }
+
+    /// Tokens `session` consumed today, including those charged by servers
+    /// in other processes.
+    pub fn used_today(&self, session: &str) -> u64 {
+        let today = self.clock.now().date_naive();
+        let mut usage = self.usage.lock().unwrap();
+        reload(&self.path, &mut usage);
+
+        usage
+            .get(session)
+            .filter(|usage| usage.date == today)
+            .map_or(0, |usage| usage.tokens)
+    }
+
+    /// Time left until the daily budgets reset.
+    pub fn resets_in(&self) -> Duration {
+        until_midnight(self.clock.now())
+    }
+
+    /// Fails if `session` has used up its daily `budget`.
+    pub fn check(&self, session: &str, budget: Option<u64>) -> Result<(), QuotaExceeded> {
+        let Some(budget) = budget else {
+            return Ok(());
+        };
+
+        let used = self.used_today(session);
+        if used < budget {
+            return Ok(());
+        }
+
+        Err(QuotaExceeded {
+            limit: "dailyTokens",
+            session: session.to_string(),
+            message: format!(
+                "Session '{}' has used its daily budget of {} LLM tokens ({} used today); \
+                 it resets at midnight UTC.",
+                session, budget, used
+            ),
+            retry_after: self.resets_in(),
+        })
+    }
+
+    /// Adds `tokens` to what `session` consumed today.
+    pub fn charge(&self, session: &str, tokens: u64) {
+        if tokens == 0 {
+            return;
+        }
+
+        let today = self.clock.now().date_naive();
+        let mut usage = self.usage.lock().unwrap();
+        // Added to the latest counts under the lock the other servers charge
+        // under too, so that their charges are kept
+        let file_lock = self
+            .lock_file()
+            .inspect_err(|e| {
+                tracing::warn!("Failed to lock token usage {}: {}", self.path.display(), e)
+            })
+            .ok();
+        reload(&self.path, &mut usage);
+
+        usage.retain(|_, usage| usage.date == today);
+        usage
+            .entry(session.to_string())
+            .or_insert(DailyUsage {
+                date: today,
+                tokens: 0,
+            })
+            .tokens += tokens;
+
+        if let Err(e) = self.store(&usage) {
+            tracing::warn!("Failed to store token usage {}: {}", self.path.display(), e);
+        }
+        drop(file_lock);
+    }
+
+    /// Takes the advisory lock that guards the stored ledger from being read
+    /// for a charge until it is replaced. Released when the file is dropped.
+    fn lock_file(&self) -> anyhow::Result<File> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.path.with_extension("lock"))?;
+        file.lock()?;
+
+        Ok(file)
+    }
+
+    /// Replaces the stored ledger with `usage`, writing it to a temporary
+    /// file first.
+    fn store(&self, usage: &HashMap<String, DailyUsage>) -> anyhow::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let staged = self.path.with_extension("json.tmp");
+        fs::write(&staged, serde_json::to_string_pretty(usage)?)?;
+        fs::rename(&staged, &self.path)?;
+
+        Ok(())
+    }
+}
+
+/// Reads the ledger stored at `path`; `None` if there is none or it can't be
+/// parsed.
+fn read_usage(path: &Path) -> Option<HashMap<String, DailyUsage>> {
+    let json = fs::read_to_string(path).ok()?;
+
+    serde_json::from_str(&json)
+        .inspect_err(|e| tracing::warn!("Failed to parse token usage {}: {}", path.display(), e))
+        .ok()
+}
+
+/// Merges the ledger stored at `path` into `usage`. Counts only grow during
+/// a day, so the later day or the higher count of each session is kept.
+fn reload(path: &Path, usage: &mut HashMap<String, DailyUsage>) {
+    for (session, stored) in read_usage(path).unwrap_or_default() {
+        let entry = usage.entry(session).or_insert(stored);
+        if (stored.date, stored.tokens) > (entry.date, entry.tokens) {
+            *entry = stored;
+        }
+    }
+}
+
+/// Time left after `now` until the daily budgets reset.
+pub fn until_midnight(now: DateTime<Utc>) -> Duration {
+    let midnight = (now.date_naive() + chrono::Days::new(1))
+        .and_hms_opt(0, 0, 0)
+        .expect("midnight is a valid time")
+        .and_utc();
+
+    (midnight - now).to_std().unwrap_or_default()
+}
@@ -0,0 +1,463 @@
+//! Load test of the HTTP transport, run with `gamedesignerd bench`.
+//!
+//! Simulated MCP clients connect over SSE, complete the handshake and then
+//! call a fixed mix of tools on their design sessions at the same time.
+//! Several clients share a session when there are fewer sessions than
+//! clients, so that their calls contend for it. Unless a running server is
+//! named, the server is started in process with the mock LLM provider and a
+//! temporary sessions directory, and the report includes how long calls
+//! waited for the session locks.
+
+use crate::{
+    game_design::{
+        DesignerLlmClient, SessionManager, locks::LockSnapshot, mock_llm::MockLlmProvider,
+    },
+    tools::GameDesignerService,
+    transport::{MCP_PROTOCOL_VERSION, http_sse_server::App},
+};
+use anyhow::Result;
+use serde_json::{Value, json};
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::Barrier;
+
+/// Tools each client calls in turn: reads and LLM-backed writes of its
+/// session.
+const WORKLOAD: &[&str] = &[
+    "designOverview",
+    "nextFeature",
+    "featureReview",
+    "featureList",
+];
+
+/// Shape of a benchmark run.
+#[derive(Debug, Clone, Copy)]
+pub struct BenchConfig {
+    /// MCP clients calling tools at the same time.
+    pub clients: usize,
+    /// Tool calls each client makes.
+    pub calls: usize,
+    /// Design sessions the clients are spread over.
+    pub sessions: usize,
+}
+
+/// Latencies of the calls of one tool.
+#[derive(Debug, Clone, Default)]
+struct Latencies(Vec<Duration>);
+
+impl Latencies {
+    /// The latency `quantile` (0 to 1) of the calls are below.
+    fn quantile(&self, quantile: f64) -> Duration {
+        let mut sorted = self.0.clone();
+        sorted.sort_unstable();
+
+        let index = ((sorted.len() as f64 * quantile).ceil() as usize).saturating_sub(1);
+        sorted.get(index).copied().unwrap_or_default()
+    }
+}
+
+/// Results of a benchmark run.
+#[derive(Debug, Clone)]
+pub struct BenchReport {
+    pub config: BenchConfig,
+    /// Wall time of the timed calls.
+    pub elapsed: Duration,
+    latencies: BTreeMap<String, Latencies>,
+    /// Failed calls by error identifier, e.g. `busy`.
+    pub errors: BTreeMap<String, usize>,
+    /// Session manager lock waits during the run, for in-process servers.
+    pub locks: Option<LockSnapshot>,
+}
+
+impl BenchReport {
+    /// Number of timed calls.
+    pub fn calls(&self) -> usize {
+        self.latencies
+            .values()
+            .map(|latencies| latencies.0.len())
+            .sum()
+    }
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let calls = self.calls();
+        let failed = self.errors.values().sum::<usize>();
+
+        writeln!(
+            f,
+            "{} clients on {} sessions, {} tool calls in {:.2}s ({:.1} calls/s), {} failed",
+            self.config.clients,
+            self.config.sessions,
+            calls,
+            self.elapsed.as_secs_f64(),
+            calls as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON),
+            failed
+        )?;
+
+        writeln!(
+            f,
+            "\n{:<16} {:>6} {:>10} {:>10} {:>10}",
+            "tool", "calls", "p50", "p99", "max"
+        )?;
+        let all = Latencies(
+            self.latencies
+                .values()
+                .flat_map(|latencies| latencies.0.iter().copied())
+                .collect(),
+        );
+        for (tool, latencies) in self
+            .latencies
+            .iter()
+            .map(|(tool, latencies)| (tool.as_str(), latencies))
+            .chain(std::iter::once(("all", &all)))
+        {
+            writeln!(
+                f,
+                "{:<16} {:>6} {:>10} {:>10} {:>10}",
+                tool,
+                latencies.0.len(),
+                millis(latencies.quantile(0.5)),
+                millis(latencies.quantile(0.99)),
+                millis(latencies.quantile(1.0))
+            )?;
+        }
+
+        if !self.errors.is_empty() {
+            writeln!(f, "\nErrors:")?;
+            for (error, count) in &self.errors {
+                writeln!(f, "  {:<14} {}", error, count)?;
+            }
+        }
+
+        if let Some(locks) = &self.locks {
+            writeln!(
+                f,
+                "\nSession locks: {} acquisitions, {} contended ({:.0}%), {:.2}s waited \
+                 in total ({:.0}% of the call time), longest wait {}",
+                locks.acquisitions,
+                locks.contended,
+                locks.contended as f64 * 100.0 / locks.acquisitions.max(1) as f64,
+                locks.wait.as_secs_f64(),
+                locks.wait.as_secs_f64() * 100.0
+                    / all
+                        .0
+                        .iter()
+                        .sum::<Duration>()
+                        .as_secs_f64()
+                        .max(f64::EPSILON),
+                millis(locks.max_wait)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An MCP client connected over SSE.
+struct Client {
+    http: reqwest::Client,
+    /// The SSE stream the server answers on.
+    events: reqwest::Response,
+    /// Received bytes not yet parsed into events.
+    buffer: String,
+    /// URL requests are posted to.
+    endpoint: String,
+    next_id: u64,
+}
+
+impl Client {
+    /// Connects to the server at `base_url` and completes the handshake.
+    async fn connect(http: reqwest::Client, base_url: &str) -> Result<Self> {
+        let events = http
+            .get(format!("{}/sse", base_url))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let mut client = Self {
+            http,
+            events,
+            buffer: String::new(),
+            endpoint: String::new(),
+            next_id: 0,
+        };
+
+        let query = loop {
+            match client.next_event().await? {
+                Some((event, data)) if event == "endpoint" => break data,
+                Some(_) => continue,
+                None => return Err(anyhow::anyhow!("The server closed the SSE stream")),
+            }
+        };
+        client.endpoint = format!("{}/sse{}", base_url, query);
+
+        client
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": MCP_PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": {
+                        "name": "gamedesignerd-bench",
+                        "version": env!("CARGO_PKG_VERSION"),
+                    },
+                }),
+            )
+            .await?;
+        client
+            .post(json!({ "jsonrpc": "2.0", "method": "notifications/initialized", "params": {} }))
+            .await?;
+
+        Ok(client)
+    }
+
+    /// Reads the next event of the SSE stream as its type and data, or
+    /// `None` once the stream ends.
+    async fn next_event(&mut self) -> Result<Option<(String, String)>> {
+        loop {
+            if let Some(end) = self.buffer.find("\n\n") {
+                let block = self.buffer[..end].to_string();
+                self.buffer.drain(..end + 2);
+
+                let mut event = "message".to_string();
+                let mut data = Vec::new();
+                for line in block.lines() {
+                    if let Some(value) = line.strip_prefix("event:") {
+                        event = value.trim().to_string();
+                    } else if let Some(value) = line.strip_prefix("data:") {
+                        data.push(value.strip_prefix(' ').unwrap_or(value));
+                    }
+                }
+
+                return Ok(Some((event, data.join("\n"))));
+            }
+
+            match self.events.chunk().await? {
+                Some(chunk) => self.buffer.push_str(&String::from_utf8_lossy(&chunk)),
+                None => return Ok(None),
+            }
+        }
+    }
+
+    async fn post(&self, message: Value) -> Result<()> {
+        self.http
+            .post(&self.endpoint)
+            .json(&message)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// Sends a request and waits for its response.
+    async fn request(&mut self, method: &str, params: Value) -> Result<Value> {
+        self.next_id += 1;
+        let id = self.next_id;
+        self.post(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))
+            .await?;
+
+        // Notifications sent in between are skipped
+        loop {
+            let Some((event, data)) = self.next_event().await? else {
+                return Err(anyhow::anyhow!("The server closed the SSE stream"));
+            };
+            if event != "message" {
+                continue;
+            }
+
+            let message: Value = serde_json::from_str(&data)?;
+            if message["id"] != json!(id) {
+                continue;
+            }
+            if let Some(error) = message.get("error") {
+                return Err(anyhow::anyhow!("{} failed: {}", method, error));
+            }
+
+            return Ok(message["result"].clone());
+        }
+    }
+
+    /// Calls a tool. A failed call is returned as its error identifier.
+    async fn call(&mut self, tool: &str, mut arguments: Value) -> Result<Result<(), String>> {
+        arguments["outputFormat"] = json!("json");
+        let result = self
+            .request(
+                "tools/call",
+                json!({ "name": tool, "arguments": arguments }),
+            )
+            .await?;
+
+        if result["isError"].as_bool() != Some(true) {
+            return Ok(Ok(()));
+        }
+
+        let text = result["content"][0]["text"].as_str().unwrap_or_default();
+        let error = serde_json::from_str::<Value>(text)
+            .ok()
+            .and_then(|error| error["error"].as_str().map(str::to_string))
+            .unwrap_or_else(|| "failed".to_string());
+
+        Ok(Err(error))
+    }
+}
+
+fn session_name(index: usize) -> String {
+    format!("bench_{}", index)
+}
+
+/// Arguments of the `call`-th call of the workload on `session`.
+fn arguments(tool: &str, session: &str, call: usize) -> Value {
+    match tool {
+        "featureReview" => json!({
+            "sessionName": session,
+            "changesMade": format!("Implemented the feature, iteration {}.", call),
+        }),
+        _ => json!({ "sessionName": session }),
+    }
+}
+
+/// Runs the benchmark against the server at `base_url`, e.g.
+/// `http://127.0.0.1:8080`.
+pub async fn run(base_url: &str, config: BenchConfig) -> Result<BenchReport> {
+    measure(base_url, config, None).await
+}
+
+/// Runs the benchmark, taking the lock waits of the timed calls from
+/// `service` if the server runs in process.
+async fn measure(
+    base_url: &str,
+    config: BenchConfig,
+    service: Option<&GameDesignerService>,
+) -> Result<BenchReport> {
+    if config.clients == 0 || config.sessions == 0 {
+        return Err(anyhow::anyhow!(
+            "A benchmark needs at least one client and one session"
+        ));
+    }
+
+    let http = reqwest::Client::new();
+    let base_url = base_url.trim_end_matches('/').to_string();
+
+    // The sessions are created up front and not timed
+    let mut setup = Client::connect(http.clone(), &base_url).await?;
+    for index in 0..config.sessions {
+        let name = session_name(index);
+        let result = setup
+            .call(
+                "designNew",
+                json!({
+                    "sessionName": name,
+                    "gameDescription": "A benchmark game about cats defending a space station",
+                }),
+            )
+            .await?;
+
+        if let Err(error) = result {
+            return Err(anyhow::anyhow!(
+                "Failed to create session '{}': {}",
+                name,
+                error
+            ));
+        }
+    }
+
+    let start = Arc::new(Barrier::new(config.clients + 1));
+    let mut clients = Vec::with_capacity(config.clients);
+
+    for index in 0..config.clients {
+        let mut client = Client::connect(http.clone(), &base_url).await?;
+        let start = start.clone();
+        let session = session_name(index % config.sessions);
+
+        clients.push(tokio::spawn(async move {
+            start.wait().await;
+
+            let mut outcomes = Vec::with_capacity(config.calls);
+            for call in 0..config.calls {
+                let tool = WORKLOAD[call % WORKLOAD.len()];
+                let started_at = Instant::now();
+                let result = client.call(tool, arguments(tool, &session, call)).await?;
+                outcomes.push((tool, started_at.elapsed(), result));
+            }
+
+            anyhow::Ok(outcomes)
+        }));
+    }
+
+    start.wait().await;
+    let started_at = Instant::now();
+    let locks_before = service.map(GameDesignerService::lock_stats);
+
+    let mut latencies = BTreeMap::<String, Latencies>::new();
+    let mut errors = BTreeMap::new();
+    for client in clients {
+        for (tool, latency, result) in client.await?? {
+            latencies
+                .entry(tool.to_string())
+                .or_default()
+                .0
+                .push(latency);
+            if let Err(error) = result {
+                *errors.entry(error).or_default() += 1;
+            }
+        }
+    }
+
+    let elapsed = started_at.elapsed();
+    let locks = service
+        .map(GameDesignerService::lock_stats)
+        .zip(locks_before)
+        .map(|(after, before)| LockSnapshot {
+            acquisitions: after.acquisitions - before.acquisitions,
+            contended: after.contended - before.contended,
+            wait: after.wait - before.wait,
+            // The sessions are created one at a time, so the longest wait
+            // is one of the run
+            max_wait: after.max_wait,
+        });
+
+    Ok(BenchReport {
+        config,
+        elapsed,
+        latencies,
+        errors,
+        locks,
+    })
+}
+
+/// Starts the HTTP server in process with the mock LLM provider answering
+/// after `llm_latency`, keeping its sessions in `dir`, and runs the
+/// benchmark against it.
+pub async fn run_in_process(
+    dir: &Path,
+    config: BenchConfig,
+    llm_latency: Duration,
+) -> Result<BenchReport> {
+    fs::create_dir_all(dir.join("fixtures"))?;
+
+    let session_manager = SessionManager::in_dir(&dir.join("sessions"))?;
+    let llm_client = DesignerLlmClient::from_mock(
+        MockLlmProvider::with_fixtures(dir.join("fixtures")).with_latency(llm_latency),
+    );
+    let service = GameDesignerService::with(session_manager, Some(llm_client));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let base_url = format!("http://{}", listener.local_addr()?);
+    let router = App::new(service.clone()).router();
+    let server = tokio::spawn(async move { axum::serve(listener, router).await });
+
+    let report = measure(&base_url, config, Some(&service)).await;
+    server.abort();
+
+    report
+}
@@ -0,0 +1,123 @@
+//! Reads ahead of the MCP server on the client's byte stream to pick up
+//! `notifications/cancelled`.
+//!
+//! The server answers one request at a time, so a cancellation would only be
+//! read after the call it cancels has finished. The messages are therefore
+//! inspected as they arrive and passed on unchanged.
+
+use crate::tools::Cancellations;
+use serde::Deserialize;
+use serde_json::Value;
+use std::borrow::Cow;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader, ReadHalf, SimplexStream};
+
+/// Passes `read` on line by line, registering `tools/call` requests with
+/// `cancellations` and cancelling those the client gives up on.
+pub fn watch<R>(read: R, cancellations: Cancellations) -> ReadHalf<SimplexStream>
+where
+    R: AsyncRead + Send + Unpin + 'static,
+{
+    // 4KB
+    const BUFFER_SIZE: usize = 1 << 12;
+
+    let (reader, mut writer) = tokio::io::simplex(BUFFER_SIZE);
+
+    tokio::spawn(async move {
+        let mut read = BufReader::new(read);
+        let mut line = Vec::new();
+
+        loop {
+            line.clear();
+
+            match read.read_until(b'\n', &mut line).await {
+                Ok(0) => break,
+                Ok(_) => inspect(&line, &cancellations),
+                Err(e) => {
+                    tracing::warn!(?e, "failed to read from the client");
+                    break;
+                }
+            }
+
+            if writer.write_all(&line).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    reader
+}
+
+/// The parts of a JSON-RPC message the watcher needs. Everything else, such
+/// as the arguments of a tool call, is skipped without being parsed into
+/// values.
+#[derive(Deserialize)]
+struct Message<'a> {
+    #[serde(borrow)]
+    method: Option<Cow<'a, str>>,
+    id: Option<Value>,
+    #[serde(default, borrow)]
+    params: Params<'a>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Params<'a> {
+    /// Tool of a `tools/call` request.
+    #[serde(borrow)]
+    name: Option<Cow<'a, str>>,
+    /// Request of a `notifications/cancelled` notification.
+    request_id: Option<Value>,
+    #[serde(borrow)]
+    reason: Option<Cow<'a, str>>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Messages<'a> {
+    #[serde(borrow)]
+    Batch(Vec<Message<'a>>),
+    #[serde(borrow)]
+    One(Message<'a>),
+}
+
+/// Handles the messages of one line, a single message or a batch.
+///
+/// Lines without a tool call or cancellation, which are most of them, are
+/// passed over without parsing; the server parses every line again anyway.
+fn inspect(line: &[u8], cancellations: &Cancellations) {
+    let Ok(text) = std::str::from_utf8(line) else {
+        return;
+    };
+    if !text.contains("tools/call") && !text.contains("notifications/cancelled") {
+        return;
+    }
+
+    let messages = match serde_json::from_str::<Messages>(text) {
+        Ok(Messages::Batch(batch)) => batch,
+        Ok(Messages::One(message)) => vec![message],
+        Err(_) => return,
+    };
+
+    for message in messages {
+        let params = message.params;
+
+        match message.method.as_deref() {
+            Some("tools/call") => {
+                if let (Some(id), Some(tool)) = (message.id, params.name) {
+                    cancellations.register(id, tool.into_owned());
+                }
+            }
+            Some("notifications/cancelled") => {
+                let Some(id) = params.request_id else {
+                    continue;
+                };
+                let reason = params.reason.as_deref().unwrap_or_default();
+
+                if cancellations.cancel(&id) {
+                    tracing::info!(request_id = %id, reason, "tool call cancelled by the client");
+                }
+            }
+            _ => {}
+        }
+    }
+}
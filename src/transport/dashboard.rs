@@ -0,0 +1,194 @@
+//! Read-only web dashboard of the design sessions.
+//!
+//! A static page (`dashboard/index.html`, embedded at build time) renders
+//! the JSON endpoints below: the session list, a session's design overview,
+//! feature board and review transcripts, and its activity for the charts.
+//! Nothing here changes a session. Sessions are read from their files on
+//! every request, so the dashboard follows servers running in other
+//! processes.
+
+use crate::{
+    game_design::{
+        events::{EventFilter, SessionEvent},
+        project, session,
+        state::SessionState,
+    },
+    tools::GameDesignerService,
+};
+use axum::{
+    Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{Html, Json},
+    routing::get,
+};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+
+/// The dashboard page.
+const INDEX: &str = include_str!("../../dashboard/index.html");
+
+#[derive(Debug, serde::Deserialize)]
+pub struct ProjectQuery {
+    /// Project of the session; the server's default project if absent.
+    pub project: Option<String>,
+}
+
+/// Routes of the dashboard.
+pub fn router(service: GameDesignerService) -> Router {
+    Router::new()
+        .route("/", get(index_handler))
+        .route("/api/sessions", get(sessions_handler))
+        .route("/api/sessions/{session_name}", get(session_handler))
+        .route("/api/sessions/{session_name}/usage", get(usage_handler))
+        .with_state(service)
+}
+
+async fn index_handler() -> Html<&'static str> {
+    Html(INDEX)
+}
+
+/// Resolves the `?project=` of a request.
+fn resolve_project(project: Option<String>) -> Result<Option<String>, StatusCode> {
+    match project {
+        Some(project) => project::normalize(&project).map_err(|_| StatusCode::BAD_REQUEST),
+        None => Ok(project::default_project().map(str::to_string)),
+    }
+}
+
+/// Loads a session of `project`, answering `400` for names that would leave
+/// the sessions directory and `404` if there is none.
+async fn load(
+    service: &GameDesignerService,
+    project: Option<&str>,
+    session_name: &str,
+) -> Result<SessionState, StatusCode> {
+    // The path segment is percent-decoded, so it may well hold a `../`
+    session::check_session_id(session_name).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let manager = service
+        .sessions(project)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    manager
+        .read_session(session_name)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Every session of every project with its feature counts by status.
+async fn sessions_handler(
+    State(service): State<GameDesignerService>,
+) -> Result<Json<Value>, StatusCode> {
+    let projects = project::list().map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let mut sessions = Vec::new();
+
+    for project in std::iter::once(None).chain(projects.into_iter().map(Some)) {
+        let manager = service
+            .sessions(project.as_deref())
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        let ids = manager
+            .session_ids()
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+        for id in ids {
+            // Sessions that fail to load are reported by `doctor`
+            let Ok(Some(session)) = manager.read_session(&id) else {
+                continue;
+            };
+
+            let mut statuses = BTreeMap::<String, usize>::new();
+            for feature in &session.planned_features {
+                *statuses.entry(format!("{:?}", feature.status)).or_default() += 1;
+            }
+
+            sessions.push(json!({
+                "project": project,
+                "session": session.id,
+                "persona": session.persona.name(),
+                "features": session.planned_features.len(),
+                "statuses": statuses,
+                "nextFeature": session.next_feature_to_implement,
+            }));
+        }
+    }
+
+    Ok(Json(json!({ "sessions": sessions })))
+}
+
+/// A session's overview, features and review transcripts.
+async fn session_handler(
+    State(service): State<GameDesignerService>,
+    Path(session_name): Path<String>,
+    Query(ProjectQuery { project }): Query<ProjectQuery>,
+) -> Result<Json<Value>, StatusCode> {
+    let project = resolve_project(project)?;
+    let session = load(&service, project.as_deref(), &session_name).await?;
+
+    Ok(Json(json!({
+        "project": project,
+        "session": session.id,
+        "overview": session.initial_description,
+        "designVersion": session.design_version(),
+        "persona": session.persona.name(),
+        "template": session.template,
+        "metadata": session.metadata,
+        "nextFeature": session.next_feature_to_implement,
+        "decisions": session.design_memory,
+        "features": session.planned_features,
+        "reviews": session.review_log,
+        "questions": session.qa_log,
+    })))
+}
+
+/// The numbers behind the dashboard's charts: events per day and kind, the
+/// time spent per feature and the LLM tokens used today.
+async fn usage_handler(
+    State(service): State<GameDesignerService>,
+    Path(session_name): Path<String>,
+    Query(ProjectQuery { project }): Query<ProjectQuery>,
+) -> Result<Json<Value>, StatusCode> {
+    let project = resolve_project(project)?;
+    let session = load(&service, project.as_deref(), &session_name).await?;
+
+    let manager = service
+        .sessions(project.as_deref())
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let events = manager
+        .session_history(&session_name, &EventFilter::default(), usize::MAX)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    let time_spent = session
+        .planned_features
+        .iter()
+        .map(|feature| json!({ "feature": feature.name, "minutes": feature.time_spent_minutes }))
+        .collect::<Vec<_>>();
+
+    Ok(Json(json!({
+        "activity": activity(&events),
+        "timeSpent": time_spent,
+        "tokensToday": service.tokens_today(project.as_deref(), &session_name),
+    })))
+}
+
+/// Counts events per day and kind, oldest day first.
+fn activity(events: &[SessionEvent]) -> Vec<Value> {
+    let mut days = BTreeMap::<String, BTreeMap<&'static str, usize>>::new();
+
+    for event in events {
+        let day = event.timestamp.date_naive().to_string();
+        *days
+            .entry(day)
+            .or_default()
+            .entry(event.kind.as_str())
+            .or_default() += 1;
+    }
+
+    days.into_iter()
+        .map(|(date, counts)| json!({ "date": date, "counts": counts }))
+        .collect()
+}
@@ -0,0 +1,243 @@
+//! Discord bot that exposes the designer in a channel, so that teammates
+//! without an MCP client can take part in the design loop.
+//!
+//! The slash commands map to the tools of the same session:
+//!
+//! - `/nextfeature` calls `nextFeature`,
+//! - `/review changes:` calls `featureReview`,
+//! - `/reply content:` calls `reviewReply`,
+//! - `/ask question:` calls `featureAsk`.
+//!
+//! The bot serves the one session given on the command line, so that
+//! members of a server can't reach other sessions through it. With a guild,
+//! commands from anywhere else are refused. Reviews, replies and questions
+//! are recorded with the Discord user name as their author.
+
+use crate::tools::{GameDesignerService, GameToolsRouter};
+use anyhow::Result;
+use mcp_core::Content;
+use mcp_server::Router;
+use serde_json::json;
+use serenity::{
+    all::{
+        Command, CommandInteraction, CommandOptionType, Context, CreateCommand,
+        CreateCommandOption, CreateInteractionResponseFollowup, EditInteractionResponse,
+        EventHandler, GatewayIntents, GuildId, Interaction, Ready,
+    },
+    async_trait,
+};
+
+/// Longest message Discord accepts.
+const MESSAGE_LIMIT: usize = 2000;
+
+/// Runs the bot for `session` until the gateway connection ends.
+///
+/// Commands are registered for `guild` if given, where they are available
+/// right away and only accepted there, and globally otherwise, which can
+/// take up to an hour.
+pub async fn serve(
+    token: &str,
+    session: String,
+    guild: Option<u64>,
+    service: GameDesignerService,
+) -> Result<()> {
+    let handler = Handler {
+        router: GameToolsRouter::new(service),
+        session,
+        guild: guild.map(GuildId::new),
+    };
+
+    // Slash commands arrive as interactions, which need no privileged intents
+    let mut client = serenity::Client::builder(token, GatewayIntents::empty())
+        .event_handler(handler)
+        .await?;
+
+    client.start().await?;
+    Ok(())
+}
+
+struct Handler {
+    router: GameToolsRouter,
+    /// Session of every command.
+    session: String,
+    /// Guild the commands are restricted to, if any.
+    guild: Option<GuildId>,
+}
+
+/// The slash commands of the bot.
+fn commands() -> Vec<CreateCommand> {
+    let text = |name: &str, description: &str| {
+        CreateCommandOption::new(CommandOptionType::String, name, description).required(true)
+    };
+
+    vec![
+        CreateCommand::new("nextfeature")
+            .description("Get the specification of the next feature to implement"),
+        CreateCommand::new("review")
+            .description("Submit the implementation of the current feature for review")
+            .add_option(text("changes", "What was implemented and how")),
+        CreateCommand::new("reply")
+            .description("Answer the designer's questions from the last review")
+            .add_option(text("content", "Your answers")),
+        CreateCommand::new("ask")
+            .description("Ask the designer about the current feature or the design")
+            .add_option(text("question", "Your question")),
+    ]
+}
+
+#[async_trait]
+impl EventHandler for Handler {
+    async fn ready(&self, ctx: Context, ready: Ready) {
+        let result = match self.guild {
+            Some(guild) => guild.set_commands(&ctx.http, commands()).await,
+            None => Command::set_global_commands(&ctx.http, commands()).await,
+        };
+
+        match result {
+            Ok(commands) => tracing::info!(
+                "Discord bot '{}' ready with {} commands",
+                ready.user.name,
+                commands.len()
+            ),
+            Err(e) => tracing::error!("Failed to register Discord commands: {}", e),
+        }
+    }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        let Interaction::Command(command) = interaction else {
+            return;
+        };
+
+        if let Err(e) = self.respond(&ctx, &command).await {
+            tracing::warn!("Failed to answer /{}: {}", command.data.name, e);
+        }
+    }
+}
+
+impl Handler {
+    async fn respond(&self, ctx: &Context, command: &CommandInteraction) -> Result<()> {
+        // The designer may take longer than the three seconds Discord waits
+        // for an answer
+        command.defer(&ctx.http).await?;
+
+        let text = match self.call(command).await {
+            Ok(text) => text,
+            Err(e) => format!("Error: {}", e),
+        };
+
+        let mut chunks = split_message(&text).into_iter();
+        let first = chunks.next().unwrap_or_else(|| "(no output)".to_string());
+        command
+            .edit_response(&ctx.http, EditInteractionResponse::new().content(first))
+            .await?;
+
+        for chunk in chunks {
+            command
+                .create_followup(
+                    &ctx.http,
+                    CreateInteractionResponseFollowup::new().content(chunk),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Runs the tool of `command` and returns its text output.
+    async fn call(&self, command: &CommandInteraction) -> Result<String> {
+        let option = |name: &str| {
+            command
+                .data
+                .options
+                .iter()
+                .find(|option| option.name == name)
+                .and_then(|option| option.value.as_str())
+                .map(str::to_string)
+        };
+
+        if self.guild.is_some() && command.guild_id != self.guild {
+            return Err(anyhow::anyhow!(
+                "This bot only takes commands in its own server"
+            ));
+        }
+
+        let session = self.session.as_str();
+        let author = command.user.name.clone();
+
+        let (tool, arguments) = match command.data.name.as_str() {
+            "nextfeature" => ("nextFeature", json!({ "sessionName": session })),
+            "review" => (
+                "featureReview",
+                json!({
+                    "sessionName": session,
+                    "changesMade": option("changes").unwrap_or_default(),
+                    "author": author,
+                }),
+            ),
+            "reply" => (
+                "reviewReply",
+                json!({
+                    "sessionName": session,
+                    "content": option("content").unwrap_or_default(),
+                    "author": author,
+                }),
+            ),
+            "ask" => (
+                "featureAsk",
+                json!({
+                    "sessionName": session,
+                    "question": option("question").unwrap_or_default(),
+                    "author": author,
+                }),
+            ),
+            name => return Err(anyhow::anyhow!("Unknown command /{}", name)),
+        };
+
+        tracing::info!(tool, session, author, "Discord command");
+
+        let content = self.router.call_tool(tool, arguments).await?;
+        Ok(text_of(content))
+    }
+}
+
+/// Joins the text blocks of a tool's output.
+fn text_of(content: Vec<Content>) -> String {
+    content
+        .into_iter()
+        .filter_map(|content| match content {
+            Content::Text(text) => Some(text.text),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Splits `text` into messages Discord accepts, preferring line breaks.
+fn split_message(text: &str) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut chunk = String::new();
+
+    for line in text.split_inclusive('\n') {
+        if chunk.len() + line.len() > MESSAGE_LIMIT && !chunk.is_empty() {
+            chunks.push(std::mem::take(&mut chunk));
+        }
+
+        // A single line longer than a message is cut at character boundaries
+        let mut line = line;
+        while line.len() > MESSAGE_LIMIT {
+            let mut end = MESSAGE_LIMIT;
+            while !line.is_char_boundary(end) {
+                end -= 1;
+            }
+            chunks.push(line[..end].to_string());
+            line = &line[end..];
+        }
+        chunk.push_str(line);
+    }
+
+    if !chunk.trim().is_empty() {
+        chunks.push(chunk);
+    }
+
+    chunks
+}
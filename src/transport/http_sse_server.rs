@@ -1,15 +1,25 @@
-use crate::{tools::DocRouter, transport::jsonrpc_frame_codec::JsonRpcFrameCodec};
+use crate::{
+    game_design::{
+        diagnostics, events::SessionEvent, project, residency::ProjectResidency, session,
+    },
+    tools::{GameDesignerService, GameToolsRouter},
+    transport::{cancellation, jsonrpc_frame_codec::JsonRpcFrameCodec, telemetry},
+};
 use axum::{
     Router,
     body::Body,
-    extract::{Query, State},
-    http::StatusCode,
-    response::sse::{Event, Sse},
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{
+        Json,
+        sse::{Event, KeepAlive, Sse},
+    },
     routing::get,
 };
 use futures::{Stream, StreamExt, TryStreamExt};
 use mcp_server::{ByteTransport, Server, router::RouterService};
-use std::{collections::HashMap, sync::Arc};
+use serde_json::{Value, json};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 use tokio::{
     io::{self, AsyncWriteExt},
     sync::Mutex,
@@ -19,22 +29,33 @@ use tokio_util::codec::FramedRead;
 type C2SWriter = Arc<Mutex<io::WriteHalf<io::SimplexStream>>>;
 type SessionId = Arc<str>;
 
-#[derive(Clone, Default)]
+#[derive(Clone)]
 pub struct App {
     pub txs: Arc<tokio::sync::RwLock<HashMap<SessionId, C2SWriter>>>,
+    /// Shared by the routers of all SSE connections.
+    pub service: GameDesignerService,
 }
 
 impl App {
-    pub fn new() -> Self {
+    pub fn new(service: GameDesignerService) -> Self {
         Self {
             txs: Default::default(),
+            service,
         }
     }
 
     pub fn router(&self) -> Router {
         Router::new()
             .route("/sse", get(sse_handler).post(post_event_handler))
+            .route("/sessions/stats", get(session_stats_handler))
+            .route(
+                "/sessions/{session_name}/events",
+                get(session_events_handler),
+            )
+            .route("/healthz", get(healthz_handler))
+            .route("/readyz", get(readyz_handler))
             .with_state(self.clone())
+            .merge(telemetry::router())
     }
 }
 
@@ -122,9 +143,11 @@ async fn sse_handler(State(app): State<App>) -> Sse<impl Stream<Item = Result<Ev
         let session = session.clone();
 
         async move {
-            let router = RouterService(DocRouter::new());
-            let server = Server::new(router);
+            let router = GameToolsRouter::new(app_clone.service.clone());
+            let c2s_read = cancellation::watch(c2s_read, router.cancellations());
+            let server = Server::new(RouterService(router));
             let bytes_transport = ByteTransport::new(c2s_read, s2c_write);
+            let _active = telemetry::ActiveSession::start();
 
             let _result = server
                 .run(bytes_transport)
@@ -141,7 +164,7 @@ async fn sse_handler(State(app): State<App>) -> Sse<impl Stream<Item = Result<Ev
             .data(format!("?sessionId={session}")),
     ))
     .chain(
-        FramedRead::new(s2c_read, JsonRpcFrameCodec)
+        FramedRead::new(s2c_read, JsonRpcFrameCodec::default())
             .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
             .and_then(move |bytes| match std::str::from_utf8(bytes.as_ref()) {
                 Ok(message) => futures::future::ok(Event::default().event("message").data(message)),
@@ -151,3 +174,152 @@ async fn sse_handler(State(app): State<App>) -> Sse<impl Stream<Item = Result<Ev
 
     Sse::new(stream)
 }
+
+#[derive(Debug, serde::Deserialize)]
+pub struct SessionEventsQuery {
+    pub cursor: Option<u64>,
+    /// Project of the session; the server's default project if absent.
+    pub project: Option<String>,
+}
+
+/// Streams a design session's event log, starting at `?cursor=` or right after
+/// the `Last-Event-ID` sent by a reconnecting client.
+async fn session_events_handler(
+    State(app): State<App>,
+    Path(session_name): Path<String>,
+    Query(SessionEventsQuery { cursor, project }): Query<SessionEventsQuery>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, io::Error>>>, StatusCode> {
+    const POLL_INTERVAL: Duration = Duration::from_secs(1);
+    const BATCH_SIZE: usize = 100;
+
+    // The name becomes part of a path, so it is checked before any lookup
+    session::check_session_id(&session_name).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let project = match project {
+        Some(project) => project::normalize(&project).map_err(|_| StatusCode::BAD_REQUEST)?,
+        None => project::default_project().map(str::to_string),
+    };
+    let manager = app
+        .service
+        .sessions(project.as_deref())
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if !manager.session_exists(&session_name) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    let cursor = last_event_id
+        .map(|id| id.saturating_add(1))
+        .or(cursor)
+        .unwrap_or(0);
+
+    tracing::info!(%session_name, cursor, "session events connection");
+
+    let batches = futures::stream::unfold(cursor, move |cursor| {
+        let manager = manager.clone();
+        let session_name = session_name.clone();
+
+        async move {
+            loop {
+                match manager
+                    .event_log()
+                    .read_since(&session_name, cursor, BATCH_SIZE)
+                {
+                    Ok(events) if events.is_empty() => tokio::time::sleep(POLL_INTERVAL).await,
+                    Ok(events) => {
+                        let next_cursor = events.last().map_or(cursor, |event| event.seq + 1);
+                        return Some((Ok(events), next_cursor));
+                    }
+                    Err(e) => return Some((Err(io::Error::other(e.to_string())), cursor)),
+                }
+            }
+        }
+    });
+
+    let stream = batches
+        .map_ok(|events| futures::stream::iter(events.into_iter().map(Ok::<_, io::Error>)))
+        .try_flatten()
+        .and_then(|event: SessionEvent| async move {
+            let data = serde_json::to_string(&event).map_err(io::Error::other)?;
+
+            Ok::<_, io::Error>(
+                Event::default()
+                    .event(event.kind.as_str())
+                    .id(event.seq.to_string())
+                    .data(data),
+            )
+        });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// Describes the sessions the session managers keep in memory.
+async fn session_stats_handler(State(app): State<App>) -> Json<Vec<ProjectResidency>> {
+    Json(app.service.residency().await)
+}
+
+/// Turns named check results into a response: `200` if all passed and `503`
+/// otherwise, with the outcome of each check in the body.
+fn health_response(checks: Vec<(&str, Result<(), String>)>) -> (StatusCode, Json<Value>) {
+    let healthy = checks.iter().all(|(_, result)| result.is_ok());
+    let checks = checks
+        .into_iter()
+        .map(|(name, result)| (name.to_string(), json!(result.err().unwrap_or("ok".into()))))
+        .collect::<serde_json::Map<_, _>>();
+
+    let status = if healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        status,
+        Json(json!({
+            "status": if healthy { "ok" } else { "unavailable" },
+            "checks": checks,
+        })),
+    )
+}
+
+/// Liveness probe.
+async fn healthz_handler() -> (StatusCode, Json<Value>) {
+    health_response(vec![("persistence", diagnostics::persistence_writable())])
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct ReadyzQuery {
+    /// Also ping the LLM provider.
+    #[serde(default)]
+    pub llm: bool,
+}
+
+/// Readiness probe. With `?llm=true` the LLM provider must answer a ping too.
+async fn readyz_handler(
+    State(app): State<App>,
+    Query(ReadyzQuery { llm }): Query<ReadyzQuery>,
+) -> (StatusCode, Json<Value>) {
+    const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+    let mut checks = vec![("persistence", diagnostics::persistence_writable())];
+
+    if llm {
+        let result = match tokio::time::timeout(PING_TIMEOUT, app.service.ping_llm()).await {
+            Ok(result) => result.map_err(|e| e.to_string()),
+            Err(_) => Err(format!(
+                "LLM provider did not answer within {} seconds",
+                PING_TIMEOUT.as_secs()
+            )),
+        };
+
+        checks.push(("llm", result));
+    }
+
+    health_response(checks)
+}
@@ -1,7 +1,14 @@
 use tokio_util::codec::Decoder;
 
+/// Splits newline-delimited JSON-RPC messages.
+///
+/// Remembers how much of the buffer holds no newline, so that a large
+/// message arriving in many chunks is scanned once rather than once per
+/// chunk.
 #[derive(Default)]
-pub struct JsonRpcFrameCodec;
+pub struct JsonRpcFrameCodec {
+    scanned: usize,
+}
 
 impl Decoder for JsonRpcFrameCodec {
     type Item = tokio_util::bytes::Bytes;
@@ -11,15 +18,21 @@ impl Decoder for JsonRpcFrameCodec {
         &mut self,
         src: &mut tokio_util::bytes::BytesMut,
     ) -> Result<Option<Self::Item>, Self::Error> {
-        Ok(src
-            .iter()
-            .enumerate()
-            .find_map(|(idx, &b)| (b == b'\n').then_some(idx))
-            .map(|end| {
-                let line = src.split_to(end);
+        let start = self.scanned.min(src.len());
+
+        match src[start..].iter().position(|&b| b == b'\n') {
+            Some(offset) => {
+                self.scanned = 0;
+
+                let line = src.split_to(start + offset);
                 let _char_next_line = src.split_to(1);
 
-                line.freeze()
-            }))
+                Ok(Some(line.freeze()))
+            }
+            None => {
+                self.scanned = src.len();
+                Ok(None)
+            }
+        }
     }
 }
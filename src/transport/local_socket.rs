@@ -0,0 +1,155 @@
+//! Serves the MCP byte transport over a local socket: a Unix domain socket on
+//! Unix platforms and a named pipe on Windows.
+
+use crate::{
+    tools::{GameDesignerService, GameToolsRouter},
+    transport::{cancellation, telemetry::ActiveSession},
+};
+use anyhow::Result;
+use mcp_server::{ByteTransport, Server, router::RouterService};
+use std::path::Path;
+
+/// Runs a fresh MCP server over a single accepted connection, or over any
+/// other byte stream, such as an in-memory one in tests.
+pub async fn serve_connection<S>(stream: S, service: GameDesignerService)
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Send + 'static,
+{
+    let router = GameToolsRouter::new(service);
+
+    let (read, write) = tokio::io::split(stream);
+    let read = cancellation::watch(read, router.cancellations());
+    let server = Server::new(RouterService(router));
+    let transport = ByteTransport::new(read, write);
+    let _active = ActiveSession::start();
+
+    if let Err(e) = server.run(transport).await {
+        tracing::error!(?e, "socket connection error");
+    }
+}
+
+/// Pause after a failed accept, so that running out of file descriptors
+/// doesn't turn the accept loop into a busy loop.
+#[cfg(unix)]
+const ACCEPT_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Removes the socket file when the listener goes away.
+#[cfg(unix)]
+struct SocketFileGuard(std::path::PathBuf);
+
+#[cfg(unix)]
+impl Drop for SocketFileGuard {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.0) {
+            tracing::warn!(path = %self.0.display(), ?e, "failed to remove socket file");
+        }
+    }
+}
+
+/// Removes the socket file at `path` if a crashed server left it behind.
+///
+/// Anything other than a socket, and a socket another server still accepts
+/// connections on, is left alone and reported as an error.
+#[cfg(unix)]
+async fn remove_stale_socket(path: &Path) -> Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+
+    if !metadata.file_type().is_socket() {
+        anyhow::bail!("{} exists and is not a socket", path.display());
+    }
+
+    if tokio::net::UnixStream::connect(path).await.is_ok() {
+        anyhow::bail!("another server is listening on {}", path.display());
+    }
+
+    tracing::warn!(path = %path.display(), "removing stale socket file");
+    std::fs::remove_file(path)?;
+
+    Ok(())
+}
+
+/// Listens on a Unix domain socket at `path` until Ctrl-C or `SIGTERM` is
+/// received.
+///
+/// A stale socket file left behind by a crashed server is replaced; the file
+/// is removed again on exit.
+#[cfg(unix)]
+pub async fn serve(path: &Path, service: GameDesignerService) -> Result<()> {
+    use tokio::{
+        net::UnixListener,
+        signal::unix::{SignalKind, signal},
+    };
+
+    remove_stale_socket(path).await?;
+
+    let mut terminate = signal(SignalKind::terminate())?;
+    let listener = UnixListener::bind(path)?;
+    let _guard = SocketFileGuard(path.to_path_buf());
+
+    tracing::info!("Game Designer MCP server listening on {}", path.display());
+
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _addr)) => {
+                    tracing::info!("socket connection accepted");
+                    tokio::spawn(serve_connection(stream, service.clone()));
+                }
+                // The listener itself is broken
+                Err(e) if e.kind() == std::io::ErrorKind::InvalidInput => return Err(e.into()),
+                Err(e) => {
+                    // E.g. a client that hung up before it was accepted, or
+                    // too many open files, which clears up as connections
+                    // close
+                    tracing::warn!(?e, "failed to accept a socket connection");
+                    tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+                }
+            },
+            _ = tokio::signal::ctrl_c() => {
+                tracing::info!("shutting down socket server");
+                return Ok(());
+            }
+            _ = terminate.recv() => {
+                tracing::info!("shutting down socket server on SIGTERM");
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Listens on a Windows named pipe at `path` (e.g. `\\.\pipe\gamedesignerd`)
+/// until Ctrl-C is received.
+#[cfg(windows)]
+pub async fn serve(path: &Path, service: GameDesignerService) -> Result<()> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let mut server = ServerOptions::new()
+        .first_pipe_instance(true)
+        .create(path)?;
+
+    tracing::info!("Game Designer MCP server listening on {}", path.display());
+
+    loop {
+        tokio::select! {
+            connected = server.connect() => {
+                connected?;
+                tracing::info!("named pipe connection accepted");
+
+                // Create the next pipe instance before handing this one off so
+                // that clients never observe a missing pipe.
+                let stream = std::mem::replace(&mut server, ServerOptions::new().create(path)?);
+                tokio::spawn(serve_connection(stream, service.clone()));
+            }
+            _ = tokio::signal::ctrl_c() => {
+                tracing::info!("shutting down named pipe server");
+                return Ok(());
+            }
+        }
+    }
+}
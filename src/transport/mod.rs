@@ -1,2 +1,29 @@
+pub mod bench;
+pub mod cancellation;
+pub mod dashboard;
+pub mod discord;
 pub mod http_sse_server;
 pub mod jsonrpc_frame_codec;
+pub mod local_socket;
+pub mod telemetry;
+
+/// Newest version of the MCP protocol the server speaks.
+pub const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// Versions of the MCP protocol the server speaks, newest first.
+pub const MCP_PROTOCOL_VERSIONS: &[&str] = &[MCP_PROTOCOL_VERSION];
+
+/// Picks the protocol version of a connection from the one the client
+/// requested on `initialize`: that version if the server speaks it, otherwise
+/// the newest one the server speaks, which the client may reject by
+/// disconnecting.
+pub fn negotiate_protocol_version(requested: Option<&str>) -> &'static str {
+    requested
+        .and_then(|requested| {
+            MCP_PROTOCOL_VERSIONS
+                .iter()
+                .find(|&&version| version == requested)
+        })
+        .copied()
+        .unwrap_or(MCP_PROTOCOL_VERSION)
+}
@@ -0,0 +1,119 @@
+//! Prometheus metrics of the server, exposed at `/metrics`.
+//!
+//! The recorder is installed once per process, so tool calls and LLM requests
+//! are counted the same way in every transport. The HTTP server serves the
+//! metrics next to its MCP routes; the stdio server serves them on
+//! `--metrics-address` when one is given.
+
+use anyhow::Result;
+use axum::{Router, http::header, response::IntoResponse, routing::get};
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use std::{net::SocketAddr, sync::OnceLock};
+
+/// Histogram buckets in seconds, spanning fast local tools to slow LLM calls.
+const DURATION_BUCKETS: &[f64] = &[
+    0.005, 0.025, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0,
+];
+
+static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// Installs the process-wide metrics recorder. Subsequent calls do nothing.
+pub fn install() -> Result<()> {
+    if HANDLE.get().is_some() {
+        return Ok(());
+    }
+
+    let handle = PrometheusBuilder::new()
+        .set_buckets(DURATION_BUCKETS)?
+        .install_recorder()?;
+    describe();
+
+    HANDLE
+        .set(handle)
+        .map_err(|_| anyhow::anyhow!("metrics recorder was installed concurrently"))
+}
+
+fn describe() {
+    metrics::describe_counter!(
+        "gamedesignerd_tool_calls_total",
+        "Tool calls by tool and outcome"
+    );
+    metrics::describe_counter!(
+        "gamedesignerd_deprecated_tool_calls_total",
+        "Tool calls made under a deprecated alias, by alias"
+    );
+    metrics::describe_histogram!(
+        "gamedesignerd_tool_call_duration_seconds",
+        metrics::Unit::Seconds,
+        "Duration of tool calls"
+    );
+    metrics::describe_histogram!(
+        "gamedesignerd_llm_request_duration_seconds",
+        metrics::Unit::Seconds,
+        "Latency of LLM requests, including cache hits"
+    );
+    metrics::describe_counter!("gamedesignerd_llm_errors_total", "Failed LLM requests");
+    metrics::describe_counter!(
+        "gamedesignerd_llm_tokens_total",
+        "Tokens consumed by LLM requests, as reported by the provider or estimated"
+    );
+    metrics::describe_counter!(
+        "gamedesignerd_llm_cached_prompt_tokens_total",
+        "Prompt tokens the provider read from its prompt cache"
+    );
+    metrics::describe_histogram!(
+        "gamedesignerd_session_lock_wait_seconds",
+        metrics::Unit::Seconds,
+        "Time tool calls waited to lock a session manager"
+    );
+    metrics::describe_gauge!(
+        "gamedesignerd_active_sessions",
+        "MCP client sessions currently connected"
+    );
+}
+
+/// Renders the metrics in the Prometheus text format.
+pub fn render() -> String {
+    HANDLE.get().map_or_else(String::new, |handle| {
+        handle.run_upkeep();
+        handle.render()
+    })
+}
+
+async fn metrics_handler() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        render(),
+    )
+}
+
+/// Routes serving `/metrics`.
+pub fn router() -> Router {
+    Router::new().route("/metrics", get(metrics_handler))
+}
+
+/// Serves `/metrics` on its own listener, for transports without HTTP.
+pub async fn serve(address: SocketAddr) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(address).await?;
+    tracing::info!("Serving metrics at http://{}/metrics", address);
+
+    axum::serve(listener, router()).await?;
+    Ok(())
+}
+
+/// Counts a connected MCP client session in `gamedesignerd_active_sessions`
+/// while alive.
+pub struct ActiveSession(());
+
+impl ActiveSession {
+    pub fn start() -> Self {
+        metrics::gauge!("gamedesignerd_active_sessions").increment(1);
+        Self(())
+    }
+}
+
+impl Drop for ActiveSession {
+    fn drop(&mut self) {
+        metrics::gauge!("gamedesignerd_active_sessions").decrement(1);
+    }
+}
@@ -0,0 +1,200 @@
+//! `gamedesignerd self-update`: replaces the running binary with the one of
+//! the latest GitHub release.
+//!
+//! Every release carries one binary per platform, named
+//! `gamedesignerd-<arch>-<os>[.exe]` (e.g. `gamedesignerd-x86_64-linux`),
+//! and next to it a `<binary>.sha256` file holding its SHA-256 checksum. A
+//! binary whose checksum is missing or does not match is not installed.
+
+use crate::game_design::trackers::USER_AGENT;
+use anyhow::Result;
+use reqwest::Client;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+/// `owner/name` of the repository whose releases are installed.
+fn repository() -> &'static str {
+    env!("CARGO_PKG_REPOSITORY").trim_start_matches("https://github.com/")
+}
+
+/// A published GitHub release.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub html_url: String,
+    #[serde(default)]
+    assets: Vec<Asset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+impl Release {
+    /// Version of the release, its tag without the `v` prefix.
+    pub fn version(&self) -> &str {
+        self.tag_name.trim_start_matches('v')
+    }
+
+    fn asset(&self, name: &str) -> Option<&Asset> {
+        self.assets.iter().find(|asset| asset.name == name)
+    }
+}
+
+/// Name of the release binary of this platform.
+pub fn asset_name() -> String {
+    format!(
+        "gamedesignerd-{}-{}{}",
+        env::consts::ARCH,
+        env::consts::OS,
+        env::consts::EXE_SUFFIX
+    )
+}
+
+/// Whether `version` is newer than `current`. Compares the numbers of
+/// `major.minor.patch`; pre-release and build suffixes are ignored.
+pub fn is_newer(version: &str, current: &str) -> bool {
+    fn parse(version: &str) -> Vec<u64> {
+        let mut numbers = version
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|number| number.parse().unwrap_or(0))
+            .collect::<Vec<u64>>();
+        numbers.resize(3, 0);
+        numbers
+    }
+
+    parse(version) > parse(current)
+}
+
+async fn get(client: &Client, url: &str) -> Result<reqwest::Response> {
+    let mut request = client.get(url).header("User-Agent", USER_AGENT);
+
+    // Raises the rate limit of unauthenticated requests
+    if let Ok(token) = env::var("GITHUB_TOKEN") {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+
+    let response = request.send().await?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().await?;
+
+        return Err(anyhow::anyhow!(
+            "GitHub request for {} failed with status {}: {}",
+            url,
+            status,
+            error_text
+        ));
+    }
+
+    Ok(response)
+}
+
+/// Fetches the latest release, which excludes drafts and pre-releases. Uses
+/// `GITHUB_TOKEN` if it is set.
+pub async fn latest_release() -> Result<Release> {
+    let url = format!(
+        "https://api.github.com/repos/{}/releases/latest",
+        repository()
+    );
+
+    Ok(get(&Client::new(), &url).await?.json().await?)
+}
+
+/// Downloads the binary of this platform from `release`, verifies its
+/// checksum and swaps it with the running executable. Returns the path of
+/// the replaced executable.
+pub async fn install(release: &Release) -> Result<PathBuf> {
+    let name = asset_name();
+    let binary = release.asset(&name).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Release {} has no binary for this platform ({})",
+            release.tag_name,
+            name
+        )
+    })?;
+    let checksum = release.asset(&format!("{}.sha256", name)).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Release {} has no checksum of {}, refusing to install it",
+            release.tag_name,
+            name
+        )
+    })?;
+
+    let client = Client::new();
+    let expected = get(&client, &checksum.browser_download_url)
+        .await?
+        .text()
+        .await?;
+    // `sha256sum` output: the checksum, then the file name
+    let expected = expected
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+
+    let bytes = get(&client, &binary.browser_download_url)
+        .await?
+        .bytes()
+        .await?;
+    let actual = hex::encode(Sha256::digest(&bytes));
+
+    if actual != expected {
+        return Err(anyhow::anyhow!(
+            "Checksum mismatch of {}: expected {}, downloaded {}",
+            name,
+            expected,
+            actual
+        ));
+    }
+
+    // Replace the binary a symlink on the PATH points to, not the symlink
+    let exe = env::current_exe()?.canonicalize()?;
+    swap(&exe, &bytes)?;
+
+    Ok(exe)
+}
+
+/// Writes `bytes` next to `exe` and renames them over it, so that `exe` is
+/// never half written. Windows does not let a running executable be
+/// replaced, only renamed, so there the old one is moved aside first.
+fn swap(exe: &Path, bytes: &[u8]) -> Result<()> {
+    let file_name = exe
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let staged = exe.with_file_name(format!(".{}.update", file_name));
+
+    fs::write(&staged, bytes)
+        .and_then(|()| fs::set_permissions(&staged, fs::metadata(exe)?.permissions()))
+        .map_err(|e| {
+            fs::remove_file(&staged).ok();
+            anyhow::anyhow!("Failed to write {}: {}", staged.display(), e)
+        })?;
+
+    #[cfg(windows)]
+    {
+        let old = exe.with_extension("old.exe");
+        fs::remove_file(&old).ok();
+        fs::rename(exe, &old)
+            .map_err(|e| anyhow::anyhow!("Failed to move {} aside: {}", exe.display(), e))?;
+    }
+
+    fs::rename(&staged, exe).map_err(|e| {
+        fs::remove_file(&staged).ok();
+        #[cfg(windows)]
+        fs::rename(exe.with_extension("old.exe"), exe).ok();
+        anyhow::anyhow!("Failed to replace {}: {}", exe.display(), e)
+    })
+}
@@ -0,0 +1,66 @@
+//! Legacy tool names stay routed to their current tools.
+
+mod common;
+
+use common::TestServer;
+use game_designer_mcp::tools::aliases::ALIASES;
+use serde_json::{Value, json};
+
+#[tokio::test]
+async fn aliases_route_to_listed_tools() {
+    let mut server = TestServer::start("aliases_route_to_listed_tools").await;
+
+    let result = server.request("tools/list", json!({})).await;
+    let tools = result["tools"].as_array().expect("a list of tools");
+
+    for alias in ALIASES {
+        assert!(
+            tools.iter().all(|tool| tool["name"] != alias.name),
+            "the alias {} is listed",
+            alias.name
+        );
+
+        let tool = tools
+            .iter()
+            .find(|tool| tool["name"] == alias.tool)
+            .unwrap_or_else(|| panic!("{} routes to the unknown tool {}", alias.name, alias.tool));
+        for (legacy, current) in alias.arguments {
+            assert!(
+                tool["inputSchema"]["properties"].get(current).is_some(),
+                "{} renames {} to {}, which {} does not accept",
+                alias.name,
+                legacy,
+                current,
+                alias.tool
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn alias_call_carries_a_deprecation_notice() {
+    let mut server = TestServer::start("alias_call_carries_a_deprecation_notice").await;
+
+    // A crate name of the wrong type fails validation before docs.rs is
+    // reached, under its current name
+    let result = server
+        .request(
+            "tools/call",
+            json!({ "name": "lookup_item", "arguments": { "crate_name": 42, "item_path": "Vec" } }),
+        )
+        .await;
+
+    assert_eq!(result["isError"], true);
+    let error: Value =
+        serde_json::from_str(result["content"][0]["text"].as_str().unwrap()).expect("a JSON error");
+    assert_eq!(error["error"], "invalid_arguments");
+    assert!(
+        error["message"].as_str().unwrap().contains("`crateName`"),
+        "{}",
+        error["message"]
+    );
+
+    let deprecation = &result["_meta"]["deprecation"];
+    assert_eq!(deprecation["tool"], "lookup_item");
+    assert_eq!(deprecation["replacedBy"], "crateDoc");
+}
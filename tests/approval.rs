@@ -0,0 +1,258 @@
+//! With human approval required, features the designer accepts wait for
+//! a signed humanApprove before they count as reviewed.
+
+mod common;
+
+use common::TestServer;
+use game_designer_mcp::game_design::{
+    approval,
+    config::{self, Config, ReviewConfig},
+    project,
+    state::{FeatureStatus, SessionState},
+};
+use serde_json::json;
+use std::fs;
+
+// Requires human approval with the same secret in every test of this file
+fn require_human_approval() {
+    let secret_file =
+        std::env::temp_dir().join(format!("gamedesignerd-approver-{}", std::process::id()));
+    fs::write(&secret_file, "correct horse battery staple\n").expect("write the secret");
+
+    // Each test file is its own process, so the configuration only applies
+    // here
+    config::replace(Config {
+        review: ReviewConfig {
+            require_human_approval: Some(true),
+            approver_secret_file: Some(secret_file),
+            ..ReviewConfig::default()
+        },
+        ..Config::default()
+    });
+}
+
+// Signs a decision of Grace the way `gamedesignerd sign-approval` does
+fn sign(session: &SessionState, feature: &str, approved: bool, comment: Option<&str>) -> String {
+    approval::sign(
+        project::default_project(),
+        session,
+        feature,
+        approved,
+        "Grace",
+        comment,
+    )
+    .expect("sign the decision")
+}
+
+// Starts a server whose feature the designer accepted, waiting for approval
+async fn awaiting_approval(name: &str) -> TestServer {
+    require_human_approval();
+    let mut server = TestServer::with_feature(name).await;
+
+    let review = server
+        .call(
+            "featureReview",
+            json!({
+                "sessionName": "space_cats",
+                "changesMade": "Added the core loop with movement and a fail state.",
+            }),
+        )
+        .await
+        .expect("featureReview");
+    assert_eq!(review["verdict"], "awaiting_approval");
+
+    server
+}
+
+#[tokio::test]
+async fn accepted_feature_waits_for_human_approval() {
+    let mut server = awaiting_approval("accepted_feature_waits_for_human_approval").await;
+    assert_eq!(
+        server.session("space_cats").planned_features[0].status,
+        FeatureStatus::AwaitingApproval
+    );
+
+    server
+        .call("nextFeature", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect_err("nextFeature while a feature awaits approval");
+
+    let session = server.session("space_cats");
+    let feature = session.planned_features[0].name.clone();
+    let rejection = sign(&session, &feature, false, None);
+    let error = server
+        .call(
+            "humanApprove",
+            json!({
+                "sessionName": "space_cats",
+                "approve": false,
+                "author": "Grace",
+                "signature": rejection,
+            }),
+        )
+        .await
+        .expect_err("humanApprove rejecting without a comment");
+    assert_eq!(error["error"], "invalid_arguments");
+
+    // An agent can't turn a signed rejection into an approval
+    let error = server
+        .call(
+            "humanApprove",
+            json!({
+                "sessionName": "space_cats",
+                "approve": true,
+                "author": "Grace",
+                "signature": rejection,
+            }),
+        )
+        .await
+        .expect_err("humanApprove with the signature of another decision");
+    assert_eq!(error["error"], "invalid_arguments");
+
+    // Nor reuse an approval of a session of the same name in another project
+    let elsewhere =
+        approval::sign(Some("moon_base"), &session, &feature, true, "Grace", None).unwrap();
+    let error = server
+        .call(
+            "humanApprove",
+            json!({
+                "sessionName": "space_cats",
+                "approve": true,
+                "author": "Grace",
+                "signature": elsewhere,
+            }),
+        )
+        .await
+        .expect_err("humanApprove with the signature of another project");
+    assert_eq!(error["error"], "invalid_arguments");
+    assert_eq!(
+        server.session("space_cats").planned_features[0].status,
+        FeatureStatus::AwaitingApproval
+    );
+
+    let approval = server
+        .call(
+            "humanApprove",
+            json!({
+                "sessionName": "space_cats",
+                "approve": true,
+                "author": "Grace",
+                "signature": sign(&session, &feature, true, None),
+            }),
+        )
+        .await
+        .expect("humanApprove");
+    assert_eq!(approval["status"], "Reviewed");
+
+    let session = server.session("space_cats");
+    let approval = session.planned_features[0]
+        .approval
+        .as_ref()
+        .expect("the decision is stored");
+    assert!(approval.approved);
+    assert_eq!(approval.author, "Grace");
+    assert_eq!(session.next_feature_to_implement, None);
+}
+
+#[tokio::test]
+async fn feature_awaiting_approval_cant_be_reviewed_again() {
+    let mut server = awaiting_approval("feature_awaiting_approval_cant_be_reviewed_again").await;
+    let before = server.session("space_cats");
+
+    let error = server
+        .call(
+            "featureReview",
+            json!({
+                "sessionName": "space_cats",
+                "changesMade": "Reworked the fail state.",
+            }),
+        )
+        .await
+        .expect_err("featureReview while the feature awaits approval");
+    assert!(error["message"].as_str().unwrap().contains("humanApprove"));
+
+    let error = server
+        .call(
+            "reviewReply",
+            json!({ "sessionName": "space_cats", "content": "Any more concerns?" }),
+        )
+        .await
+        .expect_err("reviewReply while the feature awaits approval");
+    assert!(error["message"].as_str().unwrap().contains("humanApprove"));
+
+    // The decision to sign is still the one the designer made
+    let after = server.session("space_cats");
+    assert_eq!(
+        after.planned_features[0].status,
+        FeatureStatus::AwaitingApproval
+    );
+    assert_eq!(after.review_log.len(), before.review_log.len());
+}
+
+#[tokio::test]
+async fn caveats_become_debt_only_once_approved() {
+    require_human_approval();
+    let mut server = TestServer::with_feature("caveats_become_debt_only_once_approved").await;
+    server.fixture(
+        "feature_review",
+        "SATISFIED WITH CAVEATS\n- [high] The fail state is hard-coded",
+    );
+    let review = json!({
+        "sessionName": "space_cats",
+        "changesMade": "Added the core loop with movement and a fail state.",
+    });
+
+    server
+        .call("featureReview", review.clone())
+        .await
+        .expect("featureReview");
+    let session = server.session("space_cats");
+    assert!(session.tech_debt.is_empty());
+    assert_eq!(session.planned_features[0].pending_caveats.len(), 1);
+
+    // A rejection drops the caveats with the decision they belonged to
+    let feature = session.planned_features[0].name.clone();
+    let comment = "The cat clips through walls";
+    server
+        .call(
+            "humanApprove",
+            json!({
+                "sessionName": "space_cats",
+                "approve": false,
+                "author": "Grace",
+                "comment": comment,
+                "signature": sign(&session, &feature, false, Some(comment)),
+            }),
+        )
+        .await
+        .expect("humanApprove rejecting the feature");
+    let session = server.session("space_cats");
+    assert!(session.tech_debt.is_empty());
+    assert!(session.planned_features[0].pending_caveats.is_empty());
+
+    server
+        .call("featureReview", review)
+        .await
+        .expect("featureReview after the rework");
+    let session = server.session("space_cats");
+    server
+        .call(
+            "humanApprove",
+            json!({
+                "sessionName": "space_cats",
+                "approve": true,
+                "author": "Grace",
+                "signature": sign(&session, &feature, true, None),
+            }),
+        )
+        .await
+        .expect("humanApprove");
+
+    let session = server.session("space_cats");
+    assert_eq!(session.tech_debt.len(), 1);
+    assert_eq!(
+        session.tech_debt[0].description,
+        "The fail state is hard-coded"
+    );
+    assert!(session.planned_features[0].pending_caveats.is_empty());
+}
@@ -0,0 +1,74 @@
+//! Screenshots attached to a featureReview are stored in the session's
+//! asset directory by their hash and recorded in the transcript.
+
+mod common;
+
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use common::TestServer;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::{fs, path::Path};
+
+/// A 1x1 PNG.
+const SCREENSHOT: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==";
+
+#[tokio::test]
+async fn review_attachments_are_stored_by_hash() {
+    let mut server = TestServer::with_feature("review_attachments_are_stored_by_hash").await;
+
+    let error = server
+        .call(
+            "featureReview",
+            json!({
+                "sessionName": "space_cats",
+                "changesMade": "Added the core loop with movement and a fail state.",
+                "attachments": [{ "type": "image", "data": "not base64!", "mimeType": "image/png" }],
+            }),
+        )
+        .await
+        .expect_err("featureReview with an invalid attachment");
+    assert_eq!(error["error"], "invalid_arguments");
+    assert!(server.session("space_cats").review_log.is_empty());
+
+    server
+        .call(
+            "featureReview",
+            json!({
+                "sessionName": "space_cats",
+                "changesMade": "Added the core loop with movement and a fail state.",
+                "attachments": [
+                    { "type": "image", "data": SCREENSHOT, "mimeType": "image/png" },
+                    {
+                        "type": "resource",
+                        "resource": {
+                            "uri": "file:///captures/core_loop.png",
+                            "mimeType": "image/png",
+                            "blob": SCREENSHOT,
+                        },
+                    },
+                ],
+            }),
+        )
+        .await
+        .expect("featureReview");
+
+    let session = server.session("space_cats");
+    let attachments = &session.review_log.last().expect("review entry").attachments;
+    assert_eq!(attachments.len(), 2);
+
+    let bytes = STANDARD.decode(SCREENSHOT).expect("decode the screenshot");
+    let sha256 = hex::encode(Sha256::digest(&bytes));
+    for attachment in attachments {
+        assert_eq!(attachment.sha256, sha256);
+        assert_eq!(attachment.mime_type, "image/png");
+        assert_eq!(attachment.bytes, bytes.len());
+
+        let path = Path::new(&attachment.path);
+        assert!(path.ends_with(format!("space_cats/assets/{}.png", sha256)));
+        assert_eq!(fs::read(path).expect("read the stored attachment"), bytes);
+    }
+    assert_eq!(
+        attachments[1].uri.as_deref(),
+        Some("file:///captures/core_loop.png")
+    );
+}
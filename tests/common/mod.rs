@@ -0,0 +1,213 @@
+//! In-process MCP client for the end-to-end tests.
+//!
+//! Every [`TestServer`] runs the game tools router with the mock LLM
+//! provider over an in-memory byte stream, the same way a local socket
+//! connection is served, and keeps its sessions in a directory of its own
+//! under a temporary data directory.
+
+// Every test file includes this module but uses only some of its helpers
+#![allow(dead_code)]
+
+use game_designer_mcp::{
+    game_design::{
+        DesignerLlmClient, SessionManager, SessionState,
+        session::{persistence_dir, set_persistence_dir},
+    },
+    tools::GameDesignerService,
+    transport::local_socket::serve_connection,
+};
+use serde_json::{Value, json};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Once,
+};
+use tokio::io::{
+    AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream, ReadHalf, WriteHalf, split,
+};
+
+/// Size of the in-memory stream between client and server.
+const STREAM_BUFFER_SIZE: usize = 1 << 20;
+
+static DATA_DIR: Once = Once::new();
+
+// Points the data directory of the process, which holds caches and usage
+// counters, at a fresh temporary directory
+fn data_dir() -> &'static Path {
+    DATA_DIR.call_once(|| {
+        let dir = std::env::temp_dir().join(format!("gamedesignerd-tests-{}", std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).expect("create the test data directory");
+        set_persistence_dir(dir);
+    });
+
+    persistence_dir()
+}
+
+/// A server with its own sessions and mock fixtures, and a client connected
+/// to it.
+pub struct TestServer {
+    dir: PathBuf,
+    read: BufReader<ReadHalf<DuplexStream>>,
+    write: WriteHalf<DuplexStream>,
+    next_id: u64,
+}
+
+impl TestServer {
+    /// Starts a server keeping its files in `<data dir>/<name>` and completes
+    /// the MCP handshake.
+    pub async fn start(name: &str) -> Self {
+        let dir = data_dir().join(name);
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(dir.join("fixtures")).expect("create the fixtures directory");
+
+        let session_manager =
+            SessionManager::in_dir(&dir.join("sessions")).expect("open the sessions directory");
+        let llm_client = DesignerLlmClient::mock(dir.join("fixtures"));
+        let service = GameDesignerService::with(session_manager, Some(llm_client));
+
+        let (client, server) = tokio::io::duplex(STREAM_BUFFER_SIZE);
+        tokio::spawn(serve_connection(server, service));
+
+        let (read, write) = split(client);
+        let mut server = Self {
+            dir,
+            read: BufReader::new(read),
+            write,
+            next_id: 0,
+        };
+
+        server
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": "2024-11-05",
+                    "capabilities": {},
+                    "clientInfo": { "name": "tests", "version": "0.0.0" },
+                }),
+            )
+            .await;
+        server.notify("notifications/initialized", json!({})).await;
+
+        server
+    }
+
+    /// Starts a server like [`Self::start`] with the session `space_cats`.
+    pub async fn with_session(name: &str) -> Self {
+        let mut server = Self::start(name).await;
+
+        server
+            .call(
+                "designNew",
+                json!({
+                    "sessionName": "space_cats",
+                    "gameDescription": "Cats defend a space station from mice",
+                }),
+            )
+            .await
+            .expect("designNew");
+
+        server
+    }
+
+    /// Starts a server like [`Self::with_session`] with a feature proposed
+    /// in `space_cats` by `nextFeature`.
+    pub async fn with_feature(name: &str) -> Self {
+        let mut server = Self::with_session(name).await;
+
+        server
+            .call("nextFeature", json!({ "sessionName": "space_cats" }))
+            .await
+            .expect("nextFeature");
+
+        server
+    }
+
+    /// Makes the mock provider answer the named prompt with `response`.
+    pub fn fixture(&self, prompt: &str, response: &str) {
+        fs::write(
+            self.dir.join("fixtures").join(format!("{}.txt", prompt)),
+            response,
+        )
+        .expect("write the fixture");
+    }
+
+    /// Reads the persisted state of a session.
+    pub fn session(&self, session_name: &str) -> SessionState {
+        let path = self
+            .dir
+            .join("sessions")
+            .join(format!("{}.json", session_name));
+        let json =
+            fs::read_to_string(&path).unwrap_or_else(|e| panic!("read {}: {}", path.display(), e));
+
+        serde_json::from_str(&json).unwrap_or_else(|e| panic!("parse {}: {}", path.display(), e))
+    }
+
+    async fn send(&mut self, message: Value) {
+        let mut line = message.to_string();
+        line.push('\n');
+
+        self.write
+            .write_all(line.as_bytes())
+            .await
+            .expect("write to the server");
+        self.write.flush().await.expect("flush to the server");
+    }
+
+    /// Sends a notification.
+    pub async fn notify(&mut self, method: &str, params: Value) {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+            .await;
+    }
+
+    /// Sends a request and returns its result. Panics on a JSON-RPC error.
+    pub async fn request(&mut self, method: &str, params: Value) -> Value {
+        self.next_id += 1;
+        let id = self.next_id;
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))
+            .await;
+
+        // Notifications sent in between are skipped
+        loop {
+            let mut line = String::new();
+            let read = self
+                .read
+                .read_line(&mut line)
+                .await
+                .expect("read from the server");
+            assert!(read > 0, "the server closed the connection");
+
+            let message: Value = serde_json::from_str(&line)
+                .unwrap_or_else(|e| panic!("parse server message {}: {}", line, e));
+            if message["id"] != json!(id) {
+                continue;
+            }
+            if let Some(error) = message.get("error") {
+                panic!("{} failed: {}", method, error);
+            }
+
+            return message["result"].clone();
+        }
+    }
+
+    /// Calls a tool and returns the JSON of its result, or the message of
+    /// the error it failed with.
+    pub async fn call(&mut self, tool: &str, mut arguments: Value) -> Result<Value, Value> {
+        arguments["outputFormat"] = json!("json");
+        let result = self
+            .request(
+                "tools/call",
+                json!({ "name": tool, "arguments": arguments }),
+            )
+            .await;
+
+        let text = result["content"][0]["text"].as_str().unwrap_or_default();
+        let json = serde_json::from_str(text).unwrap_or_else(|_| json!(text));
+
+        match result["isError"].as_bool() {
+            Some(true) => Err(json),
+            _ => Ok(json),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Project conventions are merged by name and refused when they would
+//! overflow the prompts.
+
+mod common;
+
+use common::TestServer;
+use serde_json::json;
+
+#[tokio::test]
+async fn conventions_are_merged_and_limited_in_size() {
+    let mut server = TestServer::with_session("conventions_are_merged_and_limited_in_size").await;
+
+    let set = server
+        .call(
+            "conventionsSet",
+            json!({
+                "sessionName": "space_cats",
+                "conventions": {
+                    "engineVersion": "Bevy 0.16",
+                    "targetFps": "60 on a Steam Deck",
+                },
+            }),
+        )
+        .await
+        .expect("conventionsSet");
+    assert_eq!(set["conventions"]["engineVersion"], "Bevy 0.16");
+    assert!(set["tokens"].as_u64().unwrap() <= set["maxTokens"].as_u64().unwrap());
+
+    let changed = server
+        .call(
+            "conventionsSet",
+            json!({
+                "sessionName": "space_cats",
+                "conventions": { "targetFps": "", "naming": "snake_case systems" },
+            }),
+        )
+        .await
+        .expect("conventionsSet removing a convention");
+    assert_eq!(
+        changed["conventions"],
+        json!({ "engineVersion": "Bevy 0.16", "naming": "snake_case systems" })
+    );
+
+    let error = server
+        .call(
+            "conventionsSet",
+            json!({
+                "sessionName": "space_cats",
+                "conventions": { "codeStyle": "Keep it short. ".repeat(2000) },
+            }),
+        )
+        .await
+        .expect_err("conventionsSet over the size limit");
+    assert_eq!(error["error"], "invalid_arguments");
+
+    let session = server.session("space_cats");
+    assert_eq!(session.conventions.len(), 2);
+    assert!(!session.conventions.contains_key("codeStyle"));
+}
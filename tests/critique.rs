@@ -0,0 +1,73 @@
+//! uiCritique stores the critique of a screenshot with the feature it shows.
+
+mod common;
+
+use common::TestServer;
+use game_designer_mcp::game_design::config::{self, Config, LlmConfig};
+use serde_json::json;
+use std::path::Path;
+
+/// A 1x1 PNG.
+const SCREENSHOT: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==";
+
+#[tokio::test]
+async fn ui_critique_is_stored_with_the_feature() {
+    // Each test file is its own process, so the configuration only applies
+    // here
+    config::replace(Config {
+        llm: LlmConfig {
+            vision_model: Some("google/gemini-2.5-flash".to_string()),
+            ..LlmConfig::default()
+        },
+        ..Config::default()
+    });
+
+    let mut server = TestServer::with_feature("ui_critique_is_stored_with_the_feature").await;
+
+    let error = server
+        .call(
+            "uiCritique",
+            json!({
+                "sessionName": "space_cats",
+                "screenshot": {
+                    "type": "resource",
+                    "resource": {
+                        "uri": "file:///captures/hud.mp4",
+                        "mimeType": "video/mp4",
+                        "blob": SCREENSHOT,
+                    },
+                },
+            }),
+        )
+        .await
+        .expect_err("uiCritique of a video");
+    assert_eq!(error["error"], "invalid_arguments");
+
+    let result = server
+        .call(
+            "uiCritique",
+            json!({
+                "sessionName": "space_cats",
+                "screenshot": { "type": "image", "data": SCREENSHOT, "mimeType": "image/png" },
+                "context": "HUD while luring mice",
+                "author": "Grace",
+            }),
+        )
+        .await
+        .expect("uiCritique");
+    let issues = result["critique"]["issues"]
+        .as_array()
+        .expect("a list of issues");
+    assert_eq!(issues.len(), 2);
+    assert_eq!(issues[0]["aspect"], "readability");
+    assert_eq!(issues[0]["severity"], "high");
+
+    let session = server.session("space_cats");
+    let feature = &session.planned_features[0];
+    assert_eq!(feature.ui_critiques.len(), 1);
+
+    let critique = &feature.ui_critiques[0];
+    assert_eq!(critique.context.as_deref(), Some("HUD while luring mice"));
+    assert_eq!(critique.author.as_deref(), Some("Grace"));
+    assert!(Path::new(&critique.screenshot.path).is_file());
+}
@@ -0,0 +1,45 @@
+//! The dashboard only serves sessions from inside the sessions directory.
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use game_designer_mcp::{
+    game_design::SessionManager, tools::GameDesignerService, transport::dashboard,
+};
+use std::fs;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn encoded_parent_segments_are_refused() {
+    let dir = std::env::temp_dir().join(format!("gamedesignerd-dashboard-{}", std::process::id()));
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).expect("create the test directory");
+    fs::write(dir.join("secret.json"), "{}").expect("write a file outside the sessions");
+
+    let sessions = SessionManager::in_dir(&dir.join("sessions")).expect("open the sessions");
+    let router = dashboard::router(GameDesignerService::with(sessions, None));
+
+    for uri in [
+        "/api/sessions/..%2Fsecret?project=",
+        "/api/sessions/..%2Fsecret/usage?project=",
+        "/api/sessions/%2E%2E%2F%2E%2E%2Fsecret?project=",
+    ] {
+        let response = router
+            .clone()
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", uri);
+    }
+
+    let response = router
+        .oneshot(
+            Request::get("/api/sessions/space_cats?project=")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,418 @@
+//! The design loop end to end: designNew, nextFeature, featureReview and
+//! reviewReply over the MCP byte transport, checked against the persisted
+//! session.
+
+mod common;
+
+use common::TestServer;
+use game_designer_mcp::{
+    game_design::{
+        followups,
+        review_depth::ReviewDepth,
+        state::{FeatureStatus, ReviewEntryKind},
+    },
+    transport::MCP_PROTOCOL_VERSION,
+};
+use serde_json::json;
+
+/// Review with a question, so that the feature needs a reply.
+const REVIEW_WITH_QUESTION: &str = "The core loop is in place. How does the fail state reset \
+                                    the level?\nCRITERIA:\n- [pass] 1\n- [pass] 2";
+
+#[tokio::test]
+async fn reviewed_feature_is_persisted() {
+    let mut server = TestServer::start("reviewed_feature_is_persisted").await;
+    server.fixture("feature_review", REVIEW_WITH_QUESTION);
+
+    server
+        .call(
+            "designNew",
+            json!({
+                "sessionName": "space_cats",
+                "gameDescription": "Cats defend a space station from mice",
+            }),
+        )
+        .await
+        .expect("designNew");
+
+    let next = server
+        .call("nextFeature", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect("nextFeature");
+    let feature = next["feature"]["name"]
+        .as_str()
+        .expect("nextFeature names the feature")
+        .to_string();
+    assert_eq!(
+        server.session("space_cats").next_feature_to_implement,
+        Some(feature.clone())
+    );
+
+    let review = server
+        .call(
+            "featureReview",
+            json!({
+                "sessionName": "space_cats",
+                "changesMade": "Added the core loop with movement and a fail state.",
+            }),
+        )
+        .await
+        .expect("featureReview");
+    assert_eq!(review["verdict"], "needs_rework");
+    assert_eq!(
+        review["questions"][0],
+        "How does the fail state reset the level?"
+    );
+
+    let session = server.session("space_cats");
+    let planned = session
+        .planned_features
+        .iter()
+        .find(|planned| planned.name == feature)
+        .expect("the feature is planned");
+    assert_eq!(planned.status, FeatureStatus::NeedsRework);
+
+    let reply = server
+        .call(
+            "reviewReply",
+            json!({
+                "sessionName": "space_cats",
+                "content": "Dying restarts the level from its first room.",
+            }),
+        )
+        .await
+        .expect("reviewReply");
+    assert_eq!(reply["verdict"], "approved");
+
+    let session = server.session("space_cats");
+    let planned = session
+        .planned_features
+        .iter()
+        .find(|planned| planned.name == feature)
+        .expect("the feature is planned");
+    assert_eq!(planned.status, FeatureStatus::Reviewed);
+    assert_eq!(session.next_feature_to_implement, None);
+    assert!(session.implemented_features_reports.contains_key(&feature));
+
+    let kinds: Vec<_> = session.review_log.iter().map(|entry| entry.kind).collect();
+    assert_eq!(kinds, [ReviewEntryKind::Report, ReviewEntryKind::Reply]);
+    assert!(
+        session
+            .review_log
+            .iter()
+            .all(|entry| entry.feature == feature)
+    );
+}
+
+#[tokio::test]
+async fn specification_with_problems_is_regenerated_once() {
+    let mut server = TestServer::start("specification_with_problems_is_regenerated_once").await;
+    server.fixture(
+        "spec_check",
+        r#"{"problems": ["Contradicts the decision to keep mice harmless"]}"#,
+    );
+
+    server
+        .call(
+            "designNew",
+            json!({
+                "sessionName": "space_cats",
+                "gameDescription": "Cats defend a space station from mice",
+            }),
+        )
+        .await
+        .expect("designNew");
+    server
+        .call("nextFeature", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect("nextFeature");
+    assert_eq!(server.session("space_cats").planned_features.len(), 1);
+
+    let history = server
+        .call(
+            "sessionHistory",
+            json!({ "sessionName": "space_cats", "types": ["feature.spec_checked"] }),
+        )
+        .await
+        .expect("sessionHistory");
+    let check = &history["events"][0]["data"];
+    assert_eq!(
+        check["problems"][0],
+        "Contradicts the decision to keep mice harmless"
+    );
+    assert_eq!(check["regenerated"], true);
+    assert_eq!(history["total"], 1);
+}
+
+#[tokio::test]
+async fn review_depth_of_the_session_selects_the_review_prompt() {
+    let mut server =
+        TestServer::start("review_depth_of_the_session_selects_the_review_prompt").await;
+    server.fixture("feature_review", REVIEW_WITH_QUESTION);
+    server.fixture(
+        "feature_review_quick",
+        "SATISFIED\nCRITERIA:\n- [pass] 1\n- [pass] 2",
+    );
+
+    server
+        .call(
+            "designNew",
+            json!({
+                "sessionName": "space_cats",
+                "gameDescription": "Cats defend a space station from mice",
+            }),
+        )
+        .await
+        .expect("designNew");
+    let configured = server
+        .call(
+            "sessionConfigure",
+            json!({ "sessionName": "space_cats", "reviewDepth": "quick" }),
+        )
+        .await
+        .expect("sessionConfigure");
+    assert_eq!(configured["reviewDepth"], "quick");
+
+    server
+        .call("nextFeature", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect("nextFeature");
+    let review = server
+        .call(
+            "featureReview",
+            json!({
+                "sessionName": "space_cats",
+                "changesMade": "Added the core loop with movement and a fail state.",
+            }),
+        )
+        .await
+        .expect("featureReview");
+    assert_eq!(review["verdict"], "approved");
+    assert_eq!(review["reviewDepth"], "quick");
+
+    let session = server.session("space_cats");
+    assert_eq!(
+        session.planned_features[0].review_depth,
+        Some(ReviewDepth::Quick)
+    );
+
+    let error = server
+        .call(
+            "featureReview",
+            json!({
+                "sessionName": "space_cats",
+                "changesMade": "Nothing",
+                "reviewDepth": "deep",
+            }),
+        )
+        .await
+        .expect_err("featureReview with an unknown depth");
+    assert_eq!(error["error"], "invalid_arguments");
+}
+
+#[tokio::test]
+async fn review_panel_keeps_each_review_next_to_the_consensus() {
+    let mut server =
+        TestServer::start("review_panel_keeps_each_review_next_to_the_consensus").await;
+    server.fixture("review_panel_ux", REVIEW_WITH_QUESTION);
+    server.fixture("review_consensus", REVIEW_WITH_QUESTION);
+
+    server
+        .call(
+            "designNew",
+            json!({
+                "sessionName": "space_cats",
+                "gameDescription": "Cats defend a space station from mice",
+            }),
+        )
+        .await
+        .expect("designNew");
+    server
+        .call("nextFeature", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect("nextFeature");
+
+    let review = server
+        .call(
+            "featureReview",
+            json!({
+                "sessionName": "space_cats",
+                "changesMade": "Added the core loop with movement and a fail state.",
+                "reviewPanel": true,
+            }),
+        )
+        .await
+        .expect("featureReview");
+    assert_eq!(review["verdict"], "needs_rework");
+    assert_eq!(review["review"], REVIEW_WITH_QUESTION);
+    assert_eq!(review["panel"][0]["reviewer"], "design");
+    assert_eq!(review["panel"][1]["reviewer"], "technical");
+    assert_eq!(review["panel"][2]["reviewer"], "ux");
+    assert_eq!(review["panel"][2]["review"], REVIEW_WITH_QUESTION);
+
+    let session = server.session("space_cats");
+    assert_eq!(session.review_log[0].panel.len(), 3);
+    assert_eq!(
+        session.planned_features[0].status,
+        FeatureStatus::NeedsRework
+    );
+}
+
+#[tokio::test]
+async fn review_that_keeps_asking_is_cut_off_at_the_follow_up_limit() {
+    let mut server =
+        TestServer::start("review_that_keeps_asking_is_cut_off_at_the_follow_up_limit").await;
+    server.fixture("feature_review", REVIEW_WITH_QUESTION);
+    server.fixture("review_reply", REVIEW_WITH_QUESTION);
+
+    server
+        .call(
+            "designNew",
+            json!({
+                "sessionName": "space_cats",
+                "gameDescription": "Cats defend a space station from mice",
+            }),
+        )
+        .await
+        .expect("designNew");
+    server
+        .call("nextFeature", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect("nextFeature");
+    server
+        .call(
+            "featureReview",
+            json!({
+                "sessionName": "space_cats",
+                "changesMade": "Added the core loop with movement and a fail state.",
+            }),
+        )
+        .await
+        .expect("featureReview");
+
+    let reply = |round: usize| {
+        json!({
+            "sessionName": "space_cats",
+            "content": format!("Answer of round {}", round),
+        })
+    };
+    for round in 1..followups::DEFAULT_MAX_FOLLOW_UPS {
+        let outcome = server
+            .call("reviewReply", reply(round))
+            .await
+            .expect("reviewReply below the limit");
+        assert_eq!(outcome["followUpRounds"], round);
+        assert_eq!(outcome["rework"], json!([]));
+    }
+
+    // The designer still asks, which counts as a rejection
+    let outcome = server
+        .call("reviewReply", reply(followups::DEFAULT_MAX_FOLLOW_UPS))
+        .await
+        .expect("reviewReply at the limit");
+    assert_eq!(outcome["verdict"], "needs_rework");
+    assert_eq!(outcome["rework"].as_array().map(Vec::len), Some(1));
+
+    let error = server
+        .call("reviewReply", reply(followups::DEFAULT_MAX_FOLLOW_UPS + 1))
+        .await
+        .expect_err("reviewReply beyond the limit");
+    assert_eq!(error["error"], "invalid_arguments");
+    assert_eq!(
+        server.session("space_cats").review_log.len(),
+        followups::DEFAULT_MAX_FOLLOW_UPS + 1
+    );
+}
+
+#[tokio::test]
+async fn unknown_session_is_reported_with_its_code() {
+    let mut server = TestServer::start("unknown_session_is_reported_with_its_code").await;
+
+    let error = server
+        .call("nextFeature", json!({ "sessionName": "no_such_session" }))
+        .await
+        .expect_err("nextFeature of an unknown session");
+
+    assert_eq!(error["error"], "session_not_found");
+    assert_eq!(error["code"], -32002);
+    assert_eq!(error["retryable"], false);
+}
+
+#[tokio::test]
+async fn tools_of_the_loop_are_listed() {
+    let mut server = TestServer::start("tools_of_the_loop_are_listed").await;
+
+    let result = server.request("tools/list", json!({})).await;
+    let names: Vec<&str> = result["tools"]
+        .as_array()
+        .expect("a list of tools")
+        .iter()
+        .filter_map(|tool| tool["name"].as_str())
+        .collect();
+
+    for tool in ["designNew", "nextFeature", "featureReview", "reviewReply"] {
+        assert!(names.contains(&tool), "{} is not listed", tool);
+    }
+}
+
+#[tokio::test]
+async fn unsupported_protocol_version_is_answered_with_the_newest() {
+    let mut server =
+        TestServer::start("unsupported_protocol_version_is_answered_with_the_newest").await;
+
+    let result = server
+        .request(
+            "initialize",
+            json!({
+                "protocolVersion": "2000-01-01",
+                "capabilities": {},
+                "clientInfo": { "name": "tests", "version": "0.0.0" },
+            }),
+        )
+        .await;
+
+    assert_eq!(result["protocolVersion"], MCP_PROTOCOL_VERSION);
+    assert_eq!(result["serverInfo"]["version"], env!("CARGO_PKG_VERSION"));
+}
+
+#[tokio::test]
+async fn tools_are_annotated_with_their_cost() {
+    let mut server = TestServer::start("tools_are_annotated_with_their_cost").await;
+
+    let result = server.request("tools/list", json!({})).await;
+    let annotations = |name: &str| {
+        result["tools"]
+            .as_array()
+            .expect("a list of tools")
+            .iter()
+            .find(|tool| tool["name"] == name)
+            .unwrap_or_else(|| panic!("{} is not listed", name))["annotations"]
+            .clone()
+    };
+
+    let review = annotations("featureReview");
+    assert_eq!(review["costHint"], "llm");
+    assert_eq!(review["longRunningHint"], true);
+    assert_eq!(review["readOnlyHint"], false);
+
+    let overview = annotations("designOverview");
+    assert_eq!(overview["costHint"], "local");
+    assert_eq!(overview["readOnlyHint"], true);
+
+    assert_eq!(annotations("designUndo")["destructiveHint"], true);
+}
+
+#[tokio::test]
+async fn showing_the_settings_records_no_change() {
+    let mut server = TestServer::with_session("showing_the_settings_records_no_change").await;
+    let events = json!({ "sessionName": "space_cats" });
+    let before = server.call("sessionEvents", events.clone()).await.unwrap();
+
+    server
+        .call("sessionConfigure", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect("sessionConfigure without changes");
+
+    let after = server.call("sessionEvents", events).await.unwrap();
+    assert_eq!(after["nextCursor"], before["nextCursor"]);
+}
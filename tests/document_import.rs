@@ -0,0 +1,77 @@
+//! designNew only imports design documents from inside the working
+//! directory, or the repository when one is given, and creates the session
+//! with all of its settings at once.
+
+mod common;
+
+use common::TestServer;
+use serde_json::json;
+
+#[tokio::test]
+async fn document_path_stays_inside_the_working_directory() {
+    let mut server = TestServer::start("document_path_stays_inside_the_working_directory").await;
+
+    for document_path in ["/etc/passwd", "../../etc/passwd"] {
+        let error = server
+            .call(
+                "designNew",
+                json!({ "sessionName": "space_cats", "documentPath": document_path }),
+            )
+            .await
+            .expect_err("designNew with a document outside the working directory");
+        assert_eq!(error["error"], "invalid_arguments");
+    }
+
+    // Tests run in the crate directory
+    server
+        .call(
+            "designNew",
+            json!({ "sessionName": "space_cats", "documentPath": "README.md" }),
+        )
+        .await
+        .expect("designNew with a document inside the working directory");
+    assert_eq!(server.session("space_cats").id, "space_cats");
+}
+
+#[tokio::test]
+async fn design_new_creates_the_session_in_one_step() {
+    let mut server = TestServer::start("design_new_creates_the_session_in_one_step").await;
+    let design_new = |repo: &str| {
+        json!({
+            "sessionName": "space_cats",
+            "documentPath": "README.md",
+            "repo": repo,
+            "language": "German",
+            "engine": "bevy",
+            "studio": "Whisker Works",
+        })
+    };
+
+    // Nothing is created before the repository is checked
+    server
+        .call("designNew", design_new("/nonexistent/space_cats"))
+        .await
+        .expect_err("designNew with a repository that doesn't exist");
+    server
+        .call("designNew", design_new("."))
+        .await
+        .expect("designNew after fixing the repository");
+
+    let session = server.session("space_cats");
+    assert!(session.repo_path.is_some());
+    assert_eq!(session.language.as_deref(), Some("German"));
+    assert_eq!(session.engine.map(|engine| engine.name()), Some("bevy"));
+    assert_eq!(session.metadata.studio.as_deref(), Some("Whisker Works"));
+
+    let events = server
+        .call("sessionEvents", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect("sessionEvents");
+    let kinds = events["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|event| event["kind"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(kinds, ["session.created"]);
+}
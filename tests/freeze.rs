@@ -0,0 +1,75 @@
+//! Frozen sessions serve read tools and exports but refuse changes.
+
+mod common;
+
+use common::TestServer;
+use serde_json::json;
+
+#[tokio::test]
+async fn frozen_session_refuses_changes_until_unfrozen() {
+    let mut server =
+        TestServer::with_session("frozen_session_refuses_changes_until_unfrozen").await;
+
+    let freeze = server
+        .call(
+            "designFreeze",
+            json!({ "sessionName": "space_cats", "reason": "Shipped 1.0" }),
+        )
+        .await
+        .expect("designFreeze");
+    assert_eq!(freeze["frozen"], true);
+    assert_eq!(
+        server
+            .session("space_cats")
+            .freeze
+            .unwrap()
+            .reason
+            .as_deref(),
+        Some("Shipped 1.0")
+    );
+
+    let error = server
+        .call("nextFeature", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect_err("nextFeature of a frozen session");
+    assert_eq!(error["error"], "session_frozen");
+    assert_eq!(error["session"], "space_cats");
+    assert!(server.session("space_cats").planned_features.is_empty());
+
+    server
+        .call("designOverview", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect("designOverview of a frozen session");
+    server
+        .call("designExport", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect("designExport of a frozen session");
+
+    let retro = server
+        .call("designRetro", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect("designRetro of a frozen session");
+    assert_eq!(retro["finished"], true);
+    assert_eq!(retro["scope"]["designed"], 0);
+    assert!(retro["lessons"].as_str().unwrap().contains("(mock)"));
+    assert!(std::path::Path::new(retro["path"].as_str().unwrap()).exists());
+
+    let error = server
+        .call("designUnfreeze", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect_err("designUnfreeze without confirmation");
+    assert_eq!(error["error"], "invalid_arguments");
+    assert!(server.session("space_cats").freeze.is_some());
+
+    server
+        .call(
+            "designUnfreeze",
+            json!({ "sessionName": "space_cats", "confirm": true }),
+        )
+        .await
+        .expect("designUnfreeze");
+    server
+        .call("nextFeature", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect("nextFeature after unfreezing");
+}
@@ -0,0 +1,151 @@
+//! Rejected features are buried in the idea graveyard: proposals repeating
+//! them are skipped and proposals resembling them are annotated.
+
+mod common;
+
+use common::TestServer;
+use serde_json::json;
+
+const GRAPPLING_HOOK: &str = r#"{
+  "name": "Grappling Hook",
+  "description": "Cats swing across the station with a grappling hook fired at ceiling anchors, keeping momentum between swings.",
+  "acceptance_criteria": [
+    "Cats attach to any ceiling anchor within 8 meters.",
+    "Releasing keeps the swing's momentum."
+  ],
+  "estimated_effort": "S",
+  "risk": "low",
+  "milestone": "Prototype",
+  "required_assets": []
+}"#;
+
+const CANDIDATES: &str = r#"[
+  {
+    "name": "Grapple Line",
+    "description": "Cats swing across the station with a grappling hook fired at ceiling anchors, keeping momentum between swings.",
+    "acceptance_criteria": ["Cats attach to any ceiling anchor within 8 meters."],
+    "rationale": "Movement is the core of the game.",
+    "estimated_effort": "S"
+  },
+  {
+    "name": "Swinging Claw Hook",
+    "description": "Cats fire a claw hook at ceiling anchors to swing over gaps in the station, carrying their momentum.",
+    "acceptance_criteria": [
+      "Cats attach to any ceiling anchor within 8 meters.",
+      "Gaps of up to 12 meters can be crossed in one swing."
+    ],
+    "rationale": "Crossing gaps opens up the level design.",
+    "estimated_effort": "M"
+  },
+  {
+    "name": "Laser Pointer Lure",
+    "description": "Cats throw a laser pointer dot that lures nearby mice into traps.",
+    "acceptance_criteria": ["Mice within 5 meters follow the dot."],
+    "rationale": "Gives cats a way to fight back.",
+    "estimated_effort": "S"
+  }
+]"#;
+
+#[tokio::test]
+async fn proposals_repeating_a_rejected_feature_are_skipped() {
+    let mut server =
+        TestServer::with_session("proposals_repeating_a_rejected_feature_are_skipped").await;
+    server.fixture("next_feature", GRAPPLING_HOOK);
+    server.fixture("feature_candidates", CANDIDATES);
+
+    server
+        .call("nextFeature", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect("nextFeature");
+
+    let rejected = server
+        .call(
+            "featureReject",
+            json!({ "sessionName": "space_cats", "reason": "low ceilings everywhere" }),
+        )
+        .await
+        .expect("featureReject");
+    assert_eq!(rejected["feature"]["name"], "Grappling Hook");
+
+    let session = server.session("space_cats");
+    assert!(session.planned_features.is_empty());
+    assert_eq!(session.next_feature_to_implement, None);
+    assert_eq!(session.graveyard.len(), 1);
+
+    // The mock proposes the same feature again, even when asked for another
+    let repeated = server
+        .call("nextFeature", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect("nextFeature repeating the rejected feature");
+    assert_eq!(
+        repeated["feature"]["similarRejected"]["feature"],
+        "Grappling Hook"
+    );
+    let score = repeated["feature"]["similarRejected"]["score"]
+        .as_f64()
+        .unwrap();
+    assert!(score >= 0.85);
+
+    let history = server
+        .call(
+            "sessionHistory",
+            json!({ "sessionName": "space_cats", "types": ["feature.duplicate_skipped"] }),
+        )
+        .await
+        .expect("sessionHistory");
+    assert_eq!(history["total"], 1);
+    assert_eq!(history["events"][0]["data"]["rejected"], "Grappling Hook");
+
+    server
+        .call("featureReject", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect("featureReject without a reason");
+
+    let proposed = server
+        .call(
+            "nextFeature",
+            json!({ "sessionName": "space_cats", "candidates": 3 }),
+        )
+        .await
+        .expect("nextFeature with candidates");
+    let candidates = proposed["candidates"].as_array().unwrap();
+    assert_eq!(candidates.len(), 2);
+
+    let similar = &candidates[0]["feature"];
+    assert_eq!(similar["name"], "Swinging Claw Hook");
+    assert_eq!(similar["similarRejected"]["feature"], "Grappling Hook");
+    assert_eq!(
+        similar["similarRejected"]["differences"],
+        json!([
+            "adds \"Gaps of up to 12 meters can be crossed in one swing.\"",
+            "drops \"Releasing keeps the swing's momentum.\"",
+            "effort M instead of S",
+        ])
+    );
+    assert!(candidates[1]["feature"]["similarRejected"].is_null());
+}
+
+#[tokio::test]
+async fn only_untouched_proposals_can_be_rejected() {
+    let mut server = TestServer::with_session("only_untouched_proposals_can_be_rejected").await;
+
+    server
+        .call("featureReject", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect_err("featureReject without a proposal");
+
+    server
+        .call("nextFeature", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect("nextFeature");
+    server
+        .call("startWork", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect("startWork");
+    server
+        .call("featureReject", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect_err("featureReject after startWork");
+
+    assert_eq!(server.session("space_cats").planned_features.len(), 1);
+}
@@ -0,0 +1,63 @@
+//! The language of a session is stored with it and asked for in every
+//! prompt until it is changed.
+
+mod common;
+
+use common::TestServer;
+use serde_json::json;
+
+/// Feature proposed in the German next-feature prompt.
+const GERMAN_FEATURE: &str = r#"{
+  "name": "Laserpointer",
+  "description": "Katzen jagen einen Laserpointer durch die Station.",
+  "acceptance_criteria": ["Der Laserpointer lockt die Katzen an."],
+  "estimated_effort": "S",
+  "risk": "low",
+  "milestone": "Prototype",
+  "required_assets": [],
+  "implementation_hints": "- Den Laserpointer als eigenes System umsetzen."
+}"#;
+
+#[tokio::test]
+async fn language_reaches_the_prompts() {
+    let mut server = TestServer::start("language_reaches_the_prompts").await;
+    server.fixture("design_new.german", "# Spieldesign-Dokument (mock)");
+    server.fixture("next_feature.german", GERMAN_FEATURE);
+
+    server
+        .call(
+            "designNew",
+            json!({
+                "sessionName": "space_cats",
+                "gameDescription": "Cats defend a space station from mice",
+                "language": " German ",
+            }),
+        )
+        .await
+        .expect("designNew");
+
+    let session = server.session("space_cats");
+    assert_eq!(session.language.as_deref(), Some("German"));
+    assert_eq!(session.initial_description, "# Spieldesign-Dokument (mock)");
+
+    let next = server
+        .call("nextFeature", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect("nextFeature");
+    assert_eq!(next["feature"]["name"], "Laserpointer");
+
+    server
+        .call(
+            "sessionConfigure",
+            json!({ "sessionName": "space_cats", "language": "" }),
+        )
+        .await
+        .expect("sessionConfigure");
+    assert_eq!(server.session("space_cats").language, None);
+
+    let next = server
+        .call("nextFeature", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect("nextFeature");
+    assert_eq!(next["feature"]["name"], "Mock Feature 2");
+}
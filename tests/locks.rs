@@ -0,0 +1,84 @@
+//! Changes of a session wait for each other, LLM calls included, while
+//! reads and changes of other sessions go ahead.
+
+use game_designer_mcp::game_design::{
+    DesignerLlmClient, Persona, SessionManager, SessionSetup, clock::SystemClock,
+    mock_llm::MockLlmProvider, storage::MemoryStorage,
+};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Time each mock response takes.
+const LLM_LATENCY: Duration = Duration::from_millis(500);
+
+#[tokio::test]
+async fn llm_calls_only_hold_their_session() {
+    let manager = Arc::new(
+        SessionManager::with_backends(
+            Path::new("/memory/sessions"),
+            Arc::new(MemoryStorage::new()),
+            Arc::new(SystemClock),
+        )
+        .expect("open the in-memory sessions"),
+    );
+
+    for (session_name, description) in [
+        ("space_cats", "Cats defend a space station from mice"),
+        ("moon_miner", "Mine the moon before the oxygen runs out"),
+    ] {
+        manager
+            .create_session(
+                session_name.to_string(),
+                description.to_string(),
+                Persona::default(),
+                None,
+                SessionSetup::default(),
+            )
+            .await
+            .expect("create the session");
+    }
+
+    let proposal = tokio::spawn({
+        let manager = manager.clone();
+        let llm_client = DesignerLlmClient::from_mock(
+            MockLlmProvider::with_fixtures(PathBuf::from("/nonexistent/fixtures"))
+                .with_latency(LLM_LATENCY),
+        );
+
+        async move {
+            manager
+                .get_next_feature("space_cats", Some(&llm_client))
+                .await
+        }
+    });
+
+    // Let the proposal reach the LLM
+    tokio::time::sleep(LLM_LATENCY / 5).await;
+
+    let started_at = Instant::now();
+    manager
+        .set_persona("moon_miner", Persona::default())
+        .await
+        .expect("change the other session");
+    manager
+        .load_session("space_cats")
+        .await
+        .unwrap()
+        .expect("read the session being changed");
+    assert!(started_at.elapsed() < LLM_LATENCY / 2);
+
+    // Waits for the proposal instead of overwriting it
+    manager
+        .freeze("space_cats", None)
+        .await
+        .expect("freeze the session");
+    proposal.await.unwrap().expect("propose a feature");
+
+    let session = manager.load_session("space_cats").await.unwrap().unwrap();
+    assert_eq!(session.planned_features.len(), 1);
+    assert!(session.freeze.is_some());
+    assert_eq!(manager.lock_stats().contended, 1);
+}
@@ -0,0 +1,59 @@
+//! Approving the last open feature of a milestone completes the milestone.
+
+mod common;
+
+use common::TestServer;
+use serde_json::{Value, json};
+
+async fn review(server: &mut TestServer) {
+    let review = server
+        .call(
+            "featureReview",
+            json!({
+                "sessionName": "space_cats",
+                "changesMade": "Added the core loop with movement and a fail state.",
+            }),
+        )
+        .await
+        .expect("featureReview");
+    assert_eq!(review["verdict"], "approved");
+}
+
+async fn completed(server: &mut TestServer) -> Vec<Value> {
+    let history = server
+        .call(
+            "sessionHistory",
+            json!({ "sessionName": "space_cats", "types": ["milestone.completed"] }),
+        )
+        .await
+        .expect("sessionHistory");
+
+    history["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|event| event["data"].clone())
+        .collect()
+}
+
+#[tokio::test]
+async fn approving_the_last_feature_completes_the_milestone() {
+    let mut server =
+        TestServer::with_feature("approving_the_last_feature_completes_the_milestone").await;
+
+    review(&mut server).await;
+    assert_eq!(
+        completed(&mut server).await,
+        [json!({ "milestone": "Prototype", "features": ["Mock Feature 1"] })]
+    );
+
+    server
+        .call("nextFeature", json!({ "sessionName": "space_cats" }))
+        .await
+        .expect("nextFeature");
+    review(&mut server).await;
+    assert_eq!(
+        completed(&mut server).await[1],
+        json!({ "milestone": "Prototype", "features": ["Mock Feature 1", "Mock Feature 2"] })
+    );
+}
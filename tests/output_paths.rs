@@ -0,0 +1,116 @@
+//! Tools writing files the client names only write inside the session's
+//! repository or the data directory.
+
+mod common;
+
+use common::TestServer;
+use serde_json::json;
+use std::path::Path;
+
+/// Paths outside the data directory of a session without a repository.
+const OUTSIDE: &[&str] = &[
+    "/tmp/gamedesignerd-outside.md",
+    "../outside.md",
+    "docs/../../x.md",
+];
+
+#[tokio::test]
+async fn retro_path_stays_inside_the_data_directory() {
+    let mut server = TestServer::with_session("retro_path_stays_inside_the_data_directory").await;
+
+    for path in OUTSIDE {
+        let error = server
+            .call(
+                "designRetro",
+                json!({ "sessionName": "space_cats", "path": path }),
+            )
+            .await
+            .expect_err("designRetro with a path outside the data directory");
+        assert_eq!(error["error"], "invalid_arguments");
+    }
+    assert!(!Path::new(OUTSIDE[0]).exists());
+
+    let retro = server
+        .call(
+            "designRetro",
+            json!({ "sessionName": "space_cats", "path": "docs/retro-1.0.md" }),
+        )
+        .await
+        .expect("designRetro with a relative path");
+    let path = Path::new(retro["path"].as_str().unwrap());
+    assert!(path.ends_with("sessions/docs/retro-1.0.md"));
+    assert!(path.exists());
+}
+
+#[tokio::test]
+async fn test_plan_path_stays_inside_the_data_directory() {
+    let mut server =
+        TestServer::with_feature("test_plan_path_stays_inside_the_data_directory").await;
+
+    server
+        .call(
+            "featureReview",
+            json!({
+                "sessionName": "space_cats",
+                "changesMade": "Added the core loop with movement and a fail state.",
+            }),
+        )
+        .await
+        .expect("featureReview");
+
+    for path in OUTSIDE {
+        let error = server
+            .call(
+                "testPlan",
+                json!({ "sessionName": "space_cats", "path": path }),
+            )
+            .await
+            .expect_err("testPlan with a path outside the data directory");
+        assert_eq!(error["error"], "invalid_arguments");
+    }
+    assert!(!Path::new(OUTSIDE[0]).exists());
+
+    let plan = server
+        .call(
+            "testPlan",
+            json!({ "sessionName": "space_cats", "path": "docs/tests/core-loop.md" }),
+        )
+        .await
+        .expect("testPlan with a relative path");
+    let path = Path::new(plan["path"].as_str().unwrap());
+    assert!(path.ends_with("sessions/docs/tests/core-loop.md"));
+    assert!(path.exists());
+}
+
+#[tokio::test]
+async fn export_path_is_checked_before_it_is_stored() {
+    let mut server = TestServer::with_session("export_path_is_checked_before_it_is_stored").await;
+
+    for path in OUTSIDE {
+        let error = server
+            .call(
+                "designExport",
+                json!({ "sessionName": "space_cats", "path": path, "autoUpdate": true }),
+            )
+            .await
+            .expect_err("designExport with a path outside the data directory");
+        assert_eq!(error["error"], "invalid_arguments");
+    }
+    assert!(!Path::new(OUTSIDE[0]).exists());
+    assert_eq!(server.session("space_cats").export_path, None);
+
+    let export = server
+        .call(
+            "designExport",
+            json!({ "sessionName": "space_cats", "path": "docs/GDD.md", "autoUpdate": true }),
+        )
+        .await
+        .expect("designExport with a relative path");
+    let path = Path::new(export["path"].as_str().unwrap());
+    assert!(path.ends_with("sessions/docs/GDD.md"));
+    assert!(path.exists());
+    assert_eq!(
+        server.session("space_cats").export_path.as_deref(),
+        export["path"].as_str()
+    );
+}
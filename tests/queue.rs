@@ -0,0 +1,31 @@
+//! Calls given up on while they wait for a slot leave the LLM queue.
+
+use game_designer_mcp::tools::queue::{LlmQueue, QueueConfig};
+use std::time::Duration;
+
+#[tokio::test]
+async fn abandoned_waits_free_their_place() {
+    let queue = LlmQueue::new(QueueConfig {
+        concurrency: 1,
+        capacity: 1,
+    });
+
+    let running = queue.enter().await.expect("take the only slot");
+
+    // Times out waiting, the way run_tool gives up on a call
+    for _ in 0..3 {
+        let waiting = tokio::time::timeout(Duration::from_millis(10), queue.enter()).await;
+        assert!(waiting.is_err(), "wait for the taken slot");
+    }
+
+    drop(running);
+    let _running = queue
+        .enter()
+        .await
+        .expect("enter after the waits were abandoned");
+    let waiting = tokio::time::timeout(Duration::from_millis(10), queue.enter()).await;
+    assert!(
+        waiting.is_err(),
+        "a call may wait again instead of being busy"
+    );
+}
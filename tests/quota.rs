@@ -0,0 +1,67 @@
+//! Daily token budgets are dated by the session manager's clock and shared
+//! by the servers using the same data directory.
+
+use chrono::{DateTime, Duration, Utc};
+use game_designer_mcp::{game_design::clock::FixedClock, tools::quota::TokenLedger};
+use std::{fs, sync::Arc};
+
+#[test]
+fn token_ledgers_share_the_budget_of_a_day() {
+    let path = std::env::temp_dir()
+        .join(format!("gamedesignerd-tokens-{}", std::process::id()))
+        .join("tokens.json");
+    fs::remove_file(&path).ok();
+
+    let now = DateTime::parse_from_rfc3339("2026-03-02T23:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    let clock = Arc::new(FixedClock::new(now));
+    // Two processes sharing the data directory
+    let server = TokenLedger::open(path.clone(), clock.clone());
+    let dashboard = TokenLedger::open(path.clone(), clock.clone());
+
+    server.charge("space_cats", 600);
+    dashboard.charge("space_cats", 400);
+    assert_eq!(server.used_today("space_cats"), 1_000);
+    assert_eq!(dashboard.used_today("space_cats"), 1_000);
+
+    let exceeded = server
+        .check("space_cats", Some(1_000))
+        .expect_err("the budget is used up");
+    assert_eq!(exceeded.retry_after, Duration::hours(1).to_std().unwrap());
+    assert!(server.check("space_cats", Some(1_001)).is_ok());
+
+    clock.set(now + Duration::hours(2));
+    assert_eq!(server.used_today("space_cats"), 0);
+    assert!(server.check("space_cats", Some(1_000)).is_ok());
+}
+
+#[test]
+fn charges_made_at_the_same_time_add_up() {
+    let path = std::env::temp_dir()
+        .join(format!(
+            "gamedesignerd-tokens-concurrent-{}",
+            std::process::id()
+        ))
+        .join("tokens.json");
+    fs::remove_file(&path).ok();
+    let clock = Arc::new(FixedClock::new(Utc::now()));
+
+    // Each ledger stands for a server in another process
+    let servers = (0..4)
+        .map(|_| {
+            let ledger = TokenLedger::open(path.clone(), clock.clone());
+            std::thread::spawn(move || {
+                for _ in 0..50 {
+                    ledger.charge("space_cats", 10);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for server in servers {
+        server.join().expect("charge the tokens");
+    }
+
+    let ledger = TokenLedger::open(path, clock);
+    assert_eq!(ledger.used_today("space_cats"), 4 * 50 * 10);
+}
@@ -0,0 +1,70 @@
+//! Eviction of the sessions kept in memory.
+
+use game_designer_mcp::game_design::{residency::ResidentSessions, state::SessionState};
+
+fn session(id: &str) -> SessionState {
+    SessionState::new(id.to_string(), format!("The game of {}", id))
+}
+
+fn admit(sessions: &mut ResidentSessions, id: &str) -> Vec<String> {
+    sessions.insert(id.to_string(), session(id));
+    sessions.evict(id, |_| true)
+}
+
+fn resident(sessions: &ResidentSessions) -> Vec<String> {
+    sessions
+        .residency(|_| true)
+        .sessions
+        .into_iter()
+        .map(|session| session.id)
+        .collect()
+}
+
+#[test]
+fn least_recently_used_sessions_are_evicted() {
+    let mut sessions = ResidentSessions::new(2);
+
+    assert!(admit(&mut sessions, "space_cats").is_empty());
+    assert!(admit(&mut sessions, "dungeon_crab").is_empty());
+
+    // Using a session keeps it in memory
+    sessions.get("space_cats").unwrap();
+    assert_eq!(admit(&mut sessions, "moon_miner"), ["dungeon_crab"]);
+    assert_eq!(resident(&sessions), ["moon_miner", "space_cats"]);
+
+    let residency = sessions.residency(|_| true);
+    assert_eq!(
+        (residency.loads, residency.hits, residency.evictions),
+        (3, 1, 1)
+    );
+}
+
+#[test]
+fn unsaved_sessions_stay_in_memory() {
+    let mut sessions = ResidentSessions::new(1);
+
+    admit(&mut sessions, "space_cats");
+    sessions
+        .get_mut("space_cats")
+        .unwrap()
+        .planned_features
+        .clear();
+
+    sessions.insert("dungeon_crab".to_string(), session("dungeon_crab"));
+    assert!(sessions.evict("dungeon_crab", |_| false).is_empty());
+    assert!(sessions.residency(|_| false).sessions[1].unsaved);
+
+    // Once written, the changed session may go
+    assert_eq!(sessions.evict("dungeon_crab", |_| true), ["space_cats"]);
+    assert_eq!(resident(&sessions), ["dungeon_crab"]);
+}
+
+#[test]
+fn zero_capacity_keeps_every_session() {
+    let mut sessions = ResidentSessions::new(0);
+
+    for id in ["space_cats", "dungeon_crab", "moon_miner"] {
+        assert!(admit(&mut sessions, id).is_empty());
+    }
+    assert_eq!(resident(&sessions).len(), 3);
+}
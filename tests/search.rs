@@ -0,0 +1,48 @@
+//! designSearch finds past answers by meaning and links them to the event
+//! they came from.
+
+mod common;
+
+use common::TestServer;
+use serde_json::json;
+
+#[tokio::test]
+async fn search_links_matches_to_their_events() {
+    let mut server = TestServer::with_feature("search_links_matches_to_their_events").await;
+    server.fixture(
+        "feature_ask",
+        "Save files are written as JSON to the player's data directory, one file per save slot.",
+    );
+
+    server
+        .call(
+            "featureAsk",
+            json!({
+                "sessionName": "space_cats",
+                "question": "Where do the save files go?",
+            }),
+        )
+        .await
+        .expect("featureAsk");
+
+    let found = server
+        .call(
+            "designSearch",
+            json!({ "sessionName": "space_cats", "query": "that decision about save files" }),
+        )
+        .await
+        .expect("designSearch");
+    let top = &found["matches"][0];
+    assert_eq!(top["kind"], "question");
+    assert_eq!(top["event"]["kind"], "question.answered");
+    assert!(top["snippet"].as_str().unwrap().contains("save slot"));
+
+    let error = server
+        .call(
+            "designSearch",
+            json!({ "sessionName": "space_cats", "query": "  " }),
+        )
+        .await
+        .expect_err("designSearch without a query");
+    assert_eq!(error["error"], "invalid_arguments");
+}
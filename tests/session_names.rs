@@ -0,0 +1,45 @@
+//! Session names given to any tool stay inside the sessions directory.
+
+mod common;
+
+use common::TestServer;
+use serde_json::json;
+
+#[tokio::test]
+async fn session_names_cannot_leave_the_sessions_directory() {
+    let mut server =
+        TestServer::with_session("session_names_cannot_leave_the_sessions_directory").await;
+
+    for session_name in [
+        "../space_cats",
+        "../../sessions/space_cats",
+        ".space_cats",
+        "webhooks",
+    ] {
+        let error = server
+            .call("designOverview", json!({ "sessionName": session_name }))
+            .await
+            .expect_err("designOverview of an invalid session name");
+        assert_eq!(error["error"], "invalid_arguments");
+
+        let error = server
+            .call(
+                "designFreeze",
+                json!({ "sessionName": session_name, "reason": "Shipped 1.0" }),
+            )
+            .await
+            .expect_err("designFreeze of an invalid session name");
+        assert_eq!(error["error"], "invalid_arguments");
+    }
+
+    let error = server
+        .call(
+            "designDiff",
+            json!({ "sessionName": "space_cats", "otherSession": "../space_cats" }),
+        )
+        .await
+        .expect_err("designDiff with an invalid other session");
+    assert_eq!(error["error"], "invalid_arguments");
+
+    assert!(server.session("space_cats").freeze.is_none());
+}
@@ -0,0 +1,257 @@
+//! Sessions kept in memory with a frozen clock: nothing touches the file
+//! system and every timestamp is the one the test set.
+
+use chrono::{DateTime, Duration, Utc};
+use game_designer_mcp::game_design::{
+    Persona, SessionManager, SessionSetup,
+    clock::FixedClock,
+    events::{EventFilter, EventKind, EventLog, SessionEvent},
+    state::Feature,
+    storage::{MemoryStorage, Storage},
+};
+use serde_json::json;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+fn start() -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339("2026-03-02T09:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+fn manager(dir: &Path) -> (SessionManager, Arc<MemoryStorage>, Arc<FixedClock>) {
+    let storage = Arc::new(MemoryStorage::new());
+    let clock = Arc::new(FixedClock::new(start()));
+    let manager = SessionManager::with_backends(dir, storage.clone(), clock.clone())
+        .expect("open the in-memory sessions");
+
+    (manager, storage, clock)
+}
+
+#[tokio::test]
+async fn sessions_are_stored_in_memory() {
+    let dir = PathBuf::from("/nonexistent/gamedesignerd/sessions");
+    let (manager, storage, _clock) = manager(&dir);
+
+    manager
+        .create_session(
+            "space_cats".to_string(),
+            "Cats defend a space station from mice".to_string(),
+            Persona::default(),
+            None,
+            SessionSetup::default(),
+        )
+        .await
+        .expect("create the session");
+
+    assert!(!dir.exists());
+    assert!(storage.exists(&dir.join("space_cats.json")));
+    assert_eq!(manager.session_ids().unwrap(), ["space_cats"]);
+
+    let session = manager
+        .read_session("space_cats")
+        .unwrap()
+        .expect("the session is stored");
+    assert_eq!(session.id, "space_cats");
+}
+
+#[tokio::test]
+async fn timestamps_come_from_the_clock() {
+    let (manager, _storage, clock) = manager(Path::new("/memory/sessions"));
+
+    manager
+        .create_session(
+            "space_cats".to_string(),
+            "Cats defend a space station from mice".to_string(),
+            Persona::default(),
+            None,
+            SessionSetup::default(),
+        )
+        .await
+        .expect("create the session");
+
+    let mut session = manager.load_session("space_cats").await.unwrap().unwrap();
+    session.planned_features.push(Feature::new(
+        "Laser pointer".to_string(),
+        "Cats chase a laser pointer".to_string(),
+    ));
+    manager.save_session(session).await.unwrap();
+
+    manager
+        .start_work("space_cats", Some("Laser pointer"))
+        .await
+        .expect("start work");
+    clock.advance(Duration::minutes(42));
+    let (_, minutes, total) = manager
+        .stop_work("space_cats", Some("Laser pointer"))
+        .await
+        .expect("stop work");
+    assert_eq!((minutes, total), (42, 42));
+
+    let events = manager
+        .session_history("space_cats", &EventFilter::default(), usize::MAX)
+        .await
+        .unwrap();
+    let stamped: Vec<_> = events
+        .iter()
+        .map(|event| (event.kind, event.timestamp))
+        .collect();
+    assert_eq!(
+        stamped,
+        [
+            (EventKind::SessionCreated, start()),
+            (EventKind::FeatureWorkStarted, start()),
+            (
+                EventKind::FeatureWorkStopped,
+                start() + Duration::minutes(42)
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn search_indexes_are_stored_in_memory() {
+    let dir = PathBuf::from("/nonexistent/gamedesignerd/sessions");
+    let (manager, storage, _clock) = manager(&dir);
+
+    manager
+        .create_session(
+            "space_cats".to_string(),
+            "Cats defend a space station from mice".to_string(),
+            Persona::default(),
+            None,
+            SessionSetup::default(),
+        )
+        .await
+        .expect("create the session");
+    manager
+        .design_search("space_cats", "mice", 5)
+        .await
+        .expect("search the session");
+
+    assert!(!dir.exists());
+    assert!(storage.exists(&dir.join("space_cats/embeddings.json")));
+}
+
+#[tokio::test]
+async fn documents_asset_lists_and_retros_are_stored_in_memory() {
+    let dir = PathBuf::from("/nonexistent/gamedesignerd/sessions");
+    let (manager, storage, _clock) = manager(&dir);
+
+    manager
+        .create_session(
+            "space_cats".to_string(),
+            "Cats defend a space station from mice".to_string(),
+            Persona::default(),
+            None,
+            SessionSetup {
+                document: Some(("# Space Cats\n".to_string(), Vec::new())),
+                ..SessionSetup::default()
+            },
+        )
+        .await
+        .expect("create the session from its design document");
+    let (assets, _) = manager
+        .export_assets_csv("space_cats", None)
+        .await
+        .expect("export the asset list");
+    let (retro, _) = manager
+        .design_retro("space_cats", None, None)
+        .await
+        .expect("write the retrospective");
+
+    assert!(!dir.exists());
+    assert!(storage.exists(&dir.join("documents/space_cats.md")));
+    assert!(storage.exists(Path::new(&assets)));
+    assert!(storage.exists(Path::new(&retro)));
+}
+
+#[test]
+fn webhook_configuration_is_read_from_storage() {
+    let dir = PathBuf::from("/nonexistent/gamedesignerd/sessions");
+    let (manager, storage, _clock) = manager(&dir);
+    assert_eq!(manager.reload_webhooks().unwrap(), 0);
+
+    storage
+        .write(
+            &dir.join("webhooks.json"),
+            r#"{ "endpoints": [{ "url": "https://example.com/hook" }] }"#,
+        )
+        .expect("configure a webhook");
+    assert_eq!(manager.reload_webhooks().unwrap(), 1);
+    assert!(!dir.exists());
+}
+
+#[test]
+fn event_logs_pick_up_appends_of_other_writers() {
+    let storage = Arc::new(MemoryStorage::new());
+    let dir = Path::new("/memory/sessions");
+    // Two processes sharing the data directory
+    let server = EventLog::new(dir, storage.clone());
+    let dashboard = EventLog::new(dir, storage.clone());
+
+    let append = |log: &EventLog, feature: &str| {
+        let mut event = SessionEvent::new(
+            EventKind::FeatureProposed,
+            "space_cats",
+            json!({ "feature": feature }),
+            start(),
+        );
+        log.append(&mut event).expect("append the event");
+        event.seq
+    };
+
+    assert_eq!(append(&server, "Laser pointer"), 0);
+    assert_eq!(append(&server, "Cardboard box"), 1);
+    assert_eq!(dashboard.read_since("space_cats", 1, 10).unwrap().len(), 1);
+
+    assert_eq!(append(&dashboard, "Catnip"), 2);
+    assert_eq!(append(&server, "Yarn ball"), 3);
+    assert_eq!(server.next_seq("space_cats").unwrap(), 4);
+
+    let events = dashboard.read_since("space_cats", 2, 10).unwrap();
+    let features = events
+        .iter()
+        .map(|event| (event.seq, event.data["feature"].as_str().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(features, [(2, "Catnip"), (3, "Yarn ball")]);
+    assert!(
+        dashboard
+            .read_since("space_cats", 4, 10)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn event_logs_read_only_the_requested_events() {
+    let storage = Arc::new(MemoryStorage::new());
+    let log = EventLog::new("/memory/sessions", storage.clone());
+
+    for feature in ["Laser pointer", "Cardboard box", "Catnip", "Yarn ball"] {
+        let mut event = SessionEvent::new(
+            EventKind::FeatureProposed,
+            "space_cats",
+            json!({ "feature": feature }),
+            start(),
+        );
+        log.append(&mut event).expect("append the event");
+    }
+    // A line still being written is not read
+    storage
+        .append(
+            Path::new("/memory/sessions/space_cats.events.jsonl"),
+            "{\"seq\":",
+        )
+        .unwrap();
+
+    let events = log.read_since("space_cats", 1, 2).unwrap();
+    let seqs = events.iter().map(|event| event.seq).collect::<Vec<_>>();
+    assert_eq!(seqs, [1, 2]);
+    assert_eq!(log.read_since("space_cats", 3, 10).unwrap().len(), 1);
+
+    assert!(log.read_since("../space_cats", 0, 10).is_err());
+    assert!(log.read_since("webhooks", 0, 10).is_err());
+}